@@ -38,11 +38,11 @@ fn geometry_benchmark(c: &mut Criterion) {
     let columns = [
         "tx_m", "ty_m", "tz_m", "length_m", "width_m", "height_m", "qw", "qx", "qy", "qz",
     ];
-    let annotations_ndarray = ndarray_from_frame(&annotations, cols(columns));
+    let annotations_ndarray = ndarray_from_frame(&annotations, cols(columns)).unwrap();
     let cuboid_vertices = cuboids_to_polygons(&annotations_ndarray.view());
     let lidar_path = TEST_DATA_DIR.join(format!("sensors/lidar/{timestamp_ns}.feather"));
     let lidar = read_feather_eager(&lidar_path, false);
-    let lidar_ndarray = ndarray_from_frame(&lidar, cols(["x", "y", "z"]));
+    let lidar_ndarray = ndarray_from_frame(&lidar, cols(["x", "y", "z"])).unwrap();
     c.bench_function("compute_interior_points_mask", |b| {
         b.iter(|| compute_interior_points_mask(&lidar_ndarray.view(), &cuboid_vertices.view()))
     });
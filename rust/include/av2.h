@@ -0,0 +1,166 @@
+#ifndef AV2_H
+#define AV2_H
+
+/* Warning, this file is autogenerated by cbindgen. Don't modify this manually. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Number of parameters of a cuboid (see `CUBOID_COLUMNS`).
+#define AV2_CUBOID_DIM 10
+
+// Number of features of a lidar point (`x`, `y`, `z`, and `intensity`).
+#define AV2_LIDAR_DIM 4
+
+// Overlap computation mode of the IoU and NMS functions.
+typedef enum Av2IouMode {
+  // Overlap of the bird's-eye-view (xy-plane) footprints.
+  AV2_IOU_MODE_BEV = 0,
+  // Volumetric overlap.
+  AV2_IOU_MODE_THREE_D = 1,
+} Av2IouMode;
+
+// Status code returned by the C API.
+typedef enum Av2Status {
+  // The call succeeded.
+  AV2_STATUS_OK = 0,
+  // A required pointer was null.
+  AV2_STATUS_NULL_POINTER = 1,
+  // An argument was out of range or malformed.
+  AV2_STATUS_INVALID_ARGUMENT = 2,
+  // The call failed internally (e.g., a missing or corrupt file).
+  AV2_STATUS_INTERNAL = 3,
+} Av2Status;
+
+// Opaque data-loader handle.
+typedef struct Av2DataLoader Av2DataLoader;
+
+// Opaque sweep handle owning its lidar and cuboid buffers.
+typedef struct Av2Sweep Av2Sweep;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Open the sensor dataset split at `root_dir/dataset_name/dataset_type/split_name`, or return
+// null if any argument is null or the split cannot be indexed. Release the handle with
+// `av2_data_loader_free`.
+//
+// # Safety
+//
+// The string arguments must be null or valid NUL-terminated UTF-8 strings.
+Av2DataLoader *av2_data_loader_new(const char *root_dir,
+                                   const char *dataset_name,
+                                   const char *dataset_type,
+                                   const char *split_name,
+                                   size_t num_accumulated_sweeps);
+
+// Release a data-loader handle. Null is a no-op.
+//
+// # Safety
+//
+// `data_loader` must be null or a handle returned by `av2_data_loader_new` that was not released.
+void av2_data_loader_free(Av2DataLoader *data_loader);
+
+// Number of sweeps of the data-loader (`0` if it is null).
+//
+// # Safety
+//
+// `data_loader` must be null or a live handle returned by `av2_data_loader_new`.
+size_t av2_data_loader_len(const Av2DataLoader *data_loader);
+
+// Load the sweep at `index`, or return null if the data-loader is null, the index is out of
+// range, or the sweep cannot be read. Release the handle with `av2_sweep_free`.
+//
+// # Safety
+//
+// `data_loader` must be null or a live handle returned by `av2_data_loader_new`.
+Av2Sweep *av2_data_loader_get(const Av2DataLoader *data_loader, size_t index);
+
+// Release a sweep handle. Null is a no-op.
+//
+// # Safety
+//
+// `sweep` must be null or a handle returned by `av2_data_loader_get` that was not released.
+void av2_sweep_free(Av2Sweep *sweep);
+
+// Log id of the sweep, valid until the sweep is released (null if the sweep is null).
+//
+// # Safety
+//
+// `sweep` must be null or a live handle returned by `av2_data_loader_get`.
+const char *av2_sweep_log_id(const Av2Sweep *sweep);
+
+// Nanosecond timestamp of the sweep (`0` if the sweep is null).
+//
+// # Safety
+//
+// `sweep` must be null or a live handle returned by `av2_data_loader_get`.
+uint64_t av2_sweep_timestamp_ns(const Av2Sweep *sweep);
+
+// (N,4) row-major `x`, `y`, `z`, and `intensity` of the sweep's points, valid until the sweep is
+// released. `N` is written to `num_points`.
+//
+// # Safety
+//
+// `sweep` must be null or a live handle returned by `av2_data_loader_get`, and `num_points` must
+// be valid for writes.
+const float *av2_sweep_lidar(const Av2Sweep *sweep, size_t *num_points);
+
+// (M,10) row-major cuboids of the sweep in `CUBOID_COLUMNS` order, valid until the sweep is
+// released. `M` is written to `num_cuboids`. Returns null (with `M = 0`) if the sweep has no
+// annotations (e.g., in the test split).
+//
+// # Safety
+//
+// `sweep` must be null or a live handle returned by `av2_data_loader_get`, and `num_cuboids`
+// must be valid for writes.
+const float *av2_sweep_cuboids(const Av2Sweep *sweep, size_t *num_cuboids);
+
+// Write the (M,N) mask of which of the (N,3) `points` are interior to each of the (M,10)
+// `cuboids` into `mask`.
+//
+// # Safety
+//
+// `points` must hold `3 * num_points` floats, `cuboids` `10 * num_cuboids` floats, and `mask`
+// must be valid for `num_cuboids * num_points` writes.
+Av2Status av2_interior_points_mask(const float *points,
+                                   size_t num_points,
+                                   const float *cuboids,
+                                   size_t num_cuboids,
+                                   bool *mask);
+
+// Write the (N,M) pairwise IoU between (N,10) `cuboids_a` and (M,10) `cuboids_b` into `iou`.
+//
+// # Safety
+//
+// `cuboids_a` must hold `10 * num_cuboids_a` floats, `cuboids_b` `10 * num_cuboids_b` floats,
+// and `iou` must be valid for `num_cuboids_a * num_cuboids_b` writes.
+Av2Status av2_cuboid_iou(const float *cuboids_a,
+                         size_t num_cuboids_a,
+                         const float *cuboids_b,
+                         size_t num_cuboids_b,
+                         Av2IouMode mode,
+                         float *iou);
+
+// Greedy rotated non-maximum suppression of (N,10) `cuboids` with (N,) `scores`. The kept indices
+// are written to `keep` by descending score, and their number to `num_keep`.
+//
+// # Safety
+//
+// `cuboids` must hold `10 * num_cuboids` floats and `scores` `num_cuboids` floats, `keep` must be
+// valid for `num_cuboids` writes, and `num_keep` for one.
+Av2Status av2_nms(const float *cuboids,
+                  const float *scores,
+                  size_t num_cuboids,
+                  float iou_threshold,
+                  Av2IouMode mode,
+                  size_t *keep,
+                  size_t *num_keep);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AV2_H */
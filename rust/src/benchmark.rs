@@ -0,0 +1,380 @@
+//! # benchmark
+//!
+//! End-to-end pipeline throughput benchmarks.
+//!
+//! A pipeline loads each sweep of a split with the data-loader, optionally augments it, and
+//! voxelizes it. The runner measures the sustained throughput (frames per second) along with the
+//! latency of every stage, so configurations can be compared and reported on the same footing.
+
+use std::{
+    fmt,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{ensure, Result};
+use ndarray::Array;
+use polars::prelude::{DataFrame, NamedFrom, Series};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    data_loader::DataLoader,
+    geometry::{
+        augmentations::{AppliedAugmentation, AugmentationScratch},
+        voxelize::{voxelize_lidar, VoxelizationConfig},
+    },
+    structures::cuboid::{CuboidList, CUBOID_COLUMNS},
+    synthetic::{generate_mini_dataset, MiniDatasetConfig},
+};
+
+/// Stage of the benchmarked pipeline.
+#[derive(Clone, Copy, Debug, Display, EnumIter, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum PipelineStage {
+    /// Load the sweep (lidar, annotations, and pose) from disk.
+    Load,
+    /// Augment the sweep and its cuboids.
+    Augment,
+    /// Voxelize the sweep.
+    Voxelize,
+}
+
+/// Scene augmentations applied during the augment stage.
+#[derive(Clone, Debug)]
+pub struct AugmentationConfig {
+    /// Probability of reflecting the scene across the x-axis.
+    pub reflection_p: f64,
+    /// Range of the global rotation (as a fraction of a full turn).
+    pub rotation_range: (f64, f64),
+    /// Range of the global scale factor.
+    pub scale_range: (f64, f64),
+}
+
+impl Default for AugmentationConfig {
+    fn default() -> Self {
+        Self {
+            reflection_p: 0.5,
+            rotation_range: (-0.125, 0.125),
+            scale_range: (0.95, 1.05),
+        }
+    }
+}
+
+/// Pipeline benchmark configuration.
+#[derive(Clone, Debug)]
+pub struct PipelineBenchmarkConfig {
+    /// Number of frames processed (and discarded) before measuring.
+    pub num_warmup_frames: usize,
+    /// Maximum number of measured frames. Every remaining frame of the split is measured if `None`.
+    pub max_frames: Option<usize>,
+    /// Augmentations to apply. The augment stage is skipped if `None`.
+    pub augmentation: Option<AugmentationConfig>,
+    /// Voxelization of the (augmented) sweep.
+    pub voxelization: VoxelizationConfig,
+    /// Lidar columns which are voxelized. The first three must be the point coordinates.
+    pub feature_columns: Vec<String>,
+}
+
+impl Default for PipelineBenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            num_warmup_frames: 2,
+            max_frames: None,
+            augmentation: Some(AugmentationConfig::default()),
+            voxelization: VoxelizationConfig::default(),
+            feature_columns: ["x", "y", "z", "intensity"].map(String::from).to_vec(),
+        }
+    }
+}
+
+/// Latencies of a single pipeline stage.
+#[derive(Clone, Debug)]
+pub struct StageTimings {
+    /// Pipeline stage.
+    pub stage: PipelineStage,
+    /// Latency of each measured frame.
+    pub durations: Vec<Duration>,
+}
+
+impl StageTimings {
+    /// Total time spent in the stage (in seconds).
+    pub fn total_s(&self) -> f64 {
+        self.durations.iter().map(Duration::as_secs_f64).sum()
+    }
+
+    /// Mean latency (in milliseconds).
+    pub fn mean_ms(&self) -> f64 {
+        match self.durations.is_empty() {
+            true => 0.,
+            false => 1e3 * self.total_s() / self.durations.len() as f64,
+        }
+    }
+
+    /// Latency at percentile `q` in `[0, 100]` (in milliseconds), using the nearest rank.
+    pub fn percentile_ms(&self, q: f64) -> f64 {
+        let mut durations = self.durations.clone();
+        durations.sort();
+        let Some(last) = durations.len().checked_sub(1) else {
+            return 0.;
+        };
+        let rank = ((q.clamp(0., 100.) / 100.) * last as f64).round() as usize;
+        1e3 * durations[rank].as_secs_f64()
+    }
+}
+
+/// Result of a pipeline benchmark.
+#[derive(Clone, Debug)]
+pub struct PipelineBenchmarkReport {
+    /// Number of measured frames.
+    pub num_frames: usize,
+    /// Total number of lidar points in the measured frames.
+    pub num_points: usize,
+    /// Total number of voxels produced for the measured frames.
+    pub num_voxels: usize,
+    /// Wall-clock time of the measured frames.
+    pub wall_time: Duration,
+    /// Latencies of each stage that ran, in pipeline order.
+    pub stages: Vec<StageTimings>,
+}
+
+impl PipelineBenchmarkReport {
+    /// Sustained throughput (in frames per second).
+    pub fn frames_per_second(&self) -> f64 {
+        self.num_frames as f64 / self.wall_time.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Sustained throughput (in lidar points per second).
+    pub fn points_per_second(&self) -> f64 {
+        self.num_points as f64 / self.wall_time.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Timings of `stage`, if it ran.
+    pub fn stage(&self, stage: PipelineStage) -> Option<&StageTimings> {
+        self.stages.iter().find(|timings| timings.stage == stage)
+    }
+
+    /// Per-stage breakdown with the total, mean, median, 95th percentile, and maximum latencies,
+    /// and the fraction of the wall-clock time spent in each stage.
+    pub fn stage_breakdown(&self) -> DataFrame {
+        let wall_time_s = self.wall_time.as_secs_f64().max(f64::EPSILON);
+        let stat = |f: &dyn Fn(&StageTimings) -> f64| self.stages.iter().map(f).collect::<Vec<_>>();
+        let names = self
+            .stages
+            .iter()
+            .map(|timings| timings.stage.to_string())
+            .collect::<Vec<_>>();
+        DataFrame::new(vec![
+            Series::new("stage", names),
+            Series::new("total_s", stat(&|t| t.total_s())),
+            Series::new("mean_ms", stat(&|t| t.mean_ms())),
+            Series::new("p50_ms", stat(&|t| t.percentile_ms(50.))),
+            Series::new("p95_ms", stat(&|t| t.percentile_ms(95.))),
+            Series::new("max_ms", stat(&|t| t.percentile_ms(100.))),
+            Series::new("fraction", stat(&|t| t.total_s() / wall_time_s)),
+        ])
+        .unwrap()
+    }
+}
+
+impl fmt::Display for PipelineBenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} frames in {:.3} s: {:.2} frames/s, {:.0} points/s, {:.1} voxels/frame.",
+            self.num_frames,
+            self.wall_time.as_secs_f64(),
+            self.frames_per_second(),
+            self.points_per_second(),
+            self.num_voxels as f64 / self.num_frames.max(1) as f64
+        )?;
+        for timings in &self.stages {
+            writeln!(
+                f,
+                "{:>9}: mean {:.2} ms, p50 {:.2} ms, p95 {:.2} ms, max {:.2} ms.",
+                timings.stage,
+                timings.mean_ms(),
+                timings.percentile_ms(50.),
+                timings.percentile_ms(95.),
+                timings.percentile_ms(100.)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Benchmark the pipeline over the frames of a data-loader's split.
+pub fn run_pipeline_benchmark(
+    data_loader: &DataLoader,
+    cfg: &PipelineBenchmarkConfig,
+) -> Result<PipelineBenchmarkReport> {
+    ensure!(
+        data_loader.len() > cfg.num_warmup_frames,
+        "Expected more than {} frames, got {}.",
+        cfg.num_warmup_frames,
+        data_loader.len()
+    );
+    let num_frames = cfg
+        .max_frames
+        .unwrap_or(usize::MAX)
+        .min(data_loader.len() - cfg.num_warmup_frames);
+    let feature_columns = cfg
+        .feature_columns
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+
+    for index in 0..cfg.num_warmup_frames {
+        _run_pipeline(data_loader, index, &feature_columns, cfg)?;
+    }
+
+    let mut stages = PipelineStage::iter()
+        .filter(|stage| *stage != PipelineStage::Augment || cfg.augmentation.is_some())
+        .map(|stage| StageTimings {
+            stage,
+            durations: Vec::with_capacity(num_frames),
+        })
+        .collect::<Vec<_>>();
+    let (mut num_points, mut num_voxels) = (0, 0);
+    let start = Instant::now();
+    for index in cfg.num_warmup_frames..cfg.num_warmup_frames + num_frames {
+        let output = _run_pipeline(data_loader, index, &feature_columns, cfg)?;
+        num_points += output.num_points;
+        num_voxels += output.num_voxels;
+        for timings in stages.iter_mut() {
+            if let Some(duration) = output.durations[timings.stage as usize] {
+                timings.durations.push(duration);
+            }
+        }
+    }
+    Ok(PipelineBenchmarkReport {
+        num_frames,
+        num_points,
+        num_voxels,
+        wall_time: start.elapsed(),
+        stages,
+    })
+}
+
+/// Generate a synthetic mini dataset under `root_dir` and benchmark the pipeline over its first
+/// split.
+pub fn run_synthetic_pipeline_benchmark(
+    root_dir: &Path,
+    dataset_cfg: &MiniDatasetConfig,
+    cfg: &PipelineBenchmarkConfig,
+) -> Result<PipelineBenchmarkReport> {
+    let split_name = dataset_cfg.split_names.first();
+    ensure!(split_name.is_some(), "Expected at least one split.");
+    generate_mini_dataset(root_dir, dataset_cfg)?;
+    let data_loader = DataLoader::new(
+        root_dir.to_str().unwrap(),
+        &dataset_cfg.dataset_name,
+        &dataset_cfg.dataset_type,
+        split_name.unwrap(),
+        1,
+        false,
+    );
+    run_pipeline_benchmark(&data_loader, cfg)
+}
+
+/// Output of a single pass through the pipeline.
+struct PipelineOutput {
+    num_points: usize,
+    num_voxels: usize,
+    /// Latency of each stage, indexed by `PipelineStage`.
+    durations: [Option<Duration>; 3],
+}
+
+fn _run_pipeline(
+    data_loader: &DataLoader,
+    index: usize,
+    feature_columns: &[&str],
+    cfg: &PipelineBenchmarkConfig,
+) -> Result<PipelineOutput> {
+    let mut durations = [None; 3];
+
+    let start = Instant::now();
+    let sweep = data_loader.try_get(index)?;
+    durations[PipelineStage::Load as usize] = Some(start.elapsed());
+
+    let mut lidar = sweep.lidar.0;
+    if let Some(augmentation) = &cfg.augmentation {
+        let start = Instant::now();
+        // Sweeps without annotations (e.g., the test split) are augmented with no cuboids.
+        let mut cuboids = match sweep.cuboids {
+            Some(cuboids) => cuboids.0,
+            None => _empty_cuboids(),
+        };
+        let mut scratch = AugmentationScratch::default();
+        for augmentation in [
+            AppliedAugmentation::sample_scene_reflection_x(augmentation.reflection_p),
+            AppliedAugmentation::sample_scene_global_rotation(
+                augmentation.rotation_range.0,
+                augmentation.rotation_range.1,
+            ),
+            AppliedAugmentation::sample_scene_global_scale(
+                augmentation.scale_range.0,
+                augmentation.scale_range.1,
+            ),
+        ] {
+            augmentation.apply_in_place(&mut lidar, &mut cuboids, &mut scratch)?;
+        }
+        durations[PipelineStage::Augment as usize] = Some(start.elapsed());
+    }
+
+    let start = Instant::now();
+    let voxels = voxelize_lidar(&lidar, feature_columns.to_vec(), &cfg.voxelization)?;
+    durations[PipelineStage::Voxelize as usize] = Some(start.elapsed());
+
+    Ok(PipelineOutput {
+        num_points: lidar.height(),
+        num_voxels: voxels.coordinates.shape()[0],
+        durations,
+    })
+}
+
+fn _empty_cuboids() -> DataFrame {
+    let params = Array::zeros((0, CUBOID_COLUMNS.len()));
+    CuboidList::new(params, None, None).unwrap().to_data_frame()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_synthetic_pipeline_benchmark, PipelineBenchmarkConfig, PipelineStage};
+    use crate::synthetic::test_dir;
+    use crate::synthetic::{MiniDatasetConfig, SyntheticSceneConfig};
+
+    #[test]
+    fn test_run_synthetic_pipeline_benchmark() {
+        let root_dir = test_dir();
+        let dataset_cfg = MiniDatasetConfig {
+            split_names: vec!["test".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let cfg = PipelineBenchmarkConfig {
+            num_warmup_frames: 1,
+            ..Default::default()
+        };
+        let report = run_synthetic_pipeline_benchmark(&root_dir, &dataset_cfg, &cfg).unwrap();
+
+        assert_eq!(report.num_frames, 3);
+        assert!(report.num_points > 0 && report.num_voxels > 0);
+        assert!(report.frames_per_second() > 0.);
+        assert_eq!(report.stages.len(), 3);
+        assert!(report
+            .stages
+            .iter()
+            .all(|timings| timings.durations.len() == 3));
+        assert!(report.stage(PipelineStage::Augment).is_some());
+
+        let breakdown = report.stage_breakdown();
+        assert_eq!(breakdown.shape(), (3, 7));
+        let fractions = breakdown.column("fraction").unwrap().f64().unwrap();
+        assert!(fractions.into_no_null_iter().sum::<f64>() <= 1. + 1e-6);
+    }
+}
@@ -0,0 +1,94 @@
+//! # benchmark_pipeline
+//!
+//! Measures the sustained throughput of the load, augment, and voxelize pipeline over a split.
+//! Falls back to a generated synthetic split if the dataset cannot be found.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::{
+    benchmark::{
+        run_pipeline_benchmark, run_synthetic_pipeline_benchmark, PipelineBenchmarkConfig,
+    },
+    data_loader::DataLoader,
+    synthetic::MiniDatasetConfig,
+};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type. This will either be "lidar" or "sensor".
+static DATASET_TYPE: &str = "sensor";
+
+/// Split name to benchmark.
+static SPLIT_NAME: &str = "val";
+
+/// Number of accumulated sweeps.
+const NUM_ACCUMULATED_SWEEPS: usize = 1;
+
+/// Memory maps the sweeps for fast pre-processing. Requires .feather files to be uncompressed.
+const MEMORY_MAPPED: bool = false;
+
+/// Maximum number of measured frames.
+const MAX_FRAMES: usize = 500;
+
+/// Directory of the synthetic split used when the dataset cannot be found.
+static SYNTHETIC_ROOT_DIR: Lazy<PathBuf> =
+    Lazy::new(|| std::env::temp_dir().join("av2_benchmark_pipeline"));
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    let cfg = PipelineBenchmarkConfig {
+        max_frames: Some(MAX_FRAMES),
+        ..Default::default()
+    };
+    let split_dir = ROOT_DIR
+        .join(DATASET_NAME)
+        .join(DATASET_TYPE)
+        .join(SPLIT_NAME);
+    let report = match split_dir.exists() {
+        true => {
+            info!("Benchmarking {} ...", split_dir.display());
+            let data_loader = DataLoader::new(
+                ROOT_DIR.to_str().unwrap(),
+                DATASET_NAME,
+                DATASET_TYPE,
+                SPLIT_NAME,
+                NUM_ACCUMULATED_SWEEPS,
+                MEMORY_MAPPED,
+            );
+            run_pipeline_benchmark(&data_loader, &cfg)
+        }
+        false => {
+            warn!(
+                "Cannot find {}. Benchmarking a synthetic split ...",
+                split_dir.display()
+            );
+            run_synthetic_pipeline_benchmark(
+                &SYNTHETIC_ROOT_DIR,
+                &MiniDatasetConfig::default(),
+                &cfg,
+            )
+        }
+    };
+    match report {
+        Ok(report) => {
+            println!("{report}");
+            println!("{}", report.stage_breakdown());
+        }
+        Err(err) => error!("Benchmark failed: {err:#}"),
+    }
+}
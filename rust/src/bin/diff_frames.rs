@@ -0,0 +1,52 @@
+//! # diff_frames
+//!
+//! Compares two feather files column-wise and exits with a non-zero status if they differ.
+//!
+//! Usage: `diff_frames <expected.feather> <actual.feather>`.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use av2::{
+    frame_diff::{diff_frames, FrameDiffConfig},
+    io::read_feather_eager,
+};
+
+/// Script entrypoint.
+pub fn main() -> ExitCode {
+    env_logger::init();
+    let paths = std::env::args()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let [expected_path, actual_path] = paths.as_slice() else {
+        error!("Usage: diff_frames <expected.feather> <actual.feather>");
+        return ExitCode::from(2);
+    };
+    for path in [expected_path, actual_path] {
+        if !path.exists() {
+            error!("{} not found.", path.display());
+            return ExitCode::from(2);
+        }
+    }
+
+    let expected = read_feather_eager(expected_path, false);
+    let actual = read_feather_eager(actual_path, false);
+    match diff_frames(&expected, &actual, &FrameDiffConfig::default()) {
+        Ok(diff) => {
+            print!("{diff}");
+            match diff.is_empty() {
+                true => ExitCode::SUCCESS,
+                false => ExitCode::FAILURE,
+            }
+        }
+        Err(err) => {
+            error!("Failed to compare the frames: {err:#}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! # download_dataset
+//!
+//! Downloads the selected splits (or logs) of a dataset from the public `argoverse` bucket.
+//! Interrupted downloads resume from the partial files on re-run, and the listed file sizes are
+//! recorded for `verify_dataset`.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::download::{
+    download_objects, list_dataset_objects, record_file_sizes, DownloadConfig, DownloadModality,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type. This will either be "lidar" or "sensor".
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names to download.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+/// Log ids to download, or empty to download every log of the splits.
+static LOG_IDS: Lazy<Vec<&str>> = Lazy::new(Vec::new);
+
+/// Modalities to download (e.g., `vec![DownloadModality::Lidar, DownloadModality::Annotations]`
+/// without imagery), or empty to download every file of the logs.
+static MODALITIES: Lazy<Vec<DownloadModality>> = Lazy::new(Vec::new);
+
+/// Number of files downloaded concurrently.
+const NUM_THREADS: usize = 16;
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    let cfg = DownloadConfig {
+        dataset_name: DATASET_NAME.to_string(),
+        dataset_type: DATASET_TYPE.to_string(),
+        split_names: SPLIT_NAMES.iter().map(|x| x.to_string()).collect(),
+        log_ids: (!LOG_IDS.is_empty()).then(|| LOG_IDS.iter().map(|x| x.to_string()).collect()),
+        modalities: (!MODALITIES.is_empty()).then(|| MODALITIES.clone()),
+        num_threads: NUM_THREADS,
+        ..Default::default()
+    };
+    let objects = list_dataset_objects(&cfg).unwrap();
+    record_file_sizes(&ROOT_DIR, &objects, &cfg).unwrap();
+    info!(
+        "Downloading {} files of {:?} into {} ...",
+        objects.len(),
+        cfg.split_names,
+        ROOT_DIR.display()
+    );
+    let bar = ProgressBar::new(objects.iter().map(|x| x.size).sum());
+    bar.set_style(ProgressStyle::with_template("{bar:40} {bytes}/{total_bytes} ({eta})").unwrap());
+    match download_objects(&ROOT_DIR, &objects, &cfg, |n| bar.inc(n)) {
+        Ok(summary) => info!(
+            "Downloaded {} files ({} already complete).",
+            summary.num_downloaded, summary.num_skipped
+        ),
+        Err(err) => error!("{err:#}"),
+    }
+}
@@ -15,8 +15,8 @@ use indicatif::ProgressBar;
 
 use once_cell::sync::Lazy;
 
-/// Constants can be changed to fit your directory structure.
-/// However, it's recommend to place the datasets in the default folders.
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
 
 /// Root directory to datasets.
 static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
@@ -60,6 +60,7 @@ pub fn main() {
         );
         let bar = ProgressBar::new(data_loader.len() as u64);
         for sweep in data_loader {
+            let sweep = sweep.unwrap();
             let lidar = sweep.lidar.0;
             let (log_id, timestamp_ns) = sweep.sweep_uuid;
 
@@ -67,7 +68,7 @@ pub fn main() {
             let dst = DST_PREFIX.clone().join(suffix);
 
             fs::create_dir_all(dst.parent().unwrap()).unwrap();
-            write_feather_eager(&dst, lidar);
+            write_feather_eager(&dst, lidar).unwrap();
             bar.inc(1)
         }
     }
@@ -11,8 +11,9 @@ use std::{fs, path::PathBuf};
 
 use av2::{
     data_loader::DataLoader,
-    geometry::polytope::{compute_interior_points_mask, cuboids_to_polygons},
+    geometry::{aggregation::TIMEDELTA_COLUMN, polytope::compute_interior_points_mask},
     io::write_feather_eager,
+    structures::cuboid::CuboidList,
 };
 use indicatif::ProgressBar;
 
@@ -26,8 +27,8 @@ use polars::{
 };
 use std::collections::HashMap;
 
-/// Constants can be changed to fit your directory structure.
-/// However, it's recommend to place the datasets in the default folders.
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
 
 /// Root directory to datasets.
 static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
@@ -61,7 +62,7 @@ static EXPORTED_COLUMN_NAMES: Lazy<Vec<&str>> = Lazy::new(|| {
         "intensity",
         "laser_number",
         "offset_ns",
-        "timedelta_ns",
+        TIMEDELTA_COLUMN,
     ]
 });
 
@@ -86,26 +87,16 @@ pub fn main() {
         let mut category_counter: HashMap<String, u64> = HashMap::new();
         let bar = ProgressBar::new(data_loader.len() as u64);
         for sweep in data_loader {
+            let sweep = sweep.unwrap();
             let lidar = &sweep.lidar.0;
             let lidar_ndarray = lidar.to_ndarray::<Float32Type>(IndexOrder::C).unwrap();
 
-            let cuboids = sweep.cuboids.unwrap().0;
-            let category = cuboids["category"]
-                .utf8()
-                .unwrap()
-                .into_iter()
-                .map(|x| x.unwrap())
-                .collect_vec()
-                .clone();
-
-            let cuboids = cuboids
-                .clone()
-                .to_ndarray::<Float32Type>(IndexOrder::C)
-                .unwrap();
-            let cuboid_vertices = cuboids_to_polygons(&cuboids.view());
+            let cuboids = CuboidList::from_data_frame(&sweep.cuboids.unwrap().0).unwrap();
+            let category = cuboids.categories().unwrap();
+            let cuboid_vertices = cuboids.vertices();
             let points = lidar_ndarray.slice(s![.., ..3]);
             let mask = compute_interior_points_mask(&points.view(), &cuboid_vertices.view());
-            for (c, m) in category.into_iter().zip(mask.outer_iter()) {
+            for (c, m) in category.iter().zip(mask.outer_iter()) {
                 let indices = m
                     .iter()
                     .enumerate()
@@ -122,11 +113,11 @@ pub fn main() {
                     .entry(c.to_string())
                     .and_modify(|count| *count += 1)
                     .or_insert(0);
-                let count = category_counter.get(&c.to_string()).unwrap();
+                let count = category_counter.get(c).unwrap();
 
                 let dst = DST_PREFIX.join(c).join(format!("{count:08}.feather"));
                 fs::create_dir_all(dst.parent().unwrap()).unwrap();
-                write_feather_eager(&dst, data_frame_i);
+                write_feather_eager(&dst, data_frame_i).unwrap();
             }
             bar.inc(1);
         }
@@ -138,7 +129,7 @@ pub fn main() {
             df!("category" => category, "count" => count, "num_padding" => num_padding).unwrap();
 
         let dst = DST_PREFIX.join("_index.feather");
-        write_feather_eager(&dst, index);
+        write_feather_eager(&dst, index).unwrap();
     }
 }
 
@@ -164,7 +155,7 @@ fn _build_data_frame(arr: Array<f32, Ix2>, column_names: Vec<&str>) -> DataFrame
                 "offset_ns",
                 column.to_owned().mapv(|x| x as u32).into_raw_vec(),
             ),
-            "timedelta_ns" => Series::new("timedelta_ns", column.to_owned().into_raw_vec()),
+            TIMEDELTA_COLUMN => Series::new(TIMEDELTA_COLUMN, column.to_owned().into_raw_vec()),
             _ => panic!(),
         })
         .collect_vec();
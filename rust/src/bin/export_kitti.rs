@@ -0,0 +1,64 @@
+//! # export_kitti
+//!
+//! Exports the sensor dataset in the KITTI object detection format.
+//! Each split is written to its own KITTI split directory.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::{
+    data_loader::DataLoader,
+    export::kitti::{export_split, KittiExportConfig},
+};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type.
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names for the dataset.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+/// Number of accumulated sweeps.
+const NUM_ACCUMULATED_SWEEPS: usize = 1;
+
+/// Memory maps the sweeps for fast pre-processing. Requires .feather files to be uncompressed.
+const MEMORY_MAPPED: bool = false;
+
+static SRC_PREFIX: Lazy<PathBuf> = Lazy::new(|| ROOT_DIR.join(DATASET_NAME).join(DATASET_TYPE));
+static DST_PREFIX: Lazy<PathBuf> = Lazy::new(|| ROOT_DIR.join(format!("{DATASET_NAME}_kitti")));
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    let cfg = KittiExportConfig::default();
+    for split_name in SPLIT_NAMES.clone() {
+        if !SRC_PREFIX.join(split_name).exists() {
+            error!("Cannot find `{split_name}` split. Skipping ...");
+            continue;
+        }
+        let data_loader = DataLoader::new(
+            ROOT_DIR.clone().to_str().unwrap(),
+            DATASET_NAME,
+            DATASET_TYPE,
+            split_name,
+            NUM_ACCUMULATED_SWEEPS,
+            MEMORY_MAPPED,
+        );
+        let num_frames = export_split(&data_loader, &DST_PREFIX.join(split_name), &cfg).unwrap();
+        info!("Exported {num_frames} `{split_name}` frames.");
+    }
+}
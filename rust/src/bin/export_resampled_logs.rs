@@ -0,0 +1,62 @@
+//! # export_resampled_logs
+//!
+//! Resamples sensor logs to a lower frame rate.
+//! Defaults to 2 Hz (every fifth lidar sweep).
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::{path::walk_dir, resample::resample_log};
+use indicatif::ProgressBar;
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type. This will either be "lidar" or "sensor".
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names for the dataset.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+/// Target frame rate (in Hz).
+const TARGET_FRAME_RATE_HZ: f32 = 2.;
+
+static DST_DATASET_NAME: Lazy<String> =
+    Lazy::new(|| format!("{DATASET_NAME}_{TARGET_FRAME_RATE_HZ}hz"));
+static SRC_PREFIX: Lazy<PathBuf> = Lazy::new(|| ROOT_DIR.join(DATASET_NAME).join(DATASET_TYPE));
+static DST_PREFIX: Lazy<PathBuf> =
+    Lazy::new(|| ROOT_DIR.join(DST_DATASET_NAME.clone()).join(DATASET_TYPE));
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    for split_name in SPLIT_NAMES.clone() {
+        let src_split_dir = SRC_PREFIX.join(split_name);
+        if !src_split_dir.exists() {
+            error!("Cannot find `{split_name}` split. Skipping ...");
+            continue;
+        }
+        let log_dirs = walk_dir(&src_split_dir).unwrap();
+        let bar = ProgressBar::new(log_dirs.len() as u64);
+        for src_log_dir in log_dirs.iter().filter(|x| x.is_dir()) {
+            let log_id = src_log_dir.file_name().unwrap();
+            let dst_log_dir = DST_PREFIX.join(split_name).join(log_id);
+            if let Err(err) = resample_log(src_log_dir, &dst_log_dir, TARGET_FRAME_RATE_HZ) {
+                error!("Failed to resample {}: {err:#}", src_log_dir.display());
+            }
+            bar.inc(1)
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! # export_webdataset
+//!
+//! Preprocesses the sensor dataset into size-bounded tar shards (WebDataset convention).
+//! Each split is written to its own shard directory with an `index.json`.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::{
+    data_loader::DataLoader,
+    geometry::voxelize::VoxelizationConfig,
+    io::webdataset::{write_split_shards, ShardWriterConfig},
+};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type.
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names for the dataset.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+/// Number of accumulated sweeps.
+const NUM_ACCUMULATED_SWEEPS: usize = 5;
+
+/// Memory maps the sweeps for fast pre-processing. Requires .feather files to be uncompressed.
+const MEMORY_MAPPED: bool = false;
+
+/// Maximum size of a shard (in bytes).
+const MAX_SHARD_SIZE_BYTES: u64 = 1 << 30;
+
+static SRC_PREFIX: Lazy<PathBuf> = Lazy::new(|| ROOT_DIR.join(DATASET_NAME).join(DATASET_TYPE));
+static DST_PREFIX: Lazy<PathBuf> =
+    Lazy::new(|| ROOT_DIR.join(format!("{DATASET_NAME}_webdataset")));
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    let voxelization_cfg = VoxelizationConfig::default();
+    for split_name in SPLIT_NAMES.clone() {
+        if !SRC_PREFIX.join(split_name).exists() {
+            error!("Cannot find `{split_name}` split. Skipping ...");
+            continue;
+        }
+        let data_loader = DataLoader::new(
+            ROOT_DIR.clone().to_str().unwrap(),
+            DATASET_NAME,
+            DATASET_TYPE,
+            split_name,
+            NUM_ACCUMULATED_SWEEPS,
+            MEMORY_MAPPED,
+        );
+        let cfg = ShardWriterConfig {
+            output_dir: DST_PREFIX.join(split_name),
+            prefix: split_name.to_string(),
+            max_shard_size_bytes: MAX_SHARD_SIZE_BYTES,
+            ..Default::default()
+        };
+        let index = write_split_shards(&data_loader, &cfg, &voxelization_cfg).unwrap();
+        info!(
+            "Wrote {} `{split_name}` samples to {} shards.",
+            index.samples.len(),
+            index.shards.len()
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! # generate_mini_dataset
+//!
+//! Generates a tiny, schema-complete synthetic sensor dataset for integration tests.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::synthetic::{generate_mini_dataset, MiniDatasetConfig};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+
+/// Root directory of the generated dataset.
+static ROOT_DIR: Lazy<PathBuf> =
+    Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/mini/"));
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    match generate_mini_dataset(&ROOT_DIR, &MiniDatasetConfig::default()) {
+        Ok(log_dirs) => info!(
+            "Generated {} logs in {}.",
+            log_dirs.len(),
+            ROOT_DIR.display()
+        ),
+        Err(err) => error!("Failed to generate the dataset: {err:#}"),
+    }
+}
@@ -0,0 +1,54 @@
+//! # preprocess_vector_maps
+//!
+//! Converts the JSON vector maps of every log into cached feather tables with spatial indices.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::map::preprocess_vector_maps;
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type. This will either be "lidar" or "sensor".
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names for the dataset.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+static SRC_PREFIX: Lazy<PathBuf> = Lazy::new(|| ROOT_DIR.join(DATASET_NAME).join(DATASET_TYPE));
+static DST_PREFIX: Lazy<PathBuf> = Lazy::new(|| {
+    ROOT_DIR
+        .join(format!("{DATASET_NAME}_maps"))
+        .join(DATASET_TYPE)
+});
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    for split_name in SPLIT_NAMES.clone() {
+        let src_split_dir = SRC_PREFIX.join(split_name);
+        if !src_split_dir.exists() {
+            error!("Cannot find `{split_name}` split. Skipping ...");
+            continue;
+        }
+        match preprocess_vector_maps(&src_split_dir, &DST_PREFIX.join(split_name)) {
+            Ok(failed) => failed
+                .iter()
+                .for_each(|log_id| error!("Failed to preprocess the map of {log_id}.")),
+            Err(err) => error!("Failed to preprocess `{split_name}`: {err:#}"),
+        }
+    }
+}
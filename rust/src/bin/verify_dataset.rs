@@ -0,0 +1,56 @@
+//! # verify_dataset
+//!
+//! Verifies the downloaded splits of a dataset against their recorded file sizes and reports the
+//! logs which are missing, incomplete, or corrupt.
+
+#[cfg(feature = "blas")]
+extern crate blas_src;
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use av2::integrity::{file_sizes_path, read_file_sizes, verify_split};
+
+use once_cell::sync::Lazy;
+
+// Constants can be changed to fit your directory structure.
+// However, it's recommend to place the datasets in the default folders.
+
+/// Root directory to datasets.
+static ROOT_DIR: Lazy<PathBuf> = Lazy::new(|| dirs::home_dir().unwrap().join("data/datasets/"));
+
+/// Dataset name.
+static DATASET_NAME: &str = "av2";
+
+/// Dataset type. This will either be "lidar" or "sensor".
+static DATASET_TYPE: &str = "sensor";
+
+/// Split names to verify.
+static SPLIT_NAMES: Lazy<Vec<&str>> = Lazy::new(|| vec!["train", "val", "test"]);
+
+/// Validates the format of the files of the expected size (e.g., intact feather footers).
+const VALIDATE_FORMATS: bool = false;
+
+/// Script entrypoint.
+pub fn main() {
+    env_logger::init();
+    for split_name in SPLIT_NAMES.clone() {
+        let split_dir = ROOT_DIR
+            .join(DATASET_NAME)
+            .join(DATASET_TYPE)
+            .join(split_name);
+        let path = file_sizes_path(&split_dir);
+        let file_sizes = match read_file_sizes(&path) {
+            Ok(file_sizes) => file_sizes,
+            Err(err) => {
+                error!("Cannot verify `{split_name}` split: {err:#}");
+                continue;
+            }
+        };
+        match verify_split(&split_dir, &file_sizes, VALIDATE_FORMATS) {
+            Ok(report) => println!("{split_name}: {report}"),
+            Err(err) => error!("Failed to verify `{split_name}` split: {err:#}"),
+        }
+    }
+}
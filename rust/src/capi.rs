@@ -0,0 +1,510 @@
+//! # capi
+//!
+//! Stable C ABI over the data-loader and the cuboid geometry kernels.
+//!
+//! Arrays cross the boundary as flat, row-major buffers together with their leading dimension.
+//! Outputs of a known size are written into caller-allocated buffers, while sweeps are returned as
+//! opaque handles that own their buffers until released with `av2_sweep_free`. Every function
+//! catches panics, so errors surface as an `Av2Status` (or a null handle) rather than unwinding
+//! into C. The header `include/av2.h` is generated from this module with `cbindgen`.
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use ndarray::{Array, ArrayView, Ix2};
+use polars::prelude::*;
+
+use crate::{
+    data_loader::DataLoader,
+    geometry::{
+        iou::{cuboid_iou, IouMode},
+        nms::non_maximum_suppression,
+        polytope::{compute_interior_points_mask, cuboids_to_polygons},
+    },
+    io::ndarray_from_frame,
+    structures::cuboid::CUBOID_COLUMNS,
+};
+
+/// Number of parameters of a cuboid (see `CUBOID_COLUMNS`).
+pub const AV2_CUBOID_DIM: usize = 10;
+
+/// Number of features of a lidar point (`x`, `y`, `z`, and `intensity`).
+pub const AV2_LIDAR_DIM: usize = 4;
+
+/// Status code returned by the C API.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Av2Status {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// An argument was out of range or malformed.
+    InvalidArgument = 2,
+    /// The call failed internally (e.g., a missing or corrupt file).
+    Internal = 3,
+}
+
+/// Overlap computation mode of the IoU and NMS functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Av2IouMode {
+    /// Overlap of the bird's-eye-view (xy-plane) footprints.
+    Bev = 0,
+    /// Volumetric overlap.
+    ThreeD = 1,
+}
+
+impl From<Av2IouMode> for IouMode {
+    fn from(mode: Av2IouMode) -> Self {
+        match mode {
+            Av2IouMode::Bev => IouMode::Bev,
+            Av2IouMode::ThreeD => IouMode::ThreeD,
+        }
+    }
+}
+
+/// Opaque data-loader handle.
+pub struct Av2DataLoader(DataLoader);
+
+/// Opaque sweep handle owning its lidar and cuboid buffers.
+pub struct Av2Sweep {
+    log_id: CString,
+    timestamp_ns: u64,
+    /// (N,4) `x`, `y`, `z`, and `intensity` of each point.
+    lidar: Array<f32, Ix2>,
+    /// (M,10) cuboids in `CUBOID_COLUMNS` order, if annotations are available.
+    cuboids: Option<Array<f32, Ix2>>,
+}
+
+/// Open the sensor dataset split at `root_dir/dataset_name/dataset_type/split_name`, or return
+/// null if any argument is null or the split cannot be indexed. Release the handle with
+/// `av2_data_loader_free`.
+///
+/// # Safety
+///
+/// The string arguments must be null or valid NUL-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn av2_data_loader_new(
+    root_dir: *const c_char,
+    dataset_name: *const c_char,
+    dataset_type: *const c_char,
+    split_name: *const c_char,
+    num_accumulated_sweeps: usize,
+) -> *mut Av2DataLoader {
+    let arguments = [root_dir, dataset_name, dataset_type, split_name]
+        .into_iter()
+        .map(|x| _str(x))
+        .collect::<Option<Vec<_>>>();
+    let Some([root_dir, dataset_name, dataset_type, split_name]) =
+        arguments.and_then(|x| <[&str; 4]>::try_from(x).ok())
+    else {
+        return ptr::null_mut();
+    };
+    catch_unwind(|| {
+        DataLoader::new(
+            root_dir,
+            dataset_name,
+            dataset_type,
+            split_name,
+            num_accumulated_sweeps,
+            false,
+        )
+    })
+    .map_or(ptr::null_mut(), |x| {
+        Box::into_raw(Box::new(Av2DataLoader(x)))
+    })
+}
+
+/// Release a data-loader handle. Null is a no-op.
+///
+/// # Safety
+///
+/// `data_loader` must be null or a handle returned by `av2_data_loader_new` that was not released.
+#[no_mangle]
+pub unsafe extern "C" fn av2_data_loader_free(data_loader: *mut Av2DataLoader) {
+    if !data_loader.is_null() {
+        drop(Box::from_raw(data_loader));
+    }
+}
+
+/// Number of sweeps of the data-loader (`0` if it is null).
+///
+/// # Safety
+///
+/// `data_loader` must be null or a live handle returned by `av2_data_loader_new`.
+#[no_mangle]
+pub unsafe extern "C" fn av2_data_loader_len(data_loader: *const Av2DataLoader) -> usize {
+    data_loader.as_ref().map_or(0, |x| x.0.len())
+}
+
+/// Load the sweep at `index`, or return null if the data-loader is null, the index is out of
+/// range, or the sweep cannot be read. Release the handle with `av2_sweep_free`.
+///
+/// # Safety
+///
+/// `data_loader` must be null or a live handle returned by `av2_data_loader_new`.
+#[no_mangle]
+pub unsafe extern "C" fn av2_data_loader_get(
+    data_loader: *const Av2DataLoader,
+    index: usize,
+) -> *mut Av2Sweep {
+    let Some(data_loader) = data_loader.as_ref() else {
+        return ptr::null_mut();
+    };
+    if index >= data_loader.0.len() {
+        return ptr::null_mut();
+    }
+    catch_unwind(AssertUnwindSafe(|| {
+        let sweep = data_loader.0.try_get(index).ok()?;
+        let (log_id, timestamp_ns) = sweep.sweep_uuid;
+        Some(Av2Sweep {
+            log_id: CString::new(log_id).unwrap(),
+            timestamp_ns,
+            lidar: ndarray_from_frame(&sweep.lidar.0, cols(["x", "y", "z", "intensity"])).ok()?,
+            cuboids: sweep
+                .cuboids
+                .map(|x| ndarray_from_frame(&x.0, cols(CUBOID_COLUMNS)))
+                .transpose()
+                .ok()?,
+        })
+    }))
+    .ok()
+    .flatten()
+    .map_or(ptr::null_mut(), |x| Box::into_raw(Box::new(x)))
+}
+
+/// Release a sweep handle. Null is a no-op.
+///
+/// # Safety
+///
+/// `sweep` must be null or a handle returned by `av2_data_loader_get` that was not released.
+#[no_mangle]
+pub unsafe extern "C" fn av2_sweep_free(sweep: *mut Av2Sweep) {
+    if !sweep.is_null() {
+        drop(Box::from_raw(sweep));
+    }
+}
+
+/// Log id of the sweep, valid until the sweep is released (null if the sweep is null).
+///
+/// # Safety
+///
+/// `sweep` must be null or a live handle returned by `av2_data_loader_get`.
+#[no_mangle]
+pub unsafe extern "C" fn av2_sweep_log_id(sweep: *const Av2Sweep) -> *const c_char {
+    sweep.as_ref().map_or(ptr::null(), |x| x.log_id.as_ptr())
+}
+
+/// Nanosecond timestamp of the sweep (`0` if the sweep is null).
+///
+/// # Safety
+///
+/// `sweep` must be null or a live handle returned by `av2_data_loader_get`.
+#[no_mangle]
+pub unsafe extern "C" fn av2_sweep_timestamp_ns(sweep: *const Av2Sweep) -> u64 {
+    sweep.as_ref().map_or(0, |x| x.timestamp_ns)
+}
+
+/// (N,4) row-major `x`, `y`, `z`, and `intensity` of the sweep's points, valid until the sweep is
+/// released. `N` is written to `num_points`.
+///
+/// # Safety
+///
+/// `sweep` must be null or a live handle returned by `av2_data_loader_get`, and `num_points` must
+/// be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn av2_sweep_lidar(
+    sweep: *const Av2Sweep,
+    num_points: *mut usize,
+) -> *const f32 {
+    _buffer(sweep.as_ref().map(|x| &x.lidar), num_points)
+}
+
+/// (M,10) row-major cuboids of the sweep in `CUBOID_COLUMNS` order, valid until the sweep is
+/// released. `M` is written to `num_cuboids`. Returns null (with `M = 0`) if the sweep has no
+/// annotations (e.g., in the test split).
+///
+/// # Safety
+///
+/// `sweep` must be null or a live handle returned by `av2_data_loader_get`, and `num_cuboids`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn av2_sweep_cuboids(
+    sweep: *const Av2Sweep,
+    num_cuboids: *mut usize,
+) -> *const f32 {
+    _buffer(sweep.as_ref().and_then(|x| x.cuboids.as_ref()), num_cuboids)
+}
+
+/// Write the (M,N) mask of which of the (N,3) `points` are interior to each of the (M,10)
+/// `cuboids` into `mask`.
+///
+/// # Safety
+///
+/// `points` must hold `3 * num_points` floats, `cuboids` `10 * num_cuboids` floats, and `mask`
+/// must be valid for `num_cuboids * num_points` writes.
+#[no_mangle]
+pub unsafe extern "C" fn av2_interior_points_mask(
+    points: *const f32,
+    num_points: usize,
+    cuboids: *const f32,
+    num_cuboids: usize,
+    mask: *mut bool,
+) -> Av2Status {
+    let (Some(points), Some(cuboids)) = (
+        _view(points, num_points, 3),
+        _view(cuboids, num_cuboids, AV2_CUBOID_DIM),
+    ) else {
+        return Av2Status::NullPointer;
+    };
+    _call(|| {
+        let vertices = cuboids_to_polygons(&cuboids);
+        let is_interior = compute_interior_points_mask(&points, &vertices.view());
+        _write(is_interior.as_slice().unwrap(), mask)
+    })
+}
+
+/// Write the (N,M) pairwise IoU between (N,10) `cuboids_a` and (M,10) `cuboids_b` into `iou`.
+///
+/// # Safety
+///
+/// `cuboids_a` must hold `10 * num_cuboids_a` floats, `cuboids_b` `10 * num_cuboids_b` floats,
+/// and `iou` must be valid for `num_cuboids_a * num_cuboids_b` writes.
+#[no_mangle]
+pub unsafe extern "C" fn av2_cuboid_iou(
+    cuboids_a: *const f32,
+    num_cuboids_a: usize,
+    cuboids_b: *const f32,
+    num_cuboids_b: usize,
+    mode: Av2IouMode,
+    iou: *mut f32,
+) -> Av2Status {
+    let (Some(cuboids_a), Some(cuboids_b)) = (
+        _view(cuboids_a, num_cuboids_a, AV2_CUBOID_DIM),
+        _view(cuboids_b, num_cuboids_b, AV2_CUBOID_DIM),
+    ) else {
+        return Av2Status::NullPointer;
+    };
+    _call(|| {
+        let iou_ab = cuboid_iou(&cuboids_a, &cuboids_b, mode.into());
+        _write(iou_ab.as_slice().unwrap(), iou)
+    })
+}
+
+/// Greedy rotated non-maximum suppression of (N,10) `cuboids` with (N,) `scores`. The kept indices
+/// are written to `keep` by descending score, and their number to `num_keep`.
+///
+/// # Safety
+///
+/// `cuboids` must hold `10 * num_cuboids` floats and `scores` `num_cuboids` floats, `keep` must be
+/// valid for `num_cuboids` writes, and `num_keep` for one.
+#[no_mangle]
+pub unsafe extern "C" fn av2_nms(
+    cuboids: *const f32,
+    scores: *const f32,
+    num_cuboids: usize,
+    iou_threshold: f32,
+    mode: Av2IouMode,
+    keep: *mut usize,
+    num_keep: *mut usize,
+) -> Av2Status {
+    let (Some(cuboids), Some(scores)) = (
+        _view(cuboids, num_cuboids, AV2_CUBOID_DIM),
+        _view(scores, num_cuboids, 1),
+    ) else {
+        return Av2Status::NullPointer;
+    };
+    if num_keep.is_null() {
+        return Av2Status::NullPointer;
+    }
+    if iou_threshold.is_nan() {
+        return Av2Status::InvalidArgument;
+    }
+    _call(|| {
+        let Ok(kept) =
+            non_maximum_suppression(&cuboids, &scores.column(0), iou_threshold, mode.into())
+        else {
+            return Av2Status::InvalidArgument;
+        };
+        let status = _write(kept.as_slice().unwrap(), keep);
+        if status == Av2Status::Ok {
+            *num_keep = kept.len();
+        }
+        status
+    })
+}
+
+/// Borrow a NUL-terminated UTF-8 string, if it is non-null and valid.
+unsafe fn _str<'a>(string: *const c_char) -> Option<&'a str> {
+    match string.is_null() {
+        true => None,
+        false => CStr::from_ptr(string).to_str().ok(),
+    }
+}
+
+/// View a (rows,cols) row-major buffer. Empty buffers may be null.
+unsafe fn _view<'a>(data: *const f32, rows: usize, cols: usize) -> Option<ArrayView<'a, f32, Ix2>> {
+    match (rows * cols, data.is_null()) {
+        (0, _) => Some(ArrayView::from_shape((rows, cols), &[]).unwrap()),
+        (_, true) => None,
+        (_, false) => Some(ArrayView::from_shape_ptr((rows, cols), data)),
+    }
+}
+
+/// Copy `values` into `out`, which may only be null if `values` is empty.
+unsafe fn _write<T: Copy>(values: &[T], out: *mut T) -> Av2Status {
+    match (values.is_empty(), out.is_null()) {
+        (true, _) => Av2Status::Ok,
+        (false, true) => Av2Status::NullPointer,
+        (false, false) => {
+            slice::from_raw_parts_mut(out, values.len()).copy_from_slice(values);
+            Av2Status::Ok
+        }
+    }
+}
+
+/// Pointer to a row-major array owned by a handle, writing its number of rows to `len`.
+unsafe fn _buffer(array: Option<&Array<f32, Ix2>>, len: *mut usize) -> *const f32 {
+    if !len.is_null() {
+        *len = array.map_or(0, |x| x.nrows());
+    }
+    array.map_or(ptr::null(), |x| x.as_ptr())
+}
+
+/// Run `f`, reporting a panic as an internal error.
+fn _call(f: impl FnOnce() -> Av2Status) -> Av2Status {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(Av2Status::Internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ffi::{CStr, CString},
+        ptr,
+    };
+
+    use super::{
+        av2_cuboid_iou, av2_data_loader_free, av2_data_loader_get, av2_data_loader_len,
+        av2_data_loader_new, av2_interior_points_mask, av2_nms, av2_sweep_cuboids, av2_sweep_free,
+        av2_sweep_lidar, av2_sweep_log_id, av2_sweep_timestamp_ns, Av2IouMode, Av2Status,
+    };
+    use crate::synthetic::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig};
+
+    #[test]
+    fn test_geometry() {
+        let cuboids = [
+            [0_f32, 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [0.5, 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [10., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+        ];
+        let points = [[0_f32, 0., 0.], [10., 0., 0.5], [5., 5., 5.]];
+        unsafe {
+            let mut mask = [false; 9];
+            let status = av2_interior_points_mask(
+                points.as_ptr().cast(),
+                3,
+                cuboids.as_ptr().cast(),
+                3,
+                mask.as_mut_ptr(),
+            );
+            assert_eq!(status, Av2Status::Ok);
+            assert_eq!(
+                mask,
+                [true, false, false, true, false, false, false, true, false]
+            );
+
+            let mut iou = [0_f32; 9];
+            let status = av2_cuboid_iou(
+                cuboids.as_ptr().cast(),
+                3,
+                cuboids.as_ptr().cast(),
+                3,
+                Av2IouMode::Bev,
+                iou.as_mut_ptr(),
+            );
+            assert_eq!(status, Av2Status::Ok);
+            assert!((iou[0] - 1.).abs() < 1e-6);
+            assert!((iou[1] - 0.6).abs() < 1e-6);
+            assert_eq!(iou[2], 0.);
+
+            let scores = [0.5_f32, 0.9, 0.1];
+            let (mut keep, mut num_keep) = ([0_usize; 3], 0);
+            let status = av2_nms(
+                cuboids.as_ptr().cast(),
+                scores.as_ptr(),
+                3,
+                0.5,
+                Av2IouMode::Bev,
+                keep.as_mut_ptr(),
+                &mut num_keep,
+            );
+            assert_eq!(status, Av2Status::Ok);
+            assert_eq!(&keep[..num_keep], &[1, 2]);
+
+            let status = av2_nms(
+                ptr::null(),
+                scores.as_ptr(),
+                3,
+                0.5,
+                Av2IouMode::Bev,
+                keep.as_mut_ptr(),
+                &mut num_keep,
+            );
+            assert_eq!(status, Av2Status::NullPointer);
+        }
+    }
+
+    #[test]
+    fn test_data_loader() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let root = CString::new(root_dir.to_str().unwrap()).unwrap();
+        unsafe {
+            let data_loader = av2_data_loader_new(
+                root.as_ptr(),
+                c"av2".as_ptr(),
+                c"sensor".as_ptr(),
+                c"val".as_ptr(),
+                1,
+            );
+            assert!(!data_loader.is_null());
+            assert_eq!(av2_data_loader_len(data_loader), 2);
+            assert!(av2_data_loader_get(data_loader, 2).is_null());
+
+            let sweep = av2_data_loader_get(data_loader, 1);
+            assert!(!sweep.is_null());
+            assert!(av2_sweep_timestamp_ns(sweep) > 0);
+            assert!(!CStr::from_ptr(av2_sweep_log_id(sweep)).is_empty());
+            let (mut num_points, mut num_cuboids) = (0, 0);
+            assert!(!av2_sweep_lidar(sweep, &mut num_points).is_null());
+            assert_eq!(num_points, 1024 + 4 * 20);
+            assert!(!av2_sweep_cuboids(sweep, &mut num_cuboids).is_null());
+            assert_eq!(num_cuboids, 4);
+            av2_sweep_free(sweep);
+            av2_data_loader_free(data_loader);
+
+            // Null arguments yield null handles.
+            let data_loader = av2_data_loader_new(
+                root.as_ptr(),
+                c"av2".as_ptr(),
+                ptr::null(),
+                c"val".as_ptr(),
+                1,
+            );
+            assert!(data_loader.is_null());
+            assert_eq!(av2_data_loader_len(data_loader), 0);
+        }
+    }
+}
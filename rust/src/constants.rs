@@ -22,10 +22,37 @@ pub const ANNOTATION_COLUMNS: [&str; 13] = [
     "track_uuid",
 ];
 
+/// Annotation file columns in their canonical order.
+/// Found in `annotations.feather`.
+pub const ANNOTATION_FILE_COLUMNS: [&str; 14] = [
+    "timestamp_ns",
+    "track_uuid",
+    "category",
+    "length_m",
+    "width_m",
+    "height_m",
+    "qw",
+    "qx",
+    "qy",
+    "qz",
+    "tx_m",
+    "ty_m",
+    "tz_m",
+    "num_interior_pts",
+];
+
+/// Lidar sweep columns in their canonical order.
+/// Found in `sensors/lidar/<timestamp_ns>.feather`.
+pub const LIDAR_COLUMNS: [&str; 6] = ["x", "y", "z", "intensity", "laser_number", "offset_ns"];
+
 /// Pose dataframe columns.
 /// Found in `city_SE3_egovehicle`.
 pub const POSE_COLUMNS: [&str; 7] = ["tx_m", "ty_m", "tz_m", "qw", "qx", "qy", "qz"];
 
+/// Number of lasers per lidar.
+/// Laser numbers in `[0, 32)` belong to the up lidar and `[32, 64)` to the down lidar.
+pub const NUM_LASERS_PER_LIDAR: u8 = 32;
+
 /// Unknown map file name for use if the map doesn't exist.
 pub const DEFAULT_MAP_FILE_NAME: &str = "log_map_archive___DEFAULT_city_00000.json";
 
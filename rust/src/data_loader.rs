@@ -2,57 +2,117 @@
 //!
 //! Data-loader for loading the sensor dataset.
 
-use constants::{ANNOTATION_COLUMNS, POSE_COLUMNS};
+/// Configuration of the data-loader.
+pub mod config;
+
+use anyhow::Context;
+use constants::{ANNOTATION_COLUMNS, NUM_LASERS_PER_LIDAR, POSE_COLUMNS};
 
-use image::ImageBuffer;
 use image::Rgba;
-use io::{read_accumulate_lidar, read_timestamped_feather};
+use image::{DynamicImage, ImageBuffer};
+use io::{
+    capsule::ArrowTable,
+    decode_image_rgba8,
+    packed::{PackedLayout, PackedSample},
+    read_accumulate_lidar, read_feather, read_timestamped_feather, MemoryMapConfig, RangePredicate,
+    ScanSelection, ThreadPools,
+};
 use itertools::Itertools;
-use ndarray::Ix3;
+use ndarray::{Array, Ix2, Ix3};
 use nshare::ToNdarray3;
 use numpy::IntoPyArray;
 use numpy::PyArray;
+use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*;
 use pyo3_polars::PyDataFrame;
-use rayon::prelude::IntoParallelRefIterator;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator};
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::{Arc, Mutex, RwLock},
 };
 use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumString};
+use tracing::info_span;
 
 use glob::glob;
 use polars::prelude::*;
 
-use crate::io::read_image_rgba8;
 use crate::{
     constants::{self, CameraNames},
-    geometry::camera::pinhole_camera::PinholeCamera,
+    data_loader::config::{validate_max_latency_jitter_ns, DataLoaderConfig},
+    error::{to_py_err, Av2Error, Av2Result},
+    geometry::{
+        aggregation::{AccumulationConfig, AccumulationFrame},
+        augmentations::{AppliedAugmentation, AugmentationScratch},
+        camera::pinhole_camera::PinholeCamera,
+        derived_columns::{append_derived_columns, parse_derived_columns},
+        ground::GroundEstimationConfig,
+        interpolate::{interpolate_city_se3_ego, interpolate_track},
+        motion_compensation::transform_sweep,
+        polytope::Polygon,
+        roi::{filter_cuboids_to_drivable_area, filter_lidar_to_drivable_area, RoiConfig},
+        se3::SE3,
+    },
     io::{self},
-    structures::timestamped_image::TimeStampedImage,
+    manifest::{read_dataset_manifest, Manifest, ManifestUpdate, DATASET_MANIFEST_COLUMNS},
+    map::{static_map::ArgoverseStaticMap, vector_map_json_path},
+    path::{extract_file_stem, walk_dir},
+    prefetch::Prefetcher,
+    schema::{validate_data_frame, ANNOTATION_SCHEMA, POSE_SCHEMA},
+    structures::{
+        cuboid::{CuboidList, CUBOID_COLUMNS},
+        timestamped_image::TimeStampedImage,
+    },
+    tbv::{self, TBV_DATASET_TYPE},
 };
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::ParallelIterator;
 
 const MIN_NUM_LIDAR_PTS: u64 = 1;
-const MAX_CAM_LIDAR_TOL_NS: f32 = 50000000.;
+
+/// Name of the training class id column appended to the annotations.
+pub const CLASS_ID_COLUMN: &str = "class_id";
+pub(crate) const MAX_CAM_LIDAR_TOL_NS: f32 = 50000000.;
 
 /// Data associated with a single lidar sweep.
 #[pyclass]
 #[derive(Clone, Debug)]
 pub struct Sweep {
-    /// Ego-vehicle city pose.
+    /// Ego-vehicle city pose (if the log's poses are available).
     #[pyo3(get, set)]
-    pub city_pose: PyDataFrame,
+    pub city_pose: Option<PyDataFrame>,
     /// Point cloud associated with the sweep.
     #[pyo3(get, set)]
     pub lidar: PyDataFrame,
     /// Log id and nanosecond timestamp (unique identifier).
     #[pyo3(get, set)]
     pub sweep_uuid: (String, u64),
-    /// Cuboids associated with the sweep.
+    /// Cuboids associated with the sweep (if the log's annotations are available).
     #[pyo3(get, set)]
     pub cuboids: Option<PyDataFrame>,
+    /// Ring camera images nearest to the sweep (if the data-loader's `load_images` is set).
+    /// Cameras without calibration or without an image near the sweep are omitted.
+    #[pyo3(get, set)]
+    pub images: Option<Vec<SynchronizedImage>>,
+    /// (4,4) egovehicle pose in the city frame interpolated at the sweep timestamp (if the log's
+    /// poses are available).
+    pub city_se3_ego: Option<Array<f32, Ix2>>,
+    /// Sensor extrinsics with `sensor_name` and the `POSE_COLUMNS` (if the data-loader's
+    /// `load_extrinsics` is set and the log's calibration is available).
+    #[pyo3(get, set)]
+    pub extrinsics: Option<PyDataFrame>,
+    /// Frame of the lidar points (`ego` for the egovehicle frame of the sweep, or `city`).
+    #[pyo3(get, set)]
+    pub lidar_frame: String,
+    /// Boolean flag indicating whether each lidar return was motion-compensated using its
+    /// `offset_ns`.
+    #[pyo3(get, set)]
+    pub motion_compensated: bool,
 }
 
 /// Encapsulates sensor data associated with a single sweep.
@@ -67,16 +127,209 @@ impl Sweep {
         sweep_uuid: (String, u64),
     ) -> Sweep {
         Sweep {
-            city_pose,
+            city_pose: Some(city_pose),
             lidar,
             sweep_uuid,
             cuboids: Some(annotations),
+            images: None,
+            city_se3_ego: None,
+            extrinsics: None,
+            lidar_frame: AccumulationFrame::Ego.to_string(),
+            motion_compensated: false,
+        }
+    }
+
+    /// Lidar table exported through the Arrow PyCapsule interface without copying.
+    #[pyo3(name = "lidar_arrow")]
+    fn py_lidar_arrow(&self) -> ArrowTable {
+        ArrowTable::from(self.lidar.0.clone())
+    }
+
+    /// Cuboid table exported through the Arrow PyCapsule interface without copying.
+    #[pyo3(name = "cuboids_arrow")]
+    fn py_cuboids_arrow(&self) -> Option<ArrowTable> {
+        self.cuboids.as_ref().map(|x| ArrowTable::from(x.0.clone()))
+    }
+
+    /// (4,4) egovehicle pose in the city frame interpolated at the sweep timestamp.
+    #[getter(city_se3_ego)]
+    fn py_city_se3_ego<'py>(&self, py: Python<'py>) -> Option<&'py PyArray<f32, Ix2>> {
+        self.city_se3_ego.clone().map(|x| x.into_pyarray(py))
+    }
+}
+
+impl Sweep {
+    /// Metadata of the sweep without its sensor data.
+    pub fn metadata(&self) -> SweepMetadata {
+        SweepMetadata {
+            log_id: self.sweep_uuid.0.clone(),
+            timestamp_ns: self.sweep_uuid.1,
+            num_points: self.lidar.0.height(),
+            num_cuboids: self.cuboids.as_ref().map(|x| x.0.height()),
+            city_se3_ego: self
+                .city_se3_ego
+                .as_ref()
+                .map(|x| SE3::from_transform_matrix(&x.view())),
+            lidar_frame: self.lidar_frame.clone(),
+            motion_compensated: self.motion_compensated,
+        }
+    }
+}
+
+/// Metadata of a sweep, which can be logged or cached without its sensor data.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SweepMetadata {
+    /// Log id of the sweep.
+    pub log_id: String,
+    /// Nanosecond timestamp of the sweep.
+    pub timestamp_ns: u64,
+    /// Number of lidar points.
+    pub num_points: usize,
+    /// Number of cuboids (if the log's annotations are available).
+    pub num_cuboids: Option<usize>,
+    /// Egovehicle pose in the city frame at the sweep timestamp (if the log's poses are
+    /// available).
+    pub city_se3_ego: Option<SE3>,
+    /// Frame of the lidar points (`ego` or `city`).
+    pub lidar_frame: String,
+    /// Boolean flag indicating whether each lidar return was motion-compensated.
+    pub motion_compensated: bool,
+}
+
+/// Consecutive sweeps of a log expressed in the egovehicle frame of the source sweep (e.g., the
+/// input of scene flow).
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct SweepPair {
+    /// Sweep at time `t`.
+    #[pyo3(get)]
+    pub source: Sweep,
+    /// Sweep at time `t + 1` with its lidar (unless accumulated in the city frame) and cuboids
+    /// transformed into the egovehicle frame of the source sweep.
+    #[pyo3(get)]
+    pub target: Sweep,
+    /// (4,4) egovehicle pose of the target sweep in the egovehicle frame of the source sweep.
+    pub source_se3_target: Array<f32, Ix2>,
+    /// Rows of the cuboids sharing a `track_uuid` in both sweeps (`track_uuid`, `source_index`,
+    /// and `target_index`), if the log's annotations are available.
+    #[pyo3(get)]
+    pub correspondences: Option<PyDataFrame>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl SweepPair {
+    /// (4,4) egovehicle pose of the target sweep in the egovehicle frame of the source sweep.
+    #[getter(source_se3_target)]
+    fn py_source_se3_target<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix2> {
+        self.source_se3_target.clone().into_pyarray(py)
+    }
+}
+
+/// Ring camera image synchronized to a sweep, with its camera's calibration.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct SynchronizedImage {
+    /// Camera name (e.g., `ring_front_center`).
+    #[pyo3(get)]
+    pub camera_name: String,
+    /// Nanosecond timestamp of the image (`u64::MAX` for a dropped camera).
+    #[pyo3(get)]
+    pub timestamp_ns: u64,
+    /// (H,W,3) RGB u8 image.
+    pub image: Array<u8, Ix3>,
+    /// (3,3) camera intrinsics matrix.
+    pub intrinsics: Array<f32, Ix2>,
+    /// (4,4) pose of the camera in the egovehicle frame.
+    pub ego_se3_cam: Array<f32, Ix2>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl SynchronizedImage {
+    /// (H,W,3) RGB u8 image.
+    #[getter(image)]
+    fn py_image<'py>(&self, py: Python<'py>) -> &'py PyArray<u8, Ix3> {
+        self.image.clone().into_pyarray(py)
+    }
+
+    /// (3,3) camera intrinsics matrix.
+    #[getter(intrinsics)]
+    fn py_intrinsics<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix2> {
+        self.intrinsics.clone().into_pyarray(py)
+    }
+
+    /// (4,4) pose of the camera in the egovehicle frame.
+    #[getter(ego_se3_cam)]
+    fn py_ego_se3_cam<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix2> {
+        self.ego_se3_cam.clone().into_pyarray(py)
+    }
+}
+
+impl From<TimeStampedImage> for SynchronizedImage {
+    fn from(image: TimeStampedImage) -> Self {
+        let (width, height) = image.image.dimensions();
+        let rgb = DynamicImage::ImageRgba8(image.image).into_rgb8();
+        Self {
+            camera_name: image.camera_model.camera_name.clone(),
+            timestamp_ns: image.timestamp_ns as u64,
+            image: Array::from_shape_vec((height as usize, width as usize, 3), rgb.into_raw())
+                .unwrap(),
+            intrinsics: image.camera_model.intrinsics.k(),
+            ego_se3_cam: image.camera_model.ego_se3_cam.transform_matrix(),
+        }
+    }
+}
+
+/// Modalities available in a log. Partial downloads (e.g., only lidar and annotations) leave the
+/// other modalities absent.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ModalityAvailability {
+    /// Lidar sweeps in `sensors/lidar`.
+    #[pyo3(get)]
+    pub lidar: bool,
+    /// Ring camera images in `sensors/cameras`.
+    #[pyo3(get)]
+    pub cameras: bool,
+    /// `annotations.feather`.
+    #[pyo3(get)]
+    pub annotations: bool,
+    /// `city_SE3_egovehicle.feather`.
+    #[pyo3(get)]
+    pub city_pose: bool,
+    /// `calibration/egovehicle_SE3_sensor.feather` and `calibration/intrinsics.feather`.
+    #[pyo3(get)]
+    pub calibration: bool,
+    /// Vector map in `map`.
+    #[pyo3(get)]
+    pub map: bool,
+}
+
+impl ModalityAvailability {
+    /// Check which modalities of the log in `log_dir` exist on disk.
+    pub fn from_log_dir(log_dir: &Path) -> Self {
+        let is_non_empty_dir = |dir: PathBuf| {
+            walk_dir(&dir)
+                .map(|paths| !paths.is_empty())
+                .unwrap_or(false)
+        };
+        Self {
+            lidar: is_non_empty_dir(log_dir.join("sensors/lidar")),
+            cameras: is_non_empty_dir(log_dir.join("sensors/cameras")),
+            annotations: log_dir.join("annotations.feather").exists(),
+            city_pose: log_dir.join("city_SE3_egovehicle.feather").exists(),
+            calibration: ["egovehicle_SE3_sensor.feather", "intrinsics.feather"]
+                .iter()
+                .all(|file_name| log_dir.join("calibration").join(file_name).exists()),
+            map: vector_map_json_path(log_dir).is_ok(),
         }
     }
 }
 
 /// Sensor data-loader for `av2`.
 #[pyclass(module = "av2._r")]
+#[derive(Clone)]
 pub struct DataLoader {
     /// Root dataset directory.
     #[pyo3(get, set)]
@@ -94,89 +347,534 @@ pub struct DataLoader {
     #[pyo3(get, set)]
     pub num_accumulated_sweeps: usize,
     /// Boolean flag to enable memory-mapped data-frame loading.
+    /// Uncompressed files are mapped in place, and compressed files are read through uncompressed
+    /// copies in `memory_map_dir` (see `MemoryMapConfig`).
     #[pyo3(get, set)]
     pub memory_mapped: bool,
+    /// Directory of the uncompressed copies of memory-mapped files.
+    #[pyo3(get, set)]
+    pub memory_map_dir: PathBuf,
     /// Data-frame consisting of `log_id`, `timestamp_ns`, and `city_name`.
     #[pyo3(get, set)]
     pub file_index: PyDataFrame,
-    /// Current index of the data-loader.
+    /// Current position of the data-loader's iteration in `sample_order`.
+    #[pyo3(get, set)]
+    pub current_index: usize,
+    /// Per-log fingerprints and file indices used to refresh the file index.
+    pub manifest: Manifest,
+    /// Cameras whose images are dropped to simulate sensor failures.
+    #[pyo3(get, set)]
+    pub dropped_cameras: Vec<String>,
+    /// Boolean flag to drop the up lidar returns to simulate a sensor failure.
+    #[pyo3(get, set)]
+    pub drop_up_lidar: bool,
+    /// Maximum latency jitter (in nanoseconds) added to the camera timestamps relative to the lidar.
+    #[pyo3(get)]
+    pub max_latency_jitter_ns: u64,
+    /// Random seed of the latency jitter. Camera `c` of sweep `i` is seeded with `seed + i` mixed
+    /// with a hash of `c`, so every camera is jittered independently.
+    #[pyo3(get, set)]
+    pub seed: u64,
+    /// Derived per-point columns appended to the lidar on load (e.g., `range_m`).
+    #[pyo3(get)]
+    pub derived_columns: Vec<String>,
+    /// Training class ids of the annotation categories (e.g., `{"REGULAR_VEHICLE": 0}`). If set,
+    /// only annotations of these categories are loaded and their `class_id` is appended.
     #[pyo3(get, set)]
+    pub category_ids: HashMap<String, i64>,
+    /// Boolean flag to load the synchronized ring camera images into each sweep.
+    #[pyo3(get, set)]
+    pub load_images: bool,
+    /// Boolean flag to load the sensor extrinsics into each sweep.
+    #[pyo3(get, set)]
+    pub load_extrinsics: bool,
+    /// Column projection and row predicates applied while reading each lidar sweep.
+    pub lidar_selection: ScanSelection,
+    /// Region of interest of the lidar points and cuboid centers in the egovehicle frame.
+    pub roi: RoiConfig,
+    /// Reference frame and motion compensation of the accumulated sweeps.
+    pub accumulation: AccumulationConfig,
+    /// Thread pools of the file reads and image decodes.
+    pub thread_pools: ThreadPools,
+    /// Layout of the packed samples (see `get_packed`).
+    pub packed_layout: PackedLayout,
+    /// Number of sweeps prefetched on background threads while iterating (`0` disables
+    /// prefetching). Prefetching uses a snapshot of the data-loader's configuration taken when
+    /// iteration starts or `current_index` is changed.
+    #[pyo3(get, set)]
+    pub prefetch_size: usize,
+    /// Number of background threads prefetching sweeps.
+    #[pyo3(get, set)]
+    pub num_prefetch_threads: usize,
+    /// Boolean flag to iterate over the sweeps in a random order (see `set_shuffle`).
+    #[pyo3(get)]
+    pub shuffle: bool,
+    /// Random seed of the shuffled order.
+    #[pyo3(get)]
+    pub shuffle_seed: u64,
+    /// Epoch of the shuffled order. Every epoch has a different order.
+    #[pyo3(get)]
+    pub epoch: u64,
+    /// Rank of the data-loader's shard (see `set_sharding`).
+    #[pyo3(get)]
+    pub rank: usize,
+    /// Number of shards.
+    #[pyo3(get)]
+    pub world_size: usize,
+    /// Assignment of the sweeps to the shards.
+    pub sharding_mode: ShardingMode,
+    /// File index rows of the shard in iteration order.
+    #[pyo3(get)]
+    pub sample_order: Vec<usize>,
+    /// Boolean flag to repeat the sweeps of rare categories (see `set_class_balanced`).
+    #[pyo3(get)]
+    pub class_balanced: bool,
+    /// Random seed of the class-balanced repeats.
+    #[pyo3(get)]
+    pub class_balance_seed: u64,
+    /// Number of times each file index row is sampled per epoch if `class_balanced` is set.
+    #[pyo3(get)]
+    pub num_repeats: Vec<usize>,
+    /// Prefetcher of the current iteration.
+    prefetcher: PrefetchSlot,
+    /// Sorted image timestamps of each camera of a log, shared by the clones of the data-loader.
+    camera_timestamps: CameraTimestamps,
+}
+
+/// Assignment of sweeps to the shards of a distributed data-loader.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Display, EnumString, Eq, PartialEq, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ShardingMode {
+    /// Shard `rank` iterates over every `world_size`-th sweep, so shard sizes differ by at most one.
+    #[default]
+    Strided,
+    /// Shards iterate over whole logs with about the same number of sweeps, so consecutive sweeps
+    /// share their accumulated sweeps and cached files.
+    Contiguous,
+}
+
+/// Iteration state of a data-loader, which resumes an epoch exactly after a preemption.
+///
+/// The shuffled order and the latency jitter are drawn from generators seeded per epoch and per
+/// sweep, so their seeds fully determine the random state.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LoaderState {
+    /// Number of sweeps in the file index when the state was taken.
+    pub num_sweeps: usize,
+    /// File index rows of the shard in iteration order.
+    pub sample_order: Vec<usize>,
+    /// Position of the iteration in `sample_order`.
     pub current_index: usize,
+    /// Boolean flag to iterate over the sweeps in a random order.
+    pub shuffle: bool,
+    /// Random seed of the shuffled order.
+    pub shuffle_seed: u64,
+    /// Epoch of the shuffled order.
+    pub epoch: u64,
+    /// Rank of the shard.
+    pub rank: usize,
+    /// Number of shards.
+    pub world_size: usize,
+    /// Assignment of the sweeps to the shards.
+    pub sharding_mode: ShardingMode,
+    /// Random seed of the latency jitter.
+    pub seed: u64,
 }
 
+/// Prefetcher of a data-loader's iteration. Clones start without a prefetcher.
+#[derive(Default)]
+struct PrefetchSlot(Mutex<Option<Prefetcher<Av2Result<Sweep>>>>);
+
+impl Clone for PrefetchSlot {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Sorted image timestamps keyed by log id and camera name.
+type CameraTimestampMap = HashMap<(String, String), Arc<Vec<u64>>>;
+
+/// Sorted image timestamps per log id and camera name.
+#[derive(Clone, Default)]
+struct CameraTimestamps(Arc<RwLock<CameraTimestampMap>>);
+
 /// Pythod bound methods are found here.
 #[pymethods]
 impl DataLoader {
-    /// Initialize the data-loader and build the file index.
+    /// Initialize the data-loader and build the file index. The keyword-only arguments are the
+    /// remaining fields of `DataLoaderConfig`, which are validated first.
     #[new]
-    pub fn new(
+    #[pyo3(signature = (
+        root_dir,
+        dataset_name = "av2",
+        dataset_type = "sensor",
+        split_name = "train",
+        num_accumulated_sweeps = 1,
+        memory_mapped = false,
+        *,
+        max_range_m = None,
+        x_range_m = None,
+        y_range_m = None,
+        z_range_m = None,
+        drivable_area_margin_m = None,
+        accumulation_frame = "ego",
+        compensate_per_point = false,
+        cameras = None,
+        category_ids = HashMap::new(),
+        lidar_columns = None,
+        lidar_ranges = vec![],
+        derived_columns = vec![],
+        max_latency_jitter_ns = 0,
+        seed = 0,
+        memory_map_dir = None,
+        manifest_path = None,
+        prefetch_size = 0,
+        num_prefetch_threads = 1
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn py_new(
+        py: Python<'_>,
         root_dir: &str,
         dataset_name: &str,
         dataset_type: &str,
         split_name: &str,
         num_accumulated_sweeps: usize,
         memory_mapped: bool,
-    ) -> DataLoader {
-        let root_dir = PathBuf::from_str(root_dir).unwrap();
-        let file_index = PyDataFrame(build_file_index(
-            root_dir.as_path(),
-            dataset_name,
-            dataset_type,
-            split_name,
-        ));
+        max_range_m: Option<f32>,
+        x_range_m: Option<(f32, f32)>,
+        y_range_m: Option<(f32, f32)>,
+        z_range_m: Option<(f32, f32)>,
+        drivable_area_margin_m: Option<f32>,
+        accumulation_frame: &str,
+        compensate_per_point: bool,
+        cameras: Option<Vec<String>>,
+        category_ids: HashMap<String, i64>,
+        lidar_columns: Option<Vec<String>>,
+        lidar_ranges: Vec<(String, Option<f64>, Option<f64>)>,
+        derived_columns: Vec<String>,
+        max_latency_jitter_ns: u64,
+        seed: u64,
+        memory_map_dir: Option<PathBuf>,
+        manifest_path: Option<PathBuf>,
+        prefetch_size: usize,
+        num_prefetch_threads: usize,
+    ) -> PyResult<DataLoader> {
+        let frame = AccumulationFrame::from_str(accumulation_frame).map_err(|_| {
+            PyValueError::new_err(format!(
+                "Unknown accumulation frame `{accumulation_frame}`."
+            ))
+        })?;
+        let cfg = DataLoaderConfig {
+            root_dir: PathBuf::from(root_dir),
+            dataset_name: dataset_name.to_string(),
+            dataset_type: dataset_type.to_string(),
+            split_name: split_name.to_string(),
+            num_accumulated_sweeps,
+            roi: RoiConfig {
+                max_range_m,
+                x_range_m,
+                y_range_m,
+                z_range_m,
+                drivable_area_margin_m,
+            },
+            accumulation: AccumulationConfig {
+                frame,
+                compensate_per_point,
+            },
+            cameras,
+            category_ids,
+            lidar_selection: ScanSelection {
+                columns: lidar_columns,
+                predicates: lidar_ranges
+                    .iter()
+                    .map(|(column, min, max)| RangePredicate::new(column, *min, *max))
+                    .collect(),
+            },
+            derived_columns,
+            max_latency_jitter_ns,
+            seed,
+            memory_mapped,
+            memory_map_dir,
+            manifest_path,
+            prefetch_size,
+            num_prefetch_threads,
+        };
+        py.allow_threads(|| DataLoader::from_config(&cfg))
+            .map_err(to_py_err)
+    }
 
-        let dataset_name = dataset_name.to_string();
-        let dataset_type = dataset_type.to_string();
-        let split_name = split_name.to_string();
-        let current_index = 0;
-        DataLoader {
+    /// Initialize the data-loader from a dataset manifest (see `write_dataset_manifest`) without
+    /// scanning the split directory.
+    #[staticmethod]
+    #[pyo3(name = "from_manifest")]
+    pub fn py_from_manifest(
+        root_dir: &str,
+        dataset_name: &str,
+        dataset_type: &str,
+        split_name: &str,
+        num_accumulated_sweeps: usize,
+        memory_mapped: bool,
+        manifest_path: &str,
+    ) -> PyResult<DataLoader> {
+        DataLoader::from_manifest(
             root_dir,
             dataset_name,
             dataset_type,
             split_name,
             num_accumulated_sweeps,
             memory_mapped,
-            file_index,
-            current_index,
-        }
+            Path::new(manifest_path),
+        )
+        .map_err(|err| PyIOError::new_err(err.to_string()))
     }
 
-    fn read_city_pose_py(&self, log_id: &str, timestamp_ns: u64) -> PyDataFrame {
-        PyDataFrame(self.read_city_pose(log_id, timestamp_ns))
+    /// Re-index the logs which were added, removed, or modified since the file index was built.
+    /// Returns the added, removed, and modified log ids.
+    #[pyo3(name = "refresh")]
+    fn py_refresh(&mut self) -> PyResult<(Vec<String>, Vec<String>, Vec<String>)> {
+        let update = self
+            .refresh()
+            .map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok((update.added, update.removed, update.modified))
     }
 
-    fn read_lidar_py(&self, log_id: &str, timestamp_ns: u64, index: usize) -> PyDataFrame {
-        PyDataFrame(self.read_lidar(log_id, timestamp_ns, index))
+    /// Modalities available in `log_id`.
+    #[pyo3(name = "availability")]
+    fn py_availability(&self, log_id: &str) -> ModalityAvailability {
+        self.availability(log_id)
     }
 
-    fn read_annotations_py(&self, log_id: &str, timestamp_ns: u64) -> PyDataFrame {
-        PyDataFrame(self.read_annotations(log_id, timestamp_ns))
+    /// Read only the lidar `columns` (all if `None`) of the rows within the inclusive
+    /// `(column, min, max)` ranges.
+    #[pyo3(name = "set_lidar_selection", signature = (columns = None, ranges = vec![]))]
+    fn py_set_lidar_selection(
+        &mut self,
+        columns: Option<Vec<String>>,
+        ranges: Vec<(String, Option<f64>, Option<f64>)>,
+    ) {
+        self.lidar_selection = ScanSelection {
+            columns,
+            predicates: ranges
+                .iter()
+                .map(|(column, min, max)| RangePredicate::new(column, *min, *max))
+                .collect(),
+        };
     }
 
-    /// Get the sweep at `index`.
-    pub fn get(&self, index: usize) -> Sweep {
-        let row = self.file_index.0.get_row(index).unwrap().0;
-        let (log_id, timestamp_ns) = (
-            row.first().unwrap().get_str().unwrap(),
-            row.get(1).unwrap().try_extract::<u64>().unwrap(),
-        );
+    /// Read files on a pool of `num_io_threads` and decode images on a pool of
+    /// `num_decode_threads`. Stages without a thread count run on the global pool, and a single
+    /// thread disables their parallelism.
+    #[pyo3(
+        name = "set_thread_pools",
+        signature = (num_io_threads = None, num_decode_threads = None)
+    )]
+    fn py_set_thread_pools(
+        &mut self,
+        num_io_threads: Option<usize>,
+        num_decode_threads: Option<usize>,
+    ) -> PyResult<()> {
+        self.thread_pools = ThreadPools::new(num_io_threads, num_decode_threads)
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Keep the lidar points and cuboid centers within `max_range_m` (bird's-eye-view) and the
+    /// inclusive `(min, max)` coordinate ranges in the egovehicle frame. If
+    /// `drivable_area_margin_m` is set, only the points and cuboids (with any vertex) within that
+    /// distance of the map's drivable areas are kept.
+    #[pyo3(
+        name = "set_roi",
+        signature = (
+            max_range_m = None,
+            x_range_m = None,
+            y_range_m = None,
+            z_range_m = None,
+            drivable_area_margin_m = None
+        )
+    )]
+    fn py_set_roi(
+        &mut self,
+        max_range_m: Option<f32>,
+        x_range_m: Option<(f32, f32)>,
+        y_range_m: Option<(f32, f32)>,
+        z_range_m: Option<(f32, f32)>,
+        drivable_area_margin_m: Option<f32>,
+    ) -> PyResult<()> {
+        if drivable_area_margin_m.is_some_and(|x| x.is_nan() || x < 0.0) {
+            return Err(PyValueError::new_err(
+                "The drivable area margin must be non-negative.",
+            ));
+        }
+        self.roi = RoiConfig {
+            max_range_m,
+            x_range_m,
+            y_range_m,
+            z_range_m,
+            drivable_area_margin_m,
+        };
+        Ok(())
+    }
 
-        // Annotations aren't available for the test set.
-        let cuboids = match self.split_name.as_str() {
-            "test" => None,
-            _ => Some(self.read_annotations_py(log_id, timestamp_ns)),
+    /// Pack samples (see `get_packed`) into `max_points` points and `max_cuboids` cuboids, padded
+    /// with `pad_value`.
+    #[pyo3(
+        name = "set_packed_layout",
+        signature = (max_points = 131072, max_cuboids = 512, pad_value = 0.)
+    )]
+    fn py_set_packed_layout(&mut self, max_points: usize, max_cuboids: usize, pad_value: f32) {
+        self.packed_layout = PackedLayout {
+            max_points,
+            max_cuboids,
+            pad_value,
         };
+    }
 
-        let city_pose = self.read_city_pose_py(log_id, timestamp_ns);
-        let lidar = self.read_lidar_py(log_id, timestamp_ns, index);
-        let sweep_uuid = (log_id.to_string(), timestamp_ns);
+    /// Express the lidar in the `frame` (`ego` for the egovehicle frame of the latest sweep, or
+    /// `city`) and motion-compensate each return using its `offset_ns` if `compensate_per_point`
+    /// is set. Logs without egovehicle poses are read in the egovehicle frame without compensation.
+    /// The `roi` is applied in the `frame`.
+    #[pyo3(
+        name = "set_accumulation",
+        signature = (frame = "ego", compensate_per_point = false)
+    )]
+    fn py_set_accumulation(&mut self, frame: &str, compensate_per_point: bool) -> PyResult<()> {
+        let frame = AccumulationFrame::from_str(frame)
+            .map_err(|_| PyValueError::new_err(format!("Unknown accumulation frame `{frame}`.")))?;
+        self.accumulation = AccumulationConfig {
+            frame,
+            compensate_per_point,
+        };
+        Ok(())
+    }
 
-        Sweep {
-            city_pose,
-            lidar,
-            sweep_uuid,
-            cuboids,
-        }
+    /// Accumulation frame (`ego` or `city`).
+    #[getter(accumulation_frame)]
+    fn py_accumulation_frame(&self) -> String {
+        self.accumulation.frame.to_string()
+    }
+
+    /// Boolean flag to motion-compensate each lidar return using its `offset_ns`.
+    #[getter(compensate_per_point)]
+    fn py_compensate_per_point(&self) -> bool {
+        self.accumulation.compensate_per_point
+    }
+
+    /// Iterate over the sweeps in a random order seeded by `seed` and the epoch.
+    #[pyo3(name = "set_shuffle", signature = (shuffle, seed = 0))]
+    fn py_set_shuffle(&mut self, shuffle: bool, seed: u64) {
+        self.shuffle = shuffle;
+        self.shuffle_seed = seed;
+        self.update_sample_order();
+    }
+
+    /// Repeat the sweeps of rare categories so that every category is sampled about equally
+    /// often per epoch. The repeats are drawn with `seed`.
+    #[pyo3(name = "set_class_balanced", signature = (class_balanced, seed = 0))]
+    fn py_set_class_balanced(&mut self, class_balanced: bool, seed: u64) -> PyResult<()> {
+        self.set_class_balanced(class_balanced, seed)
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+
+    /// Start epoch `epoch`: reshuffle the sweeps (if shuffling) and rewind the iteration.
+    #[pyo3(name = "set_epoch")]
+    fn py_set_epoch(&mut self, epoch: u64) {
+        self.epoch = epoch;
+        self.current_index = 0;
+        self.update_sample_order();
+    }
+
+    /// Iterate over shard `rank` of `world_size` shards. The `mode` is either `strided` or
+    /// `contiguous` (whole logs per shard).
+    #[pyo3(name = "set_sharding", signature = (rank, world_size, mode = "strided"))]
+    fn py_set_sharding(&mut self, rank: usize, world_size: usize, mode: &str) -> PyResult<()> {
+        let mode = ShardingMode::from_str(mode)
+            .map_err(|_| PyValueError::new_err(format!("Unknown sharding mode `{mode}`.")))?;
+        self.set_sharding(rank, world_size, mode)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Serialize the iteration state (see `LoaderState`) to JSON.
+    #[pyo3(name = "get_state")]
+    fn py_get_state(&self) -> PyResult<String> {
+        serde_json::to_string(&self.state()).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Resume the iteration from a state serialized by `get_state`.
+    #[pyo3(name = "set_state")]
+    fn py_set_state(&mut self, state: &str) -> PyResult<()> {
+        let state =
+            serde_json::from_str(state).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        self.set_state(&state)
+            .map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    /// Assignment of the sweeps to the shards.
+    #[getter(sharding_mode)]
+    fn py_sharding_mode(&self) -> String {
+        self.sharding_mode.to_string()
+    }
+
+    fn read_city_pose_py(&self, log_id: &str, timestamp_ns: u64) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(self.read_city_pose(log_id, timestamp_ns)?))
+    }
+
+    fn read_lidar_py(
+        &self,
+        log_id: &str,
+        timestamp_ns: u64,
+        index: usize,
+    ) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(self.read_lidar(log_id, timestamp_ns, index)?))
+    }
+
+    fn read_annotations_py(&self, log_id: &str, timestamp_ns: u64) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(self.read_annotations(log_id, timestamp_ns)?))
+    }
+
+    /// Read the cuboids of track `track_uuid` across `log_id`, interpolated at every lidar sweep
+    /// within the track's lifespan if `interpolate` is set.
+    #[pyo3(name = "read_track", signature = (log_id, track_uuid, interpolate = false))]
+    fn py_read_track(
+        &self,
+        py: Python<'_>,
+        log_id: &str,
+        track_uuid: &str,
+        interpolate: bool,
+    ) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(py.allow_threads(|| {
+            self.read_track(log_id, track_uuid, interpolate)
+        })?))
+    }
+
+    /// Summarize the tracks of `log_id` (category and lifespan of each track uuid).
+    #[pyo3(name = "read_track_summaries")]
+    fn py_read_track_summaries(&self, py: Python<'_>, log_id: &str) -> PyResult<PyDataFrame> {
+        Ok(PyDataFrame(
+            py.allow_threads(|| self.read_track_summaries(log_id))?,
+        ))
+    }
+
+    /// Get the sweep at `index`.
+    #[pyo3(name = "get")]
+    fn py_get(&self, py: Python<'_>, index: usize) -> PyResult<Sweep> {
+        Ok(py.allow_threads(|| self.try_get(index))?)
+    }
+
+    /// Get the sweep at `index` with the `augmentations` (a JSON list of applied augmentations)
+    /// applied in order. Column-level augmentations are fused into the reads.
+    #[pyo3(name = "get_augmented")]
+    fn py_get_augmented(
+        &self,
+        py: Python<'_>,
+        index: usize,
+        augmentations: &str,
+    ) -> PyResult<Sweep> {
+        let augmentations: Vec<AppliedAugmentation> = serde_json::from_str(augmentations)
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(py.allow_threads(|| self.get_augmented(index, &augmentations))?)
     }
 
     /// Get all synchronized images at the sweep index.
@@ -185,25 +883,117 @@ impl DataLoader {
         &self,
         py: Python<'py>,
         index: usize,
-    ) -> Vec<&'py PyArray<u8, Ix3>> {
-        let images = self.get_synchronized_images(index);
-        images
+    ) -> PyResult<Vec<Option<&'py PyArray<u8, Ix3>>>> {
+        let images = py.allow_threads(|| self.try_get_synchronized_images(index))?;
+        Ok(images
             .into_iter()
-            .map(|x| x.image.into_ndarray3().into_pyarray(py))
-            .collect_vec()
+            .map(|x| x.map(|x| x.image.into_ndarray3().into_pyarray(py)))
+            .collect_vec())
     }
 
     fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        // A new iteration picks up configuration changes.
+        *slf.prefetcher.0.lock().unwrap() = None;
         slf
     }
 
-    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Sweep> {
-        slf.next()
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<Sweep>> {
+        let data_loader: &mut DataLoader = &mut slf;
+        Ok(py.allow_threads(|| data_loader.try_next()).transpose()?)
     }
 
     fn __len__(slf: PyRef<'_, Self>) -> usize {
         slf.file_index.0.shape().0
     }
+
+    /// Set the maximum latency jitter, which must be at most `i64::MAX`.
+    #[setter]
+    fn set_max_latency_jitter_ns(&mut self, max_latency_jitter_ns: u64) -> PyResult<()> {
+        validate_max_latency_jitter_ns(max_latency_jitter_ns)?;
+        self.max_latency_jitter_ns = max_latency_jitter_ns;
+        Ok(())
+    }
+
+    /// Set the derived per-point columns, which must be `DerivedColumn` names.
+    #[setter]
+    fn set_derived_columns(&mut self, derived_columns: Vec<String>) -> PyResult<()> {
+        parse_derived_columns(&derived_columns)?;
+        self.derived_columns = derived_columns;
+        Ok(())
+    }
+
+    /// Log ids in file index order.
+    #[pyo3(name = "log_ids")]
+    fn py_log_ids(&self) -> Vec<String> {
+        self.log_ids()
+    }
+
+    /// Number of sweeps of each log.
+    #[pyo3(name = "num_sweeps_per_log")]
+    fn py_num_sweeps_per_log(&self) -> BTreeMap<String, usize> {
+        self.num_sweeps_per_log()
+    }
+
+    /// Get the pair of sweeps at `index` and `index + 1` in the egovehicle frame of the former.
+    #[pyo3(name = "get_pair")]
+    fn py_get_pair(&self, py: Python<'_>, index: usize) -> PyResult<SweepPair> {
+        py.allow_threads(|| self.get_pair(index)).map_err(to_py_err)
+    }
+
+    /// Get the sweep at `index` packed into padded arrays of the `packed_layout`.
+    #[pyo3(name = "get_packed")]
+    fn py_get_packed(&self, py: Python<'_>, index: usize) -> PyResult<PackedSample> {
+        py.allow_threads(|| self.get_packed(index))
+            .map_err(to_py_err)
+    }
+
+    /// File index rows of the sweeps followed by another sweep of the same log.
+    #[pyo3(name = "pair_indices")]
+    fn py_pair_indices(&self) -> Vec<usize> {
+        self.pair_indices()
+    }
+
+    /// Iterate over the sweeps of `log_id` in timestamp order.
+    #[pyo3(name = "iter_log")]
+    fn py_iter_log(&self, log_id: &str) -> PyResult<LogSweepIterator> {
+        let rows = self
+            .log_rows(log_id)
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(LogSweepIterator {
+            data_loader: self.clone(),
+            rows,
+        })
+    }
+}
+
+/// Iterator over the sweeps of a single log in timestamp order.
+#[pyclass(module = "av2._r")]
+pub struct LogSweepIterator {
+    data_loader: DataLoader,
+    /// Remaining file index rows of the log.
+    rows: Range<usize>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl LogSweepIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<Sweep>> {
+        let iterator: &mut LogSweepIterator = &mut slf;
+        let Some(row) = iterator.rows.next() else {
+            return Ok(None);
+        };
+        Ok(Some(
+            py.allow_threads(|| iterator.data_loader.try_get(row))?,
+        ))
+    }
+
+    fn __len__(&self) -> usize {
+        self.rows.len()
+    }
 }
 
 /// Rust methods.
@@ -222,13 +1012,592 @@ impl DataLoader {
         self.len() == 0
     }
 
+    /// Initialize the data-loader and build the file index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the split directory cannot be scanned (see `from_config`).
+    pub fn new(
+        root_dir: &str,
+        dataset_name: &str,
+        dataset_type: &str,
+        split_name: &str,
+        num_accumulated_sweeps: usize,
+        memory_mapped: bool,
+    ) -> DataLoader {
+        DataLoader::scan(
+            PathBuf::from_str(root_dir).unwrap(),
+            dataset_name,
+            dataset_type,
+            split_name,
+            num_accumulated_sweeps,
+            memory_mapped,
+        )
+        .unwrap()
+    }
+
+    /// Initialize the data-loader by scanning the split directory.
+    fn scan(
+        root_dir: PathBuf,
+        dataset_name: &str,
+        dataset_type: &str,
+        split_name: &str,
+        num_accumulated_sweeps: usize,
+        memory_mapped: bool,
+    ) -> anyhow::Result<DataLoader> {
+        let split_dir = _split_dir(&root_dir, dataset_name, dataset_type, split_name);
+        let manifest = match _split_log_ids(dataset_type, split_name)? {
+            Some(log_ids) => Manifest::with_log_ids(&split_dir, log_ids),
+            None => Manifest::new(&split_dir),
+        }?;
+        DataLoader::from_parts(
+            root_dir,
+            dataset_name,
+            dataset_type,
+            split_name,
+            num_accumulated_sweeps,
+            memory_mapped,
+            manifest,
+        )
+    }
+
+    /// Initialize the data-loader from a dataset manifest (see `write_dataset_manifest`) without
+    /// scanning the split directory.
+    pub fn from_manifest(
+        root_dir: &str,
+        dataset_name: &str,
+        dataset_type: &str,
+        split_name: &str,
+        num_accumulated_sweeps: usize,
+        memory_mapped: bool,
+        manifest_path: &Path,
+    ) -> anyhow::Result<DataLoader> {
+        let root_dir = PathBuf::from_str(root_dir)?;
+        let split_dir = _split_dir(&root_dir, dataset_name, dataset_type, split_name);
+        let file_index = read_dataset_manifest(manifest_path)?.drop_many(&DATASET_MANIFEST_COLUMNS);
+        let mut manifest = Manifest::from_file_index(&split_dir, &file_index)?;
+        if let Some(log_ids) = _split_log_ids(dataset_type, split_name)? {
+            manifest.retain_log_ids(log_ids);
+        }
+        DataLoader::from_parts(
+            root_dir,
+            dataset_name,
+            dataset_type,
+            split_name,
+            num_accumulated_sweeps,
+            memory_mapped,
+            manifest,
+        )
+    }
+
+    fn from_parts(
+        root_dir: PathBuf,
+        dataset_name: &str,
+        dataset_type: &str,
+        split_name: &str,
+        num_accumulated_sweeps: usize,
+        memory_mapped: bool,
+        manifest: Manifest,
+    ) -> anyhow::Result<DataLoader> {
+        let file_index = PyDataFrame(manifest.file_index()?);
+        let mut data_loader = DataLoader {
+            root_dir,
+            dataset_name: dataset_name.to_string(),
+            dataset_type: dataset_type.to_string(),
+            split_name: split_name.to_string(),
+            num_accumulated_sweeps,
+            memory_mapped,
+            memory_map_dir: MemoryMapConfig::default().cache_dir,
+            file_index,
+            current_index: 0,
+            manifest,
+            dropped_cameras: vec![],
+            drop_up_lidar: false,
+            max_latency_jitter_ns: 0,
+            seed: 0,
+            derived_columns: vec![],
+            category_ids: HashMap::new(),
+            load_images: false,
+            load_extrinsics: false,
+            lidar_selection: ScanSelection::default(),
+            roi: RoiConfig::default(),
+            accumulation: AccumulationConfig::default(),
+            thread_pools: ThreadPools::default(),
+            packed_layout: PackedLayout::default(),
+            prefetch_size: 0,
+            num_prefetch_threads: 1,
+            shuffle: false,
+            shuffle_seed: 0,
+            epoch: 0,
+            rank: 0,
+            world_size: 1,
+            sharding_mode: ShardingMode::default(),
+            sample_order: vec![],
+            class_balanced: false,
+            class_balance_seed: 0,
+            num_repeats: vec![],
+            prefetcher: PrefetchSlot::default(),
+            camera_timestamps: CameraTimestamps::default(),
+        };
+        data_loader.update_sample_order();
+        Ok(data_loader)
+    }
+
+    /// Incrementally refresh the file index. Logs whose sensor files are unchanged are not rescanned.
+    pub fn refresh(&mut self) -> anyhow::Result<ManifestUpdate> {
+        let update = self.manifest.refresh()?;
+        if !update.is_empty() {
+            self.file_index = PyDataFrame(self.manifest.file_index()?);
+            self.camera_timestamps = CameraTimestamps::default();
+            if self.class_balanced {
+                self.num_repeats =
+                    class_balanced_repeats(&self.frame_categories()?, self.class_balance_seed);
+            }
+            self.update_sample_order();
+        }
+        Ok(update)
+    }
+
+    /// Recompute `sample_order` from the file index and the shuffling and sharding
+    /// configurations. Shuffled orders are seeded by both `shuffle_seed` and `epoch`.
+    ///
+    /// Strided shards take every `world_size`-th sweep of the shuffled order. Contiguous shards
+    /// keep their logs across epochs and only shuffle the order of their logs, whose sweeps stay
+    /// in temporal order.
+    pub fn update_sample_order(&mut self) {
+        let seed = self
+            .shuffle_seed
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            .wrapping_add(self.epoch);
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.sample_order = match self.sharding_mode {
+            ShardingMode::Strided => {
+                let mut sample_order = self.repeated_rows(0..self.len());
+                if self.shuffle {
+                    sample_order.shuffle(&mut rng);
+                }
+                sample_order
+                    .into_iter()
+                    .skip(self.rank)
+                    .step_by(self.world_size)
+                    .collect()
+            }
+            ShardingMode::Contiguous => {
+                // Logs are assigned to the shard containing their middle sweep.
+                let num_sweeps = self.len().max(1);
+                let mut logs = self
+                    .log_ranges()
+                    .into_iter()
+                    .filter(|x| {
+                        let shard = (x.start + x.end) / 2 * self.world_size / num_sweeps;
+                        shard.min(self.world_size - 1) == self.rank
+                    })
+                    .collect_vec();
+                if self.shuffle {
+                    logs.shuffle(&mut rng);
+                }
+                logs.into_iter()
+                    .flat_map(|x| self.repeated_rows(x))
+                    .collect()
+            }
+        };
+        *self.prefetcher.0.lock().unwrap() = None;
+    }
+
+    /// File index `rows` repeated by their class-balanced `num_repeats` (if `class_balanced`).
+    fn repeated_rows(&self, rows: Range<usize>) -> Vec<usize> {
+        match self.class_balanced {
+            true => rows
+                .flat_map(|row| std::iter::repeat_n(row, self.num_repeats[row]))
+                .collect(),
+            false => rows.collect(),
+        }
+    }
+
+    /// Repeat the sweeps of rare categories so that every category is sampled about equally
+    /// often per epoch (class-balanced grouping and sampling). Sweeps without annotations of the
+    /// `category_ids` (or of any category if unset) are no longer sampled.
+    pub fn set_class_balanced(&mut self, class_balanced: bool, seed: u64) -> anyhow::Result<()> {
+        self.num_repeats = match class_balanced {
+            true => class_balanced_repeats(&self.frame_categories()?, seed),
+            false => vec![],
+        };
+        (self.class_balanced, self.class_balance_seed) = (class_balanced, seed);
+        self.current_index = 0;
+        self.update_sample_order();
+        Ok(())
+    }
+
+    /// Number of annotations of each category in each file index row. Only the annotations
+    /// which `read_annotations` keeps by their number of interior points and `category_ids` are
+    /// counted.
+    pub fn frame_categories(&self) -> anyhow::Result<Vec<BTreeMap<String, usize>>> {
+        let log_ids = self.file_index.0["log_id"].str()?;
+        let timestamps_ns = self.file_index.0["timestamp_ns"].u64()?;
+        let counts = self
+            .log_ranges()
+            .into_par_iter()
+            .map(|rows| -> anyhow::Result<_> {
+                let log_id = log_ids.get(rows.start).unwrap_or_default();
+                let mut counts = HashMap::<u64, BTreeMap<String, usize>>::new();
+                let annotations_path = self.annotations_path(log_id);
+                if !annotations_path.exists() {
+                    return Ok(counts);
+                }
+                let annotations = read_feather(&annotations_path, false)
+                    .map_err(|err| err.in_log(log_id))?
+                    .lazy()
+                    .filter(col("num_interior_pts").gt_eq(MIN_NUM_LIDAR_PTS))
+                    .select([col("timestamp_ns").cast(DataType::UInt64), col("category")])
+                    .collect()?;
+                let categories = annotations["category"].str()?;
+                for (timestamp_ns, category) in annotations["timestamp_ns"]
+                    .u64()?
+                    .into_no_null_iter()
+                    .zip(categories.into_no_null_iter())
+                {
+                    if self.category_ids.is_empty() || self.category_ids.contains_key(category) {
+                        *counts
+                            .entry(timestamp_ns)
+                            .or_default()
+                            .entry(category.to_string())
+                            .or_default() += 1;
+                    }
+                }
+                Ok(counts)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(self
+            .log_ranges()
+            .into_iter()
+            .zip(counts)
+            .flat_map(|(rows, mut counts)| {
+                rows.map(move |row| {
+                    let timestamp_ns = timestamps_ns.get(row).unwrap_or_default();
+                    counts.remove(&timestamp_ns).unwrap_or_default()
+                })
+            })
+            .collect())
+    }
+
+    /// Iterate over shard `rank` of `world_size` shards assigned by `mode`.
+    pub fn set_sharding(
+        &mut self,
+        rank: usize,
+        world_size: usize,
+        mode: ShardingMode,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            rank < world_size,
+            "Rank {rank} is out of range for a world size of {world_size}."
+        );
+        (self.rank, self.world_size, self.sharding_mode) = (rank, world_size, mode);
+        self.current_index = 0;
+        self.update_sample_order();
+        Ok(())
+    }
+
+    /// Iteration state of the data-loader.
+    pub fn state(&self) -> LoaderState {
+        LoaderState {
+            num_sweeps: self.len(),
+            sample_order: self.sample_order.clone(),
+            current_index: self.current_index,
+            shuffle: self.shuffle,
+            shuffle_seed: self.shuffle_seed,
+            epoch: self.epoch,
+            rank: self.rank,
+            world_size: self.world_size,
+            sharding_mode: self.sharding_mode,
+            seed: self.seed,
+        }
+    }
+
+    /// Resume the iteration from `state`. The file index must have the same number of sweeps as
+    /// when the state was taken.
+    pub fn set_state(&mut self, state: &LoaderState) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            state.num_sweeps == self.len(),
+            "The state has {} sweeps, but the file index has {}.",
+            state.num_sweeps,
+            self.len()
+        );
+        anyhow::ensure!(
+            state.sample_order.iter().all(|x| *x < self.len())
+                && state.current_index <= state.sample_order.len()
+                && state.rank < state.world_size,
+            "Invalid data-loader state."
+        );
+        self.sample_order = state.sample_order.clone();
+        self.current_index = state.current_index;
+        self.shuffle = state.shuffle;
+        self.shuffle_seed = state.shuffle_seed;
+        self.epoch = state.epoch;
+        (self.rank, self.world_size, self.sharding_mode) =
+            (state.rank, state.world_size, state.sharding_mode);
+        self.seed = state.seed;
+        *self.prefetcher.0.lock().unwrap() = None;
+        Ok(())
+    }
+
+    /// Log ids in file index order.
+    pub fn log_ids(&self) -> Vec<String> {
+        let log_ids = self.file_index.0["log_id"].str().unwrap();
+        self.log_ranges()
+            .into_iter()
+            .map(|x| log_ids.get(x.start).unwrap_or_default().to_string())
+            .collect()
+    }
+
+    /// Number of sweeps of each log.
+    pub fn num_sweeps_per_log(&self) -> BTreeMap<String, usize> {
+        self.log_ids()
+            .into_iter()
+            .zip(self.log_ranges().into_iter().map(|x| x.len()))
+            .collect()
+    }
+
+    /// File index rows of the sweeps of `log_id` in timestamp order.
+    pub fn log_rows(&self, log_id: &str) -> anyhow::Result<Range<usize>> {
+        self.log_ids()
+            .into_iter()
+            .zip(self.log_ranges())
+            .find(|(x, _)| x == log_id)
+            .map(|(_, rows)| rows)
+            .with_context(|| format!("Log {log_id} not found."))
+    }
+
+    /// Sweeps of `log_id` in timestamp order. A sweep which fails to read yields its error and
+    /// the iteration moves on to the next sweep.
+    pub fn iter_log(
+        &self,
+        log_id: &str,
+    ) -> anyhow::Result<impl Iterator<Item = Av2Result<Sweep>> + '_> {
+        Ok(self.log_rows(log_id)?.map(|row| self.try_get(row)))
+    }
+
+    /// Get the pair of sweeps at `index` and `index + 1` with the target sweep's lidar and cuboids
+    /// transformed into the egovehicle frame of the source sweep.
+    pub fn get_pair(&self, index: usize) -> anyhow::Result<SweepPair> {
+        anyhow::ensure!(
+            self.pair_indices().binary_search(&index).is_ok(),
+            "Sweep {index} is not followed by a sweep of the same log."
+        );
+        let (source, mut target) = (self.try_get(index)?, self.try_get(index + 1)?);
+        let log_id = &source.sweep_uuid.0;
+        let city_se3_ego = |timestamp_ns| {
+            self.city_se3_ego(log_id, timestamp_ns)?
+                .with_context(|| format!("Pose of log {log_id} at {timestamp_ns} not found."))
+        };
+        let city_se3_source = city_se3_ego(source.sweep_uuid.1)?;
+        let city_se3_target = city_se3_ego(target.sweep_uuid.1)?;
+        let source_se3_target = city_se3_source.inverse().compose(&city_se3_target);
+
+        // Lidar accumulated in the city frame is already in a common frame.
+        if target.lidar_frame == AccumulationFrame::Ego.to_string() {
+            target.lidar = PyDataFrame(transform_sweep(
+                &target.lidar.0,
+                &city_se3_target,
+                &city_se3_source,
+            )?);
+        }
+        if let Some(cuboids) = &target.cuboids {
+            let mut cuboid_list = CuboidList::from_data_frame(&cuboids.0)?;
+            let params = source_se3_target.transform_cuboids(&cuboid_list.params());
+            cuboid_list.params_mut().assign(&params);
+            let mut transformed = cuboid_list.update_data_frame(&cuboids.0)?;
+            // Keep the schema of the source cuboids.
+            for name in CUBOID_COLUMNS {
+                let values = transformed[name].cast(cuboids.0[name].dtype())?;
+                transformed.with_column(values)?;
+            }
+            target.cuboids = Some(PyDataFrame(transformed));
+        }
+        target.city_se3_ego = source.city_se3_ego.clone();
+
+        let correspondences = match (&source.cuboids, &target.cuboids) {
+            (Some(source_cuboids), Some(target_cuboids)) => Some(PyDataFrame(
+                cuboid_correspondences(&source_cuboids.0, &target_cuboids.0)?,
+            )),
+            _ => None,
+        };
+        Ok(SweepPair {
+            source,
+            target,
+            source_se3_target: source_se3_target.transform_matrix(),
+            correspondences,
+        })
+    }
+
+    /// Get the sweep at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sweep cannot be read (see `try_get`).
+    pub fn get(&self, index: usize) -> Sweep {
+        self.try_get(index).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Get the sweep at `index`, or the error of reading it attributed to its log.
+    pub fn try_get(&self, index: usize) -> Av2Result<Sweep> {
+        self.get_augmented(index, &[])
+    }
+
+    /// Get the sweep at `index` with the `augmentations` applied in order.
+    ///
+    /// The leading column-level augmentations are fused with the reads, the `roi`, and the
+    /// `category_ids` filter into a single lazy query per table, so the lidar and cuboids are
+    /// collected once. The remaining augmentations are applied in place afterwards, and the
+    /// `derived_columns` are computed from the augmented lidar.
+    pub fn get_augmented(
+        &self,
+        index: usize,
+        augmentations: &[AppliedAugmentation],
+    ) -> Av2Result<Sweep> {
+        let _span = info_span!("get_sweep", index).entered();
+        if index >= self.len() {
+            return Err(Av2Error::invalid_parameter(
+                "index",
+                format!("{index} is out of bounds for {} sweeps.", self.len()),
+            ));
+        }
+        let row = self.file_index.0.get_row(index)?.0;
+        let (log_id, timestamp_ns) = (
+            row.first().unwrap().get_str().unwrap(),
+            row.get(1).unwrap().try_extract::<u64>()?,
+        );
+
+        let read_sweep = || -> Av2Result<Sweep> {
+            // Annotations aren't available for the test set.
+            let availability = self.availability(log_id);
+            let cuboids = match self.split_name.as_str() {
+                "test" => None,
+                _ => availability
+                    .annotations
+                    .then(|| self.read_annotations_lazy(log_id, timestamp_ns))
+                    .transpose()?,
+            };
+
+            let city_pose = availability
+                .city_pose
+                .then(|| self.read_city_pose(log_id, timestamp_ns).map(PyDataFrame))
+                .transpose()?;
+            let city_se3_ego = match availability.city_pose {
+                true => self
+                    .city_se3_ego(log_id, timestamp_ns)?
+                    .map(|x| x.transform_matrix()),
+                false => None,
+            };
+            let extrinsics = (self.load_extrinsics && availability.calibration)
+                .then(|| self.read_extrinsics(log_id).map(PyDataFrame))
+                .transpose()?;
+            let lidar = self.read_lidar_lazy(log_id, timestamp_ns, index)?;
+            // Without poses, the lidar is read in the egovehicle frame without compensation.
+            let accumulation = match availability.city_pose {
+                true => self.accumulation,
+                false => AccumulationConfig::default(),
+            };
+            let (lidar, cuboids) = match self.roi.drivable_area_margin_m {
+                Some(margin_m) => self.filter_to_drivable_area(
+                    log_id,
+                    timestamp_ns,
+                    lidar,
+                    cuboids,
+                    accumulation.frame,
+                    margin_m,
+                )?,
+                None => (lidar, cuboids),
+            };
+            let (lidar, cuboids) = _augment_lazy(lidar, cuboids, augmentations)?;
+            let sweep_uuid = (log_id.to_string(), timestamp_ns);
+            let images = self
+                .load_images
+                .then(|| self.try_get_synchronized_images(index))
+                .transpose()?
+                .map(|images| {
+                    images
+                        .into_iter()
+                        .flatten()
+                        .map(SynchronizedImage::from)
+                        .collect()
+                });
+
+            Ok(Sweep {
+                city_pose,
+                lidar: PyDataFrame(self.append_derived_columns(&lidar)?),
+                sweep_uuid,
+                cuboids: cuboids.map(PyDataFrame),
+                images,
+                city_se3_ego,
+                extrinsics,
+                lidar_frame: accumulation.frame.to_string(),
+                motion_compensated: accumulation.compensate_per_point,
+            })
+        };
+        read_sweep().map_err(|err| err.in_log(log_id))
+    }
+
+    /// Advance the iteration, returning the error of reading the next sweep instead of panicking.
+    /// The iteration moves past a sweep which fails to read.
+    pub fn try_next(&mut self) -> Option<Av2Result<Sweep>> {
+        let idx = self.current_index;
+        let &index = self.sample_order.get(idx)?;
+        let sweep_data = match self.prefetch_size {
+            0 => self.try_get(index),
+            _ => {
+                let mut prefetcher = self.prefetcher.0.lock().unwrap();
+                if prefetcher.as_ref().map(|x| x.next_index()) != Some(idx) {
+                    let data_loader = Arc::new(self.clone());
+                    *prefetcher = Some(Prefetcher::new(
+                        move |i| data_loader.try_get(data_loader.sample_order[i]),
+                        idx..self.sample_order.len(),
+                        self.prefetch_size,
+                        self.num_prefetch_threads.max(1),
+                    ));
+                }
+                prefetcher.as_mut().unwrap().next().unwrap()
+            }
+        };
+        self.current_index += 1;
+
+        Some(sweep_data)
+    }
+
+    /// Get the sweep at `index` packed into padded arrays of the `packed_layout`.
+    pub fn get_packed(&self, index: usize) -> anyhow::Result<PackedSample> {
+        PackedSample::from_sweep(&self.try_get(index)?, &self.packed_layout)
+    }
+
+    /// File index rows of the sweeps followed by another sweep of the same log.
+    pub fn pair_indices(&self) -> Vec<usize> {
+        self.log_ranges()
+            .into_iter()
+            .flat_map(|rows| rows.start..rows.end.saturating_sub(1))
+            .collect()
+    }
+
+    /// Contiguous file index rows of each log.
+    pub fn log_ranges(&self) -> Vec<Range<usize>> {
+        let log_ids = self.file_index.0["log_id"].str().unwrap();
+        let mut ranges: Vec<Range<usize>> = vec![];
+        for (i, log_id) in log_ids.into_iter().enumerate() {
+            match ranges.last_mut() {
+                Some(range) if log_ids.get(range.start) == log_id => range.end = i + 1,
+                _ => ranges.push(i..i + 1),
+            }
+        }
+        ranges
+    }
+
     /// Log split directory.
-    /// E.g., ~/data/datasets/av2/sensor/<split_name>.
+    /// E.g., ~/data/datasets/av2/sensor/<split_name>, or ~/data/datasets/av2/tbv for every TbV split.
     pub fn split_dir(&self) -> PathBuf {
-        self.root_dir
-            .join(&self.dataset_name)
-            .join(&self.dataset_type)
-            .join(&self.split_name)
+        _split_dir(
+            &self.root_dir,
+            &self.dataset_name,
+            &self.dataset_type,
+            &self.split_name,
+        )
     }
 
     /// Log split directory.
@@ -237,6 +1606,11 @@ impl DataLoader {
         self.split_dir().join(log_id)
     }
 
+    /// Modalities available in `log_id`.
+    pub fn availability(&self, log_id: &str) -> ModalityAvailability {
+        ModalityAvailability::from_log_dir(&self.log_dir(log_id))
+    }
+
     /// Camera path for the specified `camera_name` in `log_id` captured at `timestamp_ns`.
     /// `<log_dir>/<camera_name>/<timestamp_ns>.jpg`.
     pub fn camera_path(&self, log_id: &str, camera_name: &str, timestamp_ns: u64) -> PathBuf {
@@ -270,129 +1644,599 @@ impl DataLoader {
     }
 
     /// Read the annotations occuring in `log_id` between `[timestamp_ns + timestamp_ns + ~0.1 s]`.
-    pub fn read_annotations(&self, log_id: &str, timestamp_ns: u64) -> DataFrame {
-        read_timestamped_feather(
+    /// Only the annotations of `category_ids` (if set) whose centers are within the `roi` are read.
+    pub fn read_annotations(&self, log_id: &str, timestamp_ns: u64) -> Av2Result<DataFrame> {
+        Ok(self
+            .read_annotations_lazy(log_id, timestamp_ns)?
+            .collect()?)
+    }
+
+    /// Lazy query of `read_annotations`, with the `roi` and the `category_ids` filter composed.
+    pub fn read_annotations_lazy(&self, log_id: &str, timestamp_ns: u64) -> Av2Result<LazyFrame> {
+        let annotations = read_timestamped_feather(
             &self.annotations_path(log_id),
             &ANNOTATION_COLUMNS.to_vec(),
+            &ANNOTATION_SCHEMA,
             &timestamp_ns,
-            self.memory_mapped,
-        )
-        .filter(col("num_interior_pts").gt_eq(MIN_NUM_LIDAR_PTS))
-        .collect()
-        .unwrap()
+            self.memory_map_config().as_ref(),
+        )?
+        .filter(col("num_interior_pts").gt_eq(MIN_NUM_LIDAR_PTS));
+        let annotations = self.roi.filter_cuboids_lazy(annotations);
+        Ok(match self.category_ids.is_empty() {
+            true => annotations,
+            false => remap_categories_lazy(annotations, &self.category_ids),
+        })
+    }
+
+    /// Lazy query of every annotation of `log_id` (the `ANNOTATION_SCHEMA` columns), including
+    /// those without interior lidar points.
+    pub fn read_log_annotations_lazy(&self, log_id: &str) -> Av2Result<LazyFrame> {
+        let path = self.annotations_path(log_id);
+        let annotations = io::read_feather_selected(
+            &path,
+            &ScanSelection::default(),
+            self.memory_map_config().as_ref(),
+        )?;
+        validate_data_frame(
+            &path.display().to_string(),
+            &annotations,
+            &ANNOTATION_SCHEMA,
+        )?;
+        Ok(annotations
+            .lazy()
+            .select([cols(ANNOTATION_SCHEMA.map(|(name, _)| name))]))
+    }
+
+    /// Read the cuboids of track `track_uuid` across `log_id` in timestamp order. If
+    /// `interpolate` is set, the cuboids are interpolated at every lidar sweep of the log within
+    /// the track's lifespan instead (see `interpolate_track`).
+    pub fn read_track(
+        &self,
+        log_id: &str,
+        track_uuid: &str,
+        interpolate: bool,
+    ) -> Av2Result<DataFrame> {
+        let track = self
+            .read_log_annotations_lazy(log_id)?
+            .filter(col("track_uuid").eq(lit(track_uuid)))
+            .sort("timestamp_ns", SortOptions::default())
+            .collect()?;
+        if track.height() == 0 {
+            return Err(Av2Error::invalid_parameter(
+                "track_uuid",
+                format!("Track `{track_uuid}` is not annotated in log `{log_id}`."),
+            ));
+        }
+        match interpolate {
+            true => Ok(interpolate_track(
+                &track,
+                track_uuid,
+                &self.log_timestamps_ns(log_id)?,
+            )?),
+            false => Ok(track),
+        }
+    }
+
+    /// Summarize the tracks of `log_id`: the `category`, the first and last annotated
+    /// `start_timestamp_ns` and `end_timestamp_ns`, and the `num_annotations` of each
+    /// `track_uuid`. Tracks are ordered by start timestamp and track uuid.
+    pub fn read_track_summaries(&self, log_id: &str) -> Av2Result<DataFrame> {
+        Ok(self
+            .read_log_annotations_lazy(log_id)?
+            .group_by([col("track_uuid")])
+            .agg([
+                col("category").first(),
+                col("timestamp_ns").min().alias("start_timestamp_ns"),
+                col("timestamp_ns").max().alias("end_timestamp_ns"),
+                col("timestamp_ns")
+                    .count()
+                    .cast(DataType::UInt64)
+                    .alias("num_annotations"),
+            ])
+            .sort_by_exprs(
+                [col("start_timestamp_ns"), col("track_uuid")],
+                [false, false],
+                false,
+                false,
+            )
+            .collect()?)
+    }
+
+    /// Lidar sweep timestamps of `log_id` in timestamp order.
+    pub fn log_timestamps_ns(&self, log_id: &str) -> Av2Result<Vec<u64>> {
+        let rows = self
+            .log_rows(log_id)
+            .map_err(|err| Av2Error::invalid_parameter("log_id", err))?;
+        let timestamps_ns = self.file_index.0["timestamp_ns"].u64()?;
+        Ok(rows.filter_map(|row| timestamps_ns.get(row)).collect())
     }
 
     /// Read city egovehicle pose occuring at `timestamp_ns`.
-    pub fn read_city_pose(&self, log_id: &str, timestamp_ns: u64) -> DataFrame {
-        read_timestamped_feather(
+    pub fn read_city_pose(&self, log_id: &str, timestamp_ns: u64) -> Av2Result<DataFrame> {
+        Ok(read_timestamped_feather(
             &self.city_pose_path(log_id),
             &POSE_COLUMNS.to_vec(),
+            &POSE_SCHEMA,
             &timestamp_ns,
-            self.memory_mapped,
-        )
-        .collect()
-        .unwrap()
+            self.memory_map_config().as_ref(),
+        )?
+        .collect()?)
     }
 
-    /// Read the lidar occuring in `log_id` between `[timestamp_ns + timestamp_ns + ~0.1 s]`.
-    pub fn read_lidar(&self, log_id: &str, timestamp_ns: u64, index: usize) -> DataFrame {
-        read_accumulate_lidar(
+    /// Egovehicle pose in the city frame interpolated at `timestamp_ns`, or `None` if the
+    /// timestamp is outside of the log's poses.
+    pub fn city_se3_ego(&self, log_id: &str, timestamp_ns: u64) -> Av2Result<Option<SE3>> {
+        let city_pose = read_feather(&self.city_pose_path(log_id), false)?;
+        interpolate_city_se3_ego(&city_pose, timestamp_ns)
+    }
+
+    /// Map directory associated with `log_id`.
+    /// E.g., `<log_dir>/map`.
+    pub fn map_dir(&self, log_id: &str) -> PathBuf {
+        self.log_dir(log_id).join("map")
+    }
+
+    /// Read the drivable area polygons of `log_id`'s map in the city frame.
+    pub fn read_drivable_areas(&self, log_id: &str) -> Av2Result<Vec<Polygon>> {
+        let map_dir = self.map_dir(log_id);
+        if !map_dir.is_dir() {
+            return Err(Av2Error::MissingFile { path: map_dir });
+        }
+        let map = ArgoverseStaticMap::from_map_dir(&map_dir, false)
+            .map_err(|err| Av2Error::decode(&map_dir, err))?;
+        Ok(map.drivable_area_polygons())
+    }
+
+    /// Keep the lidar points (in the `frame`) and cuboids (in the egovehicle frame) within
+    /// `margin_m` of the drivable areas of `log_id`'s map. The query is collected to compute the
+    /// masks and returned as a lazy frame again, so later augmentations are still fused.
+    pub fn filter_to_drivable_area(
+        &self,
+        log_id: &str,
+        timestamp_ns: u64,
+        lidar: LazyFrame,
+        cuboids: Option<LazyFrame>,
+        frame: AccumulationFrame,
+        margin_m: f32,
+    ) -> Av2Result<(LazyFrame, Option<LazyFrame>)> {
+        let drivable_areas = self.read_drivable_areas(log_id)?;
+        if !self.city_pose_path(log_id).exists() {
+            return Err(Av2Error::MissingFile {
+                path: self.city_pose_path(log_id),
+            });
+        }
+        let city_se3_ego = self.city_se3_ego(log_id, timestamp_ns)?.ok_or_else(|| {
+            Av2Error::invalid_parameter(
+                "roi",
+                format!("No egovehicle pose at {timestamp_ns} to locate the drivable area."),
+            )
+        })?;
+        let city_se3_lidar = match frame {
+            AccumulationFrame::Ego => city_se3_ego.clone(),
+            AccumulationFrame::City => SE3::identity(),
+        };
+        let lidar = filter_lidar_to_drivable_area(
+            &lidar.collect()?,
+            &drivable_areas,
+            &city_se3_lidar,
+            margin_m,
+        )?;
+        let cuboids = cuboids
+            .map(|x| {
+                filter_cuboids_to_drivable_area(
+                    &x.collect()?,
+                    &drivable_areas,
+                    &city_se3_ego,
+                    margin_m,
+                )
+            })
+            .transpose()?;
+        Ok((lidar.lazy(), cuboids.map(|x| x.lazy())))
+    }
+
+    /// Extrinsics path associated with `log_id`.
+    /// E.g., `<log_dir>/calibration/egovehicle_SE3_sensor.feather`.
+    pub fn extrinsics_path(&self, log_id: &str) -> PathBuf {
+        self.log_dir(log_id)
+            .join("calibration/egovehicle_SE3_sensor.feather")
+    }
+
+    /// Read the sensor extrinsics of `log_id` (`sensor_name` and the `POSE_COLUMNS`).
+    pub fn read_extrinsics(&self, log_id: &str) -> Av2Result<DataFrame> {
+        Ok(read_feather(&self.extrinsics_path(log_id), false)?
+            .lazy()
+            .select([col("sensor_name"), cols(POSE_COLUMNS)])
+            .collect()?)
+    }
+
+    /// Read the lidar occuring in `log_id` between `[timestamp_ns + timestamp_ns + ~0.1 s]` in the
+    /// `accumulation` frame. The up lidar returns are removed if `drop_up_lidar` is set, points outside of the `roi` are
+    /// removed, and the `derived_columns` are appended. Only the rows and columns of `lidar_selection` are read, although `x`, `y`, and
+    /// `z` (and `laser_number` if `drop_up_lidar` is set) are always included.
+    pub fn read_lidar(
+        &self,
+        log_id: &str,
+        timestamp_ns: u64,
+        index: usize,
+    ) -> Av2Result<DataFrame> {
+        let lidar = self
+            .read_lidar_lazy(log_id, timestamp_ns, index)?
+            .collect()?;
+        self.append_derived_columns(&lidar)
+    }
+
+    /// Lazy query of `read_lidar` without the `derived_columns`, with the up lidar filter and the
+    /// `roi` composed over the accumulated sweeps.
+    pub fn read_lidar_lazy(
+        &self,
+        log_id: &str,
+        timestamp_ns: u64,
+        index: usize,
+    ) -> Av2Result<LazyFrame> {
+        let lidar = read_accumulate_lidar(
             self.log_dir(log_id),
             &self.file_index.0,
             log_id,
             timestamp_ns,
             index,
             self.num_accumulated_sweeps,
-            self.memory_mapped,
-        )
-        .collect()
-        .unwrap()
+            &self.accumulation,
+            &self.lidar_scan_selection(),
+            self.memory_map_config().as_ref(),
+            &self.thread_pools,
+        )?;
+        let lidar = match self.drop_up_lidar {
+            true => lidar.filter(col("laser_number").gt_eq(lit(NUM_LASERS_PER_LIDAR))),
+            false => lidar,
+        };
+        Ok(self.roi.filter_lidar_lazy(lidar))
     }
 
-    /// Get the sweep at `index`.
-    pub fn get_synchronized_images(&self, index: usize) -> Vec<TimeStampedImage> {
-        let row = self.file_index.0.get_row(index).unwrap().0;
-        let (log_id, _) = (
+    /// Append the `derived_columns` to the lidar.
+    pub fn append_derived_columns(&self, lidar: &DataFrame) -> Av2Result<DataFrame> {
+        let derived_columns = parse_derived_columns(&self.derived_columns)?;
+        append_derived_columns(lidar, &derived_columns, &GroundEstimationConfig::default())
+    }
+
+    /// Memory-mapped read configuration, or `None` if `memory_mapped` is not set.
+    pub fn memory_map_config(&self) -> Option<MemoryMapConfig> {
+        self.memory_mapped.then(|| MemoryMapConfig {
+            cache_dir: self.memory_map_dir.clone(),
+            ..Default::default()
+        })
+    }
+
+    /// Lidar selection with the columns required by `read_lidar` added to its projection.
+    fn lidar_scan_selection(&self) -> ScanSelection {
+        let mut selection = self.lidar_selection.clone();
+        if let Some(columns) = selection.columns.as_mut() {
+            let mut required = match self.drop_up_lidar {
+                true => vec!["x", "y", "z", "laser_number"],
+                false => vec!["x", "y", "z"],
+            };
+            if self.accumulation.compensate_per_point {
+                required.push("offset_ns");
+            }
+            for column in required {
+                if !columns.iter().any(|x| x == column) {
+                    columns.push(column.to_string());
+                }
+            }
+        }
+        selection
+    }
+
+    /// Camera timestamp closest to `timestamp_ns` shifted by a random latency in
+    /// `[-max_latency_jitter_ns, max_latency_jitter_ns]`, or `None` if the camera has no images.
+    pub fn jittered_camera_timestamp(
+        &self,
+        log_id: &str,
+        camera_name: &str,
+        timestamp_ns: u64,
+        index: usize,
+    ) -> Av2Result<Option<u64>> {
+        validate_max_latency_jitter_ns(self.max_latency_jitter_ns)?;
+        let seed = self.seed.wrapping_add(index as u64)
+            ^ (u64::from(crc32fast::hash(camera_name.as_bytes())) << 32);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let jitter_ns = self.max_latency_jitter_ns as i64;
+        let query_ns = (timestamp_ns as i64)
+            .saturating_add(rng.gen_range(-jitter_ns..=jitter_ns))
+            .max(0) as u64;
+
+        let timestamps = self.camera_timestamps(log_id, camera_name);
+        let i = timestamps.partition_point(|&x| x < query_ns);
+        Ok(timestamps[i.saturating_sub(1)..timestamps.len().min(i + 1)]
+            .iter()
+            .copied()
+            .min_by_key(|x| x.abs_diff(query_ns)))
+    }
+
+    /// Sorted image timestamps of `camera_name` in `log_id`, which are listed once per data-loader.
+    fn camera_timestamps(&self, log_id: &str, camera_name: &str) -> Arc<Vec<u64>> {
+        let key = (log_id.to_string(), camera_name.to_string());
+        if let Some(timestamps) = self.camera_timestamps.0.read().unwrap().get(&key) {
+            return timestamps.clone();
+        }
+        let camera_dir = self
+            .log_dir(log_id)
+            .join("sensors/cameras")
+            .join(camera_name);
+        let mut timestamps = walk_dir(&camera_dir)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|path| extract_file_stem(path).ok()?.parse::<u64>().ok())
+            .collect_vec();
+        timestamps.sort_unstable();
+        let timestamps = Arc::new(timestamps);
+        self.camera_timestamps
+            .0
+            .write()
+            .unwrap()
+            .insert(key, timestamps.clone());
+        timestamps
+    }
+
+    /// Get the synchronized ring camera images of the sweep at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the images cannot be read (see `try_get_synchronized_images`).
+    pub fn get_synchronized_images(&self, index: usize) -> Vec<Option<TimeStampedImage>> {
+        self.try_get_synchronized_images(index)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Get the synchronized ring camera images of the sweep at `index`, or the error of reading
+    /// them attributed to its log.
+    /// Cameras are `None` if the log has no calibration or if they have no image near the sweep.
+    /// Cameras in `dropped_cameras` yield blank images to simulate sensor failures.
+    pub fn try_get_synchronized_images(
+        &self,
+        index: usize,
+    ) -> Av2Result<Vec<Option<TimeStampedImage>>> {
+        let row = self.file_index.0.get_row(index)?.0;
+        let (log_id, timestamp_ns) = (
             row.first().unwrap().get_str().unwrap(),
-            row.get(1).unwrap().try_extract::<u64>().unwrap(),
+            row.get(1).unwrap().try_extract::<u64>()?,
         );
+        let log_dir = self.log_dir(log_id);
+        if !self.availability(log_id).calibration {
+            return Ok(vec![None; CameraNames::iter().count()]);
+        }
 
+        // Read the encoded images on the I/O pool and decode them on the decode pool.
         let camera_names = CameraNames::iter().collect_vec();
-        let images = camera_names
-            .par_iter()
-            .enumerate()
-            .map(|(i, camera_name)| {
-                let maybe_timestamp_ns_camera = row.get(i + 3).unwrap().try_extract::<u64>();
-                match maybe_timestamp_ns_camera {
-                    Ok(maybe_timestamp_ns_camera) => {
-                        let timestamp_ns_camera = maybe_timestamp_ns_camera;
-                        let camera_path = self.camera_path(
+        let encoded_images = self.thread_pools.install_io(|| {
+            camera_names
+                .par_iter()
+                .enumerate()
+                .map(|(i, camera_name)| -> Av2Result<_> {
+                    let camera_name = camera_name.to_string();
+                    let camera_model =
+                        PinholeCamera::load(&log_dir, &camera_name).map_err(|err| {
+                            Av2Error::decode(
+                                &log_dir.join("calibration"),
+                                format!("`{camera_name}`: {err}"),
+                            )
+                        })?;
+                    if self.dropped_cameras.contains(&camera_name) {
+                        return Ok(Some((camera_model, usize::MAX, None)));
+                    }
+
+                    let timestamp_ns_camera = match self.max_latency_jitter_ns > 0 {
+                        true => self.jittered_camera_timestamp(
                             log_id,
-                            camera_name.to_string().as_str(),
-                            timestamp_ns_camera,
-                        );
-                        let image = TimeStampedImage {
-                            image: read_image_rgba8(&camera_path),
-                            camera_model: PinholeCamera::from_feather(
-                                &self.log_dir(log_id),
-                                camera_name.to_string().as_str(),
-                            ),
-                            timestamp_ns: timestamp_ns_camera as usize,
+                            &camera_name,
+                            timestamp_ns,
+                            index,
+                        )?,
+                        false => row.get(i + 3).unwrap().try_extract::<u64>().ok(),
+                    };
+                    let Some(timestamp_ns_camera) = timestamp_ns_camera else {
+                        return Ok(None);
+                    };
+                    let camera_path = self.camera_path(log_id, &camera_name, timestamp_ns_camera);
+                    let bytes = std::fs::read(&camera_path)
+                        .map_err(|err| Av2Error::open(&camera_path, err))?;
+                    Ok(Some((
+                        camera_model,
+                        timestamp_ns_camera as usize,
+                        Some((camera_path, bytes)),
+                    )))
+                })
+                .collect::<Av2Result<Vec<_>>>()
+        });
+        let encoded_images = encoded_images.map_err(|err| err.in_log(log_id))?;
+        self.thread_pools
+            .install_decode(|| {
+                encoded_images
+                    .into_par_iter()
+                    .map(|encoded_image| {
+                        let Some((camera_model, timestamp_ns, bytes)) = encoded_image else {
+                            return Ok(None);
                         };
-                        image
-                    }
-                    _ => {
-                        let camera_name = camera_name.to_string();
-                        let (width, height) = if camera_name == "ring_front_center" {
-                            (1550, 2048)
-                        } else {
-                            (2048, 1550)
+                        let image = match bytes {
+                            Some((camera_path, bytes)) => decode_image_rgba8(&bytes)
+                                .map_err(|err| Av2Error::decode(&camera_path, err))?,
+                            None => {
+                                let (width, height) =
+                                    (camera_model.width_px(), camera_model.height_px());
+                                ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(
+                                    width as u32,
+                                    height as u32,
+                                    vec![0; 4 * height * width],
+                                )
+                                .unwrap()
+                            }
                         };
-
-                        TimeStampedImage {
-                            image: ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(
-                                width,
-                                height,
-                                vec![0; 4 * height as usize * width as usize],
-                            )
-                            .unwrap(),
-                            camera_model: PinholeCamera::from_feather(
-                                &self.log_dir(log_id),
-                                camera_name.as_str(),
-                            ),
-                            timestamp_ns: usize::MAX,
-                        }
-                    }
-                }
+                        Ok(Some(TimeStampedImage {
+                            image,
+                            camera_model,
+                            timestamp_ns,
+                        }))
+                    })
+                    .collect::<Av2Result<Vec<_>>>()
             })
-            .collect();
-        images
+            .map_err(|err| err.in_log(log_id))
     }
 }
 
+/// Iterates over the sweeps in sample order, yielding the error of each sweep which fails to read
+/// (see `try_next`).
 impl Iterator for DataLoader {
-    type Item = Sweep;
+    type Item = Av2Result<Sweep>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let idx = self.current_index;
-        let sweep_data = self.get(idx);
-        self.current_index += 1;
+        self.try_next()
+    }
+}
 
-        Some(sweep_data)
+/// Rows of the `source` and `target` cuboids sharing a `track_uuid` (`track_uuid`,
+/// `source_index`, and `target_index`) in source order.
+pub fn cuboid_correspondences(source: &DataFrame, target: &DataFrame) -> PolarsResult<DataFrame> {
+    let target_rows: HashMap<&str, u32> = target["track_uuid"]
+        .str()?
+        .into_no_null_iter()
+        .zip(0..)
+        .collect();
+    let (mut track_uuids, mut source_rows, mut matched_target_rows) = (vec![], vec![], vec![]);
+    for (track_uuid, i) in source["track_uuid"].str()?.into_no_null_iter().zip(0_u32..) {
+        if let Some(&j) = target_rows.get(track_uuid) {
+            track_uuids.push(track_uuid);
+            source_rows.push(i);
+            matched_target_rows.push(j);
+        }
+    }
+    df!(
+        "track_uuid" => track_uuids,
+        "source_index" => source_rows,
+        "target_index" => matched_target_rows
+    )
+}
+
+/// Number of times each frame is sampled per epoch under class-balanced grouping and sampling
+/// (CBGS). With `N` frame occurrences of `C` categories in total, each category draws `N / C`
+/// frames with replacement from the frames containing it, so every category accounts for about
+/// the same share of the samples while the epoch length stays about the same.
+pub fn class_balanced_repeats(
+    frame_categories: &[BTreeMap<String, usize>],
+    seed: u64,
+) -> Vec<usize> {
+    let mut category_frames = BTreeMap::<&str, Vec<usize>>::new();
+    for (frame, categories) in frame_categories.iter().enumerate() {
+        for category in categories.keys() {
+            category_frames.entry(category).or_default().push(frame);
+        }
+    }
+    let num_occurrences = category_frames.values().map(|x| x.len()).sum::<usize>();
+    let num_categories = category_frames.len();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut num_repeats = vec![0; frame_categories.len()];
+    for frames in category_frames.values() {
+        for _ in 0..num_occurrences / num_categories {
+            num_repeats[frames[rng.gen_range(0..frames.len())]] += 1;
+        }
+    }
+    num_repeats
+}
+
+/// Keep the annotations whose `category` is in `category_ids` and append their training
+/// `class_id` (i64). Several categories may share a class id (e.g., to merge vehicle types).
+pub fn remap_categories(
+    annotations: &DataFrame,
+    category_ids: &HashMap<String, i64>,
+) -> PolarsResult<DataFrame> {
+    remap_categories_lazy(annotations.clone().lazy(), category_ids).collect()
+}
+
+/// Lazy query of `remap_categories`.
+pub fn remap_categories_lazy(
+    annotations: LazyFrame,
+    category_ids: &HashMap<String, i64>,
+) -> LazyFrame {
+    let category_ids = category_ids.clone();
+    let class_ids = col("category").map(
+        move |categories| {
+            let class_ids: Int64Chunked = categories
+                .str()?
+                .into_iter()
+                .map(|category| category.and_then(|x| category_ids.get(x).copied()))
+                .collect();
+            Ok(Some(class_ids.into_series()))
+        },
+        GetOutput::from_type(DataType::Int64),
+    );
+    annotations
+        .with_column(class_ids.alias(CLASS_ID_COLUMN))
+        .filter(col(CLASS_ID_COLUMN).is_not_null())
+}
+
+/// Apply the `augmentations` to the lazy lidar and cuboids in order and collect them. The leading
+/// column-level augmentations are composed into the queries and the rest are applied in place.
+fn _augment_lazy(
+    mut lidar: LazyFrame,
+    mut cuboids: Option<LazyFrame>,
+    augmentations: &[AppliedAugmentation],
+) -> Av2Result<(DataFrame, Option<DataFrame>)> {
+    let num_fused = augmentations
+        .iter()
+        .take_while(|x| x.is_column_level())
+        .count();
+    for augmentation in &augmentations[..num_fused] {
+        lidar = lidar.with_columns(augmentation.lidar_exprs().unwrap());
+        cuboids = cuboids.map(|x| x.with_columns(augmentation.cuboid_exprs().unwrap()));
+    }
+    let (mut lidar, mut cuboids) = info_span!("collect_sweep", num_fused).in_scope(|| {
+        Av2Result::Ok((lidar.collect()?, cuboids.map(|x| x.collect()).transpose()?))
+    })?;
+
+    let remaining = &augmentations[num_fused..];
+    if !remaining.is_empty() {
+        // Sweeps without annotations are augmented along with an empty cuboid list.
+        let mut no_cuboids = CuboidList::new(Array::zeros((0, CUBOID_COLUMNS.len())), None, None)
+            .map_err(|err| Av2Error::schema("cuboids", err))?
+            .to_data_frame();
+        let cuboids = cuboids.as_mut().unwrap_or(&mut no_cuboids);
+        let mut scratch = AugmentationScratch::default();
+        for augmentation in remaining {
+            augmentation.apply_in_place(&mut lidar, cuboids, &mut scratch)?;
+        }
     }
+    Ok((lidar, cuboids))
 }
 
-fn build_file_index(
+/// Directory of the logs of a split. TbV splits share a directory.
+fn _split_dir(
     root_dir: &Path,
     dataset_name: &str,
     dataset_type: &str,
     split_name: &str,
-) -> DataFrame {
-    let split_dir = root_dir.join(format!("{dataset_name}/{dataset_type}/{split_name}"));
-    let mut reference_frame = build_lidar_metadata(split_dir.clone(), "lidar");
+) -> PathBuf {
+    match dataset_type {
+        TBV_DATASET_TYPE => root_dir.join(dataset_name).join(dataset_type),
+        _ => root_dir.join(format!("{dataset_name}/{dataset_type}/{split_name}")),
+    }
+}
+
+/// Log ids of a split whose directory is shared with other splits, or `None` if every log in the
+/// split directory belongs to the split.
+fn _split_log_ids(
+    dataset_type: &str,
+    split_name: &str,
+) -> anyhow::Result<Option<&'static [&'static str]>> {
+    match dataset_type {
+        TBV_DATASET_TYPE => Ok(Some(tbv::split_log_ids(split_name)?)),
+        _ => Ok(None),
+    }
+}
+
+/// Build the file index of the logs in `split_dir` matching the glob `log_pattern`.
+pub(crate) fn build_logs_file_index(split_dir: &Path, log_pattern: &str) -> Av2Result<DataFrame> {
+    let log_dirs = split_dir.join(log_pattern);
+    let mut reference_frame = build_lidar_metadata(log_dirs.clone(), "lidar")?;
 
     reference_frame = reference_frame
-        .clone()
         .lazy()
         .sort_by_exprs(
             &[cols(["log_id", "timestamp_ns_lidar"])],
@@ -400,13 +2244,10 @@ fn build_file_index(
             false,
             true,
         )
-        .collect()
-        .unwrap();
+        .collect()?;
 
     for camera_name in CameraNames::iter().map(|x| x.to_string()) {
-        let frame = build_camera_metadata(split_dir.clone(), &camera_name);
-        frame
-            .clone()
+        let frame = build_camera_metadata(log_dirs.clone(), &camera_name)?
             .lazy()
             .sort_by_exprs(
                 &[cols([
@@ -417,113 +2258,75 @@ fn build_file_index(
                 false,
                 true,
             )
-            .collect()
-            .unwrap();
+            .collect()?;
 
-        reference_frame = reference_frame
-            .join_asof_by(
-                &frame,
-                "timestamp_ns_lidar",
-                format!("timestamp_ns_{}", camera_name).as_str(),
-                ["log_id"],
-                ["log_id"],
-                AsofStrategy::Nearest,
-                Some(AnyValue::Float32(MAX_CAM_LIDAR_TOL_NS)),
-            )
-            .unwrap();
+        reference_frame = reference_frame.join_asof_by(
+            &frame,
+            "timestamp_ns_lidar",
+            format!("timestamp_ns_{}", camera_name).as_str(),
+            ["log_id"],
+            ["log_id"],
+            AsofStrategy::Nearest,
+            Some(AnyValue::Float32(MAX_CAM_LIDAR_TOL_NS)),
+        )?;
 
         reference_frame = reference_frame.drop_many(&["city_name_right"]);
     }
-    reference_frame
-        .rename("timestamp_ns_lidar", "timestamp_ns")
-        .unwrap();
-    reference_frame
+    reference_frame.rename("timestamp_ns_lidar", "timestamp_ns")?;
+    Ok(reference_frame)
 }
 
-fn build_lidar_metadata(split_dir: PathBuf, sensor_name: &str) -> DataFrame {
-    let pattern = split_dir.join(format!("*/sensors/{sensor_name}/*.feather"));
-    let files = glob(pattern.to_str().unwrap())
-        .expect("Failed to read glob pattern.")
-        .filter_map(|path| match path {
-            Ok(x) => Some(x),
-            _ => None,
-        })
-        .collect_vec();
-
-    let log_id: Vec<_> = files
-        .iter()
-        .map(|x| {
-            x.ancestors()
-                .nth(3)
-                .unwrap()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-        })
-        .collect();
-
-    let timestamp_ns: Vec<_> = files
-        .clone()
-        .iter()
-        .map(|x| {
-            x.file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .parse::<u64>()
-                .unwrap()
-        })
-        .collect();
-    df!(
-        "log_id" => Series::new("log_id", log_id),
-        format!("timestamp_ns_{}", sensor_name).as_str() => Series::new("timestamp_ns", timestamp_ns.clone()),
-        "city_name" => Series::new("city_name", vec!["DEFAULT"; timestamp_ns.len()])
-    )
-    .unwrap()
+fn build_lidar_metadata(log_dirs: PathBuf, sensor_name: &str) -> Av2Result<DataFrame> {
+    let pattern = log_dirs.join(format!("sensors/{sensor_name}/*.feather"));
+    _build_sensor_metadata(&pattern, 3, sensor_name)
 }
 
-fn build_camera_metadata(split_dir: PathBuf, sensor_name: &str) -> DataFrame {
-    let pattern = split_dir.join(format!("*/sensors/cameras/{sensor_name}/*.jpg"));
-    let files = glob(pattern.to_str().unwrap())
-        .expect("Failed to read glob pattern.")
-        .filter_map(|path| match path {
-            Ok(x) => Some(x),
-            _ => None,
-        })
-        .collect_vec();
+fn build_camera_metadata(log_dirs: PathBuf, sensor_name: &str) -> Av2Result<DataFrame> {
+    let pattern = log_dirs.join(format!("sensors/cameras/{sensor_name}/*.jpg"));
+    _build_sensor_metadata(&pattern, 4, sensor_name)
+}
 
-    let log_id: Vec<_> = files
-        .iter()
-        .map(|x| {
-            x.ancestors()
-                .nth(4)
-                .unwrap()
-                .file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-        })
-        .collect();
+/// Index the sensor files matching `pattern`, named by their nanosecond timestamps, with the
+/// `log_id` of the log directory `log_dir_depth` levels above each file.
+fn _build_sensor_metadata(
+    pattern: &Path,
+    log_dir_depth: usize,
+    sensor_name: &str,
+) -> Av2Result<DataFrame> {
+    let pattern_str = pattern.to_str().ok_or_else(|| {
+        Av2Error::invalid_parameter(
+            "split_dir",
+            format!("{} is not valid UTF-8.", pattern.display()),
+        )
+    })?;
+    let paths = glob(pattern_str).map_err(|err| Av2Error::invalid_parameter("log_pattern", err))?;
 
-    let timestamp_ns: Vec<_> = files
-        .clone()
-        .iter()
-        .map(|x| {
-            x.file_stem()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .parse::<u64>()
-                .unwrap()
-        })
-        .collect();
-    df!(
+    let (mut log_id, mut timestamp_ns) = (vec![], vec![]);
+    for path in paths {
+        let path = path.map_err(|err| {
+            let path = err.path().to_path_buf();
+            Av2Error::open(&path, err.into_error())
+        })?;
+        let log_id_i = path
+            .ancestors()
+            .nth(log_dir_depth)
+            .and_then(|x| x.file_stem())
+            .and_then(|x| x.to_str())
+            .ok_or_else(|| Av2Error::decode(&path, "Expected a file in a log directory."))?;
+        let timestamp_ns_i = path
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .and_then(|x| x.parse::<u64>().ok())
+            .ok_or_else(|| Av2Error::decode(&path, "Expected a nanosecond timestamp file name."))?;
+        log_id.push(log_id_i.to_string());
+        timestamp_ns.push(timestamp_ns_i);
+    }
+    let num_files = timestamp_ns.len();
+    Ok(df!(
         "log_id" => Series::new("log_id", log_id),
-        format!("timestamp_ns_{}", sensor_name).as_str() => Series::new("timestamp_ns", timestamp_ns.clone()),
-        "city_name" => Series::new("city_name", vec!["DEFAULT"; timestamp_ns.len()])
-    )
-    .unwrap()
+        format!("timestamp_ns_{}", sensor_name).as_str() => Series::new("timestamp_ns", timestamp_ns),
+        "city_name" => Series::new("city_name", vec!["DEFAULT"; num_files])
+    )?)
 }
 
 // fn _fast_build(
@@ -640,3 +2443,936 @@ fn build_camera_metadata(split_dir: PathBuf, sensor_name: &str) -> DataFrame {
 //     )
 //     .unwrap()
 // }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use itertools::Itertools;
+    use polars::prelude::*;
+    use strum::IntoEnumIterator;
+
+    use super::{
+        class_balanced_repeats, remap_categories, DataLoader, LoaderState, ModalityAvailability,
+        ShardingMode, SweepMetadata, CLASS_ID_COLUMN,
+    };
+    use crate::{
+        constants::{CameraNames, POSE_COLUMNS},
+        error::Av2Error,
+        geometry::{
+            aggregation::{AccumulationConfig, AccumulationFrame},
+            augmentations::AppliedAugmentation,
+            roi::RoiConfig,
+        },
+        io::{build_lidar_file_path, read_feather, write_feather_eager},
+        manifest::{write_dataset_manifest, DATASET_MANIFEST_FILE_NAME},
+        share::data_frame_to_ndarray_f32,
+        synthetic::{test_dataset, MiniDatasetConfig},
+        tbv,
+    };
+
+    #[test]
+    fn test_tbv_split() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, log_dirs) = test_dataset(&cfg);
+
+        // TbV logs of every split share a directory.
+        let tbv_dir = root_dir.join("av2/tbv");
+        fs::create_dir_all(&tbv_dir).unwrap();
+        for (log_dir, log_id) in log_dirs.iter().zip([tbv::VAL[0], tbv::TRAIN[0]]) {
+            fs::rename(log_dir, tbv_dir.join(log_id)).unwrap();
+        }
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "tbv", "val", 1, false);
+        assert_eq!(data_loader.split_dir(), tbv_dir);
+        assert!(!data_loader.is_empty());
+        let log_ids = data_loader.file_index.0["log_id"]
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .unique()
+            .collect::<Vec<_>>();
+        assert_eq!(log_ids, vec![tbv::VAL[0]]);
+        assert_eq!(data_loader.get(0).sweep_uuid.0, tbv::VAL[0]);
+    }
+
+    #[test]
+    fn test_missing_lidar() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let (log_id, timestamp_ns) = data_loader.get(1).sweep_uuid;
+        let lidar_path = build_lidar_file_path(log_dir, timestamp_ns);
+        fs::remove_file(&lidar_path).unwrap();
+
+        let err = data_loader.try_get(1).unwrap_err();
+        assert!(matches!(err, Av2Error::InLog { .. }));
+        assert!(matches!(err.root(), Av2Error::MissingFile { path } if path == &lidar_path));
+        let message = err.to_string();
+        assert!(message.contains(&log_id) && message.contains(lidar_path.to_str().unwrap()));
+        assert!(matches!(
+            data_loader.try_get(data_loader.len()),
+            Err(Av2Error::InvalidParameter { .. })
+        ));
+
+        // The iteration moves past the sweep which fails to read.
+        assert!(data_loader.try_next().unwrap().is_ok());
+        assert!(data_loader.try_next().unwrap().is_err());
+        assert!(data_loader.try_next().unwrap().is_ok());
+        let is_ok = data_loader
+            .iter_log(&log_id)
+            .unwrap()
+            .map(|sweep| sweep.is_ok())
+            .collect_vec();
+        assert_eq!(is_ok[..3], [true, false, true]);
+    }
+
+    #[test]
+    fn test_annotation_schema() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let annotations_path = log_dir.join("annotations.feather");
+        let annotations = read_feather(&annotations_path, false)
+            .unwrap()
+            .drop("qw")
+            .unwrap()
+            .lazy()
+            .with_column(col("tx_m").cast(DataType::String))
+            .collect()
+            .unwrap();
+        write_feather_eager(&annotations_path, annotations).unwrap();
+
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let err = data_loader.try_get(0).unwrap_err();
+        assert!(matches!(err.root(), Av2Error::Schema { .. }));
+        let message = err.to_string();
+        assert!(message.contains(annotations_path.to_str().unwrap()));
+        assert!(message.contains("missing column `qw` (float)"));
+        assert!(message.contains("column `tx_m` has type str (expected float)"));
+    }
+
+    #[test]
+    fn test_partial_log() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let availability = ModalityAvailability::from_log_dir(&log_dir);
+        assert!(availability.lidar && availability.cameras && availability.map);
+
+        // Only the lidar, annotations, and calibration were downloaded.
+        fs::remove_dir_all(log_dir.join("sensors/cameras")).unwrap();
+        fs::remove_dir_all(log_dir.join("map")).unwrap();
+        fs::remove_file(log_dir.join("city_SE3_egovehicle.feather")).unwrap();
+        let availability = ModalityAvailability::from_log_dir(&log_dir);
+        assert_eq!(
+            availability,
+            ModalityAvailability {
+                lidar: true,
+                annotations: true,
+                calibration: true,
+                ..Default::default()
+            }
+        );
+
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        assert_eq!(data_loader.len(), 5);
+        let sweep = data_loader.get(0);
+        assert!(sweep.city_pose.is_none());
+        assert!(sweep.cuboids.is_some());
+        assert!(data_loader
+            .get_synchronized_images(0)
+            .iter()
+            .all(|x| x.is_none()));
+
+        // Dropped cameras still yield blank images while the calibration is available.
+        data_loader.dropped_cameras = vec!["ring_front_center".to_string()];
+        let images = data_loader.get_synchronized_images(0);
+        assert_eq!(images.iter().filter(|x| x.is_some()).count(), 1);
+        fs::remove_dir_all(log_dir.join("calibration")).unwrap();
+        assert!(data_loader
+            .get_synchronized_images(0)
+            .iter()
+            .all(|x| x.is_none()));
+    }
+
+    #[test]
+    fn test_from_manifest() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["train".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let split_dir = root_dir.join("av2/sensor/train");
+        let manifest_path = root_dir.join(DATASET_MANIFEST_FILE_NAME);
+        let manifest = write_dataset_manifest(&split_dir, &manifest_path).unwrap();
+
+        let data_loader = DataLoader::new(
+            root_dir.to_str().unwrap(),
+            "av2",
+            "sensor",
+            "train",
+            1,
+            false,
+        );
+        let mut booted = DataLoader::from_manifest(
+            root_dir.to_str().unwrap(),
+            "av2",
+            "sensor",
+            "train",
+            1,
+            false,
+            &manifest_path,
+        )
+        .unwrap();
+        assert!(booted.file_index.0.equals(&data_loader.file_index.0));
+
+        let sweep = booted.get(3);
+        let num_points = manifest["num_lidar_points"].u64().unwrap().get(3).unwrap();
+        assert_eq!(sweep.lidar.0.height() as u64, num_points);
+        let lidar_path = manifest["lidar_path"].str().unwrap().get(3).unwrap();
+        assert!(split_dir.join(lidar_path).exists());
+
+        // Logs without fingerprints are re-indexed by the next refresh.
+        let update = booted.refresh().unwrap();
+        assert_eq!(update.modified.len(), 2);
+        assert!(booted.file_index.0.equals(&data_loader.file_index.0));
+    }
+
+    #[test]
+    fn test_load_images() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        assert!(data_loader.get(0).images.is_none());
+
+        data_loader.load_images = true;
+        let images = data_loader.get(0).images.unwrap();
+        let num_expected = data_loader
+            .get_synchronized_images(0)
+            .iter()
+            .filter(|x| x.is_some())
+            .count();
+        assert!(num_expected > 0);
+        assert_eq!(images.len(), num_expected);
+        for image in &images {
+            assert_eq!(image.image.shape()[2], 3);
+            assert_eq!(image.intrinsics.shape(), &[3, 3]);
+            assert_eq!(image.ego_se3_cam.shape(), &[4, 4]);
+            assert_eq!(image.intrinsics[[0, 2]] * 2., image.image.shape()[1] as f32);
+        }
+
+        // A corrupt image fails to decode instead of panicking.
+        let (log_id, _) = data_loader.get(0).sweep_uuid;
+        let image = data_loader
+            .get_synchronized_images(0)
+            .into_iter()
+            .flatten()
+            .next()
+            .unwrap();
+        let camera_name = image.camera_model.camera_name;
+        let camera_path = data_loader.camera_path(&log_id, &camera_name, image.timestamp_ns as u64);
+        let bytes = fs::read(&camera_path).unwrap();
+        fs::write(&camera_path, &bytes[..bytes.len() / 2]).unwrap();
+        let err = data_loader.try_get(0).unwrap_err();
+        assert!(matches!(err.root(), Av2Error::Decode { path, .. } if path == &camera_path));
+        assert!(err.to_string().contains(&log_id));
+        fs::write(&camera_path, bytes).unwrap();
+
+        // Malformed intrinsics are an error rather than a silently missing camera.
+        let intrinsics_path = data_loader
+            .log_dir(&log_id)
+            .join("calibration/intrinsics.feather");
+        let intrinsics = read_feather(&intrinsics_path, false).unwrap();
+        write_feather_eager(
+            &intrinsics_path,
+            intrinsics
+                .lazy()
+                .filter(col("sensor_name").neq(lit(camera_name.as_str())))
+                .collect()
+                .unwrap(),
+        )
+        .unwrap();
+        let err = data_loader.try_get(0).unwrap_err();
+        assert!(matches!(err.root(), Av2Error::Decode { .. }));
+        assert!(err.to_string().contains(&camera_name));
+    }
+
+    #[test]
+    fn test_latency_jitter() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let (log_id, timestamp_ns) = data_loader.get(2).sweep_uuid;
+        let timestamps = data_loader.camera_timestamps(&log_id, "ring_front_center");
+        assert_eq!(timestamps.len(), data_loader.len());
+        assert!(timestamps.windows(2).all(|x| x[0] < x[1]));
+        let jittered = |data_loader: &DataLoader| {
+            CameraNames::iter()
+                .map(|camera_name| {
+                    data_loader
+                        .jittered_camera_timestamp(
+                            &log_id,
+                            &camera_name.to_string(),
+                            timestamp_ns,
+                            2,
+                        )
+                        .unwrap()
+                        .unwrap()
+                })
+                .collect_vec()
+        };
+
+        // Without jitter, the closest image is returned.
+        assert!(jittered(&data_loader)
+            .iter()
+            .all(|x| *x == timestamp_ns + 1_000_000));
+
+        // Every camera is jittered independently and deterministically.
+        data_loader.max_latency_jitter_ns = 1_000_000_000;
+        let camera_timestamps = jittered(&data_loader);
+        assert!(camera_timestamps.iter().all(|x| timestamps.contains(x)));
+        assert!(camera_timestamps.iter().any(|x| *x != camera_timestamps[0]));
+        assert_eq!(jittered(&data_loader), camera_timestamps);
+
+        // Queries before the first image are clamped rather than wrapped around.
+        data_loader.max_latency_jitter_ns = i64::MAX as u64;
+        let camera_timestamps = jittered(&data_loader);
+        assert!(camera_timestamps.contains(&timestamps[0]));
+        assert!(camera_timestamps.contains(timestamps.last().unwrap()));
+
+        data_loader.max_latency_jitter_ns = u64::MAX;
+        assert!(matches!(
+            data_loader.jittered_camera_timestamp(&log_id, "ring_front_center", timestamp_ns, 2),
+            Err(Av2Error::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sweep_calibration() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let sweep = data_loader.get(0);
+        assert!(sweep.extrinsics.is_none());
+
+        // Metadata round-trips through JSON and bincode.
+        let metadata = sweep.metadata();
+        assert_eq!(metadata.num_points, sweep.lidar.0.height());
+        assert!(metadata.city_se3_ego.is_some());
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SweepMetadata>(&json).unwrap(),
+            metadata
+        );
+        let bytes = bincode::serialize(&metadata).unwrap();
+        assert_eq!(
+            bincode::deserialize::<SweepMetadata>(&bytes).unwrap(),
+            metadata
+        );
+
+        // The interpolated pose agrees with the logged pose at the sweep timestamp.
+        let city_se3_ego = sweep.city_se3_ego.unwrap();
+        let city_pose = sweep.city_pose.unwrap().0;
+        let translation = ["tx_m", "ty_m", "tz_m"].map(|name| {
+            city_pose[name]
+                .cast(&DataType::Float32)
+                .unwrap()
+                .f32()
+                .unwrap()
+                .get(0)
+        });
+        for (i, t) in translation.into_iter().enumerate() {
+            assert!((city_se3_ego[[i, 3]] - t.unwrap()).abs() < 1e-3);
+        }
+        assert_eq!(city_se3_ego.row(3).to_vec(), vec![0., 0., 0., 1.]);
+
+        data_loader.load_extrinsics = true;
+        let extrinsics = data_loader.get(0).extrinsics.unwrap().0;
+        assert_eq!(extrinsics.width(), 1 + POSE_COLUMNS.len());
+        assert!(extrinsics.height() > 0);
+    }
+
+    #[test]
+    fn test_loader_state() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let new_data_loader =
+            || DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let mut data_loader = new_data_loader();
+        data_loader.max_latency_jitter_ns = 10_000_000;
+        data_loader.seed = 7;
+        data_loader.prefetch_size = 2;
+        data_loader.py_set_shuffle(true, 3);
+        data_loader.py_set_epoch(2);
+        data_loader
+            .set_sharding(1, 2, ShardingMode::Strided)
+            .unwrap();
+        data_loader.next().unwrap().unwrap();
+
+        // A preempted job resumes from the serialized state.
+        let state = serde_json::to_string(&data_loader.state()).unwrap();
+        let mut resumed = new_data_loader();
+        resumed.max_latency_jitter_ns = 10_000_000;
+        resumed
+            .set_state(&serde_json::from_str(&state).unwrap())
+            .unwrap();
+        assert_eq!(resumed.state(), data_loader.state());
+        let expected = data_loader.map(|x| x.unwrap().sweep_uuid).collect_vec();
+        assert!(!expected.is_empty());
+        assert_eq!(
+            resumed.map(|x| x.unwrap().sweep_uuid).collect_vec(),
+            expected
+        );
+
+        let mut state: LoaderState = serde_json::from_str(&state).unwrap();
+        state.num_sweeps += 1;
+        assert!(new_data_loader().set_state(&state).is_err());
+    }
+
+    #[test]
+    fn test_class_balanced() {
+        let frame_categories = [vec!["A"], vec!["A"], vec!["A"], vec!["A", "B"], vec![]]
+            .map(|x| x.into_iter().map(|x| (x.to_string(), 1)).collect());
+        let num_repeats = class_balanced_repeats(&frame_categories, 0);
+        // Both categories draw 5 / 2 frames, so the only frame with `B` is repeated twice.
+        assert_eq!(num_repeats.iter().sum::<usize>(), 4);
+        assert!(num_repeats[3] >= 2);
+        assert_eq!(num_repeats[4], 0);
+
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let frame_categories = data_loader.frame_categories().unwrap();
+        assert_eq!(frame_categories.len(), data_loader.len());
+        assert!(frame_categories.iter().any(|x| !x.is_empty()));
+
+        data_loader.set_class_balanced(true, 3).unwrap();
+        assert_eq!(
+            data_loader.sample_order.len(),
+            data_loader.num_repeats.iter().sum::<usize>()
+        );
+        for (row, categories) in frame_categories.iter().enumerate() {
+            let num_samples = data_loader
+                .sample_order
+                .iter()
+                .filter(|x| **x == row)
+                .count();
+            assert_eq!(num_samples, data_loader.num_repeats[row]);
+            if categories.is_empty() {
+                assert_eq!(num_samples, 0);
+            }
+        }
+        data_loader
+            .set_sharding(0, 2, ShardingMode::Contiguous)
+            .unwrap();
+        assert!(data_loader
+            .sample_order
+            .iter()
+            .all(|x| data_loader.num_repeats[*x] > 0));
+
+        data_loader.set_class_balanced(false, 0).unwrap();
+        data_loader
+            .set_sharding(0, 1, ShardingMode::Strided)
+            .unwrap();
+        assert_eq!(
+            data_loader.sample_order,
+            (0..data_loader.len()).collect_vec()
+        );
+    }
+
+    #[test]
+    fn test_log_iteration() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let log_ids = data_loader.log_ids();
+        assert_eq!(log_ids.len(), cfg.num_logs_per_split);
+        let num_sweeps = data_loader.num_sweeps_per_log();
+        assert_eq!(num_sweeps.values().sum::<usize>(), data_loader.len());
+
+        let log_id = &log_ids[1];
+        let timestamps_ns = data_loader
+            .iter_log(log_id)
+            .unwrap()
+            .map(|sweep| {
+                let sweep = sweep.unwrap();
+                assert_eq!(&sweep.sweep_uuid.0, log_id);
+                sweep.sweep_uuid.1
+            })
+            .collect_vec();
+        assert_eq!(timestamps_ns.len(), num_sweeps[log_id]);
+        assert!(timestamps_ns.windows(2).all(|x| x[0] < x[1]));
+        assert!(data_loader.iter_log("missing").is_err());
+    }
+
+    #[test]
+    fn test_sweep_pair() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let pair_indices = data_loader.pair_indices();
+        assert_eq!(
+            pair_indices.len(),
+            data_loader.len() - cfg.num_logs_per_split
+        );
+        let last_index = data_loader.log_ranges()[0].end - 1;
+        assert!(data_loader.get_pair(last_index).is_err());
+
+        let pair = data_loader.get_pair(pair_indices[0]).unwrap();
+        assert_eq!(pair.source.sweep_uuid.0, pair.target.sweep_uuid.0);
+        assert!(pair.source.sweep_uuid.1 < pair.target.sweep_uuid.1);
+        assert_eq!(pair.target.city_se3_ego, pair.source.city_se3_ego);
+
+        // The target cuboids are moved by the egovehicle motion between the sweeps.
+        let target = data_loader.get(pair_indices[0] + 1);
+        let correspondences = pair.correspondences.unwrap().0;
+        assert!(correspondences.height() > 0);
+        let (source_cuboids, target_cuboids) = (
+            pair.source.cuboids.unwrap().0,
+            pair.target.cuboids.unwrap().0,
+        );
+        let raw_cuboids = target.cuboids.unwrap().0;
+        let target_index = correspondences["target_index"].u32().unwrap();
+        for (i, (source_index, j)) in correspondences["source_index"]
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .zip(target_index.into_no_null_iter())
+            .enumerate()
+        {
+            let track_uuid = correspondences["track_uuid"].str().unwrap().get(i);
+            assert_eq!(
+                source_cuboids["track_uuid"]
+                    .str()
+                    .unwrap()
+                    .get(source_index as usize),
+                track_uuid
+            );
+            assert_eq!(
+                target_cuboids["track_uuid"].str().unwrap().get(j as usize),
+                track_uuid
+            );
+            let tx_m = target_cuboids["tx_m"].f64().unwrap().get(j as usize);
+            let raw_tx_m = raw_cuboids["tx_m"].f64().unwrap().get(j as usize);
+            let dx_m = f64::from(pair.source_se3_target[[0, 3]]);
+            assert!((tx_m.unwrap() - raw_tx_m.unwrap() - dx_m).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_accumulation() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 2, false);
+        let ego_sweep = data_loader.get(1);
+        assert_eq!(ego_sweep.lidar_frame, "ego");
+        assert!(!ego_sweep.motion_compensated);
+
+        data_loader.accumulation = AccumulationConfig {
+            frame: AccumulationFrame::City,
+            compensate_per_point: false,
+        };
+        let city_sweep = data_loader.get(1);
+        assert_eq!(city_sweep.lidar_frame, "city");
+        assert_eq!(city_sweep.lidar.0.height(), ego_sweep.lidar.0.height());
+
+        // The city frame lidar is the ego frame lidar moved by the egovehicle pose.
+        let city_se3_ego = city_sweep.city_se3_ego.unwrap();
+        let (ego_x, city_x) = (
+            ego_sweep.lidar.0["x"].f32().unwrap().get(0).unwrap(),
+            city_sweep.lidar.0["x"].f32().unwrap().get(0).unwrap(),
+        );
+        let (ego_y, ego_z) = (
+            ego_sweep.lidar.0["y"].f32().unwrap().get(0).unwrap(),
+            ego_sweep.lidar.0["z"].f32().unwrap().get(0).unwrap(),
+        );
+        let expected_x = city_se3_ego[[0, 0]] * ego_x
+            + city_se3_ego[[0, 1]] * ego_y
+            + city_se3_ego[[0, 2]] * ego_z
+            + city_se3_ego[[0, 3]];
+        assert!((city_x - expected_x).abs() < 1e-2);
+
+        data_loader.accumulation.compensate_per_point = true;
+        data_loader.lidar_selection.columns = Some(vec!["intensity".to_string()]);
+        let compensated_sweep = data_loader.get(1);
+        assert!(compensated_sweep.motion_compensated);
+        assert_eq!(
+            compensated_sweep.lidar.0.height(),
+            ego_sweep.lidar.0.height()
+        );
+    }
+
+    #[test]
+    fn test_get_augmented() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 2, false);
+        data_loader.roi = RoiConfig {
+            max_range_m: Some(40.),
+            ..Default::default()
+        };
+        let category = data_loader.get(1).cuboids.unwrap().0["category"]
+            .str()
+            .unwrap()
+            .get(0)
+            .unwrap()
+            .to_string();
+        data_loader.category_ids = HashMap::from([(category, 3)]);
+
+        let sweep = data_loader.get(1);
+        let cuboids = sweep.cuboids.unwrap().0;
+        assert!(cuboids.height() > 0);
+        assert!(cuboids[CLASS_ID_COLUMN]
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .all(|x| x == 3));
+        let augmentations = vec![
+            AppliedAugmentation::SceneReflectionY { is_reflected: true },
+            AppliedAugmentation::SceneGlobalRotation { theta: 0.2 },
+            AppliedAugmentation::SceneGlobalScale { scale_factor: 0.9 },
+            AppliedAugmentation::RandomObjectScale {
+                scale_factors: vec![1.2; cuboids.height()],
+            },
+            AppliedAugmentation::SceneReflectionX { is_reflected: true },
+        ];
+        let (expected_lidar, expected_cuboids) = augmentations
+            .iter()
+            .try_fold(
+                (sweep.lidar.0, cuboids),
+                |(lidar, cuboids), augmentation| augmentation.apply(lidar, cuboids),
+            )
+            .unwrap();
+
+        let augmented = data_loader.get_augmented(1, &augmentations).unwrap();
+        for (expected, augmented, names) in [
+            (&expected_lidar, &augmented.lidar.0, vec!["x", "y", "z"]),
+            (
+                &expected_cuboids,
+                &augmented.cuboids.as_ref().unwrap().0,
+                vec!["tx_m", "ty_m", "tz_m", "length_m", "qw", "qz"],
+            ),
+        ] {
+            assert_eq!(expected.shape(), augmented.shape());
+            for name in names {
+                let [expected, augmented] = [expected, augmented].map(|x| {
+                    x[name]
+                        .cast(&DataType::Float32)
+                        .unwrap()
+                        .f32()
+                        .unwrap()
+                        .to_vec()
+                });
+                assert!(expected
+                    .iter()
+                    .zip(&augmented)
+                    .all(|(x, y)| (x.unwrap() - y.unwrap()).abs() < 1e-3));
+            }
+        }
+    }
+
+    #[test]
+    fn test_drivable_area_roi() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let sweep = data_loader.get(0);
+        let (log_id, timestamp_ns) = sweep.sweep_uuid.clone();
+
+        data_loader.roi = RoiConfig {
+            drivable_area_margin_m: Some(0.),
+            ..Default::default()
+        };
+        let filtered = data_loader.get(0);
+        let lidar = filtered.lidar.0;
+        assert!(lidar.height() > 0 && lidar.height() < sweep.lidar.0.height());
+        let drivable_areas = data_loader.read_drivable_areas(&log_id).unwrap();
+        let city_se3_ego = data_loader
+            .city_se3_ego(&log_id, timestamp_ns)
+            .unwrap()
+            .unwrap();
+        let xyz = data_frame_to_ndarray_f32(lidar, vec!["x", "y", "z"]).unwrap();
+        let xyz_city = city_se3_ego.transform_from(&xyz.view());
+        assert!(xyz_city.outer_iter().all(|p| drivable_areas
+            .iter()
+            .any(|polygon| polygon.distance(p[0], p[1]) <= 1e-3)));
+        assert!(filtered.cuboids.unwrap().0.height() <= sweep.cuboids.unwrap().0.height());
+
+        data_loader.roi.drivable_area_margin_m = Some(1e4);
+        assert_eq!(data_loader.get(0).lidar.0.height(), sweep.lidar.0.height());
+
+        fs::remove_dir_all(log_dir.join("map")).unwrap();
+        let err = data_loader.try_get(0).unwrap_err();
+        assert!(matches!(err.root(), Av2Error::MissingFile { .. }));
+    }
+
+    #[test]
+    fn test_read_track() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let log_id = data_loader.log_ids().remove(0);
+
+        let summaries = data_loader.read_track_summaries(&log_id).unwrap();
+        assert_eq!(
+            summaries.get_column_names(),
+            [
+                "track_uuid",
+                "category",
+                "start_timestamp_ns",
+                "end_timestamp_ns",
+                "num_annotations"
+            ]
+        );
+        let track_uuid = summaries["track_uuid"].str().unwrap().get(0).unwrap();
+        let num_annotations = summaries["num_annotations"].u64().unwrap().get(0).unwrap();
+        let track = data_loader.read_track(&log_id, track_uuid, false).unwrap();
+        assert_eq!(track.height() as u64, num_annotations);
+        let timestamps_ns = track["timestamp_ns"]
+            .cast(&DataType::UInt64)
+            .unwrap()
+            .u64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect_vec();
+        assert!(timestamps_ns.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(
+            timestamps_ns.first().copied(),
+            summaries["start_timestamp_ns"]
+                .cast(&DataType::UInt64)
+                .unwrap()
+                .u64()
+                .unwrap()
+                .get(0)
+        );
+
+        // Interpolated tracks have a cuboid at every sweep within their lifespan.
+        let (start, end) = (timestamps_ns[0], *timestamps_ns.last().unwrap());
+        let num_sweeps = data_loader
+            .log_timestamps_ns(&log_id)
+            .unwrap()
+            .into_iter()
+            .filter(|x| (start..=end).contains(x))
+            .count();
+        let interpolated = data_loader.read_track(&log_id, track_uuid, true).unwrap();
+        assert_eq!(interpolated.height(), num_sweeps);
+        assert_eq!(interpolated.schema(), track.schema());
+
+        let err = data_loader
+            .read_track(&log_id, "missing", false)
+            .unwrap_err();
+        assert!(matches!(err, Av2Error::InvalidParameter { .. }));
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 2, false);
+        let expected = data_loader
+            .clone()
+            .map(Result::unwrap)
+            .map(|x| (x.sweep_uuid, x.lidar.0.height()))
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), data_loader.len());
+
+        data_loader.prefetch_size = 3;
+        data_loader.num_prefetch_threads = 2;
+        let sweeps = data_loader
+            .by_ref()
+            .take(4)
+            .map(Result::unwrap)
+            .map(|x| (x.sweep_uuid, x.lidar.0.height()))
+            .collect::<Vec<_>>();
+        assert_eq!(sweeps, expected[..4]);
+
+        // Seeking restarts the prefetcher at the new index.
+        data_loader.current_index = 1;
+        let sweeps = data_loader
+            .map(Result::unwrap)
+            .map(|x| (x.sweep_uuid, x.lidar.0.height()))
+            .collect::<Vec<_>>();
+        assert_eq!(sweeps, expected[1..]);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let num_sweeps = data_loader.len();
+        assert_eq!(
+            data_loader.sample_order,
+            (0..num_sweeps).collect::<Vec<_>>()
+        );
+
+        let mut orders = vec![];
+        for epoch in [0, 1, 0] {
+            data_loader.py_set_shuffle(true, 7);
+            data_loader.py_set_epoch(epoch);
+            let order = data_loader.sample_order.clone();
+            let mut sorted = order.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..num_sweeps).collect::<Vec<_>>());
+
+            let sweep_uuids = data_loader
+                .by_ref()
+                .map(|x| x.unwrap().sweep_uuid)
+                .collect::<Vec<_>>();
+            let expected = order
+                .iter()
+                .map(|i| data_loader.get(*i).sweep_uuid)
+                .collect::<Vec<_>>();
+            assert_eq!(sweep_uuids, expected);
+            orders.push(order);
+        }
+        // Orders are reproducible and differ per epoch.
+        assert_eq!(orders[0], orders[2]);
+        assert_ne!(orders[0], orders[1]);
+    }
+
+    #[test]
+    fn test_sharding() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 4,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let log_ranges = data_loader.log_ranges();
+        assert_eq!(log_ranges.len(), 4);
+        data_loader.py_set_shuffle(true, 3);
+
+        for mode in [ShardingMode::Strided, ShardingMode::Contiguous] {
+            let shards = (0..3)
+                .map(|rank| {
+                    data_loader.set_sharding(rank, 3, mode).unwrap();
+                    data_loader.sample_order.clone()
+                })
+                .collect::<Vec<_>>();
+            let mut rows = shards.concat();
+            rows.sort_unstable();
+            assert_eq!(rows, (0..data_loader.len()).collect::<Vec<_>>());
+            if mode == ShardingMode::Strided {
+                assert!(shards
+                    .iter()
+                    .all(|x| x.len().abs_diff(shards[0].len()) <= 1));
+            } else {
+                // Every log belongs to a single shard, and its sweeps stay in order.
+                for range in &log_ranges {
+                    let shard = shards.iter().find(|x| x.contains(&range.start)).unwrap();
+                    let start = shard.iter().position(|x| *x == range.start).unwrap();
+                    assert_eq!(
+                        shard[start..start + range.len()],
+                        range.clone().collect::<Vec<_>>()
+                    );
+                }
+            }
+        }
+        assert!(data_loader
+            .set_sharding(3, 3, ShardingMode::Strided)
+            .is_err());
+    }
+
+    #[test]
+    fn test_remap_categories() {
+        let annotations = df!(
+            "category" => ["REGULAR_VEHICLE", "PEDESTRIAN", "BUS", "LARGE_VEHICLE"],
+            "tx_m" => [0_f64, 1., 2., 3.]
+        )
+        .unwrap();
+        let category_ids = HashMap::from([
+            ("REGULAR_VEHICLE".to_string(), 0),
+            ("BUS".to_string(), 1),
+            ("LARGE_VEHICLE".to_string(), 1),
+        ]);
+        let annotations = remap_categories(&annotations, &category_ids).unwrap();
+        assert_eq!(
+            annotations["tx_m"].f64().unwrap().to_vec(),
+            vec![Some(0.), Some(2.), Some(3.)]
+        );
+        assert_eq!(
+            annotations[CLASS_ID_COLUMN].i64().unwrap().to_vec(),
+            vec![Some(0), Some(1), Some(1)]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! # config
+//!
+//! Configuration of the data-loader.
+//!
+//! `DataLoaderConfig` gathers the construction parameters of a data-loader with their defaults, so
+//! a configuration is written as `DataLoaderConfig { split_name: .., ..Default::default() }` and
+//! validated as a whole by `DataLoader::from_config` before the file index is built.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use strum::IntoEnumIterator;
+
+use super::DataLoader;
+use crate::{
+    constants::CameraNames,
+    error::{Av2Error, Av2Result},
+    geometry::{
+        aggregation::AccumulationConfig, derived_columns::parse_derived_columns, roi::RoiConfig,
+    },
+    io::ScanSelection,
+};
+
+/// Configuration of a data-loader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataLoaderConfig {
+    /// Root directory of the datasets.
+    pub root_dir: PathBuf,
+    /// Dataset name (e.g., `av2`).
+    pub dataset_name: String,
+    /// Dataset type (e.g., `sensor`).
+    pub dataset_type: String,
+    /// Split name (e.g., `train`).
+    pub split_name: String,
+    /// Number of accumulated lidar sweeps (at least one).
+    pub num_accumulated_sweeps: usize,
+    /// Region of interest of the lidar points and cuboid centers.
+    pub roi: RoiConfig,
+    /// Reference frame and motion compensation of the accumulated sweeps.
+    pub accumulation: AccumulationConfig,
+    /// Ring cameras whose images are loaded into each sweep, or `None` to skip the images. The
+    /// other ring cameras yield blank images.
+    pub cameras: Option<Vec<String>>,
+    /// Training class ids of the loaded annotation categories (all categories if empty).
+    pub category_ids: HashMap<String, i64>,
+    /// Column projection and row predicates of the lidar reads.
+    pub lidar_selection: ScanSelection,
+    /// Derived per-point columns appended to the lidar (e.g., `range_m`).
+    pub derived_columns: Vec<String>,
+    /// Maximum latency jitter (in nanoseconds, at most `i64::MAX`) of the camera timestamps.
+    pub max_latency_jitter_ns: u64,
+    /// Random seed of the latency jitter.
+    pub seed: u64,
+    /// Boolean flag to memory-map files (compressed files through uncompressed copies).
+    pub memory_mapped: bool,
+    /// Directory of the uncompressed copies, or `None` for the user's cache directory.
+    pub memory_map_dir: Option<PathBuf>,
+    /// Dataset manifest (see `write_dataset_manifest`) indexing the split, or `None` to scan the
+    /// split directory.
+    pub manifest_path: Option<PathBuf>,
+    /// Number of sweeps prefetched while iterating (`0` disables prefetching).
+    pub prefetch_size: usize,
+    /// Number of threads prefetching sweeps (at least one).
+    pub num_prefetch_threads: usize,
+}
+
+impl Default for DataLoaderConfig {
+    fn default() -> Self {
+        Self {
+            root_dir: PathBuf::new(),
+            dataset_name: "av2".to_string(),
+            dataset_type: "sensor".to_string(),
+            split_name: "train".to_string(),
+            num_accumulated_sweeps: 1,
+            roi: RoiConfig::default(),
+            accumulation: AccumulationConfig::default(),
+            cameras: None,
+            category_ids: HashMap::new(),
+            lidar_selection: ScanSelection::default(),
+            derived_columns: vec![],
+            max_latency_jitter_ns: 0,
+            seed: 0,
+            memory_mapped: false,
+            memory_map_dir: None,
+            manifest_path: None,
+            prefetch_size: 0,
+            num_prefetch_threads: 1,
+        }
+    }
+}
+
+impl DataLoaderConfig {
+    /// Default configuration of the datasets in `root_dir`.
+    pub fn new(root_dir: impl AsRef<Path>) -> Self {
+        Self {
+            root_dir: root_dir.as_ref().to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Check the parameters without reading the dataset.
+    pub fn validate(&self) -> Av2Result<()> {
+        if !self.root_dir.is_dir() {
+            return Err(Av2Error::MissingFile {
+                path: self.root_dir.clone(),
+            });
+        }
+        if self.num_accumulated_sweeps == 0 {
+            return Err(Av2Error::invalid_parameter(
+                "num_accumulated_sweeps",
+                "At least one sweep must be accumulated.",
+            ));
+        }
+        if self.num_prefetch_threads == 0 {
+            return Err(Av2Error::invalid_parameter(
+                "num_prefetch_threads",
+                "The number of prefetch threads must be positive.",
+            ));
+        }
+        if self.roi.max_range_m.is_some_and(|x| x.is_nan() || x <= 0.0) {
+            return Err(Av2Error::invalid_parameter(
+                "roi",
+                "The maximum range must be positive.",
+            ));
+        }
+        if self
+            .roi
+            .drivable_area_margin_m
+            .is_some_and(|x| x.is_nan() || x < 0.0)
+        {
+            return Err(Av2Error::invalid_parameter(
+                "roi",
+                "The drivable area margin must be non-negative.",
+            ));
+        }
+        for (axis, range) in [
+            ("x", self.roi.x_range_m),
+            ("y", self.roi.y_range_m),
+            ("z", self.roi.z_range_m),
+        ] {
+            if range.is_some_and(|(min, max)| min.is_nan() || max.is_nan() || min > max) {
+                return Err(Av2Error::invalid_parameter(
+                    "roi",
+                    format!("The {axis}-range must be ordered as (min, max)."),
+                ));
+            }
+        }
+        for predicate in &self.lidar_selection.predicates {
+            if let (Some(min), Some(max)) = (predicate.min, predicate.max) {
+                if min > max {
+                    return Err(Av2Error::invalid_parameter(
+                        "lidar_selection",
+                        format!("The range of `{}` is empty.", predicate.column),
+                    ));
+                }
+            }
+        }
+        for camera in self.cameras.iter().flatten() {
+            if CameraNames::from_str(camera).is_err() {
+                return Err(Av2Error::invalid_parameter(
+                    "cameras",
+                    format!("Unknown camera `{camera}`."),
+                ));
+            }
+        }
+        parse_derived_columns(&self.derived_columns)?;
+        validate_max_latency_jitter_ns(self.max_latency_jitter_ns)?;
+        Ok(())
+    }
+}
+
+/// Check that the latency jitter is representable as a signed offset.
+pub fn validate_max_latency_jitter_ns(max_latency_jitter_ns: u64) -> Av2Result<()> {
+    if i64::try_from(max_latency_jitter_ns).is_err() {
+        return Err(Av2Error::invalid_parameter(
+            "max_latency_jitter_ns",
+            format!("{max_latency_jitter_ns} exceeds {}.", i64::MAX),
+        ));
+    }
+    Ok(())
+}
+
+impl DataLoader {
+    /// Initialize the data-loader from a configuration, which is validated first, and build the
+    /// file index.
+    pub fn from_config(cfg: &DataLoaderConfig) -> anyhow::Result<DataLoader> {
+        cfg.validate()?;
+        let mut data_loader = match &cfg.manifest_path {
+            Some(manifest_path) => DataLoader::from_manifest(
+                &cfg.root_dir.to_string_lossy(),
+                &cfg.dataset_name,
+                &cfg.dataset_type,
+                &cfg.split_name,
+                cfg.num_accumulated_sweeps,
+                cfg.memory_mapped,
+                manifest_path,
+            )?,
+            None => DataLoader::scan(
+                cfg.root_dir.clone(),
+                &cfg.dataset_name,
+                &cfg.dataset_type,
+                &cfg.split_name,
+                cfg.num_accumulated_sweeps,
+                cfg.memory_mapped,
+            )?,
+        };
+        if let Some(memory_map_dir) = &cfg.memory_map_dir {
+            data_loader.memory_map_dir = memory_map_dir.clone();
+        }
+        data_loader.roi = cfg.roi.clone();
+        data_loader.accumulation = cfg.accumulation;
+        if let Some(cameras) = &cfg.cameras {
+            data_loader.load_images = true;
+            data_loader.dropped_cameras = CameraNames::iter()
+                .map(|x| x.to_string())
+                .filter(|x| !cameras.contains(x))
+                .collect();
+        }
+        data_loader.category_ids = cfg.category_ids.clone();
+        data_loader.lidar_selection = cfg.lidar_selection.clone();
+        data_loader.derived_columns = cfg.derived_columns.clone();
+        data_loader.max_latency_jitter_ns = cfg.max_latency_jitter_ns;
+        data_loader.seed = cfg.seed;
+        data_loader.prefetch_size = cfg.prefetch_size;
+        data_loader.num_prefetch_threads = cfg.num_prefetch_threads;
+        Ok(data_loader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataLoaderConfig;
+    use crate::{
+        data_loader::DataLoader,
+        error::Av2Error,
+        geometry::roi::RoiConfig,
+        synthetic::{test_dataset, MiniDatasetConfig},
+    };
+
+    #[test]
+    fn test_from_config() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+
+        let cfg = DataLoaderConfig {
+            split_name: "val".to_string(),
+            num_accumulated_sweeps: 2,
+            roi: RoiConfig {
+                max_range_m: Some(20.0),
+                ..Default::default()
+            },
+            cameras: Some(vec!["ring_front_center".to_string()]),
+            prefetch_size: 2,
+            ..DataLoaderConfig::new(&root_dir)
+        };
+        let data_loader = DataLoader::from_config(&cfg).unwrap();
+        let expected =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 2, false);
+        assert!(data_loader.file_index.0.equals(&expected.file_index.0));
+        assert_eq!(data_loader.roi, cfg.roi);
+        assert!(data_loader.load_images);
+        assert_eq!(data_loader.dropped_cameras.len(), 6);
+        assert_eq!(data_loader.prefetch_size, 2);
+
+        for invalid in [
+            DataLoaderConfig {
+                num_accumulated_sweeps: 0,
+                ..cfg.clone()
+            },
+            DataLoaderConfig {
+                roi: RoiConfig {
+                    x_range_m: Some((1.0, -1.0)),
+                    ..Default::default()
+                },
+                ..cfg.clone()
+            },
+            DataLoaderConfig {
+                cameras: Some(vec!["ring_front".to_string()]),
+                ..cfg.clone()
+            },
+            DataLoaderConfig {
+                max_latency_jitter_ns: u64::MAX,
+                ..cfg.clone()
+            },
+        ] {
+            assert!(matches!(
+                invalid.validate(),
+                Err(Av2Error::InvalidParameter { .. })
+            ));
+        }
+        assert!(matches!(
+            DataLoaderConfig::new(root_dir.join("missing")).validate(),
+            Err(Av2Error::MissingFile { .. })
+        ));
+    }
+}
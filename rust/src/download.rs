@@ -0,0 +1,684 @@
+//! # download
+//!
+//! Anonymous downloads of the Argoverse 2 datasets.
+//!
+//! The datasets are public objects of the `argoverse` S3 bucket, which is read over HTTPS without
+//! credentials. Objects are listed per split (or per log) and fetched by a bounded pool of threads,
+//! so at most `num_threads` files are open at once. Each file is written to a `.part` file next to
+//! its destination and renamed once complete; an interrupted download resumes from the bytes
+//! already on disk with a range request, and failed requests are retried with exponential backoff.
+//! Files whose size matches the listing are skipped, so a download can be re-run to completion.
+//!
+//! A download can be restricted to some logs and modalities (e.g., only the lidar and annotations
+//! of a split, or only the maps of the forecasting scenarios), which `DownloadConfig::selects`
+//! decides from the path of a file within its split, whether listed or recorded.
+//!
+//! The listed file sizes of each split are recorded next to the split directory (see
+//! `integrity::file_sizes_path`), so the download can be verified later with
+//! `integrity::verify_split` without network access.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use strum_macros::{Display, EnumIter, EnumString};
+use tracing::{debug, warn};
+use ureq::{Agent, AgentBuilder};
+
+use crate::{
+    integrity::{file_sizes_path, read_file_sizes, write_file_sizes},
+    parallel::build_thread_pool,
+};
+
+/// HTTPS endpoint of the public `argoverse` bucket.
+pub const AV2_BUCKET_URL: &str = "https://s3.amazonaws.com/argoverse";
+
+/// Prefix of the dataset objects within the bucket (e.g., `datasets/av2/sensor/val/...`).
+pub const DATASETS_PREFIX: &str = "datasets";
+
+/// Kind of the files of a log (or scenario).
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum DownloadModality {
+    /// Lidar sweeps (`sensors/lidar/`).
+    Lidar,
+    /// Camera images (`sensors/cameras/`).
+    Cameras,
+    /// Cuboid annotations (`annotations.feather`).
+    Annotations,
+    /// Egovehicle poses (`city_SE3_egovehicle.feather`).
+    Poses,
+    /// Sensor intrinsics and extrinsics (`calibration/`).
+    Calibration,
+    /// Vector maps and ground height surfaces (`map/`, or `log_map_archive_*.json` of a scenario).
+    Maps,
+    /// Motion forecasting scenarios (`scenario_*.parquet`).
+    Scenarios,
+}
+
+impl DownloadModality {
+    /// Modality of a file given its path within its log (e.g., `sensors/lidar/<timestamp>.feather`),
+    /// or `None` for other files.
+    pub fn of(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        let dir_name = path.components().next()?.as_os_str().to_str()?;
+        match (dir_name, file_name) {
+            ("sensors", _) if path.starts_with("sensors/lidar") => Some(Self::Lidar),
+            ("sensors", _) if path.starts_with("sensors/cameras") => Some(Self::Cameras),
+            (_, "annotations.feather") => Some(Self::Annotations),
+            (_, "city_SE3_egovehicle.feather") => Some(Self::Poses),
+            ("calibration", _) => Some(Self::Calibration),
+            ("map", _) => Some(Self::Maps),
+            (_, x) if x.starts_with("log_map_archive_") => Some(Self::Maps),
+            (_, x) if x.starts_with("scenario_") && x.ends_with(".parquet") => {
+                Some(Self::Scenarios)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Configuration of a dataset download.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DownloadConfig {
+    /// URL of the bucket.
+    pub bucket_url: String,
+    /// Dataset name (e.g., `av2`).
+    pub dataset_name: String,
+    /// Dataset type (e.g., `sensor`).
+    pub dataset_type: String,
+    /// Downloaded splits (e.g., `val`).
+    pub split_names: Vec<String>,
+    /// Downloaded logs, or `None` to download every log of the splits.
+    pub log_ids: Option<Vec<String>>,
+    /// Downloaded modalities, or `None` to download every file of the logs.
+    pub modalities: Option<Vec<DownloadModality>>,
+    /// Number of files downloaded concurrently.
+    pub num_threads: usize,
+    /// Number of retries of a failed request.
+    pub max_retries: usize,
+    /// Delay before the first retry, which doubles with each further retry.
+    pub retry_delay: Duration,
+    /// Timeout of the connection and of each read.
+    pub timeout: Duration,
+}
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            bucket_url: AV2_BUCKET_URL.to_string(),
+            dataset_name: "av2".to_string(),
+            dataset_type: "sensor".to_string(),
+            split_names: vec!["val".to_string()],
+            log_ids: None,
+            modalities: None,
+            num_threads: 8,
+            max_retries: 5,
+            retry_delay: Duration::from_secs(1),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl DownloadConfig {
+    /// HTTP agent of the download.
+    pub fn agent(&self) -> Agent {
+        AgentBuilder::new()
+            .timeout_connect(self.timeout)
+            .timeout_read(self.timeout)
+            .build()
+    }
+
+    /// Returns `true` if the file at `path` (relative to its split directory, i.e.,
+    /// `<log_id>/...`) belongs to the selected logs and modalities.
+    pub fn selects(&self, path: &Path) -> bool {
+        let mut components = path.components();
+        let Some(log_id) = components.next().and_then(|x| x.as_os_str().to_str()) else {
+            return false;
+        };
+        if let Some(log_ids) = &self.log_ids {
+            if !log_ids.iter().any(|x| x == log_id) {
+                return false;
+            }
+        }
+        match &self.modalities {
+            Some(modalities) => DownloadModality::of(components.as_path())
+                .is_some_and(|modality| modalities.contains(&modality)),
+            None => true,
+        }
+    }
+
+    /// Bucket prefix of the split `split_name` (with a trailing `/`).
+    pub fn split_prefix(&self, split_name: &str) -> String {
+        format!(
+            "{DATASETS_PREFIX}/{}/{}/{split_name}/",
+            self.dataset_name, self.dataset_type
+        )
+    }
+}
+
+/// Object of the bucket.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RemoteObject {
+    /// Key of the object (e.g., `datasets/av2/sensor/val/<log_id>/annotations.feather`).
+    pub key: String,
+    /// Size of the object (in bytes).
+    pub size: u64,
+}
+
+impl RemoteObject {
+    /// Destination of the object below `root_dir`, which mirrors the key without the
+    /// `DATASETS_PREFIX` (i.e., the data-loader's `<root_dir>/<dataset_name>/<dataset_type>/...`).
+    pub fn local_path(&self, root_dir: &Path) -> Result<PathBuf> {
+        let relative_path = Path::new(&self.key)
+            .strip_prefix(DATASETS_PREFIX)
+            .unwrap_or(Path::new(&self.key));
+        ensure!(
+            relative_path
+                .components()
+                .all(|x| matches!(x, Component::Normal(_))),
+            "Invalid object key `{}`.",
+            self.key
+        );
+        Ok(root_dir.join(relative_path))
+    }
+}
+
+/// Counts of a download.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DownloadSummary {
+    /// Number of downloaded (or resumed) files.
+    pub num_downloaded: usize,
+    /// Number of files which were already complete.
+    pub num_skipped: usize,
+    /// Number of bytes transferred.
+    pub num_bytes: u64,
+}
+
+/// List the objects of the selected splits and logs.
+pub fn list_dataset_objects(cfg: &DownloadConfig) -> Result<Vec<RemoteObject>> {
+    let agent = cfg.agent();
+    let mut objects = vec![];
+    for split_name in &cfg.split_names {
+        let split_prefix = cfg.split_prefix(split_name);
+        match &cfg.log_ids {
+            Some(log_ids) => {
+                for log_id in log_ids {
+                    let log_prefix = format!("{split_prefix}{log_id}/");
+                    objects.extend(list_objects(&agent, cfg, &log_prefix)?);
+                }
+            }
+            None => objects.extend(list_objects(&agent, cfg, &split_prefix)?),
+        }
+        objects.retain(|x| match x.key.strip_prefix(&split_prefix) {
+            Some(relative_path) => cfg.selects(Path::new(relative_path)),
+            None => true,
+        });
+    }
+    if let Some(log_ids) = &cfg.log_ids {
+        for log_id in log_ids {
+            if !objects
+                .iter()
+                .any(|x| x.key.contains(&format!("/{log_id}/")))
+            {
+                warn!(
+                    "Log `{log_id}` not found in the splits {:?}.",
+                    cfg.split_names
+                );
+            }
+        }
+    }
+    Ok(objects)
+}
+
+/// List the objects whose key starts with `prefix`, following the pages of the listing.
+pub fn list_objects(
+    agent: &Agent,
+    cfg: &DownloadConfig,
+    prefix: &str,
+) -> Result<Vec<RemoteObject>> {
+    let mut objects = vec![];
+    let mut continuation_token: Option<String> = None;
+    loop {
+        let listing = _retry(cfg, || {
+            let mut request = agent
+                .get(&cfg.bucket_url)
+                .query("list-type", "2")
+                .query("prefix", prefix);
+            if let Some(token) = &continuation_token {
+                request = request.query("continuation-token", token);
+            }
+            Ok(request.call()?.into_string()?)
+        })
+        .with_context(|| format!("Failed to list `{prefix}`."))?;
+        objects.extend(parse_listing(&listing)?);
+        continuation_token = match _xml_value(&listing, "IsTruncated").as_deref() {
+            Some("true") => _xml_value(&listing, "NextContinuationToken"),
+            _ => None,
+        };
+        if continuation_token.is_none() {
+            return Ok(objects);
+        }
+    }
+}
+
+/// Parse the objects of a page of an S3 `ListObjectsV2` response.
+pub fn parse_listing(listing: &str) -> Result<Vec<RemoteObject>> {
+    _xml_elements(listing, "Contents")
+        .into_iter()
+        .map(
+            |contents| match (_xml_value(contents, "Key"), _xml_value(contents, "Size")) {
+                (Some(key), Some(size)) => Ok(RemoteObject {
+                    key,
+                    size: size.parse()?,
+                }),
+                _ => bail!("Listed object without a key or size: {contents}"),
+            },
+        )
+        .collect()
+}
+
+/// Download the selected splits and logs into `root_dir`, recording their file sizes first.
+pub fn download_dataset(root_dir: &Path, cfg: &DownloadConfig) -> Result<DownloadSummary> {
+    let objects = list_dataset_objects(cfg)?;
+    record_file_sizes(root_dir, &objects, cfg)?;
+    download_objects(root_dir, &objects, cfg, |_| {})
+}
+
+/// Add the sizes of the listed `objects` to the file sizes manifest of each split, replacing the
+/// entries of the listed files.
+pub fn record_file_sizes(
+    root_dir: &Path,
+    objects: &[RemoteObject],
+    cfg: &DownloadConfig,
+) -> Result<()> {
+    for split_name in &cfg.split_names {
+        let split_prefix = cfg.split_prefix(split_name);
+        let split_dir = RemoteObject {
+            key: split_prefix.trim_end_matches('/').to_string(),
+            size: 0,
+        }
+        .local_path(root_dir)?;
+        let path = file_sizes_path(&split_dir);
+        let mut file_sizes = match path.exists() {
+            true => read_file_sizes(&path)?,
+            false => BTreeMap::new(),
+        };
+        for object in objects {
+            if let Some(relative_path) = object.key.strip_prefix(&split_prefix) {
+                file_sizes.insert(PathBuf::from(relative_path), object.size);
+            }
+        }
+        write_file_sizes(&path, &file_sizes)?;
+    }
+    Ok(())
+}
+
+/// Download the `objects` into `root_dir` on a pool of `num_threads` threads. `on_progress` is
+/// called with the number of bytes of each written chunk (e.g., to advance a progress bar).
+/// Every object is attempted; the error lists the objects which failed after their retries.
+pub fn download_objects(
+    root_dir: &Path,
+    objects: &[RemoteObject],
+    cfg: &DownloadConfig,
+    on_progress: impl Fn(u64) + Sync,
+) -> Result<DownloadSummary> {
+    let agent = cfg.agent();
+    let pool = build_thread_pool(Some(cfg.num_threads), "av2-download")?.unwrap();
+    let num_bytes = AtomicU64::new(0);
+    let progress = |n: u64| {
+        num_bytes.fetch_add(n, Ordering::Relaxed);
+        on_progress(n);
+    };
+    let results = pool.install(|| {
+        objects
+            .par_iter()
+            .map(|object| {
+                let path = object.local_path(root_dir)?;
+                download_object(&agent, cfg, object, &path, &progress)
+                    .with_context(|| format!("Failed to download `{}`.", object.key))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut summary = DownloadSummary {
+        num_bytes: num_bytes.into_inner(),
+        ..Default::default()
+    };
+    let mut errors = vec![];
+    for result in results {
+        match result {
+            Ok(true) => summary.num_downloaded += 1,
+            Ok(false) => summary.num_skipped += 1,
+            Err(err) => errors.push(err),
+        }
+    }
+    if let Some(err) = errors.first() {
+        bail!(
+            "{} of {} files failed to download (e.g., {err:#}); re-run to resume.",
+            errors.len(),
+            objects.len()
+        );
+    }
+    Ok(summary)
+}
+
+/// Download an object to `path`, resuming a partial `.part` file. Returns `false` if the file
+/// was already complete.
+pub fn download_object(
+    agent: &Agent,
+    cfg: &DownloadConfig,
+    object: &RemoteObject,
+    path: &Path,
+    on_progress: &(impl Fn(u64) + Sync),
+) -> Result<bool> {
+    if fs::metadata(path).is_ok_and(|x| x.len() == object.size) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut part_path = path.as_os_str().to_owned();
+    part_path.push(".part");
+    let part_path = PathBuf::from(part_path);
+    let url = format!("{}/{}", cfg.bucket_url, _encode_key(&object.key));
+
+    _retry(cfg, || {
+        let mut offset = fs::metadata(&part_path).map_or(0, |x| x.len());
+        if offset > object.size {
+            fs::remove_file(&part_path)?;
+            offset = 0;
+        }
+        if offset < object.size {
+            let mut request = agent.get(&url);
+            if offset > 0 {
+                request = request.set("Range", &format!("bytes={offset}-"));
+            }
+            let response = request.call()?;
+            // A server which ignores the range sends the whole object.
+            let mut file = match response.status() {
+                206 => OpenOptions::new().append(true).open(&part_path)?,
+                _ => File::create(&part_path)?,
+            };
+            _copy(&mut response.into_reader(), &mut file, on_progress)?;
+        }
+        let size = fs::metadata(&part_path)?.len();
+        ensure!(
+            size == object.size,
+            "Expected {} bytes, got {size}.",
+            object.size
+        );
+        Ok(())
+    })?;
+    fs::rename(&part_path, path)?;
+    Ok(true)
+}
+
+/// Copy `reader` into `writer`, reporting the size of each chunk.
+fn _copy(reader: &mut impl Read, writer: &mut File, on_progress: &impl Fn(u64)) -> io::Result<()> {
+    let mut buffer = vec![0; 1 << 16];
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        io::Write::write_all(writer, &buffer[..n])?;
+        on_progress(n as u64);
+    }
+}
+
+/// Call `f` until it succeeds, retrying up to `max_retries` times with exponential backoff.
+/// Client errors (e.g., `403` or `404`) are not retried, except for throttling (`429`).
+fn _retry<T>(cfg: &DownloadConfig, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delay = cfg.retry_delay;
+    for attempt in 0.. {
+        let err = match f() {
+            Ok(x) => return Ok(x),
+            Err(err) => err,
+        };
+        let is_permanent = matches!(
+            err.downcast_ref::<ureq::Error>(),
+            Some(ureq::Error::Status(code, _)) if (400..500).contains(code) && *code != 429
+        );
+        if is_permanent || attempt >= cfg.max_retries {
+            return Err(err);
+        }
+        debug!("Retrying in {delay:?} after: {err:#}");
+        thread::sleep(delay);
+        delay *= 2;
+    }
+    unreachable!()
+}
+
+/// Contents of the `tag` elements of an XML document.
+fn _xml_elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{tag}>"), format!("</{tag}>"));
+    let mut elements = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        elements.push(&rest[..end]);
+        rest = &rest[end + close.len()..];
+    }
+    elements
+}
+
+/// Unescaped text of the first `tag` element of an XML document.
+fn _xml_value(xml: &str, tag: &str) -> Option<String> {
+    let text = _xml_elements(xml, tag).into_iter().next()?;
+    Some(
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Percent-encode an object key for its URL, keeping the `/` separators.
+fn _encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|x| match x {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (x as char).to_string()
+            }
+            _ => format!("%{x:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::Path,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use super::{download_dataset, parse_listing, DownloadConfig, DownloadModality, RemoteObject};
+    use crate::integrity::{read_file_sizes, verify_split};
+    use crate::synthetic::test_dir;
+
+    const LISTING: &str = "<ListBucketResult><Prefix>datasets/av2/sensor/val/</Prefix>\
+        <Contents><Key>datasets/av2/sensor/val/log/annotations.feather</Key><Size>8</Size></Contents>\
+        <Contents><Key>datasets/av2/sensor/val/log/a&amp;b.feather</Key><Size>6</Size></Contents>\
+        <IsTruncated>false</IsTruncated></ListBucketResult>";
+
+    #[test]
+    fn test_parse_listing() {
+        let objects = parse_listing(LISTING).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[1].key, "datasets/av2/sensor/val/log/a&b.feather");
+        assert_eq!(objects[0].size, 8);
+        let object = RemoteObject {
+            key: "datasets/av2/../../etc/passwd".to_string(),
+            size: 0,
+        };
+        assert!(object.local_path(Path::new("/data")).is_err());
+    }
+
+    #[test]
+    fn test_selects() {
+        let cfg = DownloadConfig {
+            log_ids: Some(vec!["log".to_string()]),
+            modalities: Some(vec![DownloadModality::Lidar, DownloadModality::Annotations]),
+            ..Default::default()
+        };
+        assert!(cfg.selects(Path::new("log/sensors/lidar/0.feather")));
+        assert!(cfg.selects(Path::new("log/annotations.feather")));
+        assert!(!cfg.selects(Path::new("log/sensors/cameras/ring_front_center/0.jpg")));
+        assert!(!cfg.selects(Path::new("other/sensors/lidar/0.feather")));
+
+        // Maps of the forecasting scenarios.
+        let cfg = DownloadConfig {
+            dataset_type: "motion-forecasting".to_string(),
+            modalities: Some(vec![DownloadModality::Maps]),
+            ..Default::default()
+        };
+        assert!(cfg.selects(Path::new("id/log_map_archive_id.json")));
+        assert!(!cfg.selects(Path::new("id/scenario_id.parquet")));
+        assert_eq!(
+            DownloadModality::of(Path::new("scenario_id.parquet")),
+            Some(DownloadModality::Scenarios)
+        );
+    }
+
+    /// `(path, range)` of each served request.
+    type RequestLog = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+    /// Serve the listing and the objects, failing the first object request with `503` and
+    /// honoring range requests. Returns the bucket URL and the log of its requests.
+    fn _serve() -> (String, RequestLog) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/argoverse", listener.local_addr().unwrap());
+        let requests: RequestLog = Arc::new(Mutex::new(vec![]));
+        let log = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut lines = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    lines.push(line.trim().to_string());
+                }
+                let path = lines[0].split(' ').nth(1).unwrap().to_string();
+                let range = lines
+                    .iter()
+                    .find_map(|x| x.strip_prefix("range: ").or(x.strip_prefix("Range: ")))
+                    .map(|x| x.to_string());
+                let num_object_requests = log
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|(x, _)| !x.contains("list-type"))
+                    .count();
+                log.lock().unwrap().push((path.clone(), range.clone()));
+
+                let (status, body) = if path.contains("list-type=2") {
+                    ("200 OK", LISTING.as_bytes().to_vec())
+                } else if num_object_requests == 0 {
+                    ("503 Service Unavailable", vec![])
+                } else {
+                    let body = match path.ends_with("annotations.feather") {
+                        true => b"contents".to_vec(),
+                        false => b"resume".to_vec(),
+                    };
+                    match range.and_then(|x| {
+                        x.strip_prefix("bytes=")?
+                            .strip_suffix('-')?
+                            .parse::<usize>()
+                            .ok()
+                    }) {
+                        Some(offset) => ("206 Partial Content", body[offset..].to_vec()),
+                        None => ("200 OK", body),
+                    }
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                )
+                .unwrap();
+                stream.write_all(&body).unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    #[test]
+    fn test_download_dataset() {
+        let root_dir = test_dir();
+        let log_dir = root_dir.join("av2/sensor/val/log");
+        fs::create_dir_all(&log_dir).unwrap();
+        fs::write(log_dir.join("a&b.feather.part"), "res").unwrap();
+
+        let (bucket_url, requests) = _serve();
+        let cfg = DownloadConfig {
+            bucket_url,
+            num_threads: 1,
+            retry_delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+        let summary = download_dataset(&root_dir, &cfg).unwrap();
+        assert_eq!(summary.num_downloaded, 2);
+        assert_eq!(summary.num_bytes, 8 + 3);
+        let file_sizes = read_file_sizes(&root_dir.join("av2/sensor/val_file_sizes.txt")).unwrap();
+        assert_eq!(file_sizes[Path::new("log/annotations.feather")], 8);
+        assert!(
+            verify_split(&root_dir.join("av2/sensor/val"), &file_sizes, false)
+                .unwrap()
+                .is_ok()
+        );
+        assert_eq!(
+            fs::read_to_string(log_dir.join("annotations.feather")).unwrap(),
+            "contents"
+        );
+        assert_eq!(
+            fs::read_to_string(log_dir.join("a&b.feather")).unwrap(),
+            "resume"
+        );
+        // The partial file is resumed from its last byte.
+        assert!(requests.lock().unwrap().contains(&(
+            "/argoverse/datasets/av2/sensor/val/log/a%26b.feather".to_string(),
+            Some("bytes=3-".to_string())
+        )));
+
+        // Complete files are skipped.
+        let summary = download_dataset(&root_dir, &cfg).unwrap();
+        assert_eq!((summary.num_downloaded, summary.num_skipped), (0, 2));
+
+        // Only the annotations are downloaded (and recorded).
+        let cfg = DownloadConfig {
+            modalities: Some(vec![DownloadModality::Annotations]),
+            ..cfg
+        };
+        let subset_dir = root_dir.join("subset");
+        let summary = download_dataset(&subset_dir, &cfg).unwrap();
+        assert_eq!(summary.num_downloaded, 1);
+        let file_sizes =
+            read_file_sizes(&subset_dir.join("av2/sensor/val_file_sizes.txt")).unwrap();
+        assert_eq!(file_sizes.len(), 1);
+    }
+}
@@ -0,0 +1,169 @@
+//! # error
+//!
+//! Errors of the crate.
+//!
+//! `Av2Error` names the file (and, once wrapped by `in_log`, the log) at fault. Python bindings
+//! convert it into a matching exception (e.g., `FileNotFoundError` for a missing file) instead of
+//! panicking.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "dataframe")]
+use polars::prelude::PolarsError;
+use pyo3::{
+    exceptions::{PyFileNotFoundError, PyIOError, PyValueError},
+    PyErr,
+};
+use thiserror::Error;
+
+/// Result of the crate's fallible operations.
+pub type Av2Result<T> = Result<T, Av2Error>;
+
+/// Error of the crate.
+#[derive(Debug, Error)]
+pub enum Av2Error {
+    /// A file does not exist.
+    #[error("`{}` not found.", path.display())]
+    MissingFile {
+        /// Path of the file.
+        path: PathBuf,
+    },
+    /// A table lacks a column or a column has an unexpected type or nulls.
+    #[error("Schema mismatch of the {table}: {message}")]
+    Schema {
+        /// Name of the table (e.g., `cuboids`).
+        table: String,
+        /// Description of the mismatch.
+        message: String,
+    },
+    /// A parameter is outside of its domain.
+    #[error("Invalid `{name}`: {message}")]
+    InvalidParameter {
+        /// Name of the parameter.
+        name: String,
+        /// Description of the domain.
+        message: String,
+    },
+    /// A file cannot be decoded.
+    #[error("Failed to decode `{}`: {message}", path.display())]
+    Decode {
+        /// Path of the file.
+        path: PathBuf,
+        /// Description of the failure.
+        message: String,
+    },
+    /// Error of a `polars` operation.
+    #[cfg(feature = "dataframe")]
+    #[error(transparent)]
+    Polars(#[from] PolarsError),
+    /// I/O error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Error while loading the data of a log.
+    #[error("Log `{log_id}`: {source}")]
+    InLog {
+        /// Log id.
+        log_id: String,
+        /// Error within the log.
+        source: Box<Av2Error>,
+    },
+}
+
+impl Av2Error {
+    /// Schema mismatch of the `table`.
+    pub fn schema(table: &str, message: impl ToString) -> Self {
+        Self::Schema {
+            table: table.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Invalid value of the parameter `name`.
+    pub fn invalid_parameter(name: &str, message: impl ToString) -> Self {
+        Self::InvalidParameter {
+            name: name.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Failed decode of the file at `path`.
+    pub fn decode(path: &Path, message: impl ToString) -> Self {
+        Self::Decode {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Error of opening the file at `path`, which names the file if it does not exist.
+    pub fn open(path: &Path, err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => Self::MissingFile {
+                path: path.to_path_buf(),
+            },
+            _ => Self::Io(err),
+        }
+    }
+
+    /// Attribute the error to the log `log_id` (unless it already is).
+    pub fn in_log(self, log_id: &str) -> Self {
+        match self {
+            Self::InLog { .. } => self,
+            _ => Self::InLog {
+                log_id: log_id.to_string(),
+                source: Box::new(self),
+            },
+        }
+    }
+
+    /// Error without its log.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::InLog { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+}
+
+impl From<Av2Error> for PyErr {
+    fn from(err: Av2Error) -> Self {
+        let message = err.to_string();
+        match err.root() {
+            Av2Error::MissingFile { .. } => PyFileNotFoundError::new_err(message),
+            Av2Error::Schema { .. } | Av2Error::InvalidParameter { .. } => {
+                PyValueError::new_err(message)
+            }
+            _ => PyIOError::new_err(message),
+        }
+    }
+}
+
+/// Convert an error of a composite operation into a Python exception, which matches its
+/// `Av2Error` (if any).
+pub fn to_py_err(err: anyhow::Error) -> PyErr {
+    match err.downcast::<Av2Error>() {
+        Ok(err) => err.into(),
+        Err(err) => PyValueError::new_err(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Av2Error;
+
+    #[test]
+    fn test_in_log() {
+        let err = Av2Error::open(
+            Path::new("/data/log/city_SE3_egovehicle.feather"),
+            std::io::Error::from(std::io::ErrorKind::NotFound),
+        )
+        .in_log("log")
+        .in_log("other");
+        assert_eq!(
+            err.to_string(),
+            "Log `log`: `/data/log/city_SE3_egovehicle.feather` not found."
+        );
+        assert!(matches!(err.root(), Av2Error::MissingFile { .. }));
+    }
+}
@@ -0,0 +1,267 @@
+//! # bootstrap
+//!
+//! Bootstrapped confidence intervals for evaluation metrics.
+//!
+//! Samples are resampled at the group level (e.g., logs or scenarios) since frames within a
+//! group are strongly correlated.
+
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::error::{Av2Error, Av2Result};
+
+/// Metric name to metric value.
+pub type Metrics = BTreeMap<String, f32>;
+
+/// Default number of bootstrap replicates.
+pub const DEFAULT_NUM_REPLICATES: usize = 1000;
+
+/// Default confidence level of the reported intervals.
+pub const DEFAULT_CONFIDENCE_LEVEL: f32 = 0.95;
+
+/// Bootstrap configuration.
+#[derive(Clone, Debug)]
+pub struct BootstrapConfig {
+    /// Number of bootstrap replicates.
+    pub num_replicates: usize,
+    /// Confidence level of the reported intervals (e.g., 0.95).
+    pub confidence_level: f32,
+    /// Random seed. Each replicate draws from its own stream of the seed (see `replicate_rng`) so
+    /// results are reproducible.
+    pub seed: u64,
+}
+
+impl BootstrapConfig {
+    /// Check that there is at least one replicate and that the confidence level is in (0, 1).
+    pub fn validate(&self) -> Av2Result<()> {
+        if self.num_replicates == 0 {
+            return Err(Av2Error::invalid_parameter(
+                "num_replicates",
+                "At least one replicate is required.",
+            ));
+        }
+        if !(self.confidence_level > 0. && self.confidence_level < 1.) {
+            return Err(Av2Error::invalid_parameter(
+                "confidence_level",
+                format!("Expected a value in (0, 1), got {}.", self.confidence_level),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            num_replicates: DEFAULT_NUM_REPLICATES,
+            confidence_level: DEFAULT_CONFIDENCE_LEVEL,
+            seed: 0,
+        }
+    }
+}
+
+/// Point estimate of a metric with its percentile bootstrap confidence interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConfidenceInterval {
+    /// Metric evaluated on the original (non-resampled) groups.
+    pub estimate: f32,
+    /// Lower bound of the confidence interval.
+    pub lower: f32,
+    /// Upper bound of the confidence interval.
+    pub upper: f32,
+}
+
+/// Compute percentile bootstrap confidence intervals for every metric returned by `metric_fn`.
+///
+/// `groups` are resampled with replacement `num_replicates` times and `metric_fn` is evaluated on
+/// each replicate in parallel.
+pub fn bootstrap<T, F>(
+    groups: &[T],
+    metric_fn: F,
+    cfg: &BootstrapConfig,
+) -> Av2Result<BTreeMap<String, ConfidenceInterval>>
+where
+    T: Sync,
+    F: Fn(&[&T]) -> Metrics + Sync,
+{
+    let replicates = bootstrap_replicates(groups, &metric_fn, cfg)?;
+    let original = metric_fn(&groups.iter().collect::<Vec<_>>());
+
+    let alpha = (1. - cfg.confidence_level) / 2.;
+    Ok(original
+        .into_iter()
+        .map(|(name, estimate)| {
+            let mut values = replicates
+                .iter()
+                .filter_map(|metrics| metrics.get(&name).copied())
+                .filter(|x| x.is_finite())
+                .collect::<Vec<_>>();
+            values.sort_by(|a, b| a.total_cmp(b));
+            let interval = ConfidenceInterval {
+                estimate,
+                lower: percentile(&values, alpha),
+                upper: percentile(&values, 1. - alpha),
+            };
+            (name, interval)
+        })
+        .collect())
+}
+
+/// Evaluate `metric_fn` over `num_replicates` resamplings (with replacement) of `groups`.
+pub fn bootstrap_replicates<T, F>(
+    groups: &[T],
+    metric_fn: &F,
+    cfg: &BootstrapConfig,
+) -> Av2Result<Vec<Metrics>>
+where
+    T: Sync,
+    F: Fn(&[&T]) -> Metrics + Sync,
+{
+    cfg.validate()?;
+    let num_groups = groups.len();
+    Ok((0..cfg.num_replicates)
+        .into_par_iter()
+        .map(|i| {
+            if num_groups == 0 {
+                return metric_fn(&[]);
+            }
+            let mut rng = replicate_rng(cfg.seed, i);
+            let resampled = (0..num_groups)
+                .map(|_| &groups[rng.gen_range(0..num_groups)])
+                .collect::<Vec<_>>();
+            metric_fn(&resampled)
+        })
+        .collect())
+}
+
+/// Random number generator of replicate `i` of `seed`.
+///
+/// The generator is keyed by both the SplitMix64 hash of the seed and the replicate index, so
+/// the replicates of different seeds never share a stream (unlike seeding with `seed + i`).
+pub fn replicate_rng(seed: u64, i: usize) -> StdRng {
+    let mut key = [0_u8; 32];
+    key[..8].copy_from_slice(&_splitmix64(seed).to_le_bytes());
+    key[8..16].copy_from_slice(&(i as u64).to_le_bytes());
+    StdRng::from_seed(key)
+}
+
+/// SplitMix64 finalizer of `x`.
+fn _splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Linearly interpolated percentile `q` in `[0, 1]` of sorted `values`.
+pub fn percentile(sorted_values: &[f32], q: f32) -> f32 {
+    match sorted_values.len() {
+        0 => f32::NAN,
+        1 => sorted_values[0],
+        n => {
+            let position = q.clamp(0., 1.) * (n - 1) as f32;
+            let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+            let t = position - lower as f32;
+            sorted_values[lower] * (1. - t) + sorted_values[upper] * t
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::{Distribution, Normal};
+
+    use super::{bootstrap, percentile, replicate_rng, BootstrapConfig, Metrics};
+    use crate::error::Av2Error;
+
+    fn mean_metric(groups: &[&f32]) -> Metrics {
+        let mean = groups.iter().copied().sum::<f32>() / groups.len().max(1) as f32;
+        Metrics::from([("mean".to_string(), mean)])
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let groups = (0..20).map(|x| x as f32).collect::<Vec<_>>();
+        let cfg = BootstrapConfig {
+            num_replicates: 200,
+            ..Default::default()
+        };
+        let intervals = bootstrap(&groups, mean_metric, &cfg).unwrap();
+        let interval = intervals["mean"];
+        assert_eq!(interval.estimate, 9.5);
+        assert!(interval.lower < interval.estimate && interval.estimate < interval.upper);
+
+        // A fixed seed reproduces the intervals, while another seed does not.
+        assert_eq!(bootstrap(&groups, mean_metric, &cfg).unwrap(), intervals);
+        let other_cfg = BootstrapConfig { seed: 1, ..cfg };
+        assert_ne!(
+            bootstrap(&groups, mean_metric, &other_cfg).unwrap()["mean"],
+            interval
+        );
+
+        assert_eq!(percentile(&[0., 1., 2., 3.], 0.5), 1.5);
+        assert!(percentile(&[], 0.5).is_nan());
+    }
+
+    #[test]
+    fn test_bootstrap_coverage() {
+        // The 90% intervals of the mean of 50 normal samples cover the true mean about 90% of the
+        // time.
+        let normal = Normal::new(1_f32, 2.).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let num_trials = 200;
+        let num_covered = (0..num_trials)
+            .filter(|&trial| {
+                let groups = (0..50).map(|_| normal.sample(&mut rng)).collect::<Vec<_>>();
+                let cfg = BootstrapConfig {
+                    num_replicates: 200,
+                    confidence_level: 0.9,
+                    seed: trial * 1000,
+                };
+                let interval = bootstrap(&groups, mean_metric, &cfg).unwrap()["mean"];
+                interval.lower <= 1. && 1. <= interval.upper
+            })
+            .count();
+        let coverage = num_covered as f32 / num_trials as f32;
+        assert!((0.8..=0.97).contains(&coverage), "coverage: {coverage}");
+    }
+
+    #[test]
+    fn test_replicate_rng() {
+        // Adjacent seeds do not share the streams of shifted replicates.
+        let draw = |seed, i| replicate_rng(seed, i).gen::<u64>();
+        assert_eq!(draw(0, 1), draw(0, 1));
+        assert_ne!(draw(0, 1), draw(1, 0));
+        assert_ne!(draw(0, 2), draw(1, 1));
+        assert_ne!(draw(0, 0), draw(0, 1));
+    }
+
+    #[test]
+    fn test_bootstrap_invalid_config() {
+        let groups = [1_f32, 2.];
+        for cfg in [
+            BootstrapConfig {
+                num_replicates: 0,
+                ..Default::default()
+            },
+            BootstrapConfig {
+                confidence_level: 1.,
+                ..Default::default()
+            },
+            BootstrapConfig {
+                confidence_level: 0.,
+                ..Default::default()
+            },
+            BootstrapConfig {
+                confidence_level: f32::NAN,
+                ..Default::default()
+            },
+        ] {
+            let err = bootstrap(&groups, mean_metric, &cfg).unwrap_err();
+            assert!(matches!(err, Av2Error::InvalidParameter { .. }), "{cfg:?}");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! # breakdown
+//!
+//! Range buckets and splits of evaluation breakdown tables.
+//!
+//! Breakdown tables are tidy: one row per `split`, `category`, and `range_m` bucket, followed by
+//! the metric columns of the evaluation, so they can be written straight to parquet and joined
+//! across runs.
+
+use anyhow::Result;
+use polars::prelude::*;
+
+/// Edges (in meters) of the default range buckets (i.e., `0-50`, `50-100`, and `100-150`).
+pub const DEFAULT_RANGE_BUCKETS_M: [f32; 4] = [0.0, 50.0, 100.0, 150.0];
+
+/// Key columns of a breakdown table, in order.
+pub const BREAKDOWN_COLUMNS: [&str; 3] = ["split", "category", "range_m"];
+
+/// Labels (e.g., `0-50`) of the buckets between consecutive `edges`.
+pub fn range_bucket_labels(edges: &[f32]) -> Vec<String> {
+    edges
+        .windows(2)
+        .map(|edge| format!("{}-{}", edge[0], edge[1]))
+        .collect()
+}
+
+/// Index of the bucket of `range_m` between consecutive `edges`, if any. Buckets include their
+/// lower edge and exclude their upper edge.
+pub fn range_bucket(range_m: f32, edges: &[f32]) -> Option<usize> {
+    edges
+        .windows(2)
+        .position(|edge| (edge[0]..edge[1]).contains(&range_m))
+}
+
+/// `split` of each row, or `None` for every row if the table has no `split` column.
+pub fn splits(data_frame: &DataFrame) -> Result<Vec<Option<String>>> {
+    match data_frame.column("split") {
+        Ok(split) => Ok(split
+            .cast(&DataType::String)?
+            .str()?
+            .into_iter()
+            .map(|x| x.map(String::from))
+            .collect()),
+        Err(_) => Ok(vec![None; data_frame.height()]),
+    }
+}
+
+/// Distinct splits of the rows of several tables in first-seen order.
+pub fn unique_splits(splits: &[&[Option<String>]]) -> Vec<Option<String>> {
+    let mut unique = vec![];
+    for split in splits.iter().flat_map(|x| x.iter()) {
+        if !unique.contains(split) {
+            unique.push(split.clone());
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{range_bucket, range_bucket_labels, DEFAULT_RANGE_BUCKETS_M};
+
+    #[test]
+    fn test_range_buckets() {
+        assert_eq!(
+            range_bucket_labels(&DEFAULT_RANGE_BUCKETS_M),
+            vec!["0-50", "50-100", "100-150"]
+        );
+        assert_eq!(range_bucket(0., &DEFAULT_RANGE_BUCKETS_M), Some(0));
+        assert_eq!(range_bucket(50., &DEFAULT_RANGE_BUCKETS_M), Some(1));
+        assert_eq!(range_bucket(150., &DEFAULT_RANGE_BUCKETS_M), None);
+    }
+}
@@ -0,0 +1,941 @@
+//! # detection
+//!
+//! 3D object detection evaluation (AP, ATE, ASE, AOE, and CDS).
+//!
+//! Port of `av2.evaluation.detection`. Within each sweep and category, detections are visited in
+//! descending score order and assigned to their highest affinity annotation; only the first
+//! detection assigned to an annotation can be a true positive. Average precision is averaged over
+//! the affinity thresholds, and the true positive errors are averaged over the true positives at
+//! `tp_threshold`. Map region of interest pruning is not applied (i.e., the equivalent of
+//! `eval_only_roi_instances=False`).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    f32::consts::PI,
+};
+
+use anyhow::{ensure, Context, Result};
+use ndarray::{Array, ArrayView, Ix1, Ix2};
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use strum_macros::{Display, EnumString};
+
+use crate::{
+    geometry::{
+        iou::{cuboid_iou, IouMode},
+        so3::_quat_to_yaw,
+    },
+    io::ndarray_from_frame,
+    structures::cuboid::CUBOID_COLUMNS,
+};
+
+use super::{
+    bootstrap::{bootstrap, BootstrapConfig, ConfidenceInterval, Metrics},
+    breakdown::{
+        range_bucket, range_bucket_labels, splits, unique_splits, DEFAULT_RANGE_BUCKETS_M,
+    },
+};
+
+/// Number of recall points sampled uniformly in `[0, 1]`.
+pub const NUM_RECALL_SAMPLES: usize = 101;
+
+/// Center distance thresholds (in meters) of the average precision.
+pub const DEFAULT_AFFINITY_THRESHOLDS_M: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+/// Center distance threshold (in meters) of the true positive errors.
+pub const DEFAULT_TP_THRESHOLD_M: f32 = 2.0;
+
+/// Maximum distance (in meters) of an evaluated cuboid from the egovehicle.
+pub const DEFAULT_MAX_RANGE_M: f32 = 150.0;
+
+/// Maximum number of evaluated detections per category per sweep.
+pub const DEFAULT_MAX_NUM_DTS_PER_CATEGORY: usize = 100;
+
+/// Scale error of a detection without a true positive match.
+pub const MAX_SCALE_ERROR: f32 = 1.0;
+
+/// Orientation error (in radians) of a detection without a true positive match.
+pub const MAX_YAW_RAD_ERROR: f32 = PI;
+
+/// True positive error columns.
+pub const TP_ERROR_COLUMNS: [&str; 3] = ["ATE", "ASE", "AOE"];
+
+/// Summary metric columns.
+pub const SUMMARY_COLUMNS: [&str; 5] = ["AP", "ATE", "ASE", "AOE", "CDS"];
+
+/// Name of the summary row averaged over the categories.
+pub const AVERAGE_METRICS: &str = "AVERAGE_METRICS";
+
+/// Categories of the sensor dataset detection competition.
+pub const COMPETITION_CATEGORIES: [&str; 26] = [
+    "ARTICULATED_BUS",
+    "BICYCLE",
+    "BICYCLIST",
+    "BOLLARD",
+    "BOX_TRUCK",
+    "BUS",
+    "CONSTRUCTION_BARREL",
+    "CONSTRUCTION_CONE",
+    "DOG",
+    "LARGE_VEHICLE",
+    "MESSAGE_BOARD_TRAILER",
+    "MOBILE_PEDESTRIAN_CROSSING_SIGN",
+    "MOTORCYCLE",
+    "MOTORCYCLIST",
+    "PEDESTRIAN",
+    "REGULAR_VEHICLE",
+    "SCHOOL_BUS",
+    "SIGN",
+    "STOP_SIGN",
+    "STROLLER",
+    "TRUCK",
+    "TRUCK_CAB",
+    "VEHICULAR_TRAILER",
+    "WHEELCHAIR",
+    "WHEELED_DEVICE",
+    "WHEELED_RIDER",
+];
+
+/// Affinity between detections and annotations.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+pub enum Affinity {
+    /// Negative 3D center distance. A detection matches at threshold `t` if it is closer than `t`
+    /// meters.
+    #[default]
+    #[strum(serialize = "center")]
+    Center,
+    /// Bird's-eye-view IoU. A detection matches at threshold `t` if its IoU is at least `t`.
+    #[strum(serialize = "iou_bev")]
+    IouBev,
+    /// Volumetric IoU. A detection matches at threshold `t` if its IoU is at least `t`.
+    #[strum(serialize = "iou_3d")]
+    Iou3d,
+}
+
+impl Affinity {
+    /// (N,M) affinities between (N,10) detections and (M,10) annotations.
+    fn matrix(&self, dts: &ArrayView<f32, Ix2>, gts: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+        match self {
+            Affinity::Center => Array::from_shape_fn((dts.nrows(), gts.nrows()), |(i, j)| {
+                -_l2_distance(&dts.row(i), &gts.row(j))
+            }),
+            Affinity::IouBev => cuboid_iou(dts, gts, IouMode::Bev),
+            Affinity::Iou3d => cuboid_iou(dts, gts, IouMode::ThreeD),
+        }
+    }
+
+    /// Whether `affinity` matches at `threshold`.
+    fn is_match(&self, affinity: f32, threshold: f32) -> bool {
+        match self {
+            Affinity::Center => affinity > -threshold,
+            Affinity::IouBev | Affinity::Iou3d => affinity >= threshold,
+        }
+    }
+}
+
+/// Detection evaluation configuration.
+#[derive(Clone, Debug)]
+pub struct DetectionConfig {
+    /// Affinity between detections and annotations.
+    pub affinity: Affinity,
+    /// Affinity thresholds of the average precision.
+    pub affinity_thresholds: Vec<f32>,
+    /// Affinity threshold of the true positive errors (one of the `affinity_thresholds`).
+    pub tp_threshold: f32,
+    /// Translation error (in meters) of a detection without a true positive match, which also
+    /// normalizes the ATE.
+    pub max_translation_error_m: f32,
+    /// Evaluated categories.
+    pub categories: Vec<String>,
+    /// Maximum number of evaluated detections per category per sweep.
+    pub max_num_dts_per_category: usize,
+    /// Maximum distance (in meters) of an evaluated cuboid from the egovehicle.
+    pub max_range_m: f32,
+    /// Number of recall points sampled uniformly in `[0, 1]`.
+    pub num_recall_samples: usize,
+    /// Edges (in meters) of the range buckets of the breakdown.
+    pub range_buckets_m: Vec<f32>,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            affinity: Affinity::default(),
+            affinity_thresholds: DEFAULT_AFFINITY_THRESHOLDS_M.to_vec(),
+            tp_threshold: DEFAULT_TP_THRESHOLD_M,
+            max_translation_error_m: DEFAULT_TP_THRESHOLD_M,
+            categories: COMPETITION_CATEGORIES.map(String::from).to_vec(),
+            max_num_dts_per_category: DEFAULT_MAX_NUM_DTS_PER_CATEGORY,
+            max_range_m: DEFAULT_MAX_RANGE_M,
+            num_recall_samples: NUM_RECALL_SAMPLES,
+            range_buckets_m: DEFAULT_RANGE_BUCKETS_M.to_vec(),
+        }
+    }
+}
+
+impl DetectionConfig {
+    /// Normalization terms of the ATE, ASE, and AOE.
+    pub fn tp_normalization_terms(&self) -> [f32; 3] {
+        [
+            self.max_translation_error_m,
+            MAX_SCALE_ERROR,
+            MAX_YAW_RAD_ERROR,
+        ]
+    }
+
+    /// Column name of the true positive flags at `threshold` (e.g., `0.5`).
+    pub fn threshold_column(threshold: f32) -> String {
+        format!("{threshold:?}")
+    }
+}
+
+/// Evaluated detections and annotations with their summary metrics.
+#[derive(Clone, Debug)]
+pub struct DetectionEvaluation {
+    /// Detections with a true positive flag per affinity threshold, the `TP_ERROR_COLUMNS`, and
+    /// `is_evaluated`.
+    pub detections: DataFrame,
+    /// Annotations with `is_evaluated`.
+    pub annotations: DataFrame,
+    /// `category` and the `SUMMARY_COLUMNS` of each category followed by their average
+    /// (`AVERAGE_METRICS`).
+    pub summary: DataFrame,
+    /// `split`, `category`, `range_m`, `num_dts`, `num_gts`, and the `SUMMARY_COLUMNS` of each
+    /// split, category, and range bucket with any evaluated detections or annotations.
+    pub breakdown: DataFrame,
+}
+
+impl DetectionEvaluation {
+    /// Average summary metrics keyed by name (e.g., `AP`).
+    pub fn as_metrics(&self) -> Metrics {
+        let row = self.summary.height() - 1;
+        SUMMARY_COLUMNS
+            .iter()
+            .map(|name| {
+                let value = self.summary[*name].f32().unwrap().get(row).unwrap();
+                (name.to_string(), value)
+            })
+            .collect()
+    }
+}
+
+/// `(log_id, timestamp_ns, category)` of a detection or annotation.
+type SweepCategoryKey = (String, u64, String);
+
+/// Per-row assignment results of the detections of a sweep and category.
+struct _DetectionAssignment {
+    rows: Vec<usize>,
+    is_evaluated: Vec<bool>,
+    /// (N,T) true positive flags.
+    is_tp: Vec<Vec<bool>>,
+    /// (N,3) ATE, ASE, and AOE.
+    tp_errors: Vec<[f32; 3]>,
+}
+
+/// Evaluate `detections` against `annotations`.
+///
+/// Both tables hold `log_id`, `timestamp_ns`, `category`, and the `CUBOID_COLUMNS` in the
+/// egovehicle frame. The detections also hold a `score` and the annotations `num_interior_pts`, and
+/// both may hold a `split` to break the results down by.
+pub fn evaluate(
+    detections: &DataFrame,
+    annotations: &DataFrame,
+    cfg: &DetectionConfig,
+) -> Result<DetectionEvaluation> {
+    ensure!(
+        cfg.affinity_thresholds.contains(&cfg.tp_threshold),
+        "The true positive threshold must be one of the affinity thresholds."
+    );
+    let dts_keys = _sweep_category_keys(detections)?;
+    let gts_keys = _sweep_category_keys(annotations)?;
+    let dts_params = ndarray_from_frame(detections, cols(CUBOID_COLUMNS))?;
+    let gts_params = ndarray_from_frame(annotations, cols(CUBOID_COLUMNS))?;
+    let scores = _f32_values(detections, "score")?;
+    let num_interior_pts = _f32_values(annotations, "num_interior_pts")?;
+
+    // Group the rows by sweep and category.
+    let mut groups: HashMap<&SweepCategoryKey, (Vec<usize>, Vec<usize>)> = HashMap::new();
+    for (i, key) in dts_keys.iter().enumerate() {
+        groups.entry(key).or_default().0.push(i);
+    }
+    for (j, key) in gts_keys.iter().enumerate() {
+        groups.entry(key).or_default().1.push(j);
+    }
+
+    let assignments = groups
+        .par_iter()
+        .map(|(_, (dts_rows, gts_rows))| {
+            let gts_is_evaluated = gts_rows
+                .iter()
+                .map(|&j| {
+                    _l2_norm(&gts_params.row(j)) < cfg.max_range_m && num_interior_pts[j] > 0.
+                })
+                .collect::<Vec<_>>();
+            let assignment = _assign_sweep(
+                dts_rows,
+                gts_rows,
+                &gts_is_evaluated,
+                &dts_params.view(),
+                &gts_params.view(),
+                &scores,
+                cfg,
+            );
+            (assignment, gts_rows, gts_is_evaluated)
+        })
+        .collect::<Vec<_>>();
+
+    let num_thresholds = cfg.affinity_thresholds.len();
+    let default_errors = [
+        cfg.max_translation_error_m,
+        MAX_SCALE_ERROR,
+        MAX_YAW_RAD_ERROR,
+    ];
+    let mut dts_is_evaluated = vec![false; detections.height()];
+    let mut dts_is_tp = vec![vec![false; num_thresholds]; detections.height()];
+    let mut dts_tp_errors = vec![default_errors; detections.height()];
+    let mut gts_is_evaluated = vec![false; annotations.height()];
+    for (assignment, gts_rows, is_evaluated) in assignments {
+        for (k, &i) in assignment.rows.iter().enumerate() {
+            dts_is_evaluated[i] = assignment.is_evaluated[k];
+            dts_is_tp[i] = assignment.is_tp[k].clone();
+            dts_tp_errors[i] = assignment.tp_errors[k];
+        }
+        for (&j, is_evaluated) in gts_rows.iter().zip(is_evaluated) {
+            gts_is_evaluated[j] = is_evaluated;
+        }
+    }
+
+    let mut evaluated_detections = detections.clone();
+    for (t, threshold) in cfg.affinity_thresholds.iter().enumerate() {
+        let is_tp = dts_is_tp.iter().map(|x| x[t]).collect::<Vec<_>>();
+        let name = DetectionConfig::threshold_column(*threshold);
+        evaluated_detections.with_column(Series::new(&name, is_tp))?;
+    }
+    for (k, name) in TP_ERROR_COLUMNS.iter().enumerate() {
+        let errors = dts_tp_errors.iter().map(|x| x[k]).collect::<Vec<_>>();
+        evaluated_detections.with_column(Series::new(name, errors))?;
+    }
+    evaluated_detections.with_column(Series::new("is_evaluated", &dts_is_evaluated))?;
+    let mut evaluated_annotations = annotations.clone();
+    evaluated_annotations.with_column(Series::new("is_evaluated", &gts_is_evaluated))?;
+
+    // Summarize each category.
+    let category_summaries = cfg
+        .categories
+        .iter()
+        .map(|category| {
+            let mut category_dts = (0..detections.height())
+                .filter(|&i| dts_is_evaluated[i] && dts_keys[i].2 == *category)
+                .collect::<Vec<_>>();
+            category_dts.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+            let num_gts = (0..annotations.height())
+                .filter(|&j| gts_is_evaluated[j] && gts_keys[j].2 == *category)
+                .count();
+            summarize_category(&category_dts, &dts_is_tp, &dts_tp_errors, num_gts, cfg)
+        })
+        .collect::<Vec<_>>();
+
+    let mut categories = cfg.categories.clone();
+    categories.push(AVERAGE_METRICS.to_string());
+    let mut columns = vec![Series::new("category", categories)];
+    for (k, name) in SUMMARY_COLUMNS.iter().enumerate() {
+        let mut values = category_summaries.iter().map(|x| x[k]).collect::<Vec<_>>();
+        values.push(values.iter().sum::<f32>() / values.len().max(1) as f32);
+        columns.push(Series::new(name, values));
+    }
+    let breakdown = _breakdown(
+        detections,
+        annotations,
+        &dts_keys,
+        &gts_keys,
+        (&dts_params, &gts_params),
+        (&dts_is_evaluated, &gts_is_evaluated),
+        (&dts_is_tp, &dts_tp_errors),
+        &scores,
+        cfg,
+    )?;
+    Ok(DetectionEvaluation {
+        detections: evaluated_detections,
+        annotations: evaluated_annotations,
+        summary: DataFrame::new(columns)?,
+        breakdown,
+    })
+}
+
+/// Compute bootstrapped confidence intervals of the average summary metrics (e.g., `AP`).
+/// Logs are resampled with replacement, and the detections are assigned once (assignments never
+/// cross logs).
+pub fn evaluate_bootstrap(
+    detections: &DataFrame,
+    annotations: &DataFrame,
+    cfg: &DetectionConfig,
+    bootstrap_cfg: &BootstrapConfig,
+) -> Result<BTreeMap<String, ConfidenceInterval>> {
+    let evaluation = evaluate(detections, annotations, cfg)?;
+    let (dts, gts) = (&evaluation.detections, &evaluation.annotations);
+    let dts_keys = _sweep_category_keys(dts)?;
+    let gts_keys = _sweep_category_keys(gts)?;
+    let scores = _f32_values(dts, "score")?;
+    let dts_is_evaluated = dts.column("is_evaluated")?.bool()?;
+    let gts_is_evaluated = gts.column("is_evaluated")?.bool()?;
+    let is_tp_columns = cfg
+        .affinity_thresholds
+        .iter()
+        .map(|x| Ok(dts.column(&DetectionConfig::threshold_column(*x))?.bool()?))
+        .collect::<Result<Vec<_>>>()?;
+    let dts_is_tp = (0..dts.height())
+        .map(|i| {
+            is_tp_columns
+                .iter()
+                .map(|x| x.get(i).unwrap_or(false))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let tp_errors = TP_ERROR_COLUMNS
+        .iter()
+        .map(|x| _f32_values(dts, x))
+        .collect::<Result<Vec<_>>>()?;
+    let dts_tp_errors = (0..dts.height())
+        .map(|i| [tp_errors[0][i], tp_errors[1][i], tp_errors[2][i]])
+        .collect::<Vec<_>>();
+
+    // Evaluated detection rows and number of evaluated annotations of each log and category.
+    let category_index = cfg
+        .categories
+        .iter()
+        .enumerate()
+        .map(|(c, category)| (category.as_str(), c))
+        .collect::<HashMap<_, _>>();
+    let num_categories = cfg.categories.len();
+    let mut logs: BTreeMap<&str, (Vec<Vec<usize>>, Vec<usize>)> = BTreeMap::new();
+    for (i, (log_id, _, category)) in dts_keys.iter().enumerate() {
+        let entry = logs
+            .entry(log_id)
+            .or_insert_with(|| (vec![vec![]; num_categories], vec![0; num_categories]));
+        if let (Some(&c), Some(true)) = (
+            category_index.get(category.as_str()),
+            dts_is_evaluated.get(i),
+        ) {
+            entry.0[c].push(i);
+        }
+    }
+    for (j, (log_id, _, category)) in gts_keys.iter().enumerate() {
+        let entry = logs
+            .entry(log_id)
+            .or_insert_with(|| (vec![vec![]; num_categories], vec![0; num_categories]));
+        if let (Some(&c), Some(true)) = (
+            category_index.get(category.as_str()),
+            gts_is_evaluated.get(j),
+        ) {
+            entry.1[c] += 1;
+        }
+    }
+    let logs = logs.into_values().collect::<Vec<_>>();
+
+    let metric_fn = |logs: &[&(Vec<Vec<usize>>, Vec<usize>)]| {
+        let summaries = (0..num_categories)
+            .map(|c| {
+                let mut category_dts = logs
+                    .iter()
+                    .flat_map(|x| x.0[c].iter().copied())
+                    .collect::<Vec<_>>();
+                category_dts.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+                let num_gts = logs.iter().map(|x| x.1[c]).sum();
+                summarize_category(&category_dts, &dts_is_tp, &dts_tp_errors, num_gts, cfg)
+            })
+            .collect::<Vec<_>>();
+        SUMMARY_COLUMNS
+            .iter()
+            .enumerate()
+            .map(|(k, name)| {
+                let total = summaries.iter().map(|x| x[k]).sum::<f32>();
+                (name.to_string(), total / summaries.len().max(1) as f32)
+            })
+            .collect::<Metrics>()
+    };
+    Ok(bootstrap(&logs, metric_fn, bootstrap_cfg)?)
+}
+
+/// Summary metrics of each split, category, and range bucket. A detection falls in the bucket of
+/// its own range, so detections and annotations may match across bucket edges.
+#[allow(clippy::too_many_arguments)]
+fn _breakdown(
+    detections: &DataFrame,
+    annotations: &DataFrame,
+    dts_keys: &[SweepCategoryKey],
+    gts_keys: &[SweepCategoryKey],
+    (dts_params, gts_params): (&Array<f32, Ix2>, &Array<f32, Ix2>),
+    (dts_is_evaluated, gts_is_evaluated): (&[bool], &[bool]),
+    (dts_is_tp, dts_tp_errors): (&[Vec<bool>], &[[f32; 3]]),
+    scores: &[f32],
+    cfg: &DetectionConfig,
+) -> Result<DataFrame> {
+    let (dts_splits, gts_splits) = (splits(detections)?, splits(annotations)?);
+    let dts_buckets = dts_params
+        .rows()
+        .into_iter()
+        .map(|row| range_bucket(_l2_norm(&row), &cfg.range_buckets_m))
+        .collect::<Vec<_>>();
+    let gts_buckets = gts_params
+        .rows()
+        .into_iter()
+        .map(|row| range_bucket(_l2_norm(&row), &cfg.range_buckets_m))
+        .collect::<Vec<_>>();
+    let range_labels = range_bucket_labels(&cfg.range_buckets_m);
+
+    let (mut split_column, mut category_column, mut range_column) = (vec![], vec![], vec![]);
+    let (mut num_dts_column, mut num_gts_column) = (vec![], vec![]);
+    let mut values = vec![vec![]; SUMMARY_COLUMNS.len()];
+    for split in unique_splits(&[&dts_splits, &gts_splits]) {
+        for category in &cfg.categories {
+            for (bucket, range_label) in range_labels.iter().enumerate() {
+                let mut bucket_dts = (0..detections.height())
+                    .filter(|&i| {
+                        dts_is_evaluated[i]
+                            && dts_keys[i].2 == *category
+                            && dts_splits[i] == split
+                            && dts_buckets[i] == Some(bucket)
+                    })
+                    .collect::<Vec<_>>();
+                bucket_dts.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+                let num_gts = (0..annotations.height())
+                    .filter(|&j| {
+                        gts_is_evaluated[j]
+                            && gts_keys[j].2 == *category
+                            && gts_splits[j] == split
+                            && gts_buckets[j] == Some(bucket)
+                    })
+                    .count();
+                if bucket_dts.is_empty() && num_gts == 0 {
+                    continue;
+                }
+                let summary =
+                    summarize_category(&bucket_dts, dts_is_tp, dts_tp_errors, num_gts, cfg);
+                split_column.push(split.clone());
+                category_column.push(category.clone());
+                range_column.push(range_label.clone());
+                num_dts_column.push(bucket_dts.len() as u32);
+                num_gts_column.push(num_gts as u32);
+                for (k, value) in summary.into_iter().enumerate() {
+                    values[k].push(value);
+                }
+            }
+        }
+    }
+    let mut columns = vec![
+        Series::new("split", split_column),
+        Series::new("category", category_column),
+        Series::new("range_m", range_column),
+        Series::new("num_dts", num_dts_column),
+        Series::new("num_gts", num_gts_column),
+    ];
+    columns.extend(
+        SUMMARY_COLUMNS
+            .iter()
+            .zip(values)
+            .map(|(name, values)| Series::new(name, values)),
+    );
+    Ok(DataFrame::new(columns)?)
+}
+
+/// AP, ATE, ASE, AOE, and CDS of a category given its evaluated detections `category_dts` in
+/// descending score order and its number of evaluated annotations.
+fn summarize_category(
+    category_dts: &[usize],
+    dts_is_tp: &[Vec<bool>],
+    dts_tp_errors: &[[f32; 3]],
+    num_gts: usize,
+    cfg: &DetectionConfig,
+) -> [f32; 5] {
+    let normalization_terms = cfg.tp_normalization_terms();
+    if num_gts == 0 {
+        return [
+            0.,
+            normalization_terms[0],
+            normalization_terms[1],
+            normalization_terms[2],
+            0.,
+        ];
+    }
+    let recall_interpolated = (0..cfg.num_recall_samples)
+        .map(|k| k as f64 / (cfg.num_recall_samples.max(2) - 1) as f64)
+        .collect::<Vec<_>>();
+    let average_precisions = (0..cfg.affinity_thresholds.len())
+        .map(|t| {
+            let is_tp = category_dts
+                .iter()
+                .map(|&i| dts_is_tp[i][t])
+                .collect::<Vec<_>>();
+            match is_tp.is_empty() {
+                true => 0.,
+                false => compute_average_precision(&is_tp, &recall_interpolated, num_gts),
+            }
+        })
+        .collect::<Vec<_>>();
+    let mean_average_precision =
+        average_precisions.iter().sum::<f64>() / average_precisions.len() as f64;
+
+    // The errors are averaged over the true positives at the middle threshold.
+    let middle = cfg.affinity_thresholds.len() / 2;
+    let tp_errors = category_dts
+        .iter()
+        .filter(|&&i| dts_is_tp[i][middle])
+        .map(|&i| dts_tp_errors[i])
+        .collect::<Vec<_>>();
+    let mean_tp_errors = match tp_errors.is_empty() {
+        true => normalization_terms,
+        false => [0, 1, 2].map(|k| {
+            tp_errors.iter().map(|x| x[k] as f64).sum::<f64>() as f32 / tp_errors.len() as f32
+        }),
+    };
+    let mean_tp_score = (0..3)
+        .map(|k| 1. - mean_tp_errors[k] / normalization_terms[k])
+        .sum::<f32>()
+        / 3.;
+    let cds = mean_average_precision as f32 * mean_tp_score;
+    [
+        mean_average_precision as f32,
+        mean_tp_errors[0],
+        mean_tp_errors[1],
+        mean_tp_errors[2],
+        cds,
+    ]
+}
+
+/// VOC-style average precision of true positive flags ranked by confidence, with the precision
+/// interpolated at the `recall_interpolated` samples.
+pub fn compute_average_precision(
+    is_tp: &[bool],
+    recall_interpolated: &[f64],
+    num_gts: usize,
+) -> f64 {
+    let (mut recall, mut precision) = (vec![], vec![]);
+    let mut cum_tps = 0;
+    for (k, is_tp) in is_tp.iter().enumerate() {
+        cum_tps += *is_tp as usize;
+        let cum_fps = k + 1 - cum_tps;
+        precision.push(cum_tps as f64 / (cum_tps as f64 + cum_fps as f64 + f64::EPSILON));
+        recall.push(cum_tps as f64 / num_gts as f64);
+    }
+    // Precision at a recall is the maximum precision at any higher recall.
+    for k in (0..precision.len().saturating_sub(1)).rev() {
+        precision[k] = precision[k].max(precision[k + 1]);
+    }
+    let precision_interpolated = recall_interpolated
+        .iter()
+        .map(|&r| _interp(r, &recall, &precision))
+        .collect::<Vec<_>>();
+    precision_interpolated.iter().sum::<f64>() / precision_interpolated.len().max(1) as f64
+}
+
+/// Piecewise-linear interpolation of `(xp, fp)` at `x` (as `numpy.interp` with `right=0`).
+fn _interp(x: f64, xp: &[f64], fp: &[f64]) -> f64 {
+    let last = xp.len() - 1;
+    if x < xp[0] {
+        return fp[0];
+    }
+    if x > xp[last] {
+        return 0.;
+    }
+    if x == xp[last] {
+        return fp[last];
+    }
+    let j = xp.partition_point(|&v| v <= x) - 1;
+    let slope = (fp[j + 1] - fp[j]) / (xp[j + 1] - xp[j]);
+    fp[j] + slope * (x - xp[j])
+}
+
+/// Assign the detections of a sweep and category to its annotations.
+fn _assign_sweep(
+    dts_rows: &[usize],
+    gts_rows: &[usize],
+    gts_is_evaluated: &[bool],
+    dts_params: &ArrayView<f32, Ix2>,
+    gts_params: &ArrayView<f32, Ix2>,
+    scores: &[f32],
+    cfg: &DetectionConfig,
+) -> _DetectionAssignment {
+    let num_thresholds = cfg.affinity_thresholds.len();
+    let default_errors = [
+        cfg.max_translation_error_m,
+        MAX_SCALE_ERROR,
+        MAX_YAW_RAD_ERROR,
+    ];
+
+    // Visit the detections in descending score order.
+    let mut rows = dts_rows.to_vec();
+    rows.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    let mut num_evaluated = 0;
+    let is_evaluated = rows
+        .iter()
+        .map(|&i| {
+            let is_in_range = _l2_norm(&dts_params.row(i)) < cfg.max_range_m;
+            num_evaluated += is_in_range as usize;
+            is_in_range && num_evaluated <= cfg.max_num_dts_per_category
+        })
+        .collect::<Vec<_>>();
+    let mut is_tp = vec![vec![false; num_thresholds]; rows.len()];
+    let mut tp_errors = vec![default_errors; rows.len()];
+
+    let evaluated_dts = (0..rows.len())
+        .filter(|&k| is_evaluated[k])
+        .collect::<Vec<_>>();
+    let evaluated_gts = (0..gts_rows.len())
+        .filter(|&k| gts_is_evaluated[k])
+        .map(|k| gts_rows[k])
+        .collect::<Vec<_>>();
+    if !evaluated_dts.is_empty() && !evaluated_gts.is_empty() {
+        let dts = dts_params.select(
+            ndarray::Axis(0),
+            &evaluated_dts.iter().map(|&k| rows[k]).collect::<Vec<_>>(),
+        );
+        let gts = gts_params.select(ndarray::Axis(0), &evaluated_gts);
+        let affinities = cfg.affinity.matrix(&dts.view(), &gts.view());
+
+        // Only the first (highest scoring) detection assigned to an annotation can match it.
+        let mut is_assigned = vec![false; gts.nrows()];
+        for (n, &k) in evaluated_dts.iter().enumerate() {
+            let (m, affinity) = _argmax(&affinities.row(n));
+            if std::mem::replace(&mut is_assigned[m], true) {
+                continue;
+            }
+            for (t, threshold) in cfg.affinity_thresholds.iter().enumerate() {
+                is_tp[k][t] = cfg.affinity.is_match(affinity, *threshold);
+                if is_tp[k][t] && *threshold == cfg.tp_threshold {
+                    tp_errors[k] = _tp_errors(&dts.row(n), &gts.row(m));
+                }
+            }
+        }
+    }
+    _DetectionAssignment {
+        rows,
+        is_evaluated,
+        is_tp,
+        tp_errors,
+    }
+}
+
+/// Translation, scale, and orientation errors between a (10,) detection and annotation.
+fn _tp_errors(dt: &ArrayView<f32, Ix1>, gt: &ArrayView<f32, Ix1>) -> [f32; 3] {
+    let translation_error = _l2_distance(dt, gt);
+    let (intersection, union) = (3..6).fold((1., 1.), |(i, u), k| {
+        (i * dt[k].min(gt[k]), u * dt[k].max(gt[k]))
+    });
+    let scale_error = 1. - intersection / union;
+    let yaw_dt = _quat_to_yaw(&dt.slice(ndarray::s![6..10]));
+    let yaw_gt = _quat_to_yaw(&gt.slice(ndarray::s![6..10]));
+    [translation_error, scale_error, wrap_angle(yaw_dt - yaw_gt)]
+}
+
+/// Map an angle (in radians) to `[0, π)`.
+pub fn wrap_angle(angle: f32) -> f32 {
+    let angle = angle.abs();
+    match angle >= PI {
+        true => PI - angle % PI,
+        false => angle,
+    }
+}
+
+/// Index and value of the first maximum.
+fn _argmax(values: &ArrayView<f32, Ix1>) -> (usize, f32) {
+    values
+        .iter()
+        .enumerate()
+        .fold((0, f32::NEG_INFINITY), |(m, best), (k, &value)| {
+            match value > best {
+                true => (k, value),
+                false => (m, best),
+            }
+        })
+}
+
+/// Euclidean distance between the centers of two (10,) cuboids.
+fn _l2_distance(a: &ArrayView<f32, Ix1>, b: &ArrayView<f32, Ix1>) -> f32 {
+    (0..3).map(|k| (a[k] - b[k]).powi(2)).sum::<f32>().sqrt()
+}
+
+/// Distance of the center of a (10,) cuboid from the egovehicle.
+fn _l2_norm(a: &ArrayView<f32, Ix1>) -> f32 {
+    (0..3).map(|k| a[k].powi(2)).sum::<f32>().sqrt()
+}
+
+/// `(log_id, timestamp_ns, category)` of each row.
+fn _sweep_category_keys(data_frame: &DataFrame) -> Result<Vec<SweepCategoryKey>> {
+    let log_ids = data_frame.column("log_id")?.str()?;
+    let timestamps_ns = data_frame.column("timestamp_ns")?.cast(&DataType::UInt64)?;
+    let categories = data_frame.column("category")?.str()?;
+    log_ids
+        .into_iter()
+        .zip(timestamps_ns.u64()?)
+        .zip(categories)
+        .map(|((log_id, timestamp_ns), category)| {
+            Ok((
+                log_id.context("Missing `log_id`.")?.to_string(),
+                timestamp_ns.context("Missing `timestamp_ns`.")?,
+                category.context("Missing `category`.")?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Values of a numeric column as `f32` (missing values are `NaN`).
+fn _f32_values(data_frame: &DataFrame, column: &str) -> Result<Vec<f32>> {
+    let values = data_frame.column(column)?.cast(&DataType::Float32)?;
+    Ok(values
+        .f32()?
+        .into_iter()
+        .map(|x| x.unwrap_or(f32::NAN))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::{
+        compute_average_precision, evaluate, evaluate_bootstrap, wrap_angle, DetectionConfig,
+    };
+    use crate::evaluation::bootstrap::BootstrapConfig;
+
+    /// Cuboids at `(x, 0, 0)` with unit dimensions and identity orientations.
+    fn cuboids(log_id: &str, xs: &[f32], category: &str) -> DataFrame {
+        let n = xs.len();
+        df!(
+            "log_id" => vec![log_id; n],
+            "timestamp_ns" => vec![0_u64; n],
+            "category" => vec![category; n],
+            "tx_m" => xs,
+            "ty_m" => vec![0_f32; n],
+            "tz_m" => vec![0_f32; n],
+            "length_m" => vec![1_f32; n],
+            "width_m" => vec![1_f32; n],
+            "height_m" => vec![1_f32; n],
+            "qw" => vec![1_f32; n],
+            "qx" => vec![0_f32; n],
+            "qy" => vec![0_f32; n],
+            "qz" => vec![0_f32; n]
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compute_average_precision() {
+        let recall_interpolated = (0..101).map(|k| k as f64 / 100.).collect::<Vec<_>>();
+        let ap = compute_average_precision(&[true, true], &recall_interpolated, 2);
+        assert!((ap - 1.).abs() < 1e-6);
+        let ap = compute_average_precision(&[true, false], &recall_interpolated, 2);
+        assert!((ap - 50.5 / 101.).abs() < 1e-6);
+        assert_eq!(wrap_angle(-0.5), 0.5);
+        assert!((wrap_angle(1.5 * std::f32::consts::PI) - 0.5 * std::f32::consts::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let cfg = DetectionConfig {
+            categories: vec!["REGULAR_VEHICLE".to_string(), "BUS".to_string()],
+            ..Default::default()
+        };
+        let annotations = cuboids("log", &[10., 20.], "REGULAR_VEHICLE")
+            .lazy()
+            .with_column(lit(10_u32).alias("num_interior_pts"))
+            .collect()
+            .unwrap();
+
+        // Perfect detections.
+        let detections = annotations
+            .clone()
+            .lazy()
+            .with_column(lit(0.9_f32).alias("score"))
+            .collect()
+            .unwrap();
+        let evaluation = evaluate(&detections, &annotations, &cfg).unwrap();
+        let metrics = evaluation.as_metrics();
+        assert_eq!(evaluation.summary.height(), 3);
+        let vehicle_ap = evaluation.summary["AP"].f32().unwrap().get(0).unwrap();
+        assert!((vehicle_ap - 1.).abs() < 1e-6);
+        // The bus has no annotations.
+        assert!((metrics["AP"] - 0.5).abs() < 1e-6);
+        assert!(evaluation.summary["ATE"].f32().unwrap().get(0).unwrap() < 1e-6);
+        // Both annotations fall in the first range bucket, and the bus is left out.
+        assert_eq!(evaluation.breakdown.height(), 1);
+        let range = evaluation.breakdown["range_m"].str().unwrap().get(0);
+        assert_eq!(range, Some("0-50"));
+        assert_eq!(
+            evaluation.breakdown["num_gts"].u32().unwrap().get(0),
+            Some(2)
+        );
+
+        // A duplicate detection is a false positive, and a detection 1.5 m away only matches at
+        // the 2 m and 4 m thresholds.
+        let mut detections = cuboids("log", &[10., 10.2, 21.5], "REGULAR_VEHICLE");
+        detections
+            .with_column(Series::new("score", [0.9_f32, 0.8, 0.7]))
+            .unwrap();
+        let evaluation = evaluate(&detections, &annotations, &cfg).unwrap();
+        let detections = &evaluation.detections;
+        assert_eq!(
+            detections["0.5"]
+                .bool()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(true), Some(false), Some(false)]
+        );
+        assert_eq!(
+            detections["2.0"]
+                .bool()
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(true), Some(false), Some(true)]
+        );
+        let ate = detections["ATE"].f32().unwrap().get(2).unwrap();
+        assert!((ate - 1.5).abs() < 1e-5);
+        let vehicle_ate = evaluation.summary["ATE"].f32().unwrap().get(0).unwrap();
+        assert!((vehicle_ate - 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_evaluate_bootstrap() {
+        let cfg = DetectionConfig {
+            categories: vec!["REGULAR_VEHICLE".to_string()],
+            ..Default::default()
+        };
+        let mut annotations = cuboids("a", &[10.], "REGULAR_VEHICLE");
+        annotations
+            .vstack_mut(&cuboids("b", &[10.], "REGULAR_VEHICLE"))
+            .unwrap();
+        annotations
+            .with_column(Series::new("num_interior_pts", [10_u32, 10]))
+            .unwrap();
+        // Log `a` is detected perfectly, and log `b` is missed.
+        let mut detections = cuboids("a", &[10.], "REGULAR_VEHICLE");
+        detections
+            .vstack_mut(&cuboids("b", &[30.], "REGULAR_VEHICLE"))
+            .unwrap();
+        detections
+            .with_column(Series::new("score", [0.9_f32, 0.8]))
+            .unwrap();
+
+        let bootstrap_cfg = BootstrapConfig {
+            num_replicates: 100,
+            ..Default::default()
+        };
+        let intervals =
+            evaluate_bootstrap(&detections, &annotations, &cfg, &bootstrap_cfg).unwrap();
+        let metrics = evaluate(&detections, &annotations, &cfg)
+            .unwrap()
+            .as_metrics();
+        for (name, interval) in &intervals {
+            assert!((interval.estimate - metrics[name]).abs() < 1e-6, "{name}");
+            assert!(interval.lower <= interval.estimate && interval.estimate <= interval.upper);
+        }
+        // Replicates of only log `a` are perfect, and of only log `b` have no true positives.
+        assert!((intervals["AP"].upper - 1.).abs() < 1e-6);
+        assert_eq!(intervals["AP"].lower, 0.);
+        assert_eq!(
+            evaluate_bootstrap(&detections, &annotations, &cfg, &bootstrap_cfg).unwrap(),
+            intervals
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! # forecasting
+//!
+//! Motion forecasting metrics (minADE, minFDE, miss rate, and brier-minFDE).
+//!
+//! Follows the Argoverse 2 motion forecasting challenge: of the `K` forecasts of an agent, the
+//! best forecast is the one with the minimum final displacement error (FDE). Its average
+//! displacement error is the minADE, and its FDE plus the Brier score `(1 - p)^2` of its
+//! probability is the brier-minFDE. A forecast misses if its minFDE exceeds the miss threshold.
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Result};
+use ndarray::{s, Array, ArrayView, Axis, Ix1, Ix2, Ix3, Ix4};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::bootstrap::{bootstrap, BootstrapConfig, ConfidenceInterval, Metrics};
+
+/// Final displacement (in meters) above which a forecast misses.
+pub const DEFAULT_MISS_THRESHOLD_M: f32 = 2.0;
+
+/// Per-agent metrics of a batch of forecasts.
+#[derive(Clone, Debug)]
+pub struct ForecastingMetrics {
+    /// (B,) average displacement error of the best forecast.
+    pub min_ade: Array<f32, Ix1>,
+    /// (B,) final displacement error of the best forecast.
+    pub min_fde: Array<f32, Ix1>,
+    /// (B,) whether the best forecast missed.
+    pub is_missed: Array<bool, Ix1>,
+    /// (B,) final displacement error plus the Brier score of the best forecast.
+    pub brier_min_fde: Array<f32, Ix1>,
+    /// (B,) index of the best forecast.
+    pub best_index: Array<usize, Ix1>,
+}
+
+impl ForecastingMetrics {
+    /// Metrics averaged over the batch, keyed by name (e.g., `min_ade`).
+    pub fn as_metrics(&self) -> Metrics {
+        self._mean_metrics(&(0..self.min_ade.len()).collect::<Vec<_>>())
+    }
+
+    /// Metrics averaged over the `agents` (which may repeat).
+    fn _mean_metrics(&self, agents: &[usize]) -> Metrics {
+        let num_agents = agents.len().max(1) as f32;
+        let mean =
+            |values: &Array<f32, Ix1>| agents.iter().map(|&b| values[b]).sum::<f32>() / num_agents;
+        let num_missed = agents.iter().filter(|&&b| self.is_missed[b]).count() as f32;
+        Metrics::from([
+            ("min_ade".to_string(), mean(&self.min_ade)),
+            ("min_fde".to_string(), mean(&self.min_fde)),
+            ("miss_rate".to_string(), num_missed / num_agents),
+            ("brier_min_fde".to_string(), mean(&self.brier_min_fde)),
+        ])
+    }
+}
+
+/// Compute bootstrapped confidence intervals of the batch metrics (see
+/// `ForecastingMetrics::as_metrics`). Agents are grouped by their `scenario_ids`, and the scenarios
+/// are resampled with replacement.
+pub fn bootstrap_metrics(
+    metrics: &ForecastingMetrics,
+    scenario_ids: &[String],
+    cfg: &BootstrapConfig,
+) -> Result<BTreeMap<String, ConfidenceInterval>> {
+    ensure!(
+        scenario_ids.len() == metrics.min_ade.len(),
+        "Expected {} scenario ids, got {}.",
+        metrics.min_ade.len(),
+        scenario_ids.len()
+    );
+    let mut scenarios: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (b, scenario_id) in scenario_ids.iter().enumerate() {
+        scenarios.entry(scenario_id).or_default().push(b);
+    }
+    let scenarios = scenarios.into_values().collect::<Vec<_>>();
+    let metric_fn = |scenarios: &[&Vec<usize>]| {
+        let agents = scenarios
+            .iter()
+            .flat_map(|x| x.iter().copied())
+            .collect::<Vec<_>>();
+        metrics._mean_metrics(&agents)
+    };
+    Ok(bootstrap(&scenarios, metric_fn, cfg)?)
+}
+
+/// (K,) average displacement errors of (K,N,2) forecasts against an (N,2) ground truth trajectory.
+pub fn compute_ade(forecasts: &ArrayView<f32, Ix3>, gt: &ArrayView<f32, Ix2>) -> Array<f32, Ix1> {
+    forecasts
+        .outer_iter()
+        .map(|forecast| {
+            let errors = (&forecast - gt).map_axis(Axis(1), |x| x.dot(&x).sqrt());
+            errors.mean().unwrap_or(0.)
+        })
+        .collect()
+}
+
+/// (K,) final displacement errors of (K,N,2) forecasts against an (N,2) ground truth trajectory.
+pub fn compute_fde(forecasts: &ArrayView<f32, Ix3>, gt: &ArrayView<f32, Ix2>) -> Array<f32, Ix1> {
+    let last = gt.nrows() - 1;
+    forecasts
+        .outer_iter()
+        .map(|forecast| {
+            let error = &forecast.row(last) - &gt.row(last);
+            error.dot(&error).sqrt()
+        })
+        .collect()
+}
+
+/// (K,) Brier scores `(1 - p)^2` of the forecast probabilities, which are normalized to sum to one
+/// if `normalize` is set.
+pub fn compute_brier_score(
+    probabilities: &ArrayView<f32, Ix1>,
+    normalize: bool,
+) -> Result<Array<f32, Ix1>> {
+    ensure!(
+        probabilities.iter().all(|p| (0. ..=1.).contains(p)),
+        "At least one forecast probability falls outside the range [0, 1]."
+    );
+    let total = match normalize {
+        true => probabilities.sum(),
+        false => 1.,
+    };
+    Ok(probabilities.map(|p| (1. - p / total).powi(2)))
+}
+
+/// Compute the metrics of (B,K,N,2) forecasts with (B,K) probabilities against (B,N,2) ground
+/// truth trajectories. Agents are evaluated in parallel.
+pub fn compute_forecasting_metrics(
+    forecasts: &ArrayView<f32, Ix4>,
+    gts: &ArrayView<f32, Ix3>,
+    probabilities: &ArrayView<f32, Ix2>,
+    miss_threshold_m: f32,
+    normalize: bool,
+) -> Result<ForecastingMetrics> {
+    let shape = forecasts.shape();
+    let (num_agents, num_forecasts, num_timesteps) = (shape[0], shape[1], shape[2]);
+    ensure!(
+        gts.shape() == [num_agents, num_timesteps, 2] && forecasts.shape()[3] == 2,
+        "Expected (B,K,N,2) forecasts and (B,N,2) ground truth, got {:?} and {:?}.",
+        forecasts.shape(),
+        gts.shape()
+    );
+    ensure!(
+        probabilities.shape() == [num_agents, num_forecasts],
+        "Expected (B,K) probabilities, got {:?}.",
+        probabilities.shape()
+    );
+    ensure!(num_timesteps > 0, "Expected at least one timestep.");
+    let mut brier_scores = Array::<f32, Ix2>::zeros(probabilities.dim());
+    for (mut brier_score, probabilities) in brier_scores
+        .outer_iter_mut()
+        .zip(probabilities.outer_iter())
+    {
+        brier_score.assign(&compute_brier_score(&probabilities, normalize)?);
+    }
+
+    let best_forecasts = (0..num_agents)
+        .into_par_iter()
+        .map(|b| {
+            let (forecast, gt) = (forecasts.index_axis(Axis(0), b), gts.index_axis(Axis(0), b));
+            let fde = compute_fde(&forecast, &gt);
+            let k = fde
+                .iter()
+                .enumerate()
+                .fold(0, |best, (k, x)| if *x < fde[best] { k } else { best });
+            let ade = compute_ade(&forecast.slice(s![k..k + 1, .., ..]), &gt)[0];
+            (k, ade, fde[k], fde[k] + brier_scores[[b, k]])
+        })
+        .collect::<Vec<_>>();
+    let best_index = best_forecasts
+        .iter()
+        .map(|x| x.0)
+        .collect::<Array<_, Ix1>>();
+    let min_ade = best_forecasts
+        .iter()
+        .map(|x| x.1)
+        .collect::<Array<_, Ix1>>();
+    let min_fde = best_forecasts
+        .iter()
+        .map(|x| x.2)
+        .collect::<Array<_, Ix1>>();
+    let brier_min_fde = best_forecasts
+        .iter()
+        .map(|x| x.3)
+        .collect::<Array<_, Ix1>>();
+    let is_missed = min_fde.map(|x| *x > miss_threshold_m);
+    Ok(ForecastingMetrics {
+        min_ade,
+        min_fde,
+        is_missed,
+        brier_min_fde,
+        best_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array};
+
+    use super::{
+        bootstrap_metrics, compute_brier_score, compute_forecasting_metrics,
+        DEFAULT_MISS_THRESHOLD_M,
+    };
+    use crate::evaluation::bootstrap::BootstrapConfig;
+
+    #[test]
+    fn test_compute_forecasting_metrics() {
+        // The ground truth moves 1 m along +x per timestep.
+        let gt = array![[1_f32, 0.], [2., 0.], [3., 0.]];
+        let gts = gt.clone().insert_axis(ndarray::Axis(0));
+        // Forecast 0 is offset by 3 m along +y, forecast 1 by 1 m.
+        let forecasts = Array::from_shape_fn((1, 2, 3, 2), |(_, k, n, d)| match d {
+            0 => gt[[n, 0]],
+            _ => [3., 1.][k],
+        });
+        let probabilities = array![[0.8_f32, 0.2]];
+        let metrics = compute_forecasting_metrics(
+            &forecasts.view(),
+            &gts.view(),
+            &probabilities.view(),
+            DEFAULT_MISS_THRESHOLD_M,
+            false,
+        )
+        .unwrap();
+        assert_eq!(metrics.best_index[0], 1);
+        assert!((metrics.min_ade[0] - 1.).abs() < 1e-6);
+        assert!((metrics.min_fde[0] - 1.).abs() < 1e-6);
+        assert!(!metrics.is_missed[0]);
+        assert!((metrics.brier_min_fde[0] - 1.64).abs() < 1e-5);
+        assert_eq!(metrics.as_metrics()["miss_rate"], 0.);
+
+        let metrics = compute_forecasting_metrics(
+            &forecasts.view(),
+            &gts.view(),
+            &probabilities.view(),
+            0.5,
+            false,
+        )
+        .unwrap();
+        assert_eq!(metrics.as_metrics()["miss_rate"], 1.);
+
+        let brier_score = compute_brier_score(&array![2_f32, 2.].view(), true);
+        assert!(brier_score.is_err());
+        let brier_score = compute_brier_score(&array![0.5_f32, 0.5].view(), true).unwrap();
+        assert_eq!(brier_score, array![0.25, 0.25]);
+    }
+
+    #[test]
+    fn test_bootstrap_metrics() {
+        // Two agents of scenario `a` are forecast exactly, and the agent of scenario `b` is 3 m off.
+        let gts = Array::from_shape_fn((3, 2, 2), |(_, n, d)| (n * (1 - d)) as f32);
+        let mut forecasts = gts.clone().insert_axis(ndarray::Axis(1));
+        forecasts[[2, 0, 0, 1]] = 3.;
+        forecasts[[2, 0, 1, 1]] = 3.;
+        let probabilities = array![[1_f32], [1.], [1.]];
+        let metrics = compute_forecasting_metrics(
+            &forecasts.view(),
+            &gts.view(),
+            &probabilities.view(),
+            DEFAULT_MISS_THRESHOLD_M,
+            false,
+        )
+        .unwrap();
+        let scenario_ids = ["a", "a", "b"].map(String::from);
+        let cfg = BootstrapConfig {
+            num_replicates: 100,
+            ..Default::default()
+        };
+        let intervals = bootstrap_metrics(&metrics, &scenario_ids, &cfg).unwrap();
+        assert_eq!(intervals["min_fde"].estimate, 1.);
+        assert_eq!(intervals["miss_rate"].estimate, 1. / 3.);
+        // Replicates of only scenario `a` have no errors, and of only scenario `b` only errors.
+        assert_eq!(intervals["min_ade"].lower, 0.);
+        assert_eq!(intervals["min_ade"].upper, 3.);
+        assert_eq!(
+            bootstrap_metrics(&metrics, &scenario_ids, &cfg).unwrap(),
+            intervals
+        );
+        assert!(bootstrap_metrics(&metrics, &scenario_ids[..2], &cfg).is_err());
+    }
+}
@@ -0,0 +1,24 @@
+//! # evaluation
+//!
+//! Evaluation metrics for the Argoverse 2 challenges.
+
+/// Bootstrapped confidence intervals.
+pub mod bootstrap;
+/// Range buckets and splits of evaluation breakdown tables.
+pub mod breakdown;
+/// 3D object detection evaluation.
+pub mod detection;
+/// Motion forecasting metrics.
+pub mod forecasting;
+/// Scene flow evaluation.
+pub mod scene_flow;
+/// Paired significance testing between result sets.
+pub mod significance;
+/// Latency-aware streaming evaluation.
+pub mod streaming;
+/// Leaderboard submission writers.
+pub mod submission;
+/// Trust, but Verify (TbV) map change detection evaluation.
+pub mod tbv;
+/// Multi-object tracking evaluation.
+pub mod tracking;
@@ -0,0 +1,274 @@
+//! # scene_flow
+//!
+//! Scene flow metrics (EPE, strict/relaxed accuracy, and angle error).
+//!
+//! Follows the Argoverse 2 scene flow challenge: every valid point is assigned to a subset by its
+//! class (background or foreground), its motion (dynamic or static), and its distance from the
+//! ego-vehicle (close or far). Flow metrics are averaged within each subset, and the dynamic
+//! segmentation is scored by its true/false positive/negative counts.
+
+use anyhow::{ensure, Result};
+use ndarray::{Array, ArrayView, Axis, Ix1, Ix2};
+use polars::prelude::*;
+
+use super::bootstrap::Metrics;
+
+/// Threshold (in meters, or relative to the ground truth flow) of the strict accuracy.
+pub const ACCURACY_STRICT_DISTANCE_THRESHOLD: f32 = 0.05;
+/// Threshold (in meters, or relative to the ground truth flow) of the relaxed accuracy.
+pub const ACCURACY_RELAX_DISTANCE_THRESHOLD: f32 = 0.1;
+/// Flow magnitude (in meters) above which a point is dynamic.
+pub const SCENE_FLOW_DYNAMIC_THRESHOLD: f32 = 0.05;
+/// Time (in seconds) between the two sweeps of a pair.
+pub const SWEEP_PAIR_TIME_DELTA: f32 = 0.1;
+/// Scene flow metric columns of the results, in order.
+pub const SCENE_FLOW_METRIC_COLUMNS: [&str; 4] =
+    ["ACCURACY_RELAX", "ACCURACY_STRICT", "ANGLE_ERROR", "EPE"];
+/// Segmentation metric columns of the results, in order.
+pub const SEGMENTATION_METRIC_COLUMNS: [&str; 4] = ["TP", "TN", "FP", "FN"];
+
+const EPS: f32 = 1e-10;
+
+/// (N,) end-point errors of (N,3) predicted flows against (N,3) ground truth flows.
+pub fn compute_end_point_error(
+    dts: &ArrayView<f32, Ix2>,
+    gts: &ArrayView<f32, Ix2>,
+) -> Array<f32, Ix1> {
+    (dts - gts).map_axis(Axis(1), |x| x.dot(&x).sqrt())
+}
+
+/// (N,) inlier flags of (N,3) predicted flows, where a prediction is an inlier if either its
+/// absolute or its relative end-point error is below `distance_threshold`.
+pub fn compute_accuracy(
+    dts: &ArrayView<f32, Ix2>,
+    gts: &ArrayView<f32, Ix2>,
+    distance_threshold: f32,
+) -> Array<bool, Ix1> {
+    let end_point_error = compute_end_point_error(dts, gts);
+    let gts_norm = gts.map_axis(Axis(1), |x| x.dot(&x).sqrt());
+    ndarray::Zip::from(&end_point_error)
+        .and(&gts_norm)
+        .map_collect(|error, norm| {
+            *error < distance_threshold || error / (norm + EPS) < distance_threshold
+        })
+}
+
+/// (N,) angle errors (in radians) between the space-time vectors `(tx, ty, tz, dt)` of (N,3)
+/// predicted and ground truth flows.
+pub fn compute_angle_error(
+    dts: &ArrayView<f32, Ix2>,
+    gts: &ArrayView<f32, Ix2>,
+) -> Array<f32, Ix1> {
+    ndarray::Zip::from(dts.rows())
+        .and(gts.rows())
+        .map_collect(|dt, gt| {
+            let dt_norm = (dt.dot(&dt) + SWEEP_PAIR_TIME_DELTA.powi(2)).sqrt();
+            let gt_norm = (gt.dot(&gt) + SWEEP_PAIR_TIME_DELTA.powi(2)).sqrt();
+            let dot_product = (dt.dot(&gt) + SWEEP_PAIR_TIME_DELTA.powi(2)) / (dt_norm * gt_norm);
+            dot_product.clamp(-1., 1.).acos()
+        })
+}
+
+/// Compute the per-subset metrics of (N,3) predicted flows and (N,) dynamic predictions against
+/// (N,3) ground truth flows.
+///
+/// `category_indices` holds the class of each point (`0` is background, anything else is
+/// foreground), and only points flagged by `is_valid` are evaluated. The result has one row per
+/// class, motion, and distance subset, with the point count, the average scene flow metrics (null
+/// if the subset is empty), and the segmentation counts.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_metrics(
+    pred_flow: &ArrayView<f32, Ix2>,
+    pred_dynamic: &ArrayView<bool, Ix1>,
+    gts: &ArrayView<f32, Ix2>,
+    category_indices: &ArrayView<u8, Ix1>,
+    is_dynamic: &ArrayView<bool, Ix1>,
+    is_close: &ArrayView<bool, Ix1>,
+    is_valid: &ArrayView<bool, Ix1>,
+) -> Result<DataFrame> {
+    let num_points = gts.nrows();
+    ensure!(
+        pred_flow.shape() == [num_points, 3] && gts.ncols() == 3,
+        "Expected (N,3) predicted and ground truth flows, got {:?} and {:?}.",
+        pred_flow.shape(),
+        gts.shape()
+    );
+    ensure!(
+        [pred_dynamic.len(), category_indices.len(), is_dynamic.len()]
+            .into_iter()
+            .chain([is_close.len(), is_valid.len()])
+            .all(|len| len == num_points),
+        "Expected (N,) masks for {num_points} points."
+    );
+
+    let end_point_error = compute_end_point_error(pred_flow, gts);
+    let accuracy_relax = compute_accuracy(pred_flow, gts, ACCURACY_RELAX_DISTANCE_THRESHOLD);
+    let accuracy_strict = compute_accuracy(pred_flow, gts, ACCURACY_STRICT_DISTANCE_THRESHOLD);
+    let angle_error = compute_angle_error(pred_flow, gts);
+
+    let mut classes = vec![];
+    let mut motions = vec![];
+    let mut distances = vec![];
+    let mut counts = vec![];
+    let mut flow_metrics: [Vec<Option<f32>>; 4] = Default::default();
+    let mut segmentation_metrics: [Vec<u32>; 4] = Default::default();
+    for (class, is_foreground) in [("Background", false), ("Foreground", true)] {
+        for (motion, dynamic) in [("Dynamic", true), ("Static", false)] {
+            for (distance, close) in [("Close", true), ("Far", false)] {
+                let subset = (0..num_points)
+                    .filter(|&i| {
+                        is_valid[i]
+                            && (category_indices[i] != 0) == is_foreground
+                            && is_dynamic[i] == dynamic
+                            && is_close[i] == close
+                    })
+                    .collect::<Vec<_>>();
+                classes.push(class);
+                motions.push(motion);
+                distances.push(distance);
+                counts.push(subset.len() as u32);
+
+                let num_subset = subset.len() as f32;
+                let mean = |values: &dyn Fn(usize) -> f32| {
+                    (!subset.is_empty())
+                        .then(|| subset.iter().map(|&i| values(i)).sum::<f32>() / num_subset)
+                };
+                flow_metrics[0].push(mean(&|i| accuracy_relax[i] as u8 as f32));
+                flow_metrics[1].push(mean(&|i| accuracy_strict[i] as u8 as f32));
+                flow_metrics[2].push(mean(&|i| angle_error[i]));
+                flow_metrics[3].push(mean(&|i| end_point_error[i]));
+
+                let count = |pred: bool, gt: bool| {
+                    subset
+                        .iter()
+                        .filter(|&&i| pred_dynamic[i] == pred && is_dynamic[i] == gt)
+                        .count() as u32
+                };
+                segmentation_metrics[0].push(count(true, true));
+                segmentation_metrics[1].push(count(false, false));
+                segmentation_metrics[2].push(count(true, false));
+                segmentation_metrics[3].push(count(false, true));
+            }
+        }
+    }
+
+    let mut columns = vec![
+        Series::new("Class", classes),
+        Series::new("Motion", motions),
+        Series::new("Distance", distances),
+        Series::new("Count", counts),
+    ];
+    columns.extend(
+        SCENE_FLOW_METRIC_COLUMNS
+            .iter()
+            .zip(flow_metrics)
+            .map(|(name, values)| Series::new(name, values)),
+    );
+    columns.extend(
+        SEGMENTATION_METRIC_COLUMNS
+            .iter()
+            .zip(segmentation_metrics)
+            .map(|(name, values)| Series::new(name, values)),
+    );
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Collapse (possibly concatenated) results of `compute_metrics` into whole dataset metrics keyed
+/// by `<Class>/<Motion>/<Distance>/<Metric>`. Scene flow metrics are averaged weighted by the
+/// point count of each row, and segmentation counts are summed.
+pub fn results_to_metrics(results: &DataFrame) -> PolarsResult<Metrics> {
+    let weighted = SCENE_FLOW_METRIC_COLUMNS.iter().map(|name| {
+        (col(name).fill_null(lit(0_f32)) * col("Count").cast(DataType::Float32))
+            .sum()
+            .alias(name)
+    });
+    let summed = SEGMENTATION_METRIC_COLUMNS
+        .iter()
+        .map(|name| col(name).sum().cast(DataType::Float32));
+    let grouped = results
+        .clone()
+        .lazy()
+        .group_by_stable([col("Class"), col("Motion"), col("Distance")])
+        .agg(
+            weighted
+                .chain(summed)
+                .chain([col("Count").sum().cast(DataType::Float32)])
+                .collect::<Vec<_>>(),
+        )
+        .collect()?;
+
+    let mut metrics = Metrics::new();
+    let count = grouped.column("Count")?.f32()?;
+    let keys = ["Class", "Motion", "Distance"]
+        .iter()
+        .map(|name| grouped.column(name).and_then(|x| x.str().cloned()))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    for name in SCENE_FLOW_METRIC_COLUMNS
+        .iter()
+        .chain(SEGMENTATION_METRIC_COLUMNS.iter())
+    {
+        let values = grouped.column(name)?.f32()?;
+        for i in 0..grouped.height() {
+            let prefix = keys
+                .iter()
+                .map(|key| key.get(i).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join("/");
+            let value = values.get(i).unwrap_or(f32::NAN);
+            let value = match SCENE_FLOW_METRIC_COLUMNS.contains(name) {
+                true => value / count.get(i).unwrap_or(0.),
+                false => value,
+            };
+            metrics.insert(format!("{prefix}/{name}"), value);
+        }
+    }
+    Ok(metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::{compute_angle_error, compute_metrics, results_to_metrics};
+
+    #[test]
+    fn test_compute_metrics() {
+        let gts = array![[1_f32, 0., 0.], [0., 0., 0.], [0., 0., 0.], [1., 0., 0.]];
+        let pred_flow = array![
+            [1.02_f32, 0., 0.],
+            [0.08, 0., 0.],
+            [0., 0., 0.],
+            [0., 0., 0.]
+        ];
+        let pred_dynamic = array![true, true, false, false];
+        let category_indices = array![19_u8, 0, 0, 19];
+        let is_dynamic = array![true, false, false, true];
+        let is_close = array![true, true, true, true];
+        let is_valid = array![true, true, true, false];
+        let results = compute_metrics(
+            &pred_flow.view(),
+            &pred_dynamic.view(),
+            &gts.view(),
+            &category_indices.view(),
+            &is_dynamic.view(),
+            &is_close.view(),
+            &is_valid.view(),
+        )
+        .unwrap();
+        assert_eq!(results.height(), 8);
+
+        let metrics = results_to_metrics(&results).unwrap();
+        assert!((metrics["Foreground/Dynamic/Close/EPE"] - 0.02).abs() < 1e-6);
+        assert_eq!(metrics["Foreground/Dynamic/Close/ACCURACY_STRICT"], 1.);
+        assert_eq!(metrics["Foreground/Dynamic/Close/TP"], 1.);
+        assert!((metrics["Background/Static/Close/EPE"] - 0.04).abs() < 1e-6);
+        assert_eq!(metrics["Background/Static/Close/ACCURACY_RELAX"], 1.);
+        assert_eq!(metrics["Background/Static/Close/ACCURACY_STRICT"], 0.5);
+        assert_eq!(metrics["Background/Static/Close/FP"], 1.);
+        assert_eq!(metrics["Background/Static/Close/TN"], 1.);
+        assert!(metrics["Foreground/Static/Far/EPE"].is_nan());
+
+        let angle_error = compute_angle_error(&gts.view(), &gts.view());
+        assert!(angle_error.iter().all(|x| x.abs() < 1e-3));
+    }
+}
@@ -0,0 +1,280 @@
+//! # significance
+//!
+//! Paired significance testing between two result sets.
+//!
+//! Both result sets are evaluated per group (e.g., per log) and compared on the mean of the paired
+//! per-group differences `b - a`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use polars::prelude::{DataFrame, DataType};
+use rand::Rng;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use strum_macros::{Display, EnumString};
+
+use super::bootstrap::{replicate_rng, Metrics, DEFAULT_NUM_REPLICATES};
+use crate::{
+    error::{Av2Error, Av2Result},
+    schema::{validate_data_frame, ColumnType},
+};
+
+/// Paired significance test.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum SignificanceTest {
+    /// Resample groups with replacement and measure how often the mean difference changes sign.
+    PairedBootstrap,
+    /// Randomly swap the paired results (sign-flip the differences) under the null hypothesis.
+    Permutation,
+}
+
+/// Significance testing configuration.
+#[derive(Clone, Debug)]
+pub struct SignificanceConfig {
+    /// Test used to compute the p-values.
+    pub test: SignificanceTest,
+    /// Number of bootstrap replicates or permutations.
+    pub num_replicates: usize,
+    /// Random seed. Each replicate draws from its own stream of the seed (see `replicate_rng`) so
+    /// results are reproducible.
+    pub seed: u64,
+}
+
+impl Default for SignificanceConfig {
+    fn default() -> Self {
+        Self {
+            test: SignificanceTest::Permutation,
+            num_replicates: DEFAULT_NUM_REPLICATES,
+            seed: 0,
+        }
+    }
+}
+
+/// Result of a paired significance test for a single metric.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SignificanceResult {
+    /// Mean paired difference `b - a` over the groups.
+    pub mean_difference: f32,
+    /// Two-sided p-value of the null hypothesis that there is no difference.
+    pub p_value: f32,
+}
+
+/// Compare two result sets given their aligned per-group metrics.
+/// `metrics_a[i]` and `metrics_b[i]` must correspond to the same group.
+/// Only metrics present (and finite) in both result sets for every group are tested.
+pub fn compare(
+    metrics_a: &[Metrics],
+    metrics_b: &[Metrics],
+    cfg: &SignificanceConfig,
+) -> Av2Result<BTreeMap<String, SignificanceResult>> {
+    if cfg.num_replicates == 0 {
+        return Err(Av2Error::invalid_parameter(
+            "num_replicates",
+            "At least one replicate is required.",
+        ));
+    }
+    if metrics_a.len() != metrics_b.len() {
+        return Err(Av2Error::invalid_parameter(
+            "metrics_b",
+            format!(
+                "Both result sets must contain the same number of groups (got {} and {}).",
+                metrics_a.len(),
+                metrics_b.len()
+            ),
+        ));
+    }
+    let Some(first) = metrics_a.first() else {
+        return Ok(BTreeMap::new());
+    };
+
+    Ok(first
+        .keys()
+        .filter_map(|name| {
+            let differences = metrics_a
+                .iter()
+                .zip(metrics_b)
+                .map(|(a, b)| Some(b.get(name)? - a.get(name)?))
+                .collect::<Option<Vec<_>>>()?;
+            if differences.iter().any(|x| !x.is_finite()) {
+                return None;
+            }
+            Some((name.clone(), paired_test(&differences, cfg)))
+        })
+        .collect())
+}
+
+/// Compare two result sets given as DataFrames with a `log_id` column and one numeric column per
+/// metric. Logs are paired by `log_id`; logs missing from either result set are ignored.
+pub fn compare_frames(
+    results_a: &DataFrame,
+    results_b: &DataFrame,
+    cfg: &SignificanceConfig,
+) -> Av2Result<BTreeMap<String, SignificanceResult>> {
+    let metrics_a = _frame_to_metrics("results_a", results_a)?;
+    let metrics_b = _frame_to_metrics("results_b", results_b)?;
+
+    let mut log_ids = metrics_a
+        .keys()
+        .filter(|log_id| metrics_b.contains_key(*log_id))
+        .collect::<Vec<_>>();
+    log_ids.sort();
+
+    let aligned_a = log_ids
+        .iter()
+        .map(|log_id| metrics_a[*log_id].clone())
+        .collect::<Vec<_>>();
+    let aligned_b = log_ids
+        .iter()
+        .map(|log_id| metrics_b[*log_id].clone())
+        .collect::<Vec<_>>();
+    compare(&aligned_a, &aligned_b, cfg)
+}
+
+/// Run a paired significance test on per-group `differences`.
+pub fn paired_test(differences: &[f32], cfg: &SignificanceConfig) -> SignificanceResult {
+    let num_groups = differences.len();
+    let mean_difference = _mean(differences);
+    if num_groups == 0 {
+        return SignificanceResult {
+            mean_difference,
+            p_value: 1.,
+        };
+    }
+
+    let replicates = (0..cfg.num_replicates)
+        .into_par_iter()
+        .map(|i| {
+            let mut rng = replicate_rng(cfg.seed, i);
+            match cfg.test {
+                SignificanceTest::PairedBootstrap => {
+                    (0..num_groups)
+                        .map(|_| differences[rng.gen_range(0..num_groups)])
+                        .sum::<f32>()
+                        / num_groups as f32
+                }
+                SignificanceTest::Permutation => {
+                    differences
+                        .iter()
+                        .map(|d| if rng.gen_bool(0.5) { *d } else { -*d })
+                        .sum::<f32>()
+                        / num_groups as f32
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let num_replicates = replicates.len() as f32;
+    let p_value = match cfg.test {
+        SignificanceTest::PairedBootstrap => {
+            // Fraction of replicates on the opposite side of zero from the observed difference.
+            let num_below = replicates.iter().filter(|x| **x <= 0.).count() as f32;
+            let num_above = replicates.iter().filter(|x| **x >= 0.).count() as f32;
+            (2. * f32::min(num_below, num_above) / num_replicates).min(1.)
+        }
+        SignificanceTest::Permutation => {
+            let num_extreme = replicates
+                .iter()
+                .filter(|x| x.abs() >= mean_difference.abs())
+                .count() as f32;
+            (num_extreme + 1.) / (num_replicates + 1.)
+        }
+    };
+    SignificanceResult {
+        mean_difference,
+        p_value,
+    }
+}
+
+/// Per-log metrics of the result set `table`.
+fn _frame_to_metrics(table: &str, results: &DataFrame) -> Av2Result<HashMap<String, Metrics>> {
+    validate_data_frame(table, results, &[("log_id", ColumnType::String)])?;
+    let log_ids = results["log_id"].str()?;
+    let metric_columns = results
+        .get_columns()
+        .iter()
+        .filter(|series| series.name() != "log_id" && series.dtype().is_numeric())
+        .map(|series| {
+            let values = series
+                .cast(&DataType::Float32)
+                .map_err(|err| Av2Error::schema(table, err))?;
+            Ok((series.name().to_string(), values))
+        })
+        .collect::<Av2Result<Vec<_>>>()?;
+
+    let mut metrics: HashMap<String, Metrics> = HashMap::new();
+    for (i, log_id) in log_ids.into_no_null_iter().enumerate() {
+        let entry = metrics.entry(log_id.to_string()).or_default();
+        for (name, values) in metric_columns.iter() {
+            if let Some(value) = values.f32()?.get(i) {
+                entry.insert(name.clone(), value);
+            }
+        }
+    }
+    Ok(metrics)
+}
+
+fn _mean(values: &[f32]) -> f32 {
+    match values.len() {
+        0 => 0.,
+        n => values.iter().sum::<f32>() / n as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{df, NamedFrom};
+
+    use super::{
+        compare, compare_frames, paired_test, Metrics, SignificanceConfig, SignificanceTest,
+    };
+    use crate::error::Av2Error;
+
+    #[test]
+    fn test_paired_test() {
+        for test in [
+            SignificanceTest::PairedBootstrap,
+            SignificanceTest::Permutation,
+        ] {
+            let cfg = SignificanceConfig {
+                test,
+                ..Default::default()
+            };
+            let improved = paired_test(&[0.1, 0.12, 0.09, 0.11, 0.1, 0.13, 0.08, 0.1], &cfg);
+            assert!((improved.mean_difference - 0.10375).abs() < 1e-6);
+            assert!(improved.p_value < 0.05);
+
+            let unchanged = paired_test(&[0.1, -0.1, 0.05, -0.05, 0.2, -0.2], &cfg);
+            assert!(unchanged.p_value > 0.5);
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let cfg = SignificanceConfig::default();
+        let metrics_a = (0..4)
+            .map(|i| [("ap".to_string(), 0.5 + 0.01 * i as f32)].into())
+            .collect::<Vec<Metrics>>();
+        let metrics_b = metrics_a
+            .iter()
+            .map(|x| [("ap".to_string(), x["ap"] + 0.1)].into())
+            .collect::<Vec<_>>();
+        let results = compare(&metrics_a, &metrics_b, &cfg).unwrap();
+        assert!((results["ap"].mean_difference - 0.1).abs() < 1e-6);
+
+        // Result sets of different sizes cannot be paired.
+        assert!(matches!(
+            compare(&metrics_a, &metrics_b[1..], &cfg),
+            Err(Av2Error::InvalidParameter { .. })
+        ));
+
+        let results_a = df!("log_id" => ["a", "b"], "ap" => [0.5_f64, 0.6]).unwrap();
+        let results_b = df!("log_id" => ["b", "a", "c"], "ap" => [0.7_f64, 0.6, 0.1]).unwrap();
+        let results = compare_frames(&results_a, &results_b, &cfg).unwrap();
+        assert!((results["ap"].mean_difference - 0.1).abs() < 1e-6);
+        let without_log_ids = df!("ap" => [0.5_f64, 0.6]).unwrap();
+        assert!(matches!(
+            compare_frames(&without_log_ids, &results_b, &cfg),
+            Err(Av2Error::Schema { .. })
+        ));
+    }
+}
@@ -0,0 +1,404 @@
+//! # streaming
+//!
+//! Latency-aware streaming evaluation.
+//!
+//! Following "Towards Streaming Perception" (Li et al., 2020), predictions are only usable once
+//! they have been computed. A log is replayed in (scaled) real time: while the model is busy,
+//! incoming frames are skipped, and each ground truth timestamp is scored against the most recent
+//! prediction which had finished by then.
+//!
+//! The replay is event-driven: the callback's measured wall-clock latency advances the simulated
+//! clock, which is equivalent to a real-time replay without having to sleep between frames.
+
+use std::{convert::Infallible, time::Instant};
+
+use polars::{
+    lazy::dsl::{col, lit},
+    prelude::{concat, DataFrame, DataType, IntoLazy, UnionArgs},
+};
+
+use crate::{
+    data_loader::{DataLoader, Sweep},
+    error::{Av2Error, Av2Result},
+    schema::{validate_data_frame, ColumnType},
+};
+
+use super::bootstrap::Metrics;
+
+/// Streaming replay configuration.
+#[derive(Clone, Debug)]
+pub struct StreamingConfig {
+    /// Replay speed relative to real time (e.g., `0.5` gives the model twice as long per frame).
+    pub playback_speed: f32,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self { playback_speed: 1. }
+    }
+}
+
+/// Output of the model for a single processed frame.
+#[derive(Clone, Debug)]
+pub struct StreamedOutput {
+    /// Timestamp of the processed frame.
+    pub frame_timestamp_ns: u64,
+    /// Simulated time at which the predictions became available.
+    pub finish_timestamp_ns: u64,
+    /// Predictions returned by the callback.
+    pub predictions: DataFrame,
+}
+
+/// Replay frames with sorted `timestamps_ns` in simulated real time.
+///
+/// When the model becomes idle, the most recent frame that has arrived is loaded with `load_frame`
+/// and passed to `callback` together with its deadline (the arrival time of the next frame).
+/// Loading time is not counted towards the latency.
+pub fn replay<T, L, F>(
+    timestamps_ns: &[u64],
+    load_frame: L,
+    callback: F,
+    cfg: &StreamingConfig,
+) -> Vec<StreamedOutput>
+where
+    L: Fn(usize) -> T,
+    F: FnMut(&T, u64) -> DataFrame,
+{
+    try_replay(
+        timestamps_ns,
+        |i| Ok::<_, Infallible>(load_frame(i)),
+        callback,
+        cfg,
+    )
+    .unwrap_or_else(|err| match err {})
+}
+
+/// Replay frames as in `replay`, stopping at the first frame which fails to load.
+pub fn try_replay<T, E, L, F>(
+    timestamps_ns: &[u64],
+    load_frame: L,
+    mut callback: F,
+    cfg: &StreamingConfig,
+) -> Result<Vec<StreamedOutput>, E>
+where
+    L: Fn(usize) -> Result<T, E>,
+    F: FnMut(&T, u64) -> DataFrame,
+{
+    _replay(timestamps_ns, load_frame, |frame, deadline_ns| {
+        let start = Instant::now();
+        let predictions = callback(frame, deadline_ns);
+        let latency_ns = start.elapsed().as_nanos() as f64 * cfg.playback_speed as f64;
+        (predictions, latency_ns as u64)
+    })
+}
+
+/// Replay frames where `callback` returns its predictions and simulated latency (in nanoseconds).
+fn _replay<T, E, L, F>(
+    timestamps_ns: &[u64],
+    load_frame: L,
+    mut callback: F,
+) -> Result<Vec<StreamedOutput>, E>
+where
+    L: Fn(usize) -> Result<T, E>,
+    F: FnMut(&T, u64) -> (DataFrame, u64),
+{
+    let mut outputs = vec![];
+    let Some(first) = timestamps_ns.first() else {
+        return Ok(outputs);
+    };
+    let mut clock_ns = *first;
+    let mut next_index = 0;
+    while next_index < timestamps_ns.len() {
+        // Process the latest frame which has arrived (or wait for the next one).
+        let arrived = timestamps_ns.partition_point(|&t| t <= clock_ns);
+        let index = arrived.saturating_sub(1).max(next_index);
+        let frame_timestamp_ns = timestamps_ns[index];
+        clock_ns = clock_ns.max(frame_timestamp_ns);
+        let deadline_ns = timestamps_ns.get(index + 1).copied().unwrap_or(u64::MAX);
+
+        let frame = load_frame(index)?;
+        let (predictions, latency_ns) = callback(&frame, deadline_ns);
+
+        clock_ns += latency_ns;
+        outputs.push(StreamedOutput {
+            frame_timestamp_ns,
+            finish_timestamp_ns: clock_ns,
+            predictions,
+        });
+        next_index = index + 1;
+    }
+    Ok(outputs)
+}
+
+/// Replay the sweeps of `log_id` from the data-loader in simulated real time.
+/// The replay stops at the first sweep which fails to read, returning its error.
+pub fn replay_log<F>(
+    data_loader: &DataLoader,
+    log_id: &str,
+    callback: F,
+    cfg: &StreamingConfig,
+) -> Av2Result<Vec<StreamedOutput>>
+where
+    F: FnMut(&Sweep, u64) -> DataFrame,
+{
+    let file_index = &data_loader.file_index.0;
+    let log_ids = file_index["log_id"].str()?;
+    let timestamps = file_index["timestamp_ns"].u64()?;
+    let (indices, timestamps_ns): (Vec<_>, Vec<_>) = log_ids
+        .into_iter()
+        .zip(timestamps)
+        .enumerate()
+        .filter_map(|(i, (x, timestamp_ns))| (x == Some(log_id)).then_some((i, timestamp_ns?)))
+        .unzip();
+    try_replay(
+        &timestamps_ns,
+        |i| data_loader.try_get(indices[i]),
+        callback,
+        cfg,
+    )
+}
+
+/// Align streamed outputs to `query_timestamps_ns`.
+///
+/// Each query uses the most recent predictions which had finished by the query time, with their
+/// `timestamp_ns` column set to the query timestamp. Queries before the first finished prediction
+/// have no predictions. Fails if the predictions of the outputs have different schemas.
+pub fn align_streamed_outputs(
+    outputs: &[StreamedOutput],
+    query_timestamps_ns: &[u64],
+) -> Av2Result<DataFrame> {
+    let aligned = query_timestamps_ns
+        .iter()
+        .filter_map(|query_timestamp_ns| {
+            let output = outputs
+                .iter()
+                .filter(|output| output.finish_timestamp_ns <= *query_timestamp_ns)
+                .max_by_key(|output| output.finish_timestamp_ns)?;
+            Some(
+                output.predictions.clone().lazy().with_column(
+                    lit(*query_timestamp_ns)
+                        .cast(DataType::UInt64)
+                        .alias("timestamp_ns"),
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    match aligned.is_empty() {
+        true => Ok(DataFrame::default()),
+        false => concat(aligned, UnionArgs::default())
+            .and_then(|x| x.collect())
+            .map_err(|err| Av2Error::schema("predictions", err)),
+    }
+}
+
+/// Score streamed outputs against `annotations` with a (non-streaming) `metric_fn`.
+/// Predictions are aligned to every annotated `timestamp_ns` before being scored.
+pub fn evaluate_streaming<F>(
+    outputs: &[StreamedOutput],
+    annotations: &DataFrame,
+    metric_fn: F,
+) -> Av2Result<Metrics>
+where
+    F: Fn(&DataFrame, &DataFrame) -> Metrics,
+{
+    validate_data_frame(
+        "annotations",
+        annotations,
+        &[("timestamp_ns", ColumnType::Integer)],
+    )?;
+    let query_timestamps_ns = annotations
+        .clone()
+        .lazy()
+        .select([col("timestamp_ns").cast(DataType::UInt64).unique_stable()])
+        .collect()?["timestamp_ns"]
+        .u64()?
+        .into_no_null_iter()
+        .collect::<Vec<_>>();
+    let predictions = align_streamed_outputs(outputs, &query_timestamps_ns)?;
+    Ok(metric_fn(&predictions, annotations))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use polars::prelude::*;
+
+    use super::{
+        _replay, align_streamed_outputs, evaluate_streaming, replay, replay_log, StreamedOutput,
+        StreamingConfig,
+    };
+    use crate::{
+        data_loader::DataLoader,
+        error::Av2Error,
+        io::build_lidar_file_path,
+        synthetic::{test_dataset, MiniDatasetConfig},
+    };
+
+    const FRAME_PERIOD_NS: u64 = 100_000_000;
+
+    /// Replay five frames with a callback of fixed latency.
+    /// Returns the outputs and the deadline passed to the callback for each processed frame.
+    fn _replay_fixed_latency(latency_ns: u64) -> (Vec<StreamedOutput>, Vec<u64>) {
+        let timestamps_ns = (0..5).map(|i| i * FRAME_PERIOD_NS).collect::<Vec<_>>();
+        let mut deadlines_ns = vec![];
+        let outputs = _replay(
+            &timestamps_ns,
+            |i| Ok::<_, Av2Error>(timestamps_ns[i]),
+            |frame_timestamp_ns, deadline_ns| {
+                deadlines_ns.push(deadline_ns);
+                let predictions = df!("frame_timestamp_ns" => [*frame_timestamp_ns]).unwrap();
+                (predictions, latency_ns)
+            },
+        )
+        .unwrap();
+        (outputs, deadlines_ns)
+    }
+
+    fn _frame_and_finish_timestamps(outputs: &[StreamedOutput]) -> Vec<(u64, u64)> {
+        outputs
+            .iter()
+            .map(|x| {
+                (
+                    x.frame_timestamp_ns / 1_000_000,
+                    x.finish_timestamp_ns / 1_000_000,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_fixed_latency() {
+        // A fast model processes every frame, waiting for each to arrive, and meets each deadline.
+        let (outputs, deadlines_ns) = _replay_fixed_latency(FRAME_PERIOD_NS / 2);
+        assert_eq!(
+            _frame_and_finish_timestamps(&outputs),
+            [(0, 50), (100, 150), (200, 250), (300, 350), (400, 450)]
+        );
+        assert!(outputs
+            .iter()
+            .zip(&deadlines_ns)
+            .all(|(output, deadline_ns)| output.finish_timestamp_ns <= *deadline_ns));
+
+        // A slow model skips the frame at 200 ms, which arrives while the frame at 100 ms is
+        // processed, and misses every deadline except the last one.
+        let (outputs, deadlines_ns) = _replay_fixed_latency(3 * FRAME_PERIOD_NS / 2);
+        assert_eq!(
+            _frame_and_finish_timestamps(&outputs),
+            [(0, 150), (100, 300), (300, 450), (400, 600)]
+        );
+        assert_eq!(
+            deadlines_ns,
+            [
+                FRAME_PERIOD_NS,
+                2 * FRAME_PERIOD_NS,
+                4 * FRAME_PERIOD_NS,
+                u64::MAX
+            ]
+        );
+        let missed = outputs
+            .iter()
+            .zip(&deadlines_ns)
+            .map(|(output, deadline_ns)| output.finish_timestamp_ns > *deadline_ns)
+            .collect::<Vec<_>>();
+        assert_eq!(missed, [true, true, true, false]);
+    }
+
+    #[test]
+    fn test_replay() {
+        assert!(replay(&[], |i| i, |_, _| DataFrame::default(), &Default::default()).is_empty());
+
+        // A near-instant callback at a slow playback speed processes every frame.
+        let timestamps_ns = (0..3).map(|i| i * FRAME_PERIOD_NS).collect::<Vec<_>>();
+        let cfg = StreamingConfig {
+            playback_speed: 1e-3,
+        };
+        let outputs = replay(&timestamps_ns, |i| i, |_, _| DataFrame::default(), &cfg);
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|x| x.frame_timestamp_ns)
+                .collect::<Vec<_>>(),
+            timestamps_ns
+        );
+        for output in outputs {
+            assert!(output.finish_timestamp_ns >= output.frame_timestamp_ns);
+            assert!(output.finish_timestamp_ns < output.frame_timestamp_ns + FRAME_PERIOD_NS);
+        }
+    }
+
+    #[test]
+    fn test_align_streamed_outputs() {
+        // Outputs finish at 150, 300, 450, and 600 ms for the frames at 0, 100, 300, and 400 ms.
+        let (outputs, _) = _replay_fixed_latency(3 * FRAME_PERIOD_NS / 2);
+        let query_timestamps_ns = (0..7).map(|i| i * FRAME_PERIOD_NS).collect::<Vec<_>>();
+        let aligned = align_streamed_outputs(&outputs, &query_timestamps_ns).unwrap();
+
+        // No predictions have finished by the queries at 0 and 100 ms.
+        let timestamps_ns = aligned["timestamp_ns"]
+            .u64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps_ns, &query_timestamps_ns[2..]);
+        let frame_timestamps_ms = aligned["frame_timestamp_ns"]
+            .u64()
+            .unwrap()
+            .into_no_null_iter()
+            .map(|x| x / 1_000_000)
+            .collect::<Vec<_>>();
+        assert_eq!(frame_timestamps_ms, [0, 100, 100, 300, 400]);
+
+        assert_eq!(align_streamed_outputs(&outputs, &[0]).unwrap().height(), 0);
+
+        // Predictions with mismatched schemas cannot be aligned.
+        let mut outputs = outputs;
+        outputs[1].predictions = df!("score" => [0.5_f32]).unwrap();
+        assert!(matches!(
+            align_streamed_outputs(&outputs, &query_timestamps_ns),
+            Err(Av2Error::Schema { .. })
+        ));
+    }
+
+    #[test]
+    fn test_evaluate_streaming() {
+        let (outputs, _) = _replay_fixed_latency(3 * FRAME_PERIOD_NS / 2);
+        let annotations =
+            df!("timestamp_ns" => [200_000_000_u64, 200_000_000, 400_000_000]).unwrap();
+        let num_rows = |predictions: &DataFrame, _: &DataFrame| {
+            [("num_rows".to_string(), predictions.height() as f32)].into()
+        };
+        let metrics = evaluate_streaming(&outputs, &annotations, num_rows).unwrap();
+        assert_eq!(metrics["num_rows"], 2.);
+
+        let annotations = df!("track_uuid" => ["a"]).unwrap();
+        assert!(matches!(
+            evaluate_streaming(&outputs, &annotations, num_rows),
+            Err(Av2Error::Schema { .. })
+        ));
+    }
+
+    #[test]
+    fn test_replay_log() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let (log_id, timestamp_ns) = data_loader.get(2).sweep_uuid;
+        let cfg = StreamingConfig {
+            playback_speed: 1e-3,
+        };
+        let outputs = replay_log(&data_loader, &log_id, |_, _| DataFrame::default(), &cfg).unwrap();
+        assert_eq!(outputs.len(), data_loader.len());
+
+        // An unreadable sweep is an error rather than a panic.
+        fs::remove_file(build_lidar_file_path(log_dir, timestamp_ns)).unwrap();
+        let err = replay_log(&data_loader, &log_id, |_, _| DataFrame::default(), &cfg).unwrap_err();
+        assert!(matches!(err.root(), Av2Error::MissingFile { .. }));
+    }
+}
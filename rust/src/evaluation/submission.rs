@@ -0,0 +1,856 @@
+//! # submission
+//!
+//! Leaderboard submission writers.
+//!
+//! Predictions are validated against each challenge's schema and packaged into a zip archive with
+//! the file layout expected by the evaluation servers (see `ChallengeTask::submission_file_name`).
+//! Archives larger than the upload limit are rejected before they are written. End-to-end
+//! forecasting submissions are pickled Python objects and are only supported by the Python API.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{Cursor, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    error::{Av2Error, Av2Result},
+    structures::prediction::{DETECTION_COLUMNS, FORECASTING_COLUMNS, TRACKING_COLUMNS},
+};
+use ::zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+use polars::{
+    export::arrow::{
+        array::{Array, BooleanArray, PrimitiveArray},
+        chunk::Chunk,
+        datatypes::{ArrowDataType, ArrowSchema, Field},
+        io::ipc::write::{Compression, FileWriter, WriteOptions},
+        types::f16,
+    },
+    lazy::dsl::{col, lit},
+    prelude::*,
+};
+use strum_macros::{Display, EnumIter, EnumString};
+
+/// Columns of a scene flow submission file.
+pub const SCENE_FLOW_SUBMISSION_COLUMNS: [&str; 4] =
+    ["flow_tx_m", "flow_ty_m", "flow_tz_m", "is_dynamic"];
+
+/// Number of predicted timesteps per motion forecasting trajectory.
+pub const NUM_PREDICTED_TIMESTEPS: usize = 60;
+
+/// Default maximum size of a submission file (in bytes).
+pub const DEFAULT_MAX_SUBMISSION_SIZE_BYTES: u64 = 2 << 30;
+
+/// Tolerance used when checking that forecasting probabilities are normalized.
+const PROBABILITY_TOLERANCE: f64 = 1e-3;
+
+/// Leaderboard challenge.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ChallengeTask {
+    /// 3D object detection.
+    Detection,
+    /// 3D multi-object tracking.
+    Tracking,
+    /// Motion forecasting.
+    MotionForecasting,
+    /// Scene flow.
+    SceneFlow,
+}
+
+impl ChallengeTask {
+    /// Name of the archive uploaded to the evaluation server (e.g., `detection_submission.zip`).
+    pub fn archive_file_name(&self) -> String {
+        format!("{self}_submission.zip")
+    }
+
+    /// Name of the predictions file within the archive, or `None` if the predictions of each sweep
+    /// are a separate `<log_id>/<timestamp_ns>.feather` file.
+    pub fn submission_file_name(&self) -> Option<&'static str> {
+        match self {
+            ChallengeTask::Detection => Some("detections.feather"),
+            ChallengeTask::Tracking => Some("tracks.feather"),
+            ChallengeTask::MotionForecasting => Some("submission.parquet"),
+            ChallengeTask::SceneFlow => None,
+        }
+    }
+
+    /// Columns required by the challenge.
+    pub fn submission_columns(&self) -> &'static [&'static str] {
+        match self {
+            ChallengeTask::Detection => &DETECTION_COLUMNS,
+            ChallengeTask::Tracking => &TRACKING_COLUMNS,
+            ChallengeTask::MotionForecasting => &FORECASTING_COLUMNS,
+            ChallengeTask::SceneFlow => &SCENE_FLOW_SUBMISSION_COLUMNS,
+        }
+    }
+}
+
+/// Submission writer configuration.
+#[derive(Clone, Debug)]
+pub struct SubmissionConfig {
+    /// Archives larger than this (in bytes) are rejected before being written.
+    pub max_size_bytes: u64,
+}
+
+impl Default for SubmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_size_bytes: DEFAULT_MAX_SUBMISSION_SIZE_BYTES,
+        }
+    }
+}
+
+/// Validate a 3D object detection submission.
+pub fn validate_detections(detections: &DataFrame) -> Av2Result<()> {
+    _validate_columns(detections, "detections", &DETECTION_COLUMNS, false)?;
+    _validate_cuboids(detections, "detections")
+}
+
+/// Validate a 3D multi-object tracking submission.
+///
+/// Each row holds a tracked cuboid, whose `track_id` must be unique within its sweep.
+pub fn validate_tracking(tracks: &DataFrame) -> Av2Result<()> {
+    _validate_columns(tracks, "tracks", &TRACKING_COLUMNS, false)?;
+    _validate_cuboids(tracks, "tracks")?;
+    _validate_dtype(tracks, "tracks", "track_id", &[DataType::String])?;
+    let duplicates = tracks
+        .clone()
+        .lazy()
+        .group_by([col("log_id"), col("timestamp_ns"), col("track_id")])
+        .agg([col("score").count().alias("count")])
+        .filter(col("count").gt(lit(1)))
+        .collect()?;
+    _ensure(duplicates.height() == 0, "tracks", || {
+        format!(
+            "{} track ids appear more than once within a sweep.",
+            duplicates.height()
+        )
+    })
+}
+
+/// Validate a motion forecasting submission.
+///
+/// Each row holds one of the `K` predicted trajectories of a track. The `K` probabilities of every
+/// track must sum to one and each trajectory must contain `NUM_PREDICTED_TIMESTEPS` waypoints.
+pub fn validate_motion_forecasting(predictions: &DataFrame) -> Av2Result<()> {
+    const TABLE: &str = "predictions";
+    _validate_columns(predictions, TABLE, &FORECASTING_COLUMNS, false)?;
+    for column in ["scenario_id", "track_id"] {
+        _validate_dtype(predictions, TABLE, column, &[DataType::String])?;
+    }
+    _ensure(predictions["probability"].dtype().is_float(), TABLE, || {
+        "Column `probability` must be a floating point column."
+    })?;
+    for column in ["predicted_trajectory_x", "predicted_trajectory_y"] {
+        match predictions[column].dtype() {
+            DataType::List(inner) if inner.is_float() => {}
+            dtype => {
+                return Err(Av2Error::schema(
+                    TABLE,
+                    format!("Column `{column}` must be a list of floats, found {dtype}."),
+                ))
+            }
+        }
+        let lengths = predictions
+            .clone()
+            .lazy()
+            .select([col(column).list().len().alias("len")])
+            .filter(col("len").neq(lit(NUM_PREDICTED_TIMESTEPS as u32)))
+            .collect()?;
+        _ensure(lengths.height() == 0, TABLE, || {
+            format!(
+                "{} trajectories in `{column}` do not have {NUM_PREDICTED_TIMESTEPS} timesteps.",
+                lengths.height()
+            )
+        })?;
+    }
+
+    let tracks = predictions
+        .clone()
+        .lazy()
+        .group_by([col("scenario_id"), col("track_id")])
+        .agg([
+            col("probability")
+                .cast(DataType::Float64)
+                .sum()
+                .alias("sum"),
+            col("probability").count().alias("num_trajectories"),
+        ])
+        .collect()?;
+    let unnormalized = tracks
+        .clone()
+        .lazy()
+        .filter(
+            col("sum")
+                .lt(lit(1. - PROBABILITY_TOLERANCE))
+                .or(col("sum").gt(lit(1. + PROBABILITY_TOLERANCE))),
+        )
+        .collect()?;
+    _ensure(unnormalized.height() == 0, TABLE, || {
+        format!(
+            "Trajectory probabilities of {} tracks do not sum to one.",
+            unnormalized.height()
+        )
+    })?;
+
+    let inconsistent = tracks
+        .lazy()
+        .group_by([col("scenario_id")])
+        .agg([col("num_trajectories").n_unique().alias("n_unique")])
+        .filter(col("n_unique").gt(lit(1)))
+        .collect()?;
+    _ensure(inconsistent.height() == 0, TABLE, || {
+        format!(
+            "{} scenarios predict a different number of trajectories per track.",
+            inconsistent.height()
+        )
+    })
+}
+
+/// Validate a single scene flow submission file.
+/// If given, the number of rows must match `expected_num_points` (the number of points in the mask).
+pub fn validate_scene_flow(
+    predictions: &DataFrame,
+    expected_num_points: Option<usize>,
+) -> Av2Result<()> {
+    _validate_scene_flow(predictions, "predictions", expected_num_points)
+}
+
+fn _validate_scene_flow(
+    predictions: &DataFrame,
+    table: &str,
+    expected_num_points: Option<usize>,
+) -> Av2Result<()> {
+    _validate_columns(predictions, table, &SCENE_FLOW_SUBMISSION_COLUMNS, true)?;
+    for column in SCENE_FLOW_SUBMISSION_COLUMNS[..3].iter() {
+        _ensure(predictions[*column].dtype().is_float(), table, || {
+            format!("Column `{column}` must be a floating point column.")
+        })?;
+    }
+    _validate_dtype(predictions, table, "is_dynamic", &[DataType::Boolean])?;
+    if let Some(expected_num_points) = expected_num_points {
+        _ensure(predictions.height() == expected_num_points, table, || {
+            format!(
+                "Submission has {} rows but it should have {expected_num_points}.",
+                predictions.height()
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Validate and write a 3D object detection submission archive into `output_dir`.
+/// Returns the path of the written archive.
+pub fn write_detection_submission(
+    detections: &DataFrame,
+    output_dir: &Path,
+    cfg: &SubmissionConfig,
+) -> Av2Result<PathBuf> {
+    validate_detections(detections)?;
+    let buffer = _to_feather(detections, &DETECTION_COLUMNS)?;
+    _write_archive(ChallengeTask::Detection, &[(None, buffer)], output_dir, cfg)
+}
+
+/// Validate and write a 3D multi-object tracking submission archive into `output_dir`.
+/// Returns the path of the written archive.
+pub fn write_tracking_submission(
+    tracks: &DataFrame,
+    output_dir: &Path,
+    cfg: &SubmissionConfig,
+) -> Av2Result<PathBuf> {
+    validate_tracking(tracks)?;
+    let buffer = _to_feather(tracks, &TRACKING_COLUMNS)?;
+    _write_archive(ChallengeTask::Tracking, &[(None, buffer)], output_dir, cfg)
+}
+
+/// Validate and write a motion forecasting submission archive into `output_dir`.
+/// Returns the path of the written archive.
+pub fn write_motion_forecasting_submission(
+    predictions: &DataFrame,
+    output_dir: &Path,
+    cfg: &SubmissionConfig,
+) -> Av2Result<PathBuf> {
+    validate_motion_forecasting(predictions)?;
+    let mut submission = predictions.select(FORECASTING_COLUMNS)?;
+    let mut buffer = vec![];
+    ParquetWriter::new(&mut buffer).finish(&mut submission)?;
+    _write_archive(
+        ChallengeTask::MotionForecasting,
+        &[(None, buffer)],
+        output_dir,
+        cfg,
+    )
+}
+
+/// Validate and write a scene flow submission archive into `output_dir`.
+///
+/// `predictions` maps `(log_id, timestamp_ns)` to the sweep's predictions. If `mask_counts` is given
+/// (see `read_scene_flow_mask_counts`), every masked sweep must be predicted with the masked number
+/// of points. Flow is stored as half-precision as required by the evaluation server.
+pub fn write_scene_flow_submission(
+    predictions: &BTreeMap<(String, u64), DataFrame>,
+    mask_counts: Option<&BTreeMap<(String, u64), usize>>,
+    output_dir: &Path,
+    cfg: &SubmissionConfig,
+) -> Av2Result<PathBuf> {
+    if let Some(mask_counts) = mask_counts {
+        for (log_id, timestamp_ns) in mask_counts.keys() {
+            _ensure(
+                predictions.contains_key(&(log_id.clone(), *timestamp_ns)),
+                "predictions",
+                || format!("Missing predictions for {log_id}/{timestamp_ns}."),
+            )?;
+        }
+    }
+
+    let mut files = vec![];
+    for ((log_id, timestamp_ns), frame) in predictions.iter() {
+        let expected_num_points = match mask_counts {
+            Some(mask_counts) => Some(
+                *mask_counts
+                    .get(&(log_id.clone(), *timestamp_ns))
+                    .ok_or_else(|| {
+                        Av2Error::schema(
+                            "predictions",
+                            format!("Unexpected predictions for {log_id}/{timestamp_ns}."),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+        _validate_scene_flow(
+            frame,
+            &format!("predictions at {timestamp_ns}"),
+            expected_num_points,
+        )
+        .map_err(|err| err.in_log(log_id))?;
+        files.push((
+            Some(format!("{log_id}/{timestamp_ns}.feather")),
+            _scene_flow_to_feather(frame)?,
+        ));
+    }
+    _write_archive(ChallengeTask::SceneFlow, &files, output_dir, cfg)
+}
+
+/// Read the number of evaluated points of each sweep from a scene flow mask archive.
+pub fn read_scene_flow_mask_counts(mask_file: &Path) -> Av2Result<BTreeMap<(String, u64), usize>> {
+    let decode = |message: String| Av2Error::decode(mask_file, message);
+    let file = File::open(mask_file).map_err(|err| Av2Error::open(mask_file, err))?;
+    let mut archive = ZipArchive::new(file).map_err(|err| decode(err.to_string()))?;
+    let mut mask_counts = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|err| decode(err.to_string()))?;
+        let name = entry.name().to_string();
+        let Some(stem) = name.strip_suffix(".feather") else {
+            continue;
+        };
+        let (log_id, timestamp_ns) = stem
+            .rsplit_once('/')
+            .and_then(|(log_id, x)| Some((log_id, x.parse::<u64>().ok()?)))
+            .ok_or_else(|| decode(format!("Unexpected mask file name: {name}.")))?;
+
+        let mut buffer = vec![];
+        entry.read_to_end(&mut buffer)?;
+        let mask = IpcReader::new(Cursor::new(buffer))
+            .finish()
+            .map_err(|err| decode(format!("{name}: {err}")))?;
+        let num_points = mask
+            .get_columns()
+            .first()
+            .ok_or_else(|| decode(format!("Empty mask file: {name}.")))?
+            .cast(&DataType::UInt32)?
+            .sum::<usize>()
+            .unwrap_or(0);
+        mask_counts.insert((log_id.to_string(), timestamp_ns), num_points);
+    }
+    Ok(mask_counts)
+}
+
+/// Serialize scene flow predictions as a feather file with half-precision flow.
+fn _scene_flow_to_feather(predictions: &DataFrame) -> Av2Result<Vec<u8>> {
+    let mut fields = vec![];
+    let mut arrays: Vec<Box<dyn Array>> = vec![];
+    for column in SCENE_FLOW_SUBMISSION_COLUMNS[..3].iter() {
+        let values = predictions[*column]
+            .cast(&DataType::Float32)?
+            .f32()?
+            .into_iter()
+            .map(|x| x.map(f16::from_f32))
+            .collect::<Vec<_>>();
+        fields.push(Field::new(*column, ArrowDataType::Float16, true));
+        arrays.push(PrimitiveArray::<f16>::from(values).boxed());
+    }
+    let is_dynamic = predictions["is_dynamic"]
+        .bool()?
+        .into_iter()
+        .collect::<Vec<_>>();
+    fields.push(Field::new("is_dynamic", ArrowDataType::Boolean, true));
+    arrays.push(BooleanArray::from(is_dynamic).boxed());
+
+    let mut buffer = vec![];
+    let options = WriteOptions {
+        compression: Some(Compression::LZ4),
+    };
+    let mut writer = FileWriter::try_new(
+        &mut buffer,
+        Arc::new(ArrowSchema::from(fields)),
+        None,
+        options,
+    )?;
+    writer.write(&Chunk::new(arrays), None)?;
+    writer.finish()?;
+    Ok(buffer)
+}
+
+/// Zip the `files` of a submission, check the archive size, and write it into `output_dir`.
+/// Files without a name are named after the task (see `ChallengeTask::submission_file_name`).
+fn _write_archive(
+    task: ChallengeTask,
+    files: &[(Option<String>, Vec<u8>)],
+    output_dir: &Path,
+    cfg: &SubmissionConfig,
+) -> Av2Result<PathBuf> {
+    let mut archive = ZipWriter::new(Cursor::new(vec![]));
+    // The files are compressed already.
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+    for (name, buffer) in files {
+        let name = match name {
+            Some(name) => name.as_str(),
+            None => task.submission_file_name().ok_or_else(|| {
+                Av2Error::invalid_parameter(
+                    "files",
+                    format!("Unnamed file of the {task} submission."),
+                )
+            })?,
+        };
+        archive
+            .start_file(name, options)
+            .map_err(std::io::Error::from)?;
+        archive.write_all(buffer)?;
+    }
+    let buffer = archive.finish().map_err(std::io::Error::from)?.into_inner();
+    if buffer.len() as u64 > cfg.max_size_bytes {
+        return Err(Av2Error::invalid_parameter(
+            "max_size_bytes",
+            format!(
+                "The {task} submission is {} bytes, which exceeds the limit of {} bytes.",
+                buffer.len(),
+                cfg.max_size_bytes
+            ),
+        ));
+    }
+    fs::create_dir_all(output_dir)?;
+    let path = output_dir.join(task.archive_file_name());
+    fs::write(&path, buffer)?;
+    Ok(path)
+}
+
+/// Serialize the `columns` of a data-frame as an LZ4-compressed feather file.
+fn _to_feather(data_frame: &DataFrame, columns: &[&str]) -> Av2Result<Vec<u8>> {
+    let mut submission = data_frame.select(columns)?;
+    let mut buffer = vec![];
+    IpcWriter::new(&mut buffer)
+        .with_compression(Some(IpcCompression::LZ4))
+        .finish(&mut submission)?;
+    Ok(buffer)
+}
+
+/// Check the dtypes, dimensions, and scores of the cuboids of a detection or tracking submission.
+fn _validate_cuboids(cuboids: &DataFrame, name: &str) -> Av2Result<()> {
+    for column in ["log_id", "category"] {
+        _validate_dtype(cuboids, name, column, &[DataType::String])?;
+    }
+    for column in DETECTION_COLUMNS[..11].iter() {
+        _ensure(cuboids[*column].dtype().is_float(), name, || {
+            format!("Column `{column}` must be a floating point column.")
+        })?;
+    }
+    _ensure(cuboids["timestamp_ns"].dtype().is_integer(), name, || {
+        "Column `timestamp_ns` must be an integer column."
+    })?;
+
+    let invalid = cuboids
+        .clone()
+        .lazy()
+        .filter(
+            col("length_m")
+                .lt_eq(lit(0.))
+                .or(col("width_m").lt_eq(lit(0.)))
+                .or(col("height_m").lt_eq(lit(0.)))
+                .or(col("score").lt(lit(0.)))
+                .or(col("score").gt(lit(1.))),
+        )
+        .collect()?;
+    _ensure(invalid.height() == 0, name, || {
+        format!(
+            "{} {name} have non-positive dimensions or scores outside of [0, 1].",
+            invalid.height()
+        )
+    })
+}
+
+/// Check that all `columns` are present (and, if `exact`, that no others are).
+fn _validate_columns(
+    data_frame: &DataFrame,
+    table: &str,
+    columns: &[&str],
+    exact: bool,
+) -> Av2Result<()> {
+    let names = data_frame.get_column_names();
+    for column in columns {
+        _ensure(names.contains(column), table, || {
+            format!("Missing column `{column}`.")
+        })?;
+    }
+    if exact {
+        if let Some(extra) = names.iter().find(|name| !columns.contains(name)) {
+            return Err(Av2Error::schema(
+                table,
+                format!("Unexpected column `{extra}`."),
+            ));
+        }
+    }
+    for column in columns {
+        _ensure(data_frame[*column].null_count() == 0, table, || {
+            format!("Column `{column}` contains nulls.")
+        })?;
+    }
+    Ok(())
+}
+
+fn _validate_dtype(
+    data_frame: &DataFrame,
+    table: &str,
+    column: &str,
+    dtypes: &[DataType],
+) -> Av2Result<()> {
+    let dtype = data_frame[column].dtype();
+    _ensure(dtypes.contains(dtype), table, || {
+        format!("Column `{column}` has type {dtype}, expected one of {dtypes:?}.")
+    })
+}
+
+/// Schema error of the `table` with the `message` unless `condition` holds.
+fn _ensure<M: ToString>(
+    condition: bool,
+    table: &str,
+    message: impl FnOnce() -> M,
+) -> Av2Result<()> {
+    match condition {
+        true => Ok(()),
+        false => Err(Av2Error::schema(table, message())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        fs::File,
+        io::{Cursor, Read, Write},
+        path::Path,
+    };
+
+    use ::zip::{write::FileOptions, ZipArchive, ZipWriter};
+    use polars::prelude::*;
+
+    use super::{
+        read_scene_flow_mask_counts, validate_detections, validate_motion_forecasting,
+        validate_scene_flow, validate_tracking, write_detection_submission,
+        write_motion_forecasting_submission, write_scene_flow_submission,
+        write_tracking_submission, SubmissionConfig, NUM_PREDICTED_TIMESTEPS,
+    };
+    use crate::{error::Av2Result, synthetic::test_dir};
+
+    /// Detections of two unit cuboids in one sweep.
+    fn detections() -> DataFrame {
+        df!(
+            "tx_m" => [1_f32, 2.],
+            "ty_m" => [0_f32, 0.],
+            "tz_m" => [0_f32, 0.],
+            "length_m" => [1_f32, 1.],
+            "width_m" => [1_f32, 1.],
+            "height_m" => [1_f32, 1.],
+            "qw" => [1_f32, 1.],
+            "qx" => [0_f32, 0.],
+            "qy" => [0_f32, 0.],
+            "qz" => [0_f32, 0.],
+            "score" => [0.9_f32, 0.5],
+            "log_id" => ["log", "log"],
+            "timestamp_ns" => [0_u64, 0],
+            "category" => ["BUS", "BUS"]
+        )
+        .unwrap()
+    }
+
+    /// Two forecasts of a track with `num_timesteps` waypoints.
+    fn forecasts(probabilities: [f32; 2], num_timesteps: usize) -> DataFrame {
+        let trajectory = Series::new("", vec![0_f32; num_timesteps]);
+        df!(
+            "scenario_id" => ["scenario", "scenario"],
+            "track_id" => ["track", "track"],
+            "probability" => probabilities,
+            "predicted_trajectory_x" => [trajectory.clone(), trajectory.clone()],
+            "predicted_trajectory_y" => [trajectory.clone(), trajectory]
+        )
+        .unwrap()
+    }
+
+    fn scene_flow(num_points: usize) -> DataFrame {
+        df!(
+            "flow_tx_m" => vec![0.5_f32; num_points],
+            "flow_ty_m" => vec![-1_f32; num_points],
+            "flow_tz_m" => vec![0_f32; num_points],
+            "is_dynamic" => vec![true; num_points]
+        )
+        .unwrap()
+    }
+
+    /// Names and contents of the files of a zip archive.
+    fn read_archive(path: &Path) -> BTreeMap<String, Vec<u8>> {
+        let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        (0..archive.len())
+            .map(|i| {
+                let mut file = archive.by_index(i).unwrap();
+                let mut buffer = vec![];
+                file.read_to_end(&mut buffer).unwrap();
+                (file.name().to_string(), buffer)
+            })
+            .collect()
+    }
+
+    fn assert_rejected(result: Av2Result<()>, message: &str) {
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains(message), "{err}");
+    }
+
+    #[test]
+    fn test_validate_detections() {
+        validate_detections(&detections()).unwrap();
+        let missing = detections().drop("score").unwrap();
+        assert_rejected(validate_detections(&missing), "Missing column `score`");
+        let mut nulls = detections();
+        nulls
+            .with_column(Series::new("category", [Some("BUS"), None]))
+            .unwrap();
+        assert_rejected(validate_detections(&nulls), "contains nulls");
+        let mut strings = detections();
+        strings
+            .with_column(Series::new("tx_m", ["1", "2"]))
+            .unwrap();
+        assert_rejected(validate_detections(&strings), "floating point");
+        let mut timestamps = detections();
+        timestamps
+            .with_column(Series::new("timestamp_ns", [0_f64, 0.]))
+            .unwrap();
+        assert_rejected(validate_detections(&timestamps), "integer");
+        let mut flat = detections();
+        flat.with_column(Series::new("height_m", [1_f32, 0.]))
+            .unwrap();
+        assert_rejected(validate_detections(&flat), "1 detections have non-positive");
+        let mut scores = detections();
+        scores
+            .with_column(Series::new("score", [1.5_f32, -0.5]))
+            .unwrap();
+        assert_rejected(validate_detections(&scores), "2 detections have");
+    }
+
+    #[test]
+    fn test_validate_tracking() {
+        let mut tracks = detections();
+        tracks
+            .with_column(Series::new("track_id", ["a", "b"]))
+            .unwrap();
+        validate_tracking(&tracks).unwrap();
+        assert_rejected(
+            validate_tracking(&detections()),
+            "Missing column `track_id`",
+        );
+        let mut ids = tracks.clone();
+        ids.with_column(Series::new("track_id", [0_u32, 1]))
+            .unwrap();
+        assert_rejected(validate_tracking(&ids), "Column `track_id` has type");
+        let mut duplicates = tracks.clone();
+        duplicates
+            .with_column(Series::new("track_id", ["a", "a"]))
+            .unwrap();
+        assert_rejected(validate_tracking(&duplicates), "1 track ids appear");
+        // The same id in another sweep is the same track.
+        duplicates
+            .with_column(Series::new("timestamp_ns", [0_u64, 1]))
+            .unwrap();
+        validate_tracking(&duplicates).unwrap();
+    }
+
+    #[test]
+    fn test_validate_motion_forecasting() {
+        validate_motion_forecasting(&forecasts([0.6, 0.4], NUM_PREDICTED_TIMESTEPS)).unwrap();
+        assert_rejected(
+            validate_motion_forecasting(&forecasts([0.6, 0.6], NUM_PREDICTED_TIMESTEPS)),
+            "1 tracks do not sum to one",
+        );
+        assert_rejected(
+            validate_motion_forecasting(&forecasts([0.6, 0.4], 30)),
+            "2 trajectories in `predicted_trajectory_x`",
+        );
+        let mut scalars = forecasts([0.6, 0.4], NUM_PREDICTED_TIMESTEPS);
+        scalars
+            .with_column(Series::new("predicted_trajectory_y", [0_f32, 0.]))
+            .unwrap();
+        assert_rejected(
+            validate_motion_forecasting(&scalars),
+            "must be a list of floats",
+        );
+        // Another track of the scenario with a single forecast.
+        let mut inconsistent = forecasts([0.6, 0.4], NUM_PREDICTED_TIMESTEPS);
+        let mut single = forecasts([1., 0.], NUM_PREDICTED_TIMESTEPS).head(Some(1));
+        single
+            .with_column(Series::new("track_id", ["other"]))
+            .unwrap();
+        inconsistent.vstack_mut(&single).unwrap();
+        assert_rejected(
+            validate_motion_forecasting(&inconsistent),
+            "1 scenarios predict a different number",
+        );
+    }
+
+    #[test]
+    fn test_validate_scene_flow() {
+        validate_scene_flow(&scene_flow(3), Some(3)).unwrap();
+        assert_rejected(
+            validate_scene_flow(&scene_flow(3), Some(4)),
+            "Submission has 3 rows but it should have 4",
+        );
+        let mut extra = scene_flow(3);
+        extra.with_column(Series::new("x", [0_f32; 3])).unwrap();
+        assert_rejected(validate_scene_flow(&extra, None), "Unexpected column `x`");
+        let mut flags = scene_flow(3);
+        flags
+            .with_column(Series::new("is_dynamic", [1_u8; 3]))
+            .unwrap();
+        assert_rejected(
+            validate_scene_flow(&flags, None),
+            "Column `is_dynamic` has type",
+        );
+    }
+
+    #[test]
+    fn test_write_submissions() {
+        let output_dir = test_dir();
+        let cfg = SubmissionConfig::default();
+
+        // Extra columns are dropped.
+        let mut detections = detections();
+        detections
+            .with_column(Series::new("num_interior_pts", [1_u32, 2]))
+            .unwrap();
+        let path = write_detection_submission(&detections, &output_dir, &cfg).unwrap();
+        assert_eq!(path, output_dir.join("detection_submission.zip"));
+        let files = read_archive(&path);
+        assert_eq!(files.keys().collect::<Vec<_>>(), ["detections.feather"]);
+        let written = IpcReader::new(Cursor::new(&files["detections.feather"]))
+            .finish()
+            .unwrap();
+        assert!(written.equals(&detections.drop("num_interior_pts").unwrap()));
+
+        let mut tracks = detections.drop("num_interior_pts").unwrap();
+        tracks
+            .with_column(Series::new("track_id", ["a", "b"]))
+            .unwrap();
+        let path = write_tracking_submission(&tracks, &output_dir, &cfg).unwrap();
+        assert_eq!(path, output_dir.join("tracking_submission.zip"));
+        let files = read_archive(&path);
+        assert_eq!(files.keys().collect::<Vec<_>>(), ["tracks.feather"]);
+        let written = IpcReader::new(Cursor::new(&files["tracks.feather"]))
+            .finish()
+            .unwrap();
+        assert!(written.equals(&tracks));
+
+        let predictions = forecasts([0.6, 0.4], NUM_PREDICTED_TIMESTEPS);
+        let path = write_motion_forecasting_submission(&predictions, &output_dir, &cfg).unwrap();
+        assert_eq!(path, output_dir.join("motion_forecasting_submission.zip"));
+        let files = read_archive(&path);
+        assert_eq!(files.keys().collect::<Vec<_>>(), ["submission.parquet"]);
+        let written = ParquetReader::new(Cursor::new(&files["submission.parquet"]))
+            .finish()
+            .unwrap();
+        assert!(written.equals(&predictions));
+
+        // Archives above the size limit are not written.
+        let small_cfg = SubmissionConfig {
+            max_size_bytes: 100,
+        };
+        let result = write_tracking_submission(&tracks, &test_dir(), &small_cfg);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn test_write_scene_flow_submission() {
+        let root_dir = test_dir();
+
+        // Mask archive of two sweeps of a log.
+        let mask_path = root_dir.join("masks.zip");
+        let mut archive = ZipWriter::new(File::create(&mask_path).unwrap());
+        for (timestamp_ns, mask) in [(0_u64, [true, false, true]), (1, [true, true, true])] {
+            let mut mask = df!("mask" => mask).unwrap();
+            let mut buffer = vec![];
+            IpcWriter::new(&mut buffer).finish(&mut mask).unwrap();
+            archive
+                .start_file(
+                    format!("log/{timestamp_ns}.feather"),
+                    FileOptions::default(),
+                )
+                .unwrap();
+            archive.write_all(&buffer).unwrap();
+        }
+        archive.finish().unwrap();
+        let mask_counts = read_scene_flow_mask_counts(&mask_path).unwrap();
+        assert_eq!(
+            mask_counts,
+            BTreeMap::from([(("log".to_string(), 0), 2), (("log".to_string(), 1), 3)])
+        );
+
+        let cfg = SubmissionConfig::default();
+        let output_dir = root_dir.join("output");
+        let mut predictions = BTreeMap::from([(("log".to_string(), 0), scene_flow(2))]);
+        let result =
+            write_scene_flow_submission(&predictions, Some(&mask_counts), &output_dir, &cfg);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Missing predictions for log/1"));
+        predictions.insert(("log".to_string(), 1), scene_flow(2));
+        let result =
+            write_scene_flow_submission(&predictions, Some(&mask_counts), &output_dir, &cfg);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Log `log`: Schema mismatch of the predictions at 1"));
+
+        predictions.insert(("log".to_string(), 1), scene_flow(3));
+        let path = write_scene_flow_submission(&predictions, Some(&mask_counts), &output_dir, &cfg)
+            .unwrap();
+        assert_eq!(path, output_dir.join("scene_flow_submission.zip"));
+        let files = read_archive(&path);
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            ["log/0.feather", "log/1.feather"]
+        );
+        // The flow is written as half-precision floats.
+        let written = IpcReader::new(Cursor::new(&files["log/1.feather"]))
+            .finish()
+            .unwrap();
+        assert_eq!(written.height(), 3);
+        let flow = written["flow_ty_m"].cast(&DataType::Float32).unwrap();
+        assert_eq!(flow.f32().unwrap().get(0), Some(-1.));
+        assert_eq!(written["is_dynamic"].bool().unwrap().get(2), Some(true));
+    }
+}
@@ -0,0 +1,707 @@
+//! # tbv
+//!
+//! Map change detection evaluation for the Trust, but Verify (TbV) dataset.
+//!
+//! Predictions are scored per-frame (is the map stale at this timestamp?) and per-log
+//! (is the map stale anywhere in this log?). Optionally, predicted change locations are
+//! scored against the annotated change locations within a localization radius.
+
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+use polars::prelude::*;
+
+use super::bootstrap::{bootstrap, BootstrapConfig, ConfidenceInterval, Metrics};
+use crate::{
+    error::Av2Result,
+    schema::{validate_data_frame, ColumnType},
+};
+
+/// Columns of the per-frame change predictions.
+const PREDICTION_SCHEMA: [(&str, ColumnType); 3] = [
+    ("log_id", ColumnType::String),
+    ("timestamp_ns", ColumnType::Integer),
+    ("change_score", ColumnType::Float),
+];
+
+/// Columns of the per-frame change labels.
+const LABEL_SCHEMA: [(&str, ColumnType); 3] = [
+    ("log_id", ColumnType::String),
+    ("timestamp_ns", ColumnType::Integer),
+    ("is_changed", ColumnType::Boolean),
+];
+
+/// Columns of the (predicted or labeled) change locations.
+const LOCATION_SCHEMA: [(&str, ColumnType); 4] = [
+    ("log_id", ColumnType::String),
+    ("timestamp_ns", ColumnType::Integer),
+    ("x_city_m", ColumnType::Float),
+    ("y_city_m", ColumnType::Float),
+];
+
+/// Default score threshold above which a frame is classified as changed.
+pub const DEFAULT_CHANGE_THRESHOLD: f32 = 0.5;
+
+/// Default radius (in meters) within which a predicted change location matches an annotated one.
+pub const DEFAULT_LOCALIZATION_RADIUS_M: f32 = 5.0;
+
+/// TbV evaluation configuration.
+#[derive(Clone, Debug)]
+pub struct TbvEvaluationConfig {
+    /// Score threshold above which a frame is classified as changed.
+    pub change_threshold: f32,
+    /// Radius (in meters) within which a predicted change location matches an annotated one.
+    pub localization_radius_m: f32,
+}
+
+impl Default for TbvEvaluationConfig {
+    fn default() -> Self {
+        Self {
+            change_threshold: DEFAULT_CHANGE_THRESHOLD,
+            localization_radius_m: DEFAULT_LOCALIZATION_RADIUS_M,
+        }
+    }
+}
+
+/// Binary classification metrics for the `changed` vs. `unchanged` classes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassificationMetrics {
+    /// Number of changed samples classified as changed.
+    pub num_true_positives: usize,
+    /// Number of unchanged samples classified as changed.
+    pub num_false_positives: usize,
+    /// Number of unchanged samples classified as unchanged.
+    pub num_true_negatives: usize,
+    /// Number of changed samples classified as unchanged.
+    pub num_false_negatives: usize,
+    /// Accuracy over the changed samples.
+    pub changed_accuracy: f32,
+    /// Accuracy over the unchanged samples.
+    pub unchanged_accuracy: f32,
+    /// Mean of the per-class accuracies (mAcc).
+    pub mean_accuracy: f32,
+    /// Precision of the changed class.
+    pub precision: f32,
+    /// Recall of the changed class.
+    pub recall: f32,
+    /// Harmonic mean of precision and recall.
+    pub f1: f32,
+    /// Average precision of the changed class computed from the raw scores.
+    pub average_precision: f32,
+}
+
+impl ClassificationMetrics {
+    /// Summary metrics keyed by name with the given `prefix` (e.g., `frame_mean_accuracy`).
+    pub fn as_metrics(&self, prefix: &str) -> Metrics {
+        Metrics::from([
+            (format!("{prefix}_mean_accuracy"), self.mean_accuracy),
+            (format!("{prefix}_precision"), self.precision),
+            (format!("{prefix}_recall"), self.recall),
+            (format!("{prefix}_f1"), self.f1),
+            (
+                format!("{prefix}_average_precision"),
+                self.average_precision,
+            ),
+        ])
+    }
+}
+
+/// Spatial localization metrics for predicted change locations.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LocalizationMetrics {
+    /// Number of predicted locations matched to an annotated change.
+    pub num_true_positives: usize,
+    /// Number of predicted locations without a matching annotated change.
+    pub num_false_positives: usize,
+    /// Number of annotated changes without a matching prediction.
+    pub num_false_negatives: usize,
+    /// Fraction of predicted locations which are matched.
+    pub precision: f32,
+    /// Fraction of annotated changes which are matched.
+    pub recall: f32,
+    /// Harmonic mean of precision and recall.
+    pub f1: f32,
+}
+
+/// TbV evaluation results.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TbvMetrics {
+    /// Per-frame classification metrics.
+    pub frame: ClassificationMetrics,
+    /// Per-log classification metrics (a log is changed if any of its frames is changed).
+    pub log: ClassificationMetrics,
+    /// Spatial localization metrics (only if change locations were provided).
+    pub localization: Option<LocalizationMetrics>,
+}
+
+/// Evaluate TbV map change predictions.
+///
+/// `predictions` must contain `log_id`, `timestamp_ns`, and `change_score` columns.
+/// `labels` must contain `log_id`, `timestamp_ns`, and a boolean `is_changed` column.
+/// Labeled frames without a prediction are scored as unchanged (`change_score` of zero).
+///
+/// If provided, the change location frames must both contain `log_id`, `timestamp_ns`,
+/// `x_city_m`, and `y_city_m` columns, and the predicted locations a `change_score` column.
+/// Logs are aggregated in log id order.
+pub fn evaluate(
+    predictions: &DataFrame,
+    labels: &DataFrame,
+    predicted_locations: Option<&DataFrame>,
+    labeled_locations: Option<&DataFrame>,
+    cfg: &TbvEvaluationConfig,
+) -> Av2Result<TbvMetrics> {
+    let (log_ids, scores, is_changed) = _join_frames(predictions, labels)?;
+    let frame = compute_classification_metrics(&scores, &is_changed, cfg.change_threshold);
+
+    // Aggregate to the log level: maximum score and any change.
+    let mut log_scores: BTreeMap<&str, (f32, bool)> = BTreeMap::new();
+    for ((log_id, score), changed) in log_ids.iter().zip(&scores).zip(&is_changed) {
+        let entry = log_scores
+            .entry(log_id.as_str())
+            .or_insert((f32::NEG_INFINITY, false));
+        entry.0 = entry.0.max(*score);
+        entry.1 |= *changed;
+    }
+    let (log_scores, log_is_changed): (Vec<_>, Vec<_>) = log_scores.into_values().unzip();
+    let log = compute_classification_metrics(&log_scores, &log_is_changed, cfg.change_threshold);
+
+    let localization = match (predicted_locations, labeled_locations) {
+        (Some(predicted_locations), Some(labeled_locations)) => Some(compute_localization_metrics(
+            predicted_locations,
+            labeled_locations,
+            cfg.localization_radius_m,
+        )?),
+        _ => None,
+    };
+
+    Ok(TbvMetrics {
+        frame,
+        log,
+        localization,
+    })
+}
+
+/// Compute bootstrapped confidence intervals of the frame and log classification metrics.
+/// Logs are resampled with replacement.
+pub fn evaluate_bootstrap(
+    predictions: &DataFrame,
+    labels: &DataFrame,
+    cfg: &TbvEvaluationConfig,
+    bootstrap_cfg: &BootstrapConfig,
+) -> Av2Result<BTreeMap<String, ConfidenceInterval>> {
+    let (log_ids, scores, is_changed) = _join_frames(predictions, labels)?;
+
+    // Logs are ordered by id so that the replicates of a seed are reproducible.
+    let mut logs: BTreeMap<&str, (Vec<f32>, Vec<bool>)> = BTreeMap::new();
+    for ((log_id, score), changed) in log_ids.iter().zip(scores).zip(is_changed) {
+        let entry = logs.entry(log_id.as_str()).or_default();
+        entry.0.push(score);
+        entry.1.push(changed);
+    }
+    let logs = logs.into_values().collect_vec();
+
+    let metric_fn = |logs: &[&(Vec<f32>, Vec<bool>)]| {
+        let scores = logs.iter().flat_map(|x| x.0.iter().copied()).collect_vec();
+        let is_changed = logs.iter().flat_map(|x| x.1.iter().copied()).collect_vec();
+        let log_scores = logs
+            .iter()
+            .map(|x| x.0.iter().copied().fold(f32::NEG_INFINITY, f32::max))
+            .collect_vec();
+        let log_is_changed = logs.iter().map(|x| x.1.iter().any(|y| *y)).collect_vec();
+
+        let mut metrics =
+            compute_classification_metrics(&scores, &is_changed, cfg.change_threshold)
+                .as_metrics("frame");
+        metrics.extend(
+            compute_classification_metrics(&log_scores, &log_is_changed, cfg.change_threshold)
+                .as_metrics("log"),
+        );
+        metrics
+    };
+    bootstrap(&logs, metric_fn, bootstrap_cfg)
+}
+
+/// Compute binary classification metrics from `scores` thresholded at `threshold`.
+pub fn compute_classification_metrics(
+    scores: &[f32],
+    is_changed: &[bool],
+    threshold: f32,
+) -> ClassificationMetrics {
+    let mut metrics = ClassificationMetrics::default();
+    for (score, changed) in scores.iter().zip(is_changed) {
+        match (*score >= threshold, *changed) {
+            (true, true) => metrics.num_true_positives += 1,
+            (true, false) => metrics.num_false_positives += 1,
+            (false, false) => metrics.num_true_negatives += 1,
+            (false, true) => metrics.num_false_negatives += 1,
+        }
+    }
+
+    let (tp, fp, tn, fn_) = (
+        metrics.num_true_positives as f32,
+        metrics.num_false_positives as f32,
+        metrics.num_true_negatives as f32,
+        metrics.num_false_negatives as f32,
+    );
+    metrics.changed_accuracy = _safe_divide(tp, tp + fn_);
+    metrics.unchanged_accuracy = _safe_divide(tn, tn + fp);
+    metrics.mean_accuracy = (metrics.changed_accuracy + metrics.unchanged_accuracy) / 2.;
+    metrics.precision = _safe_divide(tp, tp + fp);
+    metrics.recall = metrics.changed_accuracy;
+    metrics.f1 = _safe_divide(
+        2. * metrics.precision * metrics.recall,
+        metrics.precision + metrics.recall,
+    );
+    metrics.average_precision = compute_average_precision(scores, is_changed);
+    metrics
+}
+
+/// Compute the (non-interpolated) average precision of the positive class.
+pub fn compute_average_precision(scores: &[f32], is_positive: &[bool]) -> f32 {
+    let num_positives = is_positive.iter().filter(|x| **x).count();
+    if num_positives == 0 {
+        return 0.;
+    }
+
+    let order = (0..scores.len())
+        .sorted_by(|&i, &j| scores[j].total_cmp(&scores[i]))
+        .collect_vec();
+    let mut num_true_positives = 0;
+    let mut average_precision = 0.;
+    for (k, i) in order.into_iter().enumerate() {
+        if is_positive[i] {
+            num_true_positives += 1;
+            average_precision += num_true_positives as f32 / (k + 1) as f32;
+        }
+    }
+    average_precision / num_positives as f32
+}
+
+/// Greedily match predicted change locations to labeled change locations within each frame.
+/// Predictions are matched in descending `change_score` order to the nearest unmatched label.
+pub fn compute_localization_metrics(
+    predicted_locations: &DataFrame,
+    labeled_locations: &DataFrame,
+    localization_radius_m: f32,
+) -> Av2Result<LocalizationMetrics> {
+    let predicted = _group_locations_by_frame(
+        "predicted locations",
+        predicted_locations,
+        Some("change_score"),
+    )?;
+    let labeled = _group_locations_by_frame("labeled locations", labeled_locations, None)?;
+
+    let mut metrics = LocalizationMetrics::default();
+    for (frame, labeled_xy) in labeled.iter() {
+        let predicted_xy = predicted.get(frame).cloned().unwrap_or_default();
+        let mut is_matched = vec![false; labeled_xy.len()];
+        for (px, py, _) in predicted_xy
+            .into_iter()
+            .sorted_by(|a, b| b.2.total_cmp(&a.2))
+        {
+            let nearest = labeled_xy
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| !is_matched[*j])
+                .map(|(j, (lx, ly, _))| (j, (px - lx).hypot(py - ly)))
+                .filter(|(_, distance)| *distance <= localization_radius_m)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            match nearest {
+                Some((j, _)) => {
+                    is_matched[j] = true;
+                    metrics.num_true_positives += 1;
+                }
+                None => metrics.num_false_positives += 1,
+            }
+        }
+        metrics.num_false_negatives += is_matched.iter().filter(|x| !**x).count();
+    }
+
+    // Predictions in frames without any labeled change are false positives.
+    metrics.num_false_positives += predicted
+        .iter()
+        .filter(|(frame, _)| !labeled.contains_key(*frame))
+        .map(|(_, xy)| xy.len())
+        .sum::<usize>();
+
+    let (tp, fp, fn_) = (
+        metrics.num_true_positives as f32,
+        metrics.num_false_positives as f32,
+        metrics.num_false_negatives as f32,
+    );
+    metrics.precision = _safe_divide(tp, tp + fp);
+    metrics.recall = _safe_divide(tp, tp + fn_);
+    metrics.f1 = _safe_divide(
+        2. * metrics.precision * metrics.recall,
+        metrics.precision + metrics.recall,
+    );
+    Ok(metrics)
+}
+
+/// Left join predictions onto the labeled frames.
+/// Returns the log ids, change scores, and change labels of every labeled frame.
+fn _join_frames(
+    predictions: &DataFrame,
+    labels: &DataFrame,
+) -> Av2Result<(Vec<String>, Vec<f32>, Vec<bool>)> {
+    validate_data_frame("predictions", predictions, &PREDICTION_SCHEMA)?;
+    validate_data_frame("labels", labels, &LABEL_SCHEMA)?;
+    let keys = [col("log_id"), col("timestamp_ns")];
+    let frames = labels
+        .clone()
+        .lazy()
+        .select([
+            col("log_id"),
+            col("timestamp_ns").cast(DataType::UInt64),
+            col("is_changed"),
+        ])
+        .join(
+            predictions.clone().lazy().select([
+                col("log_id"),
+                col("timestamp_ns").cast(DataType::UInt64),
+                col("change_score"),
+            ]),
+            keys.clone(),
+            keys,
+            JoinArgs::new(JoinType::Left),
+        )
+        .with_column(
+            col("change_score")
+                .cast(DataType::Float32)
+                .fill_null(lit(0_f32)),
+        )
+        .collect()?;
+
+    let log_ids = frames["log_id"]
+        .str()?
+        .into_no_null_iter()
+        .map(|x| x.to_string())
+        .collect_vec();
+    let is_changed = frames["is_changed"]
+        .bool()?
+        .into_no_null_iter()
+        .collect_vec();
+    let scores = frames["change_score"]
+        .f32()?
+        .into_no_null_iter()
+        .collect_vec();
+
+    Ok((log_ids, scores, is_changed))
+}
+
+/// `(x, y, score)` change locations keyed by `(log_id, timestamp_ns)`.
+type FrameLocations = HashMap<(String, u64), Vec<(f32, f32, f32)>>;
+
+/// Group the `(x, y, score)` locations by `(log_id, timestamp_ns)`.
+/// The score is zero if `score_column` is not given.
+fn _group_locations_by_frame(
+    table: &str,
+    locations: &DataFrame,
+    score_column: Option<&str>,
+) -> Av2Result<FrameLocations> {
+    validate_data_frame(table, locations, &LOCATION_SCHEMA)?;
+    let score = match score_column {
+        Some(score_column) => {
+            validate_data_frame(table, locations, &[(score_column, ColumnType::Float)])?;
+            col(score_column).cast(DataType::Float32)
+        }
+        None => lit(0_f32),
+    };
+    let locations = locations
+        .clone()
+        .lazy()
+        .select([
+            col("log_id"),
+            col("timestamp_ns").cast(DataType::UInt64),
+            col("x_city_m").cast(DataType::Float32),
+            col("y_city_m").cast(DataType::Float32),
+            score.alias("score"),
+        ])
+        .collect()?;
+
+    // The columns are validated to be free of nulls.
+    let log_ids = locations["log_id"].str()?.into_no_null_iter();
+    let timestamps = locations["timestamp_ns"].u64()?.into_no_null_iter();
+    let x = locations["x_city_m"].f32()?.into_no_null_iter();
+    let y = locations["y_city_m"].f32()?.into_no_null_iter();
+    let score = locations["score"].f32()?.into_no_null_iter();
+
+    let mut groups = FrameLocations::new();
+    for ((((log_id, timestamp_ns), x), y), score) in
+        log_ids.zip(timestamps).zip(x).zip(y).zip(score)
+    {
+        groups
+            .entry((log_id.to_string(), timestamp_ns))
+            .or_default()
+            .push((x, y, score));
+    }
+    Ok(groups)
+}
+
+fn _safe_divide(numerator: f32, denominator: f32) -> f32 {
+    match denominator > 0. {
+        true => numerator / denominator,
+        false => 0.,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::{
+        compute_average_precision, compute_classification_metrics, compute_localization_metrics,
+        evaluate, evaluate_bootstrap, ClassificationMetrics, LocalizationMetrics,
+        TbvEvaluationConfig,
+    };
+    use crate::evaluation::bootstrap::BootstrapConfig;
+
+    fn _assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_compute_average_precision() {
+        _assert_close(compute_average_precision(&[0.9, 0.1], &[false, false]), 0.);
+        _assert_close(compute_average_precision(&[0.9, 0.1], &[true, true]), 1.);
+        _assert_close(compute_average_precision(&[0.1, 0.9], &[true, false]), 0.5);
+        // Ranks 1 and 3 are positive: (1/1 + 2/3) / 2.
+        _assert_close(
+            compute_average_precision(&[0.2, 0.9, 0.4, 0.6], &[false, true, true, false]),
+            5. / 6.,
+        );
+    }
+
+    #[test]
+    fn test_compute_classification_metrics() {
+        let metrics =
+            compute_classification_metrics(&[0.9, 0.6, 0.4, 0.2], &[true, false, true, false], 0.5);
+        assert_eq!(
+            metrics,
+            ClassificationMetrics {
+                num_true_positives: 1,
+                num_false_positives: 1,
+                num_true_negatives: 1,
+                num_false_negatives: 1,
+                changed_accuracy: 0.5,
+                unchanged_accuracy: 0.5,
+                mean_accuracy: 0.5,
+                precision: 0.5,
+                recall: 0.5,
+                f1: 0.5,
+                average_precision: metrics.average_precision,
+            }
+        );
+        _assert_close(metrics.average_precision, 5. / 6.);
+
+        // Without predicted changes, precision and F1 are zero rather than NaN.
+        let metrics = compute_classification_metrics(&[0.1, 0.2], &[true, false], 0.5);
+        assert_eq!(
+            (metrics.precision, metrics.recall, metrics.f1),
+            (0., 0., 0.)
+        );
+        _assert_close(metrics.mean_accuracy, 0.5);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let labels = df!(
+            "log_id" => ["a", "a", "b", "b", "c", "c"],
+            "timestamp_ns" => [0_u64, 1, 0, 1, 0, 1],
+            "is_changed" => [true, false, false, false, true, true]
+        )
+        .unwrap();
+        // The last frame of `c` has no prediction and is scored as unchanged.
+        let predictions = df!(
+            "log_id" => ["a", "a", "b", "b", "c"],
+            "timestamp_ns" => [0_u64, 1, 0, 1, 0],
+            "change_score" => [0.9_f32, 0.2, 0.7, 0.1, 0.3]
+        )
+        .unwrap();
+        let cfg = TbvEvaluationConfig::default();
+        let metrics = evaluate(&predictions, &labels, None, None, &cfg).unwrap();
+
+        // Frames: TP 0.9; FP 0.7; TN 0.2, 0.1; FN 0.3, 0.
+        let frame = &metrics.frame;
+        assert_eq!(
+            (
+                frame.num_true_positives,
+                frame.num_false_positives,
+                frame.num_true_negatives,
+                frame.num_false_negatives
+            ),
+            (1, 1, 2, 2)
+        );
+        _assert_close(frame.changed_accuracy, 1. / 3.);
+        _assert_close(frame.unchanged_accuracy, 2. / 3.);
+        _assert_close(frame.mean_accuracy, 0.5);
+        _assert_close(frame.precision, 0.5);
+        _assert_close(frame.recall, 1. / 3.);
+        _assert_close(frame.f1, 0.4);
+        // Positives at ranks 1, 3, and 6.
+        _assert_close(frame.average_precision, (1. + 2. / 3. + 3. / 6.) / 3.);
+
+        // Logs: a (0.9, changed), b (0.7, unchanged), c (0.3, changed).
+        let log = &metrics.log;
+        assert_eq!(
+            (
+                log.num_true_positives,
+                log.num_false_positives,
+                log.num_true_negatives,
+                log.num_false_negatives
+            ),
+            (1, 1, 0, 1)
+        );
+        _assert_close(log.mean_accuracy, 0.25);
+        _assert_close(log.f1, 0.5);
+        _assert_close(log.average_precision, (1. + 2. / 3.) / 2.);
+        assert_eq!(metrics.localization, None);
+    }
+
+    #[test]
+    fn test_compute_localization_metrics() {
+        let labeled_locations = df!(
+            "log_id" => ["a", "a", "b"],
+            "timestamp_ns" => [0_u64, 0, 0],
+            "x_city_m" => [0_f32, 6., 0.],
+            "y_city_m" => [0_f32, 0., 0.]
+        )
+        .unwrap();
+        // In frame `a/0`, the first prediction is nearer the first label, which is the only label
+        // within reach of the higher-scoring second prediction. Frame `a/1` has no labels.
+        let predicted_locations = df!(
+            "log_id" => ["a", "a", "a"],
+            "timestamp_ns" => [0_u64, 0, 1],
+            "x_city_m" => [2_f32, -1., 0.],
+            "y_city_m" => [0_f32, 0., 0.],
+            "change_score" => [0.1_f32, 0.9, 0.5]
+        )
+        .unwrap();
+        let metrics =
+            compute_localization_metrics(&predicted_locations, &labeled_locations, 5.).unwrap();
+        assert_eq!(
+            metrics,
+            LocalizationMetrics {
+                num_true_positives: 2,
+                num_false_positives: 1,
+                num_false_negatives: 1,
+                precision: 2. / 3.,
+                recall: 2. / 3.,
+                f1: 2. / 3.,
+            }
+        );
+
+        let labels = df!(
+            "log_id" => ["a", "a", "b"],
+            "timestamp_ns" => [0_u64, 1, 0],
+            "is_changed" => [true, false, true]
+        )
+        .unwrap();
+        let predictions = df!(
+            "log_id" => ["a", "a", "b"],
+            "timestamp_ns" => [0_u64, 1, 0],
+            "change_score" => [0.9_f32, 0.5, 0.]
+        )
+        .unwrap();
+        let metrics = evaluate(
+            &predictions,
+            &labels,
+            Some(&predicted_locations),
+            Some(&labeled_locations),
+            &TbvEvaluationConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            metrics.localization,
+            Some(
+                compute_localization_metrics(&predicted_locations, &labeled_locations, 5.).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_evaluate_bootstrap() {
+        let labels = df!(
+            "log_id" => ["a", "a", "b", "b", "c", "c"],
+            "timestamp_ns" => [0_u64, 1, 0, 1, 0, 1],
+            "is_changed" => [true, false, false, false, true, true]
+        )
+        .unwrap();
+        let predictions = df!(
+            "log_id" => ["a", "a", "b", "b", "c", "c"],
+            "timestamp_ns" => [0_u64, 1, 0, 1, 0, 1],
+            "change_score" => [0.9_f32, 0.2, 0.7, 0.1, 0.3, 0.8]
+        )
+        .unwrap();
+        let cfg = TbvEvaluationConfig::default();
+        let bootstrap_cfg = BootstrapConfig {
+            num_replicates: 200,
+            ..Default::default()
+        };
+        let intervals = evaluate_bootstrap(&predictions, &labels, &cfg, &bootstrap_cfg).unwrap();
+        let metrics = evaluate(&predictions, &labels, None, None, &cfg).unwrap();
+        let expected = metrics.frame.as_metrics("frame");
+        for (name, value) in expected.iter().chain(&metrics.log.as_metrics("log")) {
+            let interval = intervals[name];
+            assert!((interval.estimate - value).abs() < 1e-6, "{name}");
+            assert!(interval.lower <= interval.estimate && interval.estimate <= interval.upper);
+        }
+        // The replicates are reproducible, regardless of the order of the logs.
+        for _ in 0..3 {
+            assert_eq!(
+                evaluate_bootstrap(&predictions, &labels, &cfg, &bootstrap_cfg).unwrap(),
+                intervals
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_invalid_frames() {
+        let labels = df!(
+            "log_id" => ["a"],
+            "timestamp_ns" => [0_u64],
+            "is_changed" => [true]
+        )
+        .unwrap();
+        let cfg = TbvEvaluationConfig::default();
+
+        // Predictions without scores and labels with non-boolean changes are rejected.
+        let predictions = df!("log_id" => ["a"], "timestamp_ns" => [0_u64]).unwrap();
+        let err = evaluate(&predictions, &labels, None, None, &cfg).unwrap_err();
+        assert!(err.to_string().contains("missing column `change_score`"));
+        let predictions = df!(
+            "log_id" => ["a"],
+            "timestamp_ns" => [0_u64],
+            "change_score" => [0.5_f32]
+        )
+        .unwrap();
+        let mistyped_labels = labels
+            .clone()
+            .lazy()
+            .with_column(col("is_changed").cast(DataType::Float32))
+            .collect()
+            .unwrap();
+        let err = evaluate(&predictions, &mistyped_labels, None, None, &cfg).unwrap_err();
+        assert!(err.to_string().contains("column `is_changed` has type f32"));
+
+        // Null locations are rejected rather than unwrapped.
+        let locations = df!(
+            "log_id" => ["a"],
+            "timestamp_ns" => [0_u64],
+            "x_city_m" => [None::<f32>],
+            "y_city_m" => [0_f32]
+        )
+        .unwrap();
+        let err = evaluate(
+            &predictions,
+            &labels,
+            Some(&locations),
+            Some(&locations),
+            &cfg,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("column `x_city_m` contains 1 nulls"));
+    }
+}
@@ -0,0 +1,831 @@
+//! # tracking
+//!
+//! Multi-object tracking evaluation (HOTA, MOTA, IDF1, and id switches).
+//!
+//! Port of `av2.evaluation.tracking`, which wraps TrackEval. Tracks and annotations are compared
+//! by the similarity `max(0, 1 - d / zero_distance_m)` of their bird's-eye-view center distance
+//! `d`. Each log and category is evaluated as its own sequence (in parallel over logs), and the
+//! sequences of a category are combined as TrackEval does: counts are summed and association
+//! scores are weighted by their true positives.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use ndarray::{Array, Ix2};
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::ops::linear_sum_assignment;
+
+use super::{
+    bootstrap::{bootstrap, BootstrapConfig, ConfidenceInterval, Metrics},
+    breakdown::{
+        range_bucket, range_bucket_labels, splits, unique_splits, DEFAULT_RANGE_BUCKETS_M,
+    },
+    detection::{AVERAGE_METRICS, COMPETITION_CATEGORIES},
+};
+
+/// Center distance (in meters) at which the similarity between a track and an annotation is zero.
+pub const DEFAULT_ZERO_DISTANCE_M: f32 = 2.0;
+
+/// Similarity threshold of a match for the CLEAR (MOTA) and identity (IDF1) metrics.
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Summary metric columns, in order.
+pub const SUMMARY_COLUMNS: [&str; 8] = [
+    "HOTA", "DetA", "AssA", "LocA", "MOTA", "MOTP", "IDF1", "IDSW",
+];
+
+/// Number of HOTA similarity thresholds (`0.05, 0.10, ..., 0.95`).
+const NUM_ALPHAS: usize = 19;
+
+const EPS: f64 = f64::EPSILON;
+
+/// Tracking evaluation configuration.
+#[derive(Clone, Debug)]
+pub struct TrackingConfig {
+    /// Evaluated categories.
+    pub categories: Vec<String>,
+    /// Center distance (in meters) at which the similarity is zero.
+    pub zero_distance_m: f32,
+    /// Similarity threshold of a match for the CLEAR and identity metrics.
+    pub similarity_threshold: f32,
+    /// Edges (in meters) of the bird's-eye-view range buckets of the breakdown.
+    pub range_buckets_m: Vec<f32>,
+}
+
+impl Default for TrackingConfig {
+    fn default() -> Self {
+        Self {
+            categories: COMPETITION_CATEGORIES.map(String::from).to_vec(),
+            zero_distance_m: DEFAULT_ZERO_DISTANCE_M,
+            similarity_threshold: DEFAULT_SIMILARITY_THRESHOLD,
+            range_buckets_m: DEFAULT_RANGE_BUCKETS_M.to_vec(),
+        }
+    }
+}
+
+/// Tracking metrics per log and over all logs.
+#[derive(Clone, Debug)]
+pub struct TrackingEvaluation {
+    /// `log_id`, `category`, and the `SUMMARY_COLUMNS` of each log and category.
+    pub logs: DataFrame,
+    /// `category` and the `SUMMARY_COLUMNS` of each category over all logs followed by their
+    /// average (`AVERAGE_METRICS`). Categories without tracks or annotations are left out of the
+    /// average, and id switches are summed rather than averaged.
+    pub summary: DataFrame,
+    /// `split`, `category`, `range_m`, and the `SUMMARY_COLUMNS` of each split, category, and
+    /// range bucket with any tracks or annotations.
+    pub breakdown: DataFrame,
+}
+
+impl TrackingEvaluation {
+    /// Average summary metrics keyed by name (e.g., `HOTA`).
+    pub fn as_metrics(&self) -> Metrics {
+        let row = self.summary.height() - 1;
+        SUMMARY_COLUMNS
+            .iter()
+            .map(|name| {
+                let value = self.summary[*name].f32().unwrap().get(row).unwrap();
+                (name.to_string(), value)
+            })
+            .collect()
+    }
+}
+
+/// Ids and similarities of the tracks and annotations of one log and category, per timestep.
+struct _Sequence {
+    gt_ids: Vec<Vec<usize>>,
+    tracker_ids: Vec<Vec<usize>>,
+    /// (G,T) similarities between the annotations and tracks of each timestep.
+    similarities: Vec<Array<f64, Ix2>>,
+    num_gt_ids: usize,
+    num_tracker_ids: usize,
+}
+
+/// HOTA, CLEAR, and identity counts of one or more sequences.
+#[derive(Clone, Debug, Default)]
+struct _Counts {
+    hota_tp: [f64; NUM_ALPHAS],
+    hota_fn: [f64; NUM_ALPHAS],
+    hota_fp: [f64; NUM_ALPHAS],
+    ass_a: [f64; NUM_ALPHAS],
+    loc_a: [f64; NUM_ALPHAS],
+    clr_tp: f64,
+    clr_fn: f64,
+    clr_fp: f64,
+    idsw: f64,
+    motp_sum: f64,
+    idtp: f64,
+    idfn: f64,
+    idfp: f64,
+}
+
+impl _Counts {
+    /// Combine the counts of several sequences.
+    fn combine(all: &[_Counts]) -> _Counts {
+        let mut combined = _Counts::default();
+        for counts in all {
+            for a in 0..NUM_ALPHAS {
+                combined.hota_tp[a] += counts.hota_tp[a];
+                combined.hota_fn[a] += counts.hota_fn[a];
+                combined.hota_fp[a] += counts.hota_fp[a];
+                combined.ass_a[a] += counts.ass_a[a] * counts.hota_tp[a];
+                combined.loc_a[a] += counts.loc_a[a] * counts.hota_tp[a];
+            }
+            combined.clr_tp += counts.clr_tp;
+            combined.clr_fn += counts.clr_fn;
+            combined.clr_fp += counts.clr_fp;
+            combined.idsw += counts.idsw;
+            combined.motp_sum += counts.motp_sum;
+            combined.idtp += counts.idtp;
+            combined.idfn += counts.idfn;
+            combined.idfp += counts.idfp;
+        }
+        for a in 0..NUM_ALPHAS {
+            combined.ass_a[a] /= combined.hota_tp[a].max(1e-10);
+            combined.loc_a[a] = combined.loc_a[a].max(1e-10) / combined.hota_tp[a].max(1e-10);
+        }
+        combined
+    }
+
+    /// Whether no tracks or annotations were evaluated.
+    fn is_empty(&self) -> bool {
+        self.hota_tp[0] + self.hota_fn[0] + self.hota_fp[0] == 0.
+    }
+
+    /// Values of the `SUMMARY_COLUMNS`.
+    fn summary(&self) -> [f32; 8] {
+        let mut hota = 0.;
+        let mut det_a = 0.;
+        for a in 0..NUM_ALPHAS {
+            let (tp, fn_, fp) = (self.hota_tp[a], self.hota_fn[a], self.hota_fp[a]);
+            let det_a_alpha = tp / (tp + fn_ + fp).max(1.);
+            hota += (det_a_alpha * self.ass_a[a]).sqrt();
+            det_a += det_a_alpha;
+        }
+        let num_alphas = NUM_ALPHAS as f64;
+        let ass_a = self.ass_a.iter().sum::<f64>() / num_alphas;
+        let loc_a = self.loc_a.iter().sum::<f64>() / num_alphas;
+        let mota = (self.clr_tp - self.clr_fp - self.idsw) / (self.clr_tp + self.clr_fn).max(1.);
+        let motp = self.motp_sum / self.clr_tp.max(1.);
+        let idf1 = self.idtp / (self.idtp + 0.5 * self.idfp + 0.5 * self.idfn).max(1.);
+        [
+            hota / num_alphas,
+            det_a / num_alphas,
+            ass_a,
+            loc_a,
+            mota,
+            motp,
+            idf1,
+            self.idsw,
+        ]
+        .map(|x| x as f32)
+    }
+}
+
+/// Evaluate `tracks` against `annotations`.
+///
+/// Both tables hold `log_id`, `timestamp_ns`, `category`, `track_uuid`, `tx_m`, and `ty_m` in the
+/// egovehicle frame, with track ids unique within each timestep, and optionally a `split`.
+pub fn evaluate(
+    tracks: &DataFrame,
+    annotations: &DataFrame,
+    cfg: &TrackingConfig,
+) -> Result<TrackingEvaluation> {
+    let tracks = _TrackRows::from_data_frame(tracks)?;
+    let annotations = _TrackRows::from_data_frame(annotations)?;
+
+    let log_counts = _log_counts(&tracks, &annotations, cfg, &|_, _| true);
+
+    let mut log_ids = vec![];
+    let mut log_categories = vec![];
+    let mut log_values = vec![vec![]; SUMMARY_COLUMNS.len()];
+    for (log_id, counts) in &log_counts {
+        for (category, counts) in cfg.categories.iter().zip(counts) {
+            log_ids.push(log_id.clone());
+            log_categories.push(category.clone());
+            for (k, value) in counts.summary().into_iter().enumerate() {
+                log_values[k].push(value);
+            }
+        }
+    }
+    let mut columns = vec![
+        Series::new("log_id", log_ids),
+        Series::new("category", log_categories),
+    ];
+    columns.extend(
+        SUMMARY_COLUMNS
+            .iter()
+            .zip(log_values)
+            .map(|(name, values)| Series::new(name, values)),
+    );
+    let logs = DataFrame::new(columns)?;
+
+    let category_counts = (0..cfg.categories.len())
+        .map(|c| {
+            let counts = log_counts
+                .iter()
+                .map(|(_, counts)| counts[c].clone())
+                .collect::<Vec<_>>();
+            _Counts::combine(&counts)
+        })
+        .collect::<Vec<_>>();
+    let summaries = category_counts
+        .iter()
+        .map(|counts| counts.summary())
+        .collect::<Vec<_>>();
+    let average = _average_summary(&category_counts);
+
+    let mut categories = cfg.categories.clone();
+    categories.push(AVERAGE_METRICS.to_string());
+    let mut columns = vec![Series::new("category", categories)];
+    for (k, name) in SUMMARY_COLUMNS.iter().enumerate() {
+        let mut values = summaries.iter().map(|x| x[k]).collect::<Vec<_>>();
+        values.push(average[k]);
+        columns.push(Series::new(name, values));
+    }
+    Ok(TrackingEvaluation {
+        logs,
+        summary: DataFrame::new(columns)?,
+        breakdown: _breakdown(&tracks, &annotations, cfg)?,
+    })
+}
+
+/// Compute bootstrapped confidence intervals of the average summary metrics (e.g., `HOTA`).
+/// Logs are resampled with replacement, and each log is evaluated once.
+pub fn evaluate_bootstrap(
+    tracks: &DataFrame,
+    annotations: &DataFrame,
+    cfg: &TrackingConfig,
+    bootstrap_cfg: &BootstrapConfig,
+) -> Result<BTreeMap<String, ConfidenceInterval>> {
+    let tracks = _TrackRows::from_data_frame(tracks)?;
+    let annotations = _TrackRows::from_data_frame(annotations)?;
+    let log_counts = _log_counts(&tracks, &annotations, cfg, &|_, _| true)
+        .into_iter()
+        .map(|(_, counts)| counts)
+        .collect::<Vec<_>>();
+
+    let metric_fn = |logs: &[&Vec<_Counts>]| {
+        let category_counts = (0..cfg.categories.len())
+            .map(|c| {
+                let counts = logs.iter().map(|x| x[c].clone()).collect::<Vec<_>>();
+                _Counts::combine(&counts)
+            })
+            .collect::<Vec<_>>();
+        SUMMARY_COLUMNS
+            .iter()
+            .zip(_average_summary(&category_counts))
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<Metrics>()
+    };
+    Ok(bootstrap(&log_counts, metric_fn, bootstrap_cfg)?)
+}
+
+/// Values of the `SUMMARY_COLUMNS` averaged over the categories with any tracks or annotations,
+/// except for the id switches, which are summed.
+fn _average_summary(category_counts: &[_Counts]) -> [f32; 8] {
+    let summaries = category_counts
+        .iter()
+        .filter(|counts| !counts.is_empty())
+        .map(|counts| counts.summary())
+        .collect::<Vec<_>>();
+    let mut average = [0.; 8];
+    for (k, name) in SUMMARY_COLUMNS.iter().enumerate() {
+        let total = summaries.iter().map(|x| x[k]).sum::<f32>();
+        average[k] = match *name {
+            "IDSW" => total,
+            _ => total / summaries.len().max(1) as f32,
+        };
+    }
+    average
+}
+
+/// Counts of each log and category over the rows selected by `keep`.
+fn _log_counts(
+    tracks: &_TrackRows,
+    annotations: &_TrackRows,
+    cfg: &TrackingConfig,
+    keep: &(dyn Fn(&_TrackRows, usize) -> bool + Sync),
+) -> Vec<(String, Vec<_Counts>)> {
+    let mut logs: BTreeMap<&str, (Vec<usize>, Vec<usize>)> = BTreeMap::new();
+    for (i, log_id) in tracks.log_ids.iter().enumerate() {
+        logs.entry(log_id).or_default().0.push(i);
+    }
+    for (j, log_id) in annotations.log_ids.iter().enumerate() {
+        logs.entry(log_id).or_default().1.push(j);
+    }
+    logs.par_iter()
+        .map(|(log_id, (tracks_rows, gts_rows))| {
+            let counts = cfg
+                .categories
+                .iter()
+                .map(|category| {
+                    let select = |data: &_TrackRows, rows: &[usize]| {
+                        rows.iter()
+                            .copied()
+                            .filter(|&k| data.categories[k] == *category && keep(data, k))
+                            .collect::<Vec<_>>()
+                    };
+                    let sequence = _Sequence::new(
+                        tracks,
+                        &select(tracks, tracks_rows),
+                        annotations,
+                        &select(annotations, gts_rows),
+                        cfg.zero_distance_m,
+                    );
+                    _evaluate_sequence(&sequence, cfg.similarity_threshold as f64)
+                })
+                .collect::<Vec<_>>();
+            (log_id.to_string(), counts)
+        })
+        .collect()
+}
+
+/// Summary metrics of each split, category, and range bucket. Each bucket is evaluated on the
+/// rows within it, so a track that crosses a bucket edge is cut into separate tracks.
+fn _breakdown(
+    tracks: &_TrackRows,
+    annotations: &_TrackRows,
+    cfg: &TrackingConfig,
+) -> Result<DataFrame> {
+    let range_labels = range_bucket_labels(&cfg.range_buckets_m);
+    let (mut split_column, mut category_column, mut range_column) = (vec![], vec![], vec![]);
+    let mut values = vec![vec![]; SUMMARY_COLUMNS.len()];
+    for split in unique_splits(&[&tracks.splits, &annotations.splits]) {
+        for (bucket, range_label) in range_labels.iter().enumerate() {
+            let keep = |data: &_TrackRows, k: usize| {
+                let [x, y] = data.xy[k];
+                data.splits[k] == split
+                    && range_bucket(x.hypot(y), &cfg.range_buckets_m) == Some(bucket)
+            };
+            let log_counts = _log_counts(tracks, annotations, cfg, &keep);
+            for (c, category) in cfg.categories.iter().enumerate() {
+                let counts = log_counts
+                    .iter()
+                    .map(|(_, counts)| counts[c].clone())
+                    .collect::<Vec<_>>();
+                let counts = _Counts::combine(&counts);
+                if counts.is_empty() {
+                    continue;
+                }
+                split_column.push(split.clone());
+                category_column.push(category.clone());
+                range_column.push(range_label.clone());
+                for (k, value) in counts.summary().into_iter().enumerate() {
+                    values[k].push(value);
+                }
+            }
+        }
+    }
+    let mut columns = vec![
+        Series::new("split", split_column),
+        Series::new("category", category_column),
+        Series::new("range_m", range_column),
+    ];
+    columns.extend(
+        SUMMARY_COLUMNS
+            .iter()
+            .zip(values)
+            .map(|(name, values)| Series::new(name, values)),
+    );
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Columns of a track or annotation table.
+struct _TrackRows {
+    log_ids: Vec<String>,
+    timestamps_ns: Vec<u64>,
+    categories: Vec<String>,
+    track_uuids: Vec<String>,
+    xy: Vec<[f32; 2]>,
+    splits: Vec<Option<String>>,
+}
+
+impl _TrackRows {
+    fn from_data_frame(data_frame: &DataFrame) -> Result<Self> {
+        let strings = |column: &str| -> Result<Vec<String>> {
+            let values = data_frame.column(column)?.cast(&DataType::String)?;
+            values
+                .str()?
+                .into_iter()
+                .map(|x| {
+                    Ok(x.with_context(|| format!("Missing `{column}`."))?
+                        .to_string())
+                })
+                .collect()
+        };
+        let floats = |column: &str| -> Result<Vec<f32>> {
+            let values = data_frame.column(column)?.cast(&DataType::Float32)?;
+            Ok(values
+                .f32()?
+                .into_iter()
+                .map(|x| x.unwrap_or(f32::NAN))
+                .collect())
+        };
+        let timestamps_ns = data_frame.column("timestamp_ns")?.cast(&DataType::UInt64)?;
+        let timestamps_ns = timestamps_ns
+            .u64()?
+            .into_iter()
+            .map(|x| x.context("Missing `timestamp_ns`."))
+            .collect::<Result<Vec<_>>>()?;
+        let (tx_m, ty_m) = (floats("tx_m")?, floats("ty_m")?);
+        Ok(Self {
+            log_ids: strings("log_id")?,
+            timestamps_ns,
+            categories: strings("category")?,
+            track_uuids: strings("track_uuid")?,
+            xy: tx_m.into_iter().zip(ty_m).map(|(x, y)| [x, y]).collect(),
+            splits: splits(data_frame)?,
+        })
+    }
+}
+
+impl _Sequence {
+    /// Build the sequence of the `tracks_rows` of `tracks` and `gts_rows` of `annotations`.
+    fn new(
+        tracks: &_TrackRows,
+        tracks_rows: &[usize],
+        annotations: &_TrackRows,
+        gts_rows: &[usize],
+        zero_distance_m: f32,
+    ) -> Self {
+        let mut timesteps: BTreeMap<u64, (Vec<usize>, Vec<usize>)> = BTreeMap::new();
+        for &i in tracks_rows {
+            timesteps
+                .entry(tracks.timestamps_ns[i])
+                .or_default()
+                .0
+                .push(i);
+        }
+        for &j in gts_rows {
+            timesteps
+                .entry(annotations.timestamps_ns[j])
+                .or_default()
+                .1
+                .push(j);
+        }
+
+        let mut gt_id_map = HashMap::new();
+        let mut tracker_id_map = HashMap::new();
+        let mut sequence = _Sequence {
+            gt_ids: vec![],
+            tracker_ids: vec![],
+            similarities: vec![],
+            num_gt_ids: 0,
+            num_tracker_ids: 0,
+        };
+        for (tracks_rows, gts_rows) in timesteps.values() {
+            let map_ids = |data: &_TrackRows, rows: &[usize], map: &mut HashMap<String, _>| {
+                rows.iter()
+                    .map(|&k| {
+                        let num_ids = map.len();
+                        *map.entry(data.track_uuids[k].clone()).or_insert(num_ids)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            sequence
+                .gt_ids
+                .push(map_ids(annotations, gts_rows, &mut gt_id_map));
+            sequence
+                .tracker_ids
+                .push(map_ids(tracks, tracks_rows, &mut tracker_id_map));
+            sequence.similarities.push(Array::from_shape_fn(
+                (gts_rows.len(), tracks_rows.len()),
+                |(g, t)| {
+                    let (a, b) = (annotations.xy[gts_rows[g]], tracks.xy[tracks_rows[t]]);
+                    let distance = ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt();
+                    (1. - distance / zero_distance_m).max(0.) as f64
+                },
+            ));
+        }
+        sequence.num_gt_ids = gt_id_map.len();
+        sequence.num_tracker_ids = tracker_id_map.len();
+        sequence
+    }
+}
+
+/// HOTA, CLEAR, and identity counts of a sequence.
+fn _evaluate_sequence(sequence: &_Sequence, threshold: f64) -> _Counts {
+    let mut counts = _Counts::default();
+    _hota_counts(sequence, &mut counts);
+    _clear_counts(sequence, threshold, &mut counts);
+    _identity_counts(sequence, threshold, &mut counts);
+    counts
+}
+
+/// Higher Order Tracking Accuracy counts at each similarity threshold `alpha`.
+fn _hota_counts(sequence: &_Sequence, counts: &mut _Counts) {
+    let (num_gt_ids, num_tracker_ids) = (sequence.num_gt_ids, sequence.num_tracker_ids);
+    let mut potential_matches_count = Array::<f64, Ix2>::zeros((num_gt_ids, num_tracker_ids));
+    let mut gt_id_count = vec![0_f64; num_gt_ids];
+    let mut tracker_id_count = vec![0_f64; num_tracker_ids];
+    for ((gt_ids, tracker_ids), similarity) in sequence
+        .gt_ids
+        .iter()
+        .zip(&sequence.tracker_ids)
+        .zip(&sequence.similarities)
+    {
+        let gt_sums = similarity.sum_axis(ndarray::Axis(1));
+        let tracker_sums = similarity.sum_axis(ndarray::Axis(0));
+        for ((g, t), s) in similarity.indexed_iter() {
+            let denominator = gt_sums[g] + tracker_sums[t] - s;
+            if denominator > EPS {
+                potential_matches_count[[gt_ids[g], tracker_ids[t]]] += s / denominator;
+            }
+        }
+        gt_ids.iter().for_each(|&g| gt_id_count[g] += 1.);
+        tracker_ids.iter().for_each(|&t| tracker_id_count[t] += 1.);
+    }
+    let global_alignment_score = Array::from_shape_fn((num_gt_ids, num_tracker_ids), |(g, t)| {
+        let matches = potential_matches_count[[g, t]];
+        matches / (gt_id_count[g] + tracker_id_count[t] - matches)
+    });
+
+    let alphas = (1..=NUM_ALPHAS)
+        .map(|k| k as f64 * 0.05)
+        .collect::<Vec<_>>();
+    let mut matches_counts =
+        vec![Array::<f64, Ix2>::zeros((num_gt_ids, num_tracker_ids)); NUM_ALPHAS];
+    for ((gt_ids, tracker_ids), similarity) in sequence
+        .gt_ids
+        .iter()
+        .zip(&sequence.tracker_ids)
+        .zip(&sequence.similarities)
+    {
+        if gt_ids.is_empty() || tracker_ids.is_empty() {
+            for a in 0..NUM_ALPHAS {
+                counts.hota_fn[a] += gt_ids.len() as f64;
+                counts.hota_fp[a] += tracker_ids.len() as f64;
+            }
+            continue;
+        }
+        let score = Array::from_shape_fn(similarity.dim(), |(g, t)| {
+            (global_alignment_score[[gt_ids[g], tracker_ids[t]]] * similarity[[g, t]]) as f32
+        });
+        let (rows, cols) =
+            linear_sum_assignment(&score.view(), true).expect("Finite scores are always feasible.");
+        for (a, alpha) in alphas.iter().enumerate() {
+            let mut num_matches = 0;
+            for (&g, &t) in rows.iter().zip(&cols) {
+                if similarity[[g, t]] >= alpha - EPS {
+                    num_matches += 1;
+                    counts.loc_a[a] += similarity[[g, t]];
+                    matches_counts[a][[gt_ids[g], tracker_ids[t]]] += 1.;
+                }
+            }
+            counts.hota_tp[a] += num_matches as f64;
+            counts.hota_fn[a] += (gt_ids.len() - num_matches) as f64;
+            counts.hota_fp[a] += (tracker_ids.len() - num_matches) as f64;
+        }
+    }
+
+    for (a, matches_count) in matches_counts.iter().enumerate() {
+        let association = matches_count
+            .indexed_iter()
+            .map(|((g, t), &matches)| {
+                let denominator = (gt_id_count[g] + tracker_id_count[t] - matches).max(1.);
+                matches * matches / denominator
+            })
+            .sum::<f64>();
+        counts.ass_a[a] = association / counts.hota_tp[a].max(1.);
+        counts.loc_a[a] = counts.loc_a[a].max(1e-10) / counts.hota_tp[a].max(1e-10);
+    }
+}
+
+/// CLEAR MOT counts, where matches from the previous timestep are preferred.
+fn _clear_counts(sequence: &_Sequence, threshold: f64, counts: &mut _Counts) {
+    let mut prev_tracker_id = vec![None; sequence.num_gt_ids];
+    let mut prev_timestep_tracker_id: Vec<Option<usize>> = vec![None; sequence.num_gt_ids];
+    for ((gt_ids, tracker_ids), similarity) in sequence
+        .gt_ids
+        .iter()
+        .zip(&sequence.tracker_ids)
+        .zip(&sequence.similarities)
+    {
+        if gt_ids.is_empty() || tracker_ids.is_empty() {
+            counts.clr_fn += gt_ids.len() as f64;
+            counts.clr_fp += tracker_ids.len() as f64;
+            continue;
+        }
+        let score = Array::from_shape_fn(similarity.dim(), |(g, t)| {
+            let is_continued = prev_timestep_tracker_id[gt_ids[g]] == Some(tracker_ids[t]);
+            match similarity[[g, t]] < threshold - EPS {
+                true => 0.,
+                false => (1000. * is_continued as u8 as f64 + similarity[[g, t]]) as f32,
+            }
+        });
+        let (rows, cols) =
+            linear_sum_assignment(&score.view(), true).expect("Finite scores are always feasible.");
+        let matches = rows
+            .iter()
+            .zip(&cols)
+            .filter(|(&g, &t)| score[[g, t]] as f64 > EPS)
+            .map(|(&g, &t)| (g, t))
+            .collect::<Vec<_>>();
+
+        prev_timestep_tracker_id.fill(None);
+        for &(g, t) in &matches {
+            let (gt_id, tracker_id) = (gt_ids[g], tracker_ids[t]);
+            if prev_tracker_id[gt_id].is_some_and(|prev| prev != tracker_id) {
+                counts.idsw += 1.;
+            }
+            prev_tracker_id[gt_id] = Some(tracker_id);
+            prev_timestep_tracker_id[gt_id] = Some(tracker_id);
+            counts.motp_sum += similarity[[g, t]];
+        }
+        counts.clr_tp += matches.len() as f64;
+        counts.clr_fn += (gt_ids.len() - matches.len()) as f64;
+        counts.clr_fp += (tracker_ids.len() - matches.len()) as f64;
+    }
+}
+
+/// Identity counts of the globally optimal one-to-one assignment of tracks to annotations.
+fn _identity_counts(sequence: &_Sequence, threshold: f64, counts: &mut _Counts) {
+    let (num_gt_ids, num_tracker_ids) = (sequence.num_gt_ids, sequence.num_tracker_ids);
+    let mut potential_matches_count = Array::<f64, Ix2>::zeros((num_gt_ids, num_tracker_ids));
+    let mut gt_id_count = vec![0_f64; num_gt_ids];
+    let mut tracker_id_count = vec![0_f64; num_tracker_ids];
+    for ((gt_ids, tracker_ids), similarity) in sequence
+        .gt_ids
+        .iter()
+        .zip(&sequence.tracker_ids)
+        .zip(&sequence.similarities)
+    {
+        for ((g, t), s) in similarity.indexed_iter() {
+            if *s >= threshold {
+                potential_matches_count[[gt_ids[g], tracker_ids[t]]] += 1.;
+            }
+        }
+        gt_ids.iter().for_each(|&g| gt_id_count[g] += 1.);
+        tracker_ids.iter().for_each(|&t| tracker_id_count[t] += 1.);
+    }
+
+    // Every annotation (track) may also be left unmatched by assigning it to its own dummy track
+    // (annotation).
+    let size = num_gt_ids + num_tracker_ids;
+    let mut fn_mat = Array::<f64, Ix2>::zeros((size, size));
+    let mut fp_mat = Array::<f64, Ix2>::zeros((size, size));
+    fp_mat
+        .slice_mut(ndarray::s![num_gt_ids.., ..num_tracker_ids])
+        .fill(1e10);
+    fn_mat
+        .slice_mut(ndarray::s![..num_gt_ids, num_tracker_ids..])
+        .fill(1e10);
+    for (g, &count) in gt_id_count.iter().enumerate() {
+        fn_mat
+            .slice_mut(ndarray::s![g, ..num_tracker_ids])
+            .fill(count);
+        fn_mat[[g, num_tracker_ids + g]] = count;
+    }
+    for (t, &count) in tracker_id_count.iter().enumerate() {
+        fp_mat.slice_mut(ndarray::s![..num_gt_ids, t]).fill(count);
+        fp_mat[[num_gt_ids + t, t]] = count;
+    }
+    for ((g, t), &matches) in potential_matches_count.indexed_iter() {
+        fn_mat[[g, t]] -= matches;
+        fp_mat[[g, t]] -= matches;
+    }
+
+    let cost = (&fn_mat + &fp_mat).mapv(|x| x as f32);
+    let (rows, cols) =
+        linear_sum_assignment(&cost.view(), false).expect("Finite costs are always feasible.");
+    let idfn = rows
+        .iter()
+        .zip(&cols)
+        .map(|(&i, &j)| fn_mat[[i, j]])
+        .sum::<f64>();
+    let idfp = rows
+        .iter()
+        .zip(&cols)
+        .map(|(&i, &j)| fp_mat[[i, j]])
+        .sum::<f64>();
+    counts.idfn += idfn;
+    counts.idfp += idfp;
+    counts.idtp += gt_id_count.iter().sum::<f64>() - idfn;
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::{evaluate, evaluate_bootstrap, TrackingConfig, AVERAGE_METRICS};
+    use crate::evaluation::bootstrap::BootstrapConfig;
+
+    /// Tracks of one category along +x with one row per `(timestamp_ns, track_uuid, tx_m)`.
+    fn tracks(log_id: &str, rows: &[(u64, &str, f32)]) -> DataFrame {
+        let n = rows.len();
+        df!(
+            "log_id" => vec![log_id; n],
+            "timestamp_ns" => rows.iter().map(|x| x.0).collect::<Vec<_>>(),
+            "category" => vec!["REGULAR_VEHICLE"; n],
+            "track_uuid" => rows.iter().map(|x| x.1).collect::<Vec<_>>(),
+            "tx_m" => rows.iter().map(|x| x.2).collect::<Vec<_>>(),
+            "ty_m" => vec![0_f32; n]
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let cfg = TrackingConfig {
+            categories: vec!["REGULAR_VEHICLE".to_string(), "BUS".to_string()],
+            ..Default::default()
+        };
+        let annotations = tracks("log", &[(0, "a", 0.), (1, "a", 1.), (2, "a", 2.)]);
+
+        // A perfect tracker.
+        let evaluation = evaluate(&annotations, &annotations, &cfg).unwrap();
+        let metrics = evaluation.as_metrics();
+        for name in ["HOTA", "DetA", "AssA", "LocA", "MOTA", "MOTP", "IDF1"] {
+            assert!(
+                (metrics[name] - 1.).abs() < 1e-5,
+                "{name}: {}",
+                metrics[name]
+            );
+        }
+        assert_eq!(metrics["IDSW"], 0.);
+        assert_eq!(evaluation.logs.height(), 2);
+        let categories = evaluation.summary.column("category").unwrap();
+        assert_eq!(categories.str().unwrap().get(2), Some(AVERAGE_METRICS));
+        assert_eq!(evaluation.breakdown.height(), 1);
+        assert_eq!(evaluation.breakdown["split"].null_count(), 1);
+
+        // The track id switches halfway through.
+        let switched = tracks("log", &[(0, "x", 0.), (1, "y", 1.), (2, "y", 2.)]);
+        let metrics = evaluate(&switched, &annotations, &cfg)
+            .unwrap()
+            .as_metrics();
+        assert_eq!(metrics["IDSW"], 1.);
+        assert!((metrics["MOTA"] - 2. / 3.).abs() < 1e-5);
+        assert!((metrics["IDF1"] - 2. / 3.).abs() < 1e-5);
+        assert!((metrics["DetA"] - 1.).abs() < 1e-5);
+        assert!(metrics["HOTA"] < 1.);
+
+        // A missed timestep and a false positive.
+        let missed = tracks("log", &[(0, "x", 0.), (2, "x", 2.), (2, "z", 10.)]);
+        let metrics = evaluate(&missed, &annotations, &cfg).unwrap().as_metrics();
+        assert!((metrics["MOTA"] - 1. / 3.).abs() < 1e-5);
+        assert_eq!(metrics["IDSW"], 0.);
+
+        // The false positive falls in its own range bucket.
+        let cfg = TrackingConfig {
+            range_buckets_m: vec![0., 5., 50.],
+            ..cfg
+        };
+        let breakdown = evaluate(&missed, &annotations, &cfg).unwrap().breakdown;
+        let ranges = breakdown["range_m"].str().unwrap();
+        assert_eq!(
+            ranges.into_iter().collect::<Vec<_>>(),
+            vec![Some("0-5"), Some("5-50")]
+        );
+        assert_eq!(breakdown["MOTA"].f32().unwrap().get(1), Some(-1.));
+    }
+
+    #[test]
+    fn test_evaluate_bootstrap() {
+        let cfg = TrackingConfig {
+            categories: vec!["REGULAR_VEHICLE".to_string()],
+            ..Default::default()
+        };
+        // A perfect log and a log with an id switch.
+        let annotations = concat_tracks(&[
+            tracks("a", &[(0, "a", 0.), (1, "a", 1.), (2, "a", 2.)]),
+            tracks("b", &[(0, "b", 0.), (1, "b", 1.), (2, "b", 2.)]),
+        ]);
+        let predictions = concat_tracks(&[
+            tracks("a", &[(0, "a", 0.), (1, "a", 1.), (2, "a", 2.)]),
+            tracks("b", &[(0, "x", 0.), (1, "y", 1.), (2, "y", 2.)]),
+        ]);
+        let bootstrap_cfg = BootstrapConfig {
+            num_replicates: 100,
+            ..Default::default()
+        };
+        let intervals =
+            evaluate_bootstrap(&predictions, &annotations, &cfg, &bootstrap_cfg).unwrap();
+        let metrics = evaluate(&predictions, &annotations, &cfg)
+            .unwrap()
+            .as_metrics();
+        for (name, interval) in &intervals {
+            assert!((interval.estimate - metrics[name]).abs() < 1e-6, "{name}");
+            assert!(interval.lower <= interval.estimate && interval.estimate <= interval.upper);
+        }
+        // Replicates resample either log twice (no id switch or two id switches).
+        assert_eq!(intervals["IDSW"].lower, 0.);
+        assert_eq!(intervals["IDSW"].upper, 2.);
+        assert_eq!(
+            evaluate_bootstrap(&predictions, &annotations, &cfg, &bootstrap_cfg).unwrap(),
+            intervals
+        );
+    }
+
+    fn concat_tracks(frames: &[DataFrame]) -> DataFrame {
+        let mut frames = frames.iter();
+        let mut data_frame = frames.next().unwrap().clone();
+        for frame in frames {
+            data_frame.vstack_mut(frame).unwrap();
+        }
+        data_frame
+    }
+}
@@ -0,0 +1,8 @@
+//! # export
+//!
+//! Conversion of sweeps and annotations into other dataset formats.
+
+/// KITTI object detection format.
+pub mod kitti;
+/// nuScenes table layout.
+pub mod nuscenes;
@@ -0,0 +1,480 @@
+//! # kitti
+//!
+//! Export of sweeps and annotations in the KITTI object detection format.
+//!
+//! Each sweep becomes a frame `<index>` of a KITTI split directory: the lidar points are written
+//! to `velodyne/<index>.bin`, the calibration of the reference camera to `calib/<index>.txt`, and
+//! the mapped cuboids to `label_2/<index>.txt`. The velodyne frame is the egovehicle frame, which
+//! shares KITTI's axes (x forward, y left, z up). `sweeps.txt` lists the log id and timestamp of
+//! every frame, so results can be mapped back onto the sweeps.
+
+use std::{
+    collections::BTreeMap,
+    f32::consts::PI,
+    fmt::{self, Display},
+    fs,
+    path::Path,
+};
+
+use anyhow::{ensure, Context, Result};
+use ndarray::{s, Array, ArrayView, Ix1, Ix2};
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    data_loader::{DataLoader, Sweep},
+    error::Av2Result,
+    geometry::{aggregation::AccumulationFrame, camera::pinhole_camera::PinholeCamera, se3::SE3},
+    io::ndarray_from_frame,
+    structures::cuboid::CuboidList,
+};
+
+/// Name of the file listing the log id and timestamp of each exported frame.
+pub const SWEEPS_FILE_NAME: &str = "sweeps.txt";
+
+/// Configuration of a KITTI export.
+#[derive(Clone, Debug)]
+pub struct KittiExportConfig {
+    /// Camera whose calibration is exported and whose frame the labels are expressed in.
+    pub camera_name: String,
+    /// KITTI type (e.g., `Car`) of each exported AV2 category. Cuboids of other categories are
+    /// dropped.
+    pub category_mapping: BTreeMap<String, String>,
+    /// Boolean flag to only export labels whose center projects into the camera image.
+    pub only_in_camera_fov: bool,
+}
+
+impl Default for KittiExportConfig {
+    fn default() -> Self {
+        Self {
+            camera_name: "ring_front_center".to_string(),
+            category_mapping: default_category_mapping(),
+            only_in_camera_fov: true,
+        }
+    }
+}
+
+/// Mapping of the AV2 categories onto the closest KITTI types.
+pub fn default_category_mapping() -> BTreeMap<String, String> {
+    [
+        ("REGULAR_VEHICLE", "Car"),
+        ("LARGE_VEHICLE", "Van"),
+        ("BOX_TRUCK", "Truck"),
+        ("TRUCK", "Truck"),
+        ("TRUCK_CAB", "Truck"),
+        ("PEDESTRIAN", "Pedestrian"),
+        ("BICYCLIST", "Cyclist"),
+        ("MOTORCYCLIST", "Cyclist"),
+    ]
+    .into_iter()
+    .map(|(category, kitti_type)| (category.to_string(), kitti_type.to_string()))
+    .collect()
+}
+
+/// Calibration of a KITTI frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KittiCalibration {
+    /// (3,4) projection matrix of the reference camera, shared by `P0` through `P3`.
+    pub p: Array<f32, Ix2>,
+    /// (3,3) rectifying rotation (the identity, since AV2 images are not rectified).
+    pub r0_rect: Array<f32, Ix2>,
+    /// (3,4) transformation from the velodyne (egovehicle) frame to the camera frame.
+    pub tr_velo_to_cam: Array<f32, Ix2>,
+    /// (3,4) transformation from the IMU frame to the velodyne frame (the identity).
+    pub tr_imu_to_velo: Array<f32, Ix2>,
+}
+
+impl KittiCalibration {
+    /// Calibration of `camera`.
+    pub fn from_camera(camera: &PinholeCamera) -> Self {
+        Self {
+            p: camera.p(),
+            r0_rect: Array::eye(3),
+            tr_velo_to_cam: camera.extrinsics().slice(s![..3, ..]).to_owned(),
+            tr_imu_to_velo: Array::eye(4).slice(s![..3, ..]).to_owned(),
+        }
+    }
+}
+
+impl Display for KittiCalibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let row = |name: &str, matrix: &Array<f32, Ix2>| {
+            let values = matrix
+                .iter()
+                .map(|x| format!("{x:.12e}"))
+                .collect::<Vec<_>>();
+            format!("{name}: {}\n", values.join(" "))
+        };
+        for name in ["P0", "P1", "P2", "P3"] {
+            f.write_str(&row(name, &self.p))?;
+        }
+        f.write_str(&row("R0_rect", &self.r0_rect))?;
+        f.write_str(&row("Tr_velo_to_cam", &self.tr_velo_to_cam))?;
+        f.write_str(&row("Tr_imu_to_velo", &self.tr_imu_to_velo))
+    }
+}
+
+/// Object label of a KITTI frame, expressed in the camera frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KittiLabel {
+    /// KITTI type (e.g., `Car`).
+    pub kitti_type: String,
+    /// Fraction of the 2D box outside of the image.
+    pub truncated: f32,
+    /// Occlusion level (always `0`, since AV2 does not annotate occlusion).
+    pub occluded: u8,
+    /// Observation angle in `[-pi, pi]`.
+    pub alpha: f32,
+    /// 2D box `(left, top, right, bottom)` (in pixels) of the projected cuboid.
+    pub bbox: [f32; 4],
+    /// Extents `(height, width, length)` (in meters).
+    pub dimensions_hwl_m: [f32; 3],
+    /// Bottom center `(x, y, z)` (in meters) in the camera frame.
+    pub location_m: [f32; 3],
+    /// Rotation about the camera's y-axis in `[-pi, pi]`.
+    pub rotation_y: f32,
+}
+
+impl Display for KittiLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [left, top, right, bottom] = self.bbox;
+        let [h, w, l] = self.dimensions_hwl_m;
+        let [x, y, z] = self.location_m;
+        write!(
+            f,
+            "{} {:.2} {} {:.2} {left:.2} {top:.2} {right:.2} {bottom:.2} {h:.2} {w:.2} {l:.2} \
+             {x:.2} {y:.2} {z:.2} {:.2}",
+            self.kitti_type, self.truncated, self.occluded, self.alpha, self.rotation_y
+        )
+    }
+}
+
+/// KITTI labels of the mapped `cuboids` (in the egovehicle frame) seen by `camera`.
+pub fn cuboids_to_kitti_labels(
+    cuboids: &CuboidList,
+    camera: &PinholeCamera,
+    cfg: &KittiExportConfig,
+) -> Av2Result<Vec<KittiLabel>> {
+    let cam_se3_ego = camera.ego_se3_cam.inverse();
+    let vertices = cuboids.vertices();
+    let (width_px, height_px) = (camera.width_px() as f32, camera.height_px() as f32);
+
+    let mut labels = vec![];
+    for (i, cuboid) in cuboids.iter().enumerate() {
+        let Some(kitti_type) = cuboid
+            .category
+            .as_ref()
+            .and_then(|x| cfg.category_mapping.get(x))
+        else {
+            continue;
+        };
+        let [length_m, width_m, height_m] = cuboid.dims_lwh_m;
+        let ego_se3_object = SE3::from_quat_wxyz(
+            &ArrayView::from(&cuboid.quat_wxyz),
+            &ArrayView::from(&cuboid.translation_m),
+        )?;
+
+        let center_cam = cam_se3_ego.transform_from(
+            &ArrayView::from(&cuboid.translation_m)
+                .into_shape((1, 3))
+                .unwrap(),
+        );
+        let center_uv = _project(camera, &center_cam.row(0));
+        let is_in_fov = center_uv
+            .is_some_and(|[u, v]| (0. ..width_px).contains(&u) && (0. ..height_px).contains(&v));
+        if cfg.only_in_camera_fov && !is_in_fov {
+            continue;
+        }
+
+        // Box of the vertices in front of the camera, clipped to the image.
+        let vertices_cam = cam_se3_ego.transform_from(&vertices.slice(s![i, .., ..]));
+        let uv = vertices_cam
+            .outer_iter()
+            .filter_map(|x| _project(camera, &x))
+            .collect::<Vec<_>>();
+        let (bbox, truncated) = match uv.is_empty() {
+            true => ([0.; 4], 1.),
+            false => {
+                let bounds = |axis: usize| {
+                    uv.iter()
+                        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), x| {
+                            (lo.min(x[axis]), hi.max(x[axis]))
+                        })
+                };
+                let ((left, right), (top, bottom)) = (bounds(0), bounds(1));
+                let clipped = [
+                    left.clamp(0., width_px - 1.),
+                    top.clamp(0., height_px - 1.),
+                    right.clamp(0., width_px - 1.),
+                    bottom.clamp(0., height_px - 1.),
+                ];
+                let area = (right - left) * (bottom - top);
+                let clipped_area = (clipped[2] - clipped[0]) * (clipped[3] - clipped[1]);
+                let truncated = match area > 0. {
+                    true => (1. - clipped_area / area).clamp(0., 1.),
+                    false => 1.,
+                };
+                (clipped, truncated)
+            }
+        };
+
+        let bottom_center_ego = ego_se3_object.transform_from(
+            &Array::from_shape_vec((1, 3), vec![0., 0., -height_m / 2.])
+                .unwrap()
+                .view(),
+        );
+        let bottom_center_cam = cam_se3_ego.transform_from(&bottom_center_ego.view());
+        let heading_cam = cam_se3_ego
+            .rotation()
+            .dot(&ego_se3_object.rotation().column(0));
+        let rotation_y = (-heading_cam[2]).atan2(heading_cam[0]);
+        let [x, y, z] = [0, 1, 2].map(|j| bottom_center_cam[[0, j]]);
+        labels.push(KittiLabel {
+            kitti_type: kitti_type.clone(),
+            truncated,
+            occluded: 0,
+            alpha: _wrap_angle(rotation_y - x.atan2(z)),
+            bbox,
+            dimensions_hwl_m: [height_m, width_m, length_m],
+            location_m: [x, y, z],
+            rotation_y,
+        });
+    }
+    Ok(labels)
+}
+
+/// Write (N,4) `x`, `y`, `z`, and reflectance values as a KITTI velodyne file (little-endian
+/// `f32` rows).
+pub fn write_velodyne_bin(path: &Path, points: &ArrayView<f32, Ix2>) -> Result<()> {
+    ensure!(
+        points.ncols() == 4,
+        "Expected (N,4) points, got {:?}.",
+        points.shape()
+    );
+    let bytes = points
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    fs::write(path, bytes).with_context(|| format!("Failed to write {}.", path.display()))
+}
+
+/// Read a KITTI velodyne file as (N,4) `x`, `y`, `z`, and reflectance values.
+pub fn read_velodyne_bin(path: &Path) -> Result<Array<f32, Ix2>> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}.", path.display()))?;
+    ensure!(
+        bytes.len() % 16 == 0,
+        "{} is not a sequence of 4 `f32` rows.",
+        path.display()
+    );
+    let values = bytes
+        .chunks_exact(4)
+        .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+        .collect::<Vec<_>>();
+    Ok(Array::from_shape_vec((values.len() / 4, 4), values)?)
+}
+
+/// Export every sweep of `data_loader` to the KITTI split directory `dst_dir`, returning the
+/// number of exported frames. Labels are only written for sweeps with annotations.
+pub fn export_split(
+    data_loader: &DataLoader,
+    dst_dir: &Path,
+    cfg: &KittiExportConfig,
+) -> Result<usize> {
+    for name in ["velodyne", "calib", "label_2"] {
+        fs::create_dir_all(dst_dir.join(name))?;
+    }
+    let sweep_ids = (0..data_loader.len())
+        .into_par_iter()
+        .map(|index| {
+            let sweep = data_loader.get(index);
+            let log_dir = data_loader.log_dir(&sweep.sweep_uuid.0);
+            _export_sweep(&sweep, &log_dir, dst_dir, index, cfg)?;
+            Ok(format!(
+                "{index:06} {} {}",
+                sweep.sweep_uuid.0, sweep.sweep_uuid.1
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    fs::write(dst_dir.join(SWEEPS_FILE_NAME), sweep_ids.join("\n") + "\n")?;
+    Ok(sweep_ids.len())
+}
+
+/// Write the velodyne, calibration, and (if annotated) label files of a sweep.
+fn _export_sweep(
+    sweep: &Sweep,
+    log_dir: &Path,
+    dst_dir: &Path,
+    index: usize,
+    cfg: &KittiExportConfig,
+) -> Result<()> {
+    ensure!(
+        sweep.lidar_frame == AccumulationFrame::Ego.to_string(),
+        "Expected lidar in the egovehicle frame, got `{}`.",
+        sweep.lidar_frame
+    );
+    let file_stem = format!("{index:06}");
+    let mut points = ndarray_from_frame(&sweep.lidar.0, cols(["x", "y", "z", "intensity"]))?;
+    points.column_mut(3).mapv_inplace(|x| x / u8::MAX as f32);
+    write_velodyne_bin(
+        &dst_dir.join(format!("velodyne/{file_stem}.bin")),
+        &points.view(),
+    )?;
+
+    let camera = PinholeCamera::load(log_dir, &cfg.camera_name)?;
+    let calibration = KittiCalibration::from_camera(&camera);
+    fs::write(
+        dst_dir.join(format!("calib/{file_stem}.txt")),
+        calibration.to_string(),
+    )?;
+
+    if let Some(cuboids) = &sweep.cuboids {
+        let cuboids = CuboidList::from_data_frame(&cuboids.0)?;
+        let labels = cuboids_to_kitti_labels(&cuboids, &camera, cfg)?;
+        let lines = labels.iter().map(|x| format!("{x}\n")).collect::<String>();
+        fs::write(dst_dir.join(format!("label_2/{file_stem}.txt")), lines)?;
+    }
+    Ok(())
+}
+
+/// Pixel coordinates of a camera frame point, if it is in front of the camera. Lens distortion
+/// is ignored, matching the pinhole model of the KITTI projection matrices.
+fn _project(camera: &PinholeCamera, point_cam: &ArrayView<f32, Ix1>) -> Option<[f32; 2]> {
+    let intrinsics = &camera.intrinsics;
+    (point_cam[2] > 0.).then(|| {
+        [
+            intrinsics.fx_px * point_cam[0] / point_cam[2] + intrinsics.cx_px,
+            intrinsics.fy_px * point_cam[1] / point_cam[2] + intrinsics.cy_px,
+        ]
+    })
+}
+
+/// Wrap an angle (in radians) into `[-pi, pi]`.
+fn _wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2. * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::FRAC_PI_2, fs};
+
+    use ndarray::array;
+
+    use super::{
+        cuboids_to_kitti_labels, export_split, read_velodyne_bin, KittiExportConfig,
+        SWEEPS_FILE_NAME,
+    };
+    use crate::{
+        data_loader::DataLoader,
+        geometry::{
+            camera::{
+                distortion::RadialDistortion,
+                pinhole_camera::{Intrinsics, PinholeCamera},
+            },
+            se3::SE3,
+        },
+        structures::cuboid::CuboidList,
+        synthetic::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig},
+    };
+
+    #[test]
+    fn test_export_split() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let dst_dir = root_dir.join("kitti/training");
+        let cfg = KittiExportConfig {
+            only_in_camera_fov: false,
+            ..Default::default()
+        };
+        assert_eq!(export_split(&data_loader, &dst_dir, &cfg).unwrap(), 2);
+
+        let sweep = data_loader.get(1);
+        let points = read_velodyne_bin(&dst_dir.join("velodyne/000001.bin")).unwrap();
+        assert_eq!(points.nrows(), sweep.lidar.0.height());
+        assert!(points.column(3).iter().all(|x| (0. ..=1.).contains(x)));
+
+        let calibration = fs::read_to_string(dst_dir.join("calib/000001.txt")).unwrap();
+        let rows = calibration.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 7);
+        assert!(rows[2].starts_with("P2: "));
+        assert_eq!(rows[5].split_whitespace().count(), 13);
+
+        let labels = fs::read_to_string(dst_dir.join("label_2/000001.txt")).unwrap();
+        assert!(!labels.is_empty());
+        for label in labels.lines() {
+            let fields = label.split_whitespace().collect::<Vec<_>>();
+            assert_eq!(fields.len(), 15);
+            assert!(["Car", "Pedestrian", "Cyclist", "Truck", "Van"].contains(&fields[0]));
+        }
+
+        let sweeps = fs::read_to_string(dst_dir.join(SWEEPS_FILE_NAME)).unwrap();
+        assert_eq!(
+            sweeps.lines().nth(1).unwrap(),
+            format!("000001 {} {}", sweep.sweep_uuid.0, sweep.sweep_uuid.1)
+        );
+    }
+
+    #[test]
+    fn test_cuboids_to_kitti_labels() {
+        // Forward-facing camera at the egovehicle origin (x right, y down, z forward).
+        let camera = PinholeCamera {
+            ego_se3_cam: SE3::from_rotation(
+                &array![[0., 0., 1.], [-1., 0., 0.], [0., -1., 0.]].view(),
+                &array![0., 0., 0.].view(),
+            ),
+            intrinsics: Intrinsics {
+                fx_px: 100.,
+                fy_px: 100.,
+                cx_px: 100.,
+                cy_px: 50.,
+                width_px: 200,
+                height_px: 100,
+                distortion: RadialDistortion::default(),
+            },
+            camera_name: "ring_front_center".to_string(),
+        };
+        let cuboids = CuboidList::new(
+            array![
+                [10_f32, 0., 0., 4., 2., 1.5, 1., 0., 0., 0.],
+                [-10., 0., 0., 4., 2., 1.5, 1., 0., 0., 0.],
+                [10., 0., 0., 0.5, 0.5, 1.5, 1., 0., 0., 0.],
+            ],
+            Some(vec![
+                "REGULAR_VEHICLE".to_string(),
+                "REGULAR_VEHICLE".to_string(),
+                "SIGN".to_string(),
+            ]),
+            None,
+        )
+        .unwrap();
+        let labels =
+            cuboids_to_kitti_labels(&cuboids, &camera, &KittiExportConfig::default()).unwrap();
+        assert_eq!(labels.len(), 1);
+
+        let label = &labels[0];
+        assert_eq!(label.kitti_type, "Car");
+        assert_eq!(label.dimensions_hwl_m, [1.5, 2., 4.]);
+        let location = [0., 0.75, 10.];
+        for (x, y) in label.location_m.iter().zip(location) {
+            assert!((x - y).abs() < 1e-5);
+        }
+        assert!((label.rotation_y + FRAC_PI_2).abs() < 1e-5);
+        assert!((label.alpha + FRAC_PI_2).abs() < 1e-5);
+        assert_eq!(label.truncated, 0.);
+        // The box spans the near face (8m away) horizontally.
+        assert!((label.bbox[0] - 87.5).abs() < 1e-3 && (label.bbox[2] - 112.5).abs() < 1e-3);
+        assert_eq!(
+            label.to_string(),
+            "Car 0.00 0 -1.57 87.50 40.62 112.50 59.38 1.50 2.00 4.00 0.00 0.75 10.00 -1.57"
+        );
+    }
+}
@@ -0,0 +1,696 @@
+//! # nuscenes
+//!
+//! Export of sweeps and annotations in the nuScenes table layout.
+//!
+//! Every log becomes a scene and every sweep a keyframe sample with one `LIDAR_TOP` sample data
+//! record. The tables are written as JSON to `<dst_dir>/<version>/`, and the lidar points as
+//! nuScenes `.pcd.bin` files (`x`, `y`, `z`, `intensity`, and ring index) under
+//! `<dst_dir>/samples/LIDAR_TOP/`. The lidar sensor is the egovehicle frame, while annotations and
+//! ego poses are in the city frame, which plays the role of the nuScenes global frame.
+//!
+//! Tokens are derived from the log ids, timestamps, and track UUIDs, so re-exporting a split
+//! yields the same tokens. AV2 does not annotate visibility, so visibility tokens are empty.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::BufWriter,
+    path::Path,
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use serde::Serialize;
+
+use crate::{
+    data_loader::DataLoader, geometry::aggregation::AccumulationFrame, io::ndarray_from_frame,
+    structures::cuboid::CuboidList,
+};
+
+/// Channel of the exported lidar sample data.
+pub const LIDAR_CHANNEL: &str = "LIDAR_TOP";
+
+/// Attribute of cycles with a rider.
+const CYCLE_WITH_RIDER: &str = "cycle.with_rider";
+/// Attribute of cycles without a rider.
+const CYCLE_WITHOUT_RIDER: &str = "cycle.without_rider";
+
+/// Configuration of a nuScenes export.
+#[derive(Clone, Debug)]
+pub struct NuScenesExportConfig {
+    /// Version (i.e., the name of the table directory), e.g., `v1.0-trainval`.
+    pub version: String,
+    /// nuScenes category (e.g., `vehicle.car`) of each exported AV2 category. Cuboids of other
+    /// categories are dropped.
+    pub category_mapping: BTreeMap<String, String>,
+}
+
+impl Default for NuScenesExportConfig {
+    fn default() -> Self {
+        Self {
+            version: "v1.0-trainval".to_string(),
+            category_mapping: default_category_mapping(),
+        }
+    }
+}
+
+/// Mapping of the AV2 categories onto the nuScenes categories. Signs and railed vehicles have no
+/// nuScenes counterpart and are not mapped.
+pub fn default_category_mapping() -> BTreeMap<String, String> {
+    [
+        ("ANIMAL", "animal"),
+        ("ARTICULATED_BUS", "vehicle.bus.bendy"),
+        ("BICYCLE", "vehicle.bicycle"),
+        ("BICYCLIST", "vehicle.bicycle"),
+        ("BOLLARD", "movable_object.barrier"),
+        ("BOX_TRUCK", "vehicle.truck"),
+        ("BUS", "vehicle.bus.rigid"),
+        ("CONSTRUCTION_BARREL", "movable_object.trafficcone"),
+        ("CONSTRUCTION_CONE", "movable_object.trafficcone"),
+        ("DOG", "animal"),
+        ("LARGE_VEHICLE", "vehicle.truck"),
+        ("MESSAGE_BOARD_TRAILER", "vehicle.trailer"),
+        (
+            "MOBILE_PEDESTRIAN_CROSSING_SIGN",
+            "movable_object.pushable_pullable",
+        ),
+        ("MOTORCYCLE", "vehicle.motorcycle"),
+        ("MOTORCYCLIST", "vehicle.motorcycle"),
+        ("OFFICIAL_SIGNALER", "human.pedestrian.police_officer"),
+        ("PEDESTRIAN", "human.pedestrian.adult"),
+        ("REGULAR_VEHICLE", "vehicle.car"),
+        ("SCHOOL_BUS", "vehicle.bus.rigid"),
+        ("STROLLER", "human.pedestrian.stroller"),
+        ("TRAFFIC_LIGHT_TRAILER", "vehicle.trailer"),
+        ("TRUCK", "vehicle.truck"),
+        ("TRUCK_CAB", "vehicle.truck"),
+        ("VEHICULAR_TRAILER", "vehicle.trailer"),
+        ("WHEELCHAIR", "human.pedestrian.wheelchair"),
+        ("WHEELED_DEVICE", "human.pedestrian.personal_mobility"),
+        ("WHEELED_RIDER", "human.pedestrian.personal_mobility"),
+    ]
+    .into_iter()
+    .map(|(category, nuscenes_category)| (category.to_string(), nuscenes_category.to_string()))
+    .collect()
+}
+
+/// Number of records of each exported table.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NuScenesExportSummary {
+    /// Number of scenes (i.e., logs).
+    pub num_scenes: usize,
+    /// Number of samples (i.e., sweeps).
+    pub num_samples: usize,
+    /// Number of instances (i.e., tracks).
+    pub num_instances: usize,
+    /// Number of sample annotations (i.e., cuboids).
+    pub num_sample_annotations: usize,
+}
+
+/// Record of the category and attribute tables.
+#[derive(Serialize)]
+struct _Definition {
+    token: String,
+    name: String,
+    description: String,
+}
+
+/// Record of the `visibility` table.
+#[derive(Serialize)]
+struct _Visibility {
+    token: String,
+    level: String,
+    description: String,
+}
+
+/// Record of the `sensor` table.
+#[derive(Serialize)]
+struct _Sensor {
+    token: String,
+    channel: String,
+    modality: String,
+}
+
+/// Record of the `calibrated_sensor` table.
+#[derive(Serialize)]
+struct _CalibratedSensor {
+    token: String,
+    sensor_token: String,
+    translation: [f32; 3],
+    rotation: [f32; 4],
+    camera_intrinsic: Vec<[f32; 3]>,
+}
+
+/// Record of the `log` table.
+#[derive(Serialize)]
+struct _Log {
+    token: String,
+    logfile: String,
+    vehicle: String,
+    date_captured: String,
+    location: String,
+}
+
+/// Record of the `map` table.
+#[derive(Serialize)]
+struct _Map {
+    token: String,
+    log_tokens: Vec<String>,
+    category: String,
+    filename: String,
+}
+
+/// Record of the `scene` table.
+#[derive(Serialize)]
+struct _Scene {
+    token: String,
+    log_token: String,
+    nbr_samples: usize,
+    first_sample_token: String,
+    last_sample_token: String,
+    name: String,
+    description: String,
+}
+
+/// Record of the `sample` table.
+#[derive(Serialize)]
+struct _Sample {
+    token: String,
+    timestamp: u64,
+    prev: String,
+    next: String,
+    scene_token: String,
+}
+
+/// Record of the `sample_data` table.
+#[derive(Serialize)]
+struct _SampleData {
+    token: String,
+    sample_token: String,
+    ego_pose_token: String,
+    calibrated_sensor_token: String,
+    timestamp: u64,
+    fileformat: String,
+    is_key_frame: bool,
+    height: usize,
+    width: usize,
+    filename: String,
+    prev: String,
+    next: String,
+}
+
+/// Record of the `ego_pose` table.
+#[derive(Serialize)]
+struct _EgoPose {
+    token: String,
+    timestamp: u64,
+    rotation: [f32; 4],
+    translation: [f32; 3],
+}
+
+/// Record of the `instance` table.
+#[derive(Serialize)]
+struct _Instance {
+    token: String,
+    category_token: String,
+    nbr_annotations: usize,
+    first_annotation_token: String,
+    last_annotation_token: String,
+}
+
+/// Record of the `sample_annotation` table.
+#[derive(Serialize)]
+struct _SampleAnnotation {
+    token: String,
+    sample_token: String,
+    instance_token: String,
+    visibility_token: String,
+    attribute_tokens: Vec<String>,
+    translation: [f32; 3],
+    size: [f32; 3],
+    rotation: [f32; 4],
+    prev: String,
+    next: String,
+    num_lidar_pts: u32,
+    num_radar_pts: u32,
+}
+
+/// Cuboid of a sweep in the city frame.
+struct _Annotation {
+    track_uuid: String,
+    category: String,
+    /// Parameters in `CUBOID_COLUMNS` order.
+    params: [f32; 10],
+    num_lidar_pts: u32,
+}
+
+/// Exported sweep.
+struct _SweepRecord {
+    log_id: String,
+    timestamp_ns: u64,
+    /// City pose `(tx_m, ty_m, tz_m, qw, qx, qy, qz)` of the egovehicle.
+    city_pose: [f32; 7],
+    filename: String,
+    annotations: Vec<_Annotation>,
+}
+
+/// Export every sweep of `data_loader` to the nuScenes tables of `dst_dir`.
+pub fn export_split(
+    data_loader: &DataLoader,
+    dst_dir: &Path,
+    cfg: &NuScenesExportConfig,
+) -> Result<NuScenesExportSummary> {
+    let lidar_dir = dst_dir.join("samples").join(LIDAR_CHANNEL);
+    let table_dir = dst_dir.join(&cfg.version);
+    fs::create_dir_all(&lidar_dir)?;
+    fs::create_dir_all(&table_dir)?;
+
+    let mut records = (0..data_loader.len())
+        .into_par_iter()
+        .map(|index| _export_sweep(data_loader, index, dst_dir, cfg))
+        .collect::<Result<Vec<_>>>()?;
+    records.sort_by(|a, b| (&a.log_id, a.timestamp_ns).cmp(&(&b.log_id, b.timestamp_ns)));
+
+    let nuscenes_categories = cfg
+        .category_mapping
+        .values()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let categories = nuscenes_categories
+        .iter()
+        .map(|name| _Definition {
+            token: _token(&["category", name]),
+            name: name.clone(),
+            description: String::new(),
+        })
+        .collect::<Vec<_>>();
+    let attributes = [CYCLE_WITH_RIDER, CYCLE_WITHOUT_RIDER]
+        .map(|name| _Definition {
+            token: _token(&["attribute", name]),
+            name: name.to_string(),
+            description: String::new(),
+        })
+        .into_iter()
+        .collect::<Vec<_>>();
+    let visibility = [
+        ("1", "v0-40"),
+        ("2", "v40-60"),
+        ("3", "v60-80"),
+        ("4", "v80-100"),
+    ]
+    .map(|(token, level)| _Visibility {
+        token: token.to_string(),
+        level: level.to_string(),
+        description: String::new(),
+    })
+    .into_iter()
+    .collect::<Vec<_>>();
+    let sensor = _Sensor {
+        token: _token(&["sensor", LIDAR_CHANNEL]),
+        channel: LIDAR_CHANNEL.to_string(),
+        modality: "lidar".to_string(),
+    };
+
+    let log_ids = records
+        .iter()
+        .map(|x| x.log_id.clone())
+        .dedup()
+        .collect::<Vec<_>>();
+    let logs = log_ids
+        .iter()
+        .map(|log_id| _Log {
+            token: _token(&["log", log_id]),
+            logfile: log_id.clone(),
+            vehicle: String::new(),
+            date_captured: String::new(),
+            location: String::new(),
+        })
+        .collect::<Vec<_>>();
+    let calibrated_sensors = log_ids
+        .iter()
+        .map(|log_id| _CalibratedSensor {
+            token: _token(&["calibrated_sensor", log_id, LIDAR_CHANNEL]),
+            sensor_token: sensor.token.clone(),
+            translation: [0.; 3],
+            rotation: [1., 0., 0., 0.],
+            camera_intrinsic: vec![],
+        })
+        .collect::<Vec<_>>();
+    let maps = vec![_Map {
+        token: _token(&["map"]),
+        log_tokens: logs.iter().map(|x| x.token.clone()).collect(),
+        category: "semantic_prior".to_string(),
+        filename: String::new(),
+    }];
+
+    let mut scenes = vec![];
+    let mut samples = vec![];
+    let mut sample_data = vec![];
+    let mut ego_poses = vec![];
+    let mut sample_annotations = vec![];
+    let mut tracks: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for log_id in &log_ids {
+        let log_records = records
+            .iter()
+            .filter(|x| &x.log_id == log_id)
+            .collect::<Vec<_>>();
+        let sample_tokens = log_records
+            .iter()
+            .map(|x| _token(&["sample", log_id, &x.timestamp_ns.to_string()]))
+            .collect::<Vec<_>>();
+        let linked = |tokens: &[String], i: usize| {
+            let prev = i.checked_sub(1).map(|j| tokens[j].clone());
+            (
+                prev.unwrap_or_default(),
+                tokens.get(i + 1).cloned().unwrap_or_default(),
+            )
+        };
+        let sample_data_tokens = sample_tokens
+            .iter()
+            .map(|x| _token(&["sample_data", x]))
+            .collect::<Vec<_>>();
+
+        let scene_token = _token(&["scene", log_id]);
+        scenes.push(_Scene {
+            token: scene_token.clone(),
+            log_token: _token(&["log", log_id]),
+            nbr_samples: log_records.len(),
+            first_sample_token: sample_tokens.first().cloned().unwrap_or_default(),
+            last_sample_token: sample_tokens.last().cloned().unwrap_or_default(),
+            name: log_id.clone(),
+            description: String::new(),
+        });
+        for (i, record) in log_records.iter().enumerate() {
+            let timestamp_us = record.timestamp_ns / 1000;
+            let (prev, next) = linked(&sample_tokens, i);
+            samples.push(_Sample {
+                token: sample_tokens[i].clone(),
+                timestamp: timestamp_us,
+                prev,
+                next,
+                scene_token: scene_token.clone(),
+            });
+
+            let ego_pose_token = _token(&["ego_pose", &sample_tokens[i]]);
+            let [tx, ty, tz, qw, qx, qy, qz] = record.city_pose;
+            ego_poses.push(_EgoPose {
+                token: ego_pose_token.clone(),
+                timestamp: timestamp_us,
+                rotation: [qw, qx, qy, qz],
+                translation: [tx, ty, tz],
+            });
+
+            let (prev, next) = linked(&sample_data_tokens, i);
+            sample_data.push(_SampleData {
+                token: sample_data_tokens[i].clone(),
+                sample_token: sample_tokens[i].clone(),
+                ego_pose_token,
+                calibrated_sensor_token: _token(&["calibrated_sensor", log_id, LIDAR_CHANNEL]),
+                timestamp: timestamp_us,
+                fileformat: "pcd".to_string(),
+                is_key_frame: true,
+                height: 0,
+                width: 0,
+                filename: record.filename.clone(),
+                prev,
+                next,
+            });
+
+            for annotation in &record.annotations {
+                let instance_token = _token(&["instance", log_id, &annotation.track_uuid]);
+                let attribute = match annotation.category.as_str() {
+                    "BICYCLIST" | "MOTORCYCLIST" => Some(CYCLE_WITH_RIDER),
+                    "BICYCLE" | "MOTORCYCLE" => Some(CYCLE_WITHOUT_RIDER),
+                    _ => None,
+                };
+                let p = annotation.params;
+                tracks
+                    .entry(instance_token.clone())
+                    .or_default()
+                    .push(sample_annotations.len());
+                sample_annotations.push((
+                    annotation.category.clone(),
+                    _SampleAnnotation {
+                        token: _token(&["sample_annotation", &sample_tokens[i], &instance_token]),
+                        sample_token: sample_tokens[i].clone(),
+                        instance_token,
+                        visibility_token: String::new(),
+                        attribute_tokens: attribute
+                            .map(|x| vec![_token(&["attribute", x])])
+                            .unwrap_or_default(),
+                        translation: [p[0], p[1], p[2]],
+                        size: [p[4], p[3], p[5]],
+                        rotation: [p[6], p[7], p[8], p[9]],
+                        prev: String::new(),
+                        next: String::new(),
+                        num_lidar_pts: annotation.num_lidar_pts,
+                        num_radar_pts: 0,
+                    },
+                ));
+            }
+        }
+    }
+
+    // Link the annotations of each track in time.
+    let mut instances = vec![];
+    for (instance_token, indices) in &tracks {
+        let tokens = indices
+            .iter()
+            .map(|&i| sample_annotations[i].1.token.clone())
+            .collect::<Vec<_>>();
+        for (j, &i) in indices.iter().enumerate() {
+            let annotation = &mut sample_annotations[i].1;
+            annotation.prev = j
+                .checked_sub(1)
+                .map(|k| tokens[k].clone())
+                .unwrap_or_default();
+            annotation.next = tokens.get(j + 1).cloned().unwrap_or_default();
+        }
+        let category = &sample_annotations[indices[0]].0;
+        instances.push(_Instance {
+            token: instance_token.clone(),
+            category_token: _token(&["category", &cfg.category_mapping[category]]),
+            nbr_annotations: indices.len(),
+            first_annotation_token: tokens[0].clone(),
+            last_annotation_token: tokens[tokens.len() - 1].clone(),
+        });
+    }
+    let sample_annotations = sample_annotations
+        .into_iter()
+        .map(|(_, x)| x)
+        .collect::<Vec<_>>();
+
+    _write_table(&table_dir, "category", &categories)?;
+    _write_table(&table_dir, "attribute", &attributes)?;
+    _write_table(&table_dir, "visibility", &visibility)?;
+    _write_table(&table_dir, "sensor", &[sensor])?;
+    _write_table(&table_dir, "calibrated_sensor", &calibrated_sensors)?;
+    _write_table(&table_dir, "log", &logs)?;
+    _write_table(&table_dir, "map", &maps)?;
+    _write_table(&table_dir, "scene", &scenes)?;
+    _write_table(&table_dir, "sample", &samples)?;
+    _write_table(&table_dir, "sample_data", &sample_data)?;
+    _write_table(&table_dir, "ego_pose", &ego_poses)?;
+    _write_table(&table_dir, "instance", &instances)?;
+    _write_table(&table_dir, "sample_annotation", &sample_annotations)?;
+    Ok(NuScenesExportSummary {
+        num_scenes: scenes.len(),
+        num_samples: samples.len(),
+        num_instances: instances.len(),
+        num_sample_annotations: sample_annotations.len(),
+    })
+}
+
+/// Write the lidar file of a sweep and collect its pose and mapped cuboids in the city frame.
+fn _export_sweep(
+    data_loader: &DataLoader,
+    index: usize,
+    dst_dir: &Path,
+    cfg: &NuScenesExportConfig,
+) -> Result<_SweepRecord> {
+    let sweep = data_loader.get(index);
+    let (log_id, timestamp_ns) = sweep.sweep_uuid.clone();
+    ensure!(
+        sweep.lidar_frame == AccumulationFrame::Ego.to_string(),
+        "Expected lidar in the egovehicle frame, got `{}`.",
+        sweep.lidar_frame
+    );
+    let city_se3_ego = sweep
+        .metadata()
+        .city_se3_ego
+        .with_context(|| format!("Pose of log {log_id} at {timestamp_ns} not found."))?;
+
+    let filename =
+        format!("samples/{LIDAR_CHANNEL}/{log_id}__{LIDAR_CHANNEL}__{timestamp_ns}.pcd.bin");
+    let points = ndarray_from_frame(
+        &sweep.lidar.0,
+        cols(["x", "y", "z", "intensity", "laser_number"]),
+    )?;
+    let bytes = points
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+    fs::write(dst_dir.join(&filename), bytes)?;
+
+    let translation = &city_se3_ego.translation;
+    let quat_wxyz = city_se3_ego.quat_wxyz();
+    let city_pose = [
+        translation[0],
+        translation[1],
+        translation[2],
+        quat_wxyz[0],
+        quat_wxyz[1],
+        quat_wxyz[2],
+        quat_wxyz[3],
+    ];
+
+    let mut annotations = vec![];
+    if let Some(cuboids) = &sweep.cuboids {
+        let num_lidar_pts = match cuboids.0.column("num_interior_pts") {
+            Ok(x) => x
+                .cast(&DataType::UInt32)?
+                .u32()?
+                .into_iter()
+                .map(|x| x.unwrap_or(0))
+                .collect(),
+            Err(_) => vec![0; cuboids.0.height()],
+        };
+        let cuboids = CuboidList::from_data_frame(&cuboids.0)?;
+        let params = city_se3_ego.transform_cuboids(&cuboids.params());
+        let (Some(categories), Some(track_uuids)) = (cuboids.categories(), cuboids.track_uuids())
+        else {
+            bail!("Annotations of log {log_id} need categories and track UUIDs.");
+        };
+        for (i, category) in categories.iter().enumerate() {
+            if !cfg.category_mapping.contains_key(category) {
+                continue;
+            }
+            annotations.push(_Annotation {
+                track_uuid: track_uuids[i].clone(),
+                category: category.clone(),
+                params: std::array::from_fn(|j| params[[i, j]]),
+                num_lidar_pts: num_lidar_pts[i],
+            });
+        }
+    }
+    Ok(_SweepRecord {
+        log_id,
+        timestamp_ns,
+        city_pose,
+        filename,
+        annotations,
+    })
+}
+
+/// Write a table as `<name>.json`.
+fn _write_table<T: Serialize>(table_dir: &Path, name: &str, records: &[T]) -> Result<()> {
+    let path = table_dir.join(format!("{name}.json"));
+    let file =
+        fs::File::create(&path).with_context(|| format!("Failed to create {}.", path.display()))?;
+    serde_json::to_writer(BufWriter::new(file), records)?;
+    Ok(())
+}
+
+/// 32 character hexadecimal token of the `parts` (128-bit FNV-1a hash).
+fn _token(parts: &[&str]) -> String {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    let hash = parts
+        .iter()
+        .flat_map(|x| x.bytes().chain([0]))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        });
+    format!("{hash:032x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use serde_json::Value;
+
+    use super::{default_category_mapping, export_split, NuScenesExportConfig};
+    use crate::{
+        data_loader::DataLoader,
+        evaluation::detection::COMPETITION_CATEGORIES,
+        synthetic::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig},
+    };
+
+    #[test]
+    fn test_default_category_mapping() {
+        let mapping = default_category_mapping();
+        for category in COMPETITION_CATEGORIES {
+            match category {
+                "SIGN" | "STOP_SIGN" => assert!(!mapping.contains_key(category)),
+                _ => assert!(mapping.contains_key(category), "{category} is not mapped."),
+            }
+        }
+    }
+
+    #[test]
+    fn test_export_split() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 2,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let dst_dir = root_dir.join("nuscenes");
+        let cfg = NuScenesExportConfig::default();
+        let summary = export_split(&data_loader, &dst_dir, &cfg).unwrap();
+        assert_eq!(summary.num_scenes, 2);
+        assert_eq!(summary.num_samples, 6);
+        assert!(summary.num_sample_annotations > summary.num_instances);
+
+        let table = |name: &str| -> Vec<Value> {
+            let path = dst_dir.join(format!("v1.0-trainval/{name}.json"));
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+        };
+        let samples = table("sample");
+        let scenes = table("scene");
+        assert_eq!(scenes[0]["first_sample_token"], samples[0]["token"]);
+        assert_eq!(samples[0]["next"], samples[1]["token"]);
+        assert_eq!(samples[2]["next"], "");
+        assert_eq!(samples[0]["token"].as_str().unwrap().len(), 32);
+
+        // Annotations of a track are linked in time and reference their instance.
+        let annotations = table("sample_annotation");
+        let instances = table("instance");
+        assert_eq!(annotations.len(), summary.num_sample_annotations);
+        let instance = instances
+            .iter()
+            .find(|x| x["nbr_annotations"] == 3)
+            .unwrap();
+        let first = annotations
+            .iter()
+            .find(|x| x["token"] == instance["first_annotation_token"])
+            .unwrap();
+        assert_eq!(first["prev"], "");
+        assert_eq!(first["instance_token"], instance["token"]);
+        let second = annotations
+            .iter()
+            .find(|x| x["token"] == first["next"])
+            .unwrap();
+        assert_eq!(second["prev"], first["token"]);
+
+        let sample_data = table("sample_data");
+        let filename = sample_data[0]["filename"].as_str().unwrap();
+        let num_bytes = fs::metadata(dst_dir.join(filename)).unwrap().len() as usize;
+        assert_eq!(num_bytes, 5 * 4 * data_loader.get(0).lidar.0.height());
+        assert_eq!(table("category").len(), {
+            let mut categories = cfg.category_mapping.values().collect::<Vec<_>>();
+            categories.sort();
+            categories.dedup();
+            categories.len()
+        });
+    }
+}
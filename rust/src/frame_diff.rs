@@ -0,0 +1,279 @@
+//! # frame_diff
+//!
+//! Column-wise comparison of two versions of a derived `DataFrame`.
+//!
+//! Numeric columns are compared with absolute and relative tolerances, and every other column
+//! exactly. Nulls (and NaNs) only match nulls (and NaNs). This is meant for regression tests of
+//! preprocessing pipelines, e.g., after upgrading the crate.
+
+use std::fmt;
+
+use anyhow::Result;
+use polars::prelude::*;
+
+/// Frame diff configuration.
+#[derive(Clone, Debug)]
+pub struct FrameDiffConfig {
+    /// Absolute tolerance of numeric columns.
+    pub abs_tolerance: f64,
+    /// Relative tolerance of numeric columns (w.r.t. the expected value).
+    pub rel_tolerance: f64,
+    /// Columns which are not compared.
+    pub ignore_columns: Vec<String>,
+}
+
+impl Default for FrameDiffConfig {
+    fn default() -> Self {
+        Self {
+            abs_tolerance: 1e-6,
+            rel_tolerance: 1e-5,
+            ignore_columns: vec![],
+        }
+    }
+}
+
+/// Discrepancies of a column present in both frames.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnDiscrepancy {
+    /// Column name.
+    pub column: String,
+    /// Number of compared rows whose values differ beyond the tolerances.
+    pub num_mismatches: usize,
+    /// First mismatching row.
+    pub first_mismatch_row: usize,
+    /// Largest absolute difference of the non-null values (numeric columns only).
+    pub max_abs_diff: Option<f64>,
+}
+
+/// Differences between an expected and an actual frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameDiff {
+    /// Heights of the expected and actual frames. Only the common rows are compared.
+    pub heights: (usize, usize),
+    /// Columns of the expected frame missing from the actual one.
+    pub missing_columns: Vec<String>,
+    /// Columns of the actual frame absent from the expected one.
+    pub extra_columns: Vec<String>,
+    /// Columns whose expected and actual data types differ, as `(column, expected, actual)`.
+    pub dtype_mismatches: Vec<(String, DataType, DataType)>,
+    /// Columns whose values differ.
+    pub discrepancies: Vec<ColumnDiscrepancy>,
+}
+
+impl FrameDiff {
+    /// Returns `true` if the frames match.
+    pub fn is_empty(&self) -> bool {
+        self.heights.0 == self.heights.1
+            && self.missing_columns.is_empty()
+            && self.extra_columns.is_empty()
+            && self.dtype_mismatches.is_empty()
+            && self.discrepancies.is_empty()
+    }
+
+    /// Discrepancies as a `DataFrame` with one row per mismatching column.
+    pub fn discrepancies_frame(&self) -> DataFrame {
+        let discrepancies = &self.discrepancies;
+        df!(
+            "column" => discrepancies.iter().map(|x| x.column.as_str()).collect::<Vec<_>>(),
+            "num_mismatches" => discrepancies.iter().map(|x| x.num_mismatches as u64).collect::<Vec<_>>(),
+            "first_mismatch_row" => discrepancies.iter().map(|x| x.first_mismatch_row as u64).collect::<Vec<_>>(),
+            "max_abs_diff" => discrepancies.iter().map(|x| x.max_abs_diff).collect::<Vec<_>>()
+        )
+        .unwrap()
+    }
+}
+
+impl fmt::Display for FrameDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "Frames match.");
+        }
+        if self.heights.0 != self.heights.1 {
+            writeln!(
+                f,
+                "Height: expected {}, got {}.",
+                self.heights.0, self.heights.1
+            )?;
+        }
+        for column in &self.missing_columns {
+            writeln!(f, "Missing column `{column}`.")?;
+        }
+        for column in &self.extra_columns {
+            writeln!(f, "Extra column `{column}`.")?;
+        }
+        for (column, expected, actual) in &self.dtype_mismatches {
+            writeln!(f, "Column `{column}`: expected {expected}, got {actual}.")?;
+        }
+        for x in &self.discrepancies {
+            write!(
+                f,
+                "Column `{}`: {} mismatches (first at row {})",
+                x.column, x.num_mismatches, x.first_mismatch_row
+            )?;
+            match x.max_abs_diff {
+                Some(max_abs_diff) => writeln!(f, ", max abs diff {max_abs_diff:e}.")?,
+                None => writeln!(f, ".")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compare the `actual` frame against the `expected` one.
+/// Columns are matched by name and rows by position.
+pub fn diff_frames(
+    expected: &DataFrame,
+    actual: &DataFrame,
+    cfg: &FrameDiffConfig,
+) -> Result<FrameDiff> {
+    let compared_names = |data_frame: &DataFrame| {
+        data_frame
+            .get_column_names()
+            .into_iter()
+            .filter(|name| !cfg.ignore_columns.iter().any(|x| x == name))
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>()
+    };
+    let expected_names = compared_names(expected);
+    let actual_names = compared_names(actual);
+    let mut diff = FrameDiff {
+        heights: (expected.height(), actual.height()),
+        missing_columns: expected_names
+            .iter()
+            .filter(|x| !actual_names.contains(x))
+            .cloned()
+            .collect(),
+        extra_columns: actual_names
+            .iter()
+            .filter(|x| !expected_names.contains(x))
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+
+    let num_rows = expected.height().min(actual.height());
+    for name in &expected_names {
+        let Ok(actual_column) = actual.column(name) else {
+            continue;
+        };
+        let expected_column = expected.column(name)?.slice(0, num_rows);
+        let actual_column = actual_column.slice(0, num_rows);
+        let (expected_dtype, actual_dtype) = (expected_column.dtype(), actual_column.dtype());
+        if expected_dtype.is_numeric() && actual_dtype.is_numeric() {
+            if expected_dtype != actual_dtype {
+                diff.dtype_mismatches.push((
+                    name.to_string(),
+                    expected_dtype.clone(),
+                    actual_dtype.clone(),
+                ));
+            }
+            diff.discrepancies
+                .extend(_diff_numeric(name, &expected_column, &actual_column, cfg)?);
+        } else if expected_dtype != actual_dtype {
+            diff.dtype_mismatches.push((
+                name.to_string(),
+                expected_dtype.clone(),
+                actual_dtype.clone(),
+            ));
+        } else {
+            diff.discrepancies
+                .extend(_diff_exact(name, &expected_column, &actual_column)?);
+        }
+    }
+    Ok(diff)
+}
+
+fn _diff_numeric(
+    name: &str,
+    expected: &Series,
+    actual: &Series,
+    cfg: &FrameDiffConfig,
+) -> Result<Option<ColumnDiscrepancy>> {
+    let expected = expected.cast(&DataType::Float64)?;
+    let actual = actual.cast(&DataType::Float64)?;
+    let mut discrepancy: Option<ColumnDiscrepancy> = None;
+    let mut max_abs_diff: Option<f64> = None;
+    for (row, (e, a)) in expected.f64()?.into_iter().zip(actual.f64()?).enumerate() {
+        let is_match = match (e, a) {
+            (Some(e), Some(a)) if e.is_nan() || a.is_nan() => e.is_nan() && a.is_nan(),
+            (Some(e), Some(a)) => {
+                let abs_diff = (e - a).abs();
+                max_abs_diff = Some(max_abs_diff.unwrap_or(0.).max(abs_diff));
+                abs_diff <= cfg.abs_tolerance + cfg.rel_tolerance * e.abs()
+            }
+            (e, a) => e.is_none() && a.is_none(),
+        };
+        if !is_match {
+            _record_mismatch(&mut discrepancy, name, row);
+        }
+    }
+    Ok(discrepancy.map(|x| ColumnDiscrepancy { max_abs_diff, ..x }))
+}
+
+fn _diff_exact(
+    name: &str,
+    expected: &Series,
+    actual: &Series,
+) -> Result<Option<ColumnDiscrepancy>> {
+    let mut discrepancy = None;
+    for row in 0..expected.len() {
+        if expected.get(row)? != actual.get(row)? {
+            _record_mismatch(&mut discrepancy, name, row);
+        }
+    }
+    Ok(discrepancy)
+}
+
+fn _record_mismatch(discrepancy: &mut Option<ColumnDiscrepancy>, name: &str, row: usize) {
+    discrepancy
+        .get_or_insert_with(|| ColumnDiscrepancy {
+            column: name.to_string(),
+            num_mismatches: 0,
+            first_mismatch_row: row,
+            max_abs_diff: None,
+        })
+        .num_mismatches += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::{diff_frames, FrameDiffConfig};
+
+    #[test]
+    fn test_diff_frames() {
+        let expected = df!(
+            "x" => [1_f32, 2., f32::NAN],
+            "category" => ["CAR", "BUS", "CAR"],
+            "track" => [1_u32, 2, 3],
+            "ignored" => [0_u8, 0, 0]
+        )
+        .unwrap();
+        let actual = df!(
+            "x" => [1.000_000_1_f64, 2.1, f64::NAN],
+            "category" => ["CAR", "BUS", "BUS"],
+            "track" => [1_u32, 2, 3],
+            "extra" => [0_u8, 0, 0]
+        )
+        .unwrap();
+        let cfg = FrameDiffConfig {
+            ignore_columns: vec!["ignored".to_string()],
+            ..Default::default()
+        };
+        let diff = diff_frames(&expected, &actual, &cfg).unwrap();
+        assert!(!diff.is_empty());
+        assert_eq!(diff.extra_columns, vec!["extra"]);
+        assert!(diff.missing_columns.is_empty());
+        assert_eq!(diff.dtype_mismatches.len(), 1);
+        assert_eq!(diff.discrepancies.len(), 2);
+        assert_eq!(diff.discrepancies[0].column, "x");
+        assert_eq!(diff.discrepancies[0].first_mismatch_row, 1);
+        assert!((diff.discrepancies[0].max_abs_diff.unwrap() - 0.1).abs() < 1e-6);
+        assert_eq!(diff.discrepancies[1].column, "category");
+        assert_eq!(diff.discrepancies[1].first_mismatch_row, 2);
+        assert_eq!(diff.discrepancies_frame().height(), 2);
+
+        assert!(diff_frames(&expected, &expected, &cfg).unwrap().is_empty());
+    }
+}
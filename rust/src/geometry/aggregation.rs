@@ -0,0 +1,183 @@
+//! # aggregation
+//!
+//! Multi-sweep lidar aggregation.
+//!
+//! Sweeps are expressed in the egovehicle frame of a reference sweep (or the city frame) and tagged
+//! with their time relative to it, which temporal detectors use to tell older returns apart.
+
+use polars::{
+    lazy::dsl::lit,
+    prelude::{concat, DataFrame, IntoLazy, UnionArgs},
+};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use strum_macros::{Display, EnumString};
+use tracing::info_span;
+
+use crate::{
+    error::{Av2Error, Av2Result},
+    parallel::install_compute,
+    schema::{validate_data_frame, LIDAR_SCHEMA, POSE_SCHEMA},
+};
+
+use super::{
+    interpolate::interpolate_city_se3_ego,
+    motion_compensation::{compensate_ego_motion, transform_sweep},
+    se3::SE3,
+};
+
+/// Column holding each point's time before the reference sweep (in seconds).
+pub const TIMEDELTA_COLUMN: &str = "timedelta_s";
+
+/// Reference frame of aggregated sweeps.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum AccumulationFrame {
+    /// Egovehicle frame of the reference (latest) sweep.
+    #[default]
+    Ego,
+    /// City frame of the log.
+    City,
+}
+
+/// Reference frame and motion compensation of aggregated sweeps.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AccumulationConfig {
+    /// Frame the sweeps are expressed in.
+    pub frame: AccumulationFrame,
+    /// Boolean flag to motion-compensate each return using its `offset_ns`.
+    pub compensate_per_point: bool,
+}
+
+/// Aggregate `(timestamp_ns, lidar)` sweeps into the `config` frame at `reference_timestamp_ns`.
+///
+/// `city_pose` contains the log's egovehicle poses (`timestamp_ns` and the `POSE_COLUMNS`).
+/// If `compensate_per_point` is set, each return is motion-compensated using its `offset_ns`;
+/// otherwise every sweep is transformed with the egovehicle pose at its timestamp. Sweeps are
+/// transformed in parallel on the compute pool (see `crate::parallel`) and concatenated in the
+/// given order with a `TIMEDELTA_COLUMN` column appended.
+pub fn aggregate_sweeps(
+    sweeps: &[(u64, DataFrame)],
+    city_pose: &DataFrame,
+    reference_timestamp_ns: u64,
+    config: &AccumulationConfig,
+) -> Av2Result<DataFrame> {
+    if sweeps.is_empty() {
+        return Ok(DataFrame::default());
+    }
+    let _span = info_span!("aggregate_sweeps", num_sweeps = sweeps.len()).entered();
+    validate_data_frame("poses", city_pose, &POSE_SCHEMA)?;
+    for (_, lidar) in sweeps {
+        validate_data_frame("lidar", lidar, &LIDAR_SCHEMA)?;
+    }
+    let city_se3_ref = match config.frame {
+        AccumulationFrame::Ego => _city_se3_ego(city_pose, reference_timestamp_ns)?,
+        AccumulationFrame::City => SE3::identity(),
+    };
+
+    let lidar_list = install_compute(|| {
+        sweeps
+            .par_iter()
+            .map(|(timestamp_ns, lidar)| {
+                let lidar = if config.compensate_per_point {
+                    compensate_ego_motion(lidar, *timestamp_ns, city_pose, &city_se3_ref)?
+                } else if config.frame == AccumulationFrame::Ego
+                    && *timestamp_ns == reference_timestamp_ns
+                {
+                    lidar.clone()
+                } else {
+                    let city_se3_ego = _city_se3_ego(city_pose, *timestamp_ns)?;
+                    transform_sweep(lidar, &city_se3_ego, &city_se3_ref)?
+                };
+                let timedelta_s =
+                    (reference_timestamp_ns as i64 - *timestamp_ns as i64) as f32 * 1e-9;
+                Ok(lidar
+                    .lazy()
+                    .with_column(lit(timedelta_s).alias(TIMEDELTA_COLUMN)))
+            })
+            .collect::<Av2Result<Vec<_>>>()
+    })?;
+
+    Ok(concat(lidar_list, UnionArgs::default())?.collect()?)
+}
+
+/// Egovehicle pose interpolated at `timestamp_ns`, which must be within the logged poses.
+fn _city_se3_ego(city_pose: &DataFrame, timestamp_ns: u64) -> Av2Result<SE3> {
+    interpolate_city_se3_ego(city_pose, timestamp_ns)?.ok_or_else(|| {
+        Av2Error::invalid_parameter(
+            "timestamp_ns",
+            format!("{timestamp_ns} is outside of the logged poses."),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    use polars::prelude::*;
+
+    use super::{aggregate_sweeps, AccumulationConfig, AccumulationFrame, TIMEDELTA_COLUMN};
+    use crate::error::Av2Error;
+
+    fn _column(data_frame: &DataFrame, name: &str) -> Vec<f32> {
+        data_frame[name]
+            .f32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_sweeps() {
+        // The egovehicle drives 10 m along x while turning a quarter turn to the left.
+        let city_pose = df!(
+            "timestamp_ns" => [0_u64, 100_000_000],
+            "tx_m" => [0_f64, 10.],
+            "ty_m" => [0_f64, 0.],
+            "tz_m" => [0_f64, 0.],
+            "qw" => [1_f64, FRAC_1_SQRT_2],
+            "qx" => [0_f64, 0.],
+            "qy" => [0_f64, 0.],
+            "qz" => [0_f64, FRAC_1_SQRT_2]
+        )
+        .unwrap();
+        let lidar = df!(
+            "x" => [1_f32],
+            "y" => [0_f32],
+            "z" => [0.5_f32],
+            "offset_ns" => [0_i64]
+        )
+        .unwrap();
+        let sweeps = [(0, lidar.clone()), (100_000_000, lidar)];
+        let assert_aggregated = |config: &AccumulationConfig, expected: [[f32; 3]; 2]| {
+            let aggregated = aggregate_sweeps(&sweeps, &city_pose, 100_000_000, config).unwrap();
+            for (k, axis) in ["x", "y", "z"].into_iter().enumerate() {
+                let values = _column(&aggregated, axis);
+                for (value, point) in values.iter().zip(expected) {
+                    assert!((value - point[k]).abs() < 1e-5, "{axis}: {values:?}");
+                }
+            }
+            // Seconds before the reference sweep.
+            let timedeltas_s = _column(&aggregated, TIMEDELTA_COLUMN);
+            assert!((timedeltas_s[0] - 0.1).abs() < 1e-6 && timedeltas_s[1] == 0.);
+        };
+
+        // In the reference egovehicle frame, the earlier return is 9 m behind, i.e., to the left
+        // after the turn.
+        let ego = AccumulationConfig::default();
+        assert_aggregated(&ego, [[0., 9., 0.5], [1., 0., 0.5]]);
+        let compensated = AccumulationConfig {
+            compensate_per_point: true,
+            ..ego
+        };
+        assert_aggregated(&compensated, [[0., 9., 0.5], [1., 0., 0.5]]);
+        let city = AccumulationConfig {
+            frame: AccumulationFrame::City,
+            ..ego
+        };
+        assert_aggregated(&city, [[1., 0., 0.5], [10., 1., 0.5]]);
+
+        let err = aggregate_sweeps(&sweeps, &city_pose, 200_000_000, &ego).unwrap_err();
+        assert!(matches!(err, Av2Error::InvalidParameter { .. }));
+    }
+}
@@ -1,79 +1,323 @@
 //! # augmentations
 //!
 //! Geometric augmentations.
+//!
+//! Each `sample_*` function draws an `AppliedAugmentation` and applies it. The record holds every
+//! sampled parameter, so it can be logged with the sweep and replayed exactly with
+//! `AppliedAugmentation::apply`.
+//!
+//! `AppliedAugmentation::apply_in_place` borrows the sweep and its cuboids and updates their
+//! coordinate columns in place, reusing an `AugmentationScratch` across sweeps, so augmenting
+//! large accumulated sweeps does not clone or reallocate them.
+//!
+//! Column-level augmentations (reflections, global scaling, and global rotation) are also
+//! expressed as `polars` expressions (`lidar_exprs` and `cuboid_exprs`), which the data-loader
+//! fuses into its lazy reads so a sweep is collected once.
 
 use std::f32::consts::PI;
 
 use crate::{
-    geometry::so3::_mat3_to_quat,
-    io::ndarray_from_frame,
-    share::{data_frame_to_ndarray_f32, ndarray_to_expr_vec},
+    error::{Av2Error, Av2Result},
+    geometry::se3::SE3,
+    schema::{validate_data_frame, CUBOID_SCHEMA, LIDAR_SCHEMA},
+    share::with_f32_columns_mut,
+    structures::cuboid::CuboidList,
 };
-use itertools::Itertools;
-use ndarray::{azip, concatenate, par_azip, s, Array, Axis, Ix1, Ix2};
+use ndarray::{aview1, azip, Array1};
 use polars::{
-    lazy::dsl::{col, cols, GetOutput},
-    prelude::{DataFrame, DataType, IntoLazy},
-    series::Series,
+    lazy::dsl::{col, lit, when, Expr},
+    prelude::{DataFrame, DataType},
 };
 use rand_distr::{Bernoulli, Distribution, Uniform};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
 
 use super::{
-    polytope::{compute_interior_points_mask, cuboids_to_polygons},
+    polytope::collect_interior_points,
     so3::{
-        _quat_to_mat3, reflect_orientation_x, reflect_orientation_y, reflect_translation_x,
+        _yaw_to_quat, reflect_orientation_x, reflect_orientation_y, reflect_translation_x,
         reflect_translation_y,
     },
 };
 
+/// Buffers reused by `AppliedAugmentation::apply_in_place` across sweeps.
+#[derive(Clone, Debug, Default)]
+pub struct AugmentationScratch {
+    /// Points paired with their indices, sorted along x.
+    sorted_points: Vec<(usize, [f32; 3])>,
+    /// Indices of the points interior to each cuboid.
+    interior_points: Vec<Vec<usize>>,
+}
+
+/// Augmentation applied to a sweep and its cuboids, with its sampled parameters.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppliedAugmentation {
+    /// Reflection across the x-axis, if `is_reflected`.
+    SceneReflectionX {
+        /// Boolean flag indicating whether the scene was reflected.
+        is_reflected: bool,
+    },
+    /// Reflection across the y-axis, if `is_reflected`.
+    SceneReflectionY {
+        /// Boolean flag indicating whether the scene was reflected.
+        is_reflected: bool,
+    },
+    /// Scaling of the lidar coordinates and the cuboid centers.
+    SceneGlobalScale {
+        /// Scale factor.
+        scale_factor: f32,
+    },
+    /// Rotation about the z-axis.
+    SceneGlobalRotation {
+        /// Fraction of a turn.
+        theta: f32,
+    },
+    /// Scaling of each cuboid and its interior points about the cuboid's center.
+    RandomObjectScale {
+        /// Scale factor of each cuboid.
+        scale_factors: Vec<f32>,
+    },
+}
+
+impl AppliedAugmentation {
+    /// Sample a reflection across the x-axis with probability `p`.
+    pub fn sample_scene_reflection_x(p: f64) -> Self {
+        let distribution = Bernoulli::new(p).unwrap();
+        AppliedAugmentation::SceneReflectionX {
+            is_reflected: distribution.sample(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Sample a reflection across the y-axis with probability `p`.
+    pub fn sample_scene_reflection_y(p: f64) -> Self {
+        let distribution = Bernoulli::new(p).unwrap();
+        AppliedAugmentation::SceneReflectionY {
+            is_reflected: distribution.sample(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Sample a global scale factor uniformly from `[low_inclusive, upper_inclusive]`.
+    pub fn sample_scene_global_scale(low_inclusive: f64, upper_inclusive: f64) -> Self {
+        let distribution = Uniform::new_inclusive(low_inclusive, upper_inclusive);
+        AppliedAugmentation::SceneGlobalScale {
+            scale_factor: distribution.sample(&mut rand::thread_rng()) as f32,
+        }
+    }
+
+    /// Sample a global rotation uniformly from `[low_inclusive, upper_inclusive]` turns.
+    pub fn sample_scene_global_rotation(low_inclusive: f64, upper_inclusive: f64) -> Self {
+        let distribution = Uniform::new_inclusive(low_inclusive, upper_inclusive);
+        AppliedAugmentation::SceneGlobalRotation {
+            theta: distribution.sample(&mut rand::thread_rng()) as f32,
+        }
+    }
+
+    /// Sample a scale factor uniformly from `[low_inclusive, high_inclusive]` for each of
+    /// `num_cuboids` cuboids.
+    pub fn sample_random_object_scale(
+        num_cuboids: usize,
+        low_inclusive: f64,
+        high_inclusive: f64,
+    ) -> Self {
+        let distribution = Uniform::new_inclusive(low_inclusive, high_inclusive);
+        let mut rng = rand::thread_rng();
+        AppliedAugmentation::RandomObjectScale {
+            scale_factors: (0..num_cuboids)
+                .map(|_| distribution.sample(&mut rng) as f32)
+                .collect(),
+        }
+    }
+
+    /// Apply the augmentation to the lidar sweep and its cuboids.
+    pub fn apply(
+        &self,
+        mut lidar: DataFrame,
+        mut cuboids: DataFrame,
+    ) -> Av2Result<(DataFrame, DataFrame)> {
+        self.apply_in_place(
+            &mut lidar,
+            &mut cuboids,
+            &mut AugmentationScratch::default(),
+        )?;
+        Ok((lidar, cuboids))
+    }
+
+    /// Apply the augmentation to the coordinate columns of the lidar sweep and its cuboids in
+    /// place. Columns whose buffers are shared with another data frame are copied once.
+    pub fn apply_in_place(
+        &self,
+        lidar: &mut DataFrame,
+        cuboids: &mut DataFrame,
+        scratch: &mut AugmentationScratch,
+    ) -> Av2Result<()> {
+        let _span = info_span!("augment", kind = self.kind()).entered();
+        validate_data_frame("lidar", lidar, &LIDAR_SCHEMA)?;
+        validate_data_frame("cuboids", cuboids, &CUBOID_SCHEMA)?;
+        match self {
+            AppliedAugmentation::SceneReflectionX { is_reflected } => match is_reflected {
+                true => _reflect_x(lidar, cuboids),
+                false => Ok(()),
+            },
+            AppliedAugmentation::SceneReflectionY { is_reflected } => match is_reflected {
+                true => _reflect_y(lidar, cuboids),
+                false => Ok(()),
+            },
+            AppliedAugmentation::SceneGlobalScale { scale_factor } => {
+                _scale(lidar, cuboids, *scale_factor)
+            }
+            AppliedAugmentation::SceneGlobalRotation { theta } => _rotate(lidar, cuboids, *theta),
+            AppliedAugmentation::RandomObjectScale { scale_factors } => {
+                _scale_objects(lidar, cuboids, scale_factors, scratch)
+            }
+        }
+    }
+
+    /// Name of the augmentation (e.g., `scene_global_rotation`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppliedAugmentation::SceneReflectionX { .. } => "scene_reflection_x",
+            AppliedAugmentation::SceneReflectionY { .. } => "scene_reflection_y",
+            AppliedAugmentation::SceneGlobalScale { .. } => "scene_global_scale",
+            AppliedAugmentation::SceneGlobalRotation { .. } => "scene_global_rotation",
+            AppliedAugmentation::RandomObjectScale { .. } => "random_object_scale",
+        }
+    }
+
+    /// Returns `true` if the augmentation maps each row independently (i.e., it has
+    /// `lidar_exprs` and `cuboid_exprs`).
+    pub fn is_column_level(&self) -> bool {
+        !matches!(self, AppliedAugmentation::RandomObjectScale { .. })
+    }
+
+    /// Expressions replacing the `x`, `y`, and `z` lidar columns, or `None` if the augmentation is
+    /// not column-level. The expressions are evaluated in a single `with_columns`.
+    pub fn lidar_exprs(&self) -> Option<Vec<Expr>> {
+        let [x, y, z] = ["x", "y", "z"].map(|name| col(name).cast(DataType::Float32));
+        match self {
+            AppliedAugmentation::SceneReflectionX { is_reflected } => Some(match is_reflected {
+                true => vec![(-y).alias("y")],
+                false => vec![],
+            }),
+            AppliedAugmentation::SceneReflectionY { is_reflected } => Some(match is_reflected {
+                true => vec![(-x).alias("x")],
+                false => vec![],
+            }),
+            AppliedAugmentation::SceneGlobalScale { scale_factor } => Some(
+                [(x, "x"), (y, "y"), (z, "z")]
+                    .map(|(value, name)| (value * lit(*scale_factor)).alias(name))
+                    .to_vec(),
+            ),
+            AppliedAugmentation::SceneGlobalRotation { theta } => {
+                Some(_rotate_xy_exprs(x, y, ["x", "y"], *theta).to_vec())
+            }
+            AppliedAugmentation::RandomObjectScale { .. } => None,
+        }
+    }
+
+    /// Expressions replacing the `CUBOID_COLUMNS` of the cuboids, or `None` if the augmentation is
+    /// not column-level. The expressions are evaluated in a single `with_columns`.
+    pub fn cuboid_exprs(&self) -> Option<Vec<Expr>> {
+        let [tx, ty, tz, qw, qx, qy, qz] = ["tx_m", "ty_m", "tz_m", "qw", "qx", "qy", "qz"]
+            .map(|name| col(name).cast(DataType::Float32));
+        match self {
+            AppliedAugmentation::SceneReflectionX { is_reflected } => Some(match is_reflected {
+                true => {
+                    let [c, s] = _yaw_half_angle_exprs([qw, qx, qy, qz]);
+                    vec![
+                        (-ty).alias("ty_m"),
+                        c.alias("qw"),
+                        lit(0_f32).alias("qx"),
+                        lit(0_f32).alias("qy"),
+                        (-s).alias("qz"),
+                    ]
+                }
+                false => vec![],
+            }),
+            AppliedAugmentation::SceneReflectionY { is_reflected } => {
+                Some(match is_reflected {
+                    true => {
+                        // The yaw is reflected to `PI - yaw`, whose half-angle swaps the cosine
+                        // and sine of the yaw's half-angle.
+                        let [c, s] = _yaw_half_angle_exprs([qw, qx, qy, qz]);
+                        vec![
+                            (-tx).alias("tx_m"),
+                            s.alias("qw"),
+                            lit(0_f32).alias("qx"),
+                            lit(0_f32).alias("qy"),
+                            c.alias("qz"),
+                        ]
+                    }
+                    false => vec![],
+                })
+            }
+            AppliedAugmentation::SceneGlobalScale { scale_factor } => Some(
+                [(tx, "tx_m"), (ty, "ty_m"), (tz, "tz_m")]
+                    .map(|(value, name)| (value * lit(*scale_factor)).alias(name))
+                    .to_vec(),
+            ),
+            AppliedAugmentation::SceneGlobalRotation { theta } => {
+                // Left-multiply the orientations by the yaw quaternion `(cw, 0, 0, sw)`.
+                let (sw, cw) = (PI * theta).sin_cos();
+                let quat_wxyz = [
+                    lit(cw) * qw.clone() - lit(sw) * qz.clone(),
+                    lit(cw) * qx.clone() - lit(sw) * qy.clone(),
+                    lit(cw) * qy + lit(sw) * qx,
+                    lit(cw) * qz + lit(sw) * qw,
+                ];
+                // Canonicalize the quaternions (non-negative scalar) as `_mat3_to_quat` does.
+                let sign = when(quat_wxyz[0].clone().lt(lit(0_f32)))
+                    .then(lit(-1_f32))
+                    .otherwise(lit(1_f32));
+                let mut exprs = _rotate_xy_exprs(tx, ty, ["tx_m", "ty_m"], *theta).to_vec();
+                exprs.extend(
+                    quat_wxyz
+                        .into_iter()
+                        .zip(["qw", "qx", "qy", "qz"])
+                        .map(|(q, name)| (sign.clone() * q).alias(name)),
+                );
+                Some(exprs)
+            }
+            AppliedAugmentation::RandomObjectScale { .. } => None,
+        }
+    }
+}
+
+/// Expressions rotating the `(x, y)` coordinates about the z-axis by `theta` turns.
+fn _rotate_xy_exprs(x: Expr, y: Expr, names: [&str; 2], theta: f32) -> [Expr; 2] {
+    let (sin, cos) = (2.0 * PI * theta).sin_cos();
+    [
+        (lit(cos) * x.clone() - lit(sin) * y.clone()).alias(names[0]),
+        (lit(sin) * x + lit(cos) * y).alias(names[1]),
+    ]
+}
+
+/// Expressions of the cosine and sine of half the yaw of scalar-first quaternions.
+///
+/// With `(cos, sin)` of the yaw proportional to `(a, b)`, `(cos, sin)` of its half-angle is
+/// proportional to `(|(a, b)| + a, b)`. A yaw of `PI` (where both vanish) has a half-angle of
+/// `PI / 2`.
+fn _yaw_half_angle_exprs([qw, qx, qy, qz]: [Expr; 4]) -> [Expr; 2] {
+    let a = lit(1_f32) - lit(2_f32) * (qy.clone() * qy.clone() + qz.clone() * qz.clone());
+    let b = lit(2_f32) * (qw * qz + qx * qy);
+    let r = (a.clone() * a.clone() + b.clone() * b.clone()).sqrt();
+    let c = r + a;
+    let norm = (c.clone() * c.clone() + b.clone() * b.clone()).sqrt();
+    [
+        (c / norm.clone()).fill_nan(lit(0_f32)),
+        (b / norm).fill_nan(lit(1_f32)),
+    ]
+}
+
 /// Sample a scene reflection.
 /// This reflects both a point cloud and cuboids across the x-axis.
 pub fn sample_scene_reflection_x(
     lidar: DataFrame,
     cuboids: DataFrame,
     p: f64,
-) -> (DataFrame, DataFrame) {
-    let distribution = Bernoulli::new(p).unwrap();
-    let is_augmented = distribution.sample(&mut rand::thread_rng());
-    if is_augmented {
-        let augmented_lidar = lidar
-            .lazy()
-            .with_column(col("y").map(
-                move |x| {
-                    Ok(Some(
-                        x.f32()
-                            .unwrap()
-                            .into_no_null_iter()
-                            .map(|y| -y)
-                            .collect::<Series>(),
-                    ))
-                },
-                GetOutput::from_type(DataType::Float32),
-            ))
-            .collect()
-            .unwrap();
-
-        let translation_column_names = vec!["tx_m", "ty_m", "tz_m"];
-        let txyz_m = data_frame_to_ndarray_f32(cuboids.clone(), translation_column_names.clone());
-        let augmentation_translation = reflect_translation_x(&txyz_m.view());
-
-        let orientation_column_names = vec!["qw", "qx", "qy", "qz"];
-        let quat_wxyz =
-            data_frame_to_ndarray_f32(cuboids.clone(), orientation_column_names.clone());
-        let augmented_orientation = reflect_orientation_x(&quat_wxyz.view());
-        let augmented_poses =
-            concatenate![Axis(1), augmentation_translation, augmented_orientation];
-
-        let column_names = translation_column_names
-            .into_iter()
-            .chain(orientation_column_names)
-            .collect_vec();
-        let series_vec = ndarray_to_expr_vec(augmented_poses, column_names);
-        let augmented_cuboids = cuboids.lazy().with_columns(series_vec).collect().unwrap();
-        (augmented_lidar, augmented_cuboids)
-    } else {
-        (lidar, cuboids)
-    }
+) -> Av2Result<(DataFrame, DataFrame)> {
+    AppliedAugmentation::sample_scene_reflection_x(p).apply(lidar, cuboids)
 }
 
 /// Sample a scene reflection.
@@ -82,48 +326,8 @@ pub fn sample_scene_reflection_y(
     lidar: DataFrame,
     cuboids: DataFrame,
     p: f64,
-) -> (DataFrame, DataFrame) {
-    let distribution: Bernoulli = Bernoulli::new(p).unwrap();
-    let is_augmented = distribution.sample(&mut rand::thread_rng());
-    if is_augmented {
-        let augmented_lidar = lidar
-            .lazy()
-            .with_column(col("x").map(
-                move |x| {
-                    Ok(Some(
-                        x.f32()
-                            .unwrap()
-                            .into_no_null_iter()
-                            .map(|x| -x)
-                            .collect::<Series>(),
-                    ))
-                },
-                GetOutput::from_type(DataType::Float32),
-            ))
-            .collect()
-            .unwrap();
-
-        let translation_column_names = vec!["tx_m", "ty_m", "tz_m"];
-        let txyz_m = data_frame_to_ndarray_f32(cuboids.clone(), translation_column_names.clone());
-        let augmentation_translation = reflect_translation_y(&txyz_m.view());
-
-        let orientation_column_names = vec!["qw", "qx", "qy", "qz"];
-        let quat_wxyz =
-            data_frame_to_ndarray_f32(cuboids.clone(), orientation_column_names.clone());
-        let augmented_orientation = reflect_orientation_y(&quat_wxyz.view());
-        let augmented_poses =
-            concatenate![Axis(1), augmentation_translation, augmented_orientation];
-
-        let column_names = translation_column_names
-            .into_iter()
-            .chain(orientation_column_names)
-            .collect_vec();
-        let series_vec = ndarray_to_expr_vec(augmented_poses, column_names);
-        let augmented_cuboids = cuboids.lazy().with_columns(series_vec).collect().unwrap();
-        (augmented_lidar, augmented_cuboids)
-    } else {
-        (lidar, cuboids)
-    }
+) -> Av2Result<(DataFrame, DataFrame)> {
+    AppliedAugmentation::sample_scene_reflection_y(p).apply(lidar, cuboids)
 }
 
 /// Sample a scene global scale.
@@ -133,91 +337,9 @@ pub fn sample_scene_global_scale(
     cuboids: DataFrame,
     low_inclusive: f64,
     upper_inclusive: f64,
-) -> (DataFrame, DataFrame) {
-    let distribution = Uniform::new_inclusive(low_inclusive, upper_inclusive);
-    let scale_factor = distribution.sample(&mut rand::thread_rng()) as f32;
-    let augmented_lidar = lidar
-        .lazy()
-        .with_column(col("x").map(
-            move |x| {
-                Ok(Some(
-                    x.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|x| scale_factor * x)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .with_column(col("y").map(
-            move |y| {
-                Ok(Some(
-                    y.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|y| scale_factor * y)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .with_column(col("z").map(
-            move |z| {
-                Ok(Some(
-                    z.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|z| scale_factor * z)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .collect()
-        .unwrap();
-
-    let augmented_cuboids = cuboids
-        .lazy()
-        .with_column(col("tx_m").map(
-            move |x| {
-                Ok(Some(
-                    x.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|x| scale_factor * x)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .with_column(col("ty_m").map(
-            move |y| {
-                Ok(Some(
-                    y.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|y| scale_factor * y)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .with_column(col("tz_m").map(
-            move |z| {
-                Ok(Some(
-                    z.f32()
-                        .unwrap()
-                        .into_no_null_iter()
-                        .map(|z| scale_factor * z)
-                        .collect::<Series>(),
-                ))
-            },
-            GetOutput::from_type(DataType::Float32),
-        ))
-        .collect()
-        .unwrap();
-    (augmented_lidar, augmented_cuboids)
+) -> Av2Result<(DataFrame, DataFrame)> {
+    AppliedAugmentation::sample_scene_global_scale(low_inclusive, upper_inclusive)
+        .apply(lidar, cuboids)
 }
 
 /// Sample a scene global rotation.
@@ -227,46 +349,9 @@ pub fn sample_scene_global_rotation(
     cuboids: DataFrame,
     low_inclusive: f64,
     upper_inclusive: f64,
-) -> (DataFrame, DataFrame) {
-    let distribution = Uniform::new_inclusive(low_inclusive, upper_inclusive);
-    let theta = distribution.sample(&mut rand::thread_rng()) as f32;
-    let rotation = Array::<f32, Ix1>::from_vec(vec![
-        f32::cos(2.0 * PI * theta),
-        f32::sin(2.0 * PI * theta),
-        0.0,
-        -f32::sin(2.0 * PI * theta),
-        f32::cos(2.0 * PI * theta),
-        0.0,
-        0.0,
-        0.0,
-        1.0,
-    ])
-    .into_shape((3, 3))
-    .unwrap();
-
-    let column_names = ["x", "y", "z"];
-    let lidar_ndarray = ndarray_from_frame(&lidar, cols(column_names));
-    let augmented_lidar_ndarray = lidar_ndarray.dot(&rotation);
-    let series_vec = ndarray_to_expr_vec(augmented_lidar_ndarray, column_names.to_vec());
-    let augmented_lidar = lidar.lazy().with_columns(series_vec).collect().unwrap();
-
-    let cuboid_column_names = ["tx_m", "ty_m", "tz_m", "qw", "qx", "qy", "qz"];
-    let cuboids_ndarray = ndarray_from_frame(&cuboids, cols(cuboid_column_names));
-
-    let num_cuboids = cuboids_ndarray.shape()[0];
-    let mut augmented_cuboids = Array::<f32, Ix2>::zeros((num_cuboids, 7));
-    par_azip!((mut ac in augmented_cuboids.outer_iter_mut(), c in cuboids_ndarray.outer_iter()) {
-        let augmented_translation = c.slice(s![..3]).dot(&rotation);
-        let augmented_mat3 = _quat_to_mat3(&c.slice(s![3..7])).dot(&rotation.t());
-        let augmented_rotation = _mat3_to_quat(&augmented_mat3.view());
-
-        ac.slice_mut(s![..3]).assign(&augmented_translation);
-        ac.slice_mut(s![3..7]).assign(&augmented_rotation);
-    });
-
-    let series_vec = ndarray_to_expr_vec(augmented_cuboids, cuboid_column_names.to_vec());
-    let augmented_cuboids = cuboids.lazy().with_columns(series_vec).collect().unwrap();
-    (augmented_lidar, augmented_cuboids)
+) -> Av2Result<(DataFrame, DataFrame)> {
+    AppliedAugmentation::sample_scene_global_rotation(low_inclusive, upper_inclusive)
+        .apply(lidar, cuboids)
 }
 
 /// Sample a scene with random object scaling.
@@ -275,50 +360,349 @@ pub fn sample_random_object_scale(
     cuboids: DataFrame,
     low_inclusive: f64,
     high_inclusive: f64,
-) -> (DataFrame, DataFrame) {
-    let cuboid_column_names = [
-        "tx_m", "ty_m", "tz_m", "length_m", "width_m", "height_m", "qw", "qx", "qy", "qz",
-    ];
-    let mut lidar_ndarray = ndarray_from_frame(&lidar, cols(["x", "y", "z"]));
-    let mut cuboids_ndarray = ndarray_from_frame(&cuboids, cols(cuboid_column_names));
-    let cuboid_vertices = cuboids_to_polygons(&cuboids_ndarray.view());
-    let interior_points_mask =
-        compute_interior_points_mask(&lidar_ndarray.view(), &cuboid_vertices.view());
-
-    let distribution = Uniform::new_inclusive(low_inclusive, high_inclusive);
-
-    azip!((mut c in cuboids_ndarray.outer_iter_mut(), m in interior_points_mask.outer_iter()) {
-        let scale_factor = distribution.sample(&mut rand::thread_rng()) as f32;
-        let indices = m
-            .iter()
-            .enumerate()
-            .filter_map(|(i, x)| match *x {
-                true => Some(i),
-                _ => None,
+) -> Av2Result<(DataFrame, DataFrame)> {
+    AppliedAugmentation::sample_random_object_scale(cuboids.height(), low_inclusive, high_inclusive)
+        .apply(lidar, cuboids)
+}
+
+/// Reflect the lidar sweep and its cuboids across the x-axis.
+fn _reflect_x(lidar: &mut DataFrame, cuboids: &mut DataFrame) -> Av2Result<()> {
+    with_f32_columns_mut(lidar, ["y"], |[y]| y.par_iter_mut().for_each(|y| *y = -*y))?;
+
+    let mut cuboid_list = CuboidList::from_data_frame(cuboids).map_err(_cuboids_error)?;
+    let augmented_translation = reflect_translation_x(&cuboid_list.translation_m());
+    let augmented_orientation = reflect_orientation_x(&cuboid_list.quat_wxyz());
+    cuboid_list
+        .translation_m_mut()
+        .assign(&augmented_translation);
+    cuboid_list.quat_wxyz_mut().assign(&augmented_orientation);
+    cuboid_list
+        .write_data_frame(cuboids)
+        .map_err(_cuboids_error)
+}
+
+/// Reflect the lidar sweep and its cuboids across the y-axis.
+fn _reflect_y(lidar: &mut DataFrame, cuboids: &mut DataFrame) -> Av2Result<()> {
+    with_f32_columns_mut(lidar, ["x"], |[x]| x.par_iter_mut().for_each(|x| *x = -*x))?;
+
+    let mut cuboid_list = CuboidList::from_data_frame(cuboids).map_err(_cuboids_error)?;
+    let augmented_translation = reflect_translation_y(&cuboid_list.translation_m());
+    let augmented_orientation = reflect_orientation_y(&cuboid_list.quat_wxyz());
+    cuboid_list
+        .translation_m_mut()
+        .assign(&augmented_translation);
+    cuboid_list.quat_wxyz_mut().assign(&augmented_orientation);
+    cuboid_list
+        .write_data_frame(cuboids)
+        .map_err(_cuboids_error)
+}
+
+/// Scale the lidar coordinates and the cuboid centers by `scale_factor`.
+fn _scale(lidar: &mut DataFrame, cuboids: &mut DataFrame, scale_factor: f32) -> Av2Result<()> {
+    with_f32_columns_mut(lidar, ["x", "y", "z"], |columns| {
+        for column in columns {
+            column.par_iter_mut().for_each(|x| *x *= scale_factor);
+        }
+    })?;
+
+    let mut cuboid_list = CuboidList::from_data_frame(cuboids).map_err(_cuboids_error)?;
+    cuboid_list
+        .translation_m_mut()
+        .mapv_inplace(|x| scale_factor * x);
+    cuboid_list
+        .write_data_frame(cuboids)
+        .map_err(_cuboids_error)
+}
+
+/// Rotate the lidar sweep and its cuboids about the z-axis by `theta` turns.
+fn _rotate(lidar: &mut DataFrame, cuboids: &mut DataFrame, theta: f32) -> Av2Result<()> {
+    let quat_wxyz = _yaw_to_quat(2.0 * PI * theta);
+    let ego_se3_augmented = SE3::from_quat_wxyz(&quat_wxyz.view(), &Array1::zeros(3).view())?;
+
+    let r = &ego_se3_augmented.rotation();
+    with_f32_columns_mut(lidar, ["x", "y", "z"], |[x, y, z]| {
+        x.par_iter_mut()
+            .zip(y.par_iter_mut())
+            .zip(z.par_iter_mut())
+            .for_each(|((x, y), z)| {
+                let p = [*x, *y, *z];
+                [*x, *y, *z] =
+                    [0, 1, 2].map(|i| r[[i, 0]] * p[0] + r[[i, 1]] * p[1] + r[[i, 2]] * p[2]);
             })
-            .collect_vec();
-        let interior_points = lidar_ndarray.select(Axis(0), &indices);
-
-        // TODO: Handle with iterators.
-        for (i, j) in indices.into_iter().enumerate() {
-            let scaled_lidar_ndarray_object = (&interior_points.slice(s![i, ..])
-                - &c.slice(s![..3]))
-                * scale_factor;
-            let scaled_lidar_ndarray_egovehicle =
-                &scaled_lidar_ndarray_object + &c.slice(s![..3]);
-
-            lidar_ndarray
-                .slice_mut(s![j, ..])
-                .assign(&scaled_lidar_ndarray_egovehicle);
+    })?;
+
+    let mut cuboid_list = CuboidList::from_data_frame(cuboids).map_err(_cuboids_error)?;
+    let augmented_params = ego_se3_augmented.transform_cuboids(&cuboid_list.params());
+    cuboid_list.params_mut().assign(&augmented_params);
+    cuboid_list
+        .write_data_frame(cuboids)
+        .map_err(_cuboids_error)
+}
+
+/// Scale each cuboid and its interior points about the cuboid's center by its scale factor.
+fn _scale_objects(
+    lidar: &mut DataFrame,
+    cuboids: &mut DataFrame,
+    scale_factors: &[f32],
+    scratch: &mut AugmentationScratch,
+) -> Av2Result<()> {
+    let mut cuboid_list = CuboidList::from_data_frame(cuboids).map_err(_cuboids_error)?;
+    if scale_factors.len() != cuboid_list.len() {
+        return Err(Av2Error::invalid_parameter(
+            "scale_factors",
+            format!(
+                "Expected {} scale factors, got {}.",
+                cuboid_list.len(),
+                scale_factors.len()
+            ),
+        ));
+    }
+    let cuboid_vertices = cuboid_list.vertices();
+    let AugmentationScratch {
+        sorted_points,
+        interior_points,
+    } = scratch;
+    with_f32_columns_mut(lidar, ["x", "y", "z"], |[x, y, z]| {
+        collect_interior_points(
+            (0..x.len()).map(|i| [x[i], y[i], z[i]]),
+            &cuboid_vertices.view(),
+            sorted_points,
+            interior_points,
+        );
+        // Cuboids are scaled in order, so points interior to several cuboids are scaled by each.
+        for ((indices, c), &scale_factor) in interior_points
+            .iter()
+            .zip(cuboid_list.params().outer_iter())
+            .zip(scale_factors)
+        {
+            for &i in indices {
+                x[i] = (x[i] - c[0]) * scale_factor + c[0];
+                y[i] = (y[i] - c[1]) * scale_factor + c[1];
+                z[i] = (z[i] - c[2]) * scale_factor + c[2];
+            }
         }
-        c.slice_mut(s![3..6]).mapv_inplace(|x| x * scale_factor);
+    })?;
+
+    azip!((mut dims in cuboid_list.dims_lwh_m_mut().outer_iter_mut(), &scale_factor in &aview1(scale_factors)) {
+        dims.mapv_inplace(|x| x * scale_factor);
     });
+    cuboid_list
+        .write_data_frame(cuboids)
+        .map_err(_cuboids_error)
+}
+
+/// Schema mismatch of the cuboids (e.g., a missing coordinate column).
+fn _cuboids_error(err: anyhow::Error) -> Av2Error {
+    Av2Error::schema("cuboids", err)
+}
 
-    let lidar_column_names = vec!["x", "y", "z"];
-    let series_vec = ndarray_to_expr_vec(lidar_ndarray, lidar_column_names);
-    let augmented_lidar = lidar.lazy().with_columns(series_vec).collect().unwrap();
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{df, IntoLazy, NamedFrom};
+
+    use super::{AppliedAugmentation, AugmentationScratch};
+
+    #[test]
+    fn test_applied_augmentation() {
+        let lidar = df!(
+            "x" => [1_f32, 10.],
+            "y" => [0.5_f32, 0.],
+            "z" => [0_f32, 0.]
+        )
+        .unwrap();
+        let cuboids = df!(
+            "tx_m" => [1_f32],
+            "ty_m" => [0_f32],
+            "tz_m" => [0_f32],
+            "length_m" => [4_f32],
+            "width_m" => [2_f32],
+            "height_m" => [2_f32],
+            "qw" => [1_f32],
+            "qx" => [0_f32],
+            "qy" => [0_f32],
+            "qz" => [0_f32]
+        )
+        .unwrap();
 
-    let series_vec = ndarray_to_expr_vec(cuboids_ndarray, cuboid_column_names.to_vec());
-    let augmented_cuboids = cuboids.lazy().with_columns(series_vec).collect().unwrap();
-    (augmented_lidar, augmented_cuboids)
+        let augmentation = AppliedAugmentation::sample_random_object_scale(1, 2., 2.);
+        assert_eq!(
+            augmentation,
+            AppliedAugmentation::RandomObjectScale {
+                scale_factors: vec![2.]
+            }
+        );
+        let (augmented_lidar, augmented_cuboids) =
+            augmentation.apply(lidar.clone(), cuboids).unwrap();
+        let y = augmented_lidar["y"].f32().unwrap();
+        assert_eq!(y.into_no_null_iter().collect::<Vec<_>>(), vec![1., 0.]);
+        assert_eq!(
+            augmented_cuboids["length_m"].f32().unwrap().get(0),
+            Some(8.)
+        );
+
+        // Records round-trip through JSON and bincode.
+        for augmentation in [
+            AppliedAugmentation::sample_scene_reflection_x(1.),
+            AppliedAugmentation::sample_scene_global_rotation(0., 1.),
+            augmentation,
+        ] {
+            let json = serde_json::to_string(&augmentation).unwrap();
+            assert_eq!(
+                serde_json::from_str::<AppliedAugmentation>(&json).unwrap(),
+                augmentation
+            );
+            let bytes = bincode::serialize(&augmentation).unwrap();
+            assert_eq!(
+                bincode::deserialize::<AppliedAugmentation>(&bytes).unwrap(),
+                augmentation
+            );
+        }
+        let json =
+            serde_json::to_string(&AppliedAugmentation::sample_scene_reflection_y(0.)).unwrap();
+        assert_eq!(json, r#"{"scene_reflection_y":{"is_reflected":false}}"#);
+    }
+
+    #[test]
+    fn test_apply_in_place() {
+        let mut lidar = df!(
+            "x" => [0.5_f32, 10.],
+            "y" => [0.25_f32, 0.],
+            "z" => [0_f32, 0.],
+            "intensity" => [3_u8, 4]
+        )
+        .unwrap();
+        let mut cuboids = df!(
+            "tx_m" => [1_f32],
+            "ty_m" => [0_f32],
+            "tz_m" => [0_f32],
+            "length_m" => [4_f32],
+            "width_m" => [2_f32],
+            "height_m" => [2_f32],
+            "qw" => [1_f32],
+            "qx" => [0_f32],
+            "qy" => [0_f32],
+            "qz" => [0_f32],
+            "category" => ["REGULAR_VEHICLE"]
+        )
+        .unwrap();
+        let original_lidar = lidar.clone();
+        let mut scratch = AugmentationScratch::default();
+        let augmentations = [
+            AppliedAugmentation::SceneReflectionX { is_reflected: true },
+            AppliedAugmentation::SceneGlobalRotation { theta: 0.25 },
+            AppliedAugmentation::SceneGlobalScale { scale_factor: 2. },
+            AppliedAugmentation::RandomObjectScale {
+                scale_factors: vec![0.5],
+            },
+        ];
+        let (mut expected_lidar, mut expected_cuboids) = (lidar.clone(), cuboids.clone());
+        for augmentation in &augmentations {
+            augmentation
+                .apply_in_place(&mut lidar, &mut cuboids, &mut scratch)
+                .unwrap();
+            (expected_lidar, expected_cuboids) = augmentation
+                .apply(expected_lidar, expected_cuboids)
+                .unwrap();
+        }
+        assert_eq!(lidar, expected_lidar);
+        assert_eq!(cuboids, expected_cuboids);
+        assert_eq!(lidar.get_column_names(), original_lidar.get_column_names());
+
+        // Buffers shared with a clone are copied instead of being mutated.
+        assert_eq!(original_lidar["x"].f32().unwrap().get(0), Some(0.5));
+        let x = lidar["x"].f32().unwrap().get(0).unwrap();
+        let y = lidar["y"].f32().unwrap().get(0).unwrap();
+        // Reflected to (0.5, -0.25), rotated to (0.25, 0.5), scaled to (0.5, 1), then scaled by 0.5
+        // about the transformed cuboid center (0, 2).
+        assert!((x - 0.25).abs() < 1e-5 && (y - 1.5).abs() < 1e-5);
+        assert_eq!(cuboids["length_m"].f32().unwrap().get(0), Some(2.));
+        assert_eq!(
+            cuboids["category"].str().unwrap().get(0),
+            Some("REGULAR_VEHICLE")
+        );
+
+        // Unshared buffers are updated in place.
+        let values = lidar["z"].f32().unwrap().cont_slice().unwrap().as_ptr();
+        AppliedAugmentation::SceneGlobalScale { scale_factor: 3. }
+            .apply_in_place(&mut lidar, &mut cuboids, &mut scratch)
+            .unwrap();
+        assert_eq!(
+            lidar["z"].f32().unwrap().cont_slice().unwrap().as_ptr(),
+            values
+        );
+    }
+
+    #[test]
+    fn test_column_level_exprs() {
+        let lidar = df!(
+            "x" => [0.5_f32, 10., -3.],
+            "y" => [0.25_f32, 0., 7.],
+            "z" => [0_f32, 1., -2.]
+        )
+        .unwrap();
+        // Yaws of about -3, 0.5, and `PI` (with roll and pitch on the first cuboid).
+        let cuboids = df!(
+            "tx_m" => [1_f32, -4., 2.],
+            "ty_m" => [0_f32, 3., -1.],
+            "tz_m" => [0_f32, 0.5, 1.],
+            "length_m" => [4_f32, 2., 1.],
+            "width_m" => [2_f32, 1., 1.],
+            "height_m" => [2_f32, 1., 1.],
+            "qw" => [0.070_644_8_f32, 0.968_912_4, 0.],
+            "qx" => [0.099_922_f32, 0., 0.],
+            "qy" => [-0.049_961_f32, 0., 0.],
+            "qz" => [-0.991_225_9_f32, 0.247_403_96, 1.]
+        )
+        .unwrap();
+
+        for augmentation in [
+            AppliedAugmentation::SceneReflectionX { is_reflected: true },
+            AppliedAugmentation::SceneReflectionY { is_reflected: true },
+            AppliedAugmentation::SceneReflectionY {
+                is_reflected: false,
+            },
+            AppliedAugmentation::SceneGlobalScale { scale_factor: 1.1 },
+            AppliedAugmentation::SceneGlobalRotation { theta: 0.3 },
+            AppliedAugmentation::SceneGlobalRotation { theta: -0.45 },
+        ] {
+            assert!(augmentation.is_column_level());
+            let (expected_lidar, expected_cuboids) =
+                augmentation.apply(lidar.clone(), cuboids.clone()).unwrap();
+            let augmented_lidar = lidar
+                .clone()
+                .lazy()
+                .with_columns(augmentation.lidar_exprs().unwrap())
+                .collect()
+                .unwrap();
+            let augmented_cuboids = cuboids
+                .clone()
+                .lazy()
+                .with_columns(augmentation.cuboid_exprs().unwrap())
+                .collect()
+                .unwrap();
+            for (expected, augmented) in [
+                (&expected_lidar, &augmented_lidar),
+                (&expected_cuboids, &augmented_cuboids),
+            ] {
+                assert_eq!(expected.get_column_names(), augmented.get_column_names());
+                for name in expected.get_column_names() {
+                    let expected = expected[name].f32().unwrap();
+                    let augmented = augmented[name].f32().unwrap();
+                    assert!(
+                        expected
+                            .into_no_null_iter()
+                            .zip(augmented.into_no_null_iter())
+                            .all(|(x, y)| (x - y).abs() < 1e-5),
+                        "{augmentation:?} {name}: {expected:?} {augmented:?}"
+                    );
+                }
+            }
+        }
+
+        let augmentation = AppliedAugmentation::RandomObjectScale {
+            scale_factors: vec![2.],
+        };
+        assert!(!augmentation.is_column_level());
+        assert!(augmentation.lidar_exprs().is_none());
+    }
 }
@@ -0,0 +1,430 @@
+//! # bvh
+//!
+//! Bounding volume hierarchy over cuboids for ray, point, and box queries.
+//!
+//! Nodes hold the axis-aligned bounding box of their cuboids and are split at the median centroid
+//! along the longest axis. Leaves test the exact oriented cuboids.
+
+use ndarray::{s, Array, ArrayView, Ix1, Ix2};
+use numpy::{IntoPyArray, PyArray, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::prelude::*;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::so3::quat_to_mat3_row_major;
+
+/// Nodes with at most this many cuboids are leaves.
+const LEAF_SIZE: usize = 4;
+
+/// Axis-aligned box as `(min, max)` corners.
+pub type Aabb = ([f32; 3], [f32; 3]);
+
+/// Oriented cuboid in the frame of the hierarchy.
+#[derive(Clone, Copy, Debug)]
+struct OrientedCuboid {
+    center: [f32; 3],
+    /// Row-major rotation from the object frame to the hierarchy frame.
+    rotation: [f32; 9],
+    half_extents: [f32; 3],
+    bounding_box: Aabb,
+}
+
+impl OrientedCuboid {
+    fn new(cuboid: &ArrayView<f32, Ix1>, rotation: &ArrayView<f32, Ix1>) -> Self {
+        let center = [cuboid[0], cuboid[1], cuboid[2]];
+        let half_extents = [cuboid[3] / 2., cuboid[4] / 2., cuboid[5] / 2.];
+        let rotation: [f32; 9] = rotation.to_vec().try_into().unwrap();
+        // Extent of the rotated box along each axis.
+        let radius = [0, 1, 2].map(|k| {
+            (0..3)
+                .map(|j| rotation[3 * k + j].abs() * half_extents[j])
+                .sum::<f32>()
+        });
+        let bounding_box = (
+            [0, 1, 2].map(|k| center[k] - radius[k]),
+            [0, 1, 2].map(|k| center[k] + radius[k]),
+        );
+        Self {
+            center,
+            rotation,
+            half_extents,
+            bounding_box,
+        }
+    }
+
+    /// Rotate a hierarchy-frame vector into the object frame.
+    fn rotate_to_object(&self, v: &[f32; 3]) -> [f32; 3] {
+        let r = &self.rotation;
+        [0, 1, 2].map(|j| r[j] * v[0] + r[3 + j] * v[1] + r[6 + j] * v[2])
+    }
+
+    fn contains(&self, point: &[f32; 3]) -> bool {
+        let local = self.rotate_to_object(&[0, 1, 2].map(|k| point[k] - self.center[k]));
+        (0..3).all(|k| local[k].abs() <= self.half_extents[k])
+    }
+
+    /// Distance along the unit `direction` at which the ray enters the cuboid (zero if it starts
+    /// inside).
+    fn intersect_ray(&self, origin: &[f32; 3], direction: &[f32; 3]) -> Option<f32> {
+        let origin = self.rotate_to_object(&[0, 1, 2].map(|k| origin[k] - self.center[k]));
+        let direction = self.rotate_to_object(direction);
+        let min = self.half_extents.map(|x| -x);
+        _intersect_ray_aabb(
+            &origin,
+            &direction,
+            &(min, self.half_extents),
+            f32::INFINITY,
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bounding_box: Aabb,
+    /// Range of `order` covered by the node.
+    start: usize,
+    end: usize,
+    /// Child node indices, `None` for leaves.
+    children: Option<(usize, usize)>,
+}
+
+/// Bounding volume hierarchy over (N,10) cuboids parameterized as
+/// `(tx_m, ty_m, tz_m, length_m, width_m, height_m, qw, qx, qy, qz)`.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct CuboidBvh {
+    cuboids: Vec<OrientedCuboid>,
+    /// Hierarchy order of the cuboid indices.
+    order: Vec<usize>,
+    /// Nodes in depth-first order. The root is the first node.
+    nodes: Vec<Node>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl CuboidBvh {
+    /// Build the hierarchy over (N,10) cuboids.
+    #[new]
+    fn py_new(cuboids: PyReadonlyArray2<f32>) -> Self {
+        CuboidBvh::new(&cuboids.as_array())
+    }
+
+    /// Indices of the cuboids containing a (3,) point.
+    #[pyo3(name = "query_point")]
+    fn py_query_point<'py>(
+        &self,
+        py: Python<'py>,
+        point: PyReadonlyArray1<f32>,
+    ) -> &'py PyArray<usize, Ix1> {
+        let point = point.as_array();
+        Array::from_vec(self.query_point(&[point[0], point[1], point[2]])).into_pyarray(py)
+    }
+
+    /// Indices of the cuboids whose bounding boxes overlap the box between (3,) corners.
+    #[pyo3(name = "query_box")]
+    fn py_query_box<'py>(
+        &self,
+        py: Python<'py>,
+        min: PyReadonlyArray1<f32>,
+        max: PyReadonlyArray1<f32>,
+    ) -> &'py PyArray<usize, Ix1> {
+        let (min, max) = (min.as_array(), max.as_array());
+        let query = ([min[0], min[1], min[2]], [max[0], max[1], max[2]]);
+        Array::from_vec(self.query_box(&query)).into_pyarray(py)
+    }
+
+    /// Cast (M,3) rays up to `max_distance`.
+    /// Returns the (M,) nearest hit cuboid indices (-1 on a miss) and distances (inf on a miss).
+    #[pyo3(name = "raycast")]
+    fn py_raycast<'py>(
+        &self,
+        py: Python<'py>,
+        origins: PyReadonlyArray2<f32>,
+        directions: PyReadonlyArray2<f32>,
+        max_distance: f32,
+    ) -> (&'py PyArray<i64, Ix1>, &'py PyArray<f32, Ix1>) {
+        let (indices, distances) =
+            self.raycast_batch(&origins.as_array(), &directions.as_array(), max_distance);
+        (indices.into_pyarray(py), distances.into_pyarray(py))
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Rust methods.
+impl CuboidBvh {
+    /// Build the hierarchy over (N,10) cuboids.
+    pub fn new(cuboids: &ArrayView<f32, Ix2>) -> Self {
+        let rotations = quat_to_mat3_row_major(&cuboids.slice(s![.., 6..10]));
+        let cuboids = cuboids
+            .outer_iter()
+            .zip(rotations.outer_iter())
+            .map(|(c, r)| OrientedCuboid::new(&c, &r))
+            .collect::<Vec<_>>();
+        let mut bvh = CuboidBvh {
+            order: (0..cuboids.len()).collect(),
+            cuboids,
+            nodes: vec![],
+        };
+        bvh.build(0, bvh.len());
+        bvh
+    }
+
+    /// Number of indexed cuboids.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cuboids.len()
+    }
+
+    /// Returns `true` if the hierarchy has no cuboids.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Axis-aligned bounding box of cuboid `index`.
+    pub fn bounding_box(&self, index: usize) -> Aabb {
+        self.cuboids[index].bounding_box
+    }
+
+    /// Indices of the cuboids containing `point` (in no particular order).
+    pub fn query_point(&self, point: &[f32; 3]) -> Vec<usize> {
+        let mut hits = vec![];
+        self.traverse(
+            |bounding_box| _aabb_contains(bounding_box, point),
+            |index| {
+                if self.cuboids[index].contains(point) {
+                    hits.push(index);
+                }
+            },
+        );
+        hits
+    }
+
+    /// Indices of the cuboids whose axis-aligned bounding boxes overlap `query` (in no particular
+    /// order). Use as a broad phase before exact overlap tests.
+    pub fn query_box(&self, query: &Aabb) -> Vec<usize> {
+        let mut hits = vec![];
+        self.traverse(
+            |bounding_box| _aabb_overlaps(bounding_box, query),
+            |index| {
+                if _aabb_overlaps(&self.cuboids[index].bounding_box, query) {
+                    hits.push(index);
+                }
+            },
+        );
+        hits
+    }
+
+    /// Nearest cuboid hit by the ray from `origin` along `direction` within `max_distance`, as
+    /// `(index, distance)`. Rays starting inside a cuboid hit it at distance zero.
+    pub fn raycast(
+        &self,
+        origin: &[f32; 3],
+        direction: &[f32; 3],
+        max_distance: f32,
+    ) -> Option<(usize, f32)> {
+        let norm = _dot3(direction, direction).sqrt();
+        if self.is_empty() || norm == 0. {
+            return None;
+        }
+        let direction = direction.map(|x| x / norm);
+
+        let mut nearest: Option<(usize, f32)> = None;
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let max_distance = nearest.map_or(max_distance, |(_, d)| d);
+            if _intersect_ray_aabb(origin, &direction, &node.bounding_box, max_distance).is_none() {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([right, left]),
+                None => {
+                    for &index in &self.order[node.start..node.end] {
+                        match self.cuboids[index].intersect_ray(origin, &direction) {
+                            Some(distance)
+                                if distance <= max_distance
+                                    && nearest.is_none_or(|(_, d)| distance < d) =>
+                            {
+                                nearest = Some((index, distance));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        nearest
+    }
+
+    /// Cast (M,3) rays in parallel.
+    /// Returns the (M,) nearest hit cuboid indices (-1 on a miss) and distances (inf on a miss).
+    pub fn raycast_batch(
+        &self,
+        origins: &ArrayView<f32, Ix2>,
+        directions: &ArrayView<f32, Ix2>,
+        max_distance: f32,
+    ) -> (Array<i64, Ix1>, Array<f32, Ix1>) {
+        let hits = (0..origins.shape()[0])
+            .into_par_iter()
+            .map(|i| {
+                let (o, d) = (origins.row(i), directions.row(i));
+                self.raycast(&[o[0], o[1], o[2]], &[d[0], d[1], d[2]], max_distance)
+            })
+            .collect::<Vec<_>>();
+        let indices = hits
+            .iter()
+            .map(|hit| hit.map_or(-1, |(index, _)| index as i64))
+            .collect();
+        let distances = hits
+            .iter()
+            .map(|hit| hit.map_or(f32::INFINITY, |(_, distance)| distance))
+            .collect();
+        (indices, distances)
+    }
+
+    /// Visit the cuboids of every leaf whose ancestors and itself pass `visit_node`.
+    fn traverse(&self, visit_node: impl Fn(&Aabb) -> bool, mut visit_cuboid: impl FnMut(usize)) {
+        if self.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !visit_node(&node.bounding_box) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend([right, left]),
+                None => self.order[node.start..node.end]
+                    .iter()
+                    .for_each(|&index| visit_cuboid(index)),
+            }
+        }
+    }
+
+    /// Build the subtree over `order[start..end]` and return its node index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let bounding_box = self.order[start..end].iter().fold(
+            ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+            |(min, max), &index| {
+                let (cuboid_min, cuboid_max) = self.cuboids[index].bounding_box;
+                (
+                    [0, 1, 2].map(|k| min[k].min(cuboid_min[k])),
+                    [0, 1, 2].map(|k| max[k].max(cuboid_max[k])),
+                )
+            },
+        );
+        let node_index = self.nodes.len();
+        self.nodes.push(Node {
+            bounding_box,
+            start,
+            end,
+            children: None,
+        });
+        if end - start <= LEAF_SIZE {
+            return node_index;
+        }
+
+        let extent = [0, 1, 2].map(|k| bounding_box.1[k] - bounding_box.0[k]);
+        let axis = (0..3)
+            .max_by(|&a, &b| extent[a].total_cmp(&extent[b]))
+            .unwrap();
+        let mid = (start + end) / 2;
+        let cuboids = &self.cuboids;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            cuboids[a].center[axis].total_cmp(&cuboids[b].center[axis])
+        });
+        let left = self.build(start, mid);
+        let right = self.build(mid, end);
+        self.nodes[node_index].children = Some((left, right));
+        node_index
+    }
+}
+
+fn _dot3(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn _aabb_contains(aabb: &Aabb, point: &[f32; 3]) -> bool {
+    (0..3).all(|k| aabb.0[k] <= point[k] && point[k] <= aabb.1[k])
+}
+
+fn _aabb_overlaps(a: &Aabb, b: &Aabb) -> bool {
+    (0..3).all(|k| a.0[k] <= b.1[k] && b.0[k] <= a.1[k])
+}
+
+/// Slab test. Returns the entry distance of the ray within `[0, max_distance]`, if any.
+fn _intersect_ray_aabb(
+    origin: &[f32; 3],
+    direction: &[f32; 3],
+    aabb: &Aabb,
+    max_distance: f32,
+) -> Option<f32> {
+    let (mut t_min, mut t_max) = (0_f32, max_distance);
+    for k in 0..3 {
+        if direction[k] == 0. {
+            if origin[k] < aabb.0[k] || origin[k] > aabb.1[k] {
+                return None;
+            }
+            continue;
+        }
+        let inverse = 1. / direction[k];
+        let t_0 = (aabb.0[k] - origin[k]) * inverse;
+        let t_1 = (aabb.1[k] - origin[k]) * inverse;
+        t_min = t_min.max(t_0.min(t_1));
+        t_max = t_max.min(t_0.max(t_1));
+        if t_min > t_max {
+            return None;
+        }
+    }
+    Some(t_min)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use ndarray::{array, Array2};
+
+    use super::CuboidBvh;
+
+    #[test]
+    fn test_cuboid_bvh() {
+        let (c, s) = ((FRAC_PI_4 / 2.).cos(), (FRAC_PI_4 / 2.).sin());
+        // A unit cube at the origin rotated 45 degrees about z, and a row of cubes along +x.
+        let mut cuboids = vec![[0., 0., 0., 1., 1., 1., c, 0., 0., s]];
+        for i in 1..20 {
+            cuboids.push([2. * i as f32, 0., 0., 1., 1., 1., 1., 0., 0., 0.]);
+        }
+        let cuboids = Array2::from(cuboids);
+        let bvh = CuboidBvh::new(&cuboids.view());
+        assert_eq!(bvh.len(), 20);
+
+        // The corner of the axis-aligned cube is outside of the rotated one.
+        assert_eq!(bvh.query_point(&[0.45, 0.45, 0.]), Vec::<usize>::new());
+        assert_eq!(bvh.query_point(&[0.6, 0., 0.]), vec![0]);
+        assert_eq!(bvh.query_point(&[10.2, 0.1, -0.3]), vec![5]);
+
+        let mut hits = bvh.query_box(&([5.4, -1., -1.], [8.6, 1., 1.]));
+        hits.sort();
+        assert_eq!(hits, vec![3, 4]);
+
+        // The rotated cube's face is at 0.5 * sqrt(2) along the diagonal.
+        let (index, distance) = bvh.raycast(&[-5., -5., 0.], &[1., 1., 0.], 100.).unwrap();
+        assert_eq!(index, 0);
+        assert!((distance - (50_f32.sqrt() - 0.5)).abs() < 1e-4);
+
+        let (indices, distances) = bvh.raycast_batch(
+            &array![[3., 0., 0.], [3., 5., 0.]].view(),
+            &array![[1., 0., 0.], [1., 0., 0.]].view(),
+            100.,
+        );
+        assert_eq!(indices, array![2, -1]);
+        assert_eq!(distances, array![0.5, f32::INFINITY]);
+    }
+}
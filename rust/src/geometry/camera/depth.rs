@@ -0,0 +1,136 @@
+//! # depth
+//!
+//! Sparse depth maps rendered from lidar sweeps.
+//!
+//! Returns are projected into a camera and splatted into a depth image (in meters, zero where
+//! there is no return). Overlapping returns are resolved by z-buffering, and each return can be
+//! dilated into a square patch to densify the map.
+
+use ndarray::{Array, Ix2};
+use polars::prelude::DataFrame;
+
+use crate::{error::Av2Result, geometry::se3::SE3};
+
+use super::{pinhole_camera::PinholeCamera, projection::project_lidar_to_camera};
+
+/// Sparse depth map configuration.
+#[derive(Clone, Debug)]
+pub struct DepthMapConfig {
+    /// Keep the nearest return of each pixel. Otherwise, later returns overwrite earlier ones.
+    pub use_z_buffer: bool,
+    /// Half-width of the square patch each return is splatted into (in pixels).
+    pub dilation_radius_px: usize,
+    /// Minimum depth of a return (in meters).
+    pub min_depth_m: f32,
+    /// Maximum depth of a return (in meters).
+    pub max_depth_m: f32,
+    /// Project through the camera's lens distortion.
+    pub apply_distortion: bool,
+}
+
+impl Default for DepthMapConfig {
+    fn default() -> Self {
+        Self {
+            use_z_buffer: true,
+            dilation_radius_px: 0,
+            min_depth_m: 0.5,
+            max_depth_m: 200.,
+            apply_distortion: false,
+        }
+    }
+}
+
+/// Render the (H,W) sparse depth map of a sweep (in the egovehicle frame) in `camera`.
+///
+/// If the camera and lidar timestamps differ, `ego_camera_se3_ego_lidar` takes the egovehicle
+/// frame at the lidar timestamp to the egovehicle frame at the camera timestamp.
+pub fn render_sparse_depth_map(
+    lidar: &DataFrame,
+    camera: &PinholeCamera,
+    ego_camera_se3_ego_lidar: Option<&SE3>,
+    cfg: &DepthMapConfig,
+) -> Av2Result<Array<f32, Ix2>> {
+    let (width_px, height_px) = (camera.width_px(), camera.height_px());
+    let mut depth_map = Array::<f32, Ix2>::zeros((height_px, width_px));
+    let projection = project_lidar_to_camera(
+        lidar,
+        camera,
+        ego_camera_se3_ego_lidar,
+        cfg.apply_distortion,
+    )?;
+
+    let radius = cfg.dilation_radius_px;
+    for (uvz, is_valid) in projection.uvz.outer_iter().zip(&projection.is_valid) {
+        let depth_m = uvz[2];
+        if !is_valid || !(cfg.min_depth_m..=cfg.max_depth_m).contains(&depth_m) {
+            continue;
+        }
+        let (u, v) = (uvz[0] as usize, uvz[1] as usize);
+        for row in v.saturating_sub(radius)..(v + radius + 1).min(height_px) {
+            for column in u.saturating_sub(radius)..(u + radius + 1).min(width_px) {
+                let pixel = &mut depth_map[[row, column]];
+                if !cfg.use_z_buffer || *pixel == 0. || depth_m < *pixel {
+                    *pixel = depth_m;
+                }
+            }
+        }
+    }
+    Ok(depth_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use polars::prelude::{df, NamedFrom};
+
+    use super::{render_sparse_depth_map, DepthMapConfig};
+    use crate::geometry::{
+        camera::{
+            distortion::RadialDistortion,
+            pinhole_camera::{Intrinsics, PinholeCamera},
+        },
+        se3::SE3,
+    };
+
+    #[test]
+    fn test_render_sparse_depth_map() {
+        // The camera looks along the egovehicle x-axis.
+        let quat_wxyz = array![0.5, -0.5, 0.5, -0.5];
+        let camera = PinholeCamera {
+            ego_se3_cam: SE3::from_quat_wxyz(&quat_wxyz.view(), &array![0., 0., 0.].view())
+                .unwrap(),
+            intrinsics: Intrinsics {
+                fx_px: 10.,
+                fy_px: 10.,
+                cx_px: 5.,
+                cy_px: 5.,
+                width_px: 10,
+                height_px: 10,
+                distortion: RadialDistortion::default(),
+            },
+            camera_name: "ring_front_center".to_string(),
+        };
+        // A near return in front of a far one, and a return too close to the camera.
+        let lidar = df!(
+            "x" => [2_f32, 4., 0.1],
+            "y" => [0_f32, 0., 0.],
+            "z" => [0_f32, 0., 0.]
+        )
+        .unwrap();
+
+        let cfg = DepthMapConfig::default();
+        let depth_map = render_sparse_depth_map(&lidar, &camera, None, &cfg).unwrap();
+        assert_eq!(depth_map[[5, 5]], 2.);
+        assert_eq!(depth_map.iter().filter(|x| **x > 0.).count(), 1);
+
+        let cfg = DepthMapConfig {
+            use_z_buffer: false,
+            dilation_radius_px: 1,
+            ..Default::default()
+        };
+        // The far return overwrites the near one.
+        let depth_map = render_sparse_depth_map(&lidar, &camera, None, &cfg).unwrap();
+        assert_eq!(depth_map[[4, 6]], 4.);
+        assert_eq!(depth_map.iter().filter(|x| **x > 0.).count(), 9);
+    }
+}
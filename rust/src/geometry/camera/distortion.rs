@@ -0,0 +1,106 @@
+//! # distortion
+//!
+//! Radial lens distortion.
+//!
+//! The sensor dataset calibrates each camera with three radial coefficients `k1`, `k2`, and `k3`
+//! (the Brown-Conrady model without tangential terms). They act on normalized image coordinates,
+//! i.e., `(x / z, y / z)` in the camera frame.
+
+use ndarray::{par_azip, Array, ArrayView, Ix2};
+
+/// Number of fixed-point iterations used to invert the distortion.
+pub const NUM_UNDISTORT_ITERATIONS: usize = 20;
+
+/// Radial distortion coefficients.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RadialDistortion {
+    /// Second order coefficient.
+    pub k1: f32,
+    /// Fourth order coefficient.
+    pub k2: f32,
+    /// Sixth order coefficient.
+    pub k3: f32,
+}
+
+impl RadialDistortion {
+    /// Construct from the radial coefficients.
+    pub fn new(k1: f32, k2: f32, k3: f32) -> Self {
+        Self { k1, k2, k3 }
+    }
+
+    /// Returns `true` if every coefficient is zero.
+    pub fn is_identity(&self) -> bool {
+        self.k1 == 0. && self.k2 == 0. && self.k3 == 0.
+    }
+
+    /// Radial scaling factor at squared radius `r2`.
+    #[inline]
+    pub fn factor(&self, r2: f32) -> f32 {
+        1. + r2 * (self.k1 + r2 * (self.k2 + r2 * self.k3))
+    }
+
+    /// Distort a normalized image coordinate.
+    #[inline]
+    pub fn distort_point(&self, x: f32, y: f32) -> (f32, f32) {
+        let factor = self.factor(x * x + y * y);
+        (x * factor, y * factor)
+    }
+
+    /// Undistort a normalized image coordinate by fixed-point iteration.
+    #[inline]
+    pub fn undistort_point(&self, x_distorted: f32, y_distorted: f32) -> (f32, f32) {
+        let (mut x, mut y) = (x_distorted, y_distorted);
+        for _ in 0..NUM_UNDISTORT_ITERATIONS {
+            let factor = self.factor(x * x + y * y);
+            (x, y) = (x_distorted / factor, y_distorted / factor);
+        }
+        (x, y)
+    }
+
+    /// Distort (N,2) normalized image coordinates.
+    pub fn distort(&self, xy: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+        self._map_points(xy, |x, y| self.distort_point(x, y))
+    }
+
+    /// Undistort (N,2) normalized image coordinates.
+    pub fn undistort(&self, xy_distorted: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+        self._map_points(xy_distorted, |x, y| self.undistort_point(x, y))
+    }
+
+    fn _map_points<F>(&self, xy: &ArrayView<f32, Ix2>, f: F) -> Array<f32, Ix2>
+    where
+        F: Fn(f32, f32) -> (f32, f32) + Sync,
+    {
+        let mut mapped = xy.to_owned();
+        if self.is_identity() {
+            return mapped;
+        }
+        par_azip!((mut point in mapped.outer_iter_mut()) {
+            (point[0], point[1]) = f(point[0], point[1]);
+        });
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::RadialDistortion;
+
+    #[test]
+    fn test_radial_distortion_round_trip() {
+        let distortion = RadialDistortion::new(-0.24, -0.21, 0.33);
+        let xy = array![[0., 0.], [0.3, -0.2], [-0.5, 0.4]];
+        let distorted = distortion.distort(&xy.view());
+        assert_eq!(distorted.row(0), xy.row(0));
+        // Barrel distortion pulls points toward the center.
+        assert!(distorted[[1, 0]].abs() < 0.3);
+
+        let undistorted = distortion.undistort(&distorted.view());
+        assert!(undistorted
+            .iter()
+            .zip(xy.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-4));
+    }
+}
@@ -1,2 +1,8 @@
+/// Sparse depth maps rendered from lidar sweeps.
+pub mod depth;
+/// Radial lens distortion.
+pub mod distortion;
 /// Pinhole camera model.
 pub mod pinhole_camera;
+/// Lidar-to-camera projection and point colorization.
+pub mod projection;
@@ -1,16 +1,21 @@
 use std::{ops::DivAssign, path::Path};
 
+use anyhow::{bail, Context, Result};
 use ndarray::{par_azip, s, Array, ArrayView, Ix1, Ix2};
 use polars::{
-    lazy::dsl::{col, lit},
+    lazy::dsl::{col, cols, lit},
     prelude::{DataFrame, IntoLazy},
 };
 
 use crate::{
-    geometry::se3::SE3, geometry::so3::_quat_to_mat3, geometry::utils::cart_to_hom,
-    io::read_feather_eager,
+    constants::POSE_COLUMNS,
+    geometry::se3::SE3,
+    geometry::utils::cart_to_hom,
+    io::{ndarray_from_frame, read_feather_eager},
 };
 
+use super::distortion::RadialDistortion;
+
 /// Pinhole camera intrinsics.
 #[derive(Clone, Debug)]
 pub struct Intrinsics {
@@ -26,6 +31,8 @@ pub struct Intrinsics {
     pub width_px: usize,
     /// Height of image in pixels.
     pub height_px: usize,
+    /// Radial lens distortion.
+    pub distortion: RadialDistortion,
 }
 
 impl Intrinsics {
@@ -46,7 +53,119 @@ impl Intrinsics {
             cy_px,
             width_px,
             height_px,
+            distortion: RadialDistortion::default(),
+        }
+    }
+
+    /// Read the intrinsics of `camera_name` from a `DataFrame` with the schema of
+    /// `calibration/intrinsics.feather`. Missing distortion coefficients default to zero.
+    pub fn from_data_frame(intrinsics: &DataFrame, camera_name: &str) -> Result<Self> {
+        let intrinsics = intrinsics
+            .clone()
+            .lazy()
+            .filter(col("sensor_name").eq(lit(camera_name)))
+            .collect()?;
+        if intrinsics.height() != 1 {
+            bail!(
+                "Expected one row of intrinsics for `{camera_name}`, got {}.",
+                intrinsics.height()
+            );
+        }
+        let value = |column: &str| -> Result<f64> {
+            intrinsics
+                .column(column)
+                .with_context(|| format!("Missing column `{column}`."))?
+                .get(0)?
+                .try_extract::<f64>()
+                .with_context(|| format!("Column `{column}` is not numeric."))
+        };
+        let coefficient = |column: &str| match intrinsics.column(column) {
+            Ok(_) => value(column).map(|x| x as f32),
+            Err(_) => Ok(0.),
+        };
+        Ok(Self {
+            fx_px: value("fx_px")? as f32,
+            fy_px: value("fy_px")? as f32,
+            cx_px: value("cx_px")? as f32,
+            cy_px: value("cy_px")? as f32,
+            width_px: value("width_px")? as usize,
+            height_px: value("height_px")? as usize,
+            distortion: RadialDistortion::new(
+                coefficient("k1")?,
+                coefficient("k2")?,
+                coefficient("k3")?,
+            ),
+        })
+    }
+
+    /// Load the intrinsics of `camera_name` from `calibration/intrinsics.feather` of a log.
+    pub fn load(log_dir: &Path, camera_name: &str) -> Result<Self> {
+        let intrinsics_path = log_dir.join("calibration/intrinsics.feather");
+        if !intrinsics_path.exists() {
+            bail!("{} not found.", intrinsics_path.display());
         }
+        Self::from_data_frame(&read_feather_eager(&intrinsics_path, false), camera_name)
+    }
+
+    /// Scale the focal lengths, focal center, and image size (e.g., after resizing the image).
+    pub fn scale(&self, scale: f32) -> Self {
+        Self {
+            fx_px: self.fx_px * scale,
+            fy_px: self.fy_px * scale,
+            cx_px: self.cx_px * scale,
+            cy_px: self.cy_px * scale,
+            width_px: (self.width_px as f32 * scale).round() as usize,
+            height_px: (self.height_px as f32 * scale).round() as usize,
+            distortion: self.distortion,
+        }
+    }
+
+    /// Project (N,3) camera frame points to (N,2) pixel coordinates, optionally applying the lens
+    /// distortion. Points behind the camera project to NaN.
+    pub fn project(
+        &self,
+        points_cam: &ArrayView<f32, Ix2>,
+        apply_distortion: bool,
+    ) -> Array<f32, Ix2> {
+        let mut uv = Array::<f32, Ix2>::zeros((points_cam.shape()[0], 2));
+        par_azip!((mut uv_i in uv.outer_iter_mut(), point_cam in points_cam.outer_iter()) {
+            let z = point_cam[2];
+            if z <= 0. {
+                uv_i.fill(f32::NAN);
+                return;
+            }
+            let (mut x, mut y) = (point_cam[0] / z, point_cam[1] / z);
+            if apply_distortion {
+                (x, y) = self.distortion.distort_point(x, y);
+            }
+            uv_i[0] = self.fx_px * x + self.cx_px;
+            uv_i[1] = self.fy_px * y + self.cy_px;
+        });
+        uv
+    }
+
+    /// Compute (N,3) unit ray directions in the camera frame through (N,2) pixel coordinates,
+    /// optionally removing the lens distortion first.
+    pub fn compute_pixel_ray_directions(
+        &self,
+        uv: &ArrayView<f32, Ix2>,
+        remove_distortion: bool,
+    ) -> Array<f32, Ix2> {
+        let mut ray_directions = Array::<f32, Ix2>::ones((uv.shape()[0], 3));
+        par_azip!((mut direction in ray_directions.outer_iter_mut(), uv_i in uv.outer_iter()) {
+            let (mut x, mut y) = (
+                (uv_i[0] - self.cx_px) / self.fx_px,
+                (uv_i[1] - self.cy_px) / self.fy_px,
+            );
+            if remove_distortion {
+                (x, y) = self.distortion.undistort_point(x, y);
+            }
+            let norm = (x * x + y * y + 1.).sqrt();
+            direction[0] = x / norm;
+            direction[1] = y / norm;
+            direction[2] = 1. / norm;
+        });
+        ray_directions
     }
 
     /// Camera intrinsic matrix.
@@ -96,51 +215,33 @@ impl PinholeCamera {
 
     /// Create a pinhole camera model from a feather file.
     pub fn from_feather(log_dir: &Path, camera_name: &str) -> PinholeCamera {
-        let intrinsics_path = log_dir.join("calibration/intrinsics.feather");
-        let intrinsics = read_feather_eager(&intrinsics_path, false)
-            .lazy()
-            .filter(col("sensor_name").eq(lit(camera_name)))
-            .collect()
-            .unwrap();
+        Self::load(log_dir, camera_name).unwrap()
+    }
 
-        let intrinsics = Intrinsics {
-            fx_px: extract_f32_from_frame(&intrinsics, "fx_px"),
-            fy_px: extract_f32_from_frame(&intrinsics, "fy_px"),
-            cx_px: extract_f32_from_frame(&intrinsics, "cx_px"),
-            cy_px: extract_f32_from_frame(&intrinsics, "cy_px"),
-            width_px: extract_usize_from_frame(&intrinsics, "width_px"),
-            height_px: extract_usize_from_frame(&intrinsics, "height_px"),
-        };
+    /// Load the intrinsics and extrinsics of `camera_name` from the calibration of a log.
+    pub fn load(log_dir: &Path, camera_name: &str) -> Result<PinholeCamera> {
+        let intrinsics = Intrinsics::load(log_dir, camera_name)?;
 
         let extrinsics_path = log_dir.join("calibration/egovehicle_SE3_sensor.feather");
+        if !extrinsics_path.exists() {
+            bail!("{} not found.", extrinsics_path.display());
+        }
         let extrinsics = read_feather_eager(&extrinsics_path, false)
             .lazy()
             .filter(col("sensor_name").eq(lit(camera_name)))
-            .collect()
-            .unwrap();
-
-        let qw = extract_f32_from_frame(&extrinsics, "qw");
-        let qx = extract_f32_from_frame(&extrinsics, "qx");
-        let qy = extract_f32_from_frame(&extrinsics, "qy");
-        let qz = extract_f32_from_frame(&extrinsics, "qz");
-        let tx_m = extract_f32_from_frame(&extrinsics, "tx_m");
-        let ty_m = extract_f32_from_frame(&extrinsics, "ty_m");
-        let tz_m = extract_f32_from_frame(&extrinsics, "tz_m");
-
-        let quat_wxyz = Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz]);
-        let rotation = _quat_to_mat3(&quat_wxyz.view());
-        let translation = Array::<f32, Ix1>::from_vec(vec![tx_m, ty_m, tz_m]);
-        let ego_se3_cam = SE3 {
-            rotation,
-            translation,
-        };
-
-        let camera_name_string = camera_name.to_string();
-        Self {
-            ego_se3_cam,
-            intrinsics,
-            camera_name: camera_name_string,
+            .collect()?;
+        if extrinsics.height() != 1 {
+            bail!(
+                "Expected one row of extrinsics for `{camera_name}`, got {}.",
+                extrinsics.height()
+            );
         }
+        let pose = ndarray_from_frame(&extrinsics, cols(POSE_COLUMNS))?;
+        Ok(Self {
+            ego_se3_cam: SE3::from_pose(&pose.row(0))?,
+            intrinsics,
+            camera_name: camera_name.to_string(),
+        })
     }
 
     /// Cull 3D points to camera view frustum.
@@ -171,6 +272,81 @@ impl PinholeCamera {
         is_within_frustum
     }
 
+    /// Compute a (N,) mask of the (N,3) egovehicle frame points inside the camera's view frustum,
+    /// clipped to depths within `[near_clip_m, far_clip_m]`.
+    pub fn compute_view_frustum_mask(
+        &self,
+        points_ego: &ArrayView<f32, Ix2>,
+        near_clip_m: f32,
+        far_clip_m: f32,
+    ) -> Array<bool, Ix1> {
+        compute_view_frustum_mask(
+            points_ego,
+            &self.intrinsics,
+            &self.ego_se3_cam.inverse(),
+            near_clip_m,
+            far_clip_m,
+        )
+    }
+
+    /// Scale the intrinsics and image size.
+    pub fn scale(&self, scale: f32) -> PinholeCamera {
+        Self {
+            ego_se3_cam: self.ego_se3_cam.clone(),
+            intrinsics: self.intrinsics.scale(scale),
+            camera_name: self.camera_name.clone(),
+        }
+    }
+
+    /// Project (N,3) camera frame points to (N,2) pixel coordinates, optionally applying the lens
+    /// distortion. Also returns a (N,) mask of the points in front of the camera which land
+    /// inside the image.
+    pub fn project_cam_to_image(
+        &self,
+        points_cam: &ArrayView<f32, Ix2>,
+        apply_distortion: bool,
+    ) -> (Array<f32, Ix2>, Array<bool, Ix1>) {
+        let uv = self.intrinsics.project(points_cam, apply_distortion);
+        let (width_px, height_px) = (self.width_px() as f32, self.height_px() as f32);
+        let is_valid = uv
+            .outer_iter()
+            .map(|uv_i| (0. ..width_px).contains(&uv_i[0]) && (0. ..height_px).contains(&uv_i[1]))
+            .collect();
+        (uv, is_valid)
+    }
+
+    /// Back-project (N,2) pixel coordinates with (N,) depths (along the optical axis) to (N,3)
+    /// camera frame points, optionally removing the lens distortion first.
+    pub fn unproject_image_to_cam(
+        &self,
+        uv: &ArrayView<f32, Ix2>,
+        depth_m: &ArrayView<f32, Ix1>,
+        remove_distortion: bool,
+    ) -> Array<f32, Ix2> {
+        let mut points_cam = self
+            .intrinsics
+            .compute_pixel_ray_directions(uv, remove_distortion);
+        par_azip!((mut point in points_cam.outer_iter_mut(), &depth in depth_m) {
+            let scale = depth / point[2];
+            point.mapv_inplace(|x| x * scale);
+        });
+        points_cam
+    }
+
+    /// Cast rays through (N,2) pixel coordinates. Returns the camera center and the (N,3) unit ray
+    /// directions, both in the egovehicle frame.
+    pub fn compute_pixel_rays_ego(
+        &self,
+        uv: &ArrayView<f32, Ix2>,
+        remove_distortion: bool,
+    ) -> (Array<f32, Ix1>, Array<f32, Ix2>) {
+        let ray_directions_cam = self
+            .intrinsics
+            .compute_pixel_ray_directions(uv, remove_distortion);
+        let ray_directions_ego = ray_directions_cam.dot(&self.ego_se3_cam.rotation().t());
+        (self.ego_se3_cam.translation.clone(), ray_directions_ego)
+    }
+
     /// Project a collection of 3D points (provided in the egovehicle frame) to the image plane.
     pub fn project_ego_to_image(
         &self,
@@ -208,20 +384,76 @@ impl PinholeCamera {
     }
 }
 
-fn extract_f32_from_frame(series: &DataFrame, column: &str) -> f32 {
-    series
-        .column(column)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .try_extract::<f32>()
-        .unwrap()
+/// Compute a (N,) mask of the (N,3) egovehicle frame points which project into the image of a
+/// camera with `intrinsics` and pose `cam_se3_ego`, at depths within `[near_clip_m, far_clip_m]`.
+pub fn compute_view_frustum_mask(
+    points_ego: &ArrayView<f32, Ix2>,
+    intrinsics: &Intrinsics,
+    cam_se3_ego: &SE3,
+    near_clip_m: f32,
+    far_clip_m: f32,
+) -> Array<bool, Ix1> {
+    let points_cam = cam_se3_ego.transform_from(points_ego);
+    let (width_px, height_px) = (intrinsics.width_px as f32, intrinsics.height_px as f32);
+    let mut is_within_frustum = Array::<bool, Ix1>::from_elem(points_cam.shape()[0], false);
+    par_azip!((is_within_frustum_i in &mut is_within_frustum, point_cam in points_cam.outer_iter()) {
+        let z = point_cam[2];
+        if z < near_clip_m.max(f32::EPSILON) || z > far_clip_m {
+            return;
+        }
+        let u = intrinsics.fx_px * point_cam[0] / z + intrinsics.cx_px;
+        let v = intrinsics.fy_px * point_cam[1] / z + intrinsics.cy_px;
+        *is_within_frustum_i = (0. ..width_px).contains(&u) && (0. ..height_px).contains(&v);
+    });
+    is_within_frustum
 }
 
-fn extract_usize_from_frame(series: &DataFrame, column: &str) -> usize {
-    series[column]
-        .get(0)
-        .unwrap()
-        .try_extract::<usize>()
-        .unwrap()
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s};
+
+    use super::{Intrinsics, PinholeCamera};
+    use crate::geometry::{camera::distortion::RadialDistortion, se3::SE3};
+
+    #[test]
+    fn test_project_unproject_with_distortion() {
+        let intrinsics = Intrinsics {
+            fx_px: 1000.,
+            fy_px: 1000.,
+            cx_px: 960.,
+            cy_px: 600.,
+            width_px: 1920,
+            height_px: 1200,
+            distortion: RadialDistortion::new(-0.2, 0.05, 0.),
+        };
+        let camera = PinholeCamera {
+            ego_se3_cam: SE3::identity(),
+            intrinsics,
+            camera_name: "ring_front_center".to_string(),
+        };
+        let points_cam = array![[1., -0.5, 4.], [0., 0., 10.], [0., 0., -1.], [50., 0., 1.]];
+
+        let (uv, is_valid) = camera.project_cam_to_image(&points_cam.view(), true);
+        assert_eq!(is_valid, array![true, true, false, false]);
+        assert_eq!(uv.row(1), array![960., 600.]);
+        let (uv_pinhole, _) = camera.project_cam_to_image(&points_cam.view(), false);
+        assert!(uv[[0, 0]] < uv_pinhole[[0, 0]]);
+
+        let depth_m = points_cam.column(2).slice(s![..2]).to_owned();
+        let uv = uv.slice(s![..2, ..]);
+        let unprojected = camera.unproject_image_to_cam(&uv, &depth_m.view(), true);
+        assert!(unprojected
+            .iter()
+            .zip(points_cam.slice(s![..2, ..]).iter())
+            .all(|(x, y)| (x - y).abs() < 1e-3));
+
+        let (origin, directions) = camera.compute_pixel_rays_ego(&uv, true);
+        assert_eq!(origin, array![0., 0., 0.]);
+        assert!(directions
+            .outer_iter()
+            .all(|x| (x.dot(&x) - 1.).abs() < 1e-5));
+
+        let scaled = camera.scale(0.5);
+        assert_eq!((scaled.width_px(), scaled.height_px()), (960, 600));
+    }
 }
@@ -0,0 +1,157 @@
+//! # projection
+//!
+//! Lidar-to-camera projection and point colorization.
+//!
+//! Sweeps are projected into a camera through its pinhole model (optionally with lens distortion),
+//! and points landing inside the image can sample its colors.
+
+use anyhow::{ensure, Result};
+use image::{ImageBuffer, Rgba};
+use ndarray::{par_azip, s, Array, Ix1, Ix2};
+use polars::prelude::{DataFrame, NamedFrom, Series};
+
+use crate::{error::Av2Result, geometry::se3::SE3, share::data_frame_to_ndarray_f32};
+
+use super::pinhole_camera::PinholeCamera;
+
+/// Projection of a sweep into a camera.
+#[derive(Clone, Debug)]
+pub struct CameraProjection {
+    /// (N,3) pixel coordinates `(u, v)` and depth along the optical axis (in meters).
+    pub uvz: Array<f32, Ix2>,
+    /// (N,) mask of the points in front of the camera which land inside the image.
+    pub is_valid: Array<bool, Ix1>,
+}
+
+/// Project a sweep with `x`, `y`, and `z` columns in the egovehicle frame into `camera`.
+///
+/// If the camera and lidar timestamps differ, `ego_camera_se3_ego_lidar` takes the egovehicle
+/// frame at the lidar timestamp to the egovehicle frame at the camera timestamp.
+pub fn project_lidar_to_camera(
+    lidar: &DataFrame,
+    camera: &PinholeCamera,
+    ego_camera_se3_ego_lidar: Option<&SE3>,
+    apply_distortion: bool,
+) -> Av2Result<CameraProjection> {
+    let mut points_ego = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    if let Some(ego_camera_se3_ego_lidar) = ego_camera_se3_ego_lidar {
+        points_ego = ego_camera_se3_ego_lidar.transform_from(&points_ego.view());
+    }
+    let points_cam = camera
+        .ego_se3_cam
+        .inverse()
+        .transform_from(&points_ego.view());
+    let (uv, is_valid) = camera.project_cam_to_image(&points_cam.view(), apply_distortion);
+
+    let mut uvz = Array::<f32, Ix2>::zeros((uv.shape()[0], 3));
+    uvz.slice_mut(s![.., ..2]).assign(&uv);
+    uvz.column_mut(2).assign(&points_cam.column(2));
+    Ok(CameraProjection { uvz, is_valid })
+}
+
+/// Append `r`, `g`, and `b` columns sampled (by nearest pixel) from the camera `image` to a sweep.
+/// Points outside the image are null.
+///
+/// Images whose size differs from the camera intrinsics (e.g., downsampled images) are sampled at
+/// the rescaled pixel coordinates.
+pub fn colorize_lidar(
+    lidar: &DataFrame,
+    camera: &PinholeCamera,
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+    ego_camera_se3_ego_lidar: Option<&SE3>,
+    apply_distortion: bool,
+) -> Result<DataFrame> {
+    ensure!(
+        image.width() > 0 && image.height() > 0,
+        "Cannot colorize with an empty image."
+    );
+    let projection =
+        project_lidar_to_camera(lidar, camera, ego_camera_se3_ego_lidar, apply_distortion)?;
+    let scale_u = image.width() as f32 / camera.width_px() as f32;
+    let scale_v = image.height() as f32 / camera.height_px() as f32;
+
+    let num_points = projection.uvz.shape()[0];
+    let mut rgb = Array::<u8, Ix2>::zeros((num_points, 3));
+    par_azip!((mut rgb_i in rgb.outer_iter_mut(), uvz in projection.uvz.outer_iter(), &is_valid in &projection.is_valid) {
+        if !is_valid {
+            return;
+        }
+        let u = ((uvz[0] * scale_u) as u32).min(image.width() - 1);
+        let v = ((uvz[1] * scale_v) as u32).min(image.height() - 1);
+        for (channel, value) in rgb_i.iter_mut().zip(image.get_pixel(u, v).0) {
+            *channel = value;
+        }
+    });
+
+    let mut lidar = lidar.clone();
+    for (i, name) in ["r", "g", "b"].into_iter().enumerate() {
+        let values = rgb
+            .column(i)
+            .iter()
+            .zip(&projection.is_valid)
+            .map(|(value, is_valid)| is_valid.then_some(*value))
+            .collect::<Vec<_>>();
+        lidar.with_column(Series::new(name, values))?;
+    }
+    Ok(lidar)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgba};
+    use ndarray::array;
+    use polars::prelude::{df, NamedFrom};
+
+    use super::{colorize_lidar, project_lidar_to_camera};
+    use crate::geometry::{
+        camera::{
+            distortion::RadialDistortion,
+            pinhole_camera::{Intrinsics, PinholeCamera},
+        },
+        se3::SE3,
+    };
+
+    #[test]
+    fn test_colorize_lidar() {
+        // The camera looks along the egovehicle x-axis.
+        let quat_wxyz = array![0.5, -0.5, 0.5, -0.5];
+        let camera = PinholeCamera {
+            ego_se3_cam: SE3::from_quat_wxyz(&quat_wxyz.view(), &array![0., 0., 0.].view())
+                .unwrap(),
+            intrinsics: Intrinsics {
+                fx_px: 10.,
+                fy_px: 10.,
+                cx_px: 10.,
+                cy_px: 5.,
+                width_px: 20,
+                height_px: 10,
+                distortion: RadialDistortion::default(),
+            },
+            camera_name: "ring_front_center".to_string(),
+        };
+        let lidar = df!(
+            "x" => [10_f32, 10., -10.],
+            "y" => [0_f32, 5., 0.],
+            "z" => [0_f32, 0., 0.]
+        )
+        .unwrap();
+
+        let projection = project_lidar_to_camera(&lidar, &camera, None, false).unwrap();
+        assert_eq!(projection.is_valid.to_vec(), vec![true, true, false]);
+        assert!((projection.uvz[[0, 0]] - 10.).abs() < 1e-4);
+        assert!((projection.uvz[[0, 2]] - 10.).abs() < 1e-4);
+        // Points to the left of the camera land on the left of the image.
+        assert!((projection.uvz[[1, 0]] - 5.).abs() < 1e-4);
+
+        // Left half of the (downsampled) image is red and the right half is blue.
+        let image = ImageBuffer::from_fn(10, 5, |u, _| match u < 5 {
+            true => Rgba([255, 0, 0, 255]),
+            false => Rgba([0, 0, 255, 255]),
+        });
+        let colorized = colorize_lidar(&lidar, &camera, &image, None, false).unwrap();
+        let r = colorized.column("r").unwrap().u8().unwrap().to_vec();
+        let b = colorized.column("b").unwrap().u8().unwrap().to_vec();
+        assert_eq!(r, vec![Some(0), Some(255), None]);
+        assert_eq!(b, vec![Some(255), Some(0), None]);
+    }
+}
@@ -0,0 +1,213 @@
+//! # clustering
+//!
+//! Euclidean clustering of lidar points.
+//!
+//! Points are clustered with DBSCAN: points with at least `min_points` neighbors within `eps_m`
+//! are core points, connected core points form a cluster, and the remaining points within reach
+//! of a core point join its cluster. With `min_points = 1`, this reduces to the connected
+//! components of the `eps_m`-neighborhood graph.
+
+use ndarray::{s, Array, ArrayView, Axis, Ix1, Ix2};
+use polars::prelude::{DataFrame, NamedFrom, Series};
+
+use crate::{error::Av2Result, share::data_frame_to_ndarray_f32};
+
+use super::{
+    ground::{compute_non_ground_mask, GroundEstimationConfig},
+    kdtree::KdTree,
+    polytope::fit_oriented_cuboids,
+};
+
+/// Name of the cluster id column appended to sweeps.
+pub const CLUSTER_ID_COLUMN: &str = "cluster_id";
+
+/// Clustering configuration.
+#[derive(Clone, Debug)]
+pub struct ClusteringConfig {
+    /// Neighborhood radius (in meters).
+    pub eps_m: f32,
+    /// Minimum number of points within `eps_m` (including the point itself) of a core point.
+    pub min_points: usize,
+    /// Clusters with fewer points are treated as noise.
+    pub min_cluster_size: usize,
+    /// Cluster in the xy-plane only (bird's-eye-view).
+    pub use_bev: bool,
+}
+
+impl Default for ClusteringConfig {
+    fn default() -> Self {
+        Self {
+            eps_m: 0.5,
+            min_points: 3,
+            min_cluster_size: 10,
+            use_bev: false,
+        }
+    }
+}
+
+/// Compute the (N,) cluster id of (N,3+) points, or `-1` for noise.
+/// Cluster ids are consecutive and ordered by the first point of each cluster.
+pub fn cluster_points(points: &ArrayView<f32, Ix2>, cfg: &ClusteringConfig) -> Array<i64, Ix1> {
+    let num_dims = if cfg.use_bev { 2 } else { 3 };
+    let points = points.slice(s![.., ..num_dims]);
+    let num_points = points.shape()[0];
+    let index = KdTree::new(&points);
+    let neighbors = index.query_radius_batch(&points, cfg.eps_m);
+    let is_core = neighbors
+        .iter()
+        .map(|x| x.len() >= cfg.min_points)
+        .collect::<Vec<_>>();
+
+    // Connect neighboring core points.
+    let mut parents = (0..num_points).collect::<Vec<_>>();
+    for (i, neighbors_i) in neighbors.iter().enumerate().filter(|(i, _)| is_core[*i]) {
+        for &j in neighbors_i.iter().filter(|j| is_core[**j]) {
+            let (root_i, root_j) = (_find(&mut parents, i), _find(&mut parents, j));
+            if root_i != root_j {
+                parents[root_i.max(root_j)] = root_i.min(root_j);
+            }
+        }
+    }
+    // Border points join the cluster of their first core neighbor.
+    let roots = (0..num_points)
+        .map(|i| match is_core[i] {
+            true => Some(_find(&mut parents, i)),
+            false => neighbors[i]
+                .iter()
+                .filter(|j| is_core[**j])
+                .min()
+                .map(|j| _find(&mut parents, *j)),
+        })
+        .collect::<Vec<_>>();
+
+    let mut sizes = vec![0; num_points];
+    for root in roots.iter().flatten() {
+        sizes[*root] += 1;
+    }
+    let mut cluster_ids = vec![-1; num_points];
+    let mut num_clusters = 0;
+    let mut ids = Array::<i64, Ix1>::from_elem(num_points, -1);
+    for (id, root) in ids.iter_mut().zip(&roots) {
+        let Some(root) = root.filter(|root| sizes[*root] >= cfg.min_cluster_size) else {
+            continue;
+        };
+        if cluster_ids[root] < 0 {
+            cluster_ids[root] = num_clusters;
+            num_clusters += 1;
+        }
+        *id = cluster_ids[root];
+    }
+    ids
+}
+
+/// Cluster the non-ground points of a sweep with `x`, `y`, and `z` columns and append their
+/// `cluster_id`. Ground points are noise (`-1`). Ground removal is skipped if `ground_cfg` is
+/// `None`.
+pub fn cluster_lidar(
+    lidar: &DataFrame,
+    ground_cfg: Option<&GroundEstimationConfig>,
+    cfg: &ClusteringConfig,
+) -> Av2Result<DataFrame> {
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let cluster_ids = _cluster_non_ground(&xyz.view(), ground_cfg, cfg);
+    let mut lidar = lidar.clone();
+    lidar.with_column(Series::new(CLUSTER_ID_COLUMN, cluster_ids.to_vec()))?;
+    Ok(lidar)
+}
+
+/// Class-agnostic cuboid proposals: a minimum-area oriented cuboid fit to each cluster of the
+/// non-ground (N,3+) points. Returns the (N,) cluster ids and the (C,10) cuboids, where row `c`
+/// is fit to cluster `c`.
+pub fn propose_cuboids(
+    points: &ArrayView<f32, Ix2>,
+    ground_cfg: Option<&GroundEstimationConfig>,
+    cfg: &ClusteringConfig,
+) -> (Array<i64, Ix1>, Array<f32, Ix2>) {
+    let cluster_ids = _cluster_non_ground(points, ground_cfg, cfg);
+    let (_, cuboids) = fit_oriented_cuboids(&points.slice(s![.., ..3]), &cluster_ids.view());
+    (cluster_ids, cuboids)
+}
+
+fn _cluster_non_ground(
+    points: &ArrayView<f32, Ix2>,
+    ground_cfg: Option<&GroundEstimationConfig>,
+    cfg: &ClusteringConfig,
+) -> Array<i64, Ix1> {
+    let xyz = points.slice(s![.., ..3]);
+    let Some(ground_cfg) = ground_cfg else {
+        return cluster_points(&xyz, cfg);
+    };
+    let is_non_ground = compute_non_ground_mask(&xyz, ground_cfg);
+    let indices = (0..xyz.nrows())
+        .filter(|i| is_non_ground[*i])
+        .collect::<Vec<_>>();
+    let non_ground = xyz.select(Axis(0), &indices);
+    let mut cluster_ids = Array::<i64, Ix1>::from_elem(xyz.nrows(), -1);
+    for (i, id) in indices
+        .into_iter()
+        .zip(cluster_points(&non_ground.view(), cfg))
+    {
+        cluster_ids[i] = id;
+    }
+    cluster_ids
+}
+
+/// Find the root of `i` with path halving.
+fn _find(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+        i = parents[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, concatenate, s, Array, Axis, Ix2};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{cluster_points, propose_cuboids, ClusteringConfig};
+
+    #[test]
+    fn test_cluster_points() {
+        // Two dense blobs, a sparse chain, and an isolated point.
+        let mut rng = StdRng::seed_from_u64(0);
+        let blob = |rng: &mut StdRng, x: f32| {
+            Array::<f32, Ix2>::from_shape_fn((20, 3), |(_, k)| match k {
+                0 => x + rng.gen_range(-0.5..0.5),
+                _ => rng.gen_range(-0.5..0.5),
+            })
+        };
+        let points = concatenate![
+            Axis(0),
+            blob(&mut rng, 0.),
+            blob(&mut rng, 10.),
+            array![
+                [20_f32, 0., 0.],
+                [20.4, 0., 0.],
+                [20.8, 0., 0.],
+                [50., 0., 0.]
+            ]
+        ];
+        let cfg = ClusteringConfig {
+            eps_m: 0.6,
+            min_points: 3,
+            min_cluster_size: 3,
+            use_bev: false,
+        };
+        let ids = cluster_points(&points.view(), &cfg);
+        assert!(ids.slice(s![..20]).iter().all(|id| *id == 0));
+        assert!(ids.slice(s![20..40]).iter().all(|id| *id == 1));
+        // The chain's end points are border points of its single core point.
+        assert_eq!(ids.slice(s![40..]).to_vec(), vec![2, 2, 2, -1]);
+
+        let cfg = ClusteringConfig {
+            min_cluster_size: 10,
+            ..cfg
+        };
+        let (ids, cuboids) = propose_cuboids(&points.view(), None, &cfg);
+        assert_eq!(ids.iter().filter(|id| **id == -1).count(), 4);
+        assert_eq!(cuboids.shape(), &[2, 10]);
+        assert!((cuboids[[1, 0]] - 10.).abs() < 0.5);
+    }
+}
@@ -0,0 +1,236 @@
+//! # coordinate_frames
+//!
+//! Transforms among the city, egovehicle, and sensor frames of a log.
+//!
+//! `city_SE3_egovehicle.feather` gives the egovehicle pose over time and
+//! `calibration/egovehicle_SE3_sensor.feather` gives the fixed sensor extrinsics. Chaining the two
+//! maps points and cuboids between any pair of frames at a given timestamp.
+
+use std::{collections::HashMap, fmt, path::Path};
+
+use anyhow::{bail, Context, Result};
+use ndarray::{Array, ArrayView, Ix2};
+use polars::prelude::{col, cols, DataFrame, DataType, IntoLazy};
+
+use crate::{
+    constants::POSE_COLUMNS,
+    error::Av2Result,
+    io::{ndarray_from_frame, read_feather_eager},
+    structures::cuboid::CuboidList,
+};
+
+use super::{interpolate::interpolate_city_se3_ego, se3::SE3};
+
+/// Coordinate frame of a log.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Frame {
+    /// City frame of the log's map.
+    City,
+    /// Egovehicle frame.
+    Ego,
+    /// Frame of a sensor (e.g., `ring_front_center` or `up_lidar`).
+    Sensor(String),
+}
+
+impl Frame {
+    /// Parse `city`, `ego` (or `egovehicle`), or a sensor name.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "city" => Frame::City,
+            "ego" | "egovehicle" => Frame::Ego,
+            sensor_name => Frame::Sensor(sensor_name.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Frame::City => write!(f, "city"),
+            Frame::Ego => write!(f, "ego"),
+            Frame::Sensor(sensor_name) => write!(f, "{sensor_name}"),
+        }
+    }
+}
+
+/// Egovehicle poses and sensor extrinsics of a log.
+#[derive(Clone, Debug)]
+pub struct LogFrames {
+    /// Egovehicle poses with `timestamp_ns` and the `POSE_COLUMNS`.
+    pub city_pose: DataFrame,
+    /// Extrinsics of each sensor.
+    pub ego_se3_sensor: HashMap<String, SE3>,
+}
+
+impl LogFrames {
+    /// Construct from the egovehicle poses and a `DataFrame` of sensor extrinsics with
+    /// `sensor_name` and the `POSE_COLUMNS`.
+    pub fn new(city_pose: DataFrame, extrinsics: &DataFrame) -> Result<Self> {
+        let sensor_names = extrinsics
+            .column("sensor_name")?
+            .cast(&DataType::String)?
+            .str()?
+            .into_no_null_iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        let poses = ndarray_from_frame(extrinsics, cols(POSE_COLUMNS))?;
+        let ego_se3_sensor = sensor_names
+            .into_iter()
+            .zip(poses.outer_iter())
+            .map(|(name, pose)| Ok((name, SE3::from_pose(&pose)?)))
+            .collect::<Av2Result<_>>()?;
+        let city_pose = city_pose
+            .lazy()
+            .select([col("timestamp_ns"), cols(POSE_COLUMNS)])
+            .collect()
+            .context("Egovehicle poses need `timestamp_ns` and pose columns.")?;
+        Ok(Self {
+            city_pose,
+            ego_se3_sensor,
+        })
+    }
+
+    /// Load the egovehicle poses and sensor extrinsics of a log.
+    pub fn load(log_dir: &Path) -> Result<Self> {
+        let city_pose_path = log_dir.join("city_SE3_egovehicle.feather");
+        let extrinsics_path = log_dir.join("calibration/egovehicle_SE3_sensor.feather");
+        for path in [&city_pose_path, &extrinsics_path] {
+            if !path.exists() {
+                bail!("{} not found.", path.display());
+            }
+        }
+        Self::new(
+            read_feather_eager(&city_pose_path, false),
+            &read_feather_eager(&extrinsics_path, false),
+        )
+    }
+
+    /// Egovehicle pose in the city frame at `timestamp_ns`, interpolated between logged poses.
+    pub fn city_se3_ego(&self, timestamp_ns: u64) -> Result<SE3> {
+        interpolate_city_se3_ego(&self.city_pose, timestamp_ns)?
+            .with_context(|| format!("No egovehicle pose at {timestamp_ns}."))
+    }
+
+    /// Pose of `frame` in the egovehicle frame. The city frame depends on `timestamp_ns`.
+    pub fn ego_se3_frame(&self, frame: &Frame, timestamp_ns: u64) -> Result<SE3> {
+        match frame {
+            Frame::City => Ok(self.city_se3_ego(timestamp_ns)?.inverse()),
+            Frame::Ego => Ok(SE3::identity()),
+            Frame::Sensor(sensor_name) => self
+                .ego_se3_sensor
+                .get(sensor_name)
+                .cloned()
+                .with_context(|| format!("Unknown sensor `{sensor_name}`.")),
+        }
+    }
+
+    /// Transformation `dst_SE3_src` taking `src` coordinates to `dst` coordinates at `timestamp_ns`.
+    pub fn dst_se3_src(&self, src: &Frame, dst: &Frame, timestamp_ns: u64) -> Result<SE3> {
+        if src == dst {
+            return Ok(SE3::identity());
+        }
+        let ego_se3_src = self.ego_se3_frame(src, timestamp_ns)?;
+        let ego_se3_dst = self.ego_se3_frame(dst, timestamp_ns)?;
+        Ok(ego_se3_dst.inverse().compose(&ego_se3_src))
+    }
+
+    /// Transform (N,3) points from `src` to `dst` at `timestamp_ns`.
+    pub fn transform_points(
+        &self,
+        points: &ArrayView<f32, Ix2>,
+        src: &Frame,
+        dst: &Frame,
+        timestamp_ns: u64,
+    ) -> Result<Array<f32, Ix2>> {
+        let dst_se3_src = self.dst_se3_src(src, dst, timestamp_ns)?;
+        Ok(dst_se3_src.transform_from(points))
+    }
+
+    /// Transform cuboids from `src` to `dst` at `timestamp_ns`.
+    pub fn transform_cuboids(
+        &self,
+        cuboids: &CuboidList,
+        src: &Frame,
+        dst: &Frame,
+        timestamp_ns: u64,
+    ) -> Result<CuboidList> {
+        let dst_se3_src = self.dst_se3_src(src, dst, timestamp_ns)?;
+        let mut transformed = cuboids.clone();
+        let params = dst_se3_src.transform_cuboids(&cuboids.params());
+        transformed.params_mut().assign(&params);
+        Ok(transformed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array};
+    use polars::prelude::{df, NamedFrom};
+
+    use super::{Frame, LogFrames};
+    use crate::structures::cuboid::CuboidList;
+
+    #[test]
+    fn test_log_frames() {
+        // The egovehicle moves along +x and the sensor is 1m above it, yawed 90 degrees.
+        let city_pose = df!(
+            "timestamp_ns" => [0_u64, 10],
+            "tx_m" => [0_f32, 10.],
+            "ty_m" => [0_f32, 0.],
+            "tz_m" => [0_f32, 0.],
+            "qw" => [1_f32, 1.],
+            "qx" => [0_f32, 0.],
+            "qy" => [0_f32, 0.],
+            "qz" => [0_f32, 0.]
+        )
+        .unwrap();
+        let half_sqrt_2 = 0.5_f32.sqrt();
+        let extrinsics = df!(
+            "sensor_name" => ["lidar"],
+            "tx_m" => [0_f32],
+            "ty_m" => [0_f32],
+            "tz_m" => [1_f32],
+            "qw" => [half_sqrt_2],
+            "qx" => [0_f32],
+            "qy" => [0_f32],
+            "qz" => [half_sqrt_2]
+        )
+        .unwrap();
+        let frames = LogFrames::new(city_pose, &extrinsics).unwrap();
+
+        let (lidar, city) = (Frame::from_name("lidar"), Frame::from_name("city"));
+        let points = array![[1_f32, 0., 0.]];
+        let city_points = frames
+            .transform_points(&points.view(), &lidar, &city, 5)
+            .unwrap();
+        assert!(city_points
+            .iter()
+            .zip([5., 1., 1.])
+            .all(|(x, y)| (x - y).abs() < 1e-5));
+        let round_trip = frames
+            .transform_points(&city_points.view(), &city, &lidar, 5)
+            .unwrap();
+        assert!(round_trip
+            .iter()
+            .zip(points.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-5));
+
+        let cuboids = CuboidList::new(
+            Array::from_shape_vec((1, 10), vec![1., 0., 0., 4., 2., 1., 1., 0., 0., 0.]).unwrap(),
+            None,
+            None,
+        )
+        .unwrap();
+        let ego_cuboids = frames
+            .transform_cuboids(&cuboids, &lidar, &Frame::Ego, 5)
+            .unwrap();
+        let cuboid = ego_cuboids.get(0);
+        assert!((cuboid.translation_m[1] - 1.).abs() < 1e-5);
+        assert!((cuboid.quat_wxyz[3] - half_sqrt_2).abs() < 1e-5);
+
+        assert!(frames.city_se3_ego(11).is_err());
+        assert!(frames
+            .dst_se3_src(&Frame::from_name("camera"), &Frame::Ego, 5)
+            .is_err());
+    }
+}
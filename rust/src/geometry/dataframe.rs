@@ -0,0 +1,37 @@
+//! # dataframe
+//!
+//! Data-frame adapters of the geometry routines.
+//!
+//! The geometry core operates on `ndarray` arrays and compiles without `polars`. The adapters
+//! below read their inputs from (and write their outputs to) `polars` data-frames, and are only
+//! compiled with the `dataframe` feature.
+
+use polars::prelude::{df, DataFrame, NamedFrom, Series};
+
+use super::so3::{repair_quat_wxyz, QUAT_COLUMNS};
+use crate::{
+    error::Av2Result,
+    schema::{validate_data_frame, CUBOID_SCHEMA},
+    share::data_frame_to_ndarray_f32,
+};
+
+/// Repair the `qw`, `qx`, `qy`, and `qz` columns of a cuboid `DataFrame`.
+/// Returns the repaired cuboids and a report with the `row` and `defect` of each repair.
+pub fn repair_cuboid_quaternions(cuboids: &DataFrame) -> Av2Result<(DataFrame, DataFrame)> {
+    // Only the quaternion columns are required.
+    validate_data_frame("cuboids", cuboids, &CUBOID_SCHEMA[6..])?;
+    let quat_wxyz = data_frame_to_ndarray_f32(cuboids.clone(), QUAT_COLUMNS.to_vec())?;
+    let (repaired, defects) = repair_quat_wxyz(&quat_wxyz.view());
+
+    let mut cuboids = cuboids.clone();
+    for (k, name) in QUAT_COLUMNS.into_iter().enumerate() {
+        cuboids.with_column(Series::new(name, repaired.column(k).to_vec()))?;
+    }
+    let (rows, defects): (Vec<_>, Vec<_>) = defects
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, d)| d.into_iter().map(move |d| (i as u32, d.to_string())))
+        .unzip();
+    let report = df!("row" => rows, "defect" => defects)?;
+    Ok((cuboids, report))
+}
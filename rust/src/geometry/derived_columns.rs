@@ -0,0 +1,236 @@
+//! # derived_columns
+//!
+//! Derived per-point columns computed when lidar sweeps are loaded.
+
+use std::str::FromStr;
+
+use ndarray::{par_azip, Array, Ix1};
+use polars::prelude::{DataFrame, NamedFrom, Series};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{
+    error::{Av2Error, Av2Result},
+    share::data_frame_to_ndarray_f32,
+};
+
+use super::{
+    aggregation::TIMEDELTA_COLUMN,
+    ground::{fit_ground_plane, GroundEstimationConfig},
+};
+
+/// Per-point columns which can be derived from a sweep.
+/// The column name is the snake case variant name (e.g., `range_m`).
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum DerivedColumn {
+    /// Euclidean distance from the egovehicle origin (in meters).
+    RangeM,
+    /// Azimuth `atan2(y, x)` in the egovehicle frame (in radians).
+    AzimuthRad,
+    /// Signed distance to the fitted ground plane (in meters). Falls back to `z` if no plane fits.
+    HeightAboveGroundM,
+    /// Time before the reference sweep divided by the largest one in the sweep, in `[0, 1]`.
+    NormalizedTimedelta,
+}
+
+/// Parse derived column names (e.g., `range_m`).
+pub fn parse_derived_columns(names: &[String]) -> Av2Result<Vec<DerivedColumn>> {
+    names
+        .iter()
+        .map(|name| {
+            DerivedColumn::from_str(name).map_err(|_| {
+                Av2Error::invalid_parameter(
+                    "derived_columns",
+                    format!("Unknown derived column `{name}`."),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Append the `columns` to a lidar sweep with `x`, `y`, and `z` (and `TIMEDELTA_COLUMN` for
+/// `NormalizedTimedelta`) columns.
+pub fn append_derived_columns(
+    lidar: &DataFrame,
+    columns: &[DerivedColumn],
+    ground_cfg: &GroundEstimationConfig,
+) -> Av2Result<DataFrame> {
+    if columns.is_empty() {
+        return Ok(lidar.clone());
+    }
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let num_points = xyz.shape()[0];
+    let mut lidar = lidar.clone();
+    for column in columns {
+        let values = match column {
+            DerivedColumn::RangeM => {
+                let mut range_m = Array::<f32, Ix1>::zeros(num_points);
+                par_azip!((r in &mut range_m, p in xyz.outer_iter()) *r = p.dot(&p).sqrt());
+                range_m
+            }
+            DerivedColumn::AzimuthRad => {
+                let mut azimuth_rad = Array::<f32, Ix1>::zeros(num_points);
+                par_azip!((a in &mut azimuth_rad, p in xyz.outer_iter()) *a = p[1].atan2(p[0]));
+                azimuth_rad
+            }
+            DerivedColumn::HeightAboveGroundM => match fit_ground_plane(&xyz.view(), ground_cfg) {
+                Some(plane) => plane.signed_distances(&xyz.view()),
+                None => xyz.column(2).to_owned(),
+            },
+            DerivedColumn::NormalizedTimedelta => {
+                let timedelta = data_frame_to_ndarray_f32(lidar.clone(), vec![TIMEDELTA_COLUMN])?;
+                let max_timedelta = timedelta.fold(0_f32, |acc, x| acc.max(*x));
+                match max_timedelta > 0. {
+                    true => timedelta.column(0).mapv(|x| x / max_timedelta),
+                    false => Array::<f32, Ix1>::zeros(num_points),
+                }
+            }
+        };
+        lidar.with_column(Series::new(&column.to_string(), values.to_vec()))?;
+    }
+    Ok(lidar)
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+    use strum::IntoEnumIterator;
+
+    use super::{append_derived_columns, parse_derived_columns, DerivedColumn};
+    use crate::geometry::{aggregation::TIMEDELTA_COLUMN, ground::GroundEstimationConfig};
+
+    fn _column(lidar: &DataFrame, column: DerivedColumn) -> Vec<f32> {
+        lidar
+            .column(&column.to_string())
+            .unwrap()
+            .f32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    fn _assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_derived_columns() {
+        let names = DerivedColumn::iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "range_m",
+                "azimuth_rad",
+                "height_above_ground_m",
+                "normalized_timedelta"
+            ]
+        );
+        assert_eq!(
+            parse_derived_columns(&names).unwrap(),
+            DerivedColumn::iter().collect::<Vec<_>>()
+        );
+        assert!(parse_derived_columns(&["range".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_range_and_azimuth() {
+        let lidar = df!(
+            "x" => [3_f32, 0., -2.],
+            "y" => [4_f32, 1., 0.],
+            "z" => [0_f32, 0., 0.],
+        )
+        .unwrap();
+        let cfg = GroundEstimationConfig::default();
+        let lidar = append_derived_columns(
+            &lidar,
+            &[DerivedColumn::RangeM, DerivedColumn::AzimuthRad],
+            &cfg,
+        )
+        .unwrap();
+        _assert_close(&_column(&lidar, DerivedColumn::RangeM), &[5., 1., 2.]);
+        _assert_close(
+            &_column(&lidar, DerivedColumn::AzimuthRad),
+            &[
+                4_f32.atan2(3.),
+                std::f32::consts::FRAC_PI_2,
+                std::f32::consts::PI,
+            ],
+        );
+        assert!(append_derived_columns(&lidar, &[], &cfg)
+            .unwrap()
+            .equals(&lidar));
+    }
+
+    #[test]
+    fn test_height_above_ground() {
+        // A 5x5 grid on the plane z = -0.5 and two points above it.
+        let (mut x, mut y, mut z) = (vec![], vec![], vec![]);
+        for i in 0..5 {
+            for j in 0..5 {
+                x.push(i as f32);
+                y.push(j as f32);
+                z.push(-0.5_f32);
+            }
+        }
+        x.extend([1_f32, 2.]);
+        y.extend([1_f32, 3.]);
+        z.extend([1.5_f32, 0.5]);
+        let lidar = df!("x" => &x, "y" => &y, "z" => &z).unwrap();
+        let cfg = GroundEstimationConfig::default();
+        let lidar =
+            append_derived_columns(&lidar, &[DerivedColumn::HeightAboveGroundM], &cfg).unwrap();
+        let mut expected = vec![0_f32; 25];
+        expected.extend([2., 1.]);
+        _assert_close(
+            &_column(&lidar, DerivedColumn::HeightAboveGroundM),
+            &expected,
+        );
+
+        // Without ground candidates the height falls back to `z`.
+        let lidar = df!("x" => [0_f32, 1.], "y" => [0_f32, 1.], "z" => [5_f32, 6.]).unwrap();
+        let lidar =
+            append_derived_columns(&lidar, &[DerivedColumn::HeightAboveGroundM], &cfg).unwrap();
+        _assert_close(
+            &_column(&lidar, DerivedColumn::HeightAboveGroundM),
+            &[5., 6.],
+        );
+    }
+
+    #[test]
+    fn test_normalized_timedelta() {
+        let cfg = GroundEstimationConfig::default();
+        let lidar = df!(
+            "x" => [0_f32, 1., 2.],
+            "y" => [0_f32, 0., 0.],
+            "z" => [0_f32, 0., 0.],
+            TIMEDELTA_COLUMN => [0_i64, 50_000_000, 100_000_000],
+        )
+        .unwrap();
+        let lidar =
+            append_derived_columns(&lidar, &[DerivedColumn::NormalizedTimedelta], &cfg).unwrap();
+        _assert_close(
+            &_column(&lidar, DerivedColumn::NormalizedTimedelta),
+            &[0., 0.5, 1.],
+        );
+
+        // A single sweep has a zero maximum timedelta.
+        let lidar = df!(
+            "x" => [0_f32, 1.],
+            "y" => [0_f32, 0.],
+            "z" => [0_f32, 0.],
+            TIMEDELTA_COLUMN => [0_i64, 0],
+        )
+        .unwrap();
+        let lidar =
+            append_derived_columns(&lidar, &[DerivedColumn::NormalizedTimedelta], &cfg).unwrap();
+        _assert_close(
+            &_column(&lidar, DerivedColumn::NormalizedTimedelta),
+            &[0., 0.],
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! # ground
+//!
+//! Ground-plane estimation and ground removal.
+//!
+//! The ground plane is fit with RANSAC over points near the egovehicle's ground level (the
+//! egovehicle frame's origin lies on the ground) and refined with least squares on its inliers.
+//! When the log's map is available, points are instead tagged against its ground height raster.
+
+use ndarray::{par_azip, Array, ArrayView, Ix1, Ix2};
+use polars::prelude::{BooleanChunked, DataFrame, NamedFrom, NewChunkedArray, Series};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use super::se3::SE3;
+use crate::{
+    error::Av2Result, map::static_map::GroundHeightRaster, share::data_frame_to_ndarray_f32,
+};
+
+/// Column of the map-based ground mask appended by `annotate_ground_points`.
+pub const IS_GROUND_COLUMN: &str = "is_ground";
+
+/// Ground estimation configuration.
+#[derive(Clone, Debug)]
+pub struct GroundEstimationConfig {
+    /// Number of RANSAC iterations.
+    pub num_iterations: usize,
+    /// Maximum point-to-plane distance of ground points (in meters).
+    pub distance_threshold_m: f32,
+    /// Maximum angle between the plane normal and the z-axis (in degrees).
+    pub max_slope_deg: f32,
+    /// Only points with a height within this range (in meters) are used to fit the plane.
+    pub candidate_height_range_m: [f32; 2],
+    /// Random seed. Iteration `i` is seeded with `seed + i` so results are reproducible.
+    pub seed: u64,
+}
+
+impl Default for GroundEstimationConfig {
+    fn default() -> Self {
+        Self {
+            num_iterations: 100,
+            distance_threshold_m: 0.2,
+            max_slope_deg: 15.,
+            candidate_height_range_m: [-1., 1.],
+            seed: 0,
+        }
+    }
+}
+
+/// Plane `normal · p + offset = 0` with a unit normal pointing upwards.
+#[derive(Clone, Debug)]
+pub struct Plane {
+    /// (3,) unit normal.
+    pub normal: Array<f32, Ix1>,
+    /// Signed offset from the origin.
+    pub offset: f32,
+}
+
+impl Plane {
+    /// Signed distances of (N,3) points to the plane (positive above the plane).
+    pub fn signed_distances(&self, xyz: &ArrayView<f32, Ix2>) -> Array<f32, Ix1> {
+        xyz.dot(&self.normal) + self.offset
+    }
+}
+
+/// Fit the ground plane to (N,3) points with RANSAC.
+/// Returns `None` if there are too few candidate points or no plane satisfies the slope limit.
+pub fn fit_ground_plane(xyz: &ArrayView<f32, Ix2>, cfg: &GroundEstimationConfig) -> Option<Plane> {
+    let [min_height_m, max_height_m] = cfg.candidate_height_range_m;
+    let candidates = xyz
+        .outer_iter()
+        .filter(|p| p[2] >= min_height_m && p[2] <= max_height_m)
+        .flat_map(|p| p.to_vec())
+        .collect::<Vec<_>>();
+    let candidates = Array::from_shape_vec((candidates.len() / 3, 3), candidates).unwrap();
+    let num_candidates = candidates.shape()[0];
+    if num_candidates < 3 {
+        return None;
+    }
+
+    let min_normal_z = cfg.max_slope_deg.to_radians().cos();
+    let (_, plane) = (0..cfg.num_iterations)
+        .into_par_iter()
+        .filter_map(|i| {
+            let mut rng = StdRng::seed_from_u64(cfg.seed.wrapping_add(i as u64));
+            let sample = [0; 3].map(|_| candidates.row(rng.gen_range(0..num_candidates)));
+            let plane = _plane_from_points(&sample[0], &sample[1], &sample[2])?;
+            if plane.normal[2] < min_normal_z {
+                return None;
+            }
+            let num_inliers = plane
+                .signed_distances(&candidates.view())
+                .iter()
+                .filter(|d| d.abs() <= cfg.distance_threshold_m)
+                .count();
+            Some((num_inliers, plane))
+        })
+        .max_by_key(|(num_inliers, _)| *num_inliers)?;
+
+    let distances = plane.signed_distances(&candidates.view());
+    let inliers = candidates
+        .outer_iter()
+        .zip(distances.iter())
+        .filter(|(_, d)| d.abs() <= cfg.distance_threshold_m)
+        .map(|(p, _)| p)
+        .collect::<Vec<_>>();
+    Some(_refine_plane(&inliers).unwrap_or(plane))
+}
+
+/// Compute a (N,) mask which is `true` for points more than `distance_threshold_m` above the
+/// fitted ground plane. If no plane can be fit, every point is kept.
+pub fn compute_non_ground_mask(
+    xyz: &ArrayView<f32, Ix2>,
+    cfg: &GroundEstimationConfig,
+) -> Array<bool, Ix1> {
+    let num_points = xyz.shape()[0];
+    let Some(plane) = fit_ground_plane(xyz, cfg) else {
+        return Array::from_elem(num_points, true);
+    };
+    let distances = plane.signed_distances(xyz);
+    let mut mask = Array::from_elem(num_points, false);
+    par_azip!((m in &mut mask, &d in &distances) {
+        *m = d > cfg.distance_threshold_m;
+    });
+    mask
+}
+
+/// Remove the ground returns from a lidar sweep.
+pub fn remove_ground(lidar: &DataFrame, cfg: &GroundEstimationConfig) -> Av2Result<DataFrame> {
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let mask = compute_non_ground_mask(&xyz.view(), cfg);
+    let mask = BooleanChunked::from_iter_values("non_ground", mask.into_iter());
+    Ok(lidar.filter(&mask)?)
+}
+
+/// Append the `is_ground` column to a lidar sweep in the egovehicle frame, which is `true` for
+/// points within `GROUND_HEIGHT_THRESHOLD_M` of the map's ground surface (or below it).
+pub fn annotate_ground_points(
+    lidar: &DataFrame,
+    raster: &GroundHeightRaster,
+    city_se3_ego: &SE3,
+) -> Av2Result<DataFrame> {
+    let xyz_ego = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let xyz_city = city_se3_ego.transform_from(&xyz_ego.view());
+    let is_ground = raster.get_ground_points_boolean(&xyz_city.view());
+    let mut lidar = lidar.clone();
+    lidar.with_column(Series::new(IS_GROUND_COLUMN, is_ground.to_vec()))?;
+    Ok(lidar)
+}
+
+/// Remove the ground returns from a lidar sweep in the egovehicle frame using the map's ground
+/// height raster.
+pub fn remove_map_ground(
+    lidar: &DataFrame,
+    raster: &GroundHeightRaster,
+    city_se3_ego: &SE3,
+) -> Av2Result<DataFrame> {
+    let xyz_ego = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let xyz_city = city_se3_ego.transform_from(&xyz_ego.view());
+    let is_ground = raster.get_ground_points_boolean(&xyz_city.view());
+    let mask = BooleanChunked::from_iter_values("non_ground", is_ground.iter().map(|x| !x));
+    Ok(lidar.filter(&mask)?)
+}
+
+/// Plane through three points with an upward facing normal (`None` if they are collinear).
+fn _plane_from_points(
+    p0: &ArrayView<f32, Ix1>,
+    p1: &ArrayView<f32, Ix1>,
+    p2: &ArrayView<f32, Ix1>,
+) -> Option<Plane> {
+    let (u, v) = (p1 - p0, p2 - p0);
+    let normal = Array::from_vec(vec![
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]);
+    let norm = normal.dot(&normal).sqrt();
+    if norm < 1e-6 {
+        return None;
+    }
+    let normal = normal.mapv(|x| x / norm.copysign(normal[2]));
+    let offset = -normal.dot(p0);
+    Some(Plane { normal, offset })
+}
+
+/// Least-squares fit of `z = ax + by + c` to the inliers.
+fn _refine_plane(inliers: &[ArrayView<f32, Ix1>]) -> Option<Plane> {
+    if inliers.len() < 3 {
+        return None;
+    }
+    // Accumulate the normal equations in double precision around the centroid.
+    let n = inliers.len() as f64;
+    let centroid = inliers.iter().fold([0_f64; 3], |acc, p| {
+        [0, 1, 2].map(|k| acc[k] + p[k] as f64 / n)
+    });
+    let (mut sxx, mut sxy, mut syy, mut sxz, mut syz) = (0., 0., 0., 0., 0.);
+    for p in inliers {
+        let (x, y, z) = (
+            p[0] as f64 - centroid[0],
+            p[1] as f64 - centroid[1],
+            p[2] as f64 - centroid[2],
+        );
+        sxx += x * x;
+        sxy += x * y;
+        syy += y * y;
+        sxz += x * z;
+        syz += y * z;
+    }
+    let det = sxx * syy - sxy * sxy;
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let a = (sxz * syy - syz * sxy) / det;
+    let b = (syz * sxx - sxz * sxy) / det;
+
+    let norm = (a * a + b * b + 1.).sqrt();
+    let normal = Array::from_vec(vec![
+        (-a / norm) as f32,
+        (-b / norm) as f32,
+        (1. / norm) as f32,
+    ]);
+    let centroid = Array::from_vec(centroid.map(|x| x as f32).to_vec());
+    let offset = -normal.dot(&centroid);
+    Some(Plane { normal, offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array, Ix2};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{compute_non_ground_mask, fit_ground_plane, GroundEstimationConfig};
+
+    /// Noisy points on the tilted plane `z = 0.05x + 0.02y - 0.3` followed by `num_outliers`
+    /// points at least 0.5 m above it.
+    fn _tilted_plane(num_ground: usize, num_outliers: usize) -> Array<f32, Ix2> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut points = vec![];
+        for i in 0..num_ground + num_outliers {
+            let (x, y) = (rng.gen_range(-8_f32..8.), rng.gen_range(-8_f32..8.));
+            let height = match i < num_ground {
+                true => rng.gen_range(-0.02..0.02),
+                false => rng.gen_range(0.5..3.),
+            };
+            points.extend([x, y, 0.05 * x + 0.02 * y - 0.3 + height]);
+        }
+        Array::from_shape_vec((num_ground + num_outliers, 3), points).unwrap()
+    }
+
+    #[test]
+    fn test_fit_ground_plane() {
+        let xyz = _tilted_plane(200, 100);
+        let cfg = GroundEstimationConfig::default();
+        let plane = fit_ground_plane(&xyz.view(), &cfg).unwrap();
+
+        let norm = (1_f32 + 0.05_f32.powi(2) + 0.02_f32.powi(2)).sqrt();
+        let expected_normal = array![-0.05, -0.02, 1.] / norm;
+        assert!(
+            (&plane.normal - &expected_normal)
+                .iter()
+                .all(|x| x.abs() < 5e-3),
+            "{}",
+            plane.normal
+        );
+        assert!((plane.offset - 0.3 / norm).abs() < 1e-2, "{}", plane.offset);
+
+        let mask = compute_non_ground_mask(&xyz.view(), &cfg);
+        assert!(mask.iter().take(200).all(|x| !x));
+        assert!(mask.iter().skip(200).all(|x| *x));
+
+        // The same seed fits the same plane.
+        let other = fit_ground_plane(&xyz.view(), &cfg).unwrap();
+        assert_eq!((other.normal, other.offset), (plane.normal, plane.offset));
+    }
+
+    #[test]
+    fn test_fit_ground_plane_failure() {
+        let cfg = GroundEstimationConfig::default();
+
+        // Too few candidates near the ground keep every point.
+        let xyz = array![[0_f32, 0., 0.], [1., 0., 0.], [0., 1., 5.]];
+        assert!(fit_ground_plane(&xyz.view(), &cfg).is_none());
+        assert!(compute_non_ground_mask(&xyz.view(), &cfg)
+            .iter()
+            .all(|x| *x));
+
+        // A wall exceeds the slope limit.
+        let xyz = Array::from_shape_fn((20, 3), |(i, k)| match k {
+            0 => 1.,
+            1 => (i % 5) as f32,
+            _ => (i / 5) as f32 * 0.5 - 0.75,
+        });
+        assert!(fit_ground_plane(&xyz.view(), &cfg).is_none());
+    }
+}
@@ -0,0 +1,390 @@
+//! # interpolate
+//!
+//! Pose interpolation between timestamps.
+//!
+//! Translations are linearly interpolated while rotations are spherically interpolated (slerp).
+
+use ndarray::{par_azip, s, Array, ArrayView, Ix1, Ix2};
+use polars::{
+    lazy::dsl::{col, cols, lit},
+    prelude::{
+        ChunkCompare, DataFrame, DataType, Float64Type, IdxCa, IdxSize, IndexOrder, IntoLazy,
+        NamedFrom, Series, SortOptions,
+    },
+};
+
+use crate::{
+    constants::POSE_COLUMNS,
+    error::{Av2Error, Av2Result},
+    io::ndarray_from_frame,
+    schema::{validate_data_frame, POSE_SCHEMA},
+};
+
+use super::{scalar::GeometryFloat, se3::SE3, so3::_slerp};
+
+/// Given two SE(3) poses at specific timestamps, interpolate the pose at `query_timestamp_ns`.
+/// Returns `None` if the query timestamp does not fall within the keyframe interval.
+pub fn interpolate_pose<T: GeometryFloat>(
+    key_timestamps_ns: (u64, u64),
+    key_poses: (&SE3<T>, &SE3<T>),
+    query_timestamp_ns: u64,
+) -> Option<SE3<T>> {
+    let (t0, t1) = key_timestamps_ns;
+    if query_timestamp_ns < t0 || query_timestamp_ns > t1 {
+        return None;
+    }
+    let t = _interpolation_fraction(t0, t1, query_timestamp_ns);
+    let pose = _interpolate_pose(
+        &_se3_to_pose(key_poses.0).view(),
+        &_se3_to_pose(key_poses.1).view(),
+        t,
+    );
+    // The slerp of unit quaternions is a unit quaternion.
+    SE3::from_pose(&pose.view()).ok()
+}
+
+/// Interpolate (N,7) poses ordered as `POSE_COLUMNS` with sorted (N,) `key_timestamps_ns` at (M,)
+/// `query_timestamps_ns`. Queries outside of the keyframe interval are clamped to the first or
+/// last pose. Parallelized for batch processing.
+pub fn interpolate_poses<T: GeometryFloat>(
+    key_timestamps_ns: &ArrayView<u64, Ix1>,
+    key_poses: &ArrayView<T, Ix2>,
+    query_timestamps_ns: &ArrayView<u64, Ix1>,
+) -> Av2Result<Array<T, Ix2>> {
+    let num_keys = key_timestamps_ns.len();
+    if num_keys == 0 {
+        return Err(Av2Error::invalid_parameter(
+            "key_timestamps_ns",
+            "At least one key pose is required.",
+        ));
+    }
+    if key_poses.shape() != [num_keys, 7] {
+        return Err(Av2Error::invalid_parameter(
+            "key_poses",
+            format!(
+                "Expected ({num_keys}, 7) poses to match the key timestamps, got {:?}.",
+                key_poses.shape()
+            ),
+        ));
+    }
+    let key_timestamps_ns = key_timestamps_ns.to_vec();
+
+    let num_queries = query_timestamps_ns.len();
+    let mut poses = Array::<T, Ix2>::zeros((num_queries, 7));
+    par_azip!((mut pose in poses.outer_iter_mut(), &query_timestamp_ns in query_timestamps_ns) {
+        let j = key_timestamps_ns.partition_point(|&t| t <= query_timestamp_ns);
+        if j == 0 {
+            pose.assign(&key_poses.row(0));
+        } else if j == num_keys {
+            pose.assign(&key_poses.row(num_keys - 1));
+        } else {
+            let (t0, t1) = (key_timestamps_ns[j - 1], key_timestamps_ns[j]);
+            let t = _interpolation_fraction(t0, t1, query_timestamp_ns);
+            pose.assign(&_interpolate_pose(&key_poses.row(j - 1), &key_poses.row(j), t));
+        }
+    });
+    Ok(poses)
+}
+
+/// Interpolate the city egovehicle pose at `query_timestamp_ns` from a log's pose frame.
+/// `city_pose` must contain `timestamp_ns` and the `POSE_COLUMNS` (e.g., `city_SE3_egovehicle.feather`).
+/// Returns `None` if the query timestamp is outside of the logged poses.
+pub fn interpolate_city_se3_ego(
+    city_pose: &DataFrame,
+    query_timestamp_ns: u64,
+) -> Av2Result<Option<SE3>> {
+    _interpolate_city_se3_ego(city_pose, query_timestamp_ns)
+}
+
+/// Interpolate the city egovehicle pose at `query_timestamp_ns` in double precision, which keeps
+/// UTM-scale city coordinates exact to well below a millimeter.
+/// See `interpolate_city_se3_ego`.
+pub fn interpolate_city_se3_ego_f64(
+    city_pose: &DataFrame,
+    query_timestamp_ns: u64,
+) -> Av2Result<Option<SE3<f64>>> {
+    _interpolate_city_se3_ego(city_pose, query_timestamp_ns)
+}
+
+fn _interpolate_city_se3_ego<T: GeometryFloat>(
+    city_pose: &DataFrame,
+    query_timestamp_ns: u64,
+) -> Av2Result<Option<SE3<T>>> {
+    validate_data_frame("poses", city_pose, &POSE_SCHEMA)?;
+    let city_pose = city_pose
+        .clone()
+        .lazy()
+        .sort("timestamp_ns", SortOptions::default())
+        .collect()?;
+    let timestamps_ns = city_pose
+        .clone()
+        .lazy()
+        .select([col("timestamp_ns").cast(DataType::UInt64)])
+        .collect()?["timestamp_ns"]
+        .u64()?
+        .into_no_null_iter()
+        .collect::<Vec<_>>();
+    let (Some(&first), Some(&last)) = (timestamps_ns.first(), timestamps_ns.last()) else {
+        return Ok(None);
+    };
+    if query_timestamp_ns < first || query_timestamp_ns > last {
+        return Ok(None);
+    }
+
+    // Poses are read in double precision (as stored) before conversion.
+    let key_poses = city_pose
+        .select(POSE_COLUMNS)?
+        .to_ndarray::<Float64Type>(IndexOrder::C)?
+        .mapv(T::cast);
+    let pose = interpolate_poses(
+        &Array::from_vec(timestamps_ns).view(),
+        &key_poses.view(),
+        &Array::from_vec(vec![query_timestamp_ns]).view(),
+    )?;
+    Ok(Some(SE3::from_pose(&pose.row(0))?))
+}
+
+/// Interpolate the cuboids of track `track_uuid` at `query_timestamps_ns`.
+/// `annotations` must contain `timestamp_ns`, `track_uuid`, and the `POSE_COLUMNS` (e.g.,
+/// `annotations.feather`). Queries outside of the track's annotated interval are skipped, and the
+/// other columns (e.g., the dimensions) are taken from the preceding annotation.
+pub fn interpolate_track(
+    annotations: &DataFrame,
+    track_uuid: &str,
+    query_timestamps_ns: &[u64],
+) -> Av2Result<DataFrame> {
+    let track = annotations
+        .clone()
+        .lazy()
+        .filter(col("track_uuid").eq(lit(track_uuid)))
+        .sort("timestamp_ns", SortOptions::default())
+        .collect()?;
+    _interpolate_sorted_track(&track, query_timestamps_ns)
+}
+
+/// Interpolate the cuboids of every track annotated both before and after (or exactly at)
+/// `query_timestamp_ns`. See `interpolate_track`.
+pub fn interpolate_cuboids(
+    annotations: &DataFrame,
+    query_timestamp_ns: u64,
+) -> Av2Result<DataFrame> {
+    let annotations = annotations
+        .clone()
+        .lazy()
+        .sort("timestamp_ns", SortOptions::default())
+        .collect()?;
+    let track_uuids = annotations["track_uuid"].unique_stable()?;
+    let mut cuboids = annotations.clear();
+    for track_uuid in track_uuids.str()?.into_no_null_iter() {
+        let mask = annotations["track_uuid"].str()?.equal(track_uuid);
+        let track = annotations.filter(&mask)?;
+        cuboids.vstack_mut(&_interpolate_sorted_track(&track, &[query_timestamp_ns])?)?;
+    }
+    Ok(cuboids)
+}
+
+/// Interpolate the cuboids of a single track sorted by `timestamp_ns`.
+fn _interpolate_sorted_track(
+    track: &DataFrame,
+    query_timestamps_ns: &[u64],
+) -> Av2Result<DataFrame> {
+    let key_timestamps_ns = track["timestamp_ns"]
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .into_no_null_iter()
+        .collect::<Vec<_>>();
+    let key_poses = ndarray_from_frame(track, cols(POSE_COLUMNS))?;
+
+    let (mut rows, mut timestamps_ns, mut poses) = (vec![], vec![], vec![]);
+    for &query_timestamp_ns in query_timestamps_ns {
+        let j = key_timestamps_ns.partition_point(|&t| t <= query_timestamp_ns);
+        if j == 0
+            || (j == key_timestamps_ns.len() && key_timestamps_ns[j - 1] != query_timestamp_ns)
+        {
+            continue;
+        }
+        let pose = match key_timestamps_ns[j - 1] == query_timestamp_ns {
+            true => key_poses.row(j - 1).to_owned(),
+            false => {
+                let (t0, t1) = (key_timestamps_ns[j - 1], key_timestamps_ns[j]);
+                let t = _interpolation_fraction(t0, t1, query_timestamp_ns);
+                _interpolate_pose(&key_poses.row(j - 1), &key_poses.row(j), t)
+            }
+        };
+        rows.push((j - 1) as IdxSize);
+        timestamps_ns.push(query_timestamp_ns);
+        poses.push(pose);
+    }
+
+    let mut cuboids = track.take(&IdxCa::from_vec("", rows))?;
+    for (k, name) in POSE_COLUMNS.iter().enumerate() {
+        let values = Series::new(name, poses.iter().map(|x| x[k]).collect::<Vec<_>>());
+        cuboids.with_column(values.cast(track[*name].dtype())?)?;
+    }
+    let timestamps_ns = Series::new("timestamp_ns", timestamps_ns);
+    cuboids.with_column(timestamps_ns.cast(track["timestamp_ns"].dtype())?)?;
+    Ok(cuboids)
+}
+
+/// Interpolate a single (7,) pose with fraction `t` in `[0, 1]`.
+fn _interpolate_pose<T: GeometryFloat>(
+    pose_0: &ArrayView<T, Ix1>,
+    pose_1: &ArrayView<T, Ix1>,
+    t: T,
+) -> Array<T, Ix1> {
+    let mut pose = Array::<T, Ix1>::zeros(7);
+    let translation = &pose_0.slice(s![..3]) * (T::one() - t) + &pose_1.slice(s![..3]) * t;
+    let quat_wxyz = _slerp(&pose_0.slice(s![3..7]), &pose_1.slice(s![3..7]), t);
+    pose.slice_mut(s![..3]).assign(&translation);
+    pose.slice_mut(s![3..7]).assign(&quat_wxyz);
+    pose
+}
+
+/// Fraction of the way `query` is between `t0` and `t1` (computed in double precision).
+fn _interpolation_fraction<T: GeometryFloat>(t0: u64, t1: u64, query: u64) -> T {
+    match t1 > t0 {
+        true => T::lit((query - t0) as f64 / (t1 - t0) as f64),
+        false => T::zero(),
+    }
+}
+
+/// Convert an SE(3) into a (7,) pose ordered as `POSE_COLUMNS`.
+fn _se3_to_pose<T: GeometryFloat>(se3: &SE3<T>) -> Array<T, Ix1> {
+    let mut pose = Array::<T, Ix1>::zeros(7);
+    pose.slice_mut(s![..3]).assign(&se3.translation);
+    pose.slice_mut(s![3..7]).assign(&se3.quat_wxyz());
+    pose
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+    use polars::prelude::*;
+
+    use super::{
+        interpolate_city_se3_ego, interpolate_city_se3_ego_f64, interpolate_cuboids,
+        interpolate_poses, interpolate_track,
+    };
+    use crate::error::Av2Error;
+
+    #[test]
+    fn test_interpolate_poses() {
+        let half_turn = std::f32::consts::FRAC_1_SQRT_2;
+        let key_timestamps_ns = array![100_u64, 200];
+        let key_poses = array![
+            [0_f32, 0., 0., 1., 0., 0., 0.],
+            [10_f32, -4., 2., half_turn, 0., 0., half_turn]
+        ];
+        let poses = interpolate_poses(
+            &key_timestamps_ns.view(),
+            &key_poses.view(),
+            &array![150_u64, 0, 300].view(),
+        )
+        .unwrap();
+
+        // The midpoint lerps the translation and slerps the rotation halfway.
+        let half_angle = std::f32::consts::FRAC_PI_8;
+        let midpoint = array![5_f32, -2., 1., half_angle.cos(), 0., 0., half_angle.sin()];
+        assert!(poses.row(0).abs_diff_eq(&midpoint, 1e-6));
+        // Queries outside of the keys are clamped to the first and last poses.
+        assert_eq!(poses.row(1), key_poses.row(0));
+        assert_eq!(poses.row(2), key_poses.row(1));
+    }
+
+    #[test]
+    fn test_interpolate_poses_invalid_keys() {
+        let queries = array![0_u64];
+        let err = interpolate_poses::<f32>(
+            &Array1::zeros(0).view(),
+            &Array2::zeros((0, 7)).view(),
+            &queries.view(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Av2Error::InvalidParameter { name, .. } if name == "key_timestamps_ns")
+        );
+
+        let err = interpolate_poses::<f32>(
+            &array![0_u64, 1].view(),
+            &Array2::zeros((1, 7)).view(),
+            &queries.view(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Av2Error::InvalidParameter { name, .. } if name == "key_poses"));
+    }
+
+    #[test]
+    fn test_interpolate_city_se3_ego() {
+        // Poses of a quarter turn about z while driving 10 m along x, listed out of order.
+        let half_turn = std::f64::consts::FRAC_1_SQRT_2;
+        let city_pose = df!(
+            "timestamp_ns" => [200_u64, 100],
+            "tx_m" => [4_000_010_f64, 4_000_000.],
+            "ty_m" => [2_f64, 2.],
+            "tz_m" => [0_f64, 0.],
+            "qw" => [half_turn, 1.],
+            "qx" => [0_f64, 0.],
+            "qy" => [0_f64, 0.],
+            "qz" => [half_turn, 0.]
+        )
+        .unwrap();
+
+        // A quarter of the way along, the egovehicle has turned by pi / 8.
+        let angle = std::f64::consts::FRAC_PI_8;
+        let city_se3_ego = interpolate_city_se3_ego_f64(&city_pose, 125)
+            .unwrap()
+            .unwrap();
+        assert!(city_se3_ego
+            .translation
+            .abs_diff_eq(&array![4_000_002.5, 2., 0.], 1e-9));
+        let qz = (angle / 2.).sin();
+        assert!(city_se3_ego
+            .quat_wxyz
+            .abs_diff_eq(&array![(angle / 2.).cos(), 0., 0., qz], 1e-12));
+
+        // Single precision agrees at the key poses.
+        let city_se3_ego = interpolate_city_se3_ego(&city_pose, 200).unwrap().unwrap();
+        assert_eq!(city_se3_ego.translation, array![4_000_010_f32, 2., 0.]);
+        assert_eq!(interpolate_city_se3_ego(&city_pose, 201).unwrap(), None);
+
+        let err = interpolate_city_se3_ego(&city_pose.drop("qw").unwrap(), 150).unwrap_err();
+        assert!(err.to_string().contains("missing column `qw`"));
+    }
+
+    #[test]
+    fn test_interpolate_cuboids() {
+        let half_turn = std::f32::consts::FRAC_1_SQRT_2;
+        let annotations = df!(
+            "timestamp_ns" => [0_u64, 100, 0, 200],
+            "track_uuid" => ["a", "a", "b", "b"],
+            "length_m" => [4_f32, 4., 1., 1.],
+            "tx_m" => [0_f32, 10., 0., 0.],
+            "ty_m" => [0_f32, 0., 0., 2.],
+            "tz_m" => [0_f32, 0., 0., 0.],
+            "qw" => [1_f32, half_turn, 1., 1.],
+            "qx" => [0_f32, 0., 0., 0.],
+            "qy" => [0_f32, 0., 0., 0.],
+            "qz" => [0_f32, half_turn, 0., 0.]
+        )
+        .unwrap();
+
+        let track = interpolate_track(&annotations, "a", &[50, 100, 150]).unwrap();
+        assert_eq!(
+            track["timestamp_ns"].u64().unwrap().to_vec(),
+            vec![Some(50), Some(100)]
+        );
+        assert_eq!(
+            track["tx_m"].f32().unwrap().to_vec(),
+            vec![Some(5.), Some(10.)]
+        );
+        // Halfway along a quarter turn about z.
+        let qz = track["qz"].f32().unwrap().get(0).unwrap();
+        assert!((qz - (std::f32::consts::PI / 8.).sin()).abs() < 1e-5);
+
+        let cuboids = interpolate_cuboids(&annotations, 150).unwrap();
+        assert_eq!(cuboids.schema(), annotations.schema());
+        assert_eq!(cuboids["track_uuid"].str().unwrap().get(0), Some("b"));
+        assert_eq!(cuboids["ty_m"].f32().unwrap().to_vec(), vec![Some(1.5)]);
+        assert_eq!(interpolate_cuboids(&annotations, 50).unwrap().height(), 2);
+    }
+}
@@ -0,0 +1,135 @@
+//! # iou
+//!
+//! Intersection-over-union between rotated cuboids.
+
+use ndarray::{par_azip, Array, ArrayView, Axis, Ix1, Ix2};
+use strum_macros::{Display, EnumString};
+
+use super::polytope::{clip_convex_polygons, cuboids_to_bev_footprints, polygon_area};
+
+/// Overlap computation mode for cuboid IoU.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq)]
+pub enum IouMode {
+    /// Overlap of the bird's-eye-view (xy-plane) footprints.
+    #[strum(serialize = "bev")]
+    Bev,
+    /// Volumetric overlap (footprint overlap times vertical overlap).
+    #[strum(serialize = "3d")]
+    ThreeD,
+}
+
+/// Compute the pairwise (N,M) IoU between (N,10) and (M,10) cuboids.
+/// Parallelized over the rows of `cuboids_a`.
+pub fn cuboid_iou(
+    cuboids_a: &ArrayView<f32, Ix2>,
+    cuboids_b: &ArrayView<f32, Ix2>,
+    mode: IouMode,
+) -> Array<f32, Ix2> {
+    let footprints_a = cuboids_to_bev_footprints(cuboids_a);
+    let footprints_b = cuboids_to_bev_footprints(cuboids_b);
+
+    let shape = (cuboids_a.shape()[0], cuboids_b.shape()[0]);
+    let mut iou = Array::<f32, Ix2>::zeros(shape);
+    par_azip!((mut row in iou.outer_iter_mut(), a in cuboids_a.outer_iter(), fa in footprints_a.outer_iter()) {
+        for ((iou_ab, b), fb) in row
+            .iter_mut()
+            .zip(cuboids_b.outer_iter())
+            .zip(footprints_b.outer_iter())
+        {
+            *iou_ab = _cuboid_iou(&a, &b, &fa, &fb, mode);
+        }
+    });
+    iou
+}
+
+/// Compute the IoU between two (10,) cuboids given their precomputed (4,2) footprints.
+pub fn _cuboid_iou(
+    cuboid_a: &ArrayView<f32, Ix1>,
+    cuboid_b: &ArrayView<f32, Ix1>,
+    footprint_a: &ArrayView<f32, Ix2>,
+    footprint_b: &ArrayView<f32, Ix2>,
+    mode: IouMode,
+) -> f32 {
+    // Early-out when the circumscribed circles of the footprints are disjoint.
+    let radius_a = 0.5 * cuboid_a[3].hypot(cuboid_a[4]);
+    let radius_b = 0.5 * cuboid_b[3].hypot(cuboid_b[4]);
+    let distance = (cuboid_a[0] - cuboid_b[0]).hypot(cuboid_a[1] - cuboid_b[1]);
+    if distance > radius_a + radius_b {
+        return 0.;
+    }
+
+    let intersection = clip_convex_polygons(footprint_a, footprint_b);
+    let intersection_area = match intersection.len_of(Axis(0)) {
+        0..=2 => 0.,
+        _ => polygon_area(&intersection.view()),
+    };
+
+    let area_a = cuboid_a[3] * cuboid_a[4];
+    let area_b = cuboid_b[3] * cuboid_b[4];
+    let (intersection, union) = match mode {
+        IouMode::Bev => (intersection_area, area_a + area_b - intersection_area),
+        IouMode::ThreeD => {
+            let (za_min, za_max) = (
+                cuboid_a[2] - cuboid_a[5] / 2.,
+                cuboid_a[2] + cuboid_a[5] / 2.,
+            );
+            let (zb_min, zb_max) = (
+                cuboid_b[2] - cuboid_b[5] / 2.,
+                cuboid_b[2] + cuboid_b[5] / 2.,
+            );
+            let height_overlap = f32::max(f32::min(za_max, zb_max) - f32::max(za_min, zb_min), 0.);
+            let intersection_volume = intersection_area * height_overlap;
+            let volume_a = area_a * cuboid_a[5];
+            let volume_b = area_b * cuboid_b[5];
+            (
+                intersection_volume,
+                volume_a + volume_b - intersection_volume,
+            )
+        }
+    };
+
+    if union <= 0. {
+        return 0.;
+    }
+    intersection / union
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Axis};
+
+    use super::{cuboid_iou, IouMode};
+
+    #[test]
+    fn test_cuboid_iou() {
+        let cuboids = array![
+            [0., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [1., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [10., 10., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [0., 0., 1., 2., 2., 2., 1., 0., 0., 0.],
+        ];
+        let epsilon = 1e-6;
+        let iou_bev = cuboid_iou(&cuboids.view(), &cuboids.view(), IouMode::Bev);
+        assert!((iou_bev[[0, 0]] - 1.).abs() < epsilon);
+        assert!((iou_bev[[0, 1]] - 1. / 3.).abs() < epsilon);
+        assert!(iou_bev[[0, 2]].abs() < epsilon);
+        assert!((iou_bev[[0, 3]] - 1.).abs() < epsilon);
+
+        let iou_3d = cuboid_iou(&cuboids.view(), &cuboids.view(), IouMode::ThreeD);
+        assert!((iou_3d[[0, 3]] - 1. / 3.).abs() < epsilon);
+
+        // A square rotated 45 degrees about +z intersects the original in a regular octagon.
+        let (s, c) = (
+            std::f32::consts::FRAC_PI_8.sin(),
+            std::f32::consts::FRAC_PI_8.cos(),
+        );
+        let rotated = array![[0., 0., 0., 2., 2., 2., c, 0., 0., s]];
+        let iou = cuboid_iou(
+            &cuboids.select(Axis(0), &[0]).view(),
+            &rotated.view(),
+            IouMode::Bev,
+        );
+        let intersection = 8. * (2_f32.sqrt() - 1.);
+        assert!((iou[[0, 0]] - intersection / (8. - intersection)).abs() < 1e-5);
+    }
+}
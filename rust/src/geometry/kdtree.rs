@@ -0,0 +1,327 @@
+//! # kdtree
+//!
+//! KD-tree spatial index for point queries.
+//!
+//! The tree is balanced and stored implicitly: the points are permuted so that the median of every
+//! subrange splits it along the axis of its depth.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use ndarray::{Array, ArrayView, Ix1, Ix2};
+use numpy::{IntoPyArray, PyArray, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::prelude::*;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+/// Subranges at or below this size are scanned linearly.
+const LEAF_SIZE: usize = 16;
+
+/// KD-tree over (N,D) points.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct KdTree {
+    /// (N,D) indexed points.
+    pub points: Array<f32, Ix2>,
+    /// Tree order of the point indices.
+    order: Vec<usize>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl KdTree {
+    /// Build the tree over (N,D) points.
+    #[new]
+    fn py_new(points: PyReadonlyArray2<f32>) -> Self {
+        KdTree::new(&points.as_array())
+    }
+
+    /// Query the `k` nearest neighbors of (M,D) points.
+    /// Returns (M,k) indices and euclidean distances sorted by distance.
+    #[pyo3(name = "query_knn")]
+    #[allow(clippy::type_complexity)]
+    fn py_query_knn<'py>(
+        &self,
+        py: Python<'py>,
+        queries: PyReadonlyArray2<f32>,
+        k: usize,
+    ) -> (&'py PyArray<usize, Ix2>, &'py PyArray<f32, Ix2>) {
+        let (indices, distances) = self.query_knn_batch(&queries.as_array(), k);
+        (indices.into_pyarray(py), distances.into_pyarray(py))
+    }
+
+    /// Query the indices of all points within `radius` of a (D,) point.
+    #[pyo3(name = "query_radius")]
+    fn py_query_radius<'py>(
+        &self,
+        py: Python<'py>,
+        query: PyReadonlyArray1<f32>,
+        radius: f32,
+    ) -> &'py PyArray<usize, Ix1> {
+        Array::from_vec(self.query_radius(&query.as_array(), radius)).into_pyarray(py)
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Rust methods.
+impl KdTree {
+    /// Build the tree over (N,D) points.
+    pub fn new(points: &ArrayView<f32, Ix2>) -> Self {
+        let mut tree = KdTree {
+            points: points.to_owned(),
+            order: (0..points.shape()[0]).collect(),
+        };
+        let num_points = tree.len();
+        tree.build(0, num_points, 0);
+        tree
+    }
+
+    /// Number of indexed points.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns `true` if the tree has no points.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Indices of all points within `radius` of the (D,) `query` (in no particular order).
+    pub fn query_radius(&self, query: &ArrayView<f32, Ix1>, radius: f32) -> Vec<usize> {
+        let mut neighbors = vec![];
+        self.search_radius(0, self.len(), 0, query, radius * radius, &mut neighbors);
+        neighbors
+    }
+
+    /// The `k` nearest neighbors of the (D,) `query` as `(index, distance)` sorted by distance.
+    pub fn query_knn(&self, query: &ArrayView<f32, Ix1>, k: usize) -> Vec<(usize, f32)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search_knn(0, self.len(), 0, query, k, &mut heap);
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|neighbor| (neighbor.index, neighbor.squared_distance.sqrt()))
+            .collect()
+    }
+
+    /// Query the `k` nearest neighbors of (M,D) points in parallel.
+    /// Returns (M,k') indices and distances where `k' = min(k, N)`.
+    pub fn query_knn_batch(
+        &self,
+        queries: &ArrayView<f32, Ix2>,
+        k: usize,
+    ) -> (Array<usize, Ix2>, Array<f32, Ix2>) {
+        let num_queries = queries.shape()[0];
+        let k = k.min(self.len());
+        let neighbors = (0..num_queries)
+            .into_par_iter()
+            .map(|i| self.query_knn(&queries.row(i), k))
+            .collect::<Vec<_>>();
+
+        let mut indices = Array::<usize, Ix2>::zeros((num_queries, k));
+        let mut distances = Array::<f32, Ix2>::zeros((num_queries, k));
+        for (i, neighbors_i) in neighbors.into_iter().enumerate() {
+            for (j, (index, distance)) in neighbors_i.into_iter().enumerate() {
+                indices[[i, j]] = index;
+                distances[[i, j]] = distance;
+            }
+        }
+        (indices, distances)
+    }
+
+    /// Query the points within `radius` of (M,D) points in parallel.
+    pub fn query_radius_batch(
+        &self,
+        queries: &ArrayView<f32, Ix2>,
+        radius: f32,
+    ) -> Vec<Vec<usize>> {
+        (0..queries.shape()[0])
+            .into_par_iter()
+            .map(|i| self.query_radius(&queries.row(i), radius))
+            .collect()
+    }
+
+    fn num_dims(&self) -> usize {
+        self.points.shape()[1]
+    }
+
+    fn coordinate(&self, position: usize, axis: usize) -> f32 {
+        self.points[[self.order[position], axis]]
+    }
+
+    fn squared_distance(&self, position: usize, query: &ArrayView<f32, Ix1>) -> f32 {
+        let point = self.points.row(self.order[position]);
+        point
+            .iter()
+            .zip(query.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum()
+    }
+
+    fn build(&mut self, start: usize, end: usize, depth: usize) {
+        if end - start <= LEAF_SIZE {
+            return;
+        }
+        let axis = depth % self.num_dims();
+        let mid = (start + end) / 2;
+        let points = &self.points;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            points[[a, axis]].total_cmp(&points[[b, axis]])
+        });
+        self.build(start, mid, depth + 1);
+        self.build(mid + 1, end, depth + 1);
+    }
+
+    fn search_radius(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        query: &ArrayView<f32, Ix1>,
+        squared_radius: f32,
+        neighbors: &mut Vec<usize>,
+    ) {
+        if end - start <= LEAF_SIZE {
+            for position in start..end {
+                if self.squared_distance(position, query) <= squared_radius {
+                    neighbors.push(self.order[position]);
+                }
+            }
+            return;
+        }
+        let axis = depth % self.num_dims();
+        let mid = (start + end) / 2;
+        if self.squared_distance(mid, query) <= squared_radius {
+            neighbors.push(self.order[mid]);
+        }
+        let delta = query[axis] - self.coordinate(mid, axis);
+        if delta <= 0. || delta * delta <= squared_radius {
+            self.search_radius(start, mid, depth + 1, query, squared_radius, neighbors);
+        }
+        if delta >= 0. || delta * delta <= squared_radius {
+            self.search_radius(mid + 1, end, depth + 1, query, squared_radius, neighbors);
+        }
+    }
+
+    fn search_knn(
+        &self,
+        start: usize,
+        end: usize,
+        depth: usize,
+        query: &ArrayView<f32, Ix1>,
+        k: usize,
+        heap: &mut BinaryHeap<Neighbor>,
+    ) {
+        let visit = |position: usize, heap: &mut BinaryHeap<Neighbor>| {
+            let squared_distance = self.squared_distance(position, query);
+            if heap.len() < k {
+                heap.push(Neighbor::new(self.order[position], squared_distance));
+            } else if squared_distance < heap.peek().unwrap().squared_distance {
+                heap.pop();
+                heap.push(Neighbor::new(self.order[position], squared_distance));
+            }
+        };
+        if end - start <= LEAF_SIZE {
+            (start..end).for_each(|position| visit(position, heap));
+            return;
+        }
+        let axis = depth % self.num_dims();
+        let mid = (start + end) / 2;
+        visit(mid, heap);
+
+        let delta = query[axis] - self.coordinate(mid, axis);
+        let (near, far) = match delta <= 0. {
+            true => ((start, mid), (mid + 1, end)),
+            false => ((mid + 1, end), (start, mid)),
+        };
+        self.search_knn(near.0, near.1, depth + 1, query, k, heap);
+        if heap.len() < k || delta * delta < heap.peek().unwrap().squared_distance {
+            self.search_knn(far.0, far.1, depth + 1, query, k, heap);
+        }
+    }
+}
+
+/// Max-heap entry ordered by distance.
+#[derive(Clone, Copy, Debug)]
+struct Neighbor {
+    index: usize,
+    squared_distance: f32,
+}
+
+impl Neighbor {
+    fn new(index: usize, squared_distance: f32) -> Self {
+        Neighbor {
+            index,
+            squared_distance,
+        }
+    }
+}
+
+impl PartialEq for Neighbor {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.squared_distance
+            .total_cmp(&other.squared_distance)
+            .then(self.index.cmp(&other.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array, Ix2};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::KdTree;
+
+    #[test]
+    fn test_kdtree_queries() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let points = Array::<f32, Ix2>::from_shape_fn((500, 3), |_| rng.gen_range(-10.0..10.0));
+        let tree = KdTree::new(&points.view());
+
+        for i in 0..20 {
+            let query = points.row(i * 7);
+            let mut distances = points
+                .outer_iter()
+                .map(|p| (&p - &query).mapv(|x| x * x).sum().sqrt())
+                .enumerate()
+                .collect::<Vec<_>>();
+            distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            let knn = tree.query_knn(&query, 5);
+            assert_eq!(
+                knn.iter().map(|x| x.0).collect::<Vec<_>>(),
+                distances[..5].iter().map(|x| x.0).collect::<Vec<_>>()
+            );
+
+            let mut within = tree.query_radius(&query, 3.);
+            within.sort();
+            let mut expected = distances
+                .iter()
+                .filter(|x| x.1 <= 3.)
+                .map(|x| x.0)
+                .collect::<Vec<_>>();
+            expected.sort();
+            assert_eq!(within, expected);
+        }
+    }
+}
@@ -2,15 +2,70 @@
 //!
 //! Geometric operations for data processing.
 
+/// Multi-sweep lidar aggregation.
+#[cfg(feature = "dataframe")]
+pub mod aggregation;
 /// Geometric augmentations.
+#[cfg(feature = "dataframe")]
 pub mod augmentations;
+/// Bounding volume hierarchy over cuboids.
+pub mod bvh;
 /// Camera models.
+#[cfg(feature = "dataframe")]
 pub mod camera;
+/// Euclidean clustering of lidar points.
+#[cfg(feature = "dataframe")]
+pub mod clustering;
+/// Transforms among the city, egovehicle, and sensor frames.
+#[cfg(feature = "dataframe")]
+pub mod coordinate_frames;
+/// Data-frame adapters of the geometry routines.
+#[cfg(feature = "dataframe")]
+pub mod dataframe;
+/// Derived per-point columns computed on load.
+#[cfg(feature = "dataframe")]
+pub mod derived_columns;
+/// Ground-plane estimation and ground removal.
+#[cfg(feature = "dataframe")]
+pub mod ground;
+/// Pose interpolation.
+#[cfg(feature = "dataframe")]
+pub mod interpolate;
+/// Intersection-over-union between cuboids.
+pub mod iou;
+/// KD-tree spatial index for point queries.
+pub mod kdtree;
+/// Ego-motion compensation of lidar sweeps.
+#[cfg(feature = "dataframe")]
+pub mod motion_compensation;
+/// Rotated non-maximum suppression.
+pub mod nms;
+/// Ego-relative polar occupancy features.
+#[cfg(feature = "dataframe")]
+pub mod polar_occupancy;
+/// Arc-length parametrization and resampling of polylines.
+pub mod polyline;
+/// Spatial index over polyline segments for nearest-polyline queries.
+pub mod polyline_index;
 /// Geometric algorithms for polytopes.
 pub mod polytope;
+/// Range-image projection of lidar sweeps.
+#[cfg(feature = "dataframe")]
+pub mod range_image;
+/// Bird's-eye-view rasterization.
+#[cfg(feature = "dataframe")]
+pub mod raster;
+/// Region-of-interest filtering of sweeps and cuboids.
+#[cfg(feature = "dataframe")]
+pub mod roi;
+/// Floating-point scalars of the geometry routines.
+pub mod scalar;
 /// Special Euclidean Group 3.
 pub mod se3;
 /// Special Orthogonal Group 3.
 pub mod so3;
 /// Geometric utility functions.
 pub mod utils;
+/// Voxelization of lidar sweeps.
+#[cfg(feature = "dataframe")]
+pub mod voxelize;
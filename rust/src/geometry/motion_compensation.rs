@@ -0,0 +1,197 @@
+//! # motion_compensation
+//!
+//! Ego-motion compensation of lidar sweeps.
+//!
+//! The lidar rotates while the egovehicle moves, so each point is captured in a slightly different
+//! egovehicle frame. Each return's capture time is the sweep timestamp plus its `offset_ns`.
+
+use std::collections::HashMap;
+
+use ndarray::{par_azip, Array, ArrayView, Ix1, Ix2};
+use polars::{
+    lazy::dsl::{col, cols},
+    prelude::{DataFrame, DataType, IntoLazy, PolarsResult, SortOptions},
+};
+use tracing::info_span;
+
+use crate::{
+    constants::POSE_COLUMNS,
+    error::{Av2Error, Av2Result},
+    io::ndarray_from_frame,
+    schema::{validate_data_frame, ColumnType, LIDAR_SCHEMA, POSE_SCHEMA},
+    share::{data_frame_to_ndarray_f32, ndarray_to_expr_vec},
+};
+
+use super::{interpolate::interpolate_poses, se3::SE3};
+
+/// Transform a sweep from the egovehicle frame it was captured in to a destination frame.
+///
+/// Both poses are given in the city frame. Pass `SE3::identity()` as `city_se3_dst` to express
+/// the sweep in the city frame.
+pub fn transform_sweep(
+    lidar: &DataFrame,
+    city_se3_ego_src: &SE3,
+    city_se3_dst: &SE3,
+) -> Av2Result<DataFrame> {
+    let dst_se3_ego_src = city_se3_dst.inverse().compose(city_se3_ego_src);
+    let column_names = vec!["x", "y", "z"];
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), column_names.clone())?;
+    let xyz_dst = dst_se3_ego_src.transform_from(&xyz.view());
+    let series_vec = ndarray_to_expr_vec(xyz_dst, column_names)?;
+    Ok(lidar.clone().lazy().with_columns(series_vec).collect()?)
+}
+
+/// Motion-compensate a sweep captured at `sweep_timestamp_ns` into a destination frame.
+///
+/// `city_pose` contains the log's egovehicle poses (`timestamp_ns` and the `POSE_COLUMNS`).
+/// Each point is transformed with the egovehicle pose interpolated at its capture time
+/// (`sweep_timestamp_ns + offset_ns`), then expressed in `city_se3_dst`.
+pub fn compensate_ego_motion(
+    lidar: &DataFrame,
+    sweep_timestamp_ns: u64,
+    city_pose: &DataFrame,
+    city_se3_dst: &SE3,
+) -> Av2Result<DataFrame> {
+    let _span = info_span!("compensate_ego_motion", num_points = lidar.height()).entered();
+    validate_data_frame("poses", city_pose, &POSE_SCHEMA)?;
+    // Per-point motion compensation requires the capture time of each return.
+    let lidar_schema = [&LIDAR_SCHEMA[..], &[("offset_ns", ColumnType::Integer)]].concat();
+    validate_data_frame("lidar", lidar, &lidar_schema)?;
+    let city_pose = city_pose
+        .clone()
+        .lazy()
+        .sort("timestamp_ns", SortOptions::default())
+        .collect()
+        .map_err(|err| Av2Error::schema("poses", err))?;
+    let key_timestamps_ns = _column_to_i64(&city_pose, "timestamp_ns")
+        .map_err(|err| Av2Error::schema("poses", err))?
+        .into_iter()
+        .map(|x| x as u64)
+        .collect::<Vec<_>>();
+    let key_poses = ndarray_from_frame(&city_pose, cols(POSE_COLUMNS))?;
+    let offsets_ns =
+        _column_to_i64(lidar, "offset_ns").map_err(|err| Av2Error::schema("lidar", err))?;
+
+    // Many returns share a firing time, so only interpolate the unique capture times.
+    let mut unique_index: HashMap<u64, usize> = HashMap::new();
+    let mut unique_timestamps_ns = vec![];
+    let point_to_unique = offsets_ns
+        .iter()
+        .map(|offset_ns| {
+            let timestamp_ns = (sweep_timestamp_ns as i64 + offset_ns) as u64;
+            *unique_index.entry(timestamp_ns).or_insert_with(|| {
+                unique_timestamps_ns.push(timestamp_ns);
+                unique_timestamps_ns.len() - 1
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let city_poses_ego = interpolate_poses(
+        &Array::from_vec(key_timestamps_ns).view(),
+        &key_poses.view(),
+        &Array::from_vec(unique_timestamps_ns).view(),
+    )?;
+    let dst_se3_city = city_se3_dst.inverse();
+    let dst_se3_ego = (0..city_poses_ego.shape()[0])
+        .map(|i| Ok(dst_se3_city.compose(&SE3::from_pose(&city_poses_ego.row(i))?)))
+        .collect::<Av2Result<Vec<_>>>()?;
+
+    let column_names = vec!["x", "y", "z"];
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), column_names.clone())?;
+    let xyz_dst = _transform_per_point(
+        &xyz.view(),
+        &dst_se3_ego,
+        &Array::from_vec(point_to_unique).view(),
+    );
+    let series_vec = ndarray_to_expr_vec(xyz_dst, column_names)?;
+    Ok(lidar.clone().lazy().with_columns(series_vec).collect()?)
+}
+
+/// Transform each (N,3) point with the SE(3) selected by its (N,) `transform_indices`.
+fn _transform_per_point(
+    xyz: &ArrayView<f32, Ix2>,
+    transforms: &[SE3],
+    transform_indices: &ArrayView<usize, Ix1>,
+) -> Array<f32, Ix2> {
+    let rotations = transforms.iter().map(|x| x.rotation()).collect::<Vec<_>>();
+    let mut xyz_dst = Array::<f32, Ix2>::zeros(xyz.raw_dim());
+    par_azip!((mut p_dst in xyz_dst.outer_iter_mut(), p in xyz.outer_iter(), &i in transform_indices) {
+        p_dst.assign(&(rotations[i].dot(&p) + &transforms[i].translation));
+    });
+    xyz_dst
+}
+
+fn _column_to_i64(data_frame: &DataFrame, column: &str) -> PolarsResult<Vec<i64>> {
+    Ok(data_frame
+        .clone()
+        .lazy()
+        .select([col(column).cast(DataType::Int64)])
+        .collect()?[column]
+        .i64()?
+        .into_no_null_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{df, NamedFrom};
+
+    use super::compensate_ego_motion;
+    use crate::geometry::se3::SE3;
+
+    #[test]
+    fn test_compensate_ego_motion() {
+        // The egovehicle moves 1 m along x between the two poses, 100 ms apart.
+        let city_pose = df!(
+            "timestamp_ns" => [1_000_000_000_u64, 1_100_000_000],
+            "tx_m" => [10_f64, 11.],
+            "ty_m" => [2_f64, 2.],
+            "tz_m" => [0_f64, 0.],
+            "qw" => [1_f64, 1.],
+            "qx" => [0_f64, 0.],
+            "qy" => [0_f64, 0.],
+            "qz" => [0_f64, 0.]
+        )
+        .unwrap();
+        let offsets_ns = [0_i32, 25_000_000, 50_000_000, 100_000_000];
+        let lidar = df!(
+            "x" => [1_f32, 1., 1., 0.],
+            "y" => [0_f32, 0., 0., -1.],
+            "z" => [0.5_f32, 0.5, 0.5, 0.],
+            "offset_ns" => offsets_ns
+        )
+        .unwrap();
+
+        // Points are displaced by the ego motion up to their capture time.
+        let compensated =
+            compensate_ego_motion(&lidar, 1_000_000_000, &city_pose, &SE3::identity()).unwrap();
+        let column = |name: &str| {
+            compensated[name]
+                .f32()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>()
+        };
+        let expected_x = [11., 11.25, 11.5, 11.];
+        let expected_y = [2., 2., 2., 1.];
+        for (x, expected) in column("x").iter().zip(expected_x) {
+            assert!((x - expected).abs() < 1e-5);
+        }
+        for (y, expected) in column("y").iter().zip(expected_y) {
+            assert!((y - expected).abs() < 1e-5);
+        }
+        assert_eq!(column("z"), [0.5, 0.5, 0.5, 0.]);
+
+        // Expressed in the egovehicle frame at the sweep timestamp, only the motion remains.
+        let city_se3_ego = SE3 {
+            translation: ndarray::array![10., 2., 0.],
+            ..SE3::identity()
+        };
+        let compensated =
+            compensate_ego_motion(&lidar, 1_000_000_000, &city_pose, &city_se3_ego).unwrap();
+        let x = compensated["x"].f32().unwrap();
+        for (x, expected) in x.into_no_null_iter().zip([1., 1.25, 1.5, 1.]) {
+            assert!((x - expected).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! # nms
+//!
+//! Rotated non-maximum suppression over cuboids.
+
+use ndarray::{Array, ArrayView, Axis, Ix1, Ix2};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use super::{
+    iou::{_cuboid_iou, IouMode},
+    polytope::cuboids_to_bev_footprints,
+};
+use crate::error::{Av2Error, Av2Result};
+
+/// Greedy rotated non-maximum suppression.
+///
+/// Given (N,10) `cuboids` and (N,) `scores`, iteratively keep the highest scoring cuboid
+/// and suppress all remaining cuboids whose IoU with it exceeds `iou_threshold`.
+/// Returns the kept indices ordered by descending score, or an error if the cuboids are not
+/// (N,10) or the number of cuboids and scores differ.
+pub fn non_maximum_suppression(
+    cuboids: &ArrayView<f32, Ix2>,
+    scores: &ArrayView<f32, Ix1>,
+    iou_threshold: f32,
+    mode: IouMode,
+) -> Av2Result<Array<usize, Ix1>> {
+    if cuboids.ncols() != 10 {
+        return Err(Av2Error::invalid_parameter(
+            "cuboids",
+            format!("Expected (N,10) cuboids, got {:?}.", cuboids.shape()),
+        ));
+    }
+    let num_cuboids = cuboids.nrows();
+    if scores.len() != num_cuboids {
+        return Err(Av2Error::invalid_parameter(
+            "scores",
+            format!("Expected {num_cuboids} scores, got {}.", scores.len()),
+        ));
+    }
+
+    let mut order = (0..num_cuboids).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| scores[j].total_cmp(&scores[i]));
+
+    let footprints = cuboids_to_bev_footprints(cuboids);
+    let mut is_suppressed = vec![false; num_cuboids];
+    let mut keep = vec![];
+    for (k, &i) in order.iter().enumerate() {
+        if is_suppressed[i] {
+            continue;
+        }
+        keep.push(i);
+
+        let candidates = order[k + 1..]
+            .iter()
+            .copied()
+            .filter(|&j| !is_suppressed[j])
+            .collect::<Vec<_>>();
+        let suppressed = candidates
+            .par_iter()
+            .copied()
+            .filter(|&j| {
+                let iou = _cuboid_iou(
+                    &cuboids.row(i),
+                    &cuboids.row(j),
+                    &footprints.index_axis(Axis(0), i),
+                    &footprints.index_axis(Axis(0), j),
+                    mode,
+                );
+                iou > iou_threshold
+            })
+            .collect::<Vec<_>>();
+        for j in suppressed {
+            is_suppressed[j] = true;
+        }
+    }
+    Ok(Array::<usize, Ix1>::from_vec(keep))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s};
+
+    use super::non_maximum_suppression;
+    use crate::error::Av2Error;
+    use crate::geometry::iou::IouMode;
+
+    #[test]
+    fn test_non_maximum_suppression() {
+        let cuboids = array![
+            [0., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [0.1, 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [10., 10., 0., 2., 2., 2., 1., 0., 0., 0.],
+        ];
+        let scores = array![0.5, 0.9, 0.1];
+        let keep =
+            non_maximum_suppression(&cuboids.view(), &scores.view(), 0.5, IouMode::Bev).unwrap();
+        assert_eq!(keep.to_vec(), vec![1, 2]);
+
+        let err =
+            non_maximum_suppression(&cuboids.view(), &scores.slice(s![..2]), 0.5, IouMode::Bev)
+                .unwrap_err();
+        assert!(matches!(err, Av2Error::InvalidParameter { name, .. } if name == "scores"));
+        let err = non_maximum_suppression(
+            &cuboids.slice(s![.., ..7]),
+            &scores.view(),
+            0.5,
+            IouMode::Bev,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Av2Error::InvalidParameter { name, .. } if name == "cuboids"));
+    }
+}
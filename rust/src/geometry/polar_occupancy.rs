@@ -0,0 +1,155 @@
+//! # polar_occupancy
+//!
+//! Ego-relative polar occupancy features of lidar sweeps.
+//!
+//! Obstacle points (within a height band of the egovehicle frame) are binned by azimuth and range.
+//! Azimuth bins start at `-pi` (behind the egovehicle) and sweep counter-clockwise (when viewed
+//! from above), and range bins are uniform up to the maximum range.
+
+use std::f32::consts::PI;
+
+use ndarray::{concatenate, Array, ArrayView, Axis, Ix1, Ix2};
+use polars::prelude::DataFrame;
+
+use crate::{error::Av2Result, share::data_frame_to_ndarray_f32};
+
+/// Polar occupancy configuration.
+#[derive(Clone, Debug)]
+pub struct PolarOccupancyConfig {
+    /// Number of azimuth bins.
+    pub num_azimuth_bins: usize,
+    /// Number of range bins.
+    pub num_range_bins: usize,
+    /// Maximum range of an obstacle (in meters).
+    pub max_range_m: f32,
+    /// Minimum height of an obstacle point in the egovehicle frame (in meters).
+    pub min_height_m: f32,
+    /// Maximum height of an obstacle point in the egovehicle frame (in meters).
+    pub max_height_m: f32,
+}
+
+impl Default for PolarOccupancyConfig {
+    fn default() -> Self {
+        Self {
+            num_azimuth_bins: 360,
+            num_range_bins: 50,
+            max_range_m: 50.,
+            min_height_m: 0.3,
+            max_height_m: 2.5,
+        }
+    }
+}
+
+/// Polar occupancy features of a sweep.
+#[derive(Clone, Debug)]
+pub struct PolarOccupancy {
+    /// (A,R) number of obstacle points in each azimuth and range bin.
+    pub counts: Array<u32, Ix2>,
+    /// (A,) bird's-eye-view distance to the nearest obstacle point in each azimuth bin
+    /// (`max_range_m` if there is none).
+    pub nearest_obstacle_m: Array<f32, Ix1>,
+    /// Maximum range of an obstacle (in meters).
+    pub max_range_m: f32,
+}
+
+impl PolarOccupancy {
+    /// (A,R) binary occupancy.
+    pub fn occupancy(&self) -> Array<bool, Ix2> {
+        self.counts.mapv(|x| x > 0)
+    }
+
+    /// Flatten into a (A * R + A,) feature vector: the binary occupancy followed by the nearest
+    /// obstacle distances normalized to `[0, 1]`.
+    pub fn to_feature_vector(&self) -> Array<f32, Ix1> {
+        let occupancy = self.counts.iter().map(|x| (*x > 0) as u8 as f32);
+        let nearest_obstacle = self.nearest_obstacle_m.mapv(|x| x / self.max_range_m);
+        concatenate![Axis(0), Array::from_iter(occupancy), nearest_obstacle]
+    }
+}
+
+/// Compute the polar occupancy of a sweep with `x`, `y`, and `z` columns in the egovehicle frame.
+pub fn sweep_to_polar_occupancy(
+    lidar: &DataFrame,
+    cfg: &PolarOccupancyConfig,
+) -> Av2Result<PolarOccupancy> {
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    Ok(compute_polar_occupancy(&xyz.view(), cfg))
+}
+
+/// Compute the polar occupancy of (N,3) points in the egovehicle frame.
+pub fn compute_polar_occupancy(
+    points_ego: &ArrayView<f32, Ix2>,
+    cfg: &PolarOccupancyConfig,
+) -> PolarOccupancy {
+    let (num_azimuth_bins, num_range_bins) = (cfg.num_azimuth_bins, cfg.num_range_bins);
+    let mut counts = Array::<u32, Ix2>::zeros((num_azimuth_bins, num_range_bins));
+    let mut nearest_obstacle_m = Array::<f32, Ix1>::from_elem(num_azimuth_bins, cfg.max_range_m);
+    for point in points_ego.outer_iter() {
+        let (x, y, z) = (point[0], point[1], point[2]);
+        if !(cfg.min_height_m..=cfg.max_height_m).contains(&z) {
+            continue;
+        }
+        let range_m = x.hypot(y);
+        if range_m >= cfg.max_range_m {
+            continue;
+        }
+        let azimuth = y.atan2(x);
+        let azimuth_bin = (((azimuth + PI) / (2. * PI)) * num_azimuth_bins as f32) as usize;
+        let azimuth_bin = azimuth_bin.min(num_azimuth_bins - 1);
+        let range_bin = ((range_m / cfg.max_range_m) * num_range_bins as f32) as usize;
+
+        counts[[azimuth_bin, range_bin.min(num_range_bins - 1)]] += 1;
+        let nearest = &mut nearest_obstacle_m[azimuth_bin];
+        *nearest = nearest.min(range_m);
+    }
+    PolarOccupancy {
+        counts,
+        nearest_obstacle_m,
+        max_range_m: cfg.max_range_m,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::{compute_polar_occupancy, PolarOccupancyConfig};
+
+    #[test]
+    fn test_compute_polar_occupancy() {
+        let cfg = PolarOccupancyConfig {
+            num_azimuth_bins: 4,
+            num_range_bins: 5,
+            max_range_m: 10.,
+            ..Default::default()
+        };
+        let points = array![
+            // Ahead at 3m and 7m.
+            [3., 0.1, 1.],
+            [7., 0.1, 1.],
+            // Ground and overhanging points are ignored.
+            [1., 0.1, 0.],
+            [1., 0.1, 3.],
+            // Left at 5m.
+            [-0.1, 5., 1.],
+            // Beyond the maximum range.
+            [-20., -0.1, 1.]
+        ];
+        let occupancy = compute_polar_occupancy(&points.view(), &cfg);
+        assert_eq!(
+            occupancy.counts,
+            array![
+                [0, 0, 0, 0, 0],
+                [0, 0, 0, 0, 0],
+                [0, 1, 0, 1, 0],
+                [0, 0, 1, 0, 0]
+            ]
+        );
+        assert!((occupancy.nearest_obstacle_m[2] - 3.0017).abs() < 1e-3);
+        assert_eq!(occupancy.nearest_obstacle_m[0], 10.);
+
+        let features = occupancy.to_feature_vector();
+        assert_eq!(features.len(), 4 * 5 + 4);
+        assert_eq!(features[4 * 5], 1.);
+    }
+}
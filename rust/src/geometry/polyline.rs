@@ -0,0 +1,197 @@
+//! # polyline
+//!
+//! Arc-length parametrization and resampling of polylines.
+//!
+//! Polylines are (N,D) arrays of vertices (typically `D = 2` or `D = 3`). Arc lengths are measured
+//! over every coordinate, while headings only use the first two.
+
+use ndarray::{par_azip, s, Array, ArrayView, Axis, Ix1, Ix2, Ix3, Zip};
+
+use super::scalar::GeometryFloat;
+
+/// Compute the (N,) cumulative arc length at each vertex of a (N,D) polyline, starting at zero.
+pub fn arc_length<T: GeometryFloat>(polyline: &ArrayView<T, Ix2>) -> Array<T, Ix1> {
+    let mut total = T::zero();
+    let segment_lengths = polyline
+        .axis_windows(Axis(0), 2)
+        .into_iter()
+        .map(|segment| _distance(&segment.row(0), &segment.row(1)));
+    std::iter::once(T::zero())
+        .chain(segment_lengths.map(|length| {
+            total += length;
+            total
+        }))
+        .take(polyline.nrows())
+        .collect()
+}
+
+/// Total length of a (N,D) polyline.
+pub fn polyline_length<T: GeometryFloat>(polyline: &ArrayView<T, Ix2>) -> T {
+    arc_length(polyline).last().copied().unwrap_or(T::zero())
+}
+
+/// Interpolate a (N,D) polyline at (M,) arc lengths, which are clamped to the polyline's length.
+///
+/// Panics if the polyline is empty.
+pub fn interpolate_at_arc_length<T: GeometryFloat>(
+    polyline: &ArrayView<T, Ix2>,
+    arc_lengths: &ArrayView<T, Ix1>,
+) -> Array<T, Ix2> {
+    assert!(
+        polyline.nrows() > 0,
+        "Cannot interpolate an empty polyline."
+    );
+    let cumulative = arc_length(polyline);
+    let cumulative = cumulative.as_slice().unwrap();
+    let last = polyline.nrows() - 1;
+
+    let mut points = Array::<T, Ix2>::zeros((arc_lengths.len(), polyline.ncols()));
+    par_azip!((mut point in points.outer_iter_mut(), &s in arc_lengths) {
+        if last == 0 {
+            point.assign(&polyline.row(0));
+            return;
+        }
+        // Index of the segment `[i, i + 1]` containing `s`.
+        let i = cumulative.partition_point(|x| *x <= s).saturating_sub(1).min(last - 1);
+        let segment_length = cumulative[i + 1] - cumulative[i];
+        let t = match segment_length > T::zero() {
+            true => num_traits::clamp((s - cumulative[i]) / segment_length, T::zero(), T::one()),
+            false => T::zero(),
+        };
+        Zip::from(&mut point)
+            .and(&polyline.row(i))
+            .and(&polyline.row(i + 1))
+            .for_each(|p, &a, &b| *p = a + t * (b - a));
+    });
+    points
+}
+
+/// Resample a (N,D) polyline to (K,D) vertices evenly spaced by arc length, including both
+/// endpoints.
+///
+/// Panics if the polyline is empty.
+pub fn resample_polyline<T: GeometryFloat>(
+    polyline: &ArrayView<T, Ix2>,
+    num_points: usize,
+) -> Array<T, Ix2> {
+    let length = polyline_length(polyline);
+    let arc_lengths = match num_points {
+        1 => Array::zeros(1),
+        _ => Array::linspace(T::zero(), length, num_points),
+    };
+    interpolate_at_arc_length(polyline, &arc_lengths.view())
+}
+
+/// Resample a batch of polylines (each (N_i,D)) to (P,K,D) vertices evenly spaced by arc length.
+///
+/// Panics if any polyline is empty or if their dimensions differ.
+pub fn resample_polylines<T: GeometryFloat>(
+    polylines: &[ArrayView<T, Ix2>],
+    num_points: usize,
+) -> Array<T, Ix3> {
+    let num_dims = polylines.first().map(|x| x.ncols()).unwrap_or(0);
+    let mut resampled = Array::<T, Ix3>::zeros((polylines.len(), num_points, num_dims));
+    par_azip!((mut r in resampled.outer_iter_mut(), polyline in polylines) {
+        r.assign(&resample_polyline(polyline, num_points));
+    });
+    resampled
+}
+
+/// Compute the (N,D) unit tangents of a (N,D) polyline.
+///
+/// Interior vertices use central differences and the endpoints use one-sided differences.
+/// Tangents of degenerate (zero-length) neighborhoods are zero.
+pub fn compute_tangents<T: GeometryFloat>(polyline: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    let num_points = polyline.nrows();
+    let mut tangents = Array::<T, Ix2>::zeros(polyline.raw_dim());
+    if num_points < 2 {
+        return tangents;
+    }
+    for (i, mut tangent) in tangents.outer_iter_mut().enumerate() {
+        let (prev, next) = (i.saturating_sub(1), (i + 1).min(num_points - 1));
+        tangent.assign(&(&polyline.row(next) - &polyline.row(prev)));
+        let norm = tangent.dot(&tangent).sqrt();
+        if norm > T::zero() {
+            tangent /= norm;
+        }
+    }
+    tangents
+}
+
+/// Compute the (N,) heading (in radians, counter-clockwise from the x-axis) of a (N,2+) polyline
+/// at each vertex.
+pub fn compute_headings<T: GeometryFloat>(polyline: &ArrayView<T, Ix2>) -> Array<T, Ix1> {
+    let tangents = compute_tangents(&polyline.slice(s![.., ..2]));
+    tangents
+        .outer_iter()
+        .map(|tangent| tangent[1].atan2(tangent[0]))
+        .collect()
+}
+
+/// Compute the (K,D) centerline of a lane from its (N,D) left and (M,D) right boundaries.
+/// Both boundaries are resampled to `num_points` vertices and averaged.
+pub fn compute_centerline<T: GeometryFloat>(
+    left_boundary: &ArrayView<T, Ix2>,
+    right_boundary: &ArrayView<T, Ix2>,
+    num_points: usize,
+) -> Array<T, Ix2> {
+    let left = resample_polyline(left_boundary, num_points);
+    let right = resample_polyline(right_boundary, num_points);
+    (left + right) / T::lit(2.)
+}
+
+fn _distance<T: GeometryFloat>(a: &ArrayView<T, Ix1>, b: &ArrayView<T, Ix1>) -> T {
+    a.iter()
+        .zip(b)
+        .map(|(&a, &b)| (a - b) * (a - b))
+        .sum::<T>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use ndarray::{array, s};
+
+    use super::{
+        arc_length, compute_centerline, compute_headings, polyline_length, resample_polyline,
+        resample_polylines,
+    };
+
+    #[test]
+    fn test_resample_polyline() {
+        // An L-shaped polyline with a repeated vertex.
+        let polyline = array![[0_f32, 0.], [3., 0.], [3., 0.], [3., 1.]];
+        assert_eq!(arc_length(&polyline.view()).to_vec(), vec![0., 3., 3., 4.]);
+        assert_eq!(polyline_length(&polyline.view()), 4.);
+
+        let resampled = resample_polyline(&polyline.view(), 5);
+        let expected = array![[0_f32, 0.], [1., 0.], [2., 0.], [3., 0.], [3., 1.]];
+        assert!(resampled
+            .iter()
+            .zip(expected.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-6));
+
+        let headings = compute_headings(&resampled.view());
+        assert!(headings[0].abs() < 1e-6);
+        assert!((headings[4] - FRAC_PI_2).abs() < 1e-6);
+
+        let batch = resample_polylines(&[polyline.view(), array![[1_f32, 1.]].view()], 3);
+        assert_eq!(batch.shape(), &[2, 3, 2]);
+        // A single vertex is repeated.
+        assert!(batch.slice(s![1, .., ..]).iter().all(|x| *x == 1.));
+    }
+
+    #[test]
+    fn test_compute_centerline() {
+        let left = array![[0_f32, 2., 0.], [10., 2., 1.]];
+        let right = array![[0_f32, 0., 0.], [5., 0., 0.5], [10., 0., 1.]];
+        let centerline = compute_centerline(&left.view(), &right.view(), 3);
+        let expected = array![[0_f32, 1., 0.], [5., 1., 0.5], [10., 1., 1.]];
+        assert!(centerline
+            .iter()
+            .zip(expected.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-5));
+    }
+}
@@ -0,0 +1,329 @@
+//! # polyline_index
+//!
+//! Bird's-eye-view spatial index over polyline segments for nearest-polyline queries.
+//!
+//! Segments are stored in a bounding volume hierarchy whose nodes hold the axis-aligned bounding
+//! box of their segments and are split at the median midpoint along the longest axis. Nearest
+//! queries descend the nearer child first and prune nodes farther than the best hit so far.
+
+use ndarray::{Array, ArrayView, Ix1, Ix2};
+use numpy::{IntoPyArray, PyArray, PyReadonlyArray2};
+use pyo3::prelude::*;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+/// Nodes with at most this many segments are leaves.
+const LEAF_SIZE: usize = 4;
+
+/// Axis-aligned rectangle as `(min, max)` corners.
+type Aabb2 = ([f32; 2], [f32; 2]);
+
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    /// Index of the polyline the segment belongs to.
+    polyline: usize,
+    /// Index of the segment's first vertex within its polyline.
+    vertex: usize,
+    start: [f32; 2],
+    end: [f32; 2],
+}
+
+impl Segment {
+    fn bounding_box(&self) -> Aabb2 {
+        (
+            [0, 1].map(|k| self.start[k].min(self.end[k])),
+            [0, 1].map(|k| self.start[k].max(self.end[k])),
+        )
+    }
+
+    fn midpoint(&self, axis: usize) -> f32 {
+        (self.start[axis] + self.end[axis]) / 2.
+    }
+
+    /// Closest point on the segment to `point`.
+    fn closest_point(&self, point: &[f32; 2]) -> [f32; 2] {
+        let direction = [0, 1].map(|k| self.end[k] - self.start[k]);
+        let length2 = direction[0] * direction[0] + direction[1] * direction[1];
+        let t = match length2 > 0. {
+            true => (((point[0] - self.start[0]) * direction[0]
+                + (point[1] - self.start[1]) * direction[1])
+                / length2)
+                .clamp(0., 1.),
+            false => 0.,
+        };
+        [0, 1].map(|k| self.start[k] + t * direction[k])
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    bounding_box: Aabb2,
+    /// Range of `order` covered by the node.
+    start: usize,
+    end: usize,
+    /// Child node indices, `None` for leaves.
+    children: Option<(usize, usize)>,
+}
+
+/// Nearest polyline to a query point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PolylineHit {
+    /// Index of the polyline.
+    pub polyline: usize,
+    /// Index of the first vertex of the nearest segment within the polyline.
+    pub segment: usize,
+    /// Bird's-eye-view distance to the polyline (in meters).
+    pub distance_m: f32,
+    /// Closest point on the polyline.
+    pub closest_point: [f32; 2],
+}
+
+/// Bird's-eye-view spatial index over the segments of a set of (N_i,2+) polylines.
+/// Only the first two coordinates of the vertices are used.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct PolylineIndex {
+    num_polylines: usize,
+    segments: Vec<Segment>,
+    /// Hierarchy order of the segment indices.
+    order: Vec<usize>,
+    /// Nodes in depth-first order. The root is the first node.
+    nodes: Vec<Node>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl PolylineIndex {
+    /// Build the index over a list of (N_i,2+) polylines.
+    #[new]
+    fn py_new(polylines: Vec<PyReadonlyArray2<f32>>) -> Self {
+        let polylines = polylines.iter().map(|x| x.as_array()).collect::<Vec<_>>();
+        PolylineIndex::new(&polylines)
+    }
+
+    /// Nearest polylines of (M,2+) points within `max_distance_m`.
+    /// Returns the (M,) polyline indices (-1 on a miss) and distances (inf on a miss).
+    #[pyo3(name = "query_nearest")]
+    fn py_query_nearest<'py>(
+        &self,
+        py: Python<'py>,
+        points: PyReadonlyArray2<f32>,
+        max_distance_m: f32,
+    ) -> (&'py PyArray<i64, Ix1>, &'py PyArray<f32, Ix1>) {
+        let (indices, distances) = self.query_nearest_batch(&points.as_array(), max_distance_m);
+        (indices.into_pyarray(py), distances.into_pyarray(py))
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Rust methods.
+impl PolylineIndex {
+    /// Build the index over (N_i,2+) polylines. Polylines with a single vertex are indexed as a
+    /// degenerate segment, and empty polylines are skipped.
+    pub fn new(polylines: &[ArrayView<f32, Ix2>]) -> Self {
+        let mut segments = vec![];
+        for (polyline, vertices) in polylines.iter().enumerate() {
+            let num_vertices = vertices.nrows();
+            let num_segments = match num_vertices {
+                0 | 1 => num_vertices,
+                _ => num_vertices - 1,
+            };
+            for vertex in 0..num_segments {
+                let next = (vertex + 1).min(num_vertices - 1);
+                segments.push(Segment {
+                    polyline,
+                    vertex,
+                    start: [vertices[[vertex, 0]], vertices[[vertex, 1]]],
+                    end: [vertices[[next, 0]], vertices[[next, 1]]],
+                });
+            }
+        }
+        let mut index = PolylineIndex {
+            num_polylines: polylines.len(),
+            order: (0..segments.len()).collect(),
+            segments,
+            nodes: vec![],
+        };
+        if !index.segments.is_empty() {
+            index.build(0, index.segments.len());
+        }
+        index
+    }
+
+    /// Number of indexed polylines.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.num_polylines
+    }
+
+    /// Returns `true` if the index has no polylines.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of indexed segments.
+    pub fn num_segments(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Nearest polyline to `point` within `max_distance_m`.
+    pub fn query_nearest(&self, point: &[f32; 2], max_distance_m: f32) -> Option<PolylineHit> {
+        if self.segments.is_empty() {
+            return None;
+        }
+        let mut nearest: Option<PolylineHit> = None;
+        let mut stack = vec![0];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let max_distance_m = nearest.map_or(max_distance_m, |hit| hit.distance_m);
+            if _aabb_distance(&node.bounding_box, point) > max_distance_m {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    // Visit the nearer child first to tighten the bound early.
+                    let left_distance = _aabb_distance(&self.nodes[left].bounding_box, point);
+                    let right_distance = _aabb_distance(&self.nodes[right].bounding_box, point);
+                    match left_distance <= right_distance {
+                        true => stack.extend([right, left]),
+                        false => stack.extend([left, right]),
+                    }
+                }
+                None => {
+                    for &index in &self.order[node.start..node.end] {
+                        let segment = &self.segments[index];
+                        let closest_point = segment.closest_point(point);
+                        let distance_m = _distance(&closest_point, point);
+                        if distance_m <= max_distance_m
+                            && nearest.is_none_or(|hit| distance_m < hit.distance_m)
+                        {
+                            nearest = Some(PolylineHit {
+                                polyline: segment.polyline,
+                                segment: segment.vertex,
+                                distance_m,
+                                closest_point,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        nearest
+    }
+
+    /// Query the nearest polylines of (M,2+) points in parallel.
+    /// Returns the (M,) polyline indices (-1 on a miss) and distances (inf on a miss).
+    pub fn query_nearest_batch(
+        &self,
+        points: &ArrayView<f32, Ix2>,
+        max_distance_m: f32,
+    ) -> (Array<i64, Ix1>, Array<f32, Ix1>) {
+        let hits = (0..points.nrows())
+            .into_par_iter()
+            .map(|i| self.query_nearest(&[points[[i, 0]], points[[i, 1]]], max_distance_m))
+            .collect::<Vec<_>>();
+        let indices = hits
+            .iter()
+            .map(|hit| hit.map_or(-1, |hit| hit.polyline as i64))
+            .collect();
+        let distances = hits
+            .iter()
+            .map(|hit| hit.map_or(f32::INFINITY, |hit| hit.distance_m))
+            .collect();
+        (indices, distances)
+    }
+
+    /// Build the subtree over `order[start..end]` and return its node index.
+    fn build(&mut self, start: usize, end: usize) -> usize {
+        let bounding_box = self.order[start..end].iter().fold(
+            ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]),
+            |(min, max), &index| {
+                let (segment_min, segment_max) = self.segments[index].bounding_box();
+                (
+                    [0, 1].map(|k| min[k].min(segment_min[k])),
+                    [0, 1].map(|k| max[k].max(segment_max[k])),
+                )
+            },
+        );
+        let node_index = self.nodes.len();
+        self.nodes.push(Node {
+            bounding_box,
+            start,
+            end,
+            children: None,
+        });
+        if end - start <= LEAF_SIZE {
+            return node_index;
+        }
+
+        let extent = [0, 1].map(|k| bounding_box.1[k] - bounding_box.0[k]);
+        let axis = usize::from(extent[1] > extent[0]);
+        let mid = (start + end) / 2;
+        let segments = &self.segments;
+        self.order[start..end].select_nth_unstable_by(mid - start, |&a, &b| {
+            segments[a]
+                .midpoint(axis)
+                .total_cmp(&segments[b].midpoint(axis))
+        });
+        let left = self.build(start, mid);
+        let right = self.build(mid, end);
+        self.nodes[node_index].children = Some((left, right));
+        node_index
+    }
+}
+
+fn _distance(a: &[f32; 2], b: &[f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Distance from `point` to the rectangle (zero inside).
+fn _aabb_distance(aabb: &Aabb2, point: &[f32; 2]) -> f32 {
+    let [dx, dy] = [0, 1].map(|k| (aabb.0[k] - point[k]).max(point[k] - aabb.1[k]).max(0.));
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array2};
+
+    use super::PolylineIndex;
+
+    #[test]
+    fn test_polyline_index() {
+        // Horizontal lines at y = 0, 2, ..., 38 with many vertices, and a single vertex.
+        let mut polylines = (0..20)
+            .map(|i| {
+                let vertices = (0..=10)
+                    .flat_map(|x| [x as f32, 2. * i as f32])
+                    .collect::<Vec<_>>();
+                Array2::from_shape_vec((11, 2), vertices).unwrap()
+            })
+            .collect::<Vec<_>>();
+        polylines.push(array![[-5_f32, -5.]]);
+        let views = polylines.iter().map(|x| x.view()).collect::<Vec<_>>();
+        let index = PolylineIndex::new(&views);
+        assert_eq!(index.len(), 21);
+        assert_eq!(index.num_segments(), 20 * 10 + 1);
+
+        let hit = index.query_nearest(&[4.5, 6.8], 10.).unwrap();
+        assert_eq!((hit.polyline, hit.segment), (3, 4));
+        assert!((hit.distance_m - 0.8).abs() < 1e-5);
+        assert_eq!(hit.closest_point, [4.5, 6.]);
+
+        // Beyond the end of the lines.
+        let hit = index.query_nearest(&[13., 0.], 10.).unwrap();
+        assert_eq!(hit.polyline, 0);
+        assert!((hit.distance_m - 3.).abs() < 1e-5);
+
+        let points = array![[-5_f32, -4.], [100., 100.], [0., 37.5]];
+        let (indices, distances) = index.query_nearest_batch(&points.view(), 5.);
+        assert_eq!(indices.to_vec(), vec![20, -1, 19]);
+        assert_eq!(distances[1], f32::INFINITY);
+        assert!((distances[2] - 0.5).abs() < 1e-5);
+    }
+}
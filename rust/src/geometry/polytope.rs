@@ -2,10 +2,24 @@
 //!
 //! Geometric algorithms for polygon geometries.
 
-use ndarray::{concatenate, par_azip, s, Array, ArrayView, Axis, Ix1, Ix2, Ix3, Slice};
+use std::{
+    collections::{BTreeMap, HashSet},
+    f32::consts::{FRAC_PI_2, PI},
+};
+
+use ndarray::{par_azip, s, Array, ArrayView, Axis, Ix1, Ix2, Ix3};
 use once_cell::sync::Lazy;
+use rayon::prelude::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator,
+};
+use strum_macros::{Display, EnumString};
+
+use crate::parallel::install_compute;
 
-use super::so3::_quat_to_mat3;
+use super::{
+    kdtree::KdTree,
+    so3::{_quat_to_mat3, _quat_to_yaw, _yaw_to_quat},
+};
 
 // Safety: 24 elements (8 * 3 = 24) are defined.
 static VERTS: Lazy<Array<f32, Ix2>> = Lazy::new(|| unsafe {
@@ -19,61 +33,241 @@ static VERTS: Lazy<Array<f32, Ix2>> = Lazy::new(|| unsafe {
 });
 
 /// Compute a boolean mask indicating which points are interior to the cuboid geometry.
+///
+/// Cuboids are processed in parallel on the compute pool (see `crate::parallel`). The points are sorted along x once, so each cuboid only
+/// visits the points within the x-extent of its axis-aligned bounding box, and points outside the
+/// bounding box are rejected before the half-space tests.
 pub fn compute_interior_points_mask(
     points: &ArrayView<f32, Ix2>,
     cuboid_vertices: &ArrayView<f32, Ix3>,
 ) -> Array<bool, Ix2> {
     let num_points = points.shape()[0];
     let num_cuboids = cuboid_vertices.shape()[0];
+    let sorted_points = _sort_points_x(points);
 
-    let a = cuboid_vertices.slice_axis(Axis(1), Slice::from(6..7));
-    let b = cuboid_vertices.slice_axis(Axis(1), Slice::from(3..4));
-    let c = cuboid_vertices.slice_axis(Axis(1), Slice::from(1..2));
-    let vertices = concatenate![Axis(1), a, b, c];
+    let mut is_interior = Array::<bool, Ix2>::from_elem((num_cuboids, num_points), false);
+    install_compute(|| {
+        par_azip!((mut mask in is_interior.outer_iter_mut(), vertices in cuboid_vertices.outer_iter()) {
+            let half_spaces = CuboidHalfSpaces::new(&vertices);
+            for i in half_spaces.interior_points(&sorted_points) {
+                mask[i] = true;
+            }
+        })
+    });
+    is_interior
+}
 
-    let reference_index = cuboid_vertices
-        .slice_axis(Axis(1), Slice::from(2..3))
-        .to_owned();
+/// Policy used to assign points which are interior to more than one cuboid.
+#[derive(Clone, Copy, Debug, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum AssignmentPolicy {
+    /// Assign the cuboid with the lowest index (i.e., cuboids are sorted by priority).
+    Priority,
+    /// Assign the cuboid whose center is nearest to the point.
+    Nearest,
+    /// Treat points in overlapping cuboids as background.
+    Unique,
+}
 
-    let uvw = reference_index.clone() - vertices.clone();
-    let reference_index = reference_index.into_shape((num_cuboids, 3)).unwrap();
+/// Compute the (N,) index of the cuboid containing each point, or `-1` for background points.
+/// Points interior to several cuboids are assigned according to `policy`.
+///
+/// Unlike `compute_interior_points_mask`, the (M,N) mask is never materialized: each cuboid only
+/// collects the indices of its interior points.
+pub fn compute_interior_points_assignment(
+    points: &ArrayView<f32, Ix2>,
+    cuboid_vertices: &ArrayView<f32, Ix3>,
+    policy: AssignmentPolicy,
+) -> Array<i64, Ix1> {
+    let num_points = points.shape()[0];
+    let num_cuboids = cuboid_vertices.shape()[0];
+    let sorted_points = _sort_points_x(points);
+    let interior_points = install_compute(|| {
+        (0..num_cuboids)
+            .into_par_iter()
+            .map(|j| {
+                let vertices = cuboid_vertices.index_axis(Axis(0), j);
+                CuboidHalfSpaces::new(&vertices)
+                    .interior_points(&sorted_points)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    });
 
-    let mut dot_uvw_reference = Array::<f32, Ix2>::zeros((num_cuboids, 3));
-    par_azip!((mut a in dot_uvw_reference.outer_iter_mut(), b in uvw.outer_iter(), c in reference_index.outer_iter()) a.assign(&b.dot(&c.t())) );
+    let mut assignment = Array::<i64, Ix1>::from_elem(num_points, -1);
+    // Squared distance to the assigned cuboid's center (nearest policy only).
+    let mut distances = vec![f32::INFINITY; num_points];
+    for (j, indices) in interior_points.into_iter().enumerate() {
+        let center = cuboid_vertices
+            .index_axis(Axis(0), j)
+            .mean_axis(Axis(0))
+            .unwrap();
+        for i in indices {
+            let current = &mut assignment[i];
+            match policy {
+                AssignmentPolicy::Priority if *current >= 0 => {}
+                AssignmentPolicy::Unique if *current >= 0 => *current = -2,
+                AssignmentPolicy::Unique if *current == -2 => {}
+                AssignmentPolicy::Nearest => {
+                    let p = points.row(i);
+                    let distance = (0..3).map(|k| (p[k] - center[k]).powi(2)).sum::<f32>();
+                    if distance < distances[i] {
+                        distances[i] = distance;
+                        *current = j as i64;
+                    }
+                }
+                _ => *current = j as i64,
+            }
+        }
+    }
+    // Overlapping points were marked with `-2` under the unique policy.
+    assignment.mapv_inplace(|x| x.max(-1));
+    assignment
+}
 
-    let mut dot_uvw_vertices = Array::<f32, Ix2>::zeros((num_cuboids, 3));
-    par_azip!((mut a in dot_uvw_vertices.outer_iter_mut(), b in uvw.outer_iter(), c in vertices.outer_iter()) a.assign(&b.dot(&c.t()).diag()) );
+/// Collect the indices of the points interior to each cuboid into `interior_points` (one list per
+/// cuboid). `sorted_points` is scratch space; it and the lists are reused across calls, so calls on
+/// sweeps of similar size do not allocate.
+pub fn collect_interior_points(
+    points: impl Iterator<Item = [f32; 3]>,
+    cuboid_vertices: &ArrayView<f32, Ix3>,
+    sorted_points: &mut Vec<(usize, [f32; 3])>,
+    interior_points: &mut Vec<Vec<usize>>,
+) {
+    sorted_points.clear();
+    sorted_points.extend(points.enumerate());
+    sorted_points.sort_unstable_by(|a, b| a.1[0].total_cmp(&b.1[0]));
 
-    let dot_uvw_points = uvw
-        .into_shape((num_cuboids * 3, 3))
-        .unwrap()
-        .as_standard_layout()
-        .dot(&points.t().as_standard_layout())
-        .into_shape((num_cuboids, 3, num_points))
-        .unwrap();
-
-    let shape = (num_cuboids, num_points);
-    let mut is_interior =
-        Array::<_, Ix2>::from_shape_vec(shape, vec![false; num_cuboids * num_points]).unwrap();
-    par_azip!((mut a in is_interior.outer_iter_mut(), b in dot_uvw_reference.outer_iter(), c in dot_uvw_points.outer_iter(), d in dot_uvw_vertices.outer_iter()) {
-
-        let c0 = c.slice(s![0, ..]).mapv(|x| ((b[0] <= x) & (x <= d[0])) | ((b[0] >= x) & (x >= d[0])));
-        let c1 = c.slice(s![1, ..]).mapv(|x| ((b[1] <= x) & (x <= d[1])) | ((b[1] >= x) & (x >= d[1])));
-        let c2 = c.slice(s![2, ..]).mapv(|x| ((b[2] <= x) & (x <= d[2])) | ((b[2] >= x) & (x >= d[2])));
-
-        let is_interior_i = &c0 & &c1 & &c2;
-        a.assign(&is_interior_i);
+    let sorted_points = &*sorted_points;
+    interior_points.resize_with(cuboid_vertices.shape()[0], Vec::new);
+    install_compute(|| {
+        interior_points
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(j, indices)| {
+                let vertices = cuboid_vertices.index_axis(Axis(0), j);
+                indices.clear();
+                indices.extend(CuboidHalfSpaces::new(&vertices).interior_points(sorted_points));
+            })
     });
+}
 
+/// Compute a boolean mask indicating which points are interior to the cuboid geometry.
+/// Only the points of `index` within each cuboid's circumscribed sphere are tested.
+pub fn compute_interior_points_mask_indexed(
+    index: &KdTree,
+    cuboid_vertices: &ArrayView<f32, Ix3>,
+) -> Array<bool, Ix2> {
+    let num_cuboids = cuboid_vertices.shape()[0];
+    let mut is_interior = Array::<bool, Ix2>::from_elem((num_cuboids, index.len()), false);
+    install_compute(|| {
+        par_azip!((mut mask in is_interior.outer_iter_mut(), vertices in cuboid_vertices.outer_iter()) {
+            let center = vertices.mean_axis(Axis(0)).unwrap();
+            let radius = vertices
+                .outer_iter()
+                .map(|v| (&v - &center).mapv(|x| x * x).sum().sqrt())
+                .fold(0., f32::max);
+
+            let half_spaces = CuboidHalfSpaces::new(&vertices);
+            for i in index.query_radius(&center.view(), radius) {
+                let point = index.points.row(i);
+                mask[i] = half_spaces.contains(&[point[0], point[1], point[2]]);
+            }
+        })
+    });
     is_interior
 }
 
+/// Half-space representation of a cuboid given its (8,3) vertices.
+///
+/// A point is interior if its projection onto each of the three edges adjacent to vertex 2 lies
+/// between the projections of the edge's endpoints.
+struct CuboidHalfSpaces {
+    /// Edge directions from vertices 6, 3, and 1 to vertex 2.
+    uvw: [[f32; 3]; 3],
+    /// Projections of the edge endpoints onto their edge directions.
+    bounds: [(f32, f32); 3],
+    /// Axis-aligned bounding box of the vertices.
+    bounding_box: ([f32; 3], [f32; 3]),
+}
+
+impl CuboidHalfSpaces {
+    fn new(vertices: &ArrayView<f32, Ix2>) -> Self {
+        let vertex = |i: usize| [vertices[[i, 0]], vertices[[i, 1]], vertices[[i, 2]]];
+        let reference = vertex(2);
+        let uvw = [6, 3, 1].map(|i| {
+            let v = vertex(i);
+            [0, 1, 2].map(|k| reference[k] - v[k])
+        });
+        let bounds = [0, 1, 2].map(|k| {
+            (
+                _dot3(&uvw[k], &reference),
+                _dot3(&uvw[k], &vertex([6, 3, 1][k])),
+            )
+        });
+        let bounding_box = (0..8).map(vertex).fold(
+            ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+            |(min, max), v| {
+                (
+                    [0, 1, 2].map(|k| min[k].min(v[k])),
+                    [0, 1, 2].map(|k| max[k].max(v[k])),
+                )
+            },
+        );
+        Self {
+            uvw,
+            bounds,
+            bounding_box,
+        }
+    }
+
+    /// Indices of the x-sorted `(index, point)` pairs which are interior to the cuboid.
+    fn interior_points<'a>(
+        &'a self,
+        sorted_points: &'a [(usize, [f32; 3])],
+    ) -> impl Iterator<Item = usize> + 'a {
+        let (min, max) = self.bounding_box;
+        let start = sorted_points.partition_point(|(_, p)| p[0] < min[0]);
+        let end = sorted_points.partition_point(|(_, p)| p[0] <= max[0]);
+        sorted_points[start..end.max(start)]
+            .iter()
+            .filter(move |(_, p)| (1..3).all(|k| min[k] <= p[k] && p[k] <= max[k]))
+            .filter(|(_, p)| self.contains(p))
+            .map(|(i, _)| *i)
+    }
+
+    fn contains(&self, point: &[f32; 3]) -> bool {
+        (0..3).all(|k| {
+            let (lower, upper) = self.bounds[k];
+            let x = _dot3(&self.uvw[k], point);
+            (lower <= x && x <= upper) || (lower >= x && x >= upper)
+        })
+    }
+}
+
+/// Pair each (N,3+) point with its index and sort the pairs along x.
+fn _sort_points_x(points: &ArrayView<f32, Ix2>) -> Vec<(usize, [f32; 3])> {
+    let mut sorted_points = points
+        .outer_iter()
+        .enumerate()
+        .map(|(i, p)| (i, [p[0], p[1], p[2]]))
+        .collect::<Vec<_>>();
+    sorted_points.sort_unstable_by(|a, b| a.1[0].total_cmp(&b.1[0]));
+    sorted_points
+}
+
+fn _dot3(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
 /// Convert (N,10) cuboids to polygons.
 pub fn cuboids_to_polygons(cuboids: &ArrayView<f32, Ix2>) -> Array<f32, Ix3> {
     let num_cuboids = cuboids.shape()[0];
     let mut polygons = Array::<f32, Ix3>::zeros([num_cuboids, 8, 3]);
-    par_azip!((mut p in polygons.outer_iter_mut(), c in cuboids.outer_iter()) {
-        p.assign(&_cuboid_to_polygon(&c))
+    install_compute(|| {
+        par_azip!((mut p in polygons.outer_iter_mut(), c in cuboids.outer_iter()) {
+            p.assign(&_cuboid_to_polygon(&c))
+        })
     });
     polygons
 }
@@ -88,3 +282,938 @@ fn _cuboid_to_polygon(cuboid: &ArrayView<f32, Ix1>) -> Array<f32, Ix2> {
     let verts = verts.dot(&mat.t()) + center_xyz;
     verts.as_standard_layout().to_owned()
 }
+
+/// Convert (N,10) cuboids to (N,4,2) bird's-eye-view footprints.
+pub fn cuboids_to_bev_footprints(cuboids: &ArrayView<f32, Ix2>) -> Array<f32, Ix3> {
+    let num_cuboids = cuboids.shape()[0];
+    let mut footprints = Array::<f32, Ix3>::zeros([num_cuboids, 4, 2]);
+    install_compute(|| {
+        par_azip!((mut f in footprints.outer_iter_mut(), c in cuboids.outer_iter()) {
+            f.assign(&_cuboid_to_bev_footprint(&c))
+        })
+    });
+    footprints
+}
+
+/// Convert a single cuboid to its (4,2) bird's-eye-view footprint.
+/// Vertices are ordered counter-clockwise starting from the front-left corner.
+pub fn _cuboid_to_bev_footprint(cuboid: &ArrayView<f32, Ix1>) -> Array<f32, Ix2> {
+    let (tx, ty) = (cuboid[0], cuboid[1]);
+    let (half_length, half_width) = (cuboid[3] / 2., cuboid[4] / 2.);
+    let yaw = _quat_to_yaw(&cuboid.slice(s![6..10]));
+    let (sin, cos) = yaw.sin_cos();
+
+    let mut footprint = Array::<f32, Ix2>::zeros([4, 2]);
+    let corners = [(1., 1.), (-1., 1.), (-1., -1.), (1., -1.)];
+    for (mut v, (sx, sy)) in footprint.outer_iter_mut().zip(corners) {
+        let (x, y) = (sx * half_length, sy * half_width);
+        v[0] = cos * x - sin * y + tx;
+        v[1] = sin * x + cos * y + ty;
+    }
+    footprint
+}
+
+/// Compute the area of a simple (N,2) polygon with the shoelace formula.
+pub fn polygon_area(vertices: &ArrayView<f32, Ix2>) -> f32 {
+    let num_vertices = vertices.shape()[0];
+    let mut twice_area = 0.;
+    for i in 0..num_vertices {
+        let j = (i + 1) % num_vertices;
+        twice_area += vertices[[i, 0]] * vertices[[j, 1]] - vertices[[j, 0]] * vertices[[i, 1]];
+    }
+    twice_area.abs() / 2.
+}
+
+/// Clip a convex `subject` polygon against a convex `clip` polygon (Sutherland-Hodgman).
+/// Both polygons are (N,2) and must be ordered counter-clockwise.
+/// Returns the (K,2) intersection polygon, which is empty if the polygons are disjoint.
+pub fn clip_convex_polygons(
+    subject: &ArrayView<f32, Ix2>,
+    clip: &ArrayView<f32, Ix2>,
+) -> Array<f32, Ix2> {
+    let mut output = subject
+        .outer_iter()
+        .map(|v| [v[0], v[1]])
+        .collect::<Vec<_>>();
+    let num_clip_vertices = clip.shape()[0];
+    for i in 0..num_clip_vertices {
+        if output.is_empty() {
+            break;
+        }
+        let j = (i + 1) % num_clip_vertices;
+        let edge_start = [clip[[i, 0]], clip[[i, 1]]];
+        let edge_end = [clip[[j, 0]], clip[[j, 1]]];
+
+        // Signed distance (scaled) of a point to the left of the clip edge.
+        let side = |p: &[f32; 2]| {
+            (edge_end[0] - edge_start[0]) * (p[1] - edge_start[1])
+                - (edge_end[1] - edge_start[1]) * (p[0] - edge_start[0])
+        };
+
+        let input = std::mem::take(&mut output);
+        for (k, current) in input.iter().enumerate() {
+            let previous = &input[(k + input.len() - 1) % input.len()];
+            let (d_current, d_previous) = (side(current), side(previous));
+            if d_current >= 0. {
+                if d_previous < 0. {
+                    output.push(_intersect(previous, current, d_previous, d_current));
+                }
+                output.push(*current);
+            } else if d_previous >= 0. {
+                output.push(_intersect(previous, current, d_previous, d_current));
+            }
+        }
+    }
+
+    let num_vertices = output.len();
+    Array::<f32, Ix2>::from_shape_vec((num_vertices, 2), output.into_iter().flatten().collect())
+        .unwrap()
+}
+
+/// Intersect the segment `a -> b` with a line given the signed distances of its endpoints.
+fn _intersect(a: &[f32; 2], b: &[f32; 2], d_a: f32, d_b: f32) -> [f32; 2] {
+    let t = d_a / (d_a - d_b);
+    [a[0] + t * (b[0] - a[0]), a[1] + t * (b[1] - a[1])]
+}
+
+/// Planar polygon with holes (e.g., a drivable area or crosswalk from the map).
+/// Rings may be open or closed and only their first two coordinates are used.
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    /// (N,2+) exterior ring.
+    pub exterior: Array<f32, Ix2>,
+    /// (K,2+) interior rings.
+    pub holes: Vec<Array<f32, Ix2>>,
+}
+
+impl Polygon {
+    /// Construct a polygon without holes.
+    pub fn new(exterior: Array<f32, Ix2>) -> Self {
+        Self {
+            exterior,
+            holes: vec![],
+        }
+    }
+
+    /// Returns `true` if `(x, y)` is inside the exterior ring and outside every hole.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        // Even-odd rule over every ring.
+        std::iter::once(&self.exterior)
+            .chain(self.holes.iter())
+            .filter(|ring| _ring_crossings(&ring.view(), x, y))
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Bird's-eye-view distance from `(x, y)` to the polygon (`0` inside).
+    pub fn distance(&self, x: f32, y: f32) -> f32 {
+        if self.contains(x, y) {
+            return 0.;
+        }
+        std::iter::once(&self.exterior)
+            .chain(self.holes.iter())
+            .flat_map(|ring| {
+                let ring = ring.outer_iter().map(|v| [v[0], v[1]]).collect::<Vec<_>>();
+                _ring_edges(&ring)
+                    .map(|(a, b)| _point_segment_distance(&[x, y], &a, &b))
+                    .collect::<Vec<_>>()
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+/// Compute a (N,) boolean mask indicating which of the (N,2+) points are within `margin_m` of any
+/// of the polygons in the bird's-eye-view (e.g., near the drivable area). Parallelized over points.
+pub fn compute_points_near_polygons_mask(
+    points: &ArrayView<f32, Ix2>,
+    polygons: &[Polygon],
+    margin_m: f32,
+) -> Array<bool, Ix1> {
+    let bounding_boxes = polygons
+        .iter()
+        .map(|polygon| {
+            let exterior = polygon
+                .exterior
+                .outer_iter()
+                .map(|v| [v[0], v[1]])
+                .collect::<Vec<_>>();
+            _bounding_box_2d(&exterior)
+        })
+        .collect::<Vec<_>>();
+    let mut is_near = Array::<bool, Ix1>::from_elem(points.shape()[0], false);
+    install_compute(|| {
+        par_azip!((m in &mut is_near, point in points.outer_iter()) {
+            let (x, y) = (point[0], point[1]);
+            *m = polygons.iter().zip(&bounding_boxes).any(|(polygon, (min, max))| {
+                // Axis-aligned bounding box prefilter, dilated by the margin.
+                x >= min[0] - margin_m
+                    && x <= max[0] + margin_m
+                    && y >= min[1] - margin_m
+                    && y <= max[1] + margin_m
+                    && polygon.distance(x, y) <= margin_m
+            });
+        })
+    });
+    is_near
+}
+
+/// Compute a (M,N) boolean mask indicating which of the (N,2+) points are interior to each of
+/// the M polygons. Only the first two coordinates of the points are used.
+pub fn compute_points_in_polygons_mask(
+    points: &ArrayView<f32, Ix2>,
+    polygons: &[Polygon],
+) -> Array<bool, Ix2> {
+    let num_points = points.shape()[0];
+    let mut is_interior = Array::<bool, Ix2>::from_elem((polygons.len(), num_points), false);
+    let polygons = Array::from_iter(polygons.iter());
+    install_compute(|| {
+        par_azip!((mut mask in is_interior.outer_iter_mut(), polygon in &polygons) {
+            let exterior = polygon.exterior.slice(s![.., ..2]);
+            let (min, max) = (
+                exterior.fold_axis(Axis(0), f32::INFINITY, |a, b| a.min(*b)),
+                exterior.fold_axis(Axis(0), f32::NEG_INFINITY, |a, b| a.max(*b)),
+            );
+            for (m, point) in mask.iter_mut().zip(points.outer_iter()) {
+                let (x, y) = (point[0], point[1]);
+                // Axis-aligned bounding box prefilter.
+                if x < min[0] || x > max[0] || y < min[1] || y > max[1] {
+                    continue;
+                }
+                *m = polygon.contains(x, y);
+            }
+        })
+    });
+    is_interior
+}
+
+/// Compute a (N,M) boolean mask indicating which of the (N,10) cuboids' bird's-eye-view
+/// footprints overlap each of the M polygons (e.g., drivable areas or crosswalks).
+///
+/// A footprint overlaps a polygon if either contains a vertex of the other or their boundaries
+/// cross. Footprints entirely within a hole do not overlap. Parallelized over cuboids.
+pub fn compute_cuboid_polygon_overlap_mask(
+    cuboids: &ArrayView<f32, Ix2>,
+    polygons: &[Polygon],
+) -> Array<bool, Ix2> {
+    let num_cuboids = cuboids.shape()[0];
+    let rings = polygons
+        .iter()
+        .map(|polygon| {
+            std::iter::once(&polygon.exterior)
+                .chain(polygon.holes.iter())
+                .map(|ring| ring.outer_iter().map(|v| [v[0], v[1]]).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let bounding_boxes = rings
+        .iter()
+        .map(|r| _bounding_box_2d(&r[0]))
+        .collect::<Vec<_>>();
+
+    let mut overlaps = Array::<bool, Ix2>::from_elem((num_cuboids, polygons.len()), false);
+    install_compute(|| {
+        par_azip!((mut mask in overlaps.outer_iter_mut(), cuboid in cuboids.outer_iter()) {
+            let footprint = _cuboid_to_bev_footprint(&cuboid);
+            let footprint = footprint.outer_iter().map(|v| [v[0], v[1]]).collect::<Vec<_>>();
+            let (min, max) = _bounding_box_2d(&footprint);
+            for (j, m) in mask.iter_mut().enumerate() {
+                let (polygon_min, polygon_max) = bounding_boxes[j];
+                // Axis-aligned bounding box prefilter.
+                if (0..2).any(|k| max[k] < polygon_min[k] || polygon_max[k] < min[k]) {
+                    continue;
+                }
+                *m = footprint.iter().any(|v| polygons[j].contains(v[0], v[1]))
+                    || rings[j][0].iter().any(|v| _convex_contains(&footprint, v))
+                    || rings[j].iter().any(|ring| _rings_cross(&footprint, ring));
+            }
+        })
+    });
+    overlaps
+}
+
+fn _bounding_box_2d(ring: &[[f32; 2]]) -> ([f32; 2], [f32; 2]) {
+    ring.iter().fold(
+        ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]),
+        |(min, max), v| {
+            (
+                [min[0].min(v[0]), min[1].min(v[1])],
+                [max[0].max(v[0]), max[1].max(v[1])],
+            )
+        },
+    )
+}
+
+/// Returns `true` if `point` is inside (or on) the counter-clockwise convex `ring`.
+fn _convex_contains(ring: &[[f32; 2]], point: &[f32; 2]) -> bool {
+    (0..ring.len()).all(|i| {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        _cross_2d(&a, &b, point) >= 0.
+    })
+}
+
+/// Returns `true` if any edge of the closed ring `a` properly crosses or touches an edge of `b`.
+fn _rings_cross(a: &[[f32; 2]], b: &[[f32; 2]]) -> bool {
+    let b = _ring_edges(b).collect::<Vec<_>>();
+    _ring_edges(a).any(|(p, q)| b.iter().any(|(r, s)| _segments_intersect(&p, &q, r, s)))
+}
+
+fn _ring_edges(ring: &[[f32; 2]]) -> impl Iterator<Item = ([f32; 2], [f32; 2])> + '_ {
+    let n = ring.len();
+    (0..n).map(move |i| (ring[i], ring[(i + 1) % n]))
+}
+
+fn _segments_intersect(p: &[f32; 2], q: &[f32; 2], r: &[f32; 2], s: &[f32; 2]) -> bool {
+    let (d1, d2) = (_cross_2d(r, s, p), _cross_2d(r, s, q));
+    let (d3, d4) = (_cross_2d(p, q, r), _cross_2d(p, q, s));
+    if ((d1 > 0.) != (d2 > 0.) && d1 != 0. && d2 != 0.)
+        && ((d3 > 0.) != (d4 > 0.) && d3 != 0. && d4 != 0.)
+    {
+        return true;
+    }
+    // Collinear or touching endpoints.
+    let on_segment = |a: &[f32; 2], b: &[f32; 2], c: &[f32; 2]| {
+        a[0].min(b[0]) <= c[0]
+            && c[0] <= a[0].max(b[0])
+            && a[1].min(b[1]) <= c[1]
+            && c[1] <= a[1].max(b[1])
+    };
+    (d1 == 0. && on_segment(r, s, p))
+        || (d2 == 0. && on_segment(r, s, q))
+        || (d3 == 0. && on_segment(p, q, r))
+        || (d4 == 0. && on_segment(p, q, s))
+}
+
+/// Distance from `p` to the segment `a -> b`.
+fn _point_segment_distance(p: &[f32; 2], a: &[f32; 2], b: &[f32; 2]) -> f32 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let length2 = dx * dx + dy * dy;
+    let t = match length2 > 0. {
+        true => (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / length2).clamp(0., 1.),
+        false => 0.,
+    };
+    (p[0] - a[0] - t * dx).hypot(p[1] - a[1] - t * dy)
+}
+
+/// Z-component of `(b - a) x (c - a)`.
+fn _cross_2d(a: &[f32; 2], b: &[f32; 2], c: &[f32; 2]) -> f32 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Returns `true` if a ray cast from `(x, y)` along +x crosses the (N,2+) ring an odd number of times.
+fn _ring_crossings(ring: &ArrayView<f32, Ix2>, x: f32, y: f32) -> bool {
+    let num_vertices = ring.shape()[0];
+    let mut is_inside = false;
+    for i in 0..num_vertices {
+        let j = (i + num_vertices - 1) % num_vertices;
+        let (xi, yi, xj, yj) = (ring[[i, 0]], ring[[i, 1]], ring[[j, 0]], ring[[j, 1]]);
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            is_inside = !is_inside;
+        }
+    }
+    is_inside
+}
+
+/// Compute the convex hull of (N,2) points with Andrew's monotone chain.
+/// Returns the (K,2) hull vertices ordered counter-clockwise without collinear vertices.
+pub fn convex_hull_2d(points: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+    let mut sorted = points
+        .outer_iter()
+        .map(|p| [p[0], p[1]])
+        .collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    sorted.dedup();
+
+    let hull = match sorted.len() {
+        0..=2 => sorted,
+        _ => {
+            let mut lower = _monotone_chain(sorted.iter());
+            let mut upper = _monotone_chain(sorted.iter().rev());
+            lower.pop();
+            upper.pop();
+            lower.extend(upper);
+            lower
+        }
+    };
+
+    let num_vertices = hull.len();
+    Array::<f32, Ix2>::from_shape_vec((num_vertices, 2), hull.into_iter().flatten().collect())
+        .unwrap()
+}
+
+/// Compute the convex hull of (N,3) points with the incremental algorithm.
+/// Returns (F,3) indices into `points` of the triangular faces, ordered counter-clockwise when
+/// viewed from outside. The hull is empty if the points are coplanar.
+pub fn convex_hull_3d(points: &ArrayView<f32, Ix2>) -> Array<usize, Ix2> {
+    let points = points
+        .outer_iter()
+        .map(|p| [p[0] as f64, p[1] as f64, p[2] as f64])
+        .collect::<Vec<_>>();
+    let faces = _convex_hull_3d(&points).unwrap_or_default();
+    let num_faces = faces.len();
+    Array::<usize, Ix2>::from_shape_vec((num_faces, 3), faces.into_iter().flatten().collect())
+        .unwrap()
+}
+
+/// Compute the (K,2) bird's-eye-view convex hull of the points interior to each cuboid.
+/// Cuboids with fewer than three non-collinear interior points have degenerate hulls.
+pub fn compute_interior_points_bev_hulls(
+    points: &ArrayView<f32, Ix2>,
+    cuboid_vertices: &ArrayView<f32, Ix3>,
+) -> Vec<Array<f32, Ix2>> {
+    let is_interior = compute_interior_points_mask(points, cuboid_vertices);
+    is_interior
+        .outer_iter()
+        .map(|mask| {
+            let interior = mask
+                .iter()
+                .zip(points.outer_iter())
+                .filter(|(is_interior, _)| **is_interior)
+                .flat_map(|(_, p)| [p[0], p[1]])
+                .collect::<Vec<_>>();
+            let interior = Array::from_shape_vec((interior.len() / 2, 2), interior).unwrap();
+            convex_hull_2d(&interior.view())
+        })
+        .collect()
+}
+
+/// Rectangle in the xy-plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rectangle {
+    /// Center `(x, y)`.
+    pub center: [f32; 2],
+    /// Extent along the heading (the longer side).
+    pub length: f32,
+    /// Extent perpendicular to the heading.
+    pub width: f32,
+    /// Heading in `(-pi/2, pi/2]` (in radians).
+    pub yaw_rad: f32,
+}
+
+/// Compute the minimum-area rectangle enclosing a (K,2) counter-clockwise convex hull (e.g., from
+/// `convex_hull_2d`) with rotating calipers. Returns `None` if the hull is empty.
+///
+/// One side of the optimal rectangle is collinear with a hull edge, so each edge is visited once
+/// while the supporting vertices (farthest from the edge, and extreme along it) advance
+/// monotonically around the hull.
+pub fn minimum_area_rectangle(hull: &ArrayView<f32, Ix2>) -> Option<Rectangle> {
+    let hull = hull.outer_iter().map(|p| [p[0], p[1]]).collect::<Vec<_>>();
+    let num_vertices = hull.len();
+    let rectangle = |center: [f32; 2], length: f32, width: f32, yaw_rad: f32| {
+        // Heading along the longer side, wrapped to `(-pi/2, pi/2]`.
+        let (length, width, yaw_rad) = match width > length {
+            true => (width, length, yaw_rad + FRAC_PI_2),
+            false => (length, width, yaw_rad),
+        };
+        let yaw_rad = yaw_rad - PI * ((yaw_rad - FRAC_PI_2) / PI).ceil();
+        Rectangle {
+            center,
+            length,
+            width,
+            yaw_rad,
+        }
+    };
+    match num_vertices {
+        0 => return None,
+        1 => return Some(rectangle(hull[0], 0., 0., 0.)),
+        2 => {
+            let (a, b) = (hull[0], hull[1]);
+            let center = [(a[0] + b[0]) / 2., (a[1] + b[1]) / 2.];
+            let length = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+            return Some(rectangle(
+                center,
+                length,
+                0.,
+                (b[1] - a[1]).atan2(b[0] - a[0]),
+            ));
+        }
+        _ => {}
+    }
+
+    let (mut far, mut right, mut left) = (0, 0, 0);
+    let mut best: Option<(f32, Rectangle)> = None;
+    for i in 0..num_vertices {
+        let (origin, next) = (hull[i], hull[(i + 1) % num_vertices]);
+        let edge = [next[0] - origin[0], next[1] - origin[1]];
+        let edge_length = (edge[0] * edge[0] + edge[1] * edge[1]).sqrt();
+        let u = [edge[0] / edge_length, edge[1] / edge_length];
+        // Inward normal of a counter-clockwise edge.
+        let n = [-u[1], u[0]];
+        let project = |j: usize, axis: &[f32; 2]| {
+            (hull[j][0] - origin[0]) * axis[0] + (hull[j][1] - origin[1]) * axis[1]
+        };
+        let advance = |start: usize, f: &dyn Fn(usize) -> f32| {
+            let mut j = start;
+            for _ in 0..num_vertices {
+                let candidate = (j + 1) % num_vertices;
+                if f(candidate) <= f(j) {
+                    break;
+                }
+                j = candidate;
+            }
+            j
+        };
+        if i == 0 {
+            // Both endpoints of the edge have a zero projection onto its normal.
+            far = 1;
+        }
+        far = advance(far, &|j| project(j, &n));
+        right = advance(right, &|j| project(j, &u));
+        if i == 0 {
+            // The projection onto the edge decreases from the farthest vertex onwards.
+            left = far;
+        }
+        left = advance(left, &|j| -project(j, &u));
+
+        let (min_u, max_u) = (project(left, &u), project(right, &u));
+        let height = project(far, &n);
+        let area = (max_u - min_u) * height;
+        if best.is_some_and(|(best_area, _)| best_area <= area) {
+            continue;
+        }
+        let (offset_u, offset_n) = ((min_u + max_u) / 2., height / 2.);
+        let center = [0, 1].map(|k| origin[k] + offset_u * u[k] + offset_n * n[k]);
+        best = Some((
+            area,
+            rectangle(center, max_u - min_u, height, u[1].atan2(u[0])),
+        ));
+    }
+    best.map(|(_, rectangle)| rectangle)
+}
+
+/// Fit a cuboid to (N,3) points: the minimum-area bird's-eye-view rectangle around the points,
+/// extended over their vertical range. Returns the (10,) cuboid parameters, or `None` if there are
+/// no points.
+pub fn fit_oriented_cuboid(points: &ArrayView<f32, Ix2>) -> Option<Array<f32, Ix1>> {
+    let hull = convex_hull_2d(&points.slice(s![.., ..2]));
+    let rectangle = minimum_area_rectangle(&hull.view())?;
+    let (min_z, max_z) = points
+        .column(2)
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), z| {
+            (min.min(*z), max.max(*z))
+        });
+    let quat_wxyz = _yaw_to_quat(rectangle.yaw_rad);
+    Some(Array::from_vec(vec![
+        rectangle.center[0],
+        rectangle.center[1],
+        (min_z + max_z) / 2.,
+        rectangle.length,
+        rectangle.width,
+        max_z - min_z,
+        quat_wxyz[0],
+        quat_wxyz[1],
+        quat_wxyz[2],
+        quat_wxyz[3],
+    ]))
+}
+
+/// Fit a cuboid to each cluster of (N,3) points given their (N,) cluster ids. Points with negative
+/// ids (e.g., noise) are ignored. Clusters are fit in parallel.
+/// Returns the (C,) sorted cluster ids and their (C,10) cuboid parameters.
+pub fn fit_oriented_cuboids(
+    points: &ArrayView<f32, Ix2>,
+    cluster_ids: &ArrayView<i64, Ix1>,
+) -> (Array<i64, Ix1>, Array<f32, Ix2>) {
+    let mut clusters: BTreeMap<i64, Vec<usize>> = BTreeMap::new();
+    for (i, id) in cluster_ids.iter().enumerate().filter(|(_, id)| **id >= 0) {
+        clusters.entry(*id).or_default().push(i);
+    }
+    let ids = clusters.keys().copied().collect::<Array<i64, Ix1>>();
+    let members = clusters.into_values().collect::<Vec<_>>();
+    let mut cuboids = Array::<f32, Ix2>::zeros((members.len(), 10));
+    install_compute(|| {
+        par_azip!((mut cuboid in cuboids.outer_iter_mut(), indices in &members) {
+            let cluster = points.select(Axis(0), indices);
+            cuboid.assign(&fit_oriented_cuboid(&cluster.view()).unwrap());
+        })
+    });
+    (ids, cuboids)
+}
+
+/// Half of the monotone chain: keep only counter-clockwise turns.
+fn _monotone_chain<'a>(points: impl Iterator<Item = &'a [f32; 2]>) -> Vec<[f32; 2]> {
+    let cross = |o: &[f32; 2], a: &[f32; 2], b: &[f32; 2]| {
+        (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+    };
+    let mut chain: Vec<[f32; 2]> = vec![];
+    for p in points {
+        while chain.len() >= 2 && cross(&chain[chain.len() - 2], &chain[chain.len() - 1], p) <= 0. {
+            chain.pop();
+        }
+        chain.push(*p);
+    }
+    chain
+}
+
+type Vec3 = [f64; 3];
+
+fn _sub(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn _cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn _dot(a: &Vec3, b: &Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Incremental 3D convex hull. Returns `None` if the points are coplanar.
+fn _convex_hull_3d(points: &[Vec3]) -> Option<Vec<[usize; 3]>> {
+    let argmax = |f: &dyn Fn(&Vec3) -> f64| {
+        (0..points.len()).max_by(|&a, &b| f(&points[a]).total_cmp(&f(&points[b])))
+    };
+
+    // Initial tetrahedron from extreme points.
+    let scale = points
+        .iter()
+        .flat_map(|p| p.iter().map(|x| x.abs()))
+        .fold(1., f64::max);
+    let eps = 1e-9 * scale;
+    let i0 = 0;
+    let i1 = argmax(&|p| _dot(&_sub(p, &points[i0]), &_sub(p, &points[i0])))?;
+    let axis = _sub(&points[i1], &points[i0]);
+    let i2 = argmax(&|p| {
+        let c = _cross(&axis, &_sub(p, &points[i0]));
+        _dot(&c, &c)
+    })?;
+    let normal = _cross(&axis, &_sub(&points[i2], &points[i0]));
+    let i3 = argmax(&|p| _dot(&normal, &_sub(p, &points[i0])).abs())?;
+    let volume = _dot(&normal, &_sub(&points[i3], &points[i0]));
+    if volume.abs() <= eps * _dot(&normal, &normal).sqrt() {
+        return None;
+    }
+
+    let outward = |face: [usize; 3]| -> [usize; 3] {
+        let [a, b, c] = face;
+        let n = _cross(&_sub(&points[b], &points[a]), &_sub(&points[c], &points[a]));
+        let opposite = [i0, i1, i2, i3]
+            .into_iter()
+            .find(|i| !face.contains(i))
+            .unwrap();
+        match _dot(&n, &_sub(&points[opposite], &points[a])) > 0. {
+            true => [a, c, b],
+            false => face,
+        }
+    };
+    let mut faces = vec![
+        outward([i0, i1, i2]),
+        outward([i0, i1, i3]),
+        outward([i0, i2, i3]),
+        outward([i1, i2, i3]),
+    ];
+
+    for (i, p) in points.iter().enumerate() {
+        if [i0, i1, i2, i3].contains(&i) {
+            continue;
+        }
+        let is_visible = faces
+            .iter()
+            .map(|&[a, b, c]| {
+                let n = _cross(&_sub(&points[b], &points[a]), &_sub(&points[c], &points[a]));
+                _dot(&n, &_sub(p, &points[a])) > eps * _dot(&n, &n).sqrt()
+            })
+            .collect::<Vec<_>>();
+        if !is_visible.iter().any(|x| *x) {
+            continue;
+        }
+
+        // The horizon consists of the directed edges of visible faces whose twins are hidden.
+        let visible_edges = faces
+            .iter()
+            .zip(&is_visible)
+            .filter(|(_, v)| **v)
+            .flat_map(|(&[a, b, c], _)| [(a, b), (b, c), (c, a)])
+            .collect::<HashSet<_>>();
+        let horizon = visible_edges
+            .iter()
+            .filter(|(a, b)| !visible_edges.contains(&(*b, *a)))
+            .copied()
+            .collect::<Vec<_>>();
+
+        faces = faces
+            .into_iter()
+            .zip(is_visible)
+            .filter(|(_, v)| !v)
+            .map(|(face, _)| face)
+            .chain(horizon.into_iter().map(|(a, b)| [a, b, i]))
+            .collect();
+    }
+    Some(faces)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use ndarray::{array, Array, Ix2};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{
+        compute_cuboid_polygon_overlap_mask, compute_interior_points_assignment,
+        compute_interior_points_mask, compute_points_in_polygons_mask,
+        compute_points_near_polygons_mask, convex_hull_2d, convex_hull_3d, cuboids_to_polygons,
+        fit_oriented_cuboids, minimum_area_rectangle, polygon_area, AssignmentPolicy, Polygon,
+    };
+
+    #[test]
+    fn test_compute_interior_points_mask() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let points = Array::<f32, Ix2>::from_shape_fn((1000, 3), |_| rng.gen_range(-3.0..3.0));
+        let cuboids = array![
+            [0., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [1., 1., 1., 4., 2., 2., 1., 0., 0., 0.]
+        ];
+        let mask = compute_interior_points_mask(
+            &points.view(),
+            &cuboids_to_polygons(&cuboids.view()).view(),
+        );
+        for (i, p) in points.outer_iter().enumerate() {
+            assert_eq!(mask[[0, i]], p.iter().all(|x| x.abs() <= 1.));
+            let is_interior =
+                (p[0] - 1.).abs() <= 2. && (p[1] - 1.).abs() <= 1. && (p[2] - 1.).abs() <= 1.;
+            assert_eq!(mask[[1, i]], is_interior);
+        }
+    }
+
+    #[test]
+    fn test_fit_oriented_cuboids() {
+        // Points on the boundary and interior of a 4 m x 2 m rectangle rotated by 30 degrees.
+        let (yaw, center) = (PI / 6., [10_f32, -5.]);
+        let (sin, cos) = yaw.sin_cos();
+        let mut rng = StdRng::seed_from_u64(0);
+        let local = [(2., 1.), (-2., 1.), (-2., -1.), (2., -1.)]
+            .into_iter()
+            .chain((0..100).map(|_| (rng.gen_range(-2.0..2.0), rng.gen_range(-1.0..1.0))))
+            .collect::<Vec<(f32, f32)>>();
+        let mut points = local
+            .into_iter()
+            .map(|(x, y)| {
+                let z = rng.gen_range(0.0..1.5);
+                [
+                    cos * x - sin * y + center[0],
+                    sin * x + cos * y + center[1],
+                    z,
+                ]
+            })
+            .collect::<Vec<_>>();
+        points[0][2] = 0.;
+        points[1][2] = 1.5;
+        // A second, axis-aligned cluster and a noise point.
+        points.extend([
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 3., 1.],
+            [0., 3., 1.],
+            [50., 50., 0.],
+        ]);
+        let points = Array::from(points);
+        let cluster_ids = Array::from_iter((0..104).map(|_| 7).chain([3, 3, 3, 3, -1]));
+
+        let (ids, cuboids) = fit_oriented_cuboids(&points.view(), &cluster_ids.view());
+        assert_eq!(ids.to_vec(), vec![3, 7]);
+        let expected = [
+            [
+                0.5,
+                1.5,
+                0.5,
+                3.,
+                1.,
+                1.,
+                FRAC_PI_4.cos(),
+                0.,
+                0.,
+                FRAC_PI_4.sin(),
+            ],
+            [
+                10.,
+                -5.,
+                0.75,
+                4.,
+                2.,
+                1.5,
+                (PI / 12.).cos(),
+                0.,
+                0.,
+                (PI / 12.).sin(),
+            ],
+        ];
+        for (cuboid, expected) in cuboids.outer_iter().zip(expected) {
+            assert!(cuboid
+                .iter()
+                .zip(expected)
+                .all(|(x, y)| (x - y).abs() < 1e-4));
+        }
+
+        // Collinear points give a flat rectangle.
+        let rectangle = minimum_area_rectangle(&array![[0_f32, 0.], [0., 2.]].view()).unwrap();
+        assert_eq!((rectangle.length, rectangle.width), (2., 0.));
+        assert!((rectangle.yaw_rad - FRAC_PI_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compute_interior_points_assignment() {
+        // Two overlapping cubes centered at x = 0 and x = 1.5.
+        let cuboids = array![
+            [0., 0., 0., 2., 2., 2., 1., 0., 0., 0.],
+            [1.5, 0., 0., 2., 2., 2., 1., 0., 0., 0.]
+        ];
+        let vertices = cuboids_to_polygons(&cuboids.view());
+        let points = array![
+            [-0.5_f32, 0., 0.],
+            [0.6, 0., 0.],
+            [0.9, 0., 0.],
+            [5., 0., 0.]
+        ];
+
+        let assignment = |policy| {
+            compute_interior_points_assignment(&points.view(), &vertices.view(), policy).to_vec()
+        };
+        assert_eq!(assignment(AssignmentPolicy::Priority), vec![0, 0, 0, -1]);
+        assert_eq!(assignment(AssignmentPolicy::Nearest), vec![0, 0, 1, -1]);
+        assert_eq!(assignment(AssignmentPolicy::Unique), vec![0, -1, -1, -1]);
+
+        // Consistent with the interior points mask.
+        let mut rng = StdRng::seed_from_u64(0);
+        let points = Array::<f32, Ix2>::from_shape_fn((1000, 3), |_| rng.gen_range(-3.0..3.0));
+        let mask = compute_interior_points_mask(&points.view(), &vertices.view());
+        let assignment = compute_interior_points_assignment(
+            &points.view(),
+            &vertices.view(),
+            AssignmentPolicy::Priority,
+        );
+        for (i, j) in assignment.iter().enumerate() {
+            let expected = (0..2).find(|j| mask[[*j, i]]).map_or(-1, |j| j as i64);
+            assert_eq!(*j, expected);
+        }
+    }
+
+    #[test]
+    fn test_compute_points_in_polygons_mask() {
+        let square = array![[0., 0.], [4., 0.], [4., 4.], [0., 4.]];
+        let polygons = [
+            Polygon {
+                exterior: square.clone(),
+                holes: vec![array![[1., 1.], [3., 1.], [3., 3.], [1., 3.], [1., 1.]]],
+            },
+            Polygon::new(square + 3.),
+        ];
+        let points = array![
+            [0.5, 0.5, 10.],
+            [2., 2., 0.],
+            [3.5, 3.5, 0.],
+            [6., 6., 0.],
+            [-1., 2., 0.]
+        ];
+        let mask = compute_points_in_polygons_mask(&points.view(), &polygons);
+        assert_eq!(
+            mask,
+            array![
+                [true, false, true, false, false],
+                [false, false, true, true, false]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_points_near_polygons_mask() {
+        let polygons = [Polygon {
+            exterior: array![[0., 0.], [4., 0.], [4., 4.], [0., 4.]],
+            holes: vec![array![[1., 1.], [3., 1.], [3., 3.], [1., 3.]]],
+        }];
+        assert_eq!(polygons[0].distance(0.5, 0.5), 0.);
+        assert_eq!(polygons[0].distance(2., 2.), 1.);
+        assert_eq!(polygons[0].distance(7., 8.), 5.);
+
+        let points = array![[0.5, 0.5], [2., 2.], [5., 2.], [7., 8.]];
+        let mask = compute_points_near_polygons_mask(&points.view(), &polygons, 0.);
+        assert_eq!(mask, array![true, false, false, false]);
+        let mask = compute_points_near_polygons_mask(&points.view(), &polygons, 1.);
+        assert_eq!(mask, array![true, true, true, false]);
+    }
+
+    #[test]
+    fn test_compute_cuboid_polygon_overlap_mask() {
+        let square = array![[0., 0.], [4., 0.], [4., 4.], [0., 4.]];
+        let polygons = [
+            Polygon {
+                exterior: square.clone(),
+                holes: vec![array![[1., 1.], [3., 1.], [3., 3.], [1., 3.]]],
+            },
+            // A thin sliver crossing the footprints without containing their vertices.
+            Polygon::new(array![[-10., 6.9], [10., 6.9], [10., 7.1], [-10., 7.1]]),
+        ];
+        let cuboids = array![
+            // Inside the hole.
+            [2., 2., 0., 1., 1., 1., 1., 0., 0., 0.],
+            // Straddling the hole boundary.
+            [1., 2., 0., 1., 1., 1., 1., 0., 0., 0.],
+            // Across the sliver.
+            [0., 7., 0., 2., 2., 1., 1., 0., 0., 0.],
+            // Enclosing the square.
+            [2., 2., 0., 10., 10., 1., 1., 0., 0., 0.],
+            // Far away.
+            [20., 20., 0., 1., 1., 1., 1., 0., 0., 0.]
+        ];
+        let mask = compute_cuboid_polygon_overlap_mask(&cuboids.view(), &polygons);
+        assert_eq!(
+            mask,
+            array![
+                [false, false],
+                [true, false],
+                [false, true],
+                [true, true],
+                [false, false]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convex_hull_2d() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut points = Array::<f32, Ix2>::from_shape_fn((100, 2), |_| rng.gen_range(-1.0..1.0));
+        points.slice_mut(ndarray::s![..5, ..]).assign(&array![
+            [-2., -2.],
+            [2., -2.],
+            [2., 2.],
+            [-2., 2.],
+            [0., -2.]
+        ]);
+
+        let hull = convex_hull_2d(&points.view());
+        assert_eq!(hull, array![[-2., -2.], [2., -2.], [2., 2.], [-2., 2.]]);
+        assert_eq!(polygon_area(&hull.view()), 16.);
+    }
+
+    #[test]
+    fn test_convex_hull_3d() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut points = Array::<f32, Ix2>::from_shape_fn((100, 3), |_| rng.gen_range(-1.0..1.0));
+        for i in 0..8 {
+            let corner = [1, 2, 4].map(|bit| if i & bit == 0 { -2. } else { 2. });
+            points
+                .row_mut(10 * i)
+                .assign(&Array::from_vec(corner.to_vec()));
+        }
+
+        let faces = convex_hull_3d(&points.view());
+        assert_eq!(faces.shape()[0], 12);
+        for face in faces.outer_iter() {
+            let [a, b, c] = [0, 1, 2].map(|k| points.row(face[k]).to_owned());
+            assert!(face.iter().all(|i| i % 10 == 0 && *i < 80));
+            let (u, v) = (&b - &a, &c - &a);
+            let normal = array![
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0]
+            ];
+            assert!(points.outer_iter().all(|p| normal.dot(&(&p - &a)) <= 1e-4));
+        }
+    }
+}
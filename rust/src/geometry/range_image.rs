@@ -0,0 +1,214 @@
+//! # range_image
+//!
+//! Range-image projection of lidar sweeps.
+//!
+//! Rows correspond to laser beams ordered from the highest to the lowest elevation and columns to
+//! azimuth bins, starting behind the egovehicle and sweeping clockwise (when viewed from above).
+
+use std::f32::consts::PI;
+
+use ndarray::{s, Array, ArrayView, Ix1, Ix2, Ix3};
+use polars::prelude::{DataFrame, DataType};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    error::{Av2Error, Av2Result},
+    schema::{validate_data_frame, ColumnType, LIDAR_SCHEMA},
+    share::data_frame_to_ndarray_f32,
+};
+
+/// Channels of a range image.
+pub const RANGE_IMAGE_CHANNELS: [&str; 5] = ["range_m", "intensity", "x", "y", "z"];
+
+/// Range image configuration.
+#[derive(Clone, Debug)]
+pub struct RangeImageConfig {
+    /// Number of rows (laser beams across both lidars).
+    pub num_beams: usize,
+    /// Number of columns (azimuth bins).
+    pub num_azimuth_bins: usize,
+}
+
+impl Default for RangeImageConfig {
+    fn default() -> Self {
+        Self {
+            num_beams: 64,
+            num_azimuth_bins: 1800,
+        }
+    }
+}
+
+/// Dense range image of a sweep.
+#[derive(Clone, Debug)]
+pub struct RangeImage {
+    /// (C,H,W) image with the `RANGE_IMAGE_CHANNELS`. Empty pixels are zero.
+    pub image: Array<f32, Ix3>,
+    /// (H,W) row of the sweep projected into each pixel, or `-1` if the pixel is empty.
+    pub indices: Array<i64, Ix2>,
+}
+
+/// Project a sweep (`x`, `y`, `z`, `intensity`, and `laser_number`) into a range image.
+/// If several points fall into the same pixel, the closest one is kept.
+pub fn sweep_to_range_image(lidar: &DataFrame, cfg: &RangeImageConfig) -> Av2Result<RangeImage> {
+    let mut columns = LIDAR_SCHEMA.to_vec();
+    columns.extend([
+        ("intensity", ColumnType::Integer),
+        ("laser_number", ColumnType::Integer),
+    ]);
+    validate_data_frame("lidar", lidar, &columns)?;
+    let points = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z", "intensity"])?;
+    let laser_numbers = lidar["laser_number"]
+        .cast(&DataType::UInt32)?
+        .u32()?
+        .into_no_null_iter()
+        .map(|x| x as usize)
+        .collect::<Array<usize, Ix1>>();
+    points_to_range_image(
+        &points.slice(s![.., ..3]),
+        &points.column(3),
+        &laser_numbers.view(),
+        cfg,
+    )
+}
+
+/// Project (N,3) points with (N,) intensities and (N,) laser numbers into a range image.
+///
+/// Returns an error if the image is empty or a laser number exceeds the number of beams.
+pub fn points_to_range_image(
+    xyz: &ArrayView<f32, Ix2>,
+    intensity: &ArrayView<f32, Ix1>,
+    laser_numbers: &ArrayView<usize, Ix1>,
+    cfg: &RangeImageConfig,
+) -> Av2Result<RangeImage> {
+    let (num_rows, num_cols) = (cfg.num_beams, cfg.num_azimuth_bins);
+    if num_rows == 0 || num_cols == 0 {
+        return Err(Av2Error::invalid_parameter(
+            "cfg",
+            "The range image must have at least one row and column.",
+        ));
+    }
+    if let Some(laser_number) = laser_numbers.iter().find(|x| **x >= num_rows) {
+        return Err(Av2Error::invalid_parameter(
+            "laser_number",
+            format!("Laser number {laser_number} exceeds the number of beams ({num_rows})."),
+        ));
+    }
+    let beam_to_row = _beam_to_row(xyz, laser_numbers, num_rows);
+
+    let pixels = (0..xyz.shape()[0])
+        .into_par_iter()
+        .map(|i| {
+            let point = xyz.row(i);
+            let range_m = point.dot(&point).sqrt();
+            let azimuth = point[1].atan2(point[0]);
+            let col = (((PI - azimuth) / (2. * PI)) * num_cols as f32) as usize;
+            let row = beam_to_row[laser_numbers[i]];
+            (row, col.min(num_cols - 1), range_m)
+        })
+        .collect::<Vec<_>>();
+
+    let mut image = Array::<f32, Ix3>::zeros((RANGE_IMAGE_CHANNELS.len(), num_rows, num_cols));
+    let mut indices = Array::<i64, Ix2>::from_elem((num_rows, num_cols), -1);
+    for (i, (row, col, range_m)) in pixels.into_iter().enumerate() {
+        let index = indices[[row, col]];
+        if index >= 0 && image[[0, row, col]] <= range_m {
+            continue;
+        }
+        indices[[row, col]] = i as i64;
+        image[[0, row, col]] = range_m;
+        image[[1, row, col]] = intensity[i];
+        image.slice_mut(s![2..5, row, col]).assign(&xyz.row(i));
+    }
+    Ok(RangeImage { image, indices })
+}
+
+/// Map each laser number (below `num_beams`) to a row by sorting the beams by their mean elevation
+/// (highest first).
+fn _beam_to_row(
+    xyz: &ArrayView<f32, Ix2>,
+    laser_numbers: &ArrayView<usize, Ix1>,
+    num_beams: usize,
+) -> Vec<usize> {
+    let mut elevation_sums = vec![0.; num_beams];
+    let mut counts = vec![0; num_beams];
+    for (point, &laser_number) in xyz.outer_iter().zip(laser_numbers) {
+        let distance_xy = point[0].hypot(point[1]);
+        elevation_sums[laser_number] += point[2].atan2(distance_xy);
+        counts[laser_number] += 1;
+    }
+
+    // Beams without returns are placed at the bottom of the image.
+    let mut beams = (0..num_beams).collect::<Vec<_>>();
+    beams.sort_by(|&a, &b| {
+        let elevation = |k: usize| match counts[k] {
+            0 => f32::NEG_INFINITY,
+            n => elevation_sums[k] / n as f32,
+        };
+        elevation(b).total_cmp(&elevation(a))
+    });
+    let mut beam_to_row = vec![0; num_beams];
+    for (row, beam) in beams.into_iter().enumerate() {
+        beam_to_row[beam] = row;
+    }
+    beam_to_row
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use super::{sweep_to_range_image, RangeImageConfig};
+    use crate::error::Av2Error;
+
+    #[test]
+    fn test_sweep_to_range_image() {
+        // Two points ahead (the farther one is occluded), one to the right, and one behind.
+        let lidar = df!(
+            "x" => [10_f32, -10., 0., 20.],
+            "y" => [0_f32, 0., -5., 0.],
+            "z" => [1_f32, -1., 1., 2.],
+            "intensity" => [5_u8, 6, 7, 8],
+            "laser_number" => [0_u8, 1, 0, 0]
+        )
+        .unwrap();
+        let cfg = RangeImageConfig {
+            num_beams: 3,
+            num_azimuth_bins: 8,
+        };
+        let range_image = sweep_to_range_image(&lidar, &cfg).unwrap();
+        assert_eq!(range_image.image.shape(), [5, 3, 8]);
+
+        // Beam 0 points upwards and beam 1 downwards, while beam 2 has no returns.
+        let mut expected_indices = ndarray::Array::from_elem((3, 8), -1_i64);
+        expected_indices[[0, 4]] = 0;
+        expected_indices[[1, 0]] = 1;
+        expected_indices[[0, 6]] = 2;
+        assert_eq!(range_image.indices, expected_indices);
+
+        let pixel = range_image.image.slice(ndarray::s![.., 0, 4]).to_vec();
+        assert!((pixel[0] - 101_f32.sqrt()).abs() < 1e-5);
+        assert_eq!(pixel[1..], [5., 10., 0., 1.]);
+        let pixel = range_image.image.slice(ndarray::s![.., 1, 0]).to_vec();
+        assert_eq!(pixel[1..], [6., -10., 0., -1.]);
+        let pixel = range_image.image.slice(ndarray::s![.., 0, 6]).to_vec();
+        assert_eq!(pixel[1..], [7., 0., -5., 1.]);
+        // Empty pixels are zero.
+        let ranges = range_image.image.slice(ndarray::s![0, .., ..]);
+        assert_eq!(ranges.iter().filter(|x| **x > 0.).count(), 3);
+
+        // Laser numbers beyond the number of beams are rejected.
+        let cfg = RangeImageConfig {
+            num_beams: 1,
+            num_azimuth_bins: 8,
+        };
+        assert!(matches!(
+            sweep_to_range_image(&lidar, &cfg),
+            Err(Av2Error::InvalidParameter { .. })
+        ));
+        let lidar = lidar.drop("laser_number").unwrap();
+        assert!(matches!(
+            sweep_to_range_image(&lidar, &RangeImageConfig::default()),
+            Err(Av2Error::Schema { .. })
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+//! # raster
+//!
+//! Bird's-eye-view (BEV) rasterization.
+//!
+//! Lidar points (and optionally cuboids and map polygons) are rasterized into multi-channel grids
+//! over a rectangular extent of the xy-plane. Rows index `y` and columns index `x`, both starting
+//! from the minimum corner of the extent.
+
+use ndarray::{par_azip, s, Array, ArrayView, Axis, Ix1, Ix2, Ix3};
+use polars::prelude::DataFrame;
+
+use crate::{error::Av2Result, share::data_frame_to_ndarray_f32};
+
+use super::polytope::{_cuboid_to_bev_footprint, Polygon};
+
+/// Number of lidar channels: occupancy, maximum height, and mean intensity.
+pub const NUM_LIDAR_CHANNELS: usize = 3;
+
+/// BEV raster configuration.
+#[derive(Clone, Debug)]
+pub struct BevRasterConfig {
+    /// Side length of a (square) cell (in meters).
+    pub resolution_m: f32,
+    /// Minimum `x` and `y` of the extent (in meters).
+    pub min_range_m: [f32; 2],
+    /// Maximum `x` and `y` of the extent (in meters).
+    pub max_range_m: [f32; 2],
+    /// Minimum height of a rasterized point (in meters).
+    pub min_height_m: f32,
+    /// Maximum height of a rasterized point (in meters).
+    pub max_height_m: f32,
+}
+
+impl Default for BevRasterConfig {
+    fn default() -> Self {
+        Self {
+            resolution_m: 0.2,
+            min_range_m: [-51.2, -51.2],
+            max_range_m: [51.2, 51.2],
+            min_height_m: -3.,
+            max_height_m: 5.,
+        }
+    }
+}
+
+impl BevRasterConfig {
+    /// Number of rows (`y`) and columns (`x`) of the grid.
+    pub fn grid_size(&self) -> (usize, usize) {
+        let num_cells = |axis: usize| {
+            ((self.max_range_m[axis] - self.min_range_m[axis]) / self.resolution_m).ceil() as usize
+        };
+        (num_cells(1), num_cells(0))
+    }
+
+    /// Cell `(row, column)` containing `(x, y)`, if it is inside the extent.
+    #[inline]
+    pub fn cell(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        let (num_rows, num_columns) = self.grid_size();
+        let column = ((x - self.min_range_m[0]) / self.resolution_m).floor();
+        let row = ((y - self.min_range_m[1]) / self.resolution_m).floor();
+        let is_inside =
+            (0. ..num_columns as f32).contains(&column) && (0. ..num_rows as f32).contains(&row);
+        is_inside.then_some((row as usize, column as usize))
+    }
+
+    /// Center `(x, y)` of cell `(row, column)`.
+    #[inline]
+    pub fn cell_center(&self, row: usize, column: usize) -> (f32, f32) {
+        (
+            self.min_range_m[0] + (column as f32 + 0.5) * self.resolution_m,
+            self.min_range_m[1] + (row as f32 + 0.5) * self.resolution_m,
+        )
+    }
+}
+
+/// Rasterize a sweep with `x`, `y`, `z`, and `intensity` columns into a (3,H,W) grid.
+/// See `rasterize_points`.
+pub fn rasterize_lidar(lidar: &DataFrame, cfg: &BevRasterConfig) -> Av2Result<Array<f32, Ix3>> {
+    let points = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z", "intensity"])?;
+    Ok(rasterize_points(&points.view(), cfg))
+}
+
+/// Rasterize (N,4) points `(x, y, z, intensity)` into a (3,H,W) grid of occupancy, maximum
+/// height, and mean intensity. Empty cells are zero.
+pub fn rasterize_points(points: &ArrayView<f32, Ix2>, cfg: &BevRasterConfig) -> Array<f32, Ix3> {
+    let (num_rows, num_columns) = cfg.grid_size();
+    let mut counts = Array::<u32, Ix2>::zeros((num_rows, num_columns));
+    let mut max_height = Array::<f32, Ix2>::from_elem((num_rows, num_columns), f32::MIN);
+    let mut intensity_sum = Array::<f32, Ix2>::zeros((num_rows, num_columns));
+    for point in points.outer_iter() {
+        let (x, y, z, intensity) = (point[0], point[1], point[2], point[3]);
+        if !(cfg.min_height_m..=cfg.max_height_m).contains(&z) {
+            continue;
+        }
+        let Some(cell) = cfg.cell(x, y) else {
+            continue;
+        };
+        counts[cell] += 1;
+        max_height[cell] = max_height[cell].max(z);
+        intensity_sum[cell] += intensity;
+    }
+
+    let mut raster = Array::<f32, Ix3>::zeros((NUM_LIDAR_CHANNELS, num_rows, num_columns));
+    par_azip!((mut cell in raster.lanes_mut(Axis(0)), &count in &counts, &max_z in &max_height, &intensity_sum in &intensity_sum) {
+        if count > 0 {
+            cell[0] = 1.;
+            cell[1] = max_z;
+            cell[2] = intensity_sum / count as f32;
+        }
+    });
+    raster
+}
+
+/// Rasterize the bird's-eye-view footprints of (N,10) cuboids into a (H,W) binary mask.
+/// A cell is filled if its center is inside a footprint.
+pub fn rasterize_cuboids(cuboids: &ArrayView<f32, Ix2>, cfg: &BevRasterConfig) -> Array<f32, Ix2> {
+    let polygons = cuboids
+        .outer_iter()
+        .map(|cuboid| Polygon::new(_cuboid_to_bev_footprint(&cuboid)))
+        .collect::<Vec<_>>();
+    rasterize_polygons(&polygons, cfg)
+}
+
+/// Rasterize polygons (e.g., drivable areas) into a (H,W) binary mask.
+/// A cell is filled if its center is inside a polygon.
+pub fn rasterize_polygons(polygons: &[Polygon], cfg: &BevRasterConfig) -> Array<f32, Ix2> {
+    let (num_rows, num_columns) = cfg.grid_size();
+    let mut mask = Array::<f32, Ix2>::zeros((num_rows, num_columns));
+    for polygon in polygons {
+        let Some((min_cell, max_cell)) = _exterior_cell_bounds(&polygon.exterior.view(), cfg)
+        else {
+            continue;
+        };
+        let mut window = mask.slice_mut(s![min_cell.0..=max_cell.0, min_cell.1..=max_cell.1]);
+        for ((row, column), value) in window.indexed_iter_mut() {
+            let (x, y) = cfg.cell_center(min_cell.0 + row, min_cell.1 + column);
+            if polygon.contains(x, y) {
+                *value = 1.;
+            }
+        }
+    }
+    mask
+}
+
+/// Rasterize (N,4) points into a (3+C,H,W) grid: the lidar channels of `rasterize_points`,
+/// followed by a cuboid mask (if `cuboids` is provided) and a polygon mask (if `polygons` is
+/// provided).
+pub fn rasterize_bev(
+    points: &ArrayView<f32, Ix2>,
+    cuboids: Option<&ArrayView<f32, Ix2>>,
+    polygons: Option<&[Polygon]>,
+    cfg: &BevRasterConfig,
+) -> Array<f32, Ix3> {
+    let mut channels = rasterize_points(points, cfg)
+        .outer_iter()
+        .map(|channel| channel.to_owned())
+        .collect::<Vec<_>>();
+    if let Some(cuboids) = cuboids {
+        channels.push(rasterize_cuboids(cuboids, cfg));
+    }
+    if let Some(polygons) = polygons {
+        channels.push(rasterize_polygons(polygons, cfg));
+    }
+    let views = channels
+        .iter()
+        .map(|channel| channel.view())
+        .collect::<Vec<_>>();
+    ndarray::stack(Axis(0), &views).unwrap()
+}
+
+/// Inclusive `(row, column)` bounds of the cells overlapping the bounding box of a (N,2+) ring,
+/// or `None` if it lies outside the extent.
+fn _exterior_cell_bounds(
+    ring: &ArrayView<f32, Ix2>,
+    cfg: &BevRasterConfig,
+) -> Option<((usize, usize), (usize, usize))> {
+    let (num_rows, num_columns) = cfg.grid_size();
+    if ring.shape()[0] == 0 || num_rows == 0 || num_columns == 0 {
+        return None;
+    }
+    let bounds = |column: ArrayView<f32, Ix1>| {
+        column
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), x| {
+                (min.min(*x), max.max(*x))
+            })
+    };
+    let (min_x, max_x) = bounds(ring.column(0));
+    let (min_y, max_y) = bounds(ring.column(1));
+    let to_index = |value: f32, axis: usize, num_cells: usize| {
+        let index = ((value - cfg.min_range_m[axis]) / cfg.resolution_m).floor();
+        index.clamp(0., (num_cells - 1) as f32) as usize
+    };
+    let is_outside = max_x < cfg.min_range_m[0]
+        || min_x > cfg.max_range_m[0]
+        || max_y < cfg.min_range_m[1]
+        || min_y > cfg.max_range_m[1];
+    (!is_outside).then(|| {
+        (
+            (
+                to_index(min_y, 1, num_rows),
+                to_index(min_x, 0, num_columns),
+            ),
+            (
+                to_index(max_y, 1, num_rows),
+                to_index(max_x, 0, num_columns),
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s};
+
+    use super::{rasterize_bev, BevRasterConfig};
+    use crate::geometry::polytope::Polygon;
+
+    #[test]
+    fn test_rasterize_bev() {
+        let cfg = BevRasterConfig {
+            resolution_m: 1.,
+            min_range_m: [-2., -2.],
+            max_range_m: [2., 2.],
+            min_height_m: -1.,
+            max_height_m: 2.,
+        };
+        assert_eq!(cfg.grid_size(), (4, 4));
+
+        // Two points share a cell, one is too high, and one is out of range.
+        let points = array![
+            [0.5_f32, -1.5, 0.5, 10.],
+            [0.7, -1.2, 1.5, 20.],
+            [0.5, 0.5, 3., 30.],
+            [5., 0., 0., 40.],
+        ];
+        // A 2 m x 2 m cuboid centered at the origin.
+        let cuboids = array![[0_f32, 0., 0., 2., 2., 1., 1., 0., 0., 0.]];
+        // The left half of the extent.
+        let polygons = vec![Polygon::new(array![
+            [-2_f32, -2.],
+            [0., -2.],
+            [0., 2.],
+            [-2., 2.]
+        ])];
+
+        let raster = rasterize_bev(&points.view(), Some(&cuboids.view()), Some(&polygons), &cfg);
+        assert_eq!(raster.shape(), &[5, 4, 4]);
+        assert_eq!(raster[[0, 0, 2]], 1.);
+        assert_eq!(raster.slice(s![0, .., ..]).sum(), 1.);
+        assert_eq!(raster[[1, 0, 2]], 1.5);
+        assert_eq!(raster[[2, 0, 2]], 15.);
+
+        let cuboid_mask = raster.slice(s![3, .., ..]);
+        assert_eq!(cuboid_mask.sum(), 4.);
+        assert_eq!(cuboid_mask.slice(s![1..3, 1..3]).sum(), 4.);
+
+        let polygon_mask = raster.slice(s![4, .., ..]);
+        assert_eq!(polygon_mask.slice(s![.., ..2]).sum(), 8.);
+        assert_eq!(polygon_mask.slice(s![.., 2..]).sum(), 0.);
+    }
+}
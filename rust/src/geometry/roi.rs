@@ -0,0 +1,240 @@
+//! # roi
+//!
+//! Region-of-interest filtering of lidar sweeps and cuboids in the egovehicle frame.
+//!
+//! Points are kept if they lie within the region, and cuboids are kept if their centers do.
+//! The region can also be restricted to the map's drivable area (plus a margin), in which case
+//! cuboids are kept if any of their vertices are within it, as in `av2.evaluation`.
+
+use ndarray::Axis;
+use polars::{
+    lazy::dsl::{col, lit, Expr},
+    prelude::{
+        BooleanChunked, DataFrame, DataType, IntoLazy, LazyFrame, NewChunkedArray, PolarsResult,
+    },
+};
+
+use super::{
+    polytope::{compute_points_near_polygons_mask, cuboids_to_polygons, Polygon},
+    se3::SE3,
+};
+use crate::{
+    error::Av2Result, share::data_frame_to_ndarray_f32, structures::cuboid::CUBOID_COLUMNS,
+};
+
+/// Region of interest. Unbounded sides are `None` and every bound is inclusive.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RoiConfig {
+    /// Maximum bird's-eye-view distance from the origin (in meters).
+    pub max_range_m: Option<f32>,
+    /// `(min, max)` x-coordinates (in meters).
+    pub x_range_m: Option<(f32, f32)>,
+    /// `(min, max)` y-coordinates (in meters).
+    pub y_range_m: Option<(f32, f32)>,
+    /// `(min, max)` z-coordinates (in meters).
+    pub z_range_m: Option<(f32, f32)>,
+    /// Maximum bird's-eye-view distance to the map's drivable areas (in meters). Requires the
+    /// log's map and egovehicle poses.
+    pub drivable_area_margin_m: Option<f32>,
+}
+
+impl RoiConfig {
+    /// Returns `true` if the region is unbounded in the egovehicle frame (regardless of the
+    /// drivable area).
+    pub fn is_unbounded(&self) -> bool {
+        *self
+            == Self {
+                drivable_area_margin_m: self.drivable_area_margin_m,
+                ..Default::default()
+            }
+    }
+
+    /// Boolean expression selecting the rows whose `(x, y, z)` columns lie within the region.
+    pub fn expr(&self, x: &str, y: &str, z: &str) -> Expr {
+        let [x, y, z] = [x, y, z].map(|name| col(name).cast(DataType::Float32));
+        let mut masks = vec![];
+        if let Some(max_range_m) = self.max_range_m {
+            let range2 = x.clone() * x.clone() + y.clone() * y.clone();
+            masks.push(range2.lt_eq(lit(max_range_m * max_range_m)));
+        }
+        for (value, range) in [
+            (x, self.x_range_m),
+            (y, self.y_range_m),
+            (z, self.z_range_m),
+        ] {
+            if let Some((min, max)) = range {
+                masks.push(value.clone().gt_eq(lit(min)).and(value.lt_eq(lit(max))));
+            }
+        }
+        masks
+            .into_iter()
+            .reduce(|mask, x| mask.and(x))
+            .unwrap_or(lit(true))
+    }
+
+    /// Keep the points of a lazy sweep with `x`, `y`, and `z` columns within the region.
+    pub fn filter_lidar_lazy(&self, lidar: LazyFrame) -> LazyFrame {
+        match self.is_unbounded() {
+            true => lidar,
+            false => lidar.filter(self.expr("x", "y", "z")),
+        }
+    }
+
+    /// Keep the cuboids of a lazy frame with `tx_m`, `ty_m`, and `tz_m` columns whose centers
+    /// are within the region.
+    pub fn filter_cuboids_lazy(&self, cuboids: LazyFrame) -> LazyFrame {
+        match self.is_unbounded() {
+            true => cuboids,
+            false => cuboids.filter(self.expr("tx_m", "ty_m", "tz_m")),
+        }
+    }
+}
+
+/// Keep the points of a sweep with `x`, `y`, and `z` columns within the region.
+pub fn filter_lidar(lidar: &DataFrame, cfg: &RoiConfig) -> PolarsResult<DataFrame> {
+    cfg.filter_lidar_lazy(lidar.clone().lazy()).collect()
+}
+
+/// Keep the cuboids with `tx_m`, `ty_m`, and `tz_m` columns whose centers are within the region.
+pub fn filter_cuboids(cuboids: &DataFrame, cfg: &RoiConfig) -> PolarsResult<DataFrame> {
+    cfg.filter_cuboids_lazy(cuboids.clone().lazy()).collect()
+}
+
+/// Keep the points of a sweep with `x`, `y`, and `z` columns within `margin_m` of the
+/// `drivable_areas`. `city_se3_lidar` maps the sweep's frame to the city frame.
+pub fn filter_lidar_to_drivable_area(
+    lidar: &DataFrame,
+    drivable_areas: &[Polygon],
+    city_se3_lidar: &SE3,
+    margin_m: f32,
+) -> Av2Result<DataFrame> {
+    let xyz = data_frame_to_ndarray_f32(lidar.clone(), vec!["x", "y", "z"])?;
+    let xyz_city = city_se3_lidar.transform_from(&xyz.view());
+    let mask = compute_points_near_polygons_mask(&xyz_city.view(), drivable_areas, margin_m);
+    Ok(lidar.filter(&BooleanChunked::from_iter_values(
+        "in_drivable_area",
+        mask.into_iter(),
+    ))?)
+}
+
+/// Keep the cuboids with a vertex within `margin_m` of the `drivable_areas`. `city_se3_ego` maps
+/// the cuboids' frame to the city frame.
+pub fn filter_cuboids_to_drivable_area(
+    cuboids: &DataFrame,
+    drivable_areas: &[Polygon],
+    city_se3_ego: &SE3,
+    margin_m: f32,
+) -> Av2Result<DataFrame> {
+    let cuboids_ego = data_frame_to_ndarray_f32(cuboids.clone(), CUBOID_COLUMNS.to_vec())?;
+    let vertices = cuboids_to_polygons(&cuboids_ego.view());
+    let num_cuboids = vertices.shape()[0];
+    let vertices = vertices.into_shape((num_cuboids * 8, 3)).unwrap();
+    let vertices_city = city_se3_ego.transform_from(&vertices.view());
+    let mask = compute_points_near_polygons_mask(&vertices_city.view(), drivable_areas, margin_m)
+        .into_shape((num_cuboids, 8))
+        .unwrap();
+    let mask = mask.map_axis(Axis(1), |x| x.iter().any(|&m| m));
+    Ok(cuboids.filter(&BooleanChunked::from_iter_values(
+        "in_drivable_area",
+        mask.into_iter(),
+    ))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use ndarray::array;
+
+    use super::{
+        filter_cuboids, filter_cuboids_to_drivable_area, filter_lidar,
+        filter_lidar_to_drivable_area, RoiConfig,
+    };
+    use crate::geometry::{polytope::Polygon, se3::SE3};
+
+    #[test]
+    fn test_roi() {
+        let lidar = df!(
+            "x" => [0_f32, 3., 30., -1., 1.],
+            "y" => [0_f32, 4., 0., 0., 1.],
+            "z" => [0_f32, 0., 0., 0., 5.]
+        )
+        .unwrap();
+        let cfg = RoiConfig {
+            max_range_m: Some(5.),
+            x_range_m: Some((-0.5, 50.)),
+            z_range_m: Some((-1., 3.)),
+            ..Default::default()
+        };
+        let filtered = filter_lidar(&lidar, &cfg).unwrap();
+        assert_eq!(
+            filtered["x"].f32().unwrap().to_vec(),
+            vec![Some(0.), Some(3.)]
+        );
+        assert_eq!(
+            filter_lidar(&lidar, &RoiConfig::default())
+                .unwrap()
+                .height(),
+            5
+        );
+
+        let cuboids = df!(
+            "tx_m" => [1_f64, 10.],
+            "ty_m" => [0_f64, 0.],
+            "tz_m" => [0_f64, 0.]
+        )
+        .unwrap();
+        let filtered = filter_cuboids(&cuboids, &cfg).unwrap();
+        assert_eq!(filtered["tx_m"].f64().unwrap().to_vec(), vec![Some(1.)]);
+    }
+
+    #[test]
+    fn test_drivable_area_roi() {
+        let drivable_areas = [Polygon::new(array![
+            [100., -5.],
+            [200., -5.],
+            [200., 5.],
+            [100., 5.]
+        ])];
+        // The egovehicle is at the start of the drivable area.
+        let city_se3_ego =
+            SE3::from_quat_wxyz(&array![1., 0., 0., 0.].view(), &array![100., 0., 0.].view())
+                .unwrap();
+        let lidar = df!(
+            "x" => [1_f32, -1., -3., 10.],
+            "y" => [0_f32, 0., 0., 6.],
+            "z" => [0_f32, 0., 0., 0.]
+        )
+        .unwrap();
+        let filtered =
+            filter_lidar_to_drivable_area(&lidar, &drivable_areas, &city_se3_ego, 0.).unwrap();
+        assert_eq!(filtered["x"].f32().unwrap().to_vec(), vec![Some(1.)]);
+        let filtered =
+            filter_lidar_to_drivable_area(&lidar, &drivable_areas, &city_se3_ego, 2.).unwrap();
+        assert_eq!(
+            filtered["x"].f32().unwrap().to_vec(),
+            vec![Some(1.), Some(-1.), Some(10.)]
+        );
+
+        // The second cuboid's center is outside of the drivable area, but its front is within.
+        let cuboids = df!(
+            "tx_m" => [10_f64, -2., -10.],
+            "ty_m" => [0_f64, 0., 0.],
+            "tz_m" => [0_f64, 0., 0.],
+            "length_m" => [4_f64, 5., 4.],
+            "width_m" => [2_f64, 2., 2.],
+            "height_m" => [1.5_f64, 1.5, 1.5],
+            "qw" => [1_f64, 1., 1.],
+            "qx" => [0_f64, 0., 0.],
+            "qy" => [0_f64, 0., 0.],
+            "qz" => [0_f64, 0., 0.]
+        )
+        .unwrap();
+        let filtered =
+            filter_cuboids_to_drivable_area(&cuboids, &drivable_areas, &city_se3_ego, 0.).unwrap();
+        assert_eq!(
+            filtered["tx_m"].f64().unwrap().to_vec(),
+            vec![Some(10.), Some(-2.)]
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! # scalar
+//!
+//! Floating-point scalars of the geometry routines.
+//!
+//! Routines generic over `GeometryFloat` run in the precision of their input arrays: `f32` for
+//! sensor data, or `f64` for UTM-scale city coordinates (e.g., map alignment), where `f32` only
+//! resolves to about half a meter.
+
+use std::{
+    fmt::{Debug, Display},
+    iter::Sum,
+};
+
+use ndarray::{LinalgScalar, ScalarOperand};
+use num_traits::{Float, FloatConst, NumAssign, NumCast};
+
+/// Floating-point type of the geometry routines (`f32` or `f64`).
+pub trait GeometryFloat:
+    Float
+    + FloatConst
+    + NumAssign
+    + LinalgScalar
+    + ScalarOperand
+    + Sum
+    + Debug
+    + Default
+    + Display
+    + Send
+    + Sync
+    + 'static
+{
+    /// Convert a constant (e.g., `T::lit(0.5)`).
+    fn lit(value: f64) -> Self;
+
+    /// Convert a value of another precision.
+    fn cast<U: GeometryFloat>(value: U) -> Self {
+        <Self as NumCast>::from(value).unwrap()
+    }
+}
+
+impl GeometryFloat for f32 {
+    fn lit(value: f64) -> Self {
+        value as f32
+    }
+}
+
+impl GeometryFloat for f64 {
+    fn lit(value: f64) -> Self {
+        value
+    }
+}
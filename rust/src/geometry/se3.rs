@@ -2,26 +2,93 @@
 //!
 //! Special Euclidean Group 3.
 
-use ndarray::ArrayView2;
-use ndarray::{s, Array1, Array2};
+use ndarray::{array, par_azip, s, Array1, Array2, ArrayView1, ArrayView2};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    scalar::GeometryFloat,
+    so3::{_mat3_to_quat, _quat_multiply, _quat_to_mat3},
+};
+use crate::error::{Av2Error, Av2Result};
 
 /// Special Euclidean Group 3 (SE(3)).
-/// Rigid transformation parameterized by a rotation and translation in $R^3$.
-#[derive(Clone, Debug)]
-pub struct SE3 {
-    /// (3,3) Orthonormal rotation matrix.
-    pub rotation: Array2<f32>,
+/// Rigid transformation parameterized by a rotation quaternion and translation in $R^3$.
+/// Transformations are `f32` by default; use `SE3<f64>` for UTM-scale city coordinates.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SE3<T = f32> {
+    /// (4,) Scalar-first unit quaternion of the rotation.
+    pub quat_wxyz: Array1<T>,
     /// (3,) Translation vector.
-    pub translation: Array1<f32>,
+    pub translation: Array1<T>,
 }
 
-impl SE3 {
+impl<T: GeometryFloat> SE3<T> {
+    /// Construct the identity transformation.
+    pub fn identity() -> SE3<T> {
+        SE3 {
+            quat_wxyz: array![T::one(), T::zero(), T::zero(), T::zero()],
+            translation: Array1::zeros(3),
+        }
+    }
+
+    /// Construct an SE(3) from a (4,) scalar-first quaternion and a (3,) translation.
+    /// The quaternion is normalized (with `w >= 0`); a zero or non-finite quaternion is an error.
+    pub fn from_quat_wxyz(
+        quat_wxyz: &ArrayView1<T>,
+        translation: &ArrayView1<T>,
+    ) -> Av2Result<SE3<T>> {
+        let quat = std::array::from_fn(|i| quat_wxyz[i]);
+        let norm = quat.iter().map(|x| *x * *x).sum::<T>().sqrt();
+        if !(norm > T::zero() && norm.is_finite()) {
+            return Err(Av2Error::invalid_parameter(
+                "quat_wxyz",
+                format!("Expected a non-zero, finite quaternion, got {quat:?}."),
+            ));
+        }
+        Ok(SE3 {
+            quat_wxyz: Array1::from_vec(_canonical_quat(quat).to_vec()),
+            translation: translation.to_owned(),
+        })
+    }
+
+    /// Construct an SE(3) from a (3,3) rotation matrix and a (3,) translation.
+    pub fn from_rotation(rotation: &ArrayView2<T>, translation: &ArrayView1<T>) -> SE3<T> {
+        SE3 {
+            quat_wxyz: _mat3_to_quat(rotation),
+            translation: translation.to_owned(),
+        }
+    }
+
+    /// Construct an SE(3) from a (4,4) homogeneous transformation matrix.
+    pub fn from_transform_matrix(transform_matrix: &ArrayView2<T>) -> SE3<T> {
+        SE3::from_rotation(
+            &transform_matrix.slice(s![..3, ..3]),
+            &transform_matrix.slice(s![..3, 3]),
+        )
+    }
+
+    /// Construct an SE(3) from a (7,) pose row ordered as `POSE_COLUMNS` (tx_m, ty_m, tz_m, qw, qx, qy, qz).
+    /// See `from_quat_wxyz`.
+    pub fn from_pose(pose: &ArrayView1<T>) -> Av2Result<SE3<T>> {
+        SE3::from_quat_wxyz(&pose.slice(s![3..7]), &pose.slice(s![..3]))
+    }
+
+    /// Get the (4,) scalar-first quaternion associated with the rotation.
+    pub fn quat_wxyz(&self) -> Array1<T> {
+        self.quat_wxyz.clone()
+    }
+
+    /// Get the (3,3) rotation matrix associated with the rotation.
+    pub fn rotation(&self) -> Array2<T> {
+        _quat_to_mat3(&self.quat_wxyz.view())
+    }
+
     /// Get the (4,4) homogeneous transformation matrix associated with the rigid transformation.
-    pub fn transform_matrix(&self) -> Array2<f32> {
+    pub fn transform_matrix(&self) -> Array2<T> {
         let mut transform_matrix = Array2::eye(4);
         transform_matrix
             .slice_mut(s![..3, ..3])
-            .assign(&self.rotation);
+            .assign(&self.rotation());
         transform_matrix
             .slice_mut(s![..3, 3])
             .assign(&self.translation);
@@ -29,32 +96,141 @@ impl SE3 {
     }
 
     /// Transform the point cloud from its reference from to the SE(3) destination.
-    pub fn transform_from(&self, point_cloud: &ArrayView2<f32>) -> Array2<f32> {
-        point_cloud.dot(&self.rotation.t()) + &self.translation
+    pub fn transform_from(&self, point_cloud: &ArrayView2<T>) -> Array2<T> {
+        point_cloud.dot(&self.rotation().t()) + &self.translation
+    }
+
+    /// Transform (N,10) cuboids from their reference frame to the SE(3) destination.
+    /// Centers are transformed as points and orientations are left-multiplied by the rotation.
+    pub fn transform_cuboids(&self, cuboids: &ArrayView2<T>) -> Array2<T> {
+        let rotation = self.rotation();
+        let quat_wxyz = self._quat_wxyz();
+        let mut transformed_cuboids = cuboids.to_owned();
+        par_azip!((mut tc in transformed_cuboids.outer_iter_mut(), c in cuboids.outer_iter()) {
+            let translation = rotation.dot(&c.slice(s![..3])) + &self.translation;
+            let orientation = _quat_multiply(&quat_wxyz, &std::array::from_fn(|i| c[6 + i]));
+            tc.slice_mut(s![..3]).assign(&translation);
+            tc.slice_mut(s![6..10]).assign(&Array1::from_vec(_canonical_quat(orientation).to_vec()));
+        });
+        transformed_cuboids
     }
 
     /// Invert the SE(3) transformation.
-    pub fn inverse(&self) -> SE3 {
-        let rotation = self.rotation.t().as_standard_layout().to_owned();
-        let translation = rotation.dot(&(-&self.translation));
+    pub fn inverse(&self) -> SE3<T> {
+        let [w, x, y, z] = self._quat_wxyz();
+        let quat_wxyz = Array1::from_vec(_canonical_quat([w, -x, -y, -z]).to_vec());
+        let translation = _quat_to_mat3(&quat_wxyz.view()).dot(&self.translation.mapv(|x| -x));
         Self {
-            rotation,
+            quat_wxyz,
             translation,
         }
     }
 
     /// Compose (right multiply) an SE(3) with another SE(3).
-    pub fn compose(&self, right_se3: &SE3) -> SE3 {
-        let chained_transform_matrix = self.transform_matrix().dot(&right_se3.transform_matrix());
+    pub fn compose(&self, right_se3: &SE3<T>) -> SE3<T> {
+        let quat_wxyz = _quat_multiply(&self._quat_wxyz(), &right_se3._quat_wxyz());
+        SE3 {
+            quat_wxyz: Array1::from_vec(_canonical_quat(quat_wxyz).to_vec()),
+            translation: self.rotation().dot(&right_se3.translation) + &self.translation,
+        }
+    }
+
+    /// Convert the transformation to another precision.
+    pub fn cast<U: GeometryFloat>(&self) -> SE3<U> {
         SE3 {
-            rotation: chained_transform_matrix
-                .slice(s![..3, ..3])
-                .as_standard_layout()
-                .to_owned(),
-            translation: chained_transform_matrix
-                .slice(s![..3, 3])
-                .as_standard_layout()
-                .to_owned(),
+            quat_wxyz: self.quat_wxyz.mapv(U::cast),
+            translation: self.translation.mapv(U::cast),
         }
     }
+
+    fn _quat_wxyz(&self) -> [T; 4] {
+        std::array::from_fn(|i| self.quat_wxyz[i])
+    }
+}
+
+/// Unit quaternion of the same rotation with `w >= 0`.
+fn _canonical_quat<T: GeometryFloat>(quat_wxyz: [T; 4]) -> [T; 4] {
+    let norm = quat_wxyz.iter().map(|x| *x * *x).sum::<T>().sqrt();
+    let sign = match quat_wxyz[0] < T::zero() {
+        true => -T::one(),
+        false => T::one(),
+    };
+    quat_wxyz.map(|x| sign * x / norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::SE3;
+    use crate::geometry::so3::_yaw_to_quat;
+
+    #[test]
+    fn test_compose_and_inverse() {
+        let a =
+            SE3::from_quat_wxyz(&_yaw_to_quat(0.5_f64).view(), &array![1., 2., 3.].view()).unwrap();
+        let b = SE3::from_quat_wxyz(
+            &array![0.5_f64, 0.5, -0.5, 0.5].view(),
+            &array![-4., 0.5, 1.].view(),
+        )
+        .unwrap();
+
+        // Quaternion composition agrees with the product of the transformation matrices.
+        let a_b = a.compose(&b);
+        assert!(a_b
+            .transform_matrix()
+            .abs_diff_eq(&a.transform_matrix().dot(&b.transform_matrix()), 1e-12));
+        assert!(a_b.quat_wxyz[0] >= 0.);
+        assert!(SE3::from_transform_matrix(&a_b.transform_matrix().view())
+            .transform_matrix()
+            .abs_diff_eq(&a_b.transform_matrix(), 1e-12));
+
+        let identity = a.compose(&a.inverse());
+        assert!(identity
+            .transform_matrix()
+            .abs_diff_eq(&SE3::<f64>::identity().transform_matrix(), 1e-12));
+        let points = array![[1., 0., 0.], [0.25, -3., 7.]];
+        assert!(b
+            .inverse()
+            .transform_from(&b.transform_from(&points.view()).view())
+            .abs_diff_eq(&points, 1e-12));
+    }
+
+    #[test]
+    fn test_non_unit_quaternion() {
+        // A scaled quaternion with `w < 0` is the same rotation as its canonical unit quaternion.
+        let unit = SE3::from_quat_wxyz(
+            &array![0.5_f64, 0.5, -0.5, 0.5].view(),
+            &array![1., -2., 3.].view(),
+        )
+        .unwrap();
+        let scaled = SE3::from_quat_wxyz(
+            &array![-1.5_f64, -1.5, 1.5, -1.5].view(),
+            &array![1., -2., 3.].view(),
+        )
+        .unwrap();
+        assert!(scaled.quat_wxyz.abs_diff_eq(&unit.quat_wxyz, 1e-12));
+        assert!(scaled
+            .transform_matrix()
+            .abs_diff_eq(&unit.transform_matrix(), 1e-12));
+
+        // The inverse is canonical and round-trips to the original transformation.
+        let inverse = scaled.inverse();
+        assert!(inverse.quat_wxyz[0] >= 0.);
+        assert!((inverse.quat_wxyz.dot(&inverse.quat_wxyz) - 1.).abs() < 1e-12);
+        let round_trip = inverse.inverse();
+        assert!(round_trip.quat_wxyz.abs_diff_eq(&unit.quat_wxyz, 1e-12));
+        assert!(round_trip.translation.abs_diff_eq(&unit.translation, 1e-12));
+        let points = array![[1., 0., 0.], [0.25, -3., 7.]];
+        assert!(inverse
+            .transform_from(&scaled.transform_from(&points.view()).view())
+            .abs_diff_eq(&points, 1e-12));
+
+        let err = SE3::from_quat_wxyz(
+            &array![0_f32, 0., 0., 0.].view(),
+            &array![0., 0., 0.].view(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Invalid `quat_wxyz`"));
+    }
 }
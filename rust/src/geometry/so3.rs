@@ -4,38 +4,50 @@
 
 use std::f32::consts::PI;
 
-use ndarray::{par_azip, s, Array, Array2, ArrayView, Ix1, Ix2, Ix3};
+use ndarray::{par_azip, s, Array, Array2, ArrayView, ArrayViewMut, Ix1, Ix2, Ix3};
 use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::{IndexedParallelIterator, ParallelIterator, ParallelSlice, ParallelSliceMut};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use super::scalar::GeometryFloat;
+
+#[cfg(feature = "dataframe")]
+pub use super::dataframe::repair_cuboid_quaternions;
+
+/// Number of rows processed per task by the batched quaternion operations.
+const ROWS_PER_CHUNK: usize = 4096;
+
+/// Scalar-first quaternion columns of cuboid and pose `DataFrame`s.
+pub const QUAT_COLUMNS: [&str; 4] = ["qw", "qx", "qy", "qz"];
 
 /// Convert a quaternion in scalar-first format to a 3x3 rotation matrix.
 /// Parallelized for batch processing.
-pub fn quat_to_mat3(quat_wxyz: &ArrayView<f32, Ix2>) -> Array<f32, Ix3> {
+pub fn quat_to_mat3<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix2>) -> Array<T, Ix3> {
     let num_quats = quat_wxyz.shape()[0];
-    let mut mat3 = Array::<f32, Ix3>::zeros((num_quats, 3, 3));
-    par_azip!((mut m in mat3.outer_iter_mut(), q in quat_wxyz.outer_iter()) {
-        m.assign(&_quat_to_mat3(&q));
-    });
-    mat3
+    quat_to_mat3_row_major(quat_wxyz)
+        .into_shape((num_quats, 3, 3))
+        .unwrap()
 }
 
 /// Convert a quaternion in scalar-first format to a 3x3 rotation matrix.
-pub fn _quat_to_mat3(quat_wxyz: &ArrayView<f32, Ix1>) -> Array<f32, Ix2> {
+pub fn _quat_to_mat3<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix1>) -> Array<T, Ix2> {
     let w = quat_wxyz[0];
     let x = quat_wxyz[1];
     let y = quat_wxyz[2];
     let z = quat_wxyz[3];
+    let (one, two) = (T::one(), T::lit(2.));
 
-    let e_00 = 1. - 2. * y.powi(2) - 2. * z.powi(2);
-    let e_01: f32 = 2. * x * y - 2. * z * w;
-    let e_02: f32 = 2. * x * z + 2. * y * w;
+    let e_00 = one - two * y.powi(2) - two * z.powi(2);
+    let e_01 = two * x * y - two * z * w;
+    let e_02 = two * x * z + two * y * w;
 
-    let e_10 = 2. * x * y + 2. * z * w;
-    let e_11 = 1. - 2. * x.powi(2) - 2. * z.powi(2);
-    let e_12 = 2. * y * z - 2. * x * w;
+    let e_10 = two * x * y + two * z * w;
+    let e_11 = one - two * x.powi(2) - two * z.powi(2);
+    let e_12 = two * y * z - two * x * w;
 
-    let e_20 = 2. * x * z - 2. * y * w;
-    let e_21 = 2. * y * z + 2. * x * w;
-    let e_22 = 1. - 2. * x.powi(2) - 2. * y.powi(2);
+    let e_20 = two * x * z - two * y * w;
+    let e_21 = two * y * z + two * x * w;
+    let e_22 = one - two * x.powi(2) - two * y.powi(2);
 
     // Safety: We will always have nine elements.
     unsafe {
@@ -46,63 +58,39 @@ pub fn _quat_to_mat3(quat_wxyz: &ArrayView<f32, Ix1>) -> Array<f32, Ix2> {
     }
 }
 
-/// Convert a 3x3 rotation matrix to a scalar-first quaternion.
+/// Convert (N,3,3) rotation matrices to (N,4) scalar-first quaternions.
 /// Parallelized for batch processing.
-pub fn mat3_to_quat(mat3: &ArrayView<f32, Ix3>) -> Array<f32, Ix2> {
+///
+/// # Panics
+///
+/// If `mat3` is not (N,3,3).
+pub fn mat3_to_quat<T: GeometryFloat>(mat3: &ArrayView<T, Ix3>) -> Array<T, Ix2> {
     let num_transformations = mat3.shape()[0];
-    let mut quat_wxyz = Array::<f32, Ix2>::zeros((num_transformations, 4));
-    par_azip!((mut q in quat_wxyz.outer_iter_mut(), m in mat3.outer_iter()) {
-        q.assign(&_mat3_to_quat(&m))
-    });
-    quat_wxyz
+    assert!(
+        mat3.shape()[1..] == [3, 3],
+        "Expected (N,3,3) rotation matrices, got {:?}.",
+        mat3.shape()
+    );
+    let mat3 = mat3.as_standard_layout();
+    let mat3 = mat3
+        .view()
+        .into_shape((num_transformations, 9))
+        .expect("Standard layout (N,3,3) matrices are contiguous (N,9) rows.");
+    mat3_row_major_to_quat(&mat3)
 }
 
 /// Convert a 3x3 rotation matrix to a scalar-first quaternion.
-pub fn _mat3_to_quat(mat3: &ArrayView<f32, Ix2>) -> Array<f32, Ix1> {
-    let trace = mat3[[0, 0]] + mat3[[1, 1]] + mat3[[2, 2]];
-    let mut quat_wxyz = if trace > 0.0 {
-        let s = 0.5 / f32::sqrt(trace + 1.0);
-        let qw = 0.25 / s;
-        let qx = (mat3[[2, 1]] - mat3[[1, 2]]) * s;
-        let qy = (mat3[[0, 2]] - mat3[[2, 0]]) * s;
-        let qz = (mat3[[1, 0]] - mat3[[0, 1]]) * s;
-        Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz])
-    } else if mat3[[0, 0]] > mat3[[1, 1]] && mat3[[0, 0]] > mat3[[2, 2]] {
-        let s = 2.0 * f32::sqrt(1.0 + mat3[[0, 0]] - mat3[[1, 1]] - mat3[[2, 2]]);
-        let qw = (mat3[[2, 1]] - mat3[[1, 2]]) / s;
-        let qx = 0.25 * s;
-        let qy = (mat3[[0, 1]] + mat3[[1, 0]]) / s;
-        let qz = (mat3[[0, 2]] + mat3[[2, 0]]) / s;
-        Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz])
-    } else if mat3[[1, 1]] > mat3[[2, 2]] {
-        let s = 2.0 * f32::sqrt(1.0 + mat3[[1, 1]] - mat3[[0, 0]] - mat3[[2, 2]]);
-        let qw = (mat3[[0, 2]] - mat3[[2, 0]]) / s;
-        let qx = (mat3[[0, 1]] + mat3[[1, 0]]) / s;
-        let qy = 0.25 * s;
-        let qz = (mat3[[1, 2]] + mat3[[2, 1]]) / s;
-        Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz])
-    } else {
-        let s = 2.0 * f32::sqrt(1.0 + mat3[[2, 2]] - mat3[[0, 0]] - mat3[[1, 1]]);
-        let qw = (mat3[[1, 0]] - mat3[[0, 1]]) / s;
-        let qx = (mat3[[0, 2]] + mat3[[2, 0]]) / s;
-        let qy = (mat3[[1, 2]] + mat3[[2, 1]]) / s;
-        let qz = 0.25 * s;
-        Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz])
-    };
-
-    // Canonicalize the quaternion.
-    if quat_wxyz[0] < 0.0 {
-        quat_wxyz *= -1.0;
-    }
-    quat_wxyz
+pub fn _mat3_to_quat<T: GeometryFloat>(mat3: &ArrayView<T, Ix2>) -> Array<T, Ix1> {
+    let mat3_row_major = std::array::from_fn(|k| mat3[[k / 3, k % 3]]);
+    Array::from_vec(_mat3_row_major_to_quat(&mat3_row_major).to_vec())
 }
 
 /// Convert a scalar-first quaternion to yaw.
 /// In the Argoverse 2 coordinate system, this is counter-clockwise rotation about the +z axis.
 /// Parallelized for batch processing.
-pub fn quat_to_yaw(quat_wxyz: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+pub fn quat_to_yaw<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
     let num_quats = quat_wxyz.shape()[0];
-    let mut yaws_rad = Array::<f32, Ix2>::zeros((num_quats, 1));
+    let mut yaws_rad = Array::<T, Ix2>::zeros((num_quats, 1));
     par_azip!((mut y in yaws_rad.outer_iter_mut(), q in quat_wxyz.outer_iter()) {
         y[0] = _quat_to_yaw(&q);
     });
@@ -111,19 +99,20 @@ pub fn quat_to_yaw(quat_wxyz: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
 
 /// Convert a scalar-first quaternion to yaw.
 /// In the Argoverse 2 coordinate system, this is counter-clockwise rotation about the +z axis.
-pub fn _quat_to_yaw(quat_wxyz: &ArrayView<f32, Ix1>) -> f32 {
+pub fn _quat_to_yaw<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix1>) -> T {
     let (qw, qx, qy, qz) = (quat_wxyz[0], quat_wxyz[1], quat_wxyz[2], quat_wxyz[3]);
-    let siny_cosp = 2. * (qw * qz + qx * qy);
-    let cosy_cosp = 1. - 2. * (qy * qy + qz * qz);
+    let two = T::lit(2.);
+    let siny_cosp = two * (qw * qz + qx * qy);
+    let cosy_cosp = T::one() - two * (qy * qy + qz * qz);
     siny_cosp.atan2(cosy_cosp)
 }
 
 /// Convert a scalar-first quaternion to yaw.
 /// In the Argoverse 2 coordinate system, this is counter-clockwise rotation about the +z axis.
 /// Parallelized for batch processing.
-pub fn yaw_to_quat(yaw_rad: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
+pub fn yaw_to_quat<T: GeometryFloat>(yaw_rad: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
     let num_yaws = yaw_rad.shape()[0];
-    let mut quat_wxyz = Array::<f32, Ix2>::zeros((num_yaws, 4));
+    let mut quat_wxyz = Array::<T, Ix2>::zeros((num_yaws, 4));
     par_azip!((mut q in quat_wxyz.outer_iter_mut(), y in yaw_rad.outer_iter()) {
         q.assign(&_yaw_to_quat(y[0]));
     });
@@ -131,10 +120,267 @@ pub fn yaw_to_quat(yaw_rad: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
 }
 
 /// Convert rotation about the z-axis to a scalar-first quaternion.
-pub fn _yaw_to_quat(yaw_rad: f32) -> Array<f32, Ix1> {
-    let qw = f32::cos(0.5 * yaw_rad);
-    let qz = f32::sin(0.5 * yaw_rad);
-    Array::<f32, Ix1>::from_vec(vec![qw, 0.0, 0.0, qz])
+pub fn _yaw_to_quat<T: GeometryFloat>(yaw_rad: T) -> Array<T, Ix1> {
+    let (qz, qw) = (T::lit(0.5) * yaw_rad).sin_cos();
+    Array::<T, Ix1>::from_vec(vec![qw, T::zero(), T::zero(), qz])
+}
+
+/// Convert (N,4) scalar-first quaternions to (N,3) intrinsic z-y'-x'' Euler angles
+/// `(roll, pitch, yaw)` (in radians).
+/// Parallelized and vectorized for batch processing.
+pub fn quat_to_euler<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    let (one, two) = (T::one(), T::lit(2.));
+    _map_rows(quat_wxyz, |&[w, x, y, z]: &[T; 4]| {
+        let roll = (two * (w * x + y * z)).atan2(one - two * (x * x + y * y));
+        // Clamp to avoid NaNs at gimbal lock.
+        let pitch = num_traits::clamp(two * (w * y - z * x), -one, one).asin();
+        let yaw = (two * (w * z + x * y)).atan2(one - two * (y * y + z * z));
+        [roll, pitch, yaw]
+    })
+}
+
+/// Convert (N,3) intrinsic z-y'-x'' Euler angles `(roll, pitch, yaw)` (in radians) to (N,4)
+/// scalar-first quaternions.
+/// Parallelized and vectorized for batch processing.
+pub fn euler_to_quat<T: GeometryFloat>(euler_rad: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    let half = T::lit(0.5);
+    _map_rows(euler_rad, |&[roll, pitch, yaw]: &[T; 3]| {
+        let (sr, cr) = (half * roll).sin_cos();
+        let (sp, cp) = (half * pitch).sin_cos();
+        let (sy, cy) = (half * yaw).sin_cos();
+        let quat_wxyz = [
+            cr * cp * cy + sr * sp * sy,
+            sr * cp * cy - cr * sp * sy,
+            cr * sp * cy + sr * cp * sy,
+            cr * cp * sy - sr * sp * cy,
+        ];
+
+        // Canonicalize the quaternion.
+        match quat_wxyz[0] < T::zero() {
+            true => quat_wxyz.map(|x| -x),
+            false => quat_wxyz,
+        }
+    })
+}
+
+/// Spherical linear interpolation between (N,4) scalar-first quaternions at (N,) fractions `t`.
+/// Parallelized for batch processing.
+pub fn slerp<T: GeometryFloat>(
+    quat_wxyz_0: &ArrayView<T, Ix2>,
+    quat_wxyz_1: &ArrayView<T, Ix2>,
+    t: &ArrayView<T, Ix1>,
+) -> Array<T, Ix2> {
+    let num_quats = quat_wxyz_0.shape()[0];
+    let mut quat_wxyz = Array::<T, Ix2>::zeros((num_quats, 4));
+    par_azip!((mut q in quat_wxyz.outer_iter_mut(), q0 in quat_wxyz_0.outer_iter(), q1 in quat_wxyz_1.outer_iter(), t in t) {
+        q.assign(&_slerp(&q0, &q1, *t));
+    });
+    quat_wxyz
+}
+
+/// Spherical linear interpolation between two scalar-first quaternions.
+/// `t = 0` returns `quat_wxyz_0` and `t = 1` returns `quat_wxyz_1` (up to sign).
+pub fn _slerp<T: GeometryFloat>(
+    quat_wxyz_0: &ArrayView<T, Ix1>,
+    quat_wxyz_1: &ArrayView<T, Ix1>,
+    t: T,
+) -> Array<T, Ix1> {
+    let one = T::one();
+    let mut dot = quat_wxyz_0.dot(quat_wxyz_1);
+
+    // Take the shortest path on the hypersphere.
+    let mut quat_wxyz_1 = quat_wxyz_1.to_owned();
+    if dot < T::zero() {
+        quat_wxyz_1.mapv_inplace(|x| -x);
+        dot = -dot;
+    }
+
+    // Fall back to normalized linear interpolation for nearly parallel quaternions.
+    if dot > T::lit(0.9995) {
+        let quat_wxyz = &quat_wxyz_0.to_owned() * (one - t) + &quat_wxyz_1 * t;
+        let norm = quat_wxyz.dot(&quat_wxyz).sqrt();
+        quat_wxyz / norm
+    } else {
+        let theta = num_traits::clamp(dot, -one, one).acos();
+        let sin_theta = theta.sin();
+        let w0 = ((one - t) * theta).sin() / sin_theta;
+        let w1 = (t * theta).sin() / sin_theta;
+        &quat_wxyz_0.to_owned() * w0 + &quat_wxyz_1 * w1
+    }
+}
+
+/// Hamilton product of (N,4) scalar-first quaternions `quat_wxyz_0 * quat_wxyz_1`.
+/// Parallelized and vectorized for batch processing.
+pub fn quat_multiply<T: GeometryFloat>(
+    quat_wxyz_0: &ArrayView<T, Ix2>,
+    quat_wxyz_1: &ArrayView<T, Ix2>,
+) -> Array<T, Ix2> {
+    _zip_rows(quat_wxyz_0, quat_wxyz_1, _quat_multiply)
+}
+
+/// Conjugate (N,4) scalar-first quaternions, i.e., invert unit quaternions.
+/// Parallelized and vectorized for batch processing.
+pub fn quat_conjugate<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    _map_rows(quat_wxyz, |&[w, x, y, z]: &[T; 4]| [w, -x, -y, -z])
+}
+
+/// Rotate (N,3) points by the (N,4) scalar-first unit quaternions of the same row.
+/// Parallelized and vectorized for batch processing.
+pub fn quat_rotate_points<T: GeometryFloat>(
+    quat_wxyz: &ArrayView<T, Ix2>,
+    points_xyz: &ArrayView<T, Ix2>,
+) -> Array<T, Ix2> {
+    _zip_rows(quat_wxyz, points_xyz, |q: &[T; 4], p: &[T; 3]| {
+        let m = _quat_to_mat3_row_major(q);
+        [
+            m[0] * p[0] + m[1] * p[1] + m[2] * p[2],
+            m[3] * p[0] + m[4] * p[1] + m[5] * p[2],
+            m[6] * p[0] + m[7] * p[1] + m[8] * p[2],
+        ]
+    })
+}
+
+/// Convert (N,4) scalar-first quaternions to (N,9) row-major 3x3 rotation matrices.
+/// Parallelized and vectorized for batch processing.
+pub fn quat_to_mat3_row_major<T: GeometryFloat>(quat_wxyz: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    _map_rows(quat_wxyz, _quat_to_mat3_row_major)
+}
+
+/// Convert (N,9) row-major 3x3 rotation matrices to (N,4) scalar-first quaternions with `w >= 0`.
+/// Parallelized and vectorized for batch processing.
+pub fn mat3_row_major_to_quat<T: GeometryFloat>(mat3: &ArrayView<T, Ix2>) -> Array<T, Ix2> {
+    _map_rows(mat3, _mat3_row_major_to_quat)
+}
+
+#[inline]
+pub(crate) fn _quat_multiply<T: GeometryFloat>(q0: &[T; 4], q1: &[T; 4]) -> [T; 4] {
+    let [w0, x0, y0, z0] = *q0;
+    let [w1, x1, y1, z1] = *q1;
+    [
+        w0 * w1 - x0 * x1 - y0 * y1 - z0 * z1,
+        w0 * x1 + x0 * w1 + y0 * z1 - z0 * y1,
+        w0 * y1 - x0 * z1 + y0 * w1 + z0 * x1,
+        w0 * z1 + x0 * y1 - y0 * x1 + z0 * w1,
+    ]
+}
+
+#[inline]
+fn _quat_to_mat3_row_major<T: GeometryFloat>(quat_wxyz: &[T; 4]) -> [T; 9] {
+    let [w, x, y, z] = *quat_wxyz;
+    let (one, two) = (T::one(), T::lit(2.));
+    [
+        one - two * (y * y + z * z),
+        two * (x * y - z * w),
+        two * (x * z + y * w),
+        two * (x * y + z * w),
+        one - two * (x * x + z * z),
+        two * (y * z - x * w),
+        two * (x * z - y * w),
+        two * (y * z + x * w),
+        one - two * (x * x + y * y),
+    ]
+}
+
+#[inline]
+fn _mat3_row_major_to_quat<T: GeometryFloat>(m: &[T; 9]) -> [T; 4] {
+    let (one, two, quarter) = (T::one(), T::lit(2.), T::lit(0.25));
+    let trace = m[0] + m[4] + m[8];
+    let quat_wxyz = if trace > T::zero() {
+        let s = T::lit(0.5) / (trace + one).sqrt();
+        [
+            quarter / s,
+            (m[7] - m[5]) * s,
+            (m[2] - m[6]) * s,
+            (m[3] - m[1]) * s,
+        ]
+    } else if m[0] > m[4] && m[0] > m[8] {
+        let s = two * (one + m[0] - m[4] - m[8]).sqrt();
+        [
+            (m[7] - m[5]) / s,
+            quarter * s,
+            (m[1] + m[3]) / s,
+            (m[2] + m[6]) / s,
+        ]
+    } else if m[4] > m[8] {
+        let s = two * (one + m[4] - m[0] - m[8]).sqrt();
+        [
+            (m[2] - m[6]) / s,
+            (m[1] + m[3]) / s,
+            quarter * s,
+            (m[5] + m[7]) / s,
+        ]
+    } else {
+        let s = two * (one + m[8] - m[0] - m[4]).sqrt();
+        [
+            (m[3] - m[1]) / s,
+            (m[2] + m[6]) / s,
+            (m[5] + m[7]) / s,
+            quarter * s,
+        ]
+    };
+
+    // Canonicalize the quaternion.
+    match quat_wxyz[0] < T::zero() {
+        true => quat_wxyz.map(|x| -x),
+        false => quat_wxyz,
+    }
+}
+
+/// Apply `f` to every row of a (N,I) array.
+/// Rows are processed as fixed-size arrays over contiguous memory so the compiler can vectorize `f`.
+fn _map_rows<T: GeometryFloat, const I: usize, const O: usize>(
+    input: &ArrayView<T, Ix2>,
+    f: impl Fn(&[T; I]) -> [T; O] + Sync,
+) -> Array<T, Ix2> {
+    assert_eq!(
+        input.shape()[1],
+        I,
+        "Expected (N,{I}) rows, got {:?}.",
+        input.shape()
+    );
+    let input = input.as_standard_layout();
+    let mut output = Array::<T, Ix2>::zeros((input.shape()[0], O));
+    output
+        .as_slice_mut()
+        .unwrap()
+        .par_chunks_mut(O * ROWS_PER_CHUNK)
+        .zip(input.as_slice().unwrap().par_chunks(I * ROWS_PER_CHUNK))
+        .for_each(|(output, input)| {
+            for (o, i) in output.chunks_exact_mut(O).zip(input.chunks_exact(I)) {
+                o.copy_from_slice(&f(i.try_into().unwrap()));
+            }
+        });
+    output
+}
+
+/// Apply `f` to every pair of rows of a (N,I) and a (N,J) array.
+/// Rows are processed as fixed-size arrays over contiguous memory so the compiler can vectorize `f`.
+fn _zip_rows<T: GeometryFloat, const I: usize, const J: usize, const O: usize>(
+    input_0: &ArrayView<T, Ix2>,
+    input_1: &ArrayView<T, Ix2>,
+    f: impl Fn(&[T; I], &[T; J]) -> [T; O] + Sync,
+) -> Array<T, Ix2> {
+    assert_eq!(input_0.shape()[1], I);
+    assert_eq!(input_1.shape()[1], J);
+    assert_eq!(input_0.shape()[0], input_1.shape()[0]);
+    let (input_0, input_1) = (input_0.as_standard_layout(), input_1.as_standard_layout());
+    let mut output = Array::<T, Ix2>::zeros((input_0.shape()[0], O));
+    output
+        .as_slice_mut()
+        .unwrap()
+        .par_chunks_mut(O * ROWS_PER_CHUNK)
+        .zip(input_0.as_slice().unwrap().par_chunks(I * ROWS_PER_CHUNK))
+        .zip(input_1.as_slice().unwrap().par_chunks(J * ROWS_PER_CHUNK))
+        .for_each(|((output, input_0), input_1)| {
+            let rows = output
+                .chunks_exact_mut(O)
+                .zip(input_0.chunks_exact(I))
+                .zip(input_1.chunks_exact(J));
+            for ((o, i), j) in rows {
+                o.copy_from_slice(&f(i.try_into().unwrap(), j.try_into().unwrap()));
+            }
+        });
+    output
 }
 
 /// Reflect orientation across the x-axis.
@@ -171,6 +417,61 @@ pub fn reflect_translation_y(xyz_m: &ArrayView<f32, Ix2>) -> Array<f32, Ix2> {
     augmented_xyz_m
 }
 
+/// Quaternions whose norm deviates from one by more than this are reported as non-normalized.
+pub const QUAT_NORM_TOLERANCE: f32 = 1e-3;
+
+/// Defects of a scalar-first quaternion.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum QuatDefect {
+    /// At least one component is NaN or infinite. Repaired to the identity.
+    NonFinite,
+    /// The norm is (numerically) zero. Repaired to the identity.
+    Zero,
+    /// The norm deviates from one. Repaired by normalizing.
+    NonNormalized,
+    /// The scalar component is negative. Repaired by negating (which preserves the rotation).
+    NegativeScalar,
+}
+
+/// Repair (N,4) scalar-first quaternions.
+/// Returns the unit quaternions with a non-negative scalar component and the defects of each row.
+/// Parallelized for batch processing.
+pub fn repair_quat_wxyz(
+    quat_wxyz: &ArrayView<f32, Ix2>,
+) -> (Array<f32, Ix2>, Vec<Vec<QuatDefect>>) {
+    let mut repaired = quat_wxyz.to_owned();
+    let mut defects = Array::from_elem(quat_wxyz.shape()[0], vec![]);
+    par_azip!((mut q in repaired.outer_iter_mut(), d in &mut defects) {
+        *d = _repair_quat_wxyz(&mut q);
+    });
+    (repaired, defects.into_raw_vec())
+}
+
+/// Repair a scalar-first quaternion in place and return its defects.
+fn _repair_quat_wxyz(quat_wxyz: &mut ArrayViewMut<f32, Ix1>) -> Vec<QuatDefect> {
+    let mut defects = vec![];
+    if quat_wxyz.iter().any(|x| !x.is_finite()) {
+        defects.push(QuatDefect::NonFinite);
+    } else {
+        let norm = quat_wxyz.dot(quat_wxyz).sqrt();
+        if norm < f32::EPSILON {
+            defects.push(QuatDefect::Zero);
+        } else if (norm - 1.).abs() > QUAT_NORM_TOLERANCE {
+            defects.push(QuatDefect::NonNormalized);
+            *quat_wxyz /= norm;
+        }
+    }
+    if !defects.is_empty() && defects[0] != QuatDefect::NonNormalized {
+        quat_wxyz.assign(&Array::from_vec(vec![1., 0., 0., 0.]));
+    }
+    if quat_wxyz[0] < 0. {
+        defects.push(QuatDefect::NegativeScalar);
+        *quat_wxyz *= -1.;
+    }
+    defects
+}
+
 /// Sample a random quaternion.
 pub fn sample_random_quat_wxyz() -> Array<f32, Ix1> {
     let distribution = StandardNormal;
@@ -191,7 +492,122 @@ pub fn sample_random_quat_wxyz() -> Array<f32, Ix1> {
 
 #[cfg(test)]
 mod tests {
-    use super::{_mat3_to_quat, _quat_to_mat3, sample_random_quat_wxyz};
+    use std::f32::consts::FRAC_PI_2;
+
+    use ndarray::{array, s, stack, Array2, Axis};
+
+    use crate::geometry::se3::SE3;
+
+    use super::{
+        _mat3_to_quat, _quat_to_mat3, _slerp, _yaw_to_quat, euler_to_quat, mat3_row_major_to_quat,
+        quat_conjugate, quat_multiply, quat_rotate_points, quat_to_euler, quat_to_mat3,
+        quat_to_mat3_row_major, quat_to_yaw, repair_quat_wxyz, sample_random_quat_wxyz, QuatDefect,
+    };
+
+    #[test]
+    fn test_euler_round_trip() {
+        let euler_rad = array![[0.1_f32, -0.2, 0.3], [0., 0., -3.], [-1., 1.2, 2.5]];
+        let quat_wxyz = euler_to_quat(&euler_rad.view());
+        assert!(quat_to_euler(&quat_wxyz.view())
+            .iter()
+            .zip(euler_rad.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-5));
+
+        // Yaw agrees with the yaw-only conversions.
+        let yaw_rad = quat_to_yaw(&quat_wxyz.view());
+        assert!(yaw_rad
+            .iter()
+            .zip(euler_rad.column(2).iter())
+            .all(|(x, y)| (x - y).abs() < 1e-5));
+        let yaw_only = array![[0_f32, 0., -3.]];
+        assert!(euler_to_quat(&yaw_only.view())
+            .iter()
+            .zip(_yaw_to_quat(-3.).iter())
+            .all(|(x, y)| (x - y).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_batched_quat_ops() {
+        let quat_wxyz = stack(
+            Axis(0),
+            &(0..10)
+                .map(|_| sample_random_quat_wxyz())
+                .collect::<Vec<_>>()
+                .iter()
+                .map(|q| q.view())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        // q * q^-1 is the identity.
+        let identity = quat_multiply(&quat_wxyz.view(), &quat_conjugate(&quat_wxyz.view()).view());
+        for q in identity.outer_iter() {
+            assert!((q[0].abs() - 1.).abs() < 1e-5);
+            assert!(q.slice(s![1..]).iter().all(|x| x.abs() < 1e-5));
+        }
+
+        // The row-major matrices match the 3x3 matrices and invert back to the quaternions.
+        let mat3 = quat_to_mat3_row_major(&quat_wxyz.view());
+        let expected = quat_to_mat3(&quat_wxyz.view());
+        assert!(mat3
+            .iter()
+            .zip(expected.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-6));
+        let round_trip = mat3_row_major_to_quat(&mat3.view());
+        assert!(round_trip
+            .iter()
+            .zip(quat_wxyz.iter())
+            .all(|(x, y)| (x - y).abs() < 1e-4));
+
+        // Rotating a point matches q * p * q^-1.
+        let points_xyz = array![[1_f32, 2., 3.]]
+            .broadcast((10, 3))
+            .unwrap()
+            .to_owned();
+        let rotated = quat_rotate_points(&quat_wxyz.view(), &points_xyz.view());
+        let mut points_wxyz = Array2::<f32>::zeros((10, 4));
+        points_wxyz.slice_mut(s![.., 1..]).assign(&points_xyz);
+        let expected = quat_multiply(
+            &quat_multiply(&quat_wxyz.view(), &points_wxyz.view()).view(),
+            &quat_conjugate(&quat_wxyz.view()).view(),
+        );
+        assert!(rotated
+            .iter()
+            .zip(expected.slice(s![.., 1..]).iter())
+            .all(|(x, y)| (x - y).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_repair_quat_wxyz() {
+        let quat_wxyz = array![
+            [1., 0., 0., 0.],
+            [0., 0., 0., 0.],
+            [f32::NAN, 0., 0., 1.],
+            [-2., 0., 0., 0.],
+            [-0.6, 0., 0., 0.8]
+        ];
+        let (repaired, defects) = repair_quat_wxyz(&quat_wxyz.view());
+        assert_eq!(
+            repaired,
+            array![
+                [1., 0., 0., 0.],
+                [1., 0., 0., 0.],
+                [1., 0., 0., 0.],
+                [1., 0., 0., 0.],
+                [0.6, 0., 0., -0.8]
+            ]
+        );
+        assert_eq!(
+            defects,
+            vec![
+                vec![],
+                vec![QuatDefect::Zero],
+                vec![QuatDefect::NonFinite],
+                vec![QuatDefect::NonNormalized, QuatDefect::NegativeScalar],
+                vec![QuatDefect::NegativeScalar]
+            ]
+        );
+    }
 
     #[test]
     fn test_quat_to_mat3_round_trip() {
@@ -204,4 +620,37 @@ mod tests {
             assert!(quat_wxyz.abs_diff_eq(&_quat_wxyz, epsilon));
         }
     }
+
+    #[test]
+    fn test_slerp_yaw() {
+        let epsilon = 1e-6;
+        let quat_wxyz_0 = _yaw_to_quat(0.);
+        let quat_wxyz_1 = _yaw_to_quat(FRAC_PI_2);
+        for t in [0., 0.25, 0.5, 1.] {
+            let quat_wxyz = _slerp(&quat_wxyz_0.view(), &quat_wxyz_1.view(), t);
+            assert!(quat_wxyz.abs_diff_eq(&_yaw_to_quat(t * FRAC_PI_2), epsilon));
+        }
+    }
+
+    #[test]
+    fn test_f64_city_scale_round_trip() {
+        // UTM-scale translation, where `f32` only resolves to about half a meter.
+        let city_se3_ego = SE3 {
+            quat_wxyz: _yaw_to_quat(0.3_f64),
+            translation: array![4.5e6_f64, 5.4e6, 12.],
+        };
+        let points_ego = array![[1.25_f64, -3.5, 0.75], [40., 2., -1.]];
+        let points_city = city_se3_ego.transform_from(&points_ego.view());
+        let round_trip = city_se3_ego.inverse().transform_from(&points_city.view());
+        assert!(round_trip.abs_diff_eq(&points_ego, 1e-6));
+
+        // The same transform in `f32` drifts by centimeters or more.
+        let city_se3_ego_f32 = city_se3_ego.cast::<f32>();
+        let points_city_f32 =
+            city_se3_ego_f32.transform_from(&points_ego.mapv(|x| x as f32).view());
+        assert!(points_city_f32
+            .iter()
+            .zip(points_city.iter())
+            .any(|(x, y)| (*x as f64 - y).abs() > 1e-2));
+    }
 }
@@ -0,0 +1,242 @@
+//! # voxelize
+//!
+//! Voxelization of lidar sweeps.
+//!
+//! Fixed-grid (hard) voxelization caps the number of voxels and points per voxel, as used by
+//! PointPillars and VoxelNet. Dynamic voxelization keeps every in-range point and only returns its
+//! voxel assignment.
+
+use std::collections::HashMap;
+
+use ndarray::{s, Array, ArrayView, Ix1, Ix2, Ix3};
+use polars::prelude::DataFrame;
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+
+use crate::{error::Av2Result, share::data_frame_to_ndarray_f32};
+
+/// Voxelization configuration.
+#[derive(Clone, Debug)]
+pub struct VoxelizationConfig {
+    /// Voxel size along x, y, and z (in meters).
+    pub voxel_size_m: [f32; 3],
+    /// Minimum x, y, and z of the voxelized region (in meters).
+    pub min_range_m: [f32; 3],
+    /// Maximum x, y, and z of the voxelized region (in meters).
+    pub max_range_m: [f32; 3],
+    /// Maximum number of points kept per voxel (fixed-grid only).
+    pub max_points_per_voxel: usize,
+    /// Maximum number of voxels kept (fixed-grid only).
+    pub max_voxels: usize,
+}
+
+impl Default for VoxelizationConfig {
+    fn default() -> Self {
+        Self {
+            voxel_size_m: [0.1, 0.1, 0.2],
+            min_range_m: [-76.8, -76.8, -3.],
+            max_range_m: [76.8, 76.8, 5.],
+            max_points_per_voxel: 10,
+            max_voxels: 120000,
+        }
+    }
+}
+
+impl VoxelizationConfig {
+    /// Number of voxels along x, y, and z.
+    pub fn grid_size(&self) -> [usize; 3] {
+        [0, 1, 2].map(|i| {
+            ((self.max_range_m[i] - self.min_range_m[i]) / self.voxel_size_m[i]).round() as usize
+        })
+    }
+}
+
+/// Fixed-grid voxelization output.
+#[derive(Clone, Debug)]
+pub struct Voxels {
+    /// (V,3) voxel coordinates (x, y, z indices into the grid).
+    pub coordinates: Array<usize, Ix2>,
+    /// (V,) number of points kept in each voxel.
+    pub num_points: Array<usize, Ix1>,
+    /// (V,P,F) zero-padded point features, where P is the maximum number of points per voxel.
+    pub features: Array<f32, Ix3>,
+}
+
+/// Dynamic voxelization output.
+#[derive(Clone, Debug)]
+pub struct DynamicVoxels {
+    /// (V,3) voxel coordinates (x, y, z indices into the grid).
+    pub coordinates: Array<usize, Ix2>,
+    /// (V,) number of points in each voxel.
+    pub num_points: Array<usize, Ix1>,
+    /// (N,) voxel index of each point, or `-1` if the point is outside of the voxelized region.
+    pub point_to_voxel: Array<i64, Ix1>,
+}
+
+/// Voxelize the `columns` of a lidar sweep onto a fixed grid. The first three columns must be the
+/// point coordinates (e.g., `["x", "y", "z", "intensity"]`).
+pub fn voxelize_lidar(
+    lidar: &DataFrame,
+    columns: Vec<&str>,
+    cfg: &VoxelizationConfig,
+) -> Av2Result<Voxels> {
+    let points = data_frame_to_ndarray_f32(lidar.clone(), columns)?;
+    Ok(voxelize_points(&points.view(), cfg))
+}
+
+/// Voxelize the `columns` of a lidar sweep dynamically. The first three columns must be the point
+/// coordinates.
+pub fn voxelize_lidar_dynamic(
+    lidar: &DataFrame,
+    columns: Vec<&str>,
+    cfg: &VoxelizationConfig,
+) -> Av2Result<DynamicVoxels> {
+    let points = data_frame_to_ndarray_f32(lidar.clone(), columns)?;
+    Ok(voxelize_points_dynamic(&points.view(), cfg))
+}
+
+/// Voxelize (N,F) points onto a fixed grid where the first three features are the coordinates.
+///
+/// Voxels are ordered by their first point. Points which exceed `max_points_per_voxel`, or which
+/// fall into a new voxel once `max_voxels` has been reached, are dropped.
+pub fn voxelize_points(points: &ArrayView<f32, Ix2>, cfg: &VoxelizationConfig) -> Voxels {
+    let num_features = points.shape()[1];
+    let voxel_coordinates = _voxel_coordinates(points, cfg);
+
+    let mut voxel_index: HashMap<[usize; 3], usize> = HashMap::new();
+    let mut coordinates = vec![];
+    let mut point_indices: Vec<Vec<usize>> = vec![];
+    for (i, coordinate) in voxel_coordinates.into_iter().enumerate() {
+        let Some(coordinate) = coordinate else {
+            continue;
+        };
+        let j = match voxel_index.get(&coordinate) {
+            Some(j) => *j,
+            None if coordinates.len() < cfg.max_voxels => {
+                voxel_index.insert(coordinate, coordinates.len());
+                coordinates.push(coordinate);
+                point_indices.push(vec![]);
+                coordinates.len() - 1
+            }
+            None => continue,
+        };
+        if point_indices[j].len() < cfg.max_points_per_voxel {
+            point_indices[j].push(i);
+        }
+    }
+
+    let num_voxels = coordinates.len();
+    let mut features =
+        Array::<f32, Ix3>::zeros((num_voxels, cfg.max_points_per_voxel, num_features));
+    for (mut voxel_features, indices) in features.outer_iter_mut().zip(point_indices.iter()) {
+        for (k, i) in indices.iter().enumerate() {
+            voxel_features.row_mut(k).assign(&points.row(*i));
+        }
+    }
+    Voxels {
+        coordinates: _coordinates_to_ndarray(&coordinates),
+        num_points: point_indices.iter().map(|x| x.len()).collect(),
+        features,
+    }
+}
+
+/// Voxelize (N,F) points dynamically where the first three features are the coordinates.
+/// Every in-range point is assigned to a voxel; voxels are ordered by their first point.
+pub fn voxelize_points_dynamic(
+    points: &ArrayView<f32, Ix2>,
+    cfg: &VoxelizationConfig,
+) -> DynamicVoxels {
+    let voxel_coordinates = _voxel_coordinates(points, cfg);
+
+    let mut voxel_index: HashMap<[usize; 3], usize> = HashMap::new();
+    let mut coordinates = vec![];
+    let mut num_points = vec![];
+    let point_to_voxel = voxel_coordinates
+        .into_iter()
+        .map(|coordinate| match coordinate {
+            Some(coordinate) => {
+                let j = *voxel_index.entry(coordinate).or_insert_with(|| {
+                    coordinates.push(coordinate);
+                    num_points.push(0);
+                    coordinates.len() - 1
+                });
+                num_points[j] += 1;
+                j as i64
+            }
+            None => -1,
+        })
+        .collect();
+    DynamicVoxels {
+        coordinates: _coordinates_to_ndarray(&coordinates),
+        num_points: Array::from_vec(num_points),
+        point_to_voxel,
+    }
+}
+
+/// Compute the voxel coordinates of each point in parallel (`None` if out of range).
+fn _voxel_coordinates(
+    points: &ArrayView<f32, Ix2>,
+    cfg: &VoxelizationConfig,
+) -> Vec<Option<[usize; 3]>> {
+    let grid_size = cfg.grid_size();
+    let xyz = points.slice(s![.., ..3]);
+    (0..xyz.shape()[0])
+        .into_par_iter()
+        .map(|i| {
+            let point = xyz.row(i);
+            let mut coordinate = [0; 3];
+            for k in 0..3 {
+                let index = ((point[k] - cfg.min_range_m[k]) / cfg.voxel_size_m[k]).floor();
+                if !(index >= 0. && (index as usize) < grid_size[k]) {
+                    return None;
+                }
+                coordinate[k] = index as usize;
+            }
+            Some(coordinate)
+        })
+        .collect()
+}
+
+fn _coordinates_to_ndarray(coordinates: &[[usize; 3]]) -> Array<usize, Ix2> {
+    Array::from_shape_vec(
+        (coordinates.len(), 3),
+        coordinates.iter().flatten().copied().collect(),
+    )
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::{voxelize_points, voxelize_points_dynamic, VoxelizationConfig};
+
+    #[test]
+    fn test_voxelize_points() {
+        let cfg = VoxelizationConfig {
+            voxel_size_m: [1., 1., 1.],
+            min_range_m: [0., 0., 0.],
+            max_range_m: [4., 4., 4.],
+            max_points_per_voxel: 2,
+            max_voxels: 2,
+        };
+        let points = array![
+            [0.5, 0.5, 0.5, 1.],
+            [0.6, 0.6, 0.6, 2.],
+            [0.7, 0.7, 0.7, 3.],
+            [3.5, 0.5, 0.5, 4.],
+            [5.0, 0.5, 0.5, 5.],
+            [2.5, 2.5, 2.5, 6.],
+        ];
+
+        let voxels = voxelize_points(&points.view(), &cfg);
+        assert_eq!(voxels.coordinates, array![[0, 0, 0], [3, 0, 0]]);
+        assert_eq!(voxels.num_points, array![2, 1]);
+        assert_eq!(voxels.features.shape(), &[2, 2, 4]);
+        assert_eq!(voxels.features[[0, 1, 3]], 2.);
+        assert_eq!(voxels.features[[1, 1, 3]], 0.);
+
+        let voxels = voxelize_points_dynamic(&points.view(), &cfg);
+        assert_eq!(voxels.num_points, array![3, 1, 1]);
+        assert_eq!(voxels.point_to_voxel, array![0, 0, 0, 1, -1, 2]);
+    }
+}
@@ -0,0 +1,645 @@
+//! # integrity
+//!
+//! Integrity validation of downloaded dataset files.
+//!
+//! Every file of a split is checked against its format: feather and parquet files must have
+//! intact footers, JPEG images must have their end-of-image marker, and JSON files must parse.
+//! Files can also be verified against a checksum file with one `<crc32>  <relative_path>` line
+//! per file (e.g., written by `write_checksums` for a mirror of the dataset). Problems are
+//! reported per file instead of surfacing as panics while reading.
+//!
+//! After a download, `verify_split` checks the logs of a split against a manifest of the expected
+//! file sizes (one `<size>  <relative_path>` line per file, written by the downloader next to the
+//! split directory), and reports the logs which are missing, incomplete, or corrupt up front.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use polars::{
+    export::arrow::io::ipc::read::read_file_metadata,
+    prelude::{ParquetReader, SerReader},
+};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+/// Suffix of the file sizes manifest of a split (e.g., `val_file_sizes.txt` for `val`).
+pub const FILE_SIZES_SUFFIX: &str = "_file_sizes.txt";
+
+/// Magic bytes of Arrow IPC (feather v2) files.
+const ARROW_MAGIC: &[u8] = b"ARROW1";
+/// Magic bytes of parquet files.
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+/// Problem found in a dataset file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileIssueKind {
+    /// The file listed in the checksum file does not exist.
+    Missing,
+    /// The file ends early (e.g., an interrupted download).
+    Truncated,
+    /// The file is larger than expected.
+    Oversized {
+        /// Expected size (in bytes).
+        expected: u64,
+        /// Size on disk (in bytes).
+        actual: u64,
+    },
+    /// The file is not a valid file of its format.
+    Corrupt(String),
+    /// The checksum of the file differs from the expected one.
+    ChecksumMismatch {
+        /// Expected CRC32 checksum.
+        expected: u32,
+        /// Computed CRC32 checksum.
+        actual: u32,
+    },
+}
+
+impl fmt::Display for FileIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileIssueKind::Missing => write!(f, "missing"),
+            FileIssueKind::Truncated => write!(f, "truncated"),
+            FileIssueKind::Oversized { expected, actual } => {
+                write!(f, "oversized (expected {expected} bytes, got {actual})")
+            }
+            FileIssueKind::Corrupt(reason) => write!(f, "corrupt ({reason})"),
+            FileIssueKind::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "checksum mismatch (expected {expected:08x}, got {actual:08x})"
+                )
+            }
+        }
+    }
+}
+
+/// Problem found in a file of a log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileIssue {
+    /// Log containing the file.
+    pub log_id: String,
+    /// Path of the file relative to the split directory.
+    pub path: PathBuf,
+    /// Problem found in the file.
+    pub kind: FileIssueKind,
+}
+
+/// Integrity report of a split.
+#[derive(Clone, Debug, Default)]
+pub struct IntegrityReport {
+    /// Number of validated files.
+    pub num_files: usize,
+    /// Problems found, sorted by path.
+    pub issues: Vec<FileIssue>,
+}
+
+impl IntegrityReport {
+    /// Returns `true` if no problem was found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Problems grouped by log.
+    pub fn issues_by_log(&self) -> BTreeMap<&str, Vec<&FileIssue>> {
+        let mut issues = BTreeMap::<&str, Vec<&FileIssue>>::new();
+        for issue in &self.issues {
+            issues.entry(issue.log_id.as_str()).or_default().push(issue);
+        }
+        issues
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Validated {} files: {} problems.",
+            self.num_files,
+            self.issues.len()
+        )?;
+        for (log_id, issues) in self.issues_by_log() {
+            writeln!(f, "{log_id}:")?;
+            for issue in issues {
+                writeln!(f, "  {}: {}", issue.path.display(), issue.kind)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verification report of a split against its expected file sizes.
+#[derive(Clone, Debug, Default)]
+pub struct VerificationReport {
+    /// Number of expected logs.
+    pub num_logs: usize,
+    /// Number of expected files.
+    pub num_files: usize,
+    /// Expected logs without a directory.
+    pub missing_logs: Vec<String>,
+    /// Problems found in the logs which exist, sorted by path.
+    pub issues: Vec<FileIssue>,
+}
+
+impl VerificationReport {
+    /// Returns `true` if every expected file exists with its expected size (and format).
+    pub fn is_ok(&self) -> bool {
+        self.missing_logs.is_empty() && self.issues.is_empty()
+    }
+
+    /// Problems grouped by log.
+    pub fn issues_by_log(&self) -> BTreeMap<&str, Vec<&FileIssue>> {
+        let mut issues = BTreeMap::<&str, Vec<&FileIssue>>::new();
+        for issue in &self.issues {
+            issues.entry(issue.log_id.as_str()).or_default().push(issue);
+        }
+        issues
+    }
+
+    /// Logs with missing or truncated files (e.g., an interrupted download).
+    pub fn incomplete_logs(&self) -> Vec<&str> {
+        self.issues_by_log()
+            .into_iter()
+            .filter(|(_, issues)| {
+                issues
+                    .iter()
+                    .any(|x| matches!(x.kind, FileIssueKind::Missing | FileIssueKind::Truncated))
+            })
+            .map(|(log_id, _)| log_id)
+            .collect()
+    }
+
+    /// Logs with oversized or invalid files.
+    pub fn corrupt_logs(&self) -> Vec<&str> {
+        self.issues_by_log()
+            .into_iter()
+            .filter(|(_, issues)| {
+                issues
+                    .iter()
+                    .any(|x| !matches!(x.kind, FileIssueKind::Missing | FileIssueKind::Truncated))
+            })
+            .map(|(log_id, _)| log_id)
+            .collect()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Verified {} logs ({} files): {} missing, {} incomplete, {} corrupt.",
+            self.num_logs,
+            self.num_files,
+            self.missing_logs.len(),
+            self.incomplete_logs().len(),
+            self.corrupt_logs().len()
+        )?;
+        for log_id in &self.missing_logs {
+            writeln!(f, "{log_id}: missing")?;
+        }
+        for (log_id, issues) in self.issues_by_log() {
+            writeln!(f, "{log_id}:")?;
+            for issue in issues {
+                writeln!(f, "  {}: {}", issue.path.display(), issue.kind)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Validate the format of a feather, parquet, JPEG, or JSON file. Other files are not checked.
+/// Returns `None` if no problem was found.
+pub fn validate_file(path: &Path) -> Option<FileIssueKind> {
+    let result = match path.extension().and_then(|x| x.to_str()) {
+        Some("feather" | "arrow") => _validate_feather(path),
+        Some("parquet") => _validate_parquet(path),
+        Some("jpg" | "jpeg") => _validate_jpeg(path),
+        Some("json") => _validate_json(path),
+        _ => Ok(None),
+    };
+    result.unwrap_or_else(|err| Some(FileIssueKind::Corrupt(err.to_string())))
+}
+
+/// Validate every file in `split_dir` and, if `checksums` are provided, verify them. The first
+/// component of each relative path is its log id.
+pub fn validate_split(
+    split_dir: &Path,
+    checksums: Option<&BTreeMap<PathBuf, u32>>,
+) -> Result<IntegrityReport> {
+    let mut files = vec![];
+    _walk_files(split_dir, &mut files)?;
+    let mut issues = files
+        .par_iter()
+        .filter_map(|path| {
+            let mut kind = validate_file(path);
+            if kind.is_none() {
+                let relative_path = path.strip_prefix(split_dir).ok()?;
+                if let Some(expected) = checksums.and_then(|x| x.get(relative_path)) {
+                    kind = match compute_checksum(path) {
+                        Ok(actual) if actual == *expected => None,
+                        Ok(actual) => Some(FileIssueKind::ChecksumMismatch {
+                            expected: *expected,
+                            actual,
+                        }),
+                        Err(err) => Some(FileIssueKind::Corrupt(err.to_string())),
+                    };
+                }
+            }
+            kind.map(|kind| _issue(split_dir, path, kind))
+        })
+        .collect::<Vec<_>>();
+    if let Some(checksums) = checksums {
+        issues.extend(
+            checksums
+                .keys()
+                .filter(|x| !split_dir.join(x).exists())
+                .map(|x| _issue(split_dir, &split_dir.join(x), FileIssueKind::Missing)),
+        );
+    }
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(IntegrityReport {
+        num_files: files.len(),
+        issues,
+    })
+}
+
+/// Verify the logs of `split_dir` against their expected `file_sizes` (keyed by path relative to
+/// the split directory). Files of the expected size are also validated with `validate_file` if
+/// `validate_formats` is set. Files which are not expected (e.g., `.part` files) are ignored.
+pub fn verify_split(
+    split_dir: &Path,
+    file_sizes: &BTreeMap<PathBuf, u64>,
+    validate_formats: bool,
+) -> Result<VerificationReport> {
+    let log_ids = file_sizes
+        .keys()
+        .map(|x| _log_id(x))
+        .collect::<BTreeSet<_>>();
+    let missing_logs = log_ids
+        .iter()
+        .filter(|x| !split_dir.join(x).is_dir())
+        .cloned()
+        .collect::<Vec<_>>();
+    let files = file_sizes
+        .iter()
+        .filter(|(path, _)| !missing_logs.contains(&_log_id(path)))
+        .collect::<Vec<_>>();
+    let mut issues = files
+        .par_iter()
+        .filter_map(|(relative_path, expected)| {
+            let path = split_dir.join(relative_path);
+            let kind = match fs::metadata(&path) {
+                Err(_) => Some(FileIssueKind::Missing),
+                Ok(metadata) if metadata.len() < **expected => Some(FileIssueKind::Truncated),
+                Ok(metadata) if metadata.len() > **expected => Some(FileIssueKind::Oversized {
+                    expected: **expected,
+                    actual: metadata.len(),
+                }),
+                Ok(_) if validate_formats => validate_file(&path),
+                Ok(_) => None,
+            };
+            kind.map(|kind| _issue(split_dir, &path, kind))
+        })
+        .collect::<Vec<_>>();
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(VerificationReport {
+        num_logs: log_ids.len(),
+        num_files: file_sizes.len(),
+        missing_logs,
+        issues,
+    })
+}
+
+/// Path of the file sizes manifest of `split_dir`, which lies next to the split directory
+/// (e.g., `<root_dir>/av2/sensor/val_file_sizes.txt`).
+pub fn file_sizes_path(split_dir: &Path) -> PathBuf {
+    let split_name = split_dir
+        .file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    split_dir.with_file_name(format!("{split_name}{FILE_SIZES_SUFFIX}"))
+}
+
+/// Sizes of every file in `split_dir` (e.g., of a complete copy of the dataset), keyed by path
+/// relative to the split directory.
+pub fn compute_file_sizes(split_dir: &Path) -> Result<BTreeMap<PathBuf, u64>> {
+    let mut files = vec![];
+    _walk_files(split_dir, &mut files)?;
+    files
+        .into_iter()
+        .map(|file| {
+            let size = fs::metadata(&file)?.len();
+            Ok((file.strip_prefix(split_dir)?.to_path_buf(), size))
+        })
+        .collect()
+}
+
+/// Read a file sizes manifest with one `<size>  <relative_path>` line per file.
+pub fn read_file_sizes(path: &Path) -> Result<BTreeMap<PathBuf, u64>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("{} not found.", path.display()))?;
+    let mut file_sizes = BTreeMap::new();
+    for line in text.lines().filter(|x| !x.trim().is_empty()) {
+        let Some((size, relative_path)) = line.split_once(char::is_whitespace) else {
+            bail!("Invalid file size line: `{line}`.");
+        };
+        let size = size
+            .parse()
+            .with_context(|| format!("Invalid file size line: `{line}`."))?;
+        file_sizes.insert(PathBuf::from(relative_path.trim()), size);
+    }
+    Ok(file_sizes)
+}
+
+/// Write a file sizes manifest with one `<size>  <relative_path>` line per file.
+pub fn write_file_sizes(path: &Path, file_sizes: &BTreeMap<PathBuf, u64>) -> Result<()> {
+    let lines = file_sizes
+        .iter()
+        .map(|(relative_path, size)| format!("{size}  {}\n", relative_path.display()))
+        .collect::<String>();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, lines)?;
+    Ok(())
+}
+
+/// CRC32 checksum of a file.
+pub fn compute_checksum(path: &Path) -> Result<u32> {
+    let mut reader =
+        BufReader::new(File::open(path).with_context(|| format!("{} not found.", path.display()))?);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0; 1 << 16];
+    loop {
+        let num_bytes = reader.read(&mut buffer)?;
+        if num_bytes == 0 {
+            break;
+        }
+        hasher.update(&buffer[..num_bytes]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Read a checksum file with one `<crc32>  <relative_path>` line per file.
+pub fn read_checksums(path: &Path) -> Result<BTreeMap<PathBuf, u32>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("{} not found.", path.display()))?;
+    let mut checksums = BTreeMap::new();
+    for line in text.lines().filter(|x| !x.trim().is_empty()) {
+        let Some((checksum, relative_path)) = line.split_once(char::is_whitespace) else {
+            bail!("Invalid checksum line: `{line}`.");
+        };
+        let checksum = u32::from_str_radix(checksum, 16)
+            .with_context(|| format!("Invalid checksum line: `{line}`."))?;
+        checksums.insert(PathBuf::from(relative_path.trim()), checksum);
+    }
+    Ok(checksums)
+}
+
+/// Compute the checksums of every file in `split_dir` and write them to a checksum file.
+/// Returns the number of files.
+pub fn write_checksums(split_dir: &Path, path: &Path) -> Result<usize> {
+    let mut files = vec![];
+    _walk_files(split_dir, &mut files)?;
+    files.sort();
+    let lines = files
+        .par_iter()
+        .map(|file| {
+            let relative_path = file.strip_prefix(split_dir)?;
+            Ok(format!(
+                "{:08x}  {}\n",
+                compute_checksum(file)?,
+                relative_path.display()
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    fs::write(path, lines.concat())?;
+    Ok(files.len())
+}
+
+fn _issue(split_dir: &Path, path: &Path, kind: FileIssueKind) -> FileIssue {
+    let relative_path = path.strip_prefix(split_dir).unwrap_or(path).to_path_buf();
+    FileIssue {
+        log_id: _log_id(&relative_path),
+        path: relative_path,
+        kind,
+    }
+}
+
+/// Log id of a path relative to the split directory (i.e., its first component).
+fn _log_id(relative_path: &Path) -> String {
+    relative_path
+        .components()
+        .next()
+        .map(|x| x.as_os_str().to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn _walk_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("{} not found.", dir.display()))? {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => _walk_files(&path, files)?,
+            false => files.push(path),
+        }
+    }
+    Ok(())
+}
+
+/// Arrow IPC files start with `ARROW1\0\0` and end with the footer length and `ARROW1`.
+fn _validate_feather(path: &Path) -> Result<Option<FileIssueKind>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 2 * ARROW_MAGIC.len() + 6 || !bytes.starts_with(ARROW_MAGIC) {
+        return Ok(Some(_truncated_or_corrupt(&bytes, ARROW_MAGIC, "feather")));
+    }
+    if !bytes.ends_with(ARROW_MAGIC) {
+        return Ok(Some(FileIssueKind::Truncated));
+    }
+    if let Err(err) = read_file_metadata(&mut std::io::Cursor::new(&bytes)) {
+        return Ok(Some(FileIssueKind::Corrupt(err.to_string())));
+    }
+    Ok(None)
+}
+
+/// Parquet files start and end with `PAR1`.
+fn _validate_parquet(path: &Path) -> Result<Option<FileIssueKind>> {
+    let bytes = fs::read(path)?;
+    if bytes.len() < 2 * PARQUET_MAGIC.len() + 4 || !bytes.starts_with(PARQUET_MAGIC) {
+        return Ok(Some(_truncated_or_corrupt(
+            &bytes,
+            PARQUET_MAGIC,
+            "parquet",
+        )));
+    }
+    if !bytes.ends_with(PARQUET_MAGIC) {
+        return Ok(Some(FileIssueKind::Truncated));
+    }
+    if let Err(err) = ParquetReader::new(std::io::Cursor::new(bytes)).num_rows() {
+        return Ok(Some(FileIssueKind::Corrupt(err.to_string())));
+    }
+    Ok(None)
+}
+
+/// JPEG images start with the start-of-image marker and end with the end-of-image marker.
+fn _validate_jpeg(path: &Path) -> Result<Option<FileIssueKind>> {
+    let bytes = fs::read(path)?;
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return Ok(Some(_truncated_or_corrupt(&bytes, &[0xFF, 0xD8], "JPEG")));
+    }
+    // Some encoders pad the image after the end-of-image marker.
+    let end = bytes.iter().rposition(|x| *x != 0).map_or(0, |i| i + 1);
+    match bytes[..end].ends_with(&[0xFF, 0xD9]) {
+        true => Ok(None),
+        false => Ok(Some(FileIssueKind::Truncated)),
+    }
+}
+
+fn _validate_json(path: &Path) -> Result<Option<FileIssueKind>> {
+    let reader = BufReader::new(File::open(path)?);
+    match serde_json::from_reader::<_, serde::de::IgnoredAny>(reader) {
+        Ok(_) => Ok(None),
+        Err(err) if err.is_eof() => Ok(Some(FileIssueKind::Truncated)),
+        Err(err) => Ok(Some(FileIssueKind::Corrupt(err.to_string()))),
+    }
+}
+
+/// A file which is a prefix of the magic bytes was truncated, otherwise it is not of the format.
+fn _truncated_or_corrupt(bytes: &[u8], magic: &[u8], format: &str) -> FileIssueKind {
+    match magic.starts_with(&bytes[..bytes.len().min(magic.len())]) {
+        true => FileIssueKind::Truncated,
+        false => FileIssueKind::Corrupt(format!("not a {format} file")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        compute_file_sizes, file_sizes_path, read_checksums, read_file_sizes, validate_split,
+        verify_split, write_checksums, write_file_sizes, FileIssueKind,
+    };
+    use crate::synthetic::{test_dataset, MiniDatasetConfig};
+
+    #[test]
+    fn test_validate_split() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let log_dir = log_dirs.remove(0);
+        let split_dir = log_dir.parent().unwrap();
+        let checksums_path = root_dir.join("checksums.txt");
+        let num_files = write_checksums(split_dir, &checksums_path).unwrap();
+        let checksums = read_checksums(&checksums_path).unwrap();
+        assert_eq!(checksums.len(), num_files);
+
+        let report = validate_split(split_dir, Some(&checksums)).unwrap();
+        assert!(report.is_ok(), "{report}");
+        assert_eq!(report.num_files, num_files);
+
+        // Truncate a sweep, flip a byte of the annotations, and remove an image.
+        let lidar_path = fs::read_dir(log_dir.join("sensors/lidar"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let bytes = fs::read(&lidar_path).unwrap();
+        fs::write(&lidar_path, &bytes[..bytes.len() / 2]).unwrap();
+        let annotations_path = log_dir.join("annotations.feather");
+        let mut bytes = fs::read(&annotations_path).unwrap();
+        let i = bytes.len() / 2;
+        bytes[i] = bytes[i].wrapping_add(1);
+        fs::write(&annotations_path, bytes).unwrap();
+        let camera_dir = log_dir.join("sensors/cameras/ring_front_center");
+        let image_path = fs::read_dir(&camera_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        fs::remove_file(&image_path).unwrap();
+
+        let report = validate_split(split_dir, Some(&checksums)).unwrap();
+        let issues = report.issues_by_log();
+        let log_id = log_dir.file_name().unwrap().to_str().unwrap();
+        assert_eq!(issues.len(), 1);
+        let kinds = issues[log_id]
+            .iter()
+            .map(|x| (x.path.file_name().unwrap(), x.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(kinds.len(), 3);
+        assert!(kinds.contains(&(lidar_path.file_name().unwrap(), FileIssueKind::Truncated)));
+        assert!(kinds.contains(&(image_path.file_name().unwrap(), FileIssueKind::Missing)));
+        assert!(kinds.iter().any(|(name, kind)| {
+            *name == "annotations.feather" && matches!(kind, FileIssueKind::ChecksumMismatch { .. })
+        }));
+
+        // Without checksums, only the truncated sweep is found.
+        let report = validate_split(split_dir, None).unwrap();
+        assert_eq!(report.issues.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_split() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 2,
+            ..Default::default()
+        };
+        let (root_dir, mut log_dirs) = test_dataset(&cfg);
+        let split_dir = log_dirs[0].parent().unwrap().to_path_buf();
+        let path = file_sizes_path(&split_dir);
+        assert_eq!(path, root_dir.join("av2/sensor/val_file_sizes.txt"));
+        write_file_sizes(&path, &compute_file_sizes(&split_dir).unwrap()).unwrap();
+        let file_sizes = read_file_sizes(&path).unwrap();
+
+        let report = verify_split(&split_dir, &file_sizes, true).unwrap();
+        assert!(report.is_ok(), "{report}");
+        assert_eq!((report.num_logs, report.num_files), (2, file_sizes.len()));
+
+        // Remove a log, and truncate a sweep and remove the poses of the other.
+        fs::remove_dir_all(log_dirs.pop().unwrap()).unwrap();
+        let log_dir = log_dirs.pop().unwrap();
+        let lidar_path = fs::read_dir(log_dir.join("sensors/lidar"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let bytes = fs::read(&lidar_path).unwrap();
+        fs::write(&lidar_path, &bytes[..bytes.len() / 2]).unwrap();
+        fs::remove_file(log_dir.join("city_SE3_egovehicle.feather")).unwrap();
+        // Unexpected files are ignored.
+        fs::write(log_dir.join("annotations.feather.part"), "").unwrap();
+
+        let report = verify_split(&split_dir, &file_sizes, false).unwrap();
+        let log_id = log_dir.file_name().unwrap().to_str().unwrap();
+        assert_eq!(report.missing_logs.len(), 1);
+        assert_eq!(report.incomplete_logs(), vec![log_id]);
+        assert!(report.corrupt_logs().is_empty());
+        let kinds = report
+            .issues
+            .iter()
+            .map(|x| (x.path.file_name().unwrap(), x.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    "city_SE3_egovehicle.feather".as_ref(),
+                    FileIssueKind::Missing
+                ),
+                (lidar_path.file_name().unwrap(), FileIssueKind::Truncated),
+            ]
+        );
+    }
+}
@@ -2,59 +2,723 @@
 //!
 //! Reading and writing operations.
 
+/// Zero-copy export of dataframes through the Arrow PyCapsule interface.
+pub mod capsule;
+/// Zero-copy export of arrays through the DLPack protocol.
+pub mod dlpack;
+/// Packed sample buffers of a fixed layout.
+pub mod packed;
+/// Point cloud interchange (PCD and PLY).
+pub mod point_cloud;
+/// Reads of the dataset files from object stores (e.g., `s3://argoverse`).
+#[cfg(feature = "s3")]
+pub mod remote;
+/// Streaming reads of bounded record batches.
+pub mod stream;
+/// Size-bounded tar shards of preprocessed samples (WebDataset convention).
+pub mod webdataset;
+
 use image::ImageBuffer;
 use image::Rgba;
-use ndarray::s;
 use ndarray::Array;
 use ndarray::Array2;
 use ndarray::Array3;
 use ndarray::Ix1;
 
 use nshare::ToNdarray3;
-use polars::lazy::dsl::lit;
 use polars::lazy::dsl::Expr;
-use polars::lazy::dsl::GetOutput;
 
 use polars::prelude::*;
 
-use polars::series::Series;
 use polars::{
     self,
+    export::arrow::{
+        array::PrimitiveArray,
+        chunk::Chunk,
+        datatypes::{ArrowDataType, ArrowSchema, Field},
+        io::ipc::{
+            read::read_file_metadata,
+            write::{Compression as ArrowCompression, FileWriter, WriteOptions},
+        },
+        types::f16,
+    },
     lazy::dsl::{col, cols},
     prelude::{DataFrame, IntoLazy},
 };
+use polars_arrow_format::ipc::{planus::ReadAsRoot, MessageHeaderRef, MessageRef};
 use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
-use std::fs::File;
-use std::path::PathBuf;
+use rayon::ThreadPool;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use strum_macros::{Display, EnumString};
+use tracing::info_span;
 
-use crate::constants::POSE_COLUMNS;
+use crate::constants::{ANNOTATION_FILE_COLUMNS, LIDAR_COLUMNS};
+use crate::error::{Av2Error, Av2Result};
+use crate::geometry::aggregation::{aggregate_sweeps, AccumulationConfig, TIMEDELTA_COLUMN};
 use crate::geometry::se3::SE3;
+use crate::parallel::build_thread_pool;
+use crate::schema::{validate_data_frame, ColumnType, POSE_SCHEMA};
 use image::io::Reader as ImageReader;
 
-use crate::geometry::so3::_quat_to_mat3;
-
 /// Read a feather file and load into a `polars` dataframe.
-pub fn read_feather_eager(path: &PathBuf, memory_mapped: bool) -> DataFrame {
-    let file =
-        File::open(path).unwrap_or_else(|_| panic!("{path} not found.", path = path.display()));
-    polars::io::ipc::IpcReader::new(file)
-        .memory_mapped(memory_mapped)
+pub fn read_feather(path: &Path, memory_mapped: bool) -> Av2Result<DataFrame> {
+    _read_ipc(path, None, memory_mapped)
+}
+
+/// Read the `columns` (or all columns) of a feather file.
+///
+/// Unless memory-mapped, the file is read into memory (`read_file` span) before it is decompressed
+/// and decoded (`decode_feather` span), so that the two stages are timed separately.
+fn _read_ipc(
+    path: &Path,
+    columns: Option<Vec<String>>,
+    memory_mapped: bool,
+) -> Av2Result<DataFrame> {
+    let mut file = File::open(path).map_err(|err| Av2Error::open(path, err))?;
+    if memory_mapped {
+        let _span = info_span!("decode_feather", path = %path.display()).entered();
+        return IpcReader::new(file)
+            .with_columns(columns)
+            .memory_mapped(true)
+            .finish()
+            .map_err(|err| Av2Error::decode(path, err));
+    }
+    let mut bytes = Vec::new();
+    info_span!("read_file", path = %path.display()).in_scope(|| file.read_to_end(&mut bytes))?;
+    let _span = info_span!("decode_feather", path = %path.display()).entered();
+    IpcReader::new(Cursor::new(bytes))
+        .with_columns(columns)
         .finish()
-        .unwrap()
+        .map_err(|err| Av2Error::decode(path, err))
+}
+
+/// Read a feather file and load into a `polars` dataframe.
+///
+/// # Panics
+///
+/// Panics if the file is missing or cannot be decoded (see `read_feather`).
+pub fn read_feather_eager(path: &Path, memory_mapped: bool) -> DataFrame {
+    read_feather(path, memory_mapped).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// Inclusive range filter on a numeric column. Unbounded sides are `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangePredicate {
+    /// Filtered column.
+    pub column: String,
+    /// Minimum value (inclusive).
+    pub min: Option<f64>,
+    /// Maximum value (inclusive).
+    pub max: Option<f64>,
+}
+
+impl RangePredicate {
+    /// Construct a new range predicate.
+    pub fn new(column: &str, min: Option<f64>, max: Option<f64>) -> Self {
+        Self {
+            column: column.to_string(),
+            min,
+            max,
+        }
+    }
+
+    /// Boolean expression selecting the rows within the range.
+    pub fn expr(&self) -> Expr {
+        let value = col(&self.column).cast(DataType::Float64);
+        let is_above = self.min.map(|min| value.clone().gt_eq(lit(min)));
+        let is_below = self.max.map(|max| value.lt_eq(lit(max)));
+        match (is_above, is_below) {
+            (Some(is_above), Some(is_below)) => is_above.and(is_below),
+            (Some(mask), None) | (None, Some(mask)) => mask,
+            (None, None) => lit(true),
+        }
+    }
+}
+
+/// Column projection and row predicates applied while reading a data-frame.
+/// The default selection reads every row of every column.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScanSelection {
+    /// Projected columns in output order, or `None` for all columns.
+    pub columns: Option<Vec<String>>,
+    /// Conjunction of row predicates.
+    pub predicates: Vec<RangePredicate>,
+}
+
+impl ScanSelection {
+    /// Select `columns` of every row.
+    pub fn with_columns(columns: &[&str]) -> Self {
+        Self {
+            columns: Some(columns.iter().map(|x| x.to_string()).collect()),
+            predicates: vec![],
+        }
+    }
+
+    /// Add a range predicate on `column`.
+    pub fn filter(mut self, column: &str, min: Option<f64>, max: Option<f64>) -> Self {
+        self.predicates.push(RangePredicate::new(column, min, max));
+        self
+    }
+
+    /// Columns which must be decoded: the projection and the predicate columns.
+    /// Returns `None` if every column is projected.
+    pub fn required_columns(&self) -> Option<Vec<String>> {
+        let mut required = self.columns.clone()?;
+        for predicate in &self.predicates {
+            if !required.contains(&predicate.column) {
+                required.push(predicate.column.clone());
+            }
+        }
+        Some(required)
+    }
+
+    /// Conjunction of the row predicates, or `None` if there are none.
+    pub fn predicate(&self) -> Option<Expr> {
+        self.predicates
+            .iter()
+            .map(|x| x.expr())
+            .reduce(|mask, x| mask.and(x))
+    }
+
+    /// Apply the selection to a lazy frame.
+    pub fn apply(&self, mut frame: LazyFrame) -> LazyFrame {
+        if let Some(predicate) = self.predicate() {
+            frame = frame.filter(predicate);
+        }
+        match &self.columns {
+            Some(columns) => frame.select(&[cols(columns)]),
+            None => frame,
+        }
+    }
+}
+
+/// Memory-mapped reads of local dataset files.
+///
+/// Uncompressed feather files are memory-mapped in place. Compressed files cannot be
+/// memory-mapped, so they are decompressed once into an uncompressed feather copy under
+/// `cache_dir`, which is memory-mapped on every later read. Repeated epochs then read from the page
+/// cache, and worker processes reading the same file share its pages. Copies are keyed by the size
+/// and modification time of their source, so a modified file never serves a stale copy.
+///
+/// The least recently used copies are evicted once the cache exceeds `max_size_bytes`. Every copy
+/// can be regenerated, so the cache may also be removed at any time (see `clear`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryMapConfig {
+    /// Directory of the uncompressed copies.
+    pub cache_dir: PathBuf,
+    /// Maximum total size of the uncompressed copies (in bytes).
+    pub max_size_bytes: u64,
+}
+
+impl Default for MemoryMapConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("av2/mmap"),
+            max_size_bytes: 64 << 30,
+        }
+    }
+}
+
+impl MemoryMapConfig {
+    /// Path of the uncompressed copy of a feather or parquet file.
+    /// E.g., `<cache_dir>/<absolute_path>.<size>-<mtime_ns>.feather`.
+    pub fn copy_path(&self, path: &Path) -> Av2Result<PathBuf> {
+        let path = path
+            .canonicalize()
+            .map_err(|err| Av2Error::open(path, err))?;
+        let metadata = fs::metadata(&path)?;
+        let mtime_ns = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| Av2Error::decode(&path, err))?
+            .as_nanos();
+        let relative_path = path
+            .components()
+            .filter(|x| matches!(x, Component::Normal(_)))
+            .collect::<PathBuf>();
+        let file_name = format!(
+            "{}.{}-{mtime_ns}.feather",
+            relative_path.display(),
+            metadata.len()
+        );
+        Ok(self.cache_dir.join(file_name))
+    }
+
+    /// Path of the file to memory-map in place of `path`: the file itself if it is an uncompressed
+    /// feather file, and its uncompressed copy otherwise.
+    pub fn mapped_path(&self, path: &Path) -> Av2Result<PathBuf> {
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("parquet") => self.ensure_copy(path),
+            _ if is_uncompressed_feather(path)? => Ok(path.to_path_buf()),
+            _ => self.ensure_copy(path),
+        }
+    }
+
+    /// Path of the uncompressed copy of a feather or parquet file, which is written on the first
+    /// call.
+    pub fn ensure_copy(&self, path: &Path) -> Av2Result<PathBuf> {
+        let copy_path = self.copy_path(path)?;
+        if let Ok(metadata) = fs::metadata(&copy_path) {
+            // Mark the copy as recently used (also on disk, for the next process).
+            if let Ok(file) = File::options().append(true).open(&copy_path) {
+                let _ = file.set_modified(SystemTime::now());
+            }
+            self.with_state(|state| state.touch(&copy_path, metadata.len()))?;
+            return Ok(copy_path);
+        }
+        let _span = info_span!("decompress", path = %path.display()).entered();
+        let file = File::open(path).map_err(|err| Av2Error::open(path, err))?;
+        let mut data_frame = match path.extension().and_then(|x| x.to_str()) {
+            Some("parquet") => ParquetReader::new(file).finish(),
+            _ => IpcReader::new(file).finish(),
+        }
+        .map_err(|err| Av2Error::decode(path, err))?;
+        let copy_dir = copy_path
+            .parent()
+            .ok_or_else(|| Av2Error::decode(path, "invalid copy path"))?;
+        fs::create_dir_all(copy_dir)?;
+        // Every writer (e.g., threads prefetching the same file) writes its own temporary file, and
+        // the complete copy is moved into place so concurrent readers never map a partial copy.
+        let tmp_file = tempfile::NamedTempFile::new_in(copy_dir)?;
+        IpcWriter::new(tmp_file.as_file())
+            .with_compression(None)
+            .finish(&mut data_frame)?;
+        tmp_file
+            .persist(&copy_path)
+            .map_err(|err| Av2Error::Io(err.error))?;
+        let size_bytes = fs::metadata(&copy_path)?.len();
+        self.with_state(|state| {
+            state.touch(&copy_path, size_bytes);
+            state.evict(self.max_size_bytes, &copy_path);
+        })?;
+        Ok(copy_path)
+    }
+
+    /// Total size of the uncompressed copies (in bytes).
+    pub fn size_bytes(&self) -> Av2Result<u64> {
+        Ok(self.copies()?.iter().map(|(_, size, _)| size).sum())
+    }
+
+    /// Remove every uncompressed copy.
+    pub fn clear(&self) -> Av2Result<()> {
+        _cache_states().remove(&self.cache_dir);
+        match fs::remove_dir_all(&self.cache_dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run `f` on the tracked state of the cache, which is scanned from disk on first use.
+    fn with_state<T>(&self, f: impl FnOnce(&mut CacheState) -> T) -> Av2Result<T> {
+        let mut states = _cache_states();
+        let state = match states.entry(self.cache_dir.clone()) {
+            std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                entry.insert(CacheState::new(self.copies()?))
+            }
+        };
+        Ok(f(state))
+    }
+
+    /// Last access time, size, and path of every copy.
+    fn copies(&self) -> Av2Result<Vec<(SystemTime, u64, PathBuf)>> {
+        let mut copies = vec![];
+        if self.cache_dir.exists() {
+            _scan_copies(&self.cache_dir, &mut copies)?;
+        }
+        Ok(copies)
+    }
+}
+
+/// Size and access order of the copies of a cache directory, tracked in memory so that a new copy
+/// does not rescan the directory.
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Access tick and size of every copy.
+    copies: HashMap<PathBuf, (u64, u64)>,
+    /// Total size of the copies (in bytes).
+    size_bytes: u64,
+    /// Number of accesses so far.
+    tick: u64,
+}
+
+impl CacheState {
+    /// Track the copies found on disk, ordered by their last access time.
+    fn new(mut copies: Vec<(SystemTime, u64, PathBuf)>) -> Self {
+        copies.sort_by_key(|(accessed, _, _)| *accessed);
+        let mut state = Self::default();
+        for (_, size, path) in copies {
+            state.touch(&path, size);
+        }
+        state
+    }
+
+    /// Mark a copy as most recently used.
+    fn touch(&mut self, path: &Path, size_bytes: u64) {
+        self.tick += 1;
+        if let Some((_, previous_size)) = self
+            .copies
+            .insert(path.to_path_buf(), (self.tick, size_bytes))
+        {
+            self.size_bytes -= previous_size;
+        }
+        self.size_bytes += size_bytes;
+    }
+
+    /// Evict the least recently used copies (except `keep`) until `max_size_bytes` is met.
+    /// Copies that are still mapped by a reader stay valid until they are unmapped.
+    fn evict(&mut self, max_size_bytes: u64, keep: &Path) {
+        if self.size_bytes <= max_size_bytes {
+            return;
+        }
+        let mut copies = self
+            .copies
+            .iter()
+            .filter(|(path, _)| *path != keep)
+            .map(|(path, (tick, _))| (*tick, path.clone()))
+            .collect::<Vec<_>>();
+        copies.sort_unstable();
+        for (_, path) in copies {
+            if self.size_bytes <= max_size_bytes {
+                break;
+            }
+            // Copies removed by another process are dropped as well.
+            let _ = fs::remove_file(&path);
+            if let Some((_, size_bytes)) = self.copies.remove(&path) {
+                self.size_bytes -= size_bytes;
+            }
+        }
+    }
+}
+
+/// Tracked state of every cache directory of the process.
+static CACHE_STATES: Mutex<BTreeMap<PathBuf, CacheState>> = Mutex::new(BTreeMap::new());
+
+/// Lock the tracked cache states (a panicking holder leaves them consistent).
+fn _cache_states() -> std::sync::MutexGuard<'static, BTreeMap<PathBuf, CacheState>> {
+    CACHE_STATES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Recursively collect the copies of `dir`. Temporary files of concurrent writers are skipped.
+fn _scan_copies(dir: &Path, copies: &mut Vec<(SystemTime, u64, PathBuf)>) -> Av2Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let path = entry.path();
+        if metadata.is_dir() {
+            _scan_copies(&path, copies)?;
+        } else if path.extension().is_some_and(|x| x == "feather") {
+            copies.push((metadata.modified()?, metadata.len(), path));
+        }
+    }
+    Ok(())
+}
+
+/// Whether the record batches of a feather file are uncompressed (i.e., it can be memory-mapped).
+/// Only the first record batch is inspected, since writers compress either every batch or none.
+pub fn is_uncompressed_feather(path: &Path) -> Av2Result<bool> {
+    let mut file = BufReader::new(File::open(path).map_err(|err| Av2Error::open(path, err))?);
+    let metadata = read_file_metadata(&mut file).map_err(|err| Av2Error::decode(path, err))?;
+    let Some(block) = metadata.blocks.first() else {
+        return Ok(true);
+    };
+    file.seek(SeekFrom::Start(block.offset as u64))?;
+    let mut message_length = [0_u8; 4];
+    file.read_exact(&mut message_length)?;
+    if message_length == [0xff; 4] {
+        // Continuation marker.
+        file.read_exact(&mut message_length)?;
+    }
+    let mut message = vec![0; i32::from_le_bytes(message_length).max(0) as usize];
+    file.read_exact(&mut message)?;
+    let message = MessageRef::read_as_root(&message).map_err(|err| Av2Error::decode(path, err))?;
+    match message.header() {
+        Ok(Some(MessageHeaderRef::RecordBatch(batch))) => Ok(batch
+            .compression()
+            .map_err(|err| Av2Error::decode(path, err))?
+            .is_none()),
+        _ => Err(Av2Error::decode(path, "invalid record batch")),
+    }
+}
+
+/// Read the selected rows and columns of a feather file. Parquet files are also supported when
+/// memory-mapped.
+///
+/// Only the projected and predicate columns are decoded. The predicates are applied after
+/// decoding since the lazy `ipc` scan does not support the half-precision lidar columns.
+/// If `memory_map` is provided, the file (or its uncompressed copy) is memory-mapped instead.
+pub fn read_feather_selected(
+    path: &Path,
+    selection: &ScanSelection,
+    memory_map: Option<&MemoryMapConfig>,
+) -> Av2Result<DataFrame> {
+    let path = match memory_map {
+        Some(cfg) => cfg.mapped_path(path)?,
+        None => path.to_path_buf(),
+    };
+    let data_frame = _read_ipc(&path, selection.required_columns(), memory_map.is_some())?;
+    let _span = info_span!("select_rows", path = %path.display()).entered();
+    selection
+        .apply(data_frame.lazy())
+        .collect()
+        .map_err(|err| Av2Error::schema(&path.display().to_string(), err))
+}
+
+/// Read the selected rows and columns of a parquet file.
+/// The projection and predicates are pushed down into the scan (e.g., row groups whose
+/// statistics fail the predicates are skipped).
+pub fn read_parquet_selected(path: &PathBuf, selection: &ScanSelection) -> Av2Result<DataFrame> {
+    if !path.exists() {
+        return Err(Av2Error::MissingFile { path: path.clone() });
+    }
+    let _span = info_span!("read_parquet", path = %path.display()).entered();
+    let frame = LazyFrame::scan_parquet(path, ScanArgsParquet::default())?;
+    selection
+        .apply(frame)
+        .collect()
+        .map_err(|err| Av2Error::decode(path, err))
+}
+
+/// Read the selected rows and columns of a feather or parquet file, dispatched on its extension.
+/// Memory-mapped reads of parquet files always go through the uncompressed feather copy.
+pub fn read_selected(
+    path: &PathBuf,
+    selection: &ScanSelection,
+    memory_map: Option<&MemoryMapConfig>,
+) -> Av2Result<DataFrame> {
+    match (path.extension().and_then(|x| x.to_str()), memory_map) {
+        (Some("parquet"), None) => read_parquet_selected(path, selection),
+        _ => read_feather_selected(path, selection, memory_map),
+    }
 }
 
 /// Write a feather file to disk using LZ4 compression.
-pub fn write_feather_eager(path: &PathBuf, mut data_frame: DataFrame) {
-    let file = File::create(path).expect("could not create file");
-    IpcWriter::new(file)
+pub fn write_feather_eager(path: &Path, mut data_frame: DataFrame) -> Av2Result<()> {
+    let file = File::create(path)?;
+    Ok(IpcWriter::new(file)
         .with_compression(Some(IpcCompression::LZ4))
-        .finish(&mut data_frame)
-        .unwrap()
+        .finish(&mut data_frame)?)
 }
 
-/// Read a feather file and load into a `polars` dataframe.
-/// TODO: Implement once upstream half-type is fixed.
+/// Compression of written feather and parquet files.
+#[derive(Clone, Copy, Debug, Default, Display, EnumString, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum FileCompression {
+    /// No compression (e.g., for memory-mapped reads).
+    Uncompressed,
+    /// LZ4 compression, as used by the dataset files.
+    #[default]
+    Lz4,
+    /// Zstandard compression.
+    Zstd,
+}
+
+/// Configuration of the lidar and cuboid writers.
+/// The file format (feather or parquet) is inferred from the extension of the written path.
+#[derive(Clone, Debug)]
+pub struct WriteConfig {
+    /// File compression.
+    pub compression: FileCompression,
+    /// Write the lidar `x`, `y`, and `z` as half-precision floats, as in the dataset files.
+    /// Only supported by feather files.
+    pub half_precision: bool,
+}
+
+impl Default for WriteConfig {
+    fn default() -> Self {
+        Self {
+            compression: FileCompression::Lz4,
+            half_precision: true,
+        }
+    }
+}
+
+/// Write a lidar sweep (e.g., an aggregated or augmented one) with the canonical AV2 schema:
+/// `x`, `y`, `z` (float), `intensity`, `laser_number` (u8), and `offset_ns` (i32).
+/// Only `x`, `y`, and `z` are required. Any other columns (e.g., the `TIMEDELTA_COLUMN` of
+/// aggregated sweeps) are written after the canonical ones.
+pub fn write_lidar(path: &Path, lidar: &DataFrame, cfg: &WriteConfig) -> Av2Result<()> {
+    let dtypes = [
+        DataType::Float32,
+        DataType::Float32,
+        DataType::Float32,
+        DataType::UInt8,
+        DataType::UInt8,
+        DataType::Int32,
+    ];
+    let lidar = _canonicalize(lidar, &LIDAR_COLUMNS, &dtypes, 3)?;
+    let half_precision_columns = match cfg.half_precision {
+        true => &LIDAR_COLUMNS[..3],
+        false => &[],
+    };
+    _write_data_frame(path, lidar, half_precision_columns, cfg.compression)
+}
+
+/// Write cuboids (e.g., sampled or augmented annotations) with the canonical AV2 annotation
+/// schema: `timestamp_ns` (i64), `track_uuid`, `category` (string), the cuboid parameters (f64),
+/// and `num_interior_pts` (i64). Any other columns are written after the canonical ones.
+pub fn write_cuboids(path: &Path, cuboids: &DataFrame, cfg: &WriteConfig) -> Av2Result<()> {
+    let dtypes = [DataType::Int64, DataType::String, DataType::String]
+        .into_iter()
+        .chain(std::iter::repeat_n(DataType::Float64, 10))
+        .chain([DataType::Int64])
+        .collect::<Vec<_>>();
+    let cuboids = _canonicalize(
+        cuboids,
+        &ANNOTATION_FILE_COLUMNS,
+        &dtypes,
+        ANNOTATION_FILE_COLUMNS.len(),
+    )?;
+    _write_data_frame(path, cuboids, &[], cfg.compression)
+}
+
+/// Reorder the `columns` first and cast them to their `dtypes`. The first `num_required` columns
+/// must be present, while the other missing ones are skipped.
+fn _canonicalize(
+    data_frame: &DataFrame,
+    columns: &[&str],
+    dtypes: &[DataType],
+    num_required: usize,
+) -> Av2Result<DataFrame> {
+    let names = data_frame.get_column_names();
+    let mut series = vec![];
+    for (i, (column, dtype)) in columns.iter().zip(dtypes).enumerate() {
+        match names.contains(column) {
+            true => series.push(data_frame[*column].cast(dtype)?),
+            false if i < num_required => {
+                return Err(Av2Error::schema(
+                    "data_frame",
+                    format!("Missing column `{column}`."),
+                ))
+            }
+            false => {}
+        }
+    }
+    for name in names.iter().filter(|name| !columns.contains(name)) {
+        series.push(data_frame[*name].clone());
+    }
+    Ok(DataFrame::new(series)?)
+}
+
+/// Write a feather or parquet file, dispatched on its extension.
+fn _write_data_frame(
+    path: &Path,
+    mut data_frame: DataFrame,
+    half_precision_columns: &[&str],
+    compression: FileCompression,
+) -> Av2Result<()> {
+    let file = File::create(path)?;
+    if path.extension().and_then(|x| x.to_str()) == Some("parquet") {
+        let compression = match compression {
+            FileCompression::Uncompressed => ParquetCompression::Uncompressed,
+            FileCompression::Lz4 => ParquetCompression::Lz4Raw,
+            FileCompression::Zstd => ParquetCompression::Zstd(None),
+        };
+        ParquetWriter::new(file)
+            .with_compression(compression)
+            .finish(&mut data_frame)?;
+        return Ok(());
+    }
+    let compression = match compression {
+        FileCompression::Uncompressed => None,
+        FileCompression::Lz4 => Some(IpcCompression::LZ4),
+        FileCompression::Zstd => Some(IpcCompression::ZSTD),
+    };
+    if half_precision_columns.is_empty() {
+        IpcWriter::new(file)
+            .with_compression(compression)
+            .finish(&mut data_frame)?;
+        return Ok(());
+    }
+
+    // `polars` has no half-precision type, so the arrow arrays are written directly.
+    data_frame.as_single_chunk_par();
+    let mut fields = vec![];
+    let mut arrays = vec![];
+    for series in data_frame.get_columns() {
+        let name = series.name();
+        match half_precision_columns.contains(&name) {
+            true => {
+                let values = series
+                    .cast(&DataType::Float32)?
+                    .f32()?
+                    .into_iter()
+                    .map(|x| x.map(f16::from_f32))
+                    .collect::<Vec<_>>();
+                fields.push(Field::new(name, ArrowDataType::Float16, true));
+                arrays.push(PrimitiveArray::<f16>::from(values).boxed());
+            }
+            false => {
+                let array = series.to_arrow(0, false);
+                fields.push(Field::new(name, array.data_type().clone(), true));
+                arrays.push(array);
+            }
+        }
+    }
+    let options = WriteOptions {
+        compression: compression.map(|x| match x {
+            IpcCompression::LZ4 => ArrowCompression::LZ4,
+            IpcCompression::ZSTD => ArrowCompression::ZSTD,
+        }),
+    };
+    let mut writer = FileWriter::try_new(
+        BufWriter::new(file),
+        Arc::new(ArrowSchema::from(fields)),
+        None,
+        options,
+    )?;
+    writer.write(&Chunk::new(arrays), None)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write a feather file to disk without compression.
+/// Uncompressed files can be memory-mapped (e.g., by `pyarrow`) without copying.
+pub fn write_feather_uncompressed(path: &Path, mut data_frame: DataFrame) -> Av2Result<()> {
+    let file = File::create(path)?;
+    Ok(IpcWriter::new(file)
+        .with_compression(None)
+        .finish(&mut data_frame)?)
+}
+
+/// Write a `polars` dataframe as an uncompressed Arrow IPC stream (e.g., to a pipe or socket).
+/// Uncompressed record batches can be consumed without copying (e.g., by
+/// `pyarrow.ipc.open_stream`).
+pub fn write_ipc_stream<W: Write>(writer: W, data_frame: &mut DataFrame) -> PolarsResult<()> {
+    IpcStreamWriter::new(writer)
+        .with_compression(None)
+        .finish(data_frame)
+}
+
+/// Read an Arrow IPC stream into a `polars` dataframe.
+pub fn read_ipc_stream<R: Read>(reader: R) -> PolarsResult<DataFrame> {
+    IpcStreamReader::new(reader).finish()
+}
+
+/// Write an Arrow IPC stream file (e.g., `.arrows`) to disk.
+pub fn write_ipc_stream_eager(path: &Path, mut data_frame: DataFrame) -> Av2Result<()> {
+    let file = File::create(path)?;
+    Ok(write_ipc_stream(BufWriter::new(file), &mut data_frame)?)
+}
+
+/// Read an Arrow IPC stream file and load into a `polars` dataframe.
+pub fn read_ipc_stream_eager(path: &Path) -> Av2Result<DataFrame> {
+    let file = File::open(path).map_err(|err| Av2Error::open(path, err))?;
+    read_ipc_stream(BufReader::new(file)).map_err(|err| Av2Error::decode(path, err))
+}
+
+// Read a feather file and load into a `polars` dataframe.
+// TODO: Implement once upstream half-type is fixed.
 // pub fn read_feather_lazy(path: &PathBuf, memory_mapped: bool) -> DataFrame {
 //     LazyFrame::scan_ipc(
 //         path,
@@ -71,9 +735,88 @@ pub fn write_feather_eager(path: &PathBuf, mut data_frame: DataFrame) {
 //     .unwrap()
 // }
 
+/// Thread pools of the loader's file reads and image decodes.
+///
+/// By default, both stages run on the global `rayon` pool, which oversubscribes the cores when the
+/// loader itself runs in many worker processes (e.g., PyTorch data-loader workers). A dedicated
+/// pool bounds the threads of its stage, and a pool of a single thread disables its parallelism.
+/// `polars` kernels run on their own pool, which is sized by `POLARS_MAX_THREADS`.
+#[derive(Clone, Debug, Default)]
+pub struct ThreadPools {
+    io: Option<Arc<ThreadPool>>,
+    decode: Option<Arc<ThreadPool>>,
+}
+
+impl ThreadPools {
+    /// Build dedicated pools of `num_io_threads` and `num_decode_threads` threads.
+    /// Stages without a thread count run on the global pool.
+    pub fn new(
+        num_io_threads: Option<usize>,
+        num_decode_threads: Option<usize>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            io: build_thread_pool(num_io_threads, "av2-io")?,
+            decode: build_thread_pool(num_decode_threads, "av2-decode")?,
+        })
+    }
+
+    /// Single-threaded pools, which disable the internal parallelism of the loader.
+    pub fn serial() -> Self {
+        Self::new(Some(1), Some(1)).unwrap()
+    }
+
+    /// Number of threads reading files.
+    pub fn num_io_threads(&self) -> usize {
+        self.io
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, |pool| {
+                pool.current_num_threads()
+            })
+    }
+
+    /// Number of threads decoding images.
+    pub fn num_decode_threads(&self) -> usize {
+        self.decode
+            .as_ref()
+            .map_or_else(rayon::current_num_threads, |pool| {
+                pool.current_num_threads()
+            })
+    }
+
+    /// Run `op` (e.g., a parallel read of files) on the I/O pool.
+    pub fn install_io<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.io {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+
+    /// Run `op` (e.g., a parallel decode of images) on the decode pool.
+    pub fn install_decode<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match &self.decode {
+            Some(pool) => pool.install(op),
+            None => op(),
+        }
+    }
+}
+
 /// Read and accumulate lidar sweeps.
 /// Accumulation will only occur if `num_accumulated_sweeps` > 1.
-/// Sweeps are motion-compensated to the most recent sweep (i.e., at `timestamp_ns`).
+/// Sweeps are motion-compensated to the most recent sweep (i.e., at `timestamp_ns`) in the
+/// `accumulation` frame.
+/// Without egovehicle poses (e.g., a partial download), only the most recent sweep is read in the
+/// egovehicle frame. The `selection` is applied to each sweep before motion compensation, so its
+/// projection must include `x`, `y`, and `z` (and `offset_ns` if `compensate_per_point` is set). The sweeps are read on the I/O pool of
+/// `thread_pools`.
+#[allow(clippy::too_many_arguments)]
 pub fn read_accumulate_lidar(
     log_dir: PathBuf,
     file_index: &DataFrame,
@@ -81,119 +824,80 @@ pub fn read_accumulate_lidar(
     timestamp_ns: u64,
     idx: usize,
     num_accumulated_sweeps: usize,
-    memory_mapped: bool,
-) -> LazyFrame {
+    accumulation: &AccumulationConfig,
+    selection: &ScanSelection,
+    memory_map: Option<&MemoryMapConfig>,
+    thread_pools: &ThreadPools,
+) -> Av2Result<LazyFrame> {
     let start_idx = i64::max(idx as i64 - num_accumulated_sweeps as i64 + 1, 0) as usize;
     let log_ids = file_index["log_id"].str().unwrap();
     let timestamps = file_index["timestamp_ns"].u64().unwrap();
     let poses_path = log_dir.join("city_SE3_egovehicle.feather");
-    let poses = read_feather_eager(&poses_path, memory_mapped);
-
-    let pose_ref = ndarray_filtered_from_frame(
-        &poses,
-        cols(POSE_COLUMNS),
-        col("timestamp_ns").eq(timestamp_ns),
-    );
-
-    let translation = pose_ref.slice(s![0, ..3]).as_standard_layout().to_owned();
-    let quat_wxyz = pose_ref.slice(s![0, 3..]).as_standard_layout().to_owned();
-    let rotation = _quat_to_mat3(&quat_wxyz.view());
-    let city_se3_ego = SE3 {
-        rotation,
-        translation,
-    };
-    let ego_se3_city = city_se3_ego.inverse();
-    let indices: Vec<_> = (start_idx..=idx).collect();
-    let mut lidar_list = indices
-        .par_iter()
-        .filter_map(|i| {
-            let log_id_i = log_ids.get(*i).unwrap();
-            match log_id_i == log_id {
-                true => Some(i),
-                _ => None,
-            }
-        })
-        .map(|i| {
-            let timestamp_ns_i = timestamps.get(*i).unwrap();
-            let lidar_path = build_lidar_file_path(log_dir.clone(), timestamp_ns_i);
-            let mut lidar = read_feather_eager(&lidar_path, memory_mapped)
-                .lazy()
-                .with_column(
-                    col("x")
-                        .map(
-                            |x| Ok(Some(Series::from_vec("timedelta_ns", vec![0_f32; x.len()]))),
-                            GetOutput::float_type(),
-                        )
-                        .alias("timedelta_ns"),
-                );
-            if timestamp_ns_i != timestamp_ns {
-                let xyz = lidar
-                    .clone()
-                    .select(&[cols(["x", "y", "z"])])
-                    .collect()
-                    .unwrap()
-                    .to_ndarray::<Float32Type>(IndexOrder::C)
-                    .unwrap();
-
-                let pose_i = poses
-                    .clone()
-                    .lazy()
-                    .filter(col("timestamp_ns").eq(lit(timestamp_ns_i)))
-                    .select(&[cols(POSE_COLUMNS)])
-                    .collect()
-                    .unwrap();
-
-                let city_se3_ego_i = data_frame_to_se3(pose_i);
-                let ego_ref_se3_ego_i = ego_se3_city.compose(&city_se3_ego_i);
-                let xyz_ref = ego_ref_se3_ego_i.transform_from(&xyz.view());
-                let x_ref = Series::new("x", xyz_ref.slice(s![.., 0]).to_owned().into_raw_vec());
-                let y_ref = Series::new("y", xyz_ref.slice(s![.., 1]).to_owned().into_raw_vec());
-                let z_ref = Series::new("z", xyz_ref.slice(s![.., 2]).to_owned().into_raw_vec());
-                let timedelta_ns = Series::new(
-                    "timedelta_ns",
-                    vec![(timestamp_ns - timestamp_ns_i) as f32 * 1e-9; xyz.shape()[0]],
-                );
-                lidar =
-                    lidar.with_columns(vec![lit(x_ref), lit(y_ref), lit(z_ref), lit(timedelta_ns)]);
-            }
-            lidar
-        })
-        .collect::<Vec<_>>();
+    if !poses_path.exists() {
+        let lidar_path = build_lidar_file_path(log_dir, timestamp_ns);
+        return Ok(read_feather_selected(&lidar_path, selection, memory_map)?
+            .lazy()
+            .with_column(lit(0_f32).alias(TIMEDELTA_COLUMN)));
+    }
+    let poses = read_feather_selected(&poses_path, &ScanSelection::default(), memory_map)?;
+    validate_data_frame(&poses_path.display().to_string(), &poses, &POSE_SCHEMA)?;
 
-    lidar_list.reverse();
-    concat(lidar_list, UnionArgs::default()).unwrap()
+    let indices = (start_idx..=idx)
+        .rev()
+        .filter(|i| log_ids.get(*i).unwrap() == log_id)
+        .collect::<Vec<_>>();
+    let sweeps = thread_pools.install_io(|| {
+        indices
+            .par_iter()
+            .map(|i| {
+                let timestamp_ns_i = timestamps.get(*i).unwrap();
+                let lidar_path = build_lidar_file_path(log_dir.clone(), timestamp_ns_i);
+                Ok((
+                    timestamp_ns_i,
+                    read_feather_selected(&lidar_path, selection, memory_map)?,
+                ))
+            })
+            .collect::<Av2Result<Vec<_>>>()
+    })?;
+    Ok(aggregate_sweeps(&sweeps, &poses, timestamp_ns, accumulation)?.lazy())
 }
 
 /// Read a dataframe, but filter for the specified timestamp.
+/// The file is validated against `schema` first, which lists every missing or mistyped column.
 pub fn read_timestamped_feather(
-    path: &PathBuf,
+    path: &Path,
     columns: &Vec<&str>,
+    schema: &[(&str, ColumnType)],
     timestamp_ns: &u64,
-    memory_mapped: bool,
-) -> LazyFrame {
-    read_feather_eager(path, memory_mapped)
+    memory_map: Option<&MemoryMapConfig>,
+) -> Av2Result<LazyFrame> {
+    let data_frame = read_feather_selected(path, &ScanSelection::default(), memory_map)?;
+    validate_data_frame(&path.display().to_string(), &data_frame, schema)?;
+    Ok(data_frame
         .lazy()
         .filter(col("timestamp_ns").eq(*timestamp_ns))
-        .select(&[cols(columns)])
+        .select(&[cols(columns)]))
 }
 
 /// Read an image into an RGBA u8 image.
-pub fn read_image_rgba8(path: &PathBuf) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
-    ImageReader::open(path)
-        .unwrap()
+pub fn read_image_rgba8(path: &Path) -> Av2Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    let _span = info_span!("read_image", path = %path.display()).entered();
+    let image = ImageReader::open(path)
+        .map_err(|err| Av2Error::open(path, err))?
         .decode()
-        .unwrap()
-        .to_rgba8()
+        .map_err(|err| Av2Error::decode(path, err))?;
+    Ok(image.to_rgba8())
+}
+
+/// Decode an encoded image (e.g., the bytes of a JPEG file) into an RGBA u8 image.
+pub fn decode_image_rgba8(bytes: &[u8]) -> anyhow::Result<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    let _span = info_span!("decode_image").entered();
+    Ok(image::load_from_memory(bytes)?.to_rgba8())
 }
 
 /// Read an image into an RGBA u8 image and convert to `ndarray`.
-pub fn read_image_rgba8_ndarray(path: &PathBuf) -> Array3<u8> {
-    let image = ImageReader::open(path)
-        .unwrap()
-        .decode()
-        .unwrap()
-        .to_rgba8();
-    image.into_ndarray3()
+pub fn read_image_rgba8_ndarray(path: &Path) -> Av2Result<Array3<u8>> {
+    Ok(read_image_rgba8(path)?.into_ndarray3())
 }
 
 /// Build the lidar file path.
@@ -211,17 +915,15 @@ pub fn build_lidar_file_path(log_dir: PathBuf, timestamp_ns: u64) -> PathBuf {
 }
 
 /// Convert a dataframe to `ndarray`.
-pub fn ndarray_from_frame(frame: &DataFrame, exprs: Expr) -> Array2<f32> {
-    frame
+pub fn ndarray_from_frame(frame: &DataFrame, exprs: Expr) -> Av2Result<Array2<f32>> {
+    Ok(frame
         .clone()
         .lazy()
         .select(&[exprs])
-        .collect()
-        .unwrap()
-        .to_ndarray::<Float32Type>(IndexOrder::C)
-        .unwrap()
+        .collect()?
+        .to_ndarray::<Float32Type>(IndexOrder::C)?
         .as_standard_layout()
-        .to_owned()
+        .to_owned())
 }
 
 /// Convert a dataframe to `ndarray` and filter.
@@ -229,54 +931,291 @@ pub fn ndarray_filtered_from_frame(
     frame: &DataFrame,
     select_exprs: Expr,
     filter_exprs: Expr,
-) -> Array2<f32> {
-    frame
+) -> Av2Result<Array2<f32>> {
+    Ok(frame
         .clone()
         .lazy()
         .filter(filter_exprs)
         .select(&[select_exprs])
-        .collect()
-        .unwrap()
-        .to_ndarray::<Float32Type>(IndexOrder::C)
-        .unwrap()
+        .collect()?
+        .to_ndarray::<Float32Type>(IndexOrder::C)?
         .as_standard_layout()
-        .to_owned()
+        .to_owned())
 }
 
 /// Convert a data_frame with pose columns into an `se3` object.
-pub fn data_frame_to_se3(data_frame: DataFrame) -> SE3 {
-    let qw = extract_f32_from_data_frame(&data_frame, "qw");
-    let qx = extract_f32_from_data_frame(&data_frame, "qx");
-    let qy = extract_f32_from_data_frame(&data_frame, "qy");
-    let qz = extract_f32_from_data_frame(&data_frame, "qz");
-    let tx_m = extract_f32_from_data_frame(&data_frame, "tx_m");
-    let ty_m = extract_f32_from_data_frame(&data_frame, "ty_m");
-    let tz_m = extract_f32_from_data_frame(&data_frame, "tz_m");
+pub fn data_frame_to_se3(data_frame: DataFrame) -> Av2Result<SE3> {
+    let qw = extract_f32_from_data_frame(&data_frame, "qw")?;
+    let qx = extract_f32_from_data_frame(&data_frame, "qx")?;
+    let qy = extract_f32_from_data_frame(&data_frame, "qy")?;
+    let qz = extract_f32_from_data_frame(&data_frame, "qz")?;
+    let tx_m = extract_f32_from_data_frame(&data_frame, "tx_m")?;
+    let ty_m = extract_f32_from_data_frame(&data_frame, "ty_m")?;
+    let tz_m = extract_f32_from_data_frame(&data_frame, "tz_m")?;
     let quat_wxyz = Array::<f32, Ix1>::from_vec(vec![qw, qx, qy, qz]);
-    let rotation = _quat_to_mat3(&quat_wxyz.view());
     let translation = Array::<f32, Ix1>::from_vec(vec![tx_m, ty_m, tz_m]);
-    SE3 {
-        rotation,
-        translation,
-    }
+    SE3::from_quat_wxyz(&quat_wxyz.view(), &translation.view())
 }
 
 /// Extract an f32 field from a single row data frame.
-pub fn extract_f32_from_data_frame(data_frame: &DataFrame, column: &str) -> f32 {
-    data_frame
-        .column(column)
-        .unwrap()
-        .get(0)
-        .unwrap()
-        .try_extract::<f32>()
-        .unwrap()
+pub fn extract_f32_from_data_frame(data_frame: &DataFrame, column: &str) -> Av2Result<f32> {
+    Ok(data_frame.column(column)?.get(0)?.try_extract::<f32>()?)
 }
 
 /// Extract a usize field from a single row data frame.
-pub fn extract_usize_from_data_frame(data_frame: &DataFrame, column: &str) -> usize {
-    data_frame[column]
-        .get(0)
-        .unwrap()
-        .try_extract::<usize>()
-        .unwrap()
+pub fn extract_usize_from_data_frame(data_frame: &DataFrame, column: &str) -> Av2Result<usize> {
+    Ok(data_frame.column(column)?.get(0)?.try_extract::<usize>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use polars::prelude::*;
+
+    use super::{
+        is_uncompressed_feather, read_feather_eager, read_feather_selected, read_ipc_stream,
+        read_ipc_stream_eager, read_selected, write_cuboids, write_feather_eager,
+        write_feather_uncompressed, write_ipc_stream, write_ipc_stream_eager, write_lidar,
+        FileCompression, MemoryMapConfig, ScanSelection, ThreadPools, WriteConfig,
+    };
+    use crate::constants::{ANNOTATION_FILE_COLUMNS, LIDAR_COLUMNS};
+    use crate::error::Av2Error;
+    use crate::geometry::aggregation::TIMEDELTA_COLUMN;
+    use crate::synthetic::test_dir;
+
+    #[test]
+    fn test_read_selected() {
+        let lidar_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(
+            "../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede/sensors/lidar/315966265259836000.feather",
+        );
+        let lidar = read_feather_eager(&lidar_path, false);
+        let selection = ScanSelection::with_columns(&["x", "intensity"])
+            .filter("z", Some(-1.), Some(1.))
+            .filter("x", Some(0.), None);
+        let selected = read_selected(&lidar_path, &selection, None).unwrap();
+        assert_eq!(selected.get_column_names(), vec!["x", "intensity"]);
+
+        let z = lidar["z"].cast(&DataType::Float64).unwrap();
+        let x = lidar["x"].cast(&DataType::Float64).unwrap();
+        let is_selected = z.gt_eq(-1.).unwrap() & z.lt_eq(1.).unwrap() & x.gt_eq(0.).unwrap();
+        assert_eq!(selected.height(), is_selected.sum().unwrap() as usize);
+        assert!(selected.height() > 0 && selected.height() < lidar.height());
+
+        // Parquet scans return the same rows.
+        let root_dir = test_dir();
+        let parquet_path = root_dir.join("lidar.parquet");
+        let mut lidar = lidar;
+        ParquetWriter::new(std::fs::File::create(&parquet_path).unwrap())
+            .finish(&mut lidar)
+            .unwrap();
+        let selected_parquet = read_selected(&parquet_path, &selection, None).unwrap();
+        assert!(selected_parquet.equals(&selected));
+    }
+
+    #[test]
+    fn test_ipc_stream() {
+        let mut data_frame = df!(
+            "x" => [1_f32, 2., 3.],
+            "intensity" => [4_u8, 5, 6],
+            "log_id" => ["a", "b", "c"],
+        )
+        .unwrap();
+
+        let mut buffer = vec![];
+        write_ipc_stream(&mut buffer, &mut data_frame).unwrap();
+        assert!(read_ipc_stream(buffer.as_slice())
+            .unwrap()
+            .equals(&data_frame));
+
+        let root_dir = test_dir();
+        let stream_path = root_dir.join("sweep.arrows");
+        write_ipc_stream_eager(&stream_path, data_frame.clone()).unwrap();
+        assert!(read_ipc_stream_eager(&stream_path)
+            .unwrap()
+            .equals(&data_frame));
+
+        // Truncated and missing streams are errors rather than panics.
+        let bytes = std::fs::read(&stream_path).unwrap();
+        std::fs::write(&stream_path, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(matches!(
+            read_ipc_stream_eager(&stream_path),
+            Err(Av2Error::Decode { .. })
+        ));
+        assert!(matches!(
+            read_ipc_stream_eager(&root_dir.join("missing.arrows")),
+            Err(Av2Error::MissingFile { .. })
+        ));
+
+        let feather_path = root_dir.join("sweep.feather");
+        write_feather_uncompressed(&feather_path, data_frame.clone()).unwrap();
+        assert!(read_feather_eager(&feather_path, true).equals(&data_frame));
+    }
+
+    #[test]
+    fn test_read_memory_mapped() {
+        let root_dir = test_dir();
+        let cfg = MemoryMapConfig {
+            cache_dir: root_dir.join("cache"),
+            ..Default::default()
+        };
+        let path = root_dir.join("annotations.feather");
+        let data_frame = df!("timestamp_ns" => [0_u64, 1, 2], "x" => [1_f32, 2., 3.]).unwrap();
+        write_feather_eager(&path, data_frame.clone()).unwrap();
+
+        let selection = ScanSelection::with_columns(&["x"]).filter("timestamp_ns", Some(1.), None);
+        let selected = read_feather_selected(&path, &selection, Some(&cfg)).unwrap();
+        assert!(selected.equals(&data_frame.tail(Some(2)).select(["x"]).unwrap()));
+
+        // The uncompressed copy is written once and is invalidated by a modified source.
+        let copy_path = cfg.copy_path(&path).unwrap();
+        assert!(copy_path.starts_with(&cfg.cache_dir) && copy_path.exists());
+        assert!(read_feather_eager(&copy_path, true).equals(&data_frame));
+        write_feather_eager(&path, data_frame.head(Some(1))).unwrap();
+        assert_ne!(cfg.copy_path(&path).unwrap(), copy_path);
+        assert_eq!(
+            read_feather_selected(&path, &selection, Some(&cfg))
+                .unwrap()
+                .height(),
+            0
+        );
+
+        // Uncompressed files are mapped in place.
+        let uncompressed_path = root_dir.join("city_SE3_egovehicle.feather");
+        write_feather_uncompressed(&uncompressed_path, data_frame.clone()).unwrap();
+        assert!(is_uncompressed_feather(&uncompressed_path).unwrap());
+        assert!(!is_uncompressed_feather(&path).unwrap());
+        assert_eq!(
+            cfg.mapped_path(&uncompressed_path).unwrap(),
+            uncompressed_path
+        );
+        let selected = read_feather_selected(&uncompressed_path, &selection, Some(&cfg)).unwrap();
+        assert_eq!(selected.height(), 2);
+        assert!(!cfg.copy_path(&uncompressed_path).unwrap().exists());
+
+        cfg.clear().unwrap();
+        assert_eq!(cfg.size_bytes().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_memory_map_eviction() {
+        let root_dir = test_dir();
+        let data_frame = df!("x" => (0..1000).map(|x| x as f32).collect::<Vec<_>>()).unwrap();
+        let paths = (0..3)
+            .map(|i| {
+                let path = root_dir.join(format!("{i}.feather"));
+                write_feather_eager(&path, data_frame.clone()).unwrap();
+                path
+            })
+            .collect::<Vec<_>>();
+        let mut cfg = MemoryMapConfig {
+            cache_dir: root_dir.join("cache"),
+            max_size_bytes: u64::MAX,
+        };
+        let copy_size = std::fs::metadata(cfg.ensure_copy(&paths[0]).unwrap())
+            .unwrap()
+            .len();
+
+        // The budget fits two copies, so the least recently used one is evicted.
+        // The access order is tracked in memory, so it does not depend on the file times.
+        cfg.max_size_bytes = 2 * copy_size;
+        cfg.ensure_copy(&paths[1]).unwrap();
+        cfg.ensure_copy(&paths[0]).unwrap();
+        cfg.ensure_copy(&paths[2]).unwrap();
+        assert_eq!(cfg.size_bytes().unwrap(), 2 * copy_size);
+        assert!(cfg.copy_path(&paths[0]).unwrap().exists());
+        assert!(!cfg.copy_path(&paths[1]).unwrap().exists());
+        assert!(cfg.copy_path(&paths[2]).unwrap().exists());
+
+        // A copy removed by another process is dropped from the tracked size.
+        std::fs::remove_file(cfg.copy_path(&paths[0]).unwrap()).unwrap();
+        cfg.ensure_copy(&paths[1]).unwrap();
+        assert!(cfg.copy_path(&paths[1]).unwrap().exists());
+        assert!(cfg.copy_path(&paths[2]).unwrap().exists());
+        assert_eq!(cfg.size_bytes().unwrap(), 2 * copy_size);
+
+        // Concurrent copies of the same file never collide.
+        cfg.clear().unwrap();
+        let copies = std::thread::scope(|scope| {
+            (0..8)
+                .map(|_| scope.spawn(|| cfg.ensure_copy(&paths[0]).unwrap()))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|x| x.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert!(copies
+            .iter()
+            .all(|x| read_feather_eager(x, true).equals(&data_frame)));
+        assert_eq!(cfg.size_bytes().unwrap(), copy_size);
+    }
+
+    #[test]
+    fn test_write_lidar_and_cuboids() {
+        let root_dir = test_dir();
+        let log_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede");
+        let lidar_path = log_dir.join("sensors/lidar/315966265259836000.feather");
+        let mut lidar = read_feather_eager(&lidar_path, false);
+        lidar
+            .with_column(Series::new(TIMEDELTA_COLUMN, vec![0.1_f32; lidar.height()]))
+            .unwrap();
+        // Shuffle the columns to check that the canonical order is restored.
+        let lidar = lidar
+            .select([
+                TIMEDELTA_COLUMN,
+                "offset_ns",
+                "z",
+                "y",
+                "x",
+                "laser_number",
+                "intensity",
+            ])
+            .unwrap();
+
+        // Half-precision feather files round-trip the (half-precision) dataset sweeps.
+        let feather_path = root_dir.join("lidar.feather");
+        write_lidar(&feather_path, &lidar, &WriteConfig::default()).unwrap();
+        let written = read_feather_eager(&feather_path, false);
+        let mut expected_columns = LIDAR_COLUMNS.to_vec();
+        expected_columns.push(TIMEDELTA_COLUMN);
+        assert_eq!(written.get_column_names(), expected_columns);
+        assert!(written.equals(&lidar.select(&expected_columns).unwrap()));
+
+        let parquet_path = root_dir.join("lidar.parquet");
+        let cfg = WriteConfig {
+            compression: FileCompression::Zstd,
+            half_precision: false,
+        };
+        write_lidar(&parquet_path, &lidar.select(["x", "y", "z"]).unwrap(), &cfg).unwrap();
+        let written = read_selected(&parquet_path, &ScanSelection::default(), None).unwrap();
+        assert_eq!(written.get_column_names(), vec!["x", "y", "z"]);
+        assert_eq!(written.height(), lidar.height());
+        assert!(write_lidar(&parquet_path, &lidar.select(["x"]).unwrap(), &cfg).is_err());
+
+        let annotations = read_feather_eager(&log_dir.join("annotations.feather"), false);
+        let cuboids_path = root_dir.join("annotations.feather");
+        write_cuboids(
+            &cuboids_path,
+            &annotations
+                .select(ANNOTATION_FILE_COLUMNS.iter().rev())
+                .unwrap(),
+            &WriteConfig::default(),
+        )
+        .unwrap();
+        assert!(read_feather_eager(&cuboids_path, false).equals(&annotations));
+    }
+
+    #[test]
+    fn test_thread_pools() {
+        let pools = ThreadPools::new(Some(2), Some(1)).unwrap();
+        assert_eq!((pools.num_io_threads(), pools.num_decode_threads()), (2, 1));
+        assert_eq!(pools.install_io(rayon::current_num_threads), 2);
+        assert_eq!(pools.install_decode(rayon::current_num_threads), 1);
+
+        let pools = ThreadPools::default();
+        assert_eq!(pools.num_io_threads(), rayon::current_num_threads());
+        assert_eq!(ThreadPools::serial().num_io_threads(), 1);
+        assert!(ThreadPools::new(Some(0), None).is_err());
+    }
 }
@@ -0,0 +1,133 @@
+//! # capsule
+//!
+//! Zero-copy export of dataframes through the Arrow PyCapsule interface.
+//!
+//! Tables implement `__arrow_c_stream__`, so `pyarrow.table`, `polars.from_arrow`, and other
+//! consumers of the Arrow C stream interface view the record batches in place. Numeric columns
+//! are exported without a copy, while string columns are converted to the standard large string
+//! layout.
+
+use std::ffi::CString;
+
+use polars::{
+    export::arrow::{
+        array::{Array, StructArray},
+        datatypes::{ArrowDataType, Field},
+        ffi::{export_iterator, ArrowArrayStream},
+    },
+    prelude::{DataFrame, PolarsResult},
+};
+use pyo3::{prelude::*, types::PyCapsule};
+
+/// Capsule name of an Arrow C stream.
+const ARROW_ARRAY_STREAM: &str = "arrow_array_stream";
+
+/// Dataframe exposed to Python through the Arrow PyCapsule interface.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct ArrowTable {
+    /// Exported dataframe.
+    pub data_frame: DataFrame,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl ArrowTable {
+    /// Arrow C stream of the table's record batches. The requested schema is ignored.
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<&'py PyCapsule> {
+        let _ = requested_schema;
+        let stream = _SendStream(export_stream(&self.data_frame));
+        PyCapsule::new(py, stream, Some(CString::new(ARROW_ARRAY_STREAM)?))
+    }
+
+    /// Column names.
+    #[getter(column_names)]
+    fn py_column_names(&self) -> Vec<String> {
+        self.data_frame
+            .get_column_names()
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.data_frame.height()
+    }
+}
+
+impl From<DataFrame> for ArrowTable {
+    fn from(data_frame: DataFrame) -> Self {
+        Self { data_frame }
+    }
+}
+
+/// Export the record batches of `data_frame` as an Arrow C stream of struct arrays.
+/// The stream shares the buffers of the dataframe.
+pub fn export_stream(data_frame: &DataFrame) -> ArrowArrayStream {
+    let mut data_frame = data_frame.clone();
+    data_frame.align_chunks();
+    let fields = data_frame.schema().to_arrow(false).fields;
+    let data_type = ArrowDataType::Struct(fields);
+    let batches = data_frame
+        .iter_chunks(false)
+        .map(|chunk| {
+            let array = StructArray::try_new(data_type.clone(), chunk.into_arrays(), None)?;
+            Ok(Box::new(array) as Box<dyn Array>)
+        })
+        .collect::<Vec<PolarsResult<_>>>();
+    export_iterator(
+        Box::new(batches.into_iter()),
+        Field::new("", data_type, false),
+    )
+}
+
+/// Stream moved into a capsule. Consumers take ownership by moving it out and clearing its
+/// release callback, otherwise it is released with the capsule.
+#[repr(transparent)]
+struct _SendStream(ArrowArrayStream);
+
+// The stream's private data is owned and only accessed by its consumer.
+unsafe impl Send for _SendStream {}
+
+#[cfg(test)]
+mod tests {
+    use polars::{
+        export::arrow::{
+            array::{Array, PrimitiveArray, StructArray},
+            ffi::ArrowArrayStreamReader,
+        },
+        prelude::*,
+    };
+
+    use super::export_stream;
+
+    #[test]
+    fn test_export_stream() {
+        let data_frame = df!(
+            "x" => [1_f32, 2., 3.],
+            "category" => ["a", "b", "c"]
+        )
+        .unwrap();
+        let stream = Box::new(export_stream(&data_frame));
+        let mut reader = unsafe { ArrowArrayStreamReader::try_new(stream).unwrap() };
+        let batch = unsafe { reader.next() }.unwrap().unwrap();
+        assert!(unsafe { reader.next() }.is_none());
+
+        let batch = batch.as_any().downcast_ref::<StructArray>().unwrap();
+        assert_eq!(Array::len(batch), 3);
+        let x = batch.values()[0]
+            .as_any()
+            .downcast_ref::<PrimitiveArray<f32>>()
+            .unwrap();
+        assert_eq!(x.values().as_slice(), &[1., 2., 3.]);
+
+        // The exported values share the dataframe's buffer.
+        let values = data_frame["x"].f32().unwrap().cont_slice().unwrap();
+        assert_eq!(x.values().as_slice().as_ptr(), values.as_ptr());
+    }
+}
@@ -0,0 +1,282 @@
+//! # dlpack
+//!
+//! Zero-copy export of arrays through the DLPack protocol.
+//!
+//! Tensors implement `__dlpack__` and `__dlpack_device__`, so `torch.from_dlpack`,
+//! `numpy.from_dlpack`, and other DLPack consumers view the array's buffer in place. Arrays that
+//! are not in standard (row-major) layout are copied into it once on construction. The buffer is
+//! shared by every export and freed once the tensor and all of its consumers are dropped.
+
+use std::{
+    any::Any,
+    ffi::{c_void, CStr},
+    sync::Arc,
+};
+
+use ndarray::{Array, Dimension};
+use pyo3::{ffi, prelude::*};
+
+/// Capsule name of an unconsumed DLPack tensor.
+const DLTENSOR: &CStr = c"dltensor";
+
+/// DLPack device type of host memory.
+const DL_CPU: i32 = 1;
+
+/// Device holding a tensor's buffer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DLDevice {
+    /// Device type (e.g., `1` for the CPU).
+    pub device_type: i32,
+    /// Device index.
+    pub device_id: i32,
+}
+
+/// Element type of a tensor.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DLDataType {
+    /// Type code (`0` signed integer, `1` unsigned integer, `2` float, `6` boolean).
+    pub code: u8,
+    /// Number of bits per element.
+    pub bits: u8,
+    /// Number of lanes per element.
+    pub lanes: u16,
+}
+
+/// Plain tensor view of a buffer.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLTensor {
+    /// Buffer of the tensor.
+    pub data: *mut c_void,
+    /// Device holding the buffer.
+    pub device: DLDevice,
+    /// Number of dimensions.
+    pub ndim: i32,
+    /// Element type.
+    pub dtype: DLDataType,
+    /// Shape of the tensor.
+    pub shape: *mut i64,
+    /// Strides (in elements), or null for a row-major tensor.
+    pub strides: *mut i64,
+    /// Offset (in bytes) of the first element from `data`.
+    pub byte_offset: u64,
+}
+
+/// Tensor view together with the deleter that releases it.
+#[repr(C)]
+#[derive(Debug)]
+pub struct DLManagedTensor {
+    /// Tensor view.
+    pub dl_tensor: DLTensor,
+    /// Context of the producer.
+    pub manager_ctx: *mut c_void,
+    /// Called by the consumer once it no longer needs the tensor.
+    pub deleter: Option<unsafe extern "C" fn(*mut DLManagedTensor)>,
+}
+
+/// Element types with a DLPack data type.
+pub trait DLPackElement: Clone + Send + Sync + 'static {
+    /// DLPack data type of the element.
+    const DTYPE: DLDataType;
+}
+
+macro_rules! impl_dlpack_element {
+    ($($t:ty => ($code:expr, $bits:expr)),* $(,)?) => {
+        $(
+            impl DLPackElement for $t {
+                const DTYPE: DLDataType = DLDataType {
+                    code: $code,
+                    bits: $bits,
+                    lanes: 1,
+                };
+            }
+        )*
+    };
+}
+
+impl_dlpack_element!(
+    bool => (6, 8),
+    u8 => (1, 8),
+    u32 => (1, 32),
+    u64 => (1, 64),
+    usize => (1, usize::BITS as u8),
+    i32 => (0, 32),
+    i64 => (0, 64),
+    f32 => (2, 32),
+    f64 => (2, 64),
+);
+
+/// Array exposed to Python through the DLPack protocol.
+#[pyclass(module = "av2._r")]
+#[derive(Clone)]
+pub struct DLPackTensor {
+    /// Owner of the buffer.
+    owner: Arc<dyn Any + Send + Sync>,
+    /// Address of the first element.
+    data: usize,
+    shape: Vec<i64>,
+    dtype: DLDataType,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl DLPackTensor {
+    /// DLPack capsule viewing the tensor's buffer. The stream and version arguments are ignored:
+    /// the buffer lives in host memory and is exported as a legacy (unversioned) capsule.
+    #[pyo3(signature = (stream = None, max_version = None, dl_device = None, copy = None))]
+    fn __dlpack__(
+        &self,
+        py: Python<'_>,
+        stream: Option<PyObject>,
+        max_version: Option<PyObject>,
+        dl_device: Option<PyObject>,
+        copy: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        let _ = (stream, max_version, dl_device, copy);
+        let managed = self.export();
+        // SAFETY: `managed` stays valid until its deleter runs, which happens either in the
+        // capsule's destructor or, once consumed, in the consumer.
+        unsafe {
+            let capsule = ffi::PyCapsule_New(
+                managed as *mut c_void,
+                DLTENSOR.as_ptr(),
+                Some(_capsule_destructor),
+            );
+            if capsule.is_null() {
+                _delete_managed_tensor(managed);
+                return Err(PyErr::fetch(py));
+            }
+            Ok(PyObject::from_owned_ptr(py, capsule))
+        }
+    }
+
+    /// Device type and index of the buffer.
+    fn __dlpack_device__(&self) -> (i32, i32) {
+        (DL_CPU, 0)
+    }
+
+    /// Shape of the tensor.
+    #[getter(shape)]
+    fn py_shape(&self) -> Vec<i64> {
+        self.shape.clone()
+    }
+
+    fn __len__(&self) -> usize {
+        self.shape.first().copied().unwrap_or(0) as usize
+    }
+}
+
+impl<T: DLPackElement, D: Dimension + 'static> From<Array<T, D>> for DLPackTensor {
+    fn from(array: Array<T, D>) -> Self {
+        let array = match array.is_standard_layout() {
+            true => array,
+            false => array.as_standard_layout().into_owned(),
+        };
+        let shape = array.shape().iter().map(|x| *x as i64).collect();
+        let data = array.as_ptr() as usize;
+        Self {
+            owner: Arc::new(array),
+            data,
+            shape,
+            dtype: T::DTYPE,
+        }
+    }
+}
+
+impl DLPackTensor {
+    /// Shape of the tensor.
+    pub fn shape(&self) -> &[i64] {
+        &self.shape
+    }
+
+    /// Managed tensor sharing the buffer, to be released by calling its deleter.
+    fn export(&self) -> *mut DLManagedTensor {
+        let mut context = Box::new(_ManagedContext {
+            managed: DLManagedTensor {
+                dl_tensor: DLTensor {
+                    data: self.data as *mut c_void,
+                    device: DLDevice {
+                        device_type: DL_CPU,
+                        device_id: 0,
+                    },
+                    ndim: self.shape.len() as i32,
+                    dtype: self.dtype,
+                    shape: std::ptr::null_mut(),
+                    strides: std::ptr::null_mut(),
+                    byte_offset: 0,
+                },
+                manager_ctx: std::ptr::null_mut(),
+                deleter: Some(_delete_managed_tensor),
+            },
+            shape: self.shape.clone(),
+            _owner: self.owner.clone(),
+        });
+        context.managed.dl_tensor.shape = context.shape.as_mut_ptr();
+        Box::into_raw(context) as *mut DLManagedTensor
+    }
+}
+
+/// Managed tensor with the shape and buffer it points to. The managed tensor is the first field,
+/// so pointers to either are interchangeable.
+#[repr(C)]
+struct _ManagedContext {
+    managed: DLManagedTensor,
+    shape: Vec<i64>,
+    _owner: Arc<dyn Any + Send + Sync>,
+}
+
+/// Deleter of exported tensors.
+unsafe extern "C" fn _delete_managed_tensor(managed: *mut DLManagedTensor) {
+    drop(Box::from_raw(managed as *mut _ManagedContext));
+}
+
+/// Release the tensor of a capsule that was never consumed. Consumers rename the capsule, after
+/// which they own the tensor.
+unsafe extern "C" fn _capsule_destructor(capsule: *mut ffi::PyObject) {
+    if ffi::PyCapsule_IsValid(capsule, DLTENSOR.as_ptr()) == 1 {
+        let managed = ffi::PyCapsule_GetPointer(capsule, DLTENSOR.as_ptr()) as *mut DLManagedTensor;
+        if let Some(deleter) = (*managed).deleter {
+            deleter(managed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ndarray::{array, Array};
+
+    use super::{DLPackElement, DLPackTensor};
+
+    #[test]
+    fn test_export() {
+        let array = array![[1_f32, 2., 3.], [4., 5., 6.]];
+        let values = array.as_ptr();
+        let tensor = DLPackTensor::from(array);
+        assert_eq!(tensor.shape(), &[2, 3]);
+
+        let managed = tensor.export();
+        assert_eq!(Arc::strong_count(&tensor.owner), 2);
+        unsafe {
+            let dl_tensor = &(*managed).dl_tensor;
+            assert_eq!(dl_tensor.data as *const f32, values);
+            assert_eq!(dl_tensor.ndim, 2);
+            assert_eq!(dl_tensor.dtype, f32::DTYPE);
+            assert_eq!(std::slice::from_raw_parts(dl_tensor.shape, 2), &[2, 3]);
+            ((*managed).deleter.unwrap())(managed);
+        }
+        assert_eq!(Arc::strong_count(&tensor.owner), 1);
+
+        // Transposed arrays are copied into standard layout.
+        let array = Array::from_shape_vec((2, 3), vec![1_u8, 2, 3, 4, 5, 6])
+            .unwrap()
+            .reversed_axes();
+        let tensor = DLPackTensor::from(array);
+        assert_eq!(tensor.shape(), &[3, 2]);
+        let data = unsafe { std::slice::from_raw_parts(tensor.data as *const u8, 6) };
+        assert_eq!(data, &[1, 4, 2, 5, 3, 6]);
+    }
+}
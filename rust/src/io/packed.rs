@@ -0,0 +1,335 @@
+//! # packed
+//!
+//! Packed sample buffers of a fixed layout.
+//!
+//! GPU preprocessing pipelines (e.g., an NVIDIA DALI external source) consume each sample as a
+//! few contiguous arrays of a fixed shape instead of data frames. A packed sample holds the
+//! `(max_points,4)` points (`PACKED_POINT_COLUMNS`), the `(max_cuboids,10)` cuboids
+//! (`CUBOID_COLUMNS`), and the `(max_cuboids,)` class ids, padded past the valid rows, along with
+//! a `PackedDescriptor` of the layout and the valid counts.
+
+use anyhow::{ensure, Result};
+use ndarray::{s, Array, Ix1, Ix2};
+use numpy::{IntoPyArray, PyArray};
+use polars::lazy::dsl::cols;
+use pyo3::{prelude::*, types::PyBytes};
+
+use crate::{
+    data_loader::{Sweep, CLASS_ID_COLUMN},
+    structures::cuboid::{CuboidList, CUBOID_COLUMNS},
+};
+
+use super::{dlpack::DLPackTensor, ndarray_from_frame};
+
+/// Lidar columns of the packed points in order.
+pub const PACKED_POINT_COLUMNS: [&str; 4] = ["x", "y", "z", "intensity"];
+
+/// Version of the packed layout, bumped whenever the arrays or the descriptor change.
+pub const PACKED_LAYOUT_VERSION: u32 = 1;
+
+/// Size of an encoded `PackedDescriptor` (in bytes).
+pub const PACKED_DESCRIPTOR_SIZE_BYTES: usize = 48;
+
+const _: () = assert!(std::mem::size_of::<PackedDescriptor>() == PACKED_DESCRIPTOR_SIZE_BYTES);
+
+/// Capacities of the packed arrays.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PackedLayout {
+    /// Number of point rows. Points past it are dropped.
+    pub max_points: usize,
+    /// Number of cuboid rows. Cuboids past it are dropped.
+    pub max_cuboids: usize,
+    /// Value of the padded point and cuboid rows (the padded class ids are `-1`).
+    pub pad_value: f32,
+}
+
+impl Default for PackedLayout {
+    fn default() -> Self {
+        Self {
+            max_points: 1 << 17,
+            max_cuboids: 512,
+            pad_value: 0.,
+        }
+    }
+}
+
+/// Layout and valid counts of a packed sample.
+///
+/// The `#[repr(C)]` layout matches `to_bytes`, so the descriptor may be read as a C struct (or a
+/// NumPy structured array) without parsing.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PackedDescriptor {
+    /// Nanosecond timestamp of the sweep.
+    pub timestamp_ns: u64,
+    /// Version of the layout (`PACKED_LAYOUT_VERSION`).
+    pub version: u32,
+    /// Number of point rows.
+    pub max_points: u32,
+    /// Number of values per point row.
+    pub point_stride: u32,
+    /// Number of cuboid rows.
+    pub max_cuboids: u32,
+    /// Number of values per cuboid row.
+    pub cuboid_stride: u32,
+    /// Number of valid (leading) point rows.
+    pub num_points: u32,
+    /// Number of valid (leading) cuboid rows.
+    pub num_cuboids: u32,
+    /// Number of points which did not fit.
+    pub num_dropped_points: u32,
+    /// Number of cuboids which did not fit.
+    pub num_dropped_cuboids: u32,
+    /// Value of the padded rows.
+    pub pad_value: f32,
+}
+
+impl PackedDescriptor {
+    /// Encode the descriptor in its `#[repr(C)]` layout (little-endian).
+    pub fn to_bytes(&self) -> [u8; PACKED_DESCRIPTOR_SIZE_BYTES] {
+        let mut bytes = [0; PACKED_DESCRIPTOR_SIZE_BYTES];
+        bytes[..8].copy_from_slice(&self.timestamp_ns.to_le_bytes());
+        let fields = [
+            self.version,
+            self.max_points,
+            self.point_stride,
+            self.max_cuboids,
+            self.cuboid_stride,
+            self.num_points,
+            self.num_cuboids,
+            self.num_dropped_points,
+            self.num_dropped_cuboids,
+            self.pad_value.to_bits(),
+        ];
+        for (chunk, field) in bytes[8..].chunks_exact_mut(4).zip(fields) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode a descriptor encoded by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == PACKED_DESCRIPTOR_SIZE_BYTES,
+            "Expected a {PACKED_DESCRIPTOR_SIZE_BYTES}-byte descriptor, found {} bytes.",
+            bytes.len()
+        );
+        let field = |i: usize| u32::from_le_bytes(bytes[8 + 4 * i..12 + 4 * i].try_into().unwrap());
+        let descriptor = Self {
+            timestamp_ns: u64::from_le_bytes(bytes[..8].try_into().unwrap()),
+            version: field(0),
+            max_points: field(1),
+            point_stride: field(2),
+            max_cuboids: field(3),
+            cuboid_stride: field(4),
+            num_points: field(5),
+            num_cuboids: field(6),
+            num_dropped_points: field(7),
+            num_dropped_cuboids: field(8),
+            pad_value: f32::from_bits(field(9)),
+        };
+        ensure!(
+            descriptor.version == PACKED_LAYOUT_VERSION,
+            "Unsupported packed layout version {}.",
+            descriptor.version
+        );
+        Ok(descriptor)
+    }
+}
+
+/// Sample packed into padded arrays of a fixed layout.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct PackedSample {
+    /// Log id and nanosecond timestamp (unique identifier).
+    #[pyo3(get)]
+    pub sweep_uuid: (String, u64),
+    /// `(max_points,4)` points (`PACKED_POINT_COLUMNS`).
+    pub points: Array<f32, Ix2>,
+    /// `(max_cuboids,10)` cuboids (`CUBOID_COLUMNS`).
+    pub cuboids: Array<f32, Ix2>,
+    /// `(max_cuboids,)` class ids, or `-1` for padded rows and cuboids without a class id.
+    pub class_ids: Array<i64, Ix1>,
+    /// Layout and valid counts.
+    pub descriptor: PackedDescriptor,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl PackedSample {
+    /// `(max_points,4)` points.
+    #[getter(points)]
+    fn py_points<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix2> {
+        self.points.clone().into_pyarray(py)
+    }
+
+    /// `(max_cuboids,10)` cuboids.
+    #[getter(cuboids)]
+    fn py_cuboids<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix2> {
+        self.cuboids.clone().into_pyarray(py)
+    }
+
+    /// `(max_cuboids,)` class ids.
+    #[getter(class_ids)]
+    fn py_class_ids<'py>(&self, py: Python<'py>) -> &'py PyArray<i64, Ix1> {
+        self.class_ids.clone().into_pyarray(py)
+    }
+
+    /// Number of valid point rows.
+    #[getter(num_points)]
+    fn py_num_points(&self) -> u32 {
+        self.descriptor.num_points
+    }
+
+    /// Number of valid cuboid rows.
+    #[getter(num_cuboids)]
+    fn py_num_cuboids(&self) -> u32 {
+        self.descriptor.num_cuboids
+    }
+
+    /// Descriptor encoded in its `#[repr(C)]` layout.
+    #[getter(descriptor)]
+    fn py_descriptor<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.descriptor.to_bytes())
+    }
+
+    /// Points, cuboids, and class ids exported as DLPack tensors.
+    #[pyo3(name = "to_dlpack")]
+    fn py_to_dlpack(&self) -> (DLPackTensor, DLPackTensor, DLPackTensor) {
+        (
+            DLPackTensor::from(self.points.clone()),
+            DLPackTensor::from(self.cuboids.clone()),
+            DLPackTensor::from(self.class_ids.clone()),
+        )
+    }
+}
+
+impl PackedSample {
+    /// Pack the lidar and cuboids of `sweep` into `layout`. The leading rows are kept.
+    pub fn from_sweep(sweep: &Sweep, layout: &PackedLayout) -> Result<Self> {
+        let lidar = &sweep.lidar.0;
+        let points = ndarray_from_frame(lidar, cols(PACKED_POINT_COLUMNS))?;
+        let (cuboids, class_ids) = match &sweep.cuboids {
+            Some(cuboids) => {
+                let params = CuboidList::from_data_frame(&cuboids.0)?.params().to_owned();
+                let class_ids = match cuboids.0.column(CLASS_ID_COLUMN) {
+                    Ok(class_ids) => class_ids
+                        .i64()?
+                        .into_iter()
+                        .map(|x| x.unwrap_or(-1))
+                        .collect(),
+                    Err(_) => Array::from_elem(params.nrows(), -1),
+                };
+                (params, class_ids)
+            }
+            None => (Array::zeros((0, CUBOID_COLUMNS.len())), Array::zeros(0)),
+        };
+
+        let num_points = points.nrows().min(layout.max_points);
+        let num_cuboids = cuboids.nrows().min(layout.max_cuboids);
+        let mut packed_points = Array::from_elem(
+            (layout.max_points, PACKED_POINT_COLUMNS.len()),
+            layout.pad_value,
+        );
+        packed_points
+            .slice_mut(s![..num_points, ..])
+            .assign(&points.slice(s![..num_points, ..]));
+        let mut packed_cuboids =
+            Array::from_elem((layout.max_cuboids, CUBOID_COLUMNS.len()), layout.pad_value);
+        packed_cuboids
+            .slice_mut(s![..num_cuboids, ..])
+            .assign(&cuboids.slice(s![..num_cuboids, ..]));
+        let mut packed_class_ids = Array::from_elem(layout.max_cuboids, -1);
+        packed_class_ids
+            .slice_mut(s![..num_cuboids])
+            .assign(&class_ids.slice(s![..num_cuboids]));
+
+        Ok(Self {
+            sweep_uuid: sweep.sweep_uuid.clone(),
+            points: packed_points,
+            cuboids: packed_cuboids,
+            class_ids: packed_class_ids,
+            descriptor: PackedDescriptor {
+                timestamp_ns: sweep.sweep_uuid.1,
+                version: PACKED_LAYOUT_VERSION,
+                max_points: layout.max_points as u32,
+                point_stride: PACKED_POINT_COLUMNS.len() as u32,
+                max_cuboids: layout.max_cuboids as u32,
+                cuboid_stride: CUBOID_COLUMNS.len() as u32,
+                num_points: num_points as u32,
+                num_cuboids: num_cuboids as u32,
+                num_dropped_points: (points.nrows() - num_points) as u32,
+                num_dropped_cuboids: (cuboids.nrows() - num_cuboids) as u32,
+                pad_value: layout.pad_value,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        data_loader::DataLoader,
+        synthetic::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig},
+    };
+
+    use super::{PackedDescriptor, PackedLayout, PackedSample};
+
+    #[test]
+    fn test_pack_sweep() {
+        let config = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&config);
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let sweep = data_loader.get(0);
+        let num_points = sweep.lidar.0.height();
+        let num_cuboids = sweep.cuboids.as_ref().unwrap().0.height();
+        assert!(num_points > 2 && num_cuboids > 0);
+
+        let layout = PackedLayout {
+            max_points: num_points - 2,
+            max_cuboids: num_cuboids + 3,
+            pad_value: -1.,
+        };
+        let packed = PackedSample::from_sweep(&sweep, &layout).unwrap();
+        assert_eq!(packed.points.shape(), &[num_points - 2, 4]);
+        assert_eq!(packed.cuboids.shape(), &[num_cuboids + 3, 10]);
+        let descriptor = packed.descriptor;
+        assert_eq!(
+            (descriptor.num_points, descriptor.num_dropped_points),
+            (num_points as u32 - 2, 2)
+        );
+        assert_eq!(
+            (descriptor.num_cuboids, descriptor.num_dropped_cuboids),
+            (num_cuboids as u32, 0)
+        );
+        assert!(packed.cuboids.row(num_cuboids).iter().all(|x| *x == -1.));
+        assert_eq!(packed.class_ids[num_cuboids], -1);
+        assert_eq!(
+            packed.points[[0, 0]],
+            sweep
+                .lidar
+                .0
+                .column("x")
+                .unwrap()
+                .f32()
+                .unwrap()
+                .get(0)
+                .unwrap()
+        );
+
+        assert_eq!(
+            PackedDescriptor::from_bytes(&descriptor.to_bytes()).unwrap(),
+            descriptor
+        );
+        assert!(PackedDescriptor::from_bytes(&[0; 4]).is_err());
+    }
+}
@@ -0,0 +1,598 @@
+//! # point_cloud
+//!
+//! Point cloud interchange with standard viewers (e.g., CloudCompare and Open3D).
+//!
+//! Sweeps are written as binary PCD (v0.7) and binary little-endian PLY files with one field per
+//! numeric column. Binary and ASCII files are read back into a dataframe with one column per
+//! field, so external point clouds can be compared against sweeps directly.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, ensure, Context, Result};
+use polars::prelude::{DataFrame, DataType, NamedFrom, Series};
+
+/// Scalar type of a point cloud field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum _ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+}
+
+impl _ScalarType {
+    /// Size in bytes.
+    fn size(self) -> usize {
+        match self {
+            _ScalarType::I8 | _ScalarType::U8 => 1,
+            _ScalarType::I16 | _ScalarType::U16 => 2,
+            _ScalarType::I32 | _ScalarType::U32 | _ScalarType::F32 => 4,
+            _ScalarType::I64 | _ScalarType::U64 | _ScalarType::F64 => 8,
+        }
+    }
+
+    /// Scalar type of a column, if it is numeric.
+    fn from_dtype(dtype: &DataType) -> Option<Self> {
+        match dtype {
+            DataType::UInt8 => Some(_ScalarType::U8),
+            DataType::UInt16 => Some(_ScalarType::U16),
+            DataType::Int32 => Some(_ScalarType::I32),
+            DataType::UInt32 => Some(_ScalarType::U32),
+            DataType::Int64 => Some(_ScalarType::I64),
+            DataType::UInt64 => Some(_ScalarType::U64),
+            DataType::Float32 => Some(_ScalarType::F32),
+            DataType::Float64 => Some(_ScalarType::F64),
+            _ => None,
+        }
+    }
+
+    /// PCD `TYPE` and `SIZE`.
+    fn pcd_type(self) -> (char, usize) {
+        let kind = match self {
+            _ScalarType::I8 | _ScalarType::I16 | _ScalarType::I32 | _ScalarType::I64 => 'I',
+            _ScalarType::U8 | _ScalarType::U16 | _ScalarType::U32 | _ScalarType::U64 => 'U',
+            _ScalarType::F32 | _ScalarType::F64 => 'F',
+        };
+        (kind, self.size())
+    }
+
+    fn from_pcd_type(kind: &str, size: usize) -> Option<Self> {
+        match (kind, size) {
+            ("I", 1) => Some(_ScalarType::I8),
+            ("I", 2) => Some(_ScalarType::I16),
+            ("I", 4) => Some(_ScalarType::I32),
+            ("I", 8) => Some(_ScalarType::I64),
+            ("U", 1) => Some(_ScalarType::U8),
+            ("U", 2) => Some(_ScalarType::U16),
+            ("U", 4) => Some(_ScalarType::U32),
+            ("U", 8) => Some(_ScalarType::U64),
+            ("F", 4) => Some(_ScalarType::F32),
+            ("F", 8) => Some(_ScalarType::F64),
+            _ => None,
+        }
+    }
+
+    /// PLY property type. PLY has no 64-bit integers, so they are written as doubles.
+    fn ply_type(self) -> (&'static str, _ScalarType) {
+        match self {
+            _ScalarType::I8 => ("char", self),
+            _ScalarType::U8 => ("uchar", self),
+            _ScalarType::I16 => ("short", self),
+            _ScalarType::U16 => ("ushort", self),
+            _ScalarType::I32 => ("int", self),
+            _ScalarType::U32 => ("uint", self),
+            _ScalarType::F32 => ("float", self),
+            _ScalarType::I64 | _ScalarType::U64 | _ScalarType::F64 => ("double", _ScalarType::F64),
+        }
+    }
+
+    fn from_ply_type(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(_ScalarType::I8),
+            "uchar" | "uint8" => Some(_ScalarType::U8),
+            "short" | "int16" => Some(_ScalarType::I16),
+            "ushort" | "uint16" => Some(_ScalarType::U16),
+            "int" | "int32" => Some(_ScalarType::I32),
+            "uint" | "uint32" => Some(_ScalarType::U32),
+            "float" | "float32" => Some(_ScalarType::F32),
+            "double" | "float64" => Some(_ScalarType::F64),
+            _ => None,
+        }
+    }
+}
+
+/// Field of a point record.
+#[derive(Clone, Debug)]
+struct _Field {
+    name: String,
+    scalar_type: _ScalarType,
+    /// Padding fields (e.g., PCD `_` fields) are skipped.
+    is_padding: bool,
+}
+
+/// Write the numeric columns of a sweep as a binary PCD file.
+pub fn write_pcd(path: &Path, lidar: &DataFrame) -> Result<()> {
+    let fields = _numeric_fields(lidar, |x| x)?;
+    let num_points = lidar.height();
+    let pcd_types = fields
+        .iter()
+        .map(|x| x.scalar_type.pcd_type())
+        .collect::<Vec<_>>();
+    let join = |values: Vec<String>| values.join(" ");
+    let header = [
+        "# .PCD v0.7 - Point Cloud Data file format".to_string(),
+        "VERSION 0.7".to_string(),
+        format!(
+            "FIELDS {}",
+            join(fields.iter().map(|x| x.name.clone()).collect())
+        ),
+        format!(
+            "SIZE {}",
+            join(pcd_types.iter().map(|x| x.1.to_string()).collect())
+        ),
+        format!(
+            "TYPE {}",
+            join(pcd_types.iter().map(|x| x.0.to_string()).collect())
+        ),
+        format!("COUNT {}", join(vec!["1".to_string(); fields.len()])),
+        format!("WIDTH {num_points}"),
+        "HEIGHT 1".to_string(),
+        "VIEWPOINT 0 0 0 1 0 0 0".to_string(),
+        format!("POINTS {num_points}"),
+        "DATA binary\n".to_string(),
+    ]
+    .join("\n");
+    _write_point_cloud(path, header, lidar, &fields)
+}
+
+/// Write the numeric columns of a sweep as a binary little-endian PLY file.
+/// 64-bit integer columns are written as doubles.
+pub fn write_ply(path: &Path, lidar: &DataFrame) -> Result<()> {
+    let fields = _numeric_fields(lidar, |x| x.ply_type().1)?;
+    let mut header = vec![
+        "ply".to_string(),
+        "format binary_little_endian 1.0".to_string(),
+        format!("element vertex {}", lidar.height()),
+    ];
+    for field in &fields {
+        header.push(format!(
+            "property {} {}",
+            field.scalar_type.ply_type().0,
+            field.name
+        ));
+    }
+    header.push("end_header\n".to_string());
+    _write_point_cloud(path, header.join("\n"), lidar, &fields)
+}
+
+/// Read a binary or ASCII PCD file into a dataframe with one column per field.
+/// Fields with a `COUNT` greater than one are split into `<name>_<i>` columns and `_` padding
+/// fields are dropped. Signed 8-bit and 16-bit fields are widened to 32 bits.
+pub fn read_pcd(path: &Path) -> Result<DataFrame> {
+    let bytes = fs::read(path).with_context(|| format!("{} not found.", path.display()))?;
+    let (mut names, mut sizes, mut kinds, mut counts) = (vec![], vec![], vec![], vec![]);
+    let (mut num_points, mut data_format) = (None, None);
+    let mut offset = 0;
+    for line in bytes.split(|x| *x == b'\n') {
+        offset += line.len() + 1;
+        let line = String::from_utf8_lossy(line);
+        let mut tokens = line.split_whitespace();
+        let (Some(key), values) = (
+            tokens.next(),
+            tokens.map(str::to_string).collect::<Vec<_>>(),
+        ) else {
+            continue;
+        };
+        match key {
+            "FIELDS" => names = values,
+            "SIZE" => sizes = _parse_all::<usize>(&values)?,
+            "TYPE" => kinds = values,
+            "COUNT" => counts = _parse_all::<usize>(&values)?,
+            "POINTS" => num_points = _parse_all::<usize>(&values)?.first().copied(),
+            "DATA" => {
+                data_format = values.first().cloned();
+                break;
+            }
+            _ => {}
+        }
+    }
+    if counts.is_empty() {
+        counts = vec![1; names.len()];
+    }
+    ensure!(
+        names.len() == sizes.len() && names.len() == kinds.len() && names.len() == counts.len(),
+        "Inconsistent PCD header in {}.",
+        path.display()
+    );
+    let num_points = num_points.context("Missing PCD `POINTS`.")?;
+
+    let mut fields = vec![];
+    for (((name, size), kind), count) in names.iter().zip(&sizes).zip(&kinds).zip(&counts) {
+        let scalar_type = _ScalarType::from_pcd_type(kind, *size)
+            .with_context(|| format!("Unsupported PCD type `{kind}{size}`."))?;
+        for i in 0..*count {
+            fields.push(_Field {
+                name: match count {
+                    1 => name.clone(),
+                    _ => format!("{name}_{i}"),
+                },
+                scalar_type,
+                is_padding: name == "_",
+            });
+        }
+    }
+    let data = bytes.get(offset..).unwrap_or_default();
+    match data_format.as_deref() {
+        Some("binary") => _decode_binary(data, &fields, num_points, false),
+        Some("ascii") => _decode_ascii(data, &fields, num_points),
+        Some(format) => bail!("Unsupported PCD data format `{format}`."),
+        None => bail!("Missing PCD `DATA`."),
+    }
+}
+
+/// Read the vertices of a binary or ASCII PLY file into a dataframe with one column per
+/// property. Signed 8-bit and 16-bit properties are widened to 32 bits.
+pub fn read_ply(path: &Path) -> Result<DataFrame> {
+    let bytes = fs::read(path).with_context(|| format!("{} not found.", path.display()))?;
+    ensure!(
+        bytes.starts_with(b"ply"),
+        "{} is not a PLY file.",
+        path.display()
+    );
+    let (mut format, mut num_points, mut fields) = (None, None, vec![]);
+    let mut element = String::new();
+    let mut offset = 0;
+    for line in bytes.split(|x| *x == b'\n') {
+        offset += line.len() + 1;
+        let line = String::from_utf8_lossy(line);
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            ["format", name, ..] => format = Some(name.to_string()),
+            ["element", name, count] => {
+                match *name {
+                    "vertex" => num_points = Some(count.parse::<usize>()?),
+                    _ => ensure!(
+                        num_points.is_some(),
+                        "Only PLY files starting with their vertices are supported."
+                    ),
+                }
+                element = name.to_string();
+            }
+            ["property", "list", ..] if element == "vertex" => {
+                bail!("PLY list properties are not supported.")
+            }
+            ["property", kind, name] if element == "vertex" => fields.push(_Field {
+                name: name.to_string(),
+                scalar_type: _ScalarType::from_ply_type(kind)
+                    .with_context(|| format!("Unsupported PLY type `{kind}`."))?,
+                is_padding: false,
+            }),
+            ["end_header"] => break,
+            _ => {}
+        }
+    }
+    let num_points = num_points.context("Missing PLY vertex element.")?;
+    let data = bytes.get(offset..).unwrap_or_default();
+    match format.as_deref() {
+        Some("binary_little_endian") => _decode_binary(data, &fields, num_points, false),
+        Some("binary_big_endian") => _decode_binary(data, &fields, num_points, true),
+        Some("ascii") => _decode_ascii(data, &fields, num_points),
+        Some(format) => bail!("Unsupported PLY format `{format}`."),
+        None => bail!("Missing PLY format."),
+    }
+}
+
+/// Fields of the numeric columns, with their scalar types mapped by `map_type`.
+fn _numeric_fields(
+    data_frame: &DataFrame,
+    map_type: impl Fn(_ScalarType) -> _ScalarType,
+) -> Result<Vec<_Field>> {
+    data_frame
+        .get_columns()
+        .iter()
+        .map(|series| {
+            let scalar_type = _ScalarType::from_dtype(series.dtype()).with_context(|| {
+                format!(
+                    "Column `{}` of type {} is not supported.",
+                    series.name(),
+                    series.dtype()
+                )
+            })?;
+            Ok(_Field {
+                name: series.name().to_string(),
+                scalar_type: map_type(scalar_type),
+                is_padding: false,
+            })
+        })
+        .collect()
+}
+
+/// Write the header followed by the interleaved little-endian point records.
+fn _write_point_cloud(
+    path: &Path,
+    header: String,
+    data_frame: &DataFrame,
+    fields: &[_Field],
+) -> Result<()> {
+    let columns = data_frame
+        .get_columns()
+        .iter()
+        .zip(fields)
+        .map(|(series, field)| _encode_column(series, field.scalar_type))
+        .collect::<Result<Vec<_>>>()?;
+    let record_size = fields.iter().map(|x| x.scalar_type.size()).sum::<usize>();
+    let mut bytes = header.into_bytes();
+    bytes.reserve(record_size * data_frame.height());
+    for i in 0..data_frame.height() {
+        for (column, field) in columns.iter().zip(fields) {
+            let size = field.scalar_type.size();
+            bytes.extend_from_slice(&column[i * size..(i + 1) * size]);
+        }
+    }
+    fs::write(path, bytes).with_context(|| format!("Failed to write {}.", path.display()))
+}
+
+/// Decode interleaved binary point records.
+fn _decode_binary(
+    data: &[u8],
+    fields: &[_Field],
+    num_points: usize,
+    is_big_endian: bool,
+) -> Result<DataFrame> {
+    let record_size = fields.iter().map(|x| x.scalar_type.size()).sum::<usize>();
+    ensure!(
+        data.len() >= record_size * num_points,
+        "Truncated point cloud: expected {} bytes of points, found {}.",
+        record_size * num_points,
+        data.len()
+    );
+    let mut columns = fields
+        .iter()
+        .map(|x| Vec::with_capacity(x.scalar_type.size() * num_points))
+        .collect::<Vec<_>>();
+    for record in data.chunks_exact(record_size.max(1)).take(num_points) {
+        let mut offset = 0;
+        for (column, field) in columns.iter_mut().zip(fields) {
+            let size = field.scalar_type.size();
+            let value = &record[offset..offset + size];
+            match is_big_endian {
+                true => column.extend(value.iter().rev()),
+                false => column.extend_from_slice(value),
+            }
+            offset += size;
+        }
+    }
+    _build_data_frame(fields, columns)
+}
+
+/// Decode whitespace-separated ASCII point records, one per line.
+fn _decode_ascii(data: &[u8], fields: &[_Field], num_points: usize) -> Result<DataFrame> {
+    let text = String::from_utf8_lossy(data);
+    let mut columns = vec![vec![]; fields.len()];
+    let lines = text.lines().filter(|x| !x.trim().is_empty());
+    let mut num_decoded = 0;
+    for line in lines.take(num_points) {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        ensure!(
+            tokens.len() >= fields.len(),
+            "Expected {} values per point, found {}.",
+            fields.len(),
+            tokens.len()
+        );
+        for ((column, field), token) in columns.iter_mut().zip(fields).zip(tokens) {
+            _parse_ascii(token, field.scalar_type, column)?;
+        }
+        num_decoded += 1;
+    }
+    ensure!(
+        num_decoded == num_points,
+        "Truncated point cloud: expected {num_points} points, found {num_decoded}."
+    );
+    _build_data_frame(fields, columns)
+}
+
+/// Build a dataframe from the little-endian bytes of each non-padding field.
+fn _build_data_frame(fields: &[_Field], columns: Vec<Vec<u8>>) -> Result<DataFrame> {
+    let series = fields
+        .iter()
+        .zip(columns)
+        .filter(|(field, _)| !field.is_padding)
+        .map(|(field, bytes)| _decode_column(&field.name, field.scalar_type, &bytes))
+        .collect::<Vec<_>>();
+    Ok(DataFrame::new(series)?)
+}
+
+/// Decode a column of little-endian values.
+fn _decode_column(name: &str, scalar_type: _ScalarType, bytes: &[u8]) -> Series {
+    let chunks = |size: usize| bytes.chunks_exact(size);
+    match scalar_type {
+        _ScalarType::I8 => Series::new(
+            name,
+            bytes.iter().map(|x| *x as i8 as i32).collect::<Vec<_>>(),
+        ),
+        _ScalarType::U8 => Series::new(name, bytes.to_vec()),
+        _ScalarType::I16 => Series::new(
+            name,
+            chunks(2)
+                .map(|x| i16::from_le_bytes([x[0], x[1]]) as i32)
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::U16 => Series::new(
+            name,
+            chunks(2)
+                .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::I32 => Series::new(
+            name,
+            chunks(4)
+                .map(|x| i32::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::U32 => Series::new(
+            name,
+            chunks(4)
+                .map(|x| u32::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::I64 => Series::new(
+            name,
+            chunks(8)
+                .map(|x| i64::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::U64 => Series::new(
+            name,
+            chunks(8)
+                .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::F32 => Series::new(
+            name,
+            chunks(4)
+                .map(|x| f32::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+        _ScalarType::F64 => Series::new(
+            name,
+            chunks(8)
+                .map(|x| f64::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<_>>(),
+        ),
+    }
+}
+
+/// Encode a column as little-endian values. Nulls are written as zeros.
+fn _encode_column(series: &Series, scalar_type: _ScalarType) -> Result<Vec<u8>> {
+    let bytes = match scalar_type {
+        _ScalarType::U8 => series
+            .cast(&DataType::UInt8)?
+            .u8()?
+            .into_iter()
+            .map(|x| x.unwrap_or_default())
+            .collect(),
+        _ScalarType::U16 => _to_le_bytes(series.cast(&DataType::UInt16)?.u16()?, u16::to_le_bytes),
+        _ScalarType::I32 => _to_le_bytes(series.cast(&DataType::Int32)?.i32()?, i32::to_le_bytes),
+        _ScalarType::U32 => _to_le_bytes(series.cast(&DataType::UInt32)?.u32()?, u32::to_le_bytes),
+        _ScalarType::I64 => _to_le_bytes(series.cast(&DataType::Int64)?.i64()?, i64::to_le_bytes),
+        _ScalarType::U64 => _to_le_bytes(series.cast(&DataType::UInt64)?.u64()?, u64::to_le_bytes),
+        _ScalarType::F32 => _to_le_bytes(series.cast(&DataType::Float32)?.f32()?, f32::to_le_bytes),
+        _ScalarType::F64 => _to_le_bytes(series.cast(&DataType::Float64)?.f64()?, f64::to_le_bytes),
+        _ScalarType::I8 | _ScalarType::I16 => bail!("Cannot write {scalar_type:?} columns."),
+    };
+    Ok(bytes)
+}
+
+fn _to_le_bytes<T: Default, const N: usize>(
+    values: impl IntoIterator<Item = Option<T>>,
+    to_bytes: fn(T) -> [u8; N],
+) -> Vec<u8> {
+    values
+        .into_iter()
+        .flat_map(|x| to_bytes(x.unwrap_or_default()))
+        .collect()
+}
+
+/// Parse an ASCII value and append its little-endian bytes.
+fn _parse_ascii(token: &str, scalar_type: _ScalarType, bytes: &mut Vec<u8>) -> Result<()> {
+    let error = || format!("Invalid {scalar_type:?} value `{token}`.");
+    match scalar_type {
+        _ScalarType::I8 => bytes.push(token.parse::<i8>().with_context(error)? as u8),
+        _ScalarType::U8 => bytes.push(token.parse::<u8>().with_context(error)?),
+        _ScalarType::I16 => bytes.extend(token.parse::<i16>().with_context(error)?.to_le_bytes()),
+        _ScalarType::U16 => bytes.extend(token.parse::<u16>().with_context(error)?.to_le_bytes()),
+        _ScalarType::I32 => bytes.extend(token.parse::<i32>().with_context(error)?.to_le_bytes()),
+        _ScalarType::U32 => bytes.extend(token.parse::<u32>().with_context(error)?.to_le_bytes()),
+        _ScalarType::I64 => bytes.extend(token.parse::<i64>().with_context(error)?.to_le_bytes()),
+        _ScalarType::U64 => bytes.extend(token.parse::<u64>().with_context(error)?.to_le_bytes()),
+        _ScalarType::F32 => bytes.extend(token.parse::<f32>().with_context(error)?.to_le_bytes()),
+        _ScalarType::F64 => bytes.extend(token.parse::<f64>().with_context(error)?.to_le_bytes()),
+    }
+    Ok(())
+}
+
+fn _parse_all<T: std::str::FromStr>(values: &[String]) -> Result<Vec<T>> {
+    values
+        .iter()
+        .map(|x| {
+            x.parse::<T>()
+                .ok()
+                .with_context(|| format!("Invalid header value `{x}`."))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use polars::prelude::*;
+
+    use super::{read_pcd, read_ply, write_pcd, write_ply};
+    use crate::io::read_feather_eager;
+    use crate::synthetic::test_dir;
+
+    #[test]
+    fn test_point_cloud_round_trip() {
+        let lidar_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(
+            "../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede/sensors/lidar/315966265259836000.feather",
+        );
+        let mut lidar = read_feather_eager(&lidar_path, false);
+        lidar
+            .with_column(Series::new("timestamp_ns", vec![7_u64; lidar.height()]))
+            .unwrap();
+
+        let root_dir = test_dir();
+        let pcd_path = root_dir.join("sweep.pcd");
+        write_pcd(&pcd_path, &lidar).unwrap();
+        assert!(read_pcd(&pcd_path).unwrap().equals(&lidar));
+
+        // PLY has no 64-bit integers.
+        let ply_path = root_dir.join("sweep.ply");
+        write_ply(&ply_path, &lidar).unwrap();
+        let ply = read_ply(&ply_path).unwrap();
+        assert_eq!(ply["timestamp_ns"].dtype(), &DataType::Float64);
+        assert!(ply
+            .drop("timestamp_ns")
+            .unwrap()
+            .equals(&lidar.drop("timestamp_ns").unwrap()));
+
+        let strings = df!("x" => [1_f32], "name" => ["a"]).unwrap();
+        assert!(write_pcd(&pcd_path, &strings).is_err());
+    }
+
+    #[test]
+    fn test_read_ascii_point_clouds() {
+        let root_dir = test_dir();
+        let pcd_path = root_dir.join("points.pcd");
+        std::fs::write(
+            &pcd_path,
+            "VERSION .7\nFIELDS x y normal _\nSIZE 4 4 4 1\nTYPE F F F I\nCOUNT 1 1 2 1\n\
+             WIDTH 2\nHEIGHT 1\nPOINTS 2\nDATA ascii\n1 2 0 1 -1\n3.5 4 1 0 -1\n",
+        )
+        .unwrap();
+        let points = read_pcd(&pcd_path).unwrap();
+        assert_eq!(
+            points.get_column_names(),
+            vec!["x", "y", "normal_0", "normal_1"]
+        );
+        assert_eq!(points["x"].f32().unwrap().get(1), Some(3.5));
+
+        let ply_path = root_dir.join("points.ply");
+        std::fs::write(
+            &ply_path,
+            "ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty short label\n\
+             element face 0\nproperty list uchar int vertex_indices\nend_header\n1 -2\n3 4\n",
+        )
+        .unwrap();
+        let points = read_ply(&ply_path).unwrap();
+        assert_eq!(points["label"].i32().unwrap().get(0), Some(-2));
+        assert_eq!(points.height(), 2);
+    }
+}
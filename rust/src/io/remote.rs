@@ -0,0 +1,422 @@
+//! # remote
+//!
+//! Reads of the dataset files from object stores.
+//!
+//! The datasets are public objects of the `argoverse` S3 bucket (e.g.,
+//! `s3://argoverse/datasets/av2/sensor/val/<log_id>/annotations.feather`), which are read without
+//! credentials, so small experiments do not require a local copy of a split. Failed requests are
+//! retried with exponential backoff by the store client.
+//!
+//! Whole files are fetched with a single request, while selected reads (see
+//! `RemoteStore::read_feather_selected`) issue range requests for the footer, the record batch
+//! headers, and the buffers of the decoded columns only. Any other `object_store` backend (e.g., a
+//! local directory or an in-memory store) can be read the same way.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
+
+use futures::TryStreamExt;
+use object_store::{
+    aws::AmazonS3Builder, path::Path as ObjectPath, BackoffConfig, ObjectStore, RetryConfig,
+};
+use polars::{io::mmap::MmapBytesReader, prelude::*};
+use tokio::runtime::{Builder, Runtime};
+use tracing::info_span;
+
+use super::ScanSelection;
+use crate::error::{Av2Error, Av2Result};
+
+/// Name of the public bucket of the datasets.
+pub const AV2_BUCKET_NAME: &str = "argoverse";
+
+/// Region of the public bucket of the datasets.
+pub const AV2_BUCKET_REGION: &str = "us-east-1";
+
+/// Configuration of the reads from an object store.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteConfig {
+    /// Number of retries of a failed request.
+    pub max_retries: usize,
+    /// Delay before the first retry, which doubles with each further retry.
+    pub retry_delay: Duration,
+    /// Time after which a failing request is no longer retried.
+    pub retry_timeout: Duration,
+    /// Size of the range requests of selected reads (in bytes). Reads of larger buffers are
+    /// requested as a whole.
+    pub block_size: usize,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            retry_delay: Duration::from_millis(100),
+            retry_timeout: Duration::from_secs(60),
+            block_size: 64 << 10,
+        }
+    }
+}
+
+/// Blocking reads of the files of an object store.
+pub struct RemoteStore {
+    store: Arc<dyn ObjectStore>,
+    runtime: Runtime,
+    block_size: usize,
+}
+
+impl RemoteStore {
+    /// Read the files of `store`.
+    pub fn new(store: Arc<dyn ObjectStore>, cfg: &RemoteConfig) -> Av2Result<Self> {
+        if cfg.block_size == 0 {
+            return Err(Av2Error::invalid_parameter(
+                "block_size",
+                "Expected a positive block size.",
+            ));
+        }
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self {
+            store,
+            runtime,
+            block_size: cfg.block_size,
+        })
+    }
+
+    /// Read the files of an S3 `bucket` anonymously.
+    pub fn s3(bucket: &str, region: &str, cfg: &RemoteConfig) -> Av2Result<Self> {
+        let retry = RetryConfig {
+            backoff: BackoffConfig {
+                init_backoff: cfg.retry_delay,
+                ..Default::default()
+            },
+            max_retries: cfg.max_retries,
+            retry_timeout: cfg.retry_timeout,
+        };
+        let store = AmazonS3Builder::new()
+            .with_bucket_name(bucket)
+            .with_region(region)
+            .with_skip_signature(true)
+            .with_retry(retry)
+            .build()
+            .map_err(|err| Av2Error::invalid_parameter("bucket", err))?;
+        Self::new(Arc::new(store), cfg)
+    }
+
+    /// Read the files of the public bucket of the datasets anonymously.
+    pub fn av2(cfg: &RemoteConfig) -> Av2Result<Self> {
+        Self::s3(AV2_BUCKET_NAME, AV2_BUCKET_REGION, cfg)
+    }
+
+    /// Store of an S3 URL (e.g., `s3://argoverse/datasets/av2`) and the path of the URL within its
+    /// bucket (e.g., `datasets/av2`).
+    pub fn from_url(url: &str, cfg: &RemoteConfig) -> Av2Result<(Self, String)> {
+        let (bucket, path) = parse_s3_url(url)?;
+        Ok((Self::s3(bucket, AV2_BUCKET_REGION, cfg)?, path.to_string()))
+    }
+
+    /// Size of the file at `path` (in bytes).
+    pub fn size(&self, path: &str) -> Av2Result<usize> {
+        let location = ObjectPath::from(path);
+        let meta = self
+            .runtime
+            .block_on(self.store.head(&location))
+            .map_err(|err| _store_error(path, err))?;
+        Ok(meta.size)
+    }
+
+    /// Read the file at `path`.
+    pub fn read_bytes(&self, path: &str) -> Av2Result<Vec<u8>> {
+        let location = ObjectPath::from(path);
+        let _span = info_span!("read_remote_file", path).entered();
+        let bytes = self
+            .runtime
+            .block_on(async { self.store.get(&location).await?.bytes().await });
+        Ok(bytes.map_err(|err| _store_error(path, err))?.to_vec())
+    }
+
+    /// Read the `range` of bytes of the file at `path`.
+    pub fn read_range(&self, path: &str, range: Range<usize>) -> Av2Result<Vec<u8>> {
+        let location = ObjectPath::from(path);
+        let bytes = self
+            .runtime
+            .block_on(self.store.get_range(&location, range))
+            .map_err(|err| _store_error(path, err))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// Paths of the files under `prefix` in lexicographic order.
+    pub fn list(&self, prefix: &str) -> Av2Result<Vec<String>> {
+        let location = ObjectPath::from(prefix);
+        let metas = self
+            .runtime
+            .block_on(self.store.list(Some(&location)).try_collect::<Vec<_>>())
+            .map_err(|err| _store_error(prefix, err))?;
+        let mut paths = metas
+            .into_iter()
+            .map(|x| x.location.to_string())
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Seekable reader of the file at `path`, which fetches the bytes it reads with range
+    /// requests.
+    pub fn reader(&self, path: &str) -> Av2Result<RangeReader<'_>> {
+        Ok(RangeReader {
+            store: self,
+            path: path.to_string(),
+            size: self.size(path)? as u64,
+            position: 0,
+            blocks: HashMap::new(),
+            num_bytes_fetched: 0,
+        })
+    }
+
+    /// Read a feather file and load into a `polars` dataframe.
+    pub fn read_feather(&self, path: &str) -> Av2Result<DataFrame> {
+        let bytes = self.read_bytes(path)?;
+        let _span = info_span!("decode_feather", path).entered();
+        IpcReader::new(io::Cursor::new(bytes))
+            .finish()
+            .map_err(|err| Av2Error::decode(std::path::Path::new(path), err))
+    }
+
+    /// Read the selected rows and columns of a feather file (see `io::read_feather_selected`).
+    ///
+    /// Only the bytes of the projected and predicate columns are fetched.
+    pub fn read_feather_selected(
+        &self,
+        path: &str,
+        selection: &ScanSelection,
+    ) -> Av2Result<DataFrame> {
+        let mut reader = self.reader(path)?;
+        let data_frame = IpcReader::new(&mut reader)
+            .with_columns(selection.required_columns())
+            .finish()
+            .map_err(|err| Av2Error::decode(std::path::Path::new(path), err))?;
+        let _span = info_span!("select_rows", path).entered();
+        selection
+            .apply(data_frame.lazy())
+            .collect()
+            .map_err(|err| Av2Error::schema(path, err))
+    }
+}
+
+/// Seekable reader of a file of a `RemoteStore`.
+///
+/// Small reads (e.g., of the footer and the message headers) are served from fetched blocks of
+/// `block_size` bytes, while reads of at least a block (e.g., of column buffers) are fetched as a
+/// whole.
+pub struct RangeReader<'a> {
+    store: &'a RemoteStore,
+    path: String,
+    size: u64,
+    position: u64,
+    blocks: HashMap<u64, Vec<u8>>,
+    num_bytes_fetched: usize,
+}
+
+impl RangeReader<'_> {
+    /// Size of the file (in bytes).
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Number of bytes fetched so far.
+    pub fn num_bytes_fetched(&self) -> usize {
+        self.num_bytes_fetched
+    }
+
+    fn _fetch(&mut self, range: Range<usize>) -> io::Result<Vec<u8>> {
+        let bytes = self
+            .store
+            .read_range(&self.path, range)
+            .map_err(io::Error::other)?;
+        self.num_bytes_fetched += bytes.len();
+        Ok(bytes)
+    }
+}
+
+impl Read for RangeReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.size || buf.is_empty() {
+            return Ok(0);
+        }
+        let start = self.position as usize;
+        let block_size = self.store.block_size;
+        if buf.len() >= block_size {
+            let end = (start + buf.len()).min(self.size as usize);
+            let bytes = self._fetch(start..end)?;
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            self.position += bytes.len() as u64;
+            return Ok(bytes.len());
+        }
+        let index = (start / block_size) as u64;
+        if !self.blocks.contains_key(&index) {
+            let block_start = index as usize * block_size;
+            let block_end = (block_start + block_size).min(self.size as usize);
+            let block = self._fetch(block_start..block_end)?;
+            self.blocks.insert(index, block);
+        }
+        let block = &self.blocks[&index];
+        let offset = start - index as usize * block_size;
+        let num_bytes = buf.len().min(block.len() - offset);
+        buf[..num_bytes].copy_from_slice(&block[offset..offset + num_bytes]);
+        self.position += num_bytes as u64;
+        Ok(num_bytes)
+    }
+}
+
+impl Seek for RangeReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek before the start of file.",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+impl MmapBytesReader for RangeReader<'_> {}
+
+/// Bucket and path of an S3 URL (e.g., `argoverse` and `datasets/av2` of
+/// `s3://argoverse/datasets/av2`).
+pub fn parse_s3_url(url: &str) -> Av2Result<(&str, &str)> {
+    let Some((bucket, path)) = url
+        .strip_prefix("s3://")
+        .map(|x| x.split_once('/').unwrap_or((x, "")))
+        .filter(|(bucket, _)| !bucket.is_empty())
+    else {
+        return Err(Av2Error::invalid_parameter(
+            "url",
+            format!("Expected an `s3://<bucket>/<path>` URL, got `{url}`."),
+        ));
+    };
+    Ok((bucket, path.trim_end_matches('/')))
+}
+
+/// Error of an object store request, which names the file if it does not exist.
+fn _store_error(path: &str, err: object_store::Error) -> Av2Error {
+    match err {
+        object_store::Error::NotFound { .. } => Av2Error::MissingFile { path: path.into() },
+        _ => Av2Error::Io(io::Error::other(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use object_store::{local::LocalFileSystem, memory::InMemory, path::Path, ObjectStore};
+    use polars::prelude::{IpcReader, SerReader};
+
+    use super::{parse_s3_url, RemoteConfig, RemoteStore};
+    use crate::{
+        error::Av2Error,
+        io::{read_feather_eager, read_feather_selected, ScanSelection},
+    };
+
+    const LOG_DIR: &str = "../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede";
+    const LIDAR_PATH: &str = "sensors/lidar/315966265259836000.feather";
+
+    fn _in_memory_store(cfg: &RemoteConfig) -> RemoteStore {
+        let lidar_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(LOG_DIR)
+            .join(LIDAR_PATH);
+        let store = InMemory::new();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(store.put(
+                &Path::from(format!("val/log/{LIDAR_PATH}")),
+                std::fs::read(lidar_path).unwrap().into(),
+            ))
+            .unwrap();
+        RemoteStore::new(Arc::new(store), cfg).unwrap()
+    }
+
+    #[test]
+    fn test_read_feather() {
+        let store = _in_memory_store(&RemoteConfig::default());
+        let path = format!("val/log/{LIDAR_PATH}");
+        let local_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(LOG_DIR)
+            .join(LIDAR_PATH);
+        let expected = read_feather_eager(&local_path, false);
+        assert!(store.read_feather(&path).unwrap().equals(&expected));
+        assert_eq!(store.list("val").unwrap(), vec![path.clone()]);
+
+        // Range reads.
+        let bytes = std::fs::read(&local_path).unwrap();
+        assert_eq!(store.size(&path).unwrap(), bytes.len());
+        assert_eq!(store.read_range(&path, 10..20).unwrap(), bytes[10..20]);
+
+        assert!(matches!(
+            store.read_feather("val/log/missing.feather"),
+            Err(Av2Error::MissingFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_feather_selected() {
+        let cfg = RemoteConfig {
+            block_size: 1 << 10,
+            ..Default::default()
+        };
+        let store = _in_memory_store(&cfg);
+        let path = format!("val/log/{LIDAR_PATH}");
+        let local_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(LOG_DIR)
+            .join(LIDAR_PATH);
+        let selection =
+            ScanSelection::with_columns(&["x", "intensity"]).filter("z", Some(-1.), Some(1.));
+        let expected = read_feather_selected(&local_path, &selection, None).unwrap();
+        let selected = store.read_feather_selected(&path, &selection).unwrap();
+        assert!(selected.height() > 0);
+        assert!(selected.equals(&expected));
+
+        // Only the buffers of the decoded columns are fetched.
+        let mut reader = store.reader(&path).unwrap();
+        IpcReader::new(&mut reader)
+            .with_columns(Some(vec!["intensity".to_string()]))
+            .finish()
+            .unwrap();
+        assert!(reader.num_bytes_fetched() < reader.size() as usize / 2);
+    }
+
+    #[test]
+    fn test_local_store() {
+        let log_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(LOG_DIR);
+        let store = LocalFileSystem::new_with_prefix(&log_dir).unwrap();
+        let store = RemoteStore::new(Arc::new(store), &RemoteConfig::default()).unwrap();
+        let expected = read_feather_eager(&log_dir.join("annotations.feather"), false);
+        let selection = ScanSelection::default();
+        assert!(store
+            .read_feather_selected("annotations.feather", &selection)
+            .unwrap()
+            .equals(&expected));
+    }
+
+    #[test]
+    fn test_parse_s3_url() {
+        assert_eq!(
+            parse_s3_url("s3://argoverse/datasets/av2/").unwrap(),
+            ("argoverse", "datasets/av2")
+        );
+        assert_eq!(parse_s3_url("s3://argoverse").unwrap(), ("argoverse", ""));
+        assert!(parse_s3_url("https://argoverse/datasets").is_err());
+        assert!(parse_s3_url("s3:///datasets").is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! # stream
+//!
+//! Streaming reads of record batches for out-of-core processing.
+//!
+//! A stream walks a list of feather or parquet files and yields dataframes of at most
+//! `batch_size` rows, each backed by a single record batch. Feather files are decoded one record
+//! batch at a time, while parquet files are decoded a file at a time. Only the current batch (and
+//! the undelivered remainder of its file) is held in memory.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use polars::{
+    export::arrow::io::ipc::read::{read_file_metadata, FileReader},
+    prelude::{DataFrame, IntoLazy, Series},
+};
+use pyo3::{exceptions::PyIOError, prelude::*};
+use pyo3_polars::PyDataFrame;
+
+use super::{read_parquet_selected, ScanSelection};
+use crate::error::Av2Error;
+
+/// Default maximum number of rows per batch.
+pub const DEFAULT_BATCH_SIZE: usize = 65536;
+
+/// Iterator over bounded record batches of a list of feather or parquet files.
+#[pyclass(module = "av2._r")]
+pub struct RecordBatchStream {
+    paths: Vec<PathBuf>,
+    /// Index of the next file to open.
+    next_path: usize,
+    selection: ScanSelection,
+    batch_size: usize,
+    /// Reader of the current feather file and its projected columns.
+    reader: Option<(FileReader<BufReader<File>>, Vec<String>)>,
+    /// Rows of the current record batch which have not been yielded yet.
+    pending: Option<DataFrame>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl RecordBatchStream {
+    /// Stream the `columns` (or all columns) of the files at `paths`.
+    #[new]
+    #[pyo3(signature = (paths, batch_size = DEFAULT_BATCH_SIZE, columns = None))]
+    fn py_new(paths: Vec<String>, batch_size: usize, columns: Option<Vec<String>>) -> Self {
+        let selection = ScanSelection {
+            columns,
+            predicates: vec![],
+        };
+        RecordBatchStream::new(paths.into_iter().map(PathBuf::from).collect(), selection)
+            .with_batch_size(batch_size)
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<PyDataFrame>> {
+        self.next()
+            .transpose()
+            .map(|batch| batch.map(PyDataFrame))
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+}
+
+/// Rust methods.
+impl RecordBatchStream {
+    /// Stream the `selection` of the files at `paths` in batches of `DEFAULT_BATCH_SIZE` rows.
+    pub fn new(paths: Vec<PathBuf>, selection: ScanSelection) -> Self {
+        Self {
+            paths,
+            next_path: 0,
+            selection,
+            batch_size: DEFAULT_BATCH_SIZE,
+            reader: None,
+            pending: None,
+        }
+    }
+
+    /// Set the maximum number of rows per batch.
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be positive.");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Decode the next record batch of the current feather file, or `None` once it is exhausted.
+    fn next_record_batch(&mut self) -> Result<Option<DataFrame>> {
+        let Some((reader, columns)) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        let Some(chunk) = reader.next().transpose()? else {
+            self.reader = None;
+            return Ok(None);
+        };
+        let series = columns
+            .iter()
+            .zip(chunk.into_arrays())
+            .map(|(name, array)| Series::try_from((name.as_str(), array)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let data_frame = DataFrame::new(series)?;
+        Ok(Some(self.selection.apply(data_frame.lazy()).collect()?))
+    }
+
+    /// Open the next file. Parquet files are decoded into `pending` at once.
+    fn open(&mut self, path: &Path) -> Result<()> {
+        if path.extension().and_then(|x| x.to_str()) == Some("parquet") {
+            self.pending = Some(read_parquet_selected(&path.to_path_buf(), &self.selection)?);
+            return Ok(());
+        }
+        let mut file = BufReader::new(File::open(path).map_err(|err| Av2Error::open(path, err))?);
+        let metadata = read_file_metadata(&mut file)?;
+        // The arrow reader requires an increasing projection.
+        let projection = match self.selection.required_columns() {
+            Some(required) => {
+                let mut indices = required
+                    .iter()
+                    .map(|name| {
+                        metadata
+                            .schema
+                            .fields
+                            .iter()
+                            .position(|field| &field.name == name)
+                            .with_context(|| format!("{name} not found in {}.", path.display()))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                indices.sort_unstable();
+                Some(indices)
+            }
+            None => None,
+        };
+        let reader = FileReader::new(file, metadata, projection, None);
+        let columns = reader
+            .schema()
+            .fields
+            .iter()
+            .map(|field| field.name.clone())
+            .collect();
+        self.reader = Some((reader, columns));
+        Ok(())
+    }
+}
+
+impl Iterator for RecordBatchStream {
+    type Item = Result<DataFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pending) = self.pending.take() {
+                if pending.height() > self.batch_size {
+                    self.pending = Some(pending.slice(self.batch_size as i64, usize::MAX));
+                    return Some(Ok(pending.slice(0, self.batch_size)));
+                }
+                if pending.height() > 0 {
+                    return Some(Ok(pending));
+                }
+            }
+            match self.next_record_batch() {
+                Ok(Some(batch)) => {
+                    self.pending = Some(batch);
+                    continue;
+                }
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+            let path = self.paths.get(self.next_path)?.clone();
+            self.next_path += 1;
+            if let Err(err) = self.open(&path) {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use polars::prelude::*;
+
+    use super::RecordBatchStream;
+    use crate::io::{read_feather_eager, write_feather_eager, ScanSelection};
+    use crate::synthetic::test_dir;
+
+    #[test]
+    fn test_record_batch_stream() {
+        let log_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede");
+        let lidar_path = log_dir.join("sensors/lidar/315966265259836000.feather");
+        let lidar = read_feather_eager(&lidar_path, false);
+
+        let dir = test_dir();
+        let parquet_path = dir.join("lidar.parquet");
+        ParquetWriter::new(std::fs::File::create(&parquet_path).unwrap())
+            .finish(&mut lidar.clone())
+            .unwrap();
+        let feather_path = dir.join("lidar.feather");
+        write_feather_eager(&feather_path, lidar.clone()).unwrap();
+
+        let selection =
+            ScanSelection::with_columns(&["z", "intensity"]).filter("intensity", Some(10.), None);
+        let batches = RecordBatchStream::new(vec![feather_path, parquet_path], selection)
+            .with_batch_size(1000)
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        assert!(batches.iter().all(|x| x.height() <= 1000));
+        assert!(batches
+            .iter()
+            .all(|x| x.get_column_names() == ["z", "intensity"]));
+
+        let num_expected = lidar
+            .column("intensity")
+            .unwrap()
+            .u8()
+            .unwrap()
+            .into_iter()
+            .filter(|x| x.is_some_and(|x| x >= 10))
+            .count();
+        let num_rows = batches.iter().map(|x| x.height()).sum::<usize>();
+        assert_eq!(num_rows, 2 * num_expected);
+    }
+}
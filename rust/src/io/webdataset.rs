@@ -0,0 +1,564 @@
+//! # webdataset
+//!
+//! Size-bounded tar shards of preprocessed samples (WebDataset convention).
+//!
+//! A sample is a key and a set of members, each stored as `<key>.<extension>` (e.g.,
+//! `315969904359876000.lidar.feather` or `315969904359876000.voxels.npy`), with the members of a
+//! sample stored consecutively. Shards are named `<prefix>-<index>.tar` and are closed before they
+//! exceed the configured size, so training jobs read them sequentially instead of opening many
+//! small files. `index.json` lists the shards and the byte offset of every sample within its
+//! shard for random access.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context, Result};
+use ndarray::{ArrayView, Dimension};
+use polars::prelude::{DataFrame, IpcReader, IpcWriter, SerReader, SerWriter};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use tar::{Archive, Builder, Header};
+
+use crate::{
+    constants::LIDAR_COLUMNS,
+    data_loader::{DataLoader, Sweep},
+    geometry::voxelize::{voxelize_lidar, VoxelizationConfig},
+};
+
+/// File name of the shard index.
+pub const SHARD_INDEX_FILE_NAME: &str = "index.json";
+
+/// Size of a tar block (in bytes).
+const TAR_BLOCK_SIZE: u64 = 512;
+
+/// Element types with a NumPy `.npy` type description.
+pub trait NpyElement: Copy {
+    /// Type description (e.g., `<f4`).
+    const DESCR: &'static str;
+
+    /// Append the little-endian bytes of the element.
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>);
+}
+
+macro_rules! impl_npy_element {
+    ($($t:ty => $descr:expr),* $(,)?) => {
+        $(
+            impl NpyElement for $t {
+                const DESCR: &'static str = $descr;
+
+                fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_npy_element!(
+    u8 => "|u1",
+    u32 => "<u4",
+    u64 => "<u8",
+    i32 => "<i4",
+    i64 => "<i8",
+    f32 => "<f4",
+    f64 => "<f8",
+);
+
+impl NpyElement for usize {
+    const DESCR: &'static str = match usize::BITS {
+        64 => "<u8",
+        _ => "<u4",
+    };
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl NpyElement for bool {
+    const DESCR: &'static str = "|b1";
+
+    fn extend_le_bytes(self, bytes: &mut Vec<u8>) {
+        bytes.push(self as u8);
+    }
+}
+
+/// Encode an array in the NumPy `.npy` format (version 1.0, C order).
+pub fn npy_bytes<T: NpyElement, D: Dimension>(array: &ArrayView<T, D>) -> Vec<u8> {
+    let shape = match array.shape() {
+        [n] => format!("({n},)"),
+        shape => format!(
+            "({})",
+            shape
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {shape}, }}",
+        T::DESCR
+    );
+    // The magic string, version, and header length take 10 bytes. The header is padded with
+    // spaces and terminated by a newline so the data is 64-byte aligned.
+    let padding = (64 - (11 + header.len()) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for value in array.iter() {
+        value.extend_le_bytes(&mut bytes);
+    }
+    bytes
+}
+
+/// Preprocessed sample: a key and its members by extension.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WebDatasetSample {
+    /// Key shared by the members (e.g., `<log_id>_<timestamp_ns>`). Keys may not contain dots.
+    pub key: String,
+    /// Encoded members by extension (e.g., `voxels.npy`).
+    pub members: BTreeMap<String, Vec<u8>>,
+}
+
+impl WebDatasetSample {
+    /// Construct a sample without members.
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            members: BTreeMap::new(),
+        }
+    }
+
+    /// Add encoded bytes as the `extension` member.
+    pub fn insert(&mut self, extension: &str, bytes: Vec<u8>) {
+        self.members.insert(extension.to_string(), bytes);
+    }
+
+    /// Add an array (e.g., a voxel grid) as the `<name>.npy` member.
+    pub fn insert_npy<T: NpyElement, D: Dimension>(&mut self, name: &str, array: &ArrayView<T, D>) {
+        self.insert(&format!("{name}.npy"), npy_bytes(array));
+    }
+
+    /// Add a dataframe (e.g., an aggregated sweep or its targets) as the uncompressed
+    /// `<name>.feather` member.
+    pub fn insert_feather(&mut self, name: &str, data_frame: &DataFrame) -> Result<()> {
+        let mut bytes = vec![];
+        IpcWriter::new(&mut bytes)
+            .with_compression(None)
+            .finish(&mut data_frame.clone())?;
+        self.insert(&format!("{name}.feather"), bytes);
+        Ok(())
+    }
+
+    /// Decode the `<name>.feather` member.
+    pub fn read_feather(&self, name: &str) -> Result<DataFrame> {
+        let bytes = self
+            .members
+            .get(&format!("{name}.feather"))
+            .with_context(|| format!("Sample `{}` has no `{name}.feather` member.", self.key))?;
+        Ok(IpcReader::new(std::io::Cursor::new(bytes)).finish()?)
+    }
+
+    /// Size (in bytes) of the sample's tar entries.
+    pub fn size_bytes(&self) -> u64 {
+        self.members
+            .values()
+            .map(|x| TAR_BLOCK_SIZE + (x.len() as u64).next_multiple_of(TAR_BLOCK_SIZE))
+            .sum()
+    }
+}
+
+/// Shard writer configuration.
+#[derive(Clone, Debug)]
+pub struct ShardWriterConfig {
+    /// Directory of the shards and their index.
+    pub output_dir: PathBuf,
+    /// Prefix of the shard file names.
+    pub prefix: String,
+    /// Size (in bytes) above which a shard is closed. A sample larger than this is written to a
+    /// shard of its own.
+    pub max_shard_size_bytes: u64,
+    /// Maximum number of samples per shard.
+    pub max_samples_per_shard: usize,
+}
+
+impl Default for ShardWriterConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("shards"),
+            prefix: "shard".to_string(),
+            max_shard_size_bytes: 1 << 30,
+            max_samples_per_shard: usize::MAX,
+        }
+    }
+}
+
+/// Shard of an index.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ShardInfo {
+    /// File name of the shard (relative to the index).
+    pub file_name: String,
+    /// Number of samples.
+    pub num_samples: usize,
+    /// Size of the shard (in bytes).
+    pub size_bytes: u64,
+}
+
+/// Location of a sample in an index.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SampleLocation {
+    /// Key of the sample.
+    pub key: String,
+    /// Position of the shard in the index.
+    pub shard: usize,
+    /// Offset (in bytes) of the sample's first tar header within the shard.
+    pub offset_bytes: u64,
+}
+
+/// Index of the shards written by a `ShardWriter`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ShardIndex {
+    /// Shards in write order.
+    pub shards: Vec<ShardInfo>,
+    /// Samples in write order.
+    pub samples: Vec<SampleLocation>,
+}
+
+impl ShardIndex {
+    /// Read the index of a shard directory.
+    pub fn read(output_dir: &Path) -> Result<Self> {
+        let path = output_dir.join(SHARD_INDEX_FILE_NAME);
+        let file =
+            File::open(&path).with_context(|| format!("Failed to open {}.", path.display()))?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+}
+
+/// Writer counting the bytes written through it.
+struct _CountingWriter<W: Write> {
+    inner: W,
+    num_bytes: u64,
+}
+
+impl<W: Write> Write for _CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let num_bytes = self.inner.write(buf)?;
+        self.num_bytes += num_bytes as u64;
+        Ok(num_bytes)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Open shard. It is written to a temporary path and renamed once closed, so readers never see a
+/// partial shard.
+struct _OpenShard {
+    builder: Builder<_CountingWriter<BufWriter<File>>>,
+    path: PathBuf,
+    num_samples: usize,
+}
+
+/// Writer of size-bounded tar shards. Call `finish` to close the last shard and write the index.
+pub struct ShardWriter {
+    /// Writer configuration.
+    pub cfg: ShardWriterConfig,
+    shard: Option<_OpenShard>,
+    index: ShardIndex,
+}
+
+impl ShardWriter {
+    /// Construct a writer, creating the output directory.
+    pub fn new(cfg: ShardWriterConfig) -> Result<Self> {
+        ensure!(
+            cfg.max_samples_per_shard > 0,
+            "Shards must hold at least one sample."
+        );
+        fs::create_dir_all(&cfg.output_dir)?;
+        Ok(Self {
+            cfg,
+            shard: None,
+            index: ShardIndex::default(),
+        })
+    }
+
+    /// Append a sample, first closing the current shard if the sample would overflow it.
+    pub fn write(&mut self, sample: &WebDatasetSample) -> Result<()> {
+        ensure!(
+            !sample.key.is_empty() && !sample.key.contains('.'),
+            "Invalid sample key `{}`: keys must be non-empty and may not contain dots.",
+            sample.key
+        );
+        ensure!(
+            !sample.members.is_empty() && sample.members.keys().all(|x| !x.is_empty()),
+            "Sample `{}` needs at least one member with a non-empty extension.",
+            sample.key
+        );
+        if let Some(shard) = &self.shard {
+            let size_bytes = shard.builder.get_ref().num_bytes + sample.size_bytes();
+            if size_bytes > self.cfg.max_shard_size_bytes
+                || shard.num_samples >= self.cfg.max_samples_per_shard
+            {
+                self.close_shard()?;
+            }
+        }
+        let shard = match &mut self.shard {
+            Some(shard) => shard,
+            None => self.shard.insert(self.open_shard()?),
+        };
+
+        self.index.samples.push(SampleLocation {
+            key: sample.key.clone(),
+            shard: self.index.shards.len(),
+            offset_bytes: shard.builder.get_ref().num_bytes,
+        });
+        for (extension, bytes) in &sample.members {
+            let mut header = Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(0);
+            header.set_cksum();
+            shard.builder.append_data(
+                &mut header,
+                format!("{}.{extension}", sample.key),
+                bytes.as_slice(),
+            )?;
+        }
+        shard.num_samples += 1;
+        Ok(())
+    }
+
+    /// Close the last shard and write the index.
+    pub fn finish(mut self) -> Result<ShardIndex> {
+        self.close_shard()?;
+        let path = self.cfg.output_dir.join(SHARD_INDEX_FILE_NAME);
+        let file = File::create(&path)?;
+        serde_json::to_writer(BufWriter::new(file), &self.index)?;
+        Ok(self.index)
+    }
+
+    /// File name of the next shard.
+    fn shard_file_name(&self) -> String {
+        format!("{}-{:06}.tar", self.cfg.prefix, self.index.shards.len())
+    }
+
+    fn open_shard(&self) -> Result<_OpenShard> {
+        let path = self
+            .cfg
+            .output_dir
+            .join(format!("{}.tmp", self.shard_file_name()));
+        let file =
+            File::create(&path).with_context(|| format!("Failed to create {}.", path.display()))?;
+        Ok(_OpenShard {
+            builder: Builder::new(_CountingWriter {
+                inner: BufWriter::new(file),
+                num_bytes: 0,
+            }),
+            path,
+            num_samples: 0,
+        })
+    }
+
+    fn close_shard(&mut self) -> Result<()> {
+        let Some(shard) = self.shard.take() else {
+            return Ok(());
+        };
+        let file_name = self.shard_file_name();
+        let mut writer = shard.builder.into_inner()?;
+        writer.flush()?;
+        fs::rename(&shard.path, self.cfg.output_dir.join(&file_name))?;
+        self.index.shards.push(ShardInfo {
+            file_name,
+            num_samples: shard.num_samples,
+            size_bytes: writer.num_bytes,
+        });
+        Ok(())
+    }
+}
+
+/// Number of sweeps preprocessed in parallel before being written.
+const SWEEP_CHUNK_SIZE: usize = 64;
+
+/// Preprocess a sweep into a sample keyed by `<log_id>_<timestamp_ns>` with the members
+/// `lidar.feather`, `cuboids.feather` (if annotated), `metadata.json`, and the fixed-grid voxels
+/// `voxels.npy`, `coordinates.npy`, and `num_points.npy`.
+pub fn sweep_to_sample(sweep: &Sweep, cfg: &VoxelizationConfig) -> Result<WebDatasetSample> {
+    let (log_id, timestamp_ns) = &sweep.sweep_uuid;
+    let mut sample = WebDatasetSample::new(&format!("{log_id}_{timestamp_ns}"));
+    let lidar = &sweep.lidar.0;
+    sample.insert_feather("lidar", lidar)?;
+    if let Some(cuboids) = &sweep.cuboids {
+        sample.insert_feather("cuboids", &cuboids.0)?;
+    }
+    sample.insert("metadata.json", serde_json::to_vec(&sweep.metadata())?);
+
+    let voxels = voxelize_lidar(lidar, LIDAR_COLUMNS[..4].to_vec(), cfg)?;
+    sample.insert_npy("voxels", &voxels.features.view());
+    sample.insert_npy("coordinates", &voxels.coordinates.view());
+    sample.insert_npy("num_points", &voxels.num_points.view());
+    Ok(sample)
+}
+
+/// Preprocess every sweep of `data_loader` and write the samples to shards in data-loader order.
+pub fn write_split_shards(
+    data_loader: &DataLoader,
+    cfg: &ShardWriterConfig,
+    voxelization_cfg: &VoxelizationConfig,
+) -> Result<ShardIndex> {
+    let mut writer = ShardWriter::new(cfg.clone())?;
+    for start in (0..data_loader.len()).step_by(SWEEP_CHUNK_SIZE) {
+        let end = (start + SWEEP_CHUNK_SIZE).min(data_loader.len());
+        let samples = (start..end)
+            .into_par_iter()
+            .map(|index| sweep_to_sample(&data_loader.get(index), voxelization_cfg))
+            .collect::<Result<Vec<_>>>()?;
+        for sample in &samples {
+            writer.write(sample)?;
+        }
+    }
+    writer.finish()
+}
+
+/// Read the samples of a shard in order, grouping consecutive members by key.
+pub fn read_shard(path: &Path) -> Result<Vec<WebDatasetSample>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}.", path.display()))?;
+    let mut samples: Vec<WebDatasetSample> = vec![];
+    for entry in Archive::new(std::io::BufReader::new(file)).entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        let (key, extension) = name
+            .split_once('.')
+            .with_context(|| format!("Member `{name}` has no extension."))?;
+        let mut bytes = vec![];
+        entry.read_to_end(&mut bytes)?;
+        match samples.last_mut() {
+            Some(sample) if sample.key == key => sample.insert(extension, bytes),
+            _ => {
+                let mut sample = WebDatasetSample::new(key);
+                sample.insert(extension, bytes);
+                samples.push(sample);
+            }
+        }
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        io::{Read, Seek, SeekFrom},
+    };
+
+    use ndarray::{array, Array};
+    use polars::prelude::{df, NamedFrom};
+
+    use crate::{
+        data_loader::DataLoader,
+        geometry::voxelize::VoxelizationConfig,
+        synthetic::{test_dataset, test_dir, MiniDatasetConfig, SyntheticSceneConfig},
+    };
+
+    use super::{
+        npy_bytes, read_shard, write_split_shards, ShardIndex, ShardWriter, ShardWriterConfig,
+        WebDatasetSample,
+    };
+
+    #[test]
+    fn test_shard_writer() {
+        let output_dir = test_dir();
+        let cfg = ShardWriterConfig {
+            output_dir: output_dir.to_path_buf(),
+            max_shard_size_bytes: 8 << 10,
+            ..Default::default()
+        };
+        let mut writer = ShardWriter::new(cfg).unwrap();
+        let lidar = df!("x" => [1_f32, 2.], "y" => [3_f32, 4.]).unwrap();
+        for i in 0..6 {
+            let mut sample = WebDatasetSample::new(&format!("sample{i}"));
+            sample.insert_feather("lidar", &lidar).unwrap();
+            sample.insert_npy("voxels", &Array::<f32, _>::zeros((8, 32)).view());
+            writer.write(&sample).unwrap();
+        }
+        assert!(writer.write(&WebDatasetSample::new("a.b")).is_err());
+        let index = writer.finish().unwrap();
+        assert!(index.shards.len() > 1);
+        assert!(index.shards.iter().all(|x| x.size_bytes <= 8 << 10));
+        assert_eq!(index.samples.len(), 6);
+        assert_eq!(ShardIndex::read(&output_dir).unwrap(), index);
+
+        let location = &index.samples[5];
+        let shard_path = output_dir.join(&index.shards[location.shard].file_name);
+        let samples = read_shard(&shard_path).unwrap();
+        let sample = samples.iter().find(|x| x.key == "sample5").unwrap();
+        assert_eq!(
+            sample.members.keys().collect::<Vec<_>>(),
+            vec!["lidar.feather", "voxels.npy"]
+        );
+        assert_eq!(sample.read_feather("lidar").unwrap(), lidar);
+
+        // The offset points at the tar header of the sample's first member.
+        let mut file = File::open(&shard_path).unwrap();
+        file.seek(SeekFrom::Start(location.offset_bytes)).unwrap();
+        let mut name = [0_u8; 18];
+        file.read_exact(&mut name).unwrap();
+        assert_eq!(&name, b"sample5.lidar.feat");
+    }
+
+    #[test]
+    fn test_npy_bytes() {
+        let bytes = npy_bytes(&array![1_u8, 2, 3].view());
+        assert!(bytes.starts_with(b"\x93NUMPY\x01\x00"));
+        assert_eq!(bytes.len(), 128 + 3);
+        let header = String::from_utf8_lossy(&bytes[10..128]);
+        assert!(header.contains("'descr': '|u1'") && header.contains("'shape': (3,)"));
+    }
+
+    #[test]
+    fn test_write_split_shards() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root, _) = test_dataset(&cfg);
+        let data_loader = DataLoader::new(root.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        let shard_cfg = ShardWriterConfig {
+            output_dir: root.join("shards"),
+            max_shard_size_bytes: 256 << 10,
+            ..Default::default()
+        };
+        let index =
+            write_split_shards(&data_loader, &shard_cfg, &VoxelizationConfig::default()).unwrap();
+        assert_eq!(index.samples.len(), data_loader.len());
+
+        let sweep = data_loader.get(0);
+        let samples = read_shard(&shard_cfg.output_dir.join(&index.shards[0].file_name)).unwrap();
+        assert_eq!(
+            samples[0].key,
+            format!("{}_{}", sweep.sweep_uuid.0, sweep.sweep_uuid.1)
+        );
+        assert_eq!(samples[0].read_feather("lidar").unwrap(), sweep.lidar.0);
+        for name in [
+            "cuboids.feather",
+            "metadata.json",
+            "voxels.npy",
+            "coordinates.npy",
+        ] {
+            assert!(samples[0].members.contains_key(name));
+        }
+    }
+}
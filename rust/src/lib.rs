@@ -1,100 +1,66 @@
 //! # av2
 //!
 //! Argoverse 2 Rust library.
+//!
+//! The `dataframe` feature (enabled by default) provides the `polars` data-frame layer: I/O,
+//! data-loaders, evaluation, and the Python bindings. Without it, only the `ndarray` geometry core
+//! is compiled (e.g., `geometry::se3`, `geometry::iou`, `geometry::nms`, and
+//! `geometry::polytope`), which suits embedded and inference use.
 
 #![warn(missing_docs)]
 #![warn(missing_doc_code_examples)]
+// `pyo3` 0.20 macros expand to non-local impls which newer toolchains lint against.
+#![allow(non_local_definitions)]
 
 #[cfg(feature = "blas")]
 extern crate blas_src;
 
+#[cfg(feature = "dataframe")]
+pub mod benchmark;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod constants;
+#[cfg(feature = "dataframe")]
 pub mod data_loader;
+#[cfg(feature = "download")]
+pub mod download;
+pub mod error;
+#[cfg(feature = "dataframe")]
+pub mod evaluation;
+#[cfg(feature = "dataframe")]
+pub mod export;
+#[cfg(feature = "dataframe")]
+pub mod frame_diff;
 pub mod geometry;
+#[cfg(feature = "dataframe")]
+pub mod integrity;
+#[cfg(feature = "dataframe")]
 pub mod io;
+#[cfg(feature = "dataframe")]
+pub mod manifest;
+#[cfg(feature = "dataframe")]
+pub mod map;
+#[cfg(feature = "dataframe")]
+pub mod motion_forecasting;
 pub mod ops;
+pub mod parallel;
 pub mod path;
+pub mod prefetch;
+pub mod profiling;
+#[cfg(feature = "dataframe")]
+mod python;
+#[cfg(feature = "dataframe")]
+pub mod resample;
+#[cfg(feature = "ros2")]
+pub mod ros2;
+#[cfg(feature = "dataframe")]
+pub mod schema;
+#[cfg(feature = "dataframe")]
+pub mod shard_cache;
+#[cfg(feature = "dataframe")]
 pub mod share;
+#[cfg(feature = "dataframe")]
 pub mod structures;
-
-use data_loader::{DataLoader, Sweep};
-use ndarray::{Dim, Ix1, Ix2};
-use numpy::PyReadonlyArray;
-use numpy::{IntoPyArray, PyArray};
-use pyo3::prelude::*;
-
-use geometry::so3::{_quat_to_mat3, quat_to_yaw, yaw_to_quat};
-use numpy::PyReadonlyArray2;
-
-use crate::ops::voxelize;
-
-#[pyfunction]
-#[pyo3(name = "voxelize")]
-#[allow(clippy::type_complexity)]
-fn py_voxelize<'py>(
-    py: Python<'py>,
-    indices: PyReadonlyArray2<usize>,
-    features: PyReadonlyArray2<f32>,
-    length: usize,
-    width: usize,
-    height: usize,
-) -> (
-    &'py PyArray<usize, Dim<[usize; 2]>>,
-    &'py PyArray<f32, Dim<[usize; 2]>>,
-    &'py PyArray<f32, Dim<[usize; 2]>>,
-) {
-    let (indices, values, counts) = voxelize(
-        &indices.as_array(),
-        &features.as_array(),
-        length,
-        width,
-        height,
-    );
-    (
-        indices.into_pyarray(py),
-        values.into_pyarray(py),
-        counts.into_pyarray(py),
-    )
-}
-
-#[pyfunction]
-#[pyo3(name = "quat_to_mat3")]
-#[allow(clippy::type_complexity)]
-fn py_quat_to_mat3<'py>(
-    py: Python<'py>,
-    quat_wxyz: PyReadonlyArray<f32, Ix1>,
-) -> &'py PyArray<f32, Ix2> {
-    _quat_to_mat3(&quat_wxyz.as_array().view()).into_pyarray(py)
-}
-
-#[pyfunction]
-#[pyo3(name = "quat_to_yaw")]
-#[allow(clippy::type_complexity)]
-fn py_quat_to_yaw<'py>(
-    py: Python<'py>,
-    quat_wxyz: PyReadonlyArray<f32, Ix2>,
-) -> &'py PyArray<f32, Ix2> {
-    quat_to_yaw(&quat_wxyz.as_array().view()).into_pyarray(py)
-}
-
-#[pyfunction]
-#[pyo3(name = "yaw_to_quat")]
-#[allow(clippy::type_complexity)]
-fn py_yaw_to_quat<'py>(
-    py: Python<'py>,
-    quat_wxyz: PyReadonlyArray<f32, Ix2>,
-) -> &'py PyArray<f32, Ix2> {
-    yaw_to_quat(&quat_wxyz.as_array().view()).into_pyarray(py)
-}
-
-/// A Python module implemented in Rust.
-#[pymodule]
-fn _r(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_class::<DataLoader>()?;
-    m.add_class::<Sweep>()?;
-    m.add_function(wrap_pyfunction!(py_quat_to_mat3, m)?)?;
-    m.add_function(wrap_pyfunction!(py_quat_to_yaw, m)?)?;
-    m.add_function(wrap_pyfunction!(py_voxelize, m)?)?;
-    m.add_function(wrap_pyfunction!(py_yaw_to_quat, m)?)?;
-    Ok(())
-}
+#[cfg(feature = "dataframe")]
+pub mod synthetic;
+pub mod tbv;
@@ -0,0 +1,409 @@
+//! # manifest
+//!
+//! Incrementally refreshable file index of a dataset split.
+//!
+//! Each log is fingerprinted by the number, total size, and latest modification time of its sensor
+//! files. A refresh only re-indexes the logs whose fingerprint changed, so a split can be used
+//! while it is still being downloaded.
+//!
+//! A dataset manifest is a single parquet file with the file index, the relative lidar paths,
+//! and the number of points of every sweep. It is built by scanning a split once, after which
+//! loaders can boot from it without walking the log directories (e.g., on network filesystems).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::{
+    data_loader::build_logs_file_index,
+    error::Av2Result,
+    io::{read_feather_eager, write_feather_eager},
+    path::{extract_file_stem, walk_dir},
+};
+
+/// File name of the persisted file index.
+pub const FILE_INDEX_FILE_NAME: &str = "file_index.feather";
+/// File name of the persisted log fingerprints.
+pub const LOG_FINGERPRINTS_FILE_NAME: &str = "log_fingerprints.feather";
+/// Default file name of a dataset manifest.
+pub const DATASET_MANIFEST_FILE_NAME: &str = "manifest.parquet";
+/// Dataset manifest columns which are not part of the file index.
+pub const DATASET_MANIFEST_COLUMNS: [&str; 2] = ["lidar_path", "num_lidar_points"];
+
+/// Fingerprint of the sensor files of a log.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LogFingerprint {
+    /// Number of sensor files.
+    pub num_files: u64,
+    /// Total size of the sensor files (in bytes).
+    pub total_size_bytes: u64,
+    /// Latest modification time of the sensor files or their directories (in nanoseconds).
+    pub latest_mtime_ns: u64,
+}
+
+/// Logs which changed during a refresh.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ManifestUpdate {
+    /// Logs which were not indexed before.
+    pub added: Vec<String>,
+    /// Logs which no longer exist.
+    pub removed: Vec<String>,
+    /// Logs whose sensor files changed.
+    pub modified: Vec<String>,
+}
+
+impl ManifestUpdate {
+    /// Returns `true` if no log changed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// File index of a split with per-log fingerprints.
+#[derive(Clone, Debug)]
+pub struct Manifest {
+    /// Split directory (e.g., `~/data/datasets/av2/sensor/train`).
+    pub split_dir: PathBuf,
+    /// Fingerprint and file index of each log.
+    logs: BTreeMap<String, (LogFingerprint, DataFrame)>,
+    /// Logs of the split if `split_dir` is shared by several splits (e.g., TbV), or `None` if
+    /// every log in `split_dir` belongs to the split.
+    log_ids: Option<BTreeSet<String>>,
+}
+
+impl Manifest {
+    /// Index every log in `split_dir`.
+    pub fn new(split_dir: &Path) -> Result<Self> {
+        let mut manifest = Manifest {
+            split_dir: split_dir.to_path_buf(),
+            logs: BTreeMap::new(),
+            log_ids: None,
+        };
+        manifest.refresh()?;
+        Ok(manifest)
+    }
+
+    /// Index the logs in `split_dir` which are in `log_ids`. Other logs are never scanned.
+    pub fn with_log_ids(split_dir: &Path, log_ids: &[&str]) -> Result<Self> {
+        let mut manifest = Manifest {
+            split_dir: split_dir.to_path_buf(),
+            logs: BTreeMap::new(),
+            log_ids: None,
+        };
+        manifest.retain_log_ids(log_ids);
+        manifest.refresh()?;
+        Ok(manifest)
+    }
+
+    /// Drop the logs which are not in `log_ids` and ignore them in later refreshes.
+    pub fn retain_log_ids(&mut self, log_ids: &[&str]) {
+        let log_ids = log_ids
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<BTreeSet<_>>();
+        self.logs.retain(|log_id, _| log_ids.contains(log_id));
+        self.log_ids = Some(log_ids);
+    }
+
+    /// Load a manifest saved in `manifest_dir` and refresh it.
+    pub fn load(split_dir: &Path, manifest_dir: &Path) -> Result<Self> {
+        let file_index = read_feather_eager(&manifest_dir.join(FILE_INDEX_FILE_NAME), false);
+        let fingerprints =
+            read_feather_eager(&manifest_dir.join(LOG_FINGERPRINTS_FILE_NAME), false);
+        let log_ids = fingerprints["log_id"].str()?;
+        let [num_files, total_size_bytes, latest_mtime_ns] =
+            ["num_files", "total_size_bytes", "latest_mtime_ns"]
+                .map(|name| fingerprints[name].u64());
+
+        let (num_files, total_size_bytes, latest_mtime_ns) =
+            (num_files?, total_size_bytes?, latest_mtime_ns?);
+        let mut logs = BTreeMap::new();
+        for i in 0..fingerprints.height() {
+            let log_id = log_ids.get(i).context("Missing log id.")?;
+            let fingerprint = LogFingerprint {
+                num_files: num_files.get(i).context("Missing file count.")?,
+                total_size_bytes: total_size_bytes.get(i).context("Missing size.")?,
+                latest_mtime_ns: latest_mtime_ns.get(i).context("Missing mtime.")?,
+            };
+            let log_file_index = file_index
+                .clone()
+                .lazy()
+                .filter(col("log_id").eq(lit(log_id)))
+                .collect()?;
+            logs.insert(log_id.to_string(), (fingerprint, log_file_index));
+        }
+
+        let mut manifest = Manifest {
+            split_dir: split_dir.to_path_buf(),
+            logs,
+            log_ids: None,
+        };
+        manifest.refresh()?;
+        Ok(manifest)
+    }
+
+    /// Build a manifest from the file index of a dataset manifest without scanning `split_dir`.
+    /// The logs have no fingerprints, so the next refresh re-indexes all of them.
+    pub fn from_file_index(split_dir: &Path, file_index: &DataFrame) -> Result<Self> {
+        let file_index = file_index.sort(["log_id", "timestamp_ns"], false, true)?;
+        let log_ids = file_index["log_id"].str()?;
+        let mut logs = BTreeMap::new();
+        let mut start = 0;
+        for i in 1..=file_index.height() {
+            if i < file_index.height() && log_ids.get(i) == log_ids.get(start) {
+                continue;
+            }
+            let log_id = log_ids.get(start).context("Missing log id.")?;
+            logs.insert(
+                log_id.to_string(),
+                (
+                    LogFingerprint::default(),
+                    file_index.slice(start as i64, i - start),
+                ),
+            );
+            start = i;
+        }
+        Ok(Manifest {
+            split_dir: split_dir.to_path_buf(),
+            logs,
+            log_ids: None,
+        })
+    }
+
+    /// Save the manifest to `manifest_dir`.
+    pub fn save(&self, manifest_dir: &Path) -> Result<()> {
+        fs::create_dir_all(manifest_dir)?;
+        let fingerprints = self.logs.values().map(|(x, _)| x).collect::<Vec<_>>();
+        let fingerprints = df!(
+            "log_id" => self.logs.keys().map(|x| x.as_str()).collect::<Vec<_>>(),
+            "num_files" => fingerprints.iter().map(|x| x.num_files).collect::<Vec<_>>(),
+            "total_size_bytes" => fingerprints.iter().map(|x| x.total_size_bytes).collect::<Vec<_>>(),
+            "latest_mtime_ns" => fingerprints.iter().map(|x| x.latest_mtime_ns).collect::<Vec<_>>()
+        )?;
+        write_feather_eager(&manifest_dir.join(FILE_INDEX_FILE_NAME), self.file_index()?)?;
+        write_feather_eager(&manifest_dir.join(LOG_FINGERPRINTS_FILE_NAME), fingerprints)?;
+        Ok(())
+    }
+
+    /// Re-index the logs which were added, removed, or modified since the last refresh.
+    pub fn refresh(&mut self) -> Result<ManifestUpdate> {
+        let log_dirs = match self.split_dir.exists() {
+            true => walk_dir(&self.split_dir)?,
+            false => vec![],
+        };
+        let fingerprints = log_dirs
+            .par_iter()
+            .filter(|log_dir| log_dir.is_dir())
+            .map(|log_dir| extract_file_stem(log_dir).map(|log_id| (log_id, log_dir)))
+            .filter(|log| match (&self.log_ids, log) {
+                (Some(log_ids), Ok((log_id, _))) => log_ids.contains(log_id),
+                _ => true,
+            })
+            .map(|log| {
+                let (log_id, log_dir) = log?;
+                Ok((log_id, fingerprint_log(log_dir)?))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        let mut update = ManifestUpdate {
+            removed: self
+                .logs
+                .keys()
+                .filter(|log_id| !fingerprints.contains_key(*log_id))
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+        for log_id in update.removed.iter() {
+            self.logs.remove(log_id);
+        }
+
+        let changed = fingerprints
+            .into_iter()
+            .filter(|(log_id, fingerprint)| match self.logs.get(log_id) {
+                Some((indexed, _)) => indexed != fingerprint,
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        let indices = changed
+            .par_iter()
+            .map(|(log_id, _)| build_logs_file_index(&self.split_dir, log_id))
+            .collect::<Av2Result<Vec<_>>>()?;
+        for ((log_id, fingerprint), log_file_index) in changed.into_iter().zip(indices) {
+            match self.logs.contains_key(&log_id) {
+                true => update.modified.push(log_id.clone()),
+                false => update.added.push(log_id.clone()),
+            }
+            self.logs.insert(log_id, (fingerprint, log_file_index));
+        }
+        Ok(update)
+    }
+
+    /// Indexed log ids in sorted order.
+    pub fn log_ids(&self) -> Vec<String> {
+        self.logs.keys().cloned().collect()
+    }
+
+    /// File index of the split, sorted by `log_id` and `timestamp_ns`.
+    pub fn file_index(&self) -> Result<DataFrame> {
+        let frames = self
+            .logs
+            .values()
+            .map(|(_, x)| x.clone().lazy())
+            .collect::<Vec<_>>();
+        match frames.is_empty() {
+            // The split directory itself has no sensors, which gives an empty index with the schema.
+            true => Ok(build_logs_file_index(&self.split_dir, "")?),
+            false => Ok(concat(frames, UnionArgs::default())?.collect()?),
+        }
+    }
+}
+
+/// Scan `split_dir` once and build its dataset manifest: the file index with the `lidar_path`
+/// (relative to `split_dir`) and `num_lidar_points` of every sweep.
+pub fn build_dataset_manifest(split_dir: &Path) -> Result<DataFrame> {
+    let mut file_index = Manifest::new(split_dir)?.file_index()?;
+    let log_ids = file_index["log_id"].str()?;
+    let timestamps = file_index["timestamp_ns"].u64()?;
+    let lidar_paths = (0..file_index.height())
+        .map(|i| {
+            let log_id = log_ids.get(i).context("Missing log id.")?;
+            let timestamp_ns = timestamps.get(i).context("Missing timestamp.")?;
+            Ok(format!("{log_id}/sensors/lidar/{timestamp_ns}.feather"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let num_lidar_points = lidar_paths
+        .par_iter()
+        .map(|path| _count_rows(&split_dir.join(path)))
+        .collect::<Result<Vec<_>>>()?;
+    file_index.with_column(Series::new(DATASET_MANIFEST_COLUMNS[0], lidar_paths))?;
+    file_index.with_column(Series::new(DATASET_MANIFEST_COLUMNS[1], num_lidar_points))?;
+    Ok(file_index)
+}
+
+/// Build the dataset manifest of `split_dir` and write it to a parquet file.
+pub fn write_dataset_manifest(split_dir: &Path, path: &Path) -> Result<DataFrame> {
+    let mut manifest = build_dataset_manifest(split_dir)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file =
+        fs::File::create(path).with_context(|| format!("Failed to create {}.", path.display()))?;
+    ParquetWriter::new(file).finish(&mut manifest)?;
+    Ok(manifest)
+}
+
+/// Read a dataset manifest written by `write_dataset_manifest`.
+pub fn read_dataset_manifest(path: &Path) -> Result<DataFrame> {
+    let file = fs::File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+    Ok(ParquetReader::new(file).finish()?)
+}
+
+/// Number of rows of a feather file. Only the first column is decoded.
+fn _count_rows(path: &Path) -> Result<u64> {
+    let file = fs::File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+    let data_frame = IpcReader::new(file)
+        .with_projection(Some(vec![0]))
+        .finish()?;
+    Ok(data_frame.height() as u64)
+}
+
+/// Fingerprint the lidar and camera files of a log without reading them.
+pub fn fingerprint_log(log_dir: &Path) -> Result<LogFingerprint> {
+    let sensors_dir = log_dir.join("sensors");
+    let mut sensor_dirs = vec![sensors_dir.join("lidar")];
+    if let Ok(camera_dirs) = walk_dir(&sensors_dir.join("cameras")) {
+        sensor_dirs.extend(camera_dirs);
+    }
+
+    let mut fingerprint = LogFingerprint::default();
+    for sensor_dir in sensor_dirs.iter().filter(|x| x.is_dir()) {
+        fingerprint.latest_mtime_ns = fingerprint.latest_mtime_ns.max(_mtime_ns(sensor_dir)?);
+        for path in walk_dir(sensor_dir)? {
+            let metadata = fs::metadata(&path)?;
+            fingerprint.num_files += 1;
+            fingerprint.total_size_bytes += metadata.len();
+            fingerprint.latest_mtime_ns = fingerprint.latest_mtime_ns.max(_mtime_ns(&path)?);
+        }
+    }
+    Ok(fingerprint)
+}
+
+fn _mtime_ns(path: &Path) -> Result<u64> {
+    let mtime = fs::metadata(path)?.modified()?;
+    Ok(mtime.duration_since(UNIX_EPOCH)?.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{read_dataset_manifest, write_dataset_manifest, Manifest, ManifestUpdate};
+    use crate::{
+        path::walk_dir,
+        synthetic::{test_dataset, MiniDatasetConfig},
+    };
+
+    #[test]
+    fn test_manifest_refresh() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 3,
+            ..Default::default()
+        };
+        let (root_dir, log_dirs) = test_dataset(&cfg);
+        let split_dir = log_dirs[0].parent().unwrap();
+        let mut manifest = Manifest::new(split_dir).unwrap();
+        let file_index = manifest.file_index().unwrap();
+        let mut log_ids = manifest.log_ids();
+        assert_eq!(log_ids.len(), 3);
+
+        // A saved manifest loads to the same file index and is up to date.
+        let manifest_dir = root_dir.join("manifest");
+        manifest.save(&manifest_dir).unwrap();
+        let mut loaded = Manifest::load(split_dir, &manifest_dir).unwrap();
+        assert!(loaded.file_index().unwrap().equals(&file_index));
+        assert!(loaded.refresh().unwrap().is_empty());
+
+        let path = root_dir.join("dataset/manifest.parquet");
+        let dataset_manifest = write_dataset_manifest(split_dir, &path).unwrap();
+        assert!(read_dataset_manifest(&path)
+            .unwrap()
+            .equals(&dataset_manifest));
+        assert_eq!(dataset_manifest.height(), file_index.height());
+        let num_points = dataset_manifest["num_lidar_points"].u64().unwrap();
+        assert!(num_points.into_no_null_iter().all(|x| x > 0));
+
+        // Renaming a log removes it and adds another, and deleting a sweep modifies a log.
+        let renamed_log_id = "00000000-0000-0000-0000-000000000000".to_string();
+        fs::rename(&log_dirs[0], split_dir.join(&renamed_log_id)).unwrap();
+        let modified_log_id = log_dirs[1].file_name().unwrap().to_str().unwrap();
+        let lidar_path = walk_dir(&log_dirs[1].join("sensors/lidar")).unwrap()[0].clone();
+        fs::remove_file(lidar_path).unwrap();
+        let update = manifest.refresh().unwrap();
+        let removed_log_id = log_dirs[0].file_name().unwrap().to_str().unwrap();
+        assert_eq!(
+            update,
+            ManifestUpdate {
+                added: vec![renamed_log_id.clone()],
+                removed: vec![removed_log_id.to_string()],
+                modified: vec![modified_log_id.to_string()],
+            }
+        );
+        log_ids.retain(|log_id| log_id != removed_log_id);
+        log_ids.insert(0, renamed_log_id);
+        assert_eq!(manifest.log_ids(), log_ids);
+        assert_eq!(
+            manifest.file_index().unwrap().height(),
+            file_index.height() - 1
+        );
+        assert!(manifest.refresh().unwrap().is_empty());
+    }
+}
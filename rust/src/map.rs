@@ -0,0 +1,688 @@
+//! # map
+//!
+//! Vector map preprocessing and spatial queries.
+//!
+//! Each log's JSON vector map is converted once into feather tables (one per element type) and a
+//! uniform grid spatial index, which are cached on disk. Loading a cached map only reads the
+//! tables, so per-frame map queries never parse JSON.
+
+/// Typed port of `ArgoverseStaticMap`.
+pub mod static_map;
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use glob::glob;
+use ndarray::{concatenate, s, Array, ArrayView, Axis, Ix1, Ix2, Ix3};
+use polars::prelude::*;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{
+    geometry::{polyline::compute_centerline, polyline_index::PolylineIndex, polytope::Polygon},
+    io::{read_feather, write_feather_eager},
+    path::{extract_file_stem, walk_dir},
+};
+
+/// Default edge length of the spatial index cells (in meters).
+pub const DEFAULT_CELL_SIZE_M: f32 = 20.;
+
+/// Number of vertices of the lane centerlines used for nearest-lane queries.
+pub const NUM_CENTERLINE_POINTS: usize = 10;
+
+/// File name of the cached spatial index.
+pub const SPATIAL_INDEX_FILE_NAME: &str = "spatial_index.feather";
+
+/// Vertex of a map polyline in the city frame.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MapPoint {
+    /// x-coordinate (in meters).
+    pub x: f32,
+    /// y-coordinate (in meters).
+    pub y: f32,
+    /// z-coordinate (in meters).
+    pub z: f32,
+}
+
+/// Lane segment of the vector map.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LaneSegment {
+    /// Unique id.
+    pub id: u64,
+    /// Whether the lane segment lies within an intersection.
+    pub is_intersection: bool,
+    /// Lane type (e.g., `VEHICLE`).
+    pub lane_type: String,
+    /// Left lane boundary.
+    pub left_lane_boundary: Vec<MapPoint>,
+    /// Left lane marking type.
+    pub left_lane_mark_type: String,
+    /// Right lane boundary.
+    pub right_lane_boundary: Vec<MapPoint>,
+    /// Right lane marking type.
+    pub right_lane_mark_type: String,
+    /// Ids of the successor lane segments.
+    pub successors: Vec<u64>,
+    /// Ids of the predecessor lane segments.
+    pub predecessors: Vec<u64>,
+    /// Id of the right neighbor lane segment.
+    pub right_neighbor_id: Option<u64>,
+    /// Id of the left neighbor lane segment.
+    pub left_neighbor_id: Option<u64>,
+}
+
+/// Pedestrian crossing of the vector map.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PedestrianCrossing {
+    /// Unique id.
+    pub id: u64,
+    /// First edge.
+    pub edge1: Vec<MapPoint>,
+    /// Second edge.
+    pub edge2: Vec<MapPoint>,
+}
+
+/// Drivable area of the vector map.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct DrivableArea {
+    /// Unique id.
+    pub id: u64,
+    /// Boundary polygon.
+    pub area_boundary: Vec<MapPoint>,
+}
+
+/// Vector map as stored in `log_map_archive_*.json`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct VectorMapJson {
+    /// Lane segments keyed by id.
+    pub lane_segments: HashMap<String, LaneSegment>,
+    /// Pedestrian crossings keyed by id.
+    pub pedestrian_crossings: HashMap<String, PedestrianCrossing>,
+    /// Drivable areas keyed by id.
+    pub drivable_areas: HashMap<String, DrivableArea>,
+}
+
+/// Map element types.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, Hash, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum MapElementType {
+    /// Lane segments.
+    LaneSegments,
+    /// Pedestrian crossings.
+    PedestrianCrossings,
+    /// Drivable areas.
+    DrivableAreas,
+}
+
+impl MapElementType {
+    /// File name of the cached element table.
+    pub fn file_name(&self) -> String {
+        format!("{self}.feather")
+    }
+}
+
+/// Preprocessed vector map.
+///
+/// Elements are stored as tables with an `id` column, one list column per polyline coordinate
+/// (e.g., `left_lane_boundary_x`), and their bird's-eye-view bounding box
+/// (`min_x`, `min_y`, `max_x`, `max_y`).
+#[derive(Clone, Debug)]
+pub struct VectorMap {
+    /// Lane segments table.
+    pub lane_segments: DataFrame,
+    /// Pedestrian crossings table.
+    pub pedestrian_crossings: DataFrame,
+    /// Drivable areas table.
+    pub drivable_areas: DataFrame,
+    /// Edge length of the spatial index cells (in meters).
+    pub cell_size_m: f32,
+    /// Rows of the elements overlapping each grid cell.
+    cells: HashMap<(i32, i32), Vec<(MapElementType, usize)>>,
+}
+
+impl VectorMap {
+    /// Convert a JSON vector map and build its spatial index.
+    pub fn from_json(path: &Path, cell_size_m: f32) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+        let map: VectorMapJson = serde_json::from_reader(std::io::BufReader::new(file))?;
+
+        // Sort by id so the tables are deterministic.
+        let mut lane_segments = map.lane_segments.into_values().collect::<Vec<_>>();
+        lane_segments.sort_by_key(|x| x.id);
+        let mut pedestrian_crossings = map.pedestrian_crossings.into_values().collect::<Vec<_>>();
+        pedestrian_crossings.sort_by_key(|x| x.id);
+        let mut drivable_areas = map.drivable_areas.into_values().collect::<Vec<_>>();
+        drivable_areas.sort_by_key(|x| x.id);
+
+        let mut lane_segments_table = _element_table(
+            lane_segments.iter().map(|x| x.id),
+            &[
+                (
+                    "left_lane_boundary",
+                    lane_segments
+                        .iter()
+                        .map(|x| &x.left_lane_boundary)
+                        .collect(),
+                ),
+                (
+                    "right_lane_boundary",
+                    lane_segments
+                        .iter()
+                        .map(|x| &x.right_lane_boundary)
+                        .collect(),
+                ),
+            ],
+        )?;
+        let str_column = |name: &str, f: fn(&LaneSegment) -> &str| {
+            Series::new(name, lane_segments.iter().map(f).collect::<Vec<_>>())
+        };
+        let ids_column = |name: &str, f: fn(&LaneSegment) -> &Vec<u64>| {
+            let ids = lane_segments.iter().map(|x| Series::new("", f(x)));
+            Series::new(name, ids.collect::<Vec<_>>())
+        };
+        for column in [
+            Series::new(
+                "is_intersection",
+                lane_segments
+                    .iter()
+                    .map(|x| x.is_intersection)
+                    .collect::<Vec<_>>(),
+            ),
+            str_column("lane_type", |x| &x.lane_type),
+            str_column("left_lane_mark_type", |x| &x.left_lane_mark_type),
+            str_column("right_lane_mark_type", |x| &x.right_lane_mark_type),
+            ids_column("successors", |x| &x.successors),
+            ids_column("predecessors", |x| &x.predecessors),
+            Series::new(
+                "left_neighbor_id",
+                lane_segments
+                    .iter()
+                    .map(|x| x.left_neighbor_id)
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                "right_neighbor_id",
+                lane_segments
+                    .iter()
+                    .map(|x| x.right_neighbor_id)
+                    .collect::<Vec<_>>(),
+            ),
+        ] {
+            lane_segments_table.with_column(column)?;
+        }
+        let pedestrian_crossings = _element_table(
+            pedestrian_crossings.iter().map(|x| x.id),
+            &[
+                (
+                    "edge1",
+                    pedestrian_crossings.iter().map(|x| &x.edge1).collect(),
+                ),
+                (
+                    "edge2",
+                    pedestrian_crossings.iter().map(|x| &x.edge2).collect(),
+                ),
+            ],
+        )?;
+        let drivable_areas = _element_table(
+            drivable_areas.iter().map(|x| x.id),
+            &[(
+                "area_boundary",
+                drivable_areas.iter().map(|x| &x.area_boundary).collect(),
+            )],
+        )?;
+
+        let mut map = VectorMap {
+            lane_segments: lane_segments_table,
+            pedestrian_crossings,
+            drivable_areas,
+            cell_size_m,
+            cells: HashMap::new(),
+        };
+        map.build_index()?;
+        Ok(map)
+    }
+
+    /// Load a map preprocessed with `save`.
+    pub fn load(map_dir: &Path) -> Result<Self> {
+        let read = |file_name: &str| -> Result<DataFrame> {
+            Ok(read_feather(&map_dir.join(file_name), false)?)
+        };
+        let index = read(SPATIAL_INDEX_FILE_NAME)?;
+        let mut map = VectorMap {
+            lane_segments: read(&MapElementType::LaneSegments.file_name())?,
+            pedestrian_crossings: read(&MapElementType::PedestrianCrossings.file_name())?,
+            drivable_areas: read(&MapElementType::DrivableAreas.file_name())?,
+            cell_size_m: index["cell_size_m"]
+                .f32()?
+                .get(0)
+                .unwrap_or(DEFAULT_CELL_SIZE_M),
+            cells: HashMap::new(),
+        };
+
+        let (cell_x, cell_y) = (index["cell_x"].i32()?, index["cell_y"].i32()?);
+        let (element_type, row) = (index["element_type"].str()?, index["row"].u32()?);
+        for i in 0..index.height() {
+            let (Some(cell_x), Some(cell_y), Some(element_type), Some(row)) = (
+                cell_x.get(i),
+                cell_y.get(i),
+                element_type.get(i),
+                row.get(i),
+            ) else {
+                bail!(
+                    "Null entry at row {i} of the spatial index of {}.",
+                    map_dir.display()
+                );
+            };
+            let element_type = element_type.parse::<MapElementType>()?;
+            ensure!(
+                (row as usize) < map.elements(element_type).height(),
+                "Spatial index of {} refers to missing {element_type} row {row}.",
+                map_dir.display()
+            );
+            map.cells
+                .entry((cell_x, cell_y))
+                .or_default()
+                .push((element_type, row as usize));
+        }
+        Ok(map)
+    }
+
+    /// Save the element tables and the spatial index to `map_dir`.
+    pub fn save(&self, map_dir: &Path) -> Result<()> {
+        fs::create_dir_all(map_dir)?;
+        for element_type in [
+            MapElementType::LaneSegments,
+            MapElementType::PedestrianCrossings,
+            MapElementType::DrivableAreas,
+        ] {
+            write_feather_eager(
+                &map_dir.join(element_type.file_name()),
+                self.elements(element_type).clone(),
+            )?;
+        }
+
+        let mut entries = self
+            .cells
+            .iter()
+            .flat_map(|(cell, rows)| rows.iter().map(move |(t, r)| (*cell, *t, *r)))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(cell, t, r)| (*cell, t.to_string(), *r));
+        let index = df!(
+            "cell_x" => entries.iter().map(|x| x.0 .0).collect::<Vec<_>>(),
+            "cell_y" => entries.iter().map(|x| x.0 .1).collect::<Vec<_>>(),
+            "element_type" => entries.iter().map(|x| x.1.to_string()).collect::<Vec<_>>(),
+            "row" => entries.iter().map(|x| x.2 as u32).collect::<Vec<_>>(),
+            "cell_size_m" => vec![self.cell_size_m; entries.len()]
+        )?;
+        // The index is written last, so its presence marks a complete cache entry.
+        write_feather_eager(&map_dir.join(SPATIAL_INDEX_FILE_NAME), index)?;
+        Ok(())
+    }
+
+    /// Table of an element type.
+    pub fn elements(&self, element_type: MapElementType) -> &DataFrame {
+        match element_type {
+            MapElementType::LaneSegments => &self.lane_segments,
+            MapElementType::PedestrianCrossings => &self.pedestrian_crossings,
+            MapElementType::DrivableAreas => &self.drivable_areas,
+        }
+    }
+
+    /// Bird's-eye-view polygons of the `element_type` table in row order.
+    ///
+    /// Drivable areas use their boundary. Pedestrian crossings and lane segments are closed by
+    /// joining their first edge (or left boundary) with their reversed second edge (or right
+    /// boundary).
+    pub fn polygons(&self, element_type: MapElementType) -> Result<Vec<Polygon>> {
+        let table = self.elements(element_type);
+        let (forward, backward) = match element_type {
+            MapElementType::LaneSegments => ("left_lane_boundary", Some("right_lane_boundary")),
+            MapElementType::PedestrianCrossings => ("edge1", Some("edge2")),
+            MapElementType::DrivableAreas => ("area_boundary", None),
+        };
+        (0..table.height())
+            .map(|row| {
+                let mut ring = self.polyline(element_type, forward, row)?;
+                if let Some(backward) = backward {
+                    let backward = self.polyline(element_type, backward, row)?;
+                    ring = concatenate![Axis(0), ring, backward.slice(s![..;-1, ..])];
+                }
+                Ok(Polygon::new(ring.slice(s![.., ..2]).to_owned()))
+            })
+            .collect()
+    }
+
+    /// (N,3) vertices of the polyline `name` (e.g., `left_lane_boundary`) of a row of the
+    /// `element_type` table.
+    pub fn polyline(
+        &self,
+        element_type: MapElementType,
+        name: &str,
+        row: usize,
+    ) -> Result<Array<f32, Ix2>> {
+        let table = self.elements(element_type);
+        let mut vertices = vec![];
+        for axis in ["x", "y", "z"] {
+            let values = table[format!("{name}_{axis}").as_str()]
+                .list()?
+                .get_as_series(row)
+                .with_context(|| format!("Missing {name}_{axis}."))?;
+            vertices.push(Array::from_iter(values.f32()?.into_no_null_iter()));
+        }
+        let vertices = vertices.iter().map(|x| x.view()).collect::<Vec<_>>();
+        Ok(ndarray::stack(Axis(1), &vertices)?)
+    }
+
+    /// (L,K,3) centerlines of the lane segments in row order, each resampled to `num_points`
+    /// vertices evenly spaced by arc length.
+    pub fn lane_centerlines(&self, num_points: usize) -> Result<Array<f32, Ix3>> {
+        let element_type = MapElementType::LaneSegments;
+        let num_lanes = self.elements(element_type).height();
+        let mut centerlines = Array::<f32, Ix3>::zeros((num_lanes, num_points, 3));
+        for (row, mut centerline) in centerlines.outer_iter_mut().enumerate() {
+            let left = self.polyline(element_type, "left_lane_boundary", row)?;
+            let right = self.polyline(element_type, "right_lane_boundary", row)?;
+            ensure!(
+                left.nrows() > 0 && right.nrows() > 0,
+                "Lane segment at row {row} has an empty boundary."
+            );
+            centerline.assign(&compute_centerline(&left.view(), &right.view(), num_points));
+        }
+        Ok(centerlines)
+    }
+
+    /// Index over the lane segment centerlines (see `lane_centerlines`). Hits refer to rows of
+    /// the lane segments table.
+    pub fn lane_centerline_index(&self, num_points: usize) -> Result<PolylineIndex> {
+        let centerlines = self.lane_centerlines(num_points)?;
+        let centerlines = centerlines.outer_iter().collect::<Vec<_>>();
+        Ok(PolylineIndex::new(&centerlines))
+    }
+
+    /// Nearest lane segment ids of (N,2+) points in the city frame and their distances to the
+    /// lane centerlines (in meters). Points without a centerline within `max_distance_m` have no
+    /// id and an infinite distance.
+    #[allow(clippy::type_complexity)]
+    pub fn nearest_lanes(
+        &self,
+        points_city: &ArrayView<f32, Ix2>,
+        max_distance_m: f32,
+    ) -> Result<(Vec<Option<u64>>, Array<f32, Ix1>)> {
+        let index = self.lane_centerline_index(NUM_CENTERLINE_POINTS)?;
+        let (rows, distances) = index.query_nearest_batch(points_city, max_distance_m);
+        let ids = self.lane_segments["id"].u64()?;
+        let ids = rows
+            .iter()
+            .map(|row| usize::try_from(*row).ok().and_then(|row| ids.get(row)))
+            .collect();
+        Ok((ids, distances))
+    }
+
+    /// Rows of the `element_type` table whose bounding box is within `radius_m` of `(x, y)`.
+    /// Rows are sorted and unique.
+    pub fn query_rows(
+        &self,
+        element_type: MapElementType,
+        x: f32,
+        y: f32,
+        radius_m: f32,
+    ) -> Result<Vec<usize>> {
+        let bounds = self._bounds(element_type)?;
+        let to_cell = |v: f32| (v / self.cell_size_m).floor() as i32;
+        let mut rows = vec![];
+        for cell_x in to_cell(x - radius_m)..=to_cell(x + radius_m) {
+            for cell_y in to_cell(y - radius_m)..=to_cell(y + radius_m) {
+                let Some(entries) = self.cells.get(&(cell_x, cell_y)) else {
+                    continue;
+                };
+                rows.extend(
+                    entries
+                        .iter()
+                        .filter(|(t, _)| *t == element_type)
+                        .map(|(_, row)| *row),
+                );
+            }
+        }
+        rows.sort_unstable();
+        rows.dedup();
+        rows.retain(|row| {
+            let [min_x, min_y, max_x, max_y] = bounds[*row];
+            let dx = (min_x - x).max(x - max_x).max(0.);
+            let dy = (min_y - y).max(y - max_y).max(0.);
+            dx * dx + dy * dy <= radius_m * radius_m
+        });
+        Ok(rows)
+    }
+
+    /// Elements of `element_type` whose bounding box is within `radius_m` of `(x, y)`.
+    pub fn query(
+        &self,
+        element_type: MapElementType,
+        x: f32,
+        y: f32,
+        radius_m: f32,
+    ) -> Result<DataFrame> {
+        let rows = self.query_rows(element_type, x, y, radius_m)?;
+        let rows = IdxCa::from_vec("row", rows.into_iter().map(|x| x as IdxSize).collect());
+        Ok(self.elements(element_type).take(&rows)?)
+    }
+
+    /// `[min_x, min_y, max_x, max_y]` bounding box of every row of the `element_type` table.
+    fn _bounds(&self, element_type: MapElementType) -> Result<Vec<[f32; 4]>> {
+        let table = self.elements(element_type);
+        let [min_x, min_y, max_x, max_y] =
+            ["min_x", "min_y", "max_x", "max_y"].map(|name| table[name].f32());
+        let (min_x, min_y, max_x, max_y) = (min_x?, min_y?, max_x?, max_y?);
+        (0..table.height())
+            .map(|row| {
+                match (
+                    min_x.get(row),
+                    min_y.get(row),
+                    max_x.get(row),
+                    max_y.get(row),
+                ) {
+                    (Some(x0), Some(y0), Some(x1), Some(y1)) => Ok([x0, y0, x1, y1]),
+                    _ => bail!("Missing bounding box of {element_type} row {row}."),
+                }
+            })
+            .collect()
+    }
+
+    /// Insert every element into the grid cells overlapped by its bounding box.
+    fn build_index(&mut self) -> Result<()> {
+        let mut cells: HashMap<(i32, i32), Vec<(MapElementType, usize)>> = HashMap::new();
+        let to_cell = |v: f32| (v / self.cell_size_m).floor() as i32;
+        for element_type in [
+            MapElementType::LaneSegments,
+            MapElementType::PedestrianCrossings,
+            MapElementType::DrivableAreas,
+        ] {
+            for (row, [x0, y0, x1, y1]) in self._bounds(element_type)?.into_iter().enumerate() {
+                for cell_x in to_cell(x0)..=to_cell(x1) {
+                    for cell_y in to_cell(y0)..=to_cell(y1) {
+                        cells
+                            .entry((cell_x, cell_y))
+                            .or_default()
+                            .push((element_type, row));
+                    }
+                }
+            }
+        }
+        self.cells = cells;
+        Ok(())
+    }
+}
+
+/// Path of the JSON vector map of a log (e.g., `<log_dir>/map/log_map_archive_*.json`).
+pub fn vector_map_json_path(log_dir: &Path) -> Result<PathBuf> {
+    let pattern = log_dir.join("map/log_map_archive_*.json");
+    glob(pattern.to_str().context("Invalid log directory.")?)?
+        .filter_map(|x| x.ok())
+        .next()
+        .with_context(|| format!("No vector map found in {}.", log_dir.display()))
+}
+
+/// Load the cached map of a log from `<cache_dir>/<log_id>`, preprocessing the JSON vector map if
+/// the cache is missing or older than the JSON.
+pub fn load_vector_map(log_dir: &Path, cache_dir: &Path) -> Result<VectorMap> {
+    let json_path = vector_map_json_path(log_dir)?;
+    let map_dir = cache_dir.join(extract_file_stem(log_dir)?);
+    let index_path = map_dir.join(SPATIAL_INDEX_FILE_NAME);
+    let is_fresh = match (fs::metadata(&index_path), fs::metadata(&json_path)) {
+        (Ok(index), Ok(json)) => index.modified()? >= json.modified()?,
+        _ => false,
+    };
+    if is_fresh {
+        return VectorMap::load(&map_dir);
+    }
+    let map = VectorMap::from_json(&json_path, DEFAULT_CELL_SIZE_M)?;
+    map.save(&map_dir)?;
+    Ok(map)
+}
+
+/// Preprocess the vector maps of every log in `split_dir` in parallel.
+/// Returns the log ids which failed to preprocess.
+pub fn preprocess_vector_maps(split_dir: &Path, cache_dir: &Path) -> Result<Vec<String>> {
+    let log_dirs = walk_dir(&split_dir.to_path_buf())?
+        .into_iter()
+        .filter(|x| x.is_dir())
+        .collect::<Vec<_>>();
+    let mut failed = log_dirs
+        .par_iter()
+        .filter_map(|log_dir| match load_vector_map(log_dir, cache_dir) {
+            Ok(_) => None,
+            Err(_) => extract_file_stem(log_dir).ok(),
+        })
+        .collect::<Vec<_>>();
+    failed.sort();
+    Ok(failed)
+}
+
+/// Build an element table with `x`, `y`, and `z` list columns per polyline and the bounding box
+/// of all polylines.
+fn _element_table(
+    ids: impl Iterator<Item = u64>,
+    polylines: &[(&str, Vec<&Vec<MapPoint>>)],
+) -> Result<DataFrame> {
+    let mut columns = vec![Series::new("id", ids.collect::<Vec<_>>())];
+    for (name, values) in polylines {
+        for (axis, coordinate) in [("x", 0), ("y", 1), ("z", 2)] {
+            let lists = values
+                .iter()
+                .map(|polyline| {
+                    let values = polyline
+                        .iter()
+                        .map(|p| [p.x, p.y, p.z][coordinate])
+                        .collect::<Vec<_>>();
+                    Series::new("", values)
+                })
+                .collect::<Vec<_>>();
+            columns.push(Series::new(&format!("{name}_{axis}"), lists));
+        }
+    }
+
+    let num_elements = columns[0].len();
+    let bounds = (0..num_elements)
+        .map(|i| {
+            polylines
+                .iter()
+                .flat_map(|(_, values)| values[i].iter())
+                .fold(
+                    [
+                        f32::INFINITY,
+                        f32::INFINITY,
+                        f32::NEG_INFINITY,
+                        f32::NEG_INFINITY,
+                    ],
+                    |b, p| [b[0].min(p.x), b[1].min(p.y), b[2].max(p.x), b[3].max(p.y)],
+                )
+        })
+        .collect::<Vec<_>>();
+    for (k, name) in ["min_x", "min_y", "max_x", "max_y"].into_iter().enumerate() {
+        columns.push(Series::new(
+            name,
+            bounds.iter().map(|b| b[k]).collect::<Vec<_>>(),
+        ));
+    }
+    Ok(DataFrame::new(columns)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use strum::IntoEnumIterator;
+
+    use super::{
+        load_vector_map, preprocess_vector_maps, vector_map_json_path, MapElementType, VectorMap,
+        DEFAULT_CELL_SIZE_M, SPATIAL_INDEX_FILE_NAME,
+    };
+    use crate::{
+        path::extract_file_stem,
+        synthetic::{test_dataset, MiniDatasetConfig},
+    };
+
+    #[test]
+    fn test_vector_map_cache_round_trip() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 2,
+            ..Default::default()
+        };
+        let (root_dir, log_dirs) = test_dataset(&cfg);
+        let cache_dir = root_dir.join("map_cache");
+        let split_dir = log_dirs[0].parent().unwrap();
+        assert!(preprocess_vector_maps(split_dir, &cache_dir)
+            .unwrap()
+            .is_empty());
+        for log_dir in log_dirs.iter() {
+            let map_dir = cache_dir.join(extract_file_stem(log_dir).unwrap());
+            assert!(map_dir.join(SPATIAL_INDEX_FILE_NAME).exists());
+        }
+
+        // The cached map matches the map built from the JSON.
+        let log_dir = &log_dirs[0];
+        let json_path = vector_map_json_path(log_dir).unwrap();
+        let built = VectorMap::from_json(&json_path, DEFAULT_CELL_SIZE_M).unwrap();
+        let loaded = load_vector_map(log_dir, &cache_dir).unwrap();
+        for element_type in MapElementType::iter() {
+            assert!(loaded
+                .elements(element_type)
+                .equals_missing(built.elements(element_type)));
+        }
+
+        // Queries at the center of the pedestrian crossing agree.
+        let crossings = &built.pedestrian_crossings;
+        let center = |min: &str, max: &str| {
+            let (min, max) = (crossings[min].f32().unwrap(), crossings[max].f32().unwrap());
+            (min.get(0).unwrap() + max.get(0).unwrap()) / 2.
+        };
+        let (x, y) = (center("min_x", "max_x"), center("min_y", "max_y"));
+        let found = loaded
+            .query(MapElementType::PedestrianCrossings, x, y, 1.)
+            .unwrap();
+        assert_eq!(
+            found["id"].u64().unwrap().get(0),
+            crossings["id"].u64().unwrap().get(0)
+        );
+        for element_type in MapElementType::iter() {
+            let rows = loaded.query_rows(element_type, x, y, 30.).unwrap();
+            assert!(!rows.is_empty());
+            assert_eq!(rows, built.query_rows(element_type, x, y, 30.).unwrap());
+        }
+        assert!(loaded
+            .query_rows(MapElementType::LaneSegments, 1e6, 1e6, 1.)
+            .unwrap()
+            .is_empty());
+
+        // A truncated spatial index fails to load rather than panicking.
+        let map_dir = cache_dir.join(extract_file_stem(log_dir).unwrap());
+        fs::write(map_dir.join(SPATIAL_INDEX_FILE_NAME), b"").unwrap();
+        assert!(VectorMap::load(&map_dir).is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! # static_map
+//!
+//! Typed port of `av2.map.map_api.ArgoverseStaticMap`.
+//!
+//! A log's map directory holds the JSON vector map, the ground height raster (`*.npy`), and the
+//! similarity transform from the city frame to the raster (`*___img_Sim2_city.json`). Elements
+//! are kept as typed structures keyed by id, with lane graph, radius, polygon, and ground height
+//! queries in the city frame.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use glob::glob;
+use ndarray::{concatenate, par_azip, s, Array, ArrayView, Axis, Ix1, Ix2};
+use numpy::{IntoPyArray, PyArray, PyReadonlyArray2};
+use polars::export::arrow::types::f16;
+use pyo3::{exceptions::PyIOError, prelude::*};
+use serde::Deserialize;
+
+use super::{DrivableArea, LaneSegment, MapPoint, PedestrianCrossing, VectorMapJson};
+use crate::geometry::{polyline::compute_centerline, polytope::Polygon};
+
+/// Similarity transform `s * (R @ p + t)` of bird's-eye-view points.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub struct Sim2 {
+    /// Row-major (2,2) rotation.
+    #[serde(rename = "R")]
+    pub rotation: [f32; 4],
+    /// Translation.
+    #[serde(rename = "t")]
+    pub translation: [f32; 2],
+    /// Scale.
+    #[serde(rename = "s")]
+    pub scale: f32,
+}
+
+impl Sim2 {
+    /// Read a transform stored as `{"R": [...], "t": [...], "s": ...}`.
+    pub fn from_json(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// Transform the point `(x, y)`.
+    pub fn transform_point(&self, x: f32, y: f32) -> [f32; 2] {
+        let [r00, r01, r10, r11] = self.rotation;
+        [
+            self.scale * (r00 * x + r01 * y + self.translation[0]),
+            self.scale * (r10 * x + r11 * y + self.translation[1]),
+        ]
+    }
+}
+
+/// Maximum distance to the ground surface of ground points (in meters).
+pub const GROUND_HEIGHT_THRESHOLD_M: f32 = 0.3;
+
+/// Ground height raster of a log's map.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct GroundHeightRaster {
+    /// (H,W) ground heights (in meters).
+    pub heights: Array<f32, Ix2>,
+    /// Transform from the city frame to the raster's pixel coordinates.
+    pub img_sim2_city: Sim2,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl GroundHeightRaster {
+    /// Load the raster and its transform from a log's map directory.
+    #[new]
+    fn py_new(map_dir: PathBuf) -> PyResult<Self> {
+        Self::from_map_dir(&map_dir).map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+
+    /// (N,) ground heights of (N,2+) points in the city frame, `NaN` outside of the raster.
+    #[pyo3(name = "get_ground_height_at")]
+    fn py_get_ground_height_at<'py>(
+        &self,
+        py: Python<'py>,
+        points_city: PyReadonlyArray2<f32>,
+    ) -> &'py PyArray<f32, Ix1> {
+        let points_city = points_city.as_array();
+        py.allow_threads(|| self.get_ground_height_at(&points_city))
+            .into_pyarray(py)
+    }
+
+    /// (N,) mask of the (N,3) points in the city frame which lie on (or below) the ground.
+    #[pyo3(name = "get_ground_points_boolean")]
+    fn py_get_ground_points_boolean<'py>(
+        &self,
+        py: Python<'py>,
+        points_city: PyReadonlyArray2<f32>,
+    ) -> &'py PyArray<bool, Ix1> {
+        let points_city = points_city.as_array();
+        py.allow_threads(|| self.get_ground_points_boolean(&points_city))
+            .into_pyarray(py)
+    }
+}
+
+/// Rust methods.
+impl GroundHeightRaster {
+    /// Load the raster and its transform from a log's map directory.
+    pub fn from_map_dir(map_dir: &Path) -> Result<Self> {
+        Ok(Self {
+            heights: _read_npy_f32(&_find_file(map_dir, "*_ground_height_surface____*.npy")?)?,
+            img_sim2_city: Sim2::from_json(&_find_file(map_dir, "*___img_Sim2_city.json")?)?,
+        })
+    }
+
+    /// Ground height at `(x, y)` in the city frame, or `NaN` outside of the raster.
+    pub fn height_at(&self, x: f32, y: f32) -> f32 {
+        let [u, v] = self.img_sim2_city.transform_point(x, y);
+        // Pixel coordinates are truncated toward zero before the bounds check, as in
+        // `av2.map.map_api`.
+        let (u, v) = (u as i64, v as i64);
+        let (num_rows, num_columns) = self.heights.dim();
+        if u < 0 || v < 0 || u >= num_columns as i64 || v >= num_rows as i64 {
+            return f32::NAN;
+        }
+        self.heights[[v as usize, u as usize]]
+    }
+
+    /// (N,) ground heights of (N,2+) points in the city frame, `NaN` outside of the raster.
+    pub fn get_ground_height_at(&self, points_city: &ArrayView<f32, Ix2>) -> Array<f32, Ix1> {
+        let mut heights = Array::zeros(points_city.nrows());
+        par_azip!((h in &mut heights, point in points_city.outer_iter()) {
+            *h = self.height_at(point[0], point[1]);
+        });
+        heights
+    }
+
+    /// (N,) mask of the (N,3) points in the city frame which lie within
+    /// `GROUND_HEIGHT_THRESHOLD_M` of the ground or below it. Points outside of the raster are
+    /// never ground.
+    pub fn get_ground_points_boolean(&self, points_city: &ArrayView<f32, Ix2>) -> Array<bool, Ix1> {
+        let mut is_ground = Array::from_elem(points_city.nrows(), false);
+        par_azip!((g in &mut is_ground, point in points_city.outer_iter()) {
+            let height = self.height_at(point[0], point[1]);
+            *g = (point[2] - height).abs() <= GROUND_HEIGHT_THRESHOLD_M || point[2] < height;
+        });
+        is_ground
+    }
+}
+
+/// Map of a single log with typed elements keyed by id.
+#[derive(Clone, Debug, Default)]
+pub struct ArgoverseStaticMap {
+    /// Log id of the map.
+    pub log_id: String,
+    /// Lane segments keyed by id.
+    pub lane_segments: BTreeMap<u64, LaneSegment>,
+    /// Pedestrian crossings keyed by id.
+    pub pedestrian_crossings: BTreeMap<u64, PedestrianCrossing>,
+    /// Drivable areas keyed by id.
+    pub drivable_areas: BTreeMap<u64, DrivableArea>,
+    /// Ground height raster (if it was loaded).
+    pub ground_height: Option<GroundHeightRaster>,
+}
+
+impl ArgoverseStaticMap {
+    /// Parse a JSON vector map (e.g., `log_map_archive_<log_id>____<city>_city_<id>.json`).
+    pub fn from_json(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+        let map: VectorMapJson = serde_json::from_reader(std::io::BufReader::new(file))?;
+        let file_name = path
+            .file_name()
+            .and_then(|x| x.to_str())
+            .context("Invalid map path.")?;
+        let log_id = file_name
+            .trim_start_matches("log_map_archive_")
+            .split("__")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Ok(Self {
+            log_id,
+            lane_segments: map.lane_segments.into_values().map(|x| (x.id, x)).collect(),
+            pedestrian_crossings: map
+                .pedestrian_crossings
+                .into_values()
+                .map(|x| (x.id, x))
+                .collect(),
+            drivable_areas: map
+                .drivable_areas
+                .into_values()
+                .map(|x| (x.id, x))
+                .collect(),
+            ground_height: None,
+        })
+    }
+
+    /// Load the map of a log from its map directory (e.g., `<log_dir>/map`).
+    /// The ground height raster is only loaded if `build_raster` is set.
+    pub fn from_map_dir(map_dir: &Path, build_raster: bool) -> Result<Self> {
+        let mut map = Self::from_json(&_find_file(map_dir, "log_map_archive_*.json")?)?;
+        if build_raster {
+            map.ground_height = Some(GroundHeightRaster::from_map_dir(map_dir)?);
+        }
+        Ok(map)
+    }
+
+    /// Lane segment with `id`.
+    pub fn lane_segment(&self, id: u64) -> Result<&LaneSegment> {
+        self.lane_segments
+            .get(&id)
+            .with_context(|| format!("Lane segment {id} not found."))
+    }
+
+    /// Ids of the successors of lane segment `id`.
+    pub fn successor_ids(&self, id: u64) -> Result<&[u64]> {
+        Ok(&self.lane_segment(id)?.successors)
+    }
+
+    /// Ids of the predecessors of lane segment `id`.
+    pub fn predecessor_ids(&self, id: u64) -> Result<&[u64]> {
+        Ok(&self.lane_segment(id)?.predecessors)
+    }
+
+    /// Successors of lane segment `id` which are part of the map.
+    pub fn successors(&self, id: u64) -> Result<Vec<&LaneSegment>> {
+        let ids = self.successor_ids(id)?;
+        Ok(ids
+            .iter()
+            .filter_map(|x| self.lane_segments.get(x))
+            .collect())
+    }
+
+    /// Predecessors of lane segment `id` which are part of the map.
+    pub fn predecessors(&self, id: u64) -> Result<Vec<&LaneSegment>> {
+        let ids = self.predecessor_ids(id)?;
+        Ok(ids
+            .iter()
+            .filter_map(|x| self.lane_segments.get(x))
+            .collect())
+    }
+
+    /// Lane segments with a polygon vertex within `radius_m` of `(x, y)` in the bird's-eye-view.
+    /// Lane segments are sorted by id.
+    pub fn lane_segments_within_radius(&self, x: f32, y: f32, radius_m: f32) -> Vec<&LaneSegment> {
+        self.lane_segments
+            .values()
+            .filter(|lane_segment| {
+                lane_segment
+                    .left_lane_boundary
+                    .iter()
+                    .chain(&lane_segment.right_lane_boundary)
+                    .any(|p| (p.x - x).powi(2) + (p.y - y).powi(2) <= radius_m * radius_m)
+            })
+            .collect()
+    }
+
+    /// (K,3) centerline of lane segment `id` with `num_points` vertices.
+    pub fn lane_segment_centerline(&self, id: u64, num_points: usize) -> Result<Array<f32, Ix2>> {
+        let lane_segment = self.lane_segment(id)?;
+        let left = _polyline(&lane_segment.left_lane_boundary);
+        let right = _polyline(&lane_segment.right_lane_boundary);
+        ensure!(
+            left.nrows() > 0 && right.nrows() > 0,
+            "Lane segment {id} has an empty boundary."
+        );
+        Ok(compute_centerline(&left.view(), &right.view(), num_points))
+    }
+
+    /// (N,3) polygon boundary of lane segment `id`: the left boundary followed by the reversed
+    /// right boundary.
+    pub fn lane_segment_polygon(&self, id: u64) -> Result<Array<f32, Ix2>> {
+        let lane_segment = self.lane_segment(id)?;
+        let left = _polyline(&lane_segment.left_lane_boundary);
+        let right = _polyline(&lane_segment.right_lane_boundary);
+        Ok(concatenate![Axis(0), left, right.slice(s![..;-1, ..])])
+    }
+
+    /// Ids of the lane segments whose polygon contains `(x, y)`.
+    pub fn lane_segments_containing(&self, x: f32, y: f32) -> Vec<u64> {
+        self.lane_segments
+            .keys()
+            .filter(|id| {
+                self.lane_segment_polygon(**id)
+                    .is_ok_and(|ring| Polygon::new(ring).contains(x, y))
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Ids of the pedestrian crossings whose polygon contains `(x, y)`.
+    pub fn pedestrian_crossings_containing(&self, x: f32, y: f32) -> Vec<u64> {
+        self.pedestrian_crossings
+            .values()
+            .filter(|crossing| {
+                let edge1 = _polyline(&crossing.edge1);
+                let edge2 = _polyline(&crossing.edge2);
+                let ring = concatenate![Axis(0), edge1, edge2.slice(s![..;-1, ..])];
+                Polygon::new(ring).contains(x, y)
+            })
+            .map(|crossing| crossing.id)
+            .collect()
+    }
+
+    /// Ids of the drivable areas containing `(x, y)`.
+    pub fn drivable_areas_containing(&self, x: f32, y: f32) -> Vec<u64> {
+        self.drivable_areas
+            .values()
+            .filter(|area| Polygon::new(_polyline(&area.area_boundary)).contains(x, y))
+            .map(|area| area.id)
+            .collect()
+    }
+
+    /// Polygons of the drivable areas.
+    pub fn drivable_area_polygons(&self) -> Vec<Polygon> {
+        self.drivable_areas
+            .values()
+            .map(|x| Polygon::new(_polyline(&x.area_boundary)))
+            .collect()
+    }
+
+    /// Returns `true` if `(x, y)` lies within a drivable area.
+    pub fn is_drivable(&self, x: f32, y: f32) -> bool {
+        !self.drivable_areas_containing(x, y).is_empty()
+    }
+
+    /// (N,) ground heights of (N,2+) points in the city frame, `NaN` outside of the raster.
+    pub fn ground_heights(&self, points_city: &ArrayView<f32, Ix2>) -> Result<Array<f32, Ix1>> {
+        let raster = self
+            .ground_height
+            .as_ref()
+            .context("The ground height raster was not loaded.")?;
+        Ok(raster.get_ground_height_at(points_city))
+    }
+
+    /// (N,) mask of the (N,3) points in the city frame which lie on (or below) the ground.
+    pub fn ground_points(&self, points_city: &ArrayView<f32, Ix2>) -> Result<Array<bool, Ix1>> {
+        let raster = self
+            .ground_height
+            .as_ref()
+            .context("The ground height raster was not loaded.")?;
+        Ok(raster.get_ground_points_boolean(points_city))
+    }
+}
+
+/// (N,3) vertices of a polyline.
+fn _polyline(points: &[MapPoint]) -> Array<f32, Ix2> {
+    Array::from_shape_fn((points.len(), 3), |(i, k)| {
+        [points[i].x, points[i].y, points[i].z][k]
+    })
+}
+
+/// Path of the single file of `map_dir` matching the glob `pattern`.
+fn _find_file(map_dir: &Path, pattern: &str) -> Result<PathBuf> {
+    let pattern = map_dir.join(pattern);
+    glob(pattern.to_str().context("Invalid map directory.")?)?
+        .filter_map(|x| x.ok())
+        .next()
+        .with_context(|| format!("No {} found.", pattern.display()))
+}
+
+/// Read a C-order little-endian `float16` or `float32` (H,W) array in the NumPy `.npy` format.
+fn _read_npy_f32(path: &Path) -> Result<Array<f32, Ix2>> {
+    let bytes = fs::read(path).with_context(|| format!("{} not found.", path.display()))?;
+    ensure!(bytes.starts_with(b"\x93NUMPY"), "Invalid npy file.");
+    // Version 1.0 headers have a 2-byte length and later versions a 4-byte length.
+    let (header_start, header_len) = match bytes[6] {
+        1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+        _ => (
+            12,
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+        ),
+    };
+    let header = std::str::from_utf8(&bytes[header_start..header_start + header_len])?;
+    ensure!(
+        header.contains("'fortran_order': False"),
+        "Only C-order arrays are supported."
+    );
+    let shape = header
+        .split("'shape': (")
+        .nth(1)
+        .and_then(|x| x.split(')').next())
+        .context("Missing shape.")?
+        .split(',')
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| x.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    ensure!(shape.len() == 2, "Expected a 2D array, got {shape:?}.");
+
+    let data = &bytes[header_start + header_len..];
+    let values = if header.contains("'descr': '<f2'") {
+        data.chunks_exact(2)
+            .map(|x| f16(u16::from_le_bytes([x[0], x[1]])).to_f32())
+            .collect::<Vec<_>>()
+    } else if header.contains("'descr': '<f4'") {
+        data.chunks_exact(4)
+            .map(|x| f32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+            .collect::<Vec<_>>()
+    } else {
+        bail!("Only float16 and float32 arrays are supported.");
+    };
+    Ok(Array::from_shape_vec((shape[0], shape[1]), values)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ndarray::array;
+    use polars::{df, prelude::NamedFrom};
+
+    use super::ArgoverseStaticMap;
+    use crate::geometry::{
+        ground::{annotate_ground_points, remove_map_ground, IS_GROUND_COLUMN},
+        se3::SE3,
+    };
+
+    #[test]
+    fn test_argoverse_static_map() {
+        let map_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/unit/test_data/sensor/val/7fab2350-7eaf-3b7e-a39d-6937a4c1bede/map");
+        let map = ArgoverseStaticMap::from_map_dir(&map_dir, true).unwrap();
+        assert_eq!(map.log_id, "7fab2350-7eaf-3b7e-a39d-6937a4c1bede");
+        assert_eq!(map.lane_segments.len(), 183);
+        assert_eq!(map.drivable_areas.len(), 13);
+
+        let id = 38109167;
+        assert_eq!(map.successor_ids(id).unwrap(), &[38109400]);
+        assert_eq!(map.predecessor_ids(id).unwrap(), &[38117100]);
+        assert!(map
+            .successors(id)
+            .unwrap()
+            .iter()
+            .all(|x| x.predecessors.contains(&id)));
+        assert!(map.lane_segment(0).is_err());
+
+        let centerline = map.lane_segment_centerline(id, 10).unwrap();
+        assert_eq!(centerline.shape(), &[10, 3]);
+        let (x, y) = (centerline[[5, 0]], centerline[[5, 1]]);
+        assert!(map.lane_segments_containing(x, y).contains(&id));
+        assert!(map
+            .lane_segments_within_radius(x, y, 10.)
+            .iter()
+            .any(|x| x.id == id));
+        assert!(map.is_drivable(x, y));
+        assert!(!map.is_drivable(0., 0.));
+
+        let heights = map
+            .ground_heights(&array![[x, y], [0., 0.]].view())
+            .unwrap();
+        assert!((heights[0] - centerline[[5, 2]]).abs() < 1.);
+        assert!(heights[1].is_nan());
+
+        let h = heights[0];
+        let is_ground = map
+            .ground_points(
+                &array![
+                    [x, y, h + 0.2],
+                    [x, y, h + 1.],
+                    [x, y, h - 5.],
+                    [0., 0., 0.]
+                ]
+                .view(),
+            )
+            .unwrap();
+        assert_eq!(is_ground, array![true, false, true, false]);
+
+        // Ground points are tagged in the city frame.
+        let raster = map.ground_height.as_ref().unwrap();
+        let lidar = df!("x" => [0_f32, 0.], "y" => [0_f32, 0.], "z" => [0.2_f32, 1.]).unwrap();
+        let city_se3_ego =
+            SE3::from_quat_wxyz(&array![1., 0., 0., 0.].view(), &array![x, y, h].view()).unwrap();
+        let lidar = annotate_ground_points(&lidar, raster, &city_se3_ego).unwrap();
+        assert_eq!(
+            lidar[IS_GROUND_COLUMN]
+                .bool()
+                .unwrap()
+                .into_no_null_iter()
+                .collect::<Vec<_>>(),
+            [true, false]
+        );
+        assert_eq!(
+            remove_map_ground(&lidar, raster, &city_se3_ego)
+                .unwrap()
+                .height(),
+            1
+        );
+    }
+}
@@ -0,0 +1,620 @@
+//! # motion_forecasting
+//!
+//! Loader of the motion forecasting dataset.
+//!
+//! A scenario directory holds the tracks of the scenario (`scenario_<scenario_id>.parquet`, one
+//! row per object state) and its local vector map (`log_map_archive_<scenario_id>.json`).
+//! Scenarios are parsed into typed tracks, and batches of scenarios are converted into padded
+//! agent histories and candidate centerlines.
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+use ndarray::{s, Array, Ix2, Ix3, Ix4};
+use numpy::{IntoPyArray, PyArray};
+use polars::prelude::*;
+use pyo3::{exceptions::PyIOError, prelude::*};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{map::static_map::ArgoverseStaticMap, path::walk_dir};
+
+/// Sampling rate of the scenarios (in Hz).
+pub const SCENARIO_STEP_HZ: usize = 10;
+/// Number of observed timesteps of a scenario.
+pub const NUM_OBSERVED_TIMESTEPS: usize = 50;
+/// Number of predicted timesteps of a scenario.
+pub const NUM_PREDICTED_TIMESTEPS: usize = 60;
+/// Number of timesteps of a scenario.
+pub const NUM_SCENARIO_TIMESTEPS: usize = NUM_OBSERVED_TIMESTEPS + NUM_PREDICTED_TIMESTEPS;
+
+/// Default number of candidate centerlines per scenario.
+pub const DEFAULT_NUM_CENTERLINES: usize = 8;
+/// Default number of vertices per candidate centerline.
+pub const DEFAULT_NUM_CENTERLINE_POINTS: usize = 20;
+/// Default search radius (in meters) of the candidate centerlines around the focal agent.
+pub const DEFAULT_CENTERLINE_RADIUS_M: f32 = 30.;
+
+/// Track category, which indicates the data quality and scoring of a track.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum TrackCategory {
+    /// Low quality track which may only have a few observations.
+    TrackFragment = 0,
+    /// Track of reasonable quality which is not scored.
+    UnscoredTrack = 1,
+    /// High-quality track scored in the multi-agent challenge.
+    ScoredTrack = 2,
+    /// Track used to generate the scenario, scored in the single-agent challenge.
+    FocalTrack = 3,
+}
+
+impl TrackCategory {
+    /// Category with the serialized id `id`.
+    pub fn from_id(id: i64) -> Result<Self> {
+        Self::iter()
+            .find(|x| *x as i64 == id)
+            .with_context(|| format!("Unknown track category {id}."))
+    }
+}
+
+/// Object type of a track.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, PartialEq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ObjectType {
+    /// Vehicle.
+    Vehicle,
+    /// Pedestrian.
+    Pedestrian,
+    /// Motorcyclist.
+    Motorcyclist,
+    /// Cyclist.
+    Cyclist,
+    /// Bus.
+    Bus,
+    /// Static object.
+    Static,
+    /// Background object.
+    Background,
+    /// Construction object.
+    Construction,
+    /// Riderless bicycle.
+    RiderlessBicycle,
+    /// Other or unknown object.
+    Unknown,
+}
+
+/// State of an object at a single timestep.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectState {
+    /// Whether the state is in the observed segment of the scenario.
+    pub observed: bool,
+    /// Timestep in `[0, num_timesteps)`.
+    pub timestep: usize,
+    /// `(x, y)` center of the object in the city frame (in meters).
+    pub position: [f64; 2],
+    /// Heading in the city frame (in radians).
+    pub heading: f64,
+    /// `(x, y)` velocity (in meters per second).
+    pub velocity: [f64; 2],
+}
+
+/// Track of a single object.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track {
+    /// Unique id within the scenario.
+    pub track_id: String,
+    /// States at the timesteps where the object was observed, in temporal order.
+    pub object_states: Vec<ObjectState>,
+    /// Object type.
+    pub object_type: ObjectType,
+    /// Track category.
+    pub category: TrackCategory,
+}
+
+/// Typed port of `av2.datasets.motion_forecasting.data_schema.ArgoverseScenario`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArgoverseScenario {
+    /// Unique id.
+    pub scenario_id: String,
+    /// Nanosecond timestamps of the timesteps.
+    pub timestamps_ns: Vec<u64>,
+    /// Tracks sorted by id.
+    pub tracks: Vec<Track>,
+    /// Id of the focal track.
+    pub focal_track_id: String,
+    /// City name.
+    pub city_name: String,
+    /// Map id (if it was serialized).
+    pub map_id: Option<u64>,
+    /// Slice id (if it was serialized).
+    pub slice_id: Option<String>,
+}
+
+impl ArgoverseScenario {
+    /// Parse a scenario serialized by `serialize_argoverse_scenario_parquet`.
+    pub fn from_parquet(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("{} not found.", path.display()))?;
+        let frame = ParquetReader::new(file).finish()?;
+        ensure!(frame.height() > 0, "{} has no tracks.", path.display());
+        let first_str = |name: &str| -> Result<String> {
+            Ok(frame[name].str()?.get(0).unwrap_or_default().to_string())
+        };
+
+        let start_ns = frame["start_timestamp"].cast(&DataType::Float64)?;
+        let end_ns = frame["end_timestamp"].cast(&DataType::Float64)?;
+        let num_timestamps = frame["num_timestamps"].cast(&DataType::Int64)?;
+        let timestamps_ns = _linspace(
+            start_ns.f64()?.get(0).unwrap_or_default(),
+            end_ns.f64()?.get(0).unwrap_or_default(),
+            num_timestamps.i64()?.get(0).unwrap_or_default() as usize,
+        );
+        let map_id = match frame.column("map_id") {
+            Ok(map_id) => map_id.cast(&DataType::UInt64)?.u64()?.get(0),
+            Err(_) => None,
+        };
+        let slice_id = match frame.column("slice_id") {
+            Ok(slice_id) => slice_id.str()?.get(0).map(|x| x.to_string()),
+            Err(_) => None,
+        };
+        Ok(Self {
+            scenario_id: first_str("scenario_id")?,
+            timestamps_ns,
+            tracks: _read_tracks(&frame)?,
+            focal_track_id: first_str("focal_track_id")?,
+            city_name: first_str("city")?,
+            map_id,
+            slice_id,
+        })
+    }
+
+    /// Focal track of the scenario.
+    pub fn focal_track(&self) -> Result<&Track> {
+        self.tracks
+            .iter()
+            .find(|x| x.track_id == self.focal_track_id)
+            .with_context(|| format!("Focal track {} not found.", self.focal_track_id))
+    }
+}
+
+/// Padded tensors of a batch of scenarios.
+///
+/// Agents are ordered with the focal agent first, followed by the other tracks sorted by id.
+/// Padded agents, timesteps, and centerlines are zero and marked invalid.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct ScenarioBatch {
+    /// Scenario ids.
+    #[pyo3(get)]
+    pub scenario_ids: Vec<String>,
+    /// Track ids of the agents of each scenario.
+    #[pyo3(get)]
+    pub track_ids: Vec<Vec<String>>,
+    /// (B,A,T,2) agent positions in the city frame.
+    pub positions: Array<f32, Ix4>,
+    /// (B,A,T) agent headings.
+    pub headings: Array<f32, Ix3>,
+    /// (B,A,T,2) agent velocities.
+    pub velocities: Array<f32, Ix4>,
+    /// (B,A,T) validity of the agent states.
+    pub valid: Array<bool, Ix3>,
+    /// (B,A) index of each agent's object type in `ObjectType` order (`-1` if padded).
+    pub object_types: Array<i64, Ix2>,
+    /// (B,A) track category of each agent (`-1` if padded).
+    pub categories: Array<i64, Ix2>,
+    /// (B,C,K,2) candidate centerlines of the focal agent in the city frame.
+    pub centerlines: Array<f32, Ix4>,
+    /// (B,C) validity of the candidate centerlines.
+    pub centerlines_valid: Array<bool, Ix2>,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl ScenarioBatch {
+    /// (B,A,T,2) agent positions in the city frame.
+    #[getter(positions)]
+    fn py_positions<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix4> {
+        self.positions.clone().into_pyarray(py)
+    }
+
+    /// (B,A,T) agent headings.
+    #[getter(headings)]
+    fn py_headings<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix3> {
+        self.headings.clone().into_pyarray(py)
+    }
+
+    /// (B,A,T,2) agent velocities.
+    #[getter(velocities)]
+    fn py_velocities<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix4> {
+        self.velocities.clone().into_pyarray(py)
+    }
+
+    /// (B,A,T) validity of the agent states.
+    #[getter(valid)]
+    fn py_valid<'py>(&self, py: Python<'py>) -> &'py PyArray<bool, Ix3> {
+        self.valid.clone().into_pyarray(py)
+    }
+
+    /// (B,A) index of each agent's object type (`-1` if padded).
+    #[getter(object_types)]
+    fn py_object_types<'py>(&self, py: Python<'py>) -> &'py PyArray<i64, Ix2> {
+        self.object_types.clone().into_pyarray(py)
+    }
+
+    /// (B,A) track category of each agent (`-1` if padded).
+    #[getter(categories)]
+    fn py_categories<'py>(&self, py: Python<'py>) -> &'py PyArray<i64, Ix2> {
+        self.categories.clone().into_pyarray(py)
+    }
+
+    /// (B,C,K,2) candidate centerlines of the focal agent.
+    #[getter(centerlines)]
+    fn py_centerlines<'py>(&self, py: Python<'py>) -> &'py PyArray<f32, Ix4> {
+        self.centerlines.clone().into_pyarray(py)
+    }
+
+    /// (B,C) validity of the candidate centerlines.
+    #[getter(centerlines_valid)]
+    fn py_centerlines_valid<'py>(&self, py: Python<'py>) -> &'py PyArray<bool, Ix2> {
+        self.centerlines_valid.clone().into_pyarray(py)
+    }
+}
+
+/// Loader of the scenarios of a motion forecasting split.
+#[pyclass(module = "av2._r")]
+#[derive(Clone, Debug)]
+pub struct ScenarioLoader {
+    /// Split directory (e.g., `~/data/datasets/av2/motion_forecasting/train`).
+    pub split_dir: PathBuf,
+    /// Scenario directories sorted by scenario id.
+    pub scenario_dirs: Vec<PathBuf>,
+    /// Maximum number of candidate centerlines per scenario.
+    #[pyo3(get, set)]
+    pub num_centerlines: usize,
+    /// Number of vertices per candidate centerline.
+    #[pyo3(get, set)]
+    pub num_centerline_points: usize,
+    /// Search radius (in meters) of the candidate centerlines around the focal agent.
+    #[pyo3(get, set)]
+    pub centerline_radius_m: f32,
+}
+
+/// Python bound methods are found here.
+#[pymethods]
+impl ScenarioLoader {
+    /// Index the scenarios of the split directory.
+    #[new]
+    fn py_new(split_dir: &str) -> PyResult<Self> {
+        ScenarioLoader::new(Path::new(split_dir)).map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+
+    /// Scenario ids in loading order.
+    #[getter(scenario_ids)]
+    fn py_scenario_ids(&self) -> Vec<String> {
+        self.scenario_ids()
+    }
+
+    /// Padded tensors of the scenarios at `indices`.
+    #[pyo3(name = "get_batch")]
+    fn py_get_batch(&self, py: Python<'_>, indices: Vec<usize>) -> PyResult<ScenarioBatch> {
+        py.allow_threads(|| self.get_batch(&indices))
+            .map_err(|err| PyIOError::new_err(err.to_string()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Rust methods.
+impl ScenarioLoader {
+    /// Index the scenarios of `split_dir`.
+    pub fn new(split_dir: &Path) -> Result<Self> {
+        let mut scenario_dirs = walk_dir(&split_dir.to_path_buf())
+            .with_context(|| format!("{} not found.", split_dir.display()))?
+            .into_iter()
+            .filter(|x| x.is_dir())
+            .collect::<Vec<_>>();
+        scenario_dirs.sort();
+        Ok(Self {
+            split_dir: split_dir.to_path_buf(),
+            scenario_dirs,
+            num_centerlines: DEFAULT_NUM_CENTERLINES,
+            num_centerline_points: DEFAULT_NUM_CENTERLINE_POINTS,
+            centerline_radius_m: DEFAULT_CENTERLINE_RADIUS_M,
+        })
+    }
+
+    /// Number of scenarios.
+    pub fn len(&self) -> usize {
+        self.scenario_dirs.len()
+    }
+
+    /// Returns `true` if the split has no scenarios.
+    pub fn is_empty(&self) -> bool {
+        self.scenario_dirs.is_empty()
+    }
+
+    /// Scenario ids in loading order.
+    pub fn scenario_ids(&self) -> Vec<String> {
+        self.scenario_dirs.iter().map(|x| _scenario_id(x)).collect()
+    }
+
+    /// Scenario path of the scenario at `index`.
+    /// E.g., `<split_dir>/<scenario_id>/scenario_<scenario_id>.parquet`.
+    pub fn scenario_path(&self, index: usize) -> Result<PathBuf> {
+        let scenario_dir = self
+            .scenario_dirs
+            .get(index)
+            .with_context(|| format!("Scenario index {index} is out of bounds."))?;
+        Ok(scenario_dir.join(format!("scenario_{}.parquet", _scenario_id(scenario_dir))))
+    }
+
+    /// Scenario and local vector map at `index`.
+    pub fn get(&self, index: usize) -> Result<(ArgoverseScenario, ArgoverseStaticMap)> {
+        let scenario = ArgoverseScenario::from_parquet(&self.scenario_path(index)?)?;
+        let map = ArgoverseStaticMap::from_map_dir(&self.scenario_dirs[index], false)?;
+        Ok((scenario, map))
+    }
+
+    /// Padded tensors of the scenarios at `indices`. Scenarios are loaded in parallel.
+    pub fn get_batch(&self, indices: &[usize]) -> Result<ScenarioBatch> {
+        let scenarios = indices
+            .par_iter()
+            .map(|index| {
+                let (scenario, map) = self.get(*index)?;
+                let centerlines = self.candidate_centerlines(&scenario, &map)?;
+                Ok((scenario, centerlines))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_agents = scenarios
+            .iter()
+            .map(|(x, _)| x.tracks.len())
+            .max()
+            .unwrap_or(0);
+        let num_timesteps = scenarios
+            .iter()
+            .map(|(x, _)| x.timestamps_ns.len())
+            .max()
+            .unwrap_or(0);
+        let (b, a, t) = (scenarios.len(), num_agents, num_timesteps);
+        let (c, k) = (self.num_centerlines, self.num_centerline_points);
+        let mut batch = ScenarioBatch {
+            scenario_ids: vec![],
+            track_ids: vec![],
+            positions: Array::zeros((b, a, t, 2)),
+            headings: Array::zeros((b, a, t)),
+            velocities: Array::zeros((b, a, t, 2)),
+            valid: Array::from_elem((b, a, t), false),
+            object_types: Array::from_elem((b, a), -1),
+            categories: Array::from_elem((b, a), -1),
+            centerlines: Array::zeros((b, c, k, 2)),
+            centerlines_valid: Array::from_elem((b, c), false),
+        };
+        for (i, (scenario, centerlines)) in scenarios.iter().enumerate() {
+            let tracks = _agent_order(scenario)?;
+            for (j, track) in tracks.iter().enumerate() {
+                for state in track.object_states.iter().filter(|x| x.timestep < t) {
+                    let ts = state.timestep;
+                    batch.positions[[i, j, ts, 0]] = state.position[0] as f32;
+                    batch.positions[[i, j, ts, 1]] = state.position[1] as f32;
+                    batch.headings[[i, j, ts]] = state.heading as f32;
+                    batch.velocities[[i, j, ts, 0]] = state.velocity[0] as f32;
+                    batch.velocities[[i, j, ts, 1]] = state.velocity[1] as f32;
+                    batch.valid[[i, j, ts]] = true;
+                }
+                batch.object_types[[i, j]] = ObjectType::iter()
+                    .position(|x| x == track.object_type)
+                    .unwrap_or_default() as i64;
+                batch.categories[[i, j]] = track.category as i64;
+            }
+            for (l, centerline) in centerlines.iter().enumerate() {
+                batch
+                    .centerlines
+                    .slice_mut(s![i, l, .., ..])
+                    .assign(&centerline.slice(s![.., ..2]));
+                batch.centerlines_valid[[i, l]] = true;
+            }
+            batch.scenario_ids.push(scenario.scenario_id.clone());
+            batch
+                .track_ids
+                .push(tracks.iter().map(|x| x.track_id.clone()).collect());
+        }
+        Ok(batch)
+    }
+
+    /// (K,3) centerlines of the lane segments within `centerline_radius_m` of the focal agent's
+    /// last observed position, nearest first. At most `num_centerlines` are returned.
+    pub fn candidate_centerlines(
+        &self,
+        scenario: &ArgoverseScenario,
+        map: &ArgoverseStaticMap,
+    ) -> Result<Vec<Array<f32, Ix2>>> {
+        let focal_track = scenario.focal_track()?;
+        let Some(state) = focal_track.object_states.iter().rev().find(|x| x.observed) else {
+            bail!(
+                "The focal track {} is never observed.",
+                focal_track.track_id
+            );
+        };
+        let (x, y) = (state.position[0] as f32, state.position[1] as f32);
+        let mut centerlines = map
+            .lane_segments_within_radius(x, y, self.centerline_radius_m)
+            .iter()
+            .map(|lane_segment| {
+                let centerline =
+                    map.lane_segment_centerline(lane_segment.id, self.num_centerline_points)?;
+                let distance = centerline
+                    .outer_iter()
+                    .map(|p| (p[0] - x).powi(2) + (p[1] - y).powi(2))
+                    .fold(f32::INFINITY, f32::min);
+                Ok((distance, centerline))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        centerlines.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(centerlines
+            .into_iter()
+            .take(self.num_centerlines)
+            .map(|(_, x)| x)
+            .collect())
+    }
+}
+
+/// Tracks of a scenario with the focal track first.
+fn _agent_order(scenario: &ArgoverseScenario) -> Result<Vec<&Track>> {
+    let focal_track = scenario.focal_track()?;
+    Ok(std::iter::once(focal_track)
+        .chain(
+            scenario
+                .tracks
+                .iter()
+                .filter(|x| x.track_id != focal_track.track_id),
+        )
+        .collect())
+}
+
+/// Tracks of a scenario frame, sorted by id.
+fn _read_tracks(frame: &DataFrame) -> Result<Vec<Track>> {
+    let track_ids = frame["track_id"].str()?;
+    let observed = frame["observed"].bool()?;
+    let object_types = frame["object_type"].str()?;
+    let categories = frame["object_category"].cast(&DataType::Int64)?;
+    let categories = categories.i64()?;
+    let timesteps = frame["timestep"].cast(&DataType::Int64)?;
+    let timesteps = timesteps.i64()?;
+    let [position_x, position_y, heading, velocity_x, velocity_y] = [
+        "position_x",
+        "position_y",
+        "heading",
+        "velocity_x",
+        "velocity_y",
+    ]
+    .map(|name| frame[name].cast(&DataType::Float64));
+    let (position_x, position_y, heading, velocity_x, velocity_y) =
+        (position_x?, position_y?, heading?, velocity_x?, velocity_y?);
+    let [position_x, position_y, heading, velocity_x, velocity_y] =
+        [&position_x, &position_y, &heading, &velocity_x, &velocity_y].map(|x| x.f64());
+    let (position_x, position_y, heading, velocity_x, velocity_y) =
+        (position_x?, position_y?, heading?, velocity_x?, velocity_y?);
+
+    let mut tracks = BTreeMap::<String, Track>::new();
+    for i in 0..frame.height() {
+        let track_id = track_ids.get(i).context("Missing track id.")?;
+        if !tracks.contains_key(track_id) {
+            let object_type = object_types.get(i).context("Missing object type.")?;
+            let track = Track {
+                track_id: track_id.to_string(),
+                object_states: vec![],
+                object_type: object_type
+                    .parse()
+                    .with_context(|| format!("Unknown object type `{object_type}`."))?,
+                category: TrackCategory::from_id(
+                    categories.get(i).context("Missing track category.")?,
+                )?,
+            };
+            tracks.insert(track_id.to_string(), track);
+        }
+        let state = ObjectState {
+            observed: observed.get(i).unwrap_or_default(),
+            timestep: timesteps.get(i).context("Missing timestep.")? as usize,
+            position: [
+                position_x.get(i).unwrap_or(f64::NAN),
+                position_y.get(i).unwrap_or(f64::NAN),
+            ],
+            heading: heading.get(i).unwrap_or(f64::NAN),
+            velocity: [
+                velocity_x.get(i).unwrap_or(f64::NAN),
+                velocity_y.get(i).unwrap_or(f64::NAN),
+            ],
+        };
+        if let Some(track) = tracks.get_mut(track_id) {
+            track.object_states.push(state);
+        }
+    }
+    let mut tracks = tracks.into_values().collect::<Vec<_>>();
+    for track in tracks.iter_mut() {
+        track.object_states.sort_by_key(|x| x.timestep);
+    }
+    Ok(tracks)
+}
+
+/// `num` evenly spaced nanosecond timestamps from `start_ns` to `end_ns` (inclusive).
+fn _linspace(start_ns: f64, end_ns: f64, num: usize) -> Vec<u64> {
+    match num {
+        0 => vec![],
+        1 => vec![start_ns as u64],
+        _ => (0..num)
+            .map(|i| (start_ns + (end_ns - start_ns) * i as f64 / (num - 1) as f64).round() as u64)
+            .collect(),
+    }
+}
+
+/// Scenario id of a scenario directory.
+fn _scenario_id(scenario_dir: &Path) -> String {
+    scenario_dir
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{ObjectType, ScenarioLoader, TrackCategory, NUM_SCENARIO_TIMESTEPS};
+
+    #[test]
+    fn test_scenario_loader() {
+        let split_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../tests/unit/test_data/forecasting_scenarios");
+        let loader = ScenarioLoader::new(&split_dir).unwrap();
+        assert_eq!(loader.len(), 1);
+        assert_eq!(
+            loader.scenario_ids(),
+            vec!["0a1e6f0a-1817-4a98-b02e-db8c9327d151"]
+        );
+
+        let (scenario, map) = loader.get(0).unwrap();
+        assert_eq!(scenario.scenario_id, "0a1e6f0a-1817-4a98-b02e-db8c9327d151");
+        assert_eq!(scenario.city_name, "austin");
+        assert_eq!(scenario.timestamps_ns.len(), NUM_SCENARIO_TIMESTEPS);
+        assert!(scenario.timestamps_ns.windows(2).all(|x| x[1] > x[0]));
+        let focal_track = scenario.focal_track().unwrap();
+        assert_eq!(focal_track.category, TrackCategory::FocalTrack);
+        assert_eq!(focal_track.object_type, ObjectType::Vehicle);
+        let num_states = scenario
+            .tracks
+            .iter()
+            .map(|x| x.object_states.len())
+            .sum::<usize>();
+        assert_eq!(num_states, 2434);
+        assert!(!map.lane_segments.is_empty());
+
+        let batch = loader.get_batch(&[0, 0]).unwrap();
+        let num_agents = scenario.tracks.len();
+        assert_eq!(
+            batch.positions.shape(),
+            &[2, num_agents, NUM_SCENARIO_TIMESTEPS, 2]
+        );
+        assert_eq!(batch.track_ids[0][0], scenario.focal_track_id);
+        assert_eq!(batch.categories[[0, 0]], TrackCategory::FocalTrack as i64);
+        assert_eq!(batch.valid.iter().filter(|x| **x).count(), 2 * num_states);
+        let first_state = focal_track.object_states[0];
+        assert_eq!(
+            batch.positions[[1, 0, first_state.timestep, 0]],
+            first_state.position[0] as f32
+        );
+        assert!(batch.centerlines_valid[[0, 0]]);
+        assert_eq!(
+            batch.centerlines.shape(),
+            &[2, loader.num_centerlines, loader.num_centerline_points, 2]
+        );
+    }
+}
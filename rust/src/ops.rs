@@ -2,6 +2,7 @@
 //!
 //! Optimized operations for data processing.
 
+use anyhow::{ensure, Result};
 use itertools::Itertools;
 use ndarray::{azip, par_azip, s, Array1, Array2, ArrayView2, Axis};
 use std::{
@@ -66,3 +67,162 @@ pub fn voxelize(
     });
     (indices_buffer, values_buffer, counts)
 }
+
+/// Solve the linear sum assignment problem over an (R,C) cost matrix.
+///
+/// Uses the shortest augmenting path variant of the Jonker-Volgenant algorithm, matching
+/// `scipy.optimize.linear_sum_assignment`: every row (or column, if there are fewer columns) is
+/// assigned to exactly one column such that the total cost is minimized (or maximized if
+/// `maximize` is set). Returns the assigned row indices in ascending order and their columns.
+pub fn linear_sum_assignment(
+    cost: &ArrayView2<f32>,
+    maximize: bool,
+) -> Result<(Array1<usize>, Array1<usize>)> {
+    ensure!(
+        cost.iter().all(|x| !x.is_nan() && *x != f32::NEG_INFINITY),
+        "The cost matrix contains NaN or -inf."
+    );
+    let transpose = cost.nrows() > cost.ncols();
+    let cost = match transpose {
+        true => cost.t(),
+        false => cost.view(),
+    };
+    let sign = if maximize { -1. } else { 1. };
+    let cost = cost.mapv(|x| sign * x as f64);
+    let (num_rows, num_cols) = cost.dim();
+
+    let mut u = vec![0_f64; num_rows];
+    let mut v = vec![0_f64; num_cols];
+    let mut col4row = vec![usize::MAX; num_rows];
+    let mut row4col = vec![usize::MAX; num_cols];
+    for row in 0..num_rows {
+        let (sink, min_value, path, shortest_path_costs, visited_rows, visited_cols) =
+            _augmenting_path(&cost, &u, &v, &row4col, row);
+        ensure!(sink != usize::MAX, "The cost matrix is infeasible.");
+
+        u[row] += min_value;
+        for (i, _) in visited_rows
+            .iter()
+            .enumerate()
+            .filter(|(i, x)| **x && *i != row)
+        {
+            u[i] += min_value - shortest_path_costs[col4row[i]];
+        }
+        for (j, _) in visited_cols.iter().enumerate().filter(|(_, x)| **x) {
+            v[j] -= min_value - shortest_path_costs[j];
+        }
+
+        let mut j = sink;
+        loop {
+            let i = path[j];
+            row4col[j] = i;
+            std::mem::swap(&mut col4row[i], &mut j);
+            if i == row {
+                break;
+            }
+        }
+    }
+
+    let assignments = match transpose {
+        true => row4col,
+        false => col4row,
+    };
+    let (rows, cols): (Vec<_>, Vec<_>) = assignments
+        .into_iter()
+        .enumerate()
+        .filter(|(_, j)| *j != usize::MAX)
+        .unzip();
+    Ok((Array1::from_vec(rows), Array1::from_vec(cols)))
+}
+
+/// Find the shortest augmenting path from `row` to an unassigned column over the reduced costs.
+#[allow(clippy::type_complexity)]
+fn _augmenting_path(
+    cost: &Array2<f64>,
+    u: &[f64],
+    v: &[f64],
+    row4col: &[usize],
+    row: usize,
+) -> (usize, f64, Vec<usize>, Vec<f64>, Vec<bool>, Vec<bool>) {
+    let num_cols = cost.ncols();
+    let mut path = vec![usize::MAX; num_cols];
+    let mut shortest_path_costs = vec![f64::INFINITY; num_cols];
+    let mut visited_rows = vec![false; cost.nrows()];
+    let mut visited_cols = vec![false; num_cols];
+    let mut remaining = (0..num_cols).rev().collect::<Vec<_>>();
+
+    let mut i = row;
+    let mut min_value = 0_f64;
+    let mut sink = usize::MAX;
+    while sink == usize::MAX {
+        visited_rows[i] = true;
+        let mut index = usize::MAX;
+        let mut lowest = f64::INFINITY;
+        for (k, &j) in remaining.iter().enumerate() {
+            let reduced_cost = min_value + cost[[i, j]] - u[i] - v[j];
+            if reduced_cost < shortest_path_costs[j] {
+                path[j] = i;
+                shortest_path_costs[j] = reduced_cost;
+            }
+            if shortest_path_costs[j] < lowest
+                || (shortest_path_costs[j] == lowest && row4col[j] == usize::MAX)
+            {
+                lowest = shortest_path_costs[j];
+                index = k;
+            }
+        }
+
+        min_value = lowest;
+        if min_value == f64::INFINITY {
+            break;
+        }
+        let j = remaining.swap_remove(index);
+        visited_cols[j] = true;
+        match row4col[j] {
+            usize::MAX => sink = j,
+            assigned => i = assigned,
+        }
+    }
+    (
+        sink,
+        min_value,
+        path,
+        shortest_path_costs,
+        visited_rows,
+        visited_cols,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::linear_sum_assignment;
+
+    #[test]
+    fn test_linear_sum_assignment() {
+        let cost = array![[4_f32, 1., 3.], [2., 0., 5.], [3., 2., 2.]];
+        let (rows, cols) = linear_sum_assignment(&cost.view(), false).unwrap();
+        assert_eq!(rows.to_vec(), vec![0, 1, 2]);
+        assert_eq!(cols.to_vec(), vec![1, 0, 2]);
+
+        let (rows, cols) = linear_sum_assignment(&cost.view(), true).unwrap();
+        let total = rows
+            .iter()
+            .zip(&cols)
+            .map(|(i, j)| cost[[*i, *j]])
+            .sum::<f32>();
+        assert_eq!(total, 11.);
+
+        // Rectangular matrices assign every row of the smaller dimension.
+        let cost = array![[1_f32, 5.], [2., 1.], [0., 3.]];
+        let (rows, cols) = linear_sum_assignment(&cost.view(), false).unwrap();
+        assert_eq!(rows.to_vec(), vec![1, 2]);
+        assert_eq!(cols.to_vec(), vec![1, 0]);
+
+        let cost = array![[f32::INFINITY, 1.], [f32::INFINITY, 2.]];
+        assert!(linear_sum_assignment(&cost.view(), false).is_err());
+        let cost = array![[f32::NAN]];
+        assert!(linear_sum_assignment(&cost.view(), false).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! # parallel
+//!
+//! Thread pools of the crate's parallel routines.
+//!
+//! The polytope and aggregation routines run on the global `rayon` pool by default, which spawns
+//! a thread per core. Inside many worker processes (e.g., PyTorch data-loader workers) this
+//! oversubscribes the cores; `set_num_compute_threads` bounds them to a dedicated pool instead,
+//! and a single thread disables their parallelism. Pool threads are not pinned to cores.
+
+use std::{
+    process,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::ensure;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// Dedicated compute pool and the process which built it.
+struct _ComputePool {
+    num_threads: usize,
+    pid: u32,
+    pool: Arc<ThreadPool>,
+}
+
+impl _ComputePool {
+    fn new(num_threads: usize) -> anyhow::Result<Self> {
+        Ok(Self {
+            num_threads,
+            pid: process::id(),
+            pool: build_thread_pool(Some(num_threads), "av2-compute")?.unwrap(),
+        })
+    }
+}
+
+static COMPUTE_POOL: RwLock<Option<_ComputePool>> = RwLock::new(None);
+
+/// Run the compute routines on a dedicated pool of `num_threads` threads, or on the global pool
+/// if `None`. A single thread disables their parallelism.
+pub fn set_num_compute_threads(num_threads: Option<usize>) -> anyhow::Result<()> {
+    let pool = num_threads.map(_ComputePool::new).transpose()?;
+    *COMPUTE_POOL.write().unwrap() = pool;
+    Ok(())
+}
+
+/// Number of threads of the compute routines.
+pub fn num_compute_threads() -> usize {
+    _compute_pool().map_or_else(rayon::current_num_threads, |pool| {
+        pool.current_num_threads()
+    })
+}
+
+/// Run `op` (e.g., a parallel iteration over cuboids) on the compute pool.
+pub fn install_compute<OP, R>(op: OP) -> R
+where
+    OP: FnOnce() -> R + Send,
+    R: Send,
+{
+    match _compute_pool() {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Compute pool of the current process.
+///
+/// The threads of a pool do not survive a `fork`, so a forked process (e.g., a data-loader
+/// worker) rebuilds the pool of its parent on first use.
+fn _compute_pool() -> Option<Arc<ThreadPool>> {
+    {
+        let compute_pool = COMPUTE_POOL.read().unwrap();
+        match compute_pool.as_ref() {
+            None => return None,
+            Some(compute_pool) if compute_pool.pid == process::id() => {
+                return Some(compute_pool.pool.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    let mut compute_pool = COMPUTE_POOL.write().unwrap();
+    let compute_pool = compute_pool.as_mut()?;
+    if compute_pool.pid != process::id() {
+        let stale = std::mem::replace(
+            compute_pool,
+            _ComputePool::new(compute_pool.num_threads).unwrap(),
+        );
+        // Dropping the stale pool would signal threads which only exist in the parent.
+        std::mem::forget(stale);
+    }
+    Some(compute_pool.pool.clone())
+}
+
+/// Build a pool of `num_threads` threads named `name-{i}`, or `None` without a thread count.
+pub(crate) fn build_thread_pool(
+    num_threads: Option<usize>,
+    name: &'static str,
+) -> anyhow::Result<Option<Arc<ThreadPool>>> {
+    let Some(num_threads) = num_threads else {
+        return Ok(None);
+    };
+    ensure!(
+        num_threads > 0,
+        "The number of {name} threads must be positive."
+    );
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .thread_name(move |i| format!("{name}-{i}"))
+        .build()?;
+    Ok(Some(Arc::new(pool)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{install_compute, num_compute_threads, set_num_compute_threads};
+
+    #[test]
+    fn test_compute_pool() {
+        set_num_compute_threads(Some(2)).unwrap();
+        assert_eq!(num_compute_threads(), 2);
+        assert_eq!(install_compute(rayon::current_num_threads), 2);
+
+        set_num_compute_threads(Some(1)).unwrap();
+        assert_eq!(install_compute(rayon::current_num_threads), 1);
+        assert!(set_num_compute_threads(Some(0)).is_err());
+
+        set_num_compute_threads(None).unwrap();
+        assert_eq!(num_compute_threads(), rayon::current_num_threads());
+    }
+}
@@ -0,0 +1,130 @@
+//! # prefetch
+//!
+//! Background prefetching of indexed samples.
+//!
+//! Samples are produced ahead of iteration by worker threads into bounded channels, so the
+//! consumer (e.g., a Python training loop) overlaps with file reads and decompression. Worker `w`
+//! of `W` produces the samples `start + w`, `start + w + W`, ..., so samples are yielded in order
+//! without a reorder buffer.
+
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{sync_channel, Receiver},
+        Arc,
+    },
+    thread,
+};
+
+/// In-order iterator over samples produced by background threads.
+pub struct Prefetcher<T> {
+    receivers: Vec<Receiver<T>>,
+    start: usize,
+    end: usize,
+    next_index: usize,
+    is_stopped: Arc<AtomicBool>,
+}
+
+impl<T: Send + 'static> Prefetcher<T> {
+    /// Produce the samples of `indices` with `produce` on `num_threads` threads, keeping about
+    /// `num_prefetched` samples ahead of the consumer.
+    ///
+    /// Panics if `num_prefetched` or `num_threads` is zero.
+    pub fn new<F>(
+        produce: F,
+        indices: Range<usize>,
+        num_prefetched: usize,
+        num_threads: usize,
+    ) -> Self
+    where
+        F: Fn(usize) -> T + Send + Sync + 'static,
+    {
+        assert!(
+            num_prefetched > 0 && num_threads > 0,
+            "Prefetching requires at least one sample and one thread."
+        );
+        let produce = Arc::new(produce);
+        let is_stopped = Arc::new(AtomicBool::new(false));
+        let capacity = num_prefetched.div_ceil(num_threads);
+        let receivers = (0..num_threads)
+            .map(|worker| {
+                let (sender, receiver) = sync_channel(capacity);
+                let (produce, is_stopped) = (produce.clone(), is_stopped.clone());
+                let indices = indices.clone().skip(worker).step_by(num_threads);
+                thread::spawn(move || {
+                    for index in indices {
+                        // A send fails once the prefetcher is dropped.
+                        if is_stopped.load(Ordering::Relaxed)
+                            || sender.send(produce(index)).is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+                receiver
+            })
+            .collect();
+        Self {
+            receivers,
+            start: indices.start,
+            end: indices.end,
+            next_index: indices.start,
+            is_stopped,
+        }
+    }
+
+    /// Index of the next yielded sample.
+    pub fn next_index(&self) -> usize {
+        self.next_index
+    }
+}
+
+impl<T> Iterator for Prefetcher<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_index >= self.end {
+            return None;
+        }
+        let worker = (self.next_index - self.start) % self.receivers.len();
+        let sample = self.receivers[worker]
+            .recv()
+            .expect("A prefetch worker panicked.");
+        self.next_index += 1;
+        Some(sample)
+    }
+}
+
+impl<T> Drop for Prefetcher<T> {
+    fn drop(&mut self) {
+        self.is_stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::Prefetcher;
+
+    #[test]
+    fn test_prefetcher() {
+        // Uneven production times must not reorder the samples.
+        let produce = |i: usize| {
+            thread::sleep(Duration::from_millis((7 * i % 5) as u64));
+            i * i
+        };
+        let prefetcher = Prefetcher::new(produce, 3..20, 4, 3);
+        assert_eq!(prefetcher.next_index(), 3);
+        assert_eq!(
+            prefetcher.collect::<Vec<_>>(),
+            (3..20).map(|i| i * i).collect::<Vec<_>>()
+        );
+
+        // Dropping a partially consumed prefetcher stops its workers.
+        let mut prefetcher = Prefetcher::new(|i| i, 0..1_000_000, 2, 2);
+        assert_eq!(prefetcher.next(), Some(0));
+        assert_eq!(prefetcher.next_index(), 1);
+    }
+}
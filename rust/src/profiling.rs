@@ -0,0 +1,154 @@
+//! # profiling
+//!
+//! Per-stage timings of the loading pipeline.
+//!
+//! File reads, decompression, sweep aggregation, and augmentations are instrumented with `tracing`
+//! spans named after their stage (e.g., `read_file` or `aggregate_sweeps`), which any `tracing`
+//! subscriber receives. `StageTimingLayer` accumulates the busy time of each stage, and
+//! `enable_stage_timings` installs it as the global subscriber, so a slow pipeline can be
+//! attributed to I/O, decoding, or geometry without ad hoc timers.
+
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+/// Accumulated timing of a stage.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StageTiming {
+    /// Number of completed spans of the stage.
+    pub count: u64,
+    /// Total time spent within the spans (excluding time between their entries).
+    pub total: Duration,
+    /// Longest time spent within a single span.
+    pub max: Duration,
+}
+
+static STAGE_TIMINGS: Mutex<BTreeMap<&'static str, StageTiming>> = Mutex::new(BTreeMap::new());
+
+/// `tracing` layer which accumulates the busy time of the spans of each stage (i.e., span name).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StageTimingLayer;
+
+/// Busy time of an open span.
+struct _SpanTimer {
+    busy: Duration,
+    entered: Option<Instant>,
+}
+
+impl<S> Layer<S> for StageTimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(_SpanTimer {
+                busy: Duration::ZERO,
+                entered: None,
+            });
+        }
+    }
+
+    fn on_enter(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timer) = extensions.get_mut::<_SpanTimer>() {
+            timer.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timer) = extensions.get_mut::<_SpanTimer>() {
+            if let Some(entered) = timer.entered.take() {
+                timer.busy += entered.elapsed();
+            }
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        let Some(timer) = extensions.get::<_SpanTimer>() else {
+            return;
+        };
+        let busy = timer.busy;
+        let mut stage_timings = STAGE_TIMINGS.lock().unwrap();
+        let timing = stage_timings.entry(span.name()).or_default();
+        timing.count += 1;
+        timing.total += busy;
+        timing.max = timing.max.max(busy);
+    }
+}
+
+/// Install a global subscriber which accumulates the stage timings (see `stage_timings`).
+/// Installing it again is a no-op; installing it alongside another global subscriber fails, in
+/// which case `StageTimingLayer` can be added to the latter instead.
+pub fn enable_stage_timings() -> anyhow::Result<()> {
+    static ENABLED: Mutex<bool> = Mutex::new(false);
+    let mut enabled = ENABLED.lock().unwrap();
+    if !*enabled {
+        tracing::subscriber::set_global_default(
+            tracing_subscriber::registry().with(StageTimingLayer),
+        )?;
+        *enabled = true;
+    }
+    Ok(())
+}
+
+/// Timings of the stages completed since the last `reset_stage_timings`, keyed by stage.
+pub fn stage_timings() -> BTreeMap<String, StageTiming> {
+    STAGE_TIMINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(stage, timing)| (stage.to_string(), *timing))
+        .collect()
+}
+
+/// Clear the stage timings.
+pub fn reset_stage_timings() {
+    STAGE_TIMINGS.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use tracing_subscriber::prelude::*;
+
+    use super::{stage_timings, StageTimingLayer};
+
+    #[test]
+    fn test_stage_timings() {
+        let subscriber = tracing_subscriber::registry().with(StageTimingLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("test_stage");
+            for _ in 0..2 {
+                let _entered = span.enter();
+                thread::sleep(Duration::from_millis(5));
+            }
+            // Time between the entries is not counted.
+            thread::sleep(Duration::from_millis(50));
+            drop(span);
+            tracing::info_span!("test_stage").in_scope(|| {});
+        });
+
+        let timing = stage_timings()["test_stage"];
+        assert_eq!(timing.count, 2);
+        assert!(timing.total >= Duration::from_millis(10));
+        assert!(timing.total < Duration::from_millis(50));
+        assert!(timing.max >= Duration::from_millis(10) && timing.max <= timing.total);
+    }
+}
@@ -0,0 +1,730 @@
+//! # python
+//!
+//! Python bindings of the crate (the `av2._r` module).
+
+use crate::data_loader::{
+    DataLoader, LogSweepIterator, ModalityAvailability, Sweep, SweepPair, SynchronizedImage,
+};
+use ndarray::{Ix1, Ix2, Ix3, Ix4};
+use numpy::PyReadonlyArray;
+use numpy::{IntoPyArray, PyArray};
+use pyo3::prelude::*;
+
+use crate::evaluation::detection::{evaluate, Affinity, DetectionConfig};
+use crate::evaluation::forecasting::compute_forecasting_metrics;
+use crate::evaluation::scene_flow::compute_metrics as compute_scene_flow_metrics;
+use crate::evaluation::tracking::TrackingConfig;
+use crate::geometry::augmentations::{AppliedAugmentation, AugmentationScratch};
+use crate::geometry::bvh::CuboidBvh;
+use crate::geometry::interpolate::interpolate_cuboids;
+use crate::geometry::iou::IouMode;
+use crate::geometry::kdtree::KdTree;
+use crate::geometry::nms::non_maximum_suppression;
+use crate::geometry::polyline_index::PolylineIndex;
+use crate::geometry::polytope::{
+    compute_interior_points_assignment, cuboids_to_polygons, fit_oriented_cuboids, AssignmentPolicy,
+};
+use crate::geometry::range_image::{sweep_to_range_image, RangeImageConfig};
+use crate::geometry::raster::{rasterize_bev, BevRasterConfig};
+use crate::geometry::so3::{
+    _quat_to_mat3, euler_to_quat, mat3_to_quat, quat_to_euler, quat_to_yaw, yaw_to_quat,
+};
+use crate::integrity::{self, read_file_sizes, verify_split};
+use crate::io::capsule::ArrowTable;
+use crate::io::dlpack::{DLPackElement, DLPackTensor};
+use crate::io::packed::PackedSample;
+use crate::io::stream::RecordBatchStream;
+use crate::manifest::write_dataset_manifest;
+use crate::map::static_map::GroundHeightRaster;
+use crate::motion_forecasting::{ScenarioBatch, ScenarioLoader};
+use numpy::PyReadonlyArray2;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3_polars::PyDataFrame;
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+use crate::{evaluation, ops::voxelize, parallel, profiling};
+
+/// Voxelize `features` at their `indices`, returning the voxel indices, mean features, and point
+/// counts as NumPy arrays, or as DLPack tensors if `dlpack` is set.
+#[pyfunction]
+#[pyo3(
+    name = "voxelize",
+    signature = (indices, features, length, width, height, dlpack = false)
+)]
+fn py_voxelize(
+    py: Python<'_>,
+    indices: PyReadonlyArray2<usize>,
+    features: PyReadonlyArray2<f32>,
+    length: usize,
+    width: usize,
+    height: usize,
+    dlpack: bool,
+) -> PyResult<(PyObject, PyObject, PyObject)> {
+    let (indices, values, counts) = voxelize(
+        &indices.as_array(),
+        &features.as_array(),
+        length,
+        width,
+        height,
+    );
+    Ok((
+        _export_array(py, indices, dlpack)?,
+        _export_array(py, values, dlpack)?,
+        _export_array(py, counts, dlpack)?,
+    ))
+}
+
+/// Export `array` as a NumPy array, or as a DLPack tensor if `dlpack` is set.
+fn _export_array<T: numpy::Element + DLPackElement, D: ndarray::Dimension + 'static>(
+    py: Python<'_>,
+    array: ndarray::Array<T, D>,
+    dlpack: bool,
+) -> PyResult<PyObject> {
+    match dlpack {
+        true => Ok(Py::new(py, DLPackTensor::from(array))?.into_py(py)),
+        false => Ok(array.into_pyarray(py).into_py(py)),
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "quat_to_mat3")]
+#[allow(clippy::type_complexity)]
+fn py_quat_to_mat3<'py>(
+    py: Python<'py>,
+    quat_wxyz: PyReadonlyArray<f32, Ix1>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("quat_wxyz", quat_wxyz.shape(), &[4])?;
+    Ok(_quat_to_mat3(&quat_wxyz.as_array().view()).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "quat_to_yaw")]
+#[allow(clippy::type_complexity)]
+fn py_quat_to_yaw<'py>(
+    py: Python<'py>,
+    quat_wxyz: PyReadonlyArray<f32, Ix2>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("quat_wxyz", quat_wxyz.shape(), &[4])?;
+    Ok(quat_to_yaw(&quat_wxyz.as_array().view()).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "yaw_to_quat")]
+#[allow(clippy::type_complexity)]
+fn py_yaw_to_quat<'py>(
+    py: Python<'py>,
+    yaw_rad: PyReadonlyArray<f32, Ix2>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("yaw_rad", yaw_rad.shape(), &[1])?;
+    Ok(yaw_to_quat(&yaw_rad.as_array().view()).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "mat3_to_quat")]
+#[allow(clippy::type_complexity)]
+fn py_mat3_to_quat<'py>(
+    py: Python<'py>,
+    mat3: PyReadonlyArray<f32, Ix3>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("mat3", mat3.shape(), &[3, 3])?;
+    Ok(mat3_to_quat(&mat3.as_array().view()).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "quat_to_euler")]
+#[allow(clippy::type_complexity)]
+fn py_quat_to_euler<'py>(
+    py: Python<'py>,
+    quat_wxyz: PyReadonlyArray<f32, Ix2>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("quat_wxyz", quat_wxyz.shape(), &[4])?;
+    Ok(quat_to_euler(&quat_wxyz.as_array().view()).into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "euler_to_quat")]
+#[allow(clippy::type_complexity)]
+fn py_euler_to_quat<'py>(
+    py: Python<'py>,
+    euler_rad: PyReadonlyArray<f32, Ix2>,
+) -> PyResult<&'py PyArray<f32, Ix2>> {
+    _check_shape("euler_rad", euler_rad.shape(), &[3])?;
+    Ok(euler_to_quat(&euler_rad.as_array().view()).into_pyarray(py))
+}
+
+/// Check that the trailing dimensions of the `name` array's `shape` are `trailing_shape`, i.e.,
+/// that a batch of rows is (N,...) or a single row is exactly `trailing_shape`.
+fn _check_shape(name: &str, shape: &[usize], trailing_shape: &[usize]) -> PyResult<()> {
+    match shape.ends_with(trailing_shape) {
+        true => Ok(()),
+        false => {
+            let trailing_shape = trailing_shape
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>();
+            let expected = match shape.len() > trailing_shape.len() {
+                true => format!("(N,{})", trailing_shape.join(",")),
+                false => format!("({})", trailing_shape.join(",")),
+            };
+            Err(PyValueError::new_err(format!(
+                "Expected `{name}` of shape {expected}, got {shape:?}."
+            )))
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "nms")]
+#[allow(clippy::type_complexity)]
+fn py_nms<'py>(
+    py: Python<'py>,
+    cuboids: PyReadonlyArray2<f32>,
+    scores: PyReadonlyArray<f32, Ix1>,
+    iou_threshold: f32,
+    mode: &str,
+) -> PyResult<&'py PyArray<usize, Ix1>> {
+    let mode = IouMode::from_str(mode)
+        .map_err(|_| PyValueError::new_err(format!("Unknown IoU mode `{mode}`.")))?;
+    Ok(
+        non_maximum_suppression(&cuboids.as_array(), &scores.as_array(), iou_threshold, mode)?
+            .into_pyarray(py),
+    )
+}
+
+#[pyfunction]
+#[pyo3(name = "fit_oriented_cuboids")]
+fn py_fit_oriented_cuboids<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f32>,
+    cluster_ids: PyReadonlyArray<i64, Ix1>,
+) -> (&'py PyArray<i64, Ix1>, &'py PyArray<f32, Ix2>) {
+    let (ids, cuboids) = fit_oriented_cuboids(&points.as_array(), &cluster_ids.as_array());
+    (ids.into_pyarray(py), cuboids.into_pyarray(py))
+}
+
+#[pyfunction]
+#[pyo3(name = "interior_points_assignment")]
+fn py_interior_points_assignment<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f32>,
+    cuboids: PyReadonlyArray2<f32>,
+    policy: &str,
+) -> PyResult<&'py PyArray<i64, Ix1>> {
+    let policy = AssignmentPolicy::from_str(policy)
+        .map_err(|_| PyValueError::new_err(format!("Unknown assignment policy `{policy}`.")))?;
+    let vertices = cuboids_to_polygons(&cuboids.as_array());
+    Ok(
+        compute_interior_points_assignment(&points.as_array(), &vertices.view(), policy)
+            .into_pyarray(py),
+    )
+}
+
+#[pyfunction]
+#[pyo3(
+    name = "rasterize_bev",
+    signature = (
+        points,
+        resolution_m,
+        min_range_m,
+        max_range_m,
+        min_height_m,
+        max_height_m,
+        cuboids = None,
+        dlpack = false,
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+fn py_rasterize_bev<'py>(
+    py: Python<'py>,
+    points: PyReadonlyArray2<f32>,
+    resolution_m: f32,
+    min_range_m: [f32; 2],
+    max_range_m: [f32; 2],
+    min_height_m: f32,
+    max_height_m: f32,
+    cuboids: Option<PyReadonlyArray2<f32>>,
+    dlpack: bool,
+) -> PyResult<PyObject> {
+    if points.shape()[1] != 4 {
+        return Err(PyValueError::new_err(format!(
+            "Expected (N,4) points, got {:?}.",
+            points.shape()
+        )));
+    }
+    if resolution_m <= 0. {
+        return Err(PyValueError::new_err("Resolution must be positive."));
+    }
+    let cfg = BevRasterConfig {
+        resolution_m,
+        min_range_m,
+        max_range_m,
+        min_height_m,
+        max_height_m,
+    };
+    let cuboids = cuboids.as_ref().map(|cuboids| cuboids.as_array());
+    let raster = rasterize_bev(&points.as_array(), cuboids.as_ref(), None, &cfg);
+    _export_array(py, raster, dlpack)
+}
+
+/// Evaluate 3D object `detections` against `annotations` (see `evaluation::detection`).
+/// Returns the evaluated detections, the evaluated annotations, the summary metrics, and their
+/// breakdown by split, category, and range.
+#[pyfunction]
+#[pyo3(
+    name = "evaluate_detections",
+    signature = (
+        detections,
+        annotations,
+        categories = None,
+        affinity = "center",
+        affinity_thresholds = None,
+        tp_threshold = None,
+        max_range_m = None,
+        max_num_dts_per_category = None,
+        range_buckets_m = None,
+    )
+)]
+#[allow(clippy::too_many_arguments)]
+fn py_evaluate_detections(
+    py: Python<'_>,
+    detections: PyDataFrame,
+    annotations: PyDataFrame,
+    categories: Option<Vec<String>>,
+    affinity: &str,
+    affinity_thresholds: Option<Vec<f32>>,
+    tp_threshold: Option<f32>,
+    max_range_m: Option<f32>,
+    max_num_dts_per_category: Option<usize>,
+    range_buckets_m: Option<Vec<f32>>,
+) -> PyResult<(PyDataFrame, PyDataFrame, PyDataFrame, PyDataFrame)> {
+    let default = DetectionConfig::default();
+    let affinity = Affinity::from_str(affinity)
+        .map_err(|_| PyValueError::new_err(format!("Unknown affinity `{affinity}`.")))?;
+    let tp_threshold = tp_threshold.unwrap_or(default.tp_threshold);
+    let cfg = DetectionConfig {
+        affinity,
+        affinity_thresholds: affinity_thresholds.unwrap_or(default.affinity_thresholds),
+        tp_threshold,
+        max_translation_error_m: match affinity {
+            Affinity::Center => tp_threshold,
+            _ => default.max_translation_error_m,
+        },
+        categories: categories.unwrap_or(default.categories),
+        max_num_dts_per_category: max_num_dts_per_category
+            .unwrap_or(default.max_num_dts_per_category),
+        max_range_m: max_range_m.unwrap_or(default.max_range_m),
+        num_recall_samples: default.num_recall_samples,
+        range_buckets_m: range_buckets_m.unwrap_or(default.range_buckets_m),
+    };
+    let evaluation = py
+        .allow_threads(|| evaluate(&detections.0, &annotations.0, &cfg))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok((
+        PyDataFrame(evaluation.detections),
+        PyDataFrame(evaluation.annotations),
+        PyDataFrame(evaluation.summary),
+        PyDataFrame(evaluation.breakdown),
+    ))
+}
+
+/// Evaluate multi-object `tracks` against `annotations` (see `evaluation::tracking`).
+/// Returns the metrics of each log and category, the summary metrics, and their breakdown by
+/// split, category, and range.
+#[pyfunction]
+#[pyo3(
+    name = "evaluate_tracking",
+    signature = (
+        tracks,
+        annotations,
+        categories = None,
+        zero_distance_m = 2.0,
+        similarity_threshold = 0.5,
+        range_buckets_m = None,
+    )
+)]
+fn py_evaluate_tracking(
+    py: Python<'_>,
+    tracks: PyDataFrame,
+    annotations: PyDataFrame,
+    categories: Option<Vec<String>>,
+    zero_distance_m: f32,
+    similarity_threshold: f32,
+    range_buckets_m: Option<Vec<f32>>,
+) -> PyResult<(PyDataFrame, PyDataFrame, PyDataFrame)> {
+    let default = TrackingConfig::default();
+    let cfg = TrackingConfig {
+        categories: categories.unwrap_or(default.categories),
+        zero_distance_m,
+        similarity_threshold,
+        range_buckets_m: range_buckets_m.unwrap_or(default.range_buckets_m),
+    };
+    let evaluation = py
+        .allow_threads(|| evaluation::tracking::evaluate(&tracks.0, &annotations.0, &cfg))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok((
+        PyDataFrame(evaluation.logs),
+        PyDataFrame(evaluation.summary),
+        PyDataFrame(evaluation.breakdown),
+    ))
+}
+
+/// Compute the minADE, minFDE, miss flags, and brier-minFDE of (B,K,N,2) forecasts with (B,K)
+/// probabilities against (B,N,2) ground truth trajectories (see `evaluation::forecasting`).
+#[pyfunction]
+#[pyo3(
+    name = "compute_forecasting_metrics",
+    signature = (forecasts, gts, probabilities, miss_threshold_m = 2.0, normalize = false)
+)]
+#[allow(clippy::type_complexity)]
+fn py_compute_forecasting_metrics<'py>(
+    py: Python<'py>,
+    forecasts: PyReadonlyArray<f32, Ix4>,
+    gts: PyReadonlyArray<f32, Ix3>,
+    probabilities: PyReadonlyArray<f32, Ix2>,
+    miss_threshold_m: f32,
+    normalize: bool,
+) -> PyResult<(
+    &'py PyArray<f32, Ix1>,
+    &'py PyArray<f32, Ix1>,
+    &'py PyArray<bool, Ix1>,
+    &'py PyArray<f32, Ix1>,
+)> {
+    let metrics = compute_forecasting_metrics(
+        &forecasts.as_array(),
+        &gts.as_array(),
+        &probabilities.as_array(),
+        miss_threshold_m,
+        normalize,
+    )
+    .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok((
+        metrics.min_ade.into_pyarray(py),
+        metrics.min_fde.into_pyarray(py),
+        metrics.is_missed.into_pyarray(py),
+        metrics.brier_min_fde.into_pyarray(py),
+    ))
+}
+
+/// Compute the per-subset scene flow metrics of (N,3) predicted flows and (N,) dynamic predictions
+/// against (N,3) ground truth flows (see `evaluation::scene_flow`).
+#[pyfunction]
+#[pyo3(name = "compute_scene_flow_metrics")]
+#[allow(clippy::too_many_arguments)]
+fn py_compute_scene_flow_metrics(
+    pred_flow: PyReadonlyArray<f32, Ix2>,
+    pred_dynamic: PyReadonlyArray<bool, Ix1>,
+    gts: PyReadonlyArray<f32, Ix2>,
+    category_indices: PyReadonlyArray<u8, Ix1>,
+    is_dynamic: PyReadonlyArray<bool, Ix1>,
+    is_close: PyReadonlyArray<bool, Ix1>,
+    is_valid: PyReadonlyArray<bool, Ix1>,
+) -> PyResult<PyDataFrame> {
+    compute_scene_flow_metrics(
+        &pred_flow.as_array(),
+        &pred_dynamic.as_array(),
+        &gts.as_array(),
+        &category_indices.as_array(),
+        &is_dynamic.as_array(),
+        &is_close.as_array(),
+        &is_valid.as_array(),
+    )
+    .map(PyDataFrame)
+    .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Interpolate the cuboids of every track in `annotations` at `query_timestamp_ns`.
+#[pyfunction]
+#[pyo3(name = "interpolate_cuboids")]
+fn py_interpolate_cuboids(
+    annotations: PyDataFrame,
+    query_timestamp_ns: u64,
+) -> PyResult<PyDataFrame> {
+    interpolate_cuboids(&annotations.0, query_timestamp_ns)
+        .map(PyDataFrame)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Reflect `lidar` and `cuboids` across the x-axis with probability `p`.
+#[pyfunction]
+#[pyo3(name = "sample_scene_reflection_x", signature = (lidar, cuboids, p = 0.5))]
+fn py_sample_scene_reflection_x(
+    lidar: PyDataFrame,
+    cuboids: PyDataFrame,
+    p: f64,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    _check_probability(p)?;
+    _apply_augmentation(
+        &AppliedAugmentation::sample_scene_reflection_x(p),
+        lidar,
+        cuboids,
+    )
+}
+
+/// Reflect `lidar` and `cuboids` across the y-axis with probability `p`.
+#[pyfunction]
+#[pyo3(name = "sample_scene_reflection_y", signature = (lidar, cuboids, p = 0.5))]
+fn py_sample_scene_reflection_y(
+    lidar: PyDataFrame,
+    cuboids: PyDataFrame,
+    p: f64,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    _check_probability(p)?;
+    _apply_augmentation(
+        &AppliedAugmentation::sample_scene_reflection_y(p),
+        lidar,
+        cuboids,
+    )
+}
+
+/// Scale `lidar` and `cuboids` by a factor sampled uniformly from `[low_inclusive, upper_inclusive]`.
+#[pyfunction]
+#[pyo3(name = "sample_scene_global_scale")]
+fn py_sample_scene_global_scale(
+    lidar: PyDataFrame,
+    cuboids: PyDataFrame,
+    low_inclusive: f64,
+    upper_inclusive: f64,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    _check_range(low_inclusive, upper_inclusive)?;
+    _apply_augmentation(
+        &AppliedAugmentation::sample_scene_global_scale(low_inclusive, upper_inclusive),
+        lidar,
+        cuboids,
+    )
+}
+
+/// Rotate `lidar` and `cuboids` about the z-axis by a fraction of a turn sampled uniformly from
+/// `[low_inclusive, upper_inclusive]`.
+#[pyfunction]
+#[pyo3(name = "sample_scene_global_rotation")]
+fn py_sample_scene_global_rotation(
+    lidar: PyDataFrame,
+    cuboids: PyDataFrame,
+    low_inclusive: f64,
+    upper_inclusive: f64,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    _check_range(low_inclusive, upper_inclusive)?;
+    _apply_augmentation(
+        &AppliedAugmentation::sample_scene_global_rotation(low_inclusive, upper_inclusive),
+        lidar,
+        cuboids,
+    )
+}
+
+/// Scale each cuboid and its interior points by a factor sampled uniformly from
+/// `[low_inclusive, high_inclusive]`.
+#[pyfunction]
+#[pyo3(name = "sample_random_object_scale")]
+fn py_sample_random_object_scale(
+    lidar: PyDataFrame,
+    cuboids: PyDataFrame,
+    low_inclusive: f64,
+    high_inclusive: f64,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    _check_range(low_inclusive, high_inclusive)?;
+    let augmentation = AppliedAugmentation::sample_random_object_scale(
+        cuboids.0.height(),
+        low_inclusive,
+        high_inclusive,
+    );
+    _apply_augmentation(&augmentation, lidar, cuboids)
+}
+
+/// Apply `augmentation` to `lidar` and `cuboids`, raising on malformed frames.
+fn _apply_augmentation(
+    augmentation: &AppliedAugmentation,
+    PyDataFrame(mut lidar): PyDataFrame,
+    PyDataFrame(mut cuboids): PyDataFrame,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    augmentation.apply_in_place(
+        &mut lidar,
+        &mut cuboids,
+        &mut AugmentationScratch::default(),
+    )?;
+    Ok((PyDataFrame(lidar), PyDataFrame(cuboids)))
+}
+
+/// Raise a `ValueError` unless `p` is a probability.
+fn _check_probability(p: f64) -> PyResult<()> {
+    match (0. ..=1.).contains(&p) {
+        true => Ok(()),
+        false => Err(PyValueError::new_err(format!(
+            "The probability {p} is outside [0, 1]."
+        ))),
+    }
+}
+
+/// Raise a `ValueError` unless `[low, high]` is a finite, non-empty range.
+fn _check_range(low: f64, high: f64) -> PyResult<()> {
+    match low.is_finite() && high.is_finite() && low <= high {
+        true => Ok(()),
+        false => Err(PyValueError::new_err(format!(
+            "The range [{low}, {high}] is empty or not finite."
+        ))),
+    }
+}
+
+/// Project a `lidar` sweep into a range image, returning the (C,H,W) image and the (H,W) row of
+/// the sweep in each pixel (see `geometry::range_image`) as NumPy arrays, or as DLPack tensors if
+/// `dlpack` is set.
+#[pyfunction]
+#[pyo3(
+    name = "sweep_to_range_image",
+    signature = (lidar, num_beams = 64, num_azimuth_bins = 1800, dlpack = false)
+)]
+fn py_sweep_to_range_image(
+    py: Python<'_>,
+    lidar: PyDataFrame,
+    num_beams: usize,
+    num_azimuth_bins: usize,
+    dlpack: bool,
+) -> PyResult<(PyObject, PyObject)> {
+    let cfg = RangeImageConfig {
+        num_beams,
+        num_azimuth_bins,
+    };
+    let range_image = sweep_to_range_image(&lidar.0, &cfg)?;
+    Ok((
+        _export_array(py, range_image.image, dlpack)?,
+        _export_array(py, range_image.indices, dlpack)?,
+    ))
+}
+
+/// Scan `split_dir` once and write its dataset manifest to a parquet file at `path`.
+#[pyfunction]
+#[pyo3(name = "write_dataset_manifest")]
+fn py_write_dataset_manifest(split_dir: &str, path: &str) -> PyResult<PyDataFrame> {
+    write_dataset_manifest(Path::new(split_dir), Path::new(path))
+        .map(PyDataFrame)
+        .map_err(|err| PyIOError::new_err(err.to_string()))
+}
+
+/// Verify the logs of `split_dir` against their recorded file sizes (by default, the manifest
+/// written next to the split directory by the downloader). Returns the `(relative_path, problem)`
+/// pairs of each log with problems; a missing log has a single `(log_id, "missing")` pair.
+#[pyfunction]
+#[pyo3(
+    name = "verify_split",
+    signature = (split_dir, file_sizes_path = None, validate_formats = false)
+)]
+fn py_verify_split(
+    split_dir: &str,
+    file_sizes_path: Option<&str>,
+    validate_formats: bool,
+) -> PyResult<BTreeMap<String, Vec<(String, String)>>> {
+    let split_dir = Path::new(split_dir);
+    let path = file_sizes_path.map_or_else(|| integrity::file_sizes_path(split_dir), Into::into);
+    let report = read_file_sizes(&path)
+        .and_then(|file_sizes| verify_split(split_dir, &file_sizes, validate_formats))
+        .map_err(|err| PyIOError::new_err(err.to_string()))?;
+    let mut problems = BTreeMap::new();
+    for log_id in &report.missing_logs {
+        problems.insert(
+            log_id.clone(),
+            vec![(log_id.clone(), "missing".to_string())],
+        );
+    }
+    for (log_id, issues) in report.issues_by_log() {
+        let issues = issues
+            .into_iter()
+            .map(|x| (x.path.display().to_string(), x.kind.to_string()))
+            .collect();
+        problems.insert(log_id.to_string(), issues);
+    }
+    Ok(problems)
+}
+
+/// Run the polytope and aggregation routines on a pool of `num_threads` threads, or on the global
+/// pool if `None`. A single thread disables their parallelism (e.g., in data-loader workers).
+#[pyfunction]
+#[pyo3(name = "set_num_compute_threads", signature = (num_threads = None))]
+fn py_set_num_compute_threads(num_threads: Option<usize>) -> PyResult<()> {
+    parallel::set_num_compute_threads(num_threads)
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Number of threads of the polytope and aggregation routines.
+#[pyfunction]
+#[pyo3(name = "num_compute_threads")]
+fn py_num_compute_threads() -> usize {
+    parallel::num_compute_threads()
+}
+
+/// Accumulate the timings of the loading stages (e.g., `read_file`, `decode_feather`,
+/// `aggregate_sweeps`, or `augment`) from now on.
+#[pyfunction]
+#[pyo3(name = "enable_stage_timings")]
+fn py_enable_stage_timings() -> PyResult<()> {
+    profiling::enable_stage_timings().map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Number of spans, total seconds, and maximum seconds of each loading stage.
+#[pyfunction]
+#[pyo3(name = "stage_timings")]
+fn py_stage_timings() -> BTreeMap<String, (u64, f64, f64)> {
+    profiling::stage_timings()
+        .into_iter()
+        .map(|(stage, timing)| {
+            let (total, max) = (timing.total.as_secs_f64(), timing.max.as_secs_f64());
+            (stage, (timing.count, total, max))
+        })
+        .collect()
+}
+
+/// Clear the timings of the loading stages.
+#[pyfunction]
+#[pyo3(name = "reset_stage_timings")]
+fn py_reset_stage_timings() {
+    profiling::reset_stage_timings()
+}
+
+/// A Python module implemented in Rust.
+#[pymodule]
+fn _r(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<ArrowTable>()?;
+    m.add_class::<CuboidBvh>()?;
+    m.add_class::<DataLoader>()?;
+    m.add_class::<DLPackTensor>()?;
+    m.add_class::<GroundHeightRaster>()?;
+    m.add_class::<KdTree>()?;
+    m.add_class::<LogSweepIterator>()?;
+    m.add_class::<ModalityAvailability>()?;
+    m.add_class::<PackedSample>()?;
+    m.add_class::<PolylineIndex>()?;
+    m.add_class::<RecordBatchStream>()?;
+    m.add_class::<ScenarioBatch>()?;
+    m.add_class::<ScenarioLoader>()?;
+    m.add_class::<Sweep>()?;
+    m.add_class::<SweepPair>()?;
+    m.add_class::<SynchronizedImage>()?;
+    m.add_function(wrap_pyfunction!(py_compute_forecasting_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_compute_scene_flow_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(py_enable_stage_timings, m)?)?;
+    m.add_function(wrap_pyfunction!(py_euler_to_quat, m)?)?;
+    m.add_function(wrap_pyfunction!(py_evaluate_detections, m)?)?;
+    m.add_function(wrap_pyfunction!(py_evaluate_tracking, m)?)?;
+    m.add_function(wrap_pyfunction!(py_fit_oriented_cuboids, m)?)?;
+    m.add_function(wrap_pyfunction!(py_interior_points_assignment, m)?)?;
+    m.add_function(wrap_pyfunction!(py_interpolate_cuboids, m)?)?;
+    m.add_function(wrap_pyfunction!(py_mat3_to_quat, m)?)?;
+    m.add_function(wrap_pyfunction!(py_nms, m)?)?;
+    m.add_function(wrap_pyfunction!(py_num_compute_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quat_to_euler, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quat_to_mat3, m)?)?;
+    m.add_function(wrap_pyfunction!(py_quat_to_yaw, m)?)?;
+    m.add_function(wrap_pyfunction!(py_rasterize_bev, m)?)?;
+    m.add_function(wrap_pyfunction!(py_reset_stage_timings, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sample_random_object_scale, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sample_scene_global_rotation, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sample_scene_global_scale, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sample_scene_reflection_x, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sample_scene_reflection_y, m)?)?;
+    m.add_function(wrap_pyfunction!(py_set_num_compute_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(py_stage_timings, m)?)?;
+    m.add_function(wrap_pyfunction!(py_sweep_to_range_image, m)?)?;
+    m.add_function(wrap_pyfunction!(py_verify_split, m)?)?;
+    m.add_function(wrap_pyfunction!(py_voxelize, m)?)?;
+    m.add_function(wrap_pyfunction!(py_write_dataset_manifest, m)?)?;
+    m.add_function(wrap_pyfunction!(py_yaw_to_quat, m)?)?;
+    Ok(())
+}
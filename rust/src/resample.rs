@@ -0,0 +1,193 @@
+//! # resample
+//!
+//! Frame-rate resampling of sensor logs.
+//!
+//! Lidar sweeps are selected on a regular grid at the target frame rate. Camera images and
+//! annotations are then selected to match the kept sweeps, so the reduced log remains consistent.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use polars::prelude::{BooleanChunked, DataType, NewChunkedArray};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use strum::IntoEnumIterator;
+
+use crate::{
+    constants::CameraNames,
+    data_loader::MAX_CAM_LIDAR_TOL_NS,
+    io::{read_feather, write_feather_eager},
+    path::{extract_file_stem, walk_dir},
+};
+
+/// Select the timestamps closest to a regular grid at `target_hz`, anchored at the first timestamp.
+/// Returns sorted, unique timestamps.
+pub fn select_resampled_timestamps(timestamps_ns: &[u64], target_hz: f32) -> Vec<u64> {
+    if target_hz <= 0. {
+        panic!("Target frame rate must be positive.");
+    }
+    let mut timestamps_ns = timestamps_ns.to_vec();
+    timestamps_ns.sort();
+    let (Some(first), Some(last)) = (timestamps_ns.first(), timestamps_ns.last()) else {
+        return vec![];
+    };
+
+    let period_ns = 1e9 / target_hz as f64;
+    let num_samples = ((last - first) as f64 / period_ns).floor() as u64 + 1;
+    let mut selected = (0..num_samples)
+        .filter_map(|k| {
+            let target_ns = first + (k as f64 * period_ns).round() as u64;
+            _nearest(&timestamps_ns, target_ns)
+        })
+        .collect::<Vec<_>>();
+    selected.dedup();
+    selected
+}
+
+/// Resample the log at `src_log_dir` to `target_hz` and write the reduced log to `dst_log_dir`.
+///
+/// Kept sweeps are copied along with the nearest image of each camera (within the data-loader's
+/// camera-lidar tolerance) and their annotations. Poses, calibration, and maps are copied as-is
+/// so that the reduced log can still be motion-compensated. Returns the kept sweep timestamps.
+pub fn resample_log(src_log_dir: &Path, dst_log_dir: &Path, target_hz: f32) -> Result<Vec<u64>> {
+    let lidar_dir = src_log_dir.join("sensors/lidar");
+    let lidar_timestamps_ns = _read_timestamps(&lidar_dir)?;
+    let selected = select_resampled_timestamps(&lidar_timestamps_ns, target_hz);
+
+    let mut copies = selected
+        .iter()
+        .map(|timestamp_ns| {
+            let suffix = format!("sensors/lidar/{timestamp_ns}.feather");
+            (src_log_dir.join(&suffix), dst_log_dir.join(suffix))
+        })
+        .collect::<Vec<_>>();
+    for camera_name in CameraNames::iter().map(|x| x.to_string()) {
+        let camera_dir = src_log_dir.join(format!("sensors/cameras/{camera_name}"));
+        if !camera_dir.exists() {
+            continue;
+        }
+        let mut camera_timestamps_ns = _read_timestamps(&camera_dir)?;
+        camera_timestamps_ns.sort();
+        for timestamp_ns in selected.iter() {
+            let Some(camera_timestamp_ns) = _nearest(&camera_timestamps_ns, *timestamp_ns) else {
+                continue;
+            };
+            if camera_timestamp_ns.abs_diff(*timestamp_ns) as f32 <= MAX_CAM_LIDAR_TOL_NS {
+                let suffix = format!("sensors/cameras/{camera_name}/{camera_timestamp_ns}.jpg");
+                copies.push((src_log_dir.join(&suffix), dst_log_dir.join(suffix)));
+            }
+        }
+    }
+    copies
+        .par_iter()
+        .map(|(src, dst)| _copy_file(src, dst))
+        .collect::<Result<Vec<_>>>()?;
+
+    for entry in walk_dir(&src_log_dir.to_path_buf())? {
+        let name = entry.file_name().context("Invalid log entry.")?;
+        let dst = dst_log_dir.join(name);
+        if name == "sensors" {
+            continue;
+        } else if name == "annotations.feather" {
+            _write_selected_annotations(&entry, &dst, &selected)?;
+        } else if entry.is_dir() {
+            _copy_dir(&entry, &dst)?;
+        } else {
+            _copy_file(&entry, &dst)?;
+        }
+    }
+    Ok(selected)
+}
+
+/// Nearest value to `query` in sorted `values`.
+fn _nearest(values: &[u64], query: u64) -> Option<u64> {
+    let j = values.partition_point(|&x| x < query);
+    let candidates = [j.checked_sub(1), Some(j)];
+    candidates
+        .into_iter()
+        .flatten()
+        .filter_map(|i| values.get(i).copied())
+        .min_by_key(|x| x.abs_diff(query))
+}
+
+/// Read the timestamps encoded in the file names of a sensor directory.
+fn _read_timestamps(sensor_dir: &PathBuf) -> Result<Vec<u64>> {
+    walk_dir(sensor_dir)?
+        .iter()
+        .map(|path| Ok(extract_file_stem(path)?.parse::<u64>()?))
+        .collect()
+}
+
+fn _write_selected_annotations(src: &Path, dst: &Path, timestamps_ns: &[u64]) -> Result<()> {
+    let timestamps_ns = timestamps_ns.iter().collect::<HashSet<_>>();
+    let annotations = read_feather(src, false)?;
+    let annotation_timestamps_ns = annotations["timestamp_ns"].cast(&DataType::UInt64)?;
+    let mask = BooleanChunked::from_iter_values(
+        "mask",
+        annotation_timestamps_ns
+            .u64()?
+            .into_no_null_iter()
+            .map(|timestamp_ns| timestamps_ns.contains(&timestamp_ns)),
+    );
+    fs::create_dir_all(dst.parent().context("Invalid destination.")?)?;
+    write_feather_eager(dst, annotations.filter(&mask)?)?;
+    Ok(())
+}
+
+fn _copy_file(src: &Path, dst: &Path) -> Result<()> {
+    fs::create_dir_all(dst.parent().context("Invalid destination.")?)?;
+    fs::copy(src, dst).with_context(|| format!("Cannot copy {}.", src.display()))?;
+    Ok(())
+}
+
+fn _copy_dir(src: &PathBuf, dst: &Path) -> Result<()> {
+    for entry in walk_dir(src)? {
+        let dst = dst.join(entry.file_name().context("Invalid entry.")?);
+        match entry.is_dir() {
+            true => _copy_dir(&entry, &dst)?,
+            false => _copy_file(&entry, &dst)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_resampled_timestamps;
+
+    #[test]
+    fn test_select_resampled_timestamps() {
+        // 10 Hz with jitter and a dropped sweep.
+        let timestamps_ns = [
+            0,
+            100_000_000,
+            199_000_000,
+            301_000_000,
+            400_000_000,
+            500_000_000,
+            600_000_000,
+            702_000_000,
+            900_000_000,
+            1_000_000_000,
+        ];
+        assert_eq!(
+            select_resampled_timestamps(&timestamps_ns, 2.),
+            vec![0, 500_000_000, 1_000_000_000]
+        );
+        assert_eq!(
+            select_resampled_timestamps(&timestamps_ns, 5.),
+            vec![
+                0,
+                199_000_000,
+                400_000_000,
+                600_000_000,
+                702_000_000,
+                1_000_000_000
+            ]
+        );
+        assert_eq!(select_resampled_timestamps(&timestamps_ns, 10.).len(), 10);
+    }
+}
@@ -0,0 +1,767 @@
+//! # ros2
+//!
+//! ROS 2 message conversion.
+//!
+//! Sweeps, cuboids, and poses are converted to `sensor_msgs/msg/PointCloud2`,
+//! `visualization_msgs/msg/MarkerArray`, and `tf2_msgs/msg/TFMessage` and serialized as
+//! little-endian CDR, the encoding ROS 2 publishes and records (e.g., in MCAP files opened by
+//! Foxglove). The messages mirror the ROS 2 Humble definitions field for field, so no ROS
+//! installation is required to produce them.
+
+use anyhow::{ensure, Context, Result};
+use ndarray::{ArrayView, Ix1};
+
+use crate::{
+    data_loader::Sweep, geometry::se3::SE3, share::data_frame_to_ndarray_f32,
+    structures::cuboid::CuboidList,
+};
+
+/// Frame of the city (map) coordinates.
+pub const CITY_FRAME: &str = "city";
+
+/// Frame of the egovehicle.
+pub const EGO_FRAME: &str = "ego";
+
+/// Topic of the lidar point clouds.
+pub const LIDAR_TOPIC: &str = "/av2/lidar";
+
+/// Topic of the cuboid markers.
+pub const CUBOIDS_TOPIC: &str = "/av2/cuboids";
+
+/// Topic of the transforms.
+pub const TF_TOPIC: &str = "/tf";
+
+/// Lidar columns exported as `FLOAT32` point fields.
+const POINT_FIELDS: [&str; 4] = ["x", "y", "z", "intensity"];
+
+/// `sensor_msgs/msg/PointField` data type of 32-bit floats.
+const FLOAT32: u8 = 7;
+
+/// `visualization_msgs/msg/Marker` type of cubes.
+const MARKER_CUBE: i32 = 1;
+
+/// `visualization_msgs/msg/Marker` action adding or modifying a marker.
+const MARKER_ADD: i32 = 0;
+
+/// `visualization_msgs/msg/Marker` action deleting every marker.
+const MARKER_DELETE_ALL: i32 = 3;
+
+/// Marker colors (RGB) assigned to categories.
+const PALETTE: [[f32; 3]; 8] = [
+    [0.12, 0.47, 0.71],
+    [1.00, 0.50, 0.05],
+    [0.17, 0.63, 0.17],
+    [0.84, 0.15, 0.16],
+    [0.58, 0.40, 0.74],
+    [0.55, 0.34, 0.29],
+    [0.89, 0.47, 0.76],
+    [0.74, 0.74, 0.13],
+];
+
+/// Little-endian CDR encoder. Primitives are aligned to their size relative to the end of the
+/// encapsulation header.
+#[derive(Clone, Debug)]
+pub struct CdrWriter {
+    buffer: Vec<u8>,
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self {
+            buffer: vec![0x00, 0x01, 0x00, 0x00],
+        }
+    }
+}
+
+impl CdrWriter {
+    /// Encoded message including the encapsulation header.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    fn align(&mut self, alignment: usize) {
+        let offset = self.buffer.len() - 4;
+        let padding = (alignment - offset % alignment) % alignment;
+        self.buffer.extend(std::iter::repeat_n(0, padding));
+    }
+
+    /// Write an unsigned byte.
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    /// Write a boolean.
+    pub fn write_bool(&mut self, value: bool) {
+        self.buffer.push(value as u8);
+    }
+
+    /// Write a 32-bit signed integer.
+    pub fn write_i32(&mut self, value: i32) {
+        self.align(4);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a 32-bit unsigned integer.
+    pub fn write_u32(&mut self, value: u32) {
+        self.align(4);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a 32-bit float.
+    pub fn write_f32(&mut self, value: f32) {
+        self.align(4);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a 64-bit float.
+    pub fn write_f64(&mut self, value: f64) {
+        self.align(8);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a string as its length (including the null terminator) and null-terminated bytes.
+    pub fn write_string(&mut self, value: &str) {
+        self.write_u32(value.len() as u32 + 1);
+        self.buffer.extend_from_slice(value.as_bytes());
+        self.buffer.push(0);
+    }
+
+    /// Write a byte sequence.
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.write_u32(value.len() as u32);
+        self.buffer.extend_from_slice(value);
+    }
+
+    /// Write a sequence of messages.
+    pub fn write_sequence<T: CdrSerialize>(&mut self, values: &[T]) {
+        self.write_u32(values.len() as u32);
+        for value in values {
+            value.write_cdr(self);
+        }
+    }
+}
+
+/// Types encoded in CDR.
+pub trait CdrSerialize {
+    /// Write the fields in definition order.
+    fn write_cdr(&self, writer: &mut CdrWriter);
+}
+
+/// Top-level ROS 2 messages.
+pub trait Ros2Message: CdrSerialize {
+    /// Fully qualified type name (e.g., `sensor_msgs/msg/PointCloud2`).
+    const TYPE_NAME: &'static str;
+
+    /// Message encoded as little-endian CDR (including the encapsulation header).
+    fn to_cdr(&self) -> Vec<u8> {
+        let mut writer = CdrWriter::default();
+        self.write_cdr(&mut writer);
+        writer.into_bytes()
+    }
+}
+
+/// `builtin_interfaces/msg/Time`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Time {
+    /// Seconds since the epoch.
+    pub sec: i32,
+    /// Nanoseconds within the second.
+    pub nanosec: u32,
+}
+
+impl Time {
+    /// Construct a time from nanoseconds since the epoch.
+    pub fn from_nanos(timestamp_ns: u64) -> Self {
+        Self {
+            sec: (timestamp_ns / 1_000_000_000) as i32,
+            nanosec: (timestamp_ns % 1_000_000_000) as u32,
+        }
+    }
+}
+
+impl CdrSerialize for Time {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_i32(self.sec);
+        writer.write_u32(self.nanosec);
+    }
+}
+
+/// `std_msgs/msg/Header`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Header {
+    /// Acquisition time.
+    pub stamp: Time,
+    /// Frame of the data.
+    pub frame_id: String,
+}
+
+impl CdrSerialize for Header {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.stamp.write_cdr(writer);
+        writer.write_string(&self.frame_id);
+    }
+}
+
+/// `sensor_msgs/msg/PointField`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointField {
+    /// Name of the field.
+    pub name: String,
+    /// Offset (in bytes) from the start of the point.
+    pub offset: u32,
+    /// Data type (e.g., `7` for `FLOAT32`).
+    pub datatype: u8,
+    /// Number of elements.
+    pub count: u32,
+}
+
+impl CdrSerialize for PointField {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_string(&self.name);
+        writer.write_u32(self.offset);
+        writer.write_u8(self.datatype);
+        writer.write_u32(self.count);
+    }
+}
+
+/// `sensor_msgs/msg/PointCloud2`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointCloud2 {
+    /// Acquisition time and frame.
+    pub header: Header,
+    /// Number of rows (`1` for unorganized point clouds).
+    pub height: u32,
+    /// Number of points per row.
+    pub width: u32,
+    /// Layout of a point.
+    pub fields: Vec<PointField>,
+    /// Boolean flag indicating whether the data is big-endian.
+    pub is_bigendian: bool,
+    /// Size of a point (in bytes).
+    pub point_step: u32,
+    /// Size of a row (in bytes).
+    pub row_step: u32,
+    /// Packed points.
+    pub data: Vec<u8>,
+    /// Boolean flag indicating whether every point is finite.
+    pub is_dense: bool,
+}
+
+impl CdrSerialize for PointCloud2 {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.header.write_cdr(writer);
+        writer.write_u32(self.height);
+        writer.write_u32(self.width);
+        writer.write_sequence(&self.fields);
+        writer.write_bool(self.is_bigendian);
+        writer.write_u32(self.point_step);
+        writer.write_u32(self.row_step);
+        writer.write_bytes(&self.data);
+        writer.write_bool(self.is_dense);
+    }
+}
+
+impl Ros2Message for PointCloud2 {
+    const TYPE_NAME: &'static str = "sensor_msgs/msg/PointCloud2";
+}
+
+/// `geometry_msgs/msg/Vector3` (also used for `geometry_msgs/msg/Point`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vector3 {
+    /// x-component.
+    pub x: f64,
+    /// y-component.
+    pub y: f64,
+    /// z-component.
+    pub z: f64,
+}
+
+impl CdrSerialize for Vector3 {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_f64(self.x);
+        writer.write_f64(self.y);
+        writer.write_f64(self.z);
+    }
+}
+
+/// `geometry_msgs/msg/Quaternion`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion {
+    /// x-component.
+    pub x: f64,
+    /// y-component.
+    pub y: f64,
+    /// z-component.
+    pub z: f64,
+    /// Scalar component.
+    pub w: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+            w: 1.,
+        }
+    }
+}
+
+impl Quaternion {
+    /// Construct a quaternion from scalar-first `(qw, qx, qy, qz)` components.
+    pub fn from_wxyz(quat_wxyz: &ArrayView<f32, Ix1>) -> Self {
+        Self {
+            x: quat_wxyz[1] as f64,
+            y: quat_wxyz[2] as f64,
+            z: quat_wxyz[3] as f64,
+            w: quat_wxyz[0] as f64,
+        }
+    }
+}
+
+impl CdrSerialize for Quaternion {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_f64(self.x);
+        writer.write_f64(self.y);
+        writer.write_f64(self.z);
+        writer.write_f64(self.w);
+    }
+}
+
+/// `geometry_msgs/msg/Pose`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pose {
+    /// Position.
+    pub position: Vector3,
+    /// Orientation.
+    pub orientation: Quaternion,
+}
+
+impl CdrSerialize for Pose {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.position.write_cdr(writer);
+        self.orientation.write_cdr(writer);
+    }
+}
+
+/// `geometry_msgs/msg/Transform`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Transform {
+    /// Translation.
+    pub translation: Vector3,
+    /// Rotation.
+    pub rotation: Quaternion,
+}
+
+impl From<&SE3> for Transform {
+    fn from(se3: &SE3) -> Self {
+        Self {
+            translation: Vector3 {
+                x: se3.translation[0] as f64,
+                y: se3.translation[1] as f64,
+                z: se3.translation[2] as f64,
+            },
+            rotation: Quaternion::from_wxyz(&se3.quat_wxyz().view()),
+        }
+    }
+}
+
+impl CdrSerialize for Transform {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.translation.write_cdr(writer);
+        self.rotation.write_cdr(writer);
+    }
+}
+
+/// `geometry_msgs/msg/TransformStamped`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransformStamped {
+    /// Time and parent frame.
+    pub header: Header,
+    /// Child frame.
+    pub child_frame_id: String,
+    /// Pose of the child frame in the parent frame.
+    pub transform: Transform,
+}
+
+impl CdrSerialize for TransformStamped {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.header.write_cdr(writer);
+        writer.write_string(&self.child_frame_id);
+        self.transform.write_cdr(writer);
+    }
+}
+
+/// `tf2_msgs/msg/TFMessage`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TFMessage {
+    /// Transforms.
+    pub transforms: Vec<TransformStamped>,
+}
+
+impl CdrSerialize for TFMessage {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_sequence(&self.transforms);
+    }
+}
+
+impl Ros2Message for TFMessage {
+    const TYPE_NAME: &'static str = "tf2_msgs/msg/TFMessage";
+}
+
+/// `std_msgs/msg/ColorRGBA`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColorRGBA {
+    /// Red.
+    pub r: f32,
+    /// Green.
+    pub g: f32,
+    /// Blue.
+    pub b: f32,
+    /// Opacity.
+    pub a: f32,
+}
+
+impl CdrSerialize for ColorRGBA {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_f32(self.r);
+        writer.write_f32(self.g);
+        writer.write_f32(self.b);
+        writer.write_f32(self.a);
+    }
+}
+
+/// `visualization_msgs/msg/Marker`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Marker {
+    /// Time and frame.
+    pub header: Header,
+    /// Namespace of the marker.
+    pub ns: String,
+    /// Identifier within the namespace.
+    pub id: i32,
+    /// Shape (e.g., `1` for a cube).
+    pub type_: i32,
+    /// Action (e.g., `0` to add, `3` to delete every marker).
+    pub action: i32,
+    /// Pose of the marker.
+    pub pose: Pose,
+    /// Extents of the marker (in meters).
+    pub scale: Vector3,
+    /// Color of the marker.
+    pub color: ColorRGBA,
+    /// Duration before the marker is deleted (zero for forever).
+    pub lifetime: Time,
+    /// Boolean flag indicating whether the marker follows its frame.
+    pub frame_locked: bool,
+    /// Points of line and point markers.
+    pub points: Vec<Vector3>,
+    /// Per-point colors.
+    pub colors: Vec<ColorRGBA>,
+    /// Text of text markers.
+    pub text: String,
+    /// Mesh of mesh markers.
+    pub mesh_resource: String,
+    /// Boolean flag indicating whether meshes use their embedded materials.
+    pub mesh_use_embedded_materials: bool,
+}
+
+impl CdrSerialize for Marker {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        self.header.write_cdr(writer);
+        writer.write_string(&self.ns);
+        writer.write_i32(self.id);
+        writer.write_i32(self.type_);
+        writer.write_i32(self.action);
+        self.pose.write_cdr(writer);
+        self.scale.write_cdr(writer);
+        self.color.write_cdr(writer);
+        self.lifetime.write_cdr(writer);
+        writer.write_bool(self.frame_locked);
+        writer.write_sequence(&self.points);
+        writer.write_sequence(&self.colors);
+        writer.write_string(&self.text);
+        writer.write_string(&self.mesh_resource);
+        writer.write_bool(self.mesh_use_embedded_materials);
+    }
+}
+
+/// `visualization_msgs/msg/MarkerArray`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarkerArray {
+    /// Markers.
+    pub markers: Vec<Marker>,
+}
+
+impl CdrSerialize for MarkerArray {
+    fn write_cdr(&self, writer: &mut CdrWriter) {
+        writer.write_sequence(&self.markers);
+    }
+}
+
+impl Ros2Message for MarkerArray {
+    const TYPE_NAME: &'static str = "visualization_msgs/msg/MarkerArray";
+}
+
+/// Serialized message ready to be published or recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ros2Record {
+    /// Topic of the message.
+    pub topic: String,
+    /// Fully qualified type name of the message.
+    pub type_name: String,
+    /// Log time (in nanoseconds).
+    pub timestamp_ns: u64,
+    /// Message encoded as little-endian CDR.
+    pub data: Vec<u8>,
+}
+
+impl Ros2Record {
+    /// Serialize `message` on `topic`.
+    pub fn new<T: Ros2Message>(topic: &str, timestamp_ns: u64, message: &T) -> Self {
+        Self {
+            topic: topic.to_string(),
+            type_name: T::TYPE_NAME.to_string(),
+            timestamp_ns,
+            data: message.to_cdr(),
+        }
+    }
+}
+
+/// Convert the lidar of a sweep to a point cloud with `FLOAT32` x, y, z, and intensity fields in
+/// the sweep's lidar frame.
+pub fn sweep_to_point_cloud2(sweep: &Sweep) -> Result<PointCloud2> {
+    let points = data_frame_to_ndarray_f32(sweep.lidar.0.clone(), POINT_FIELDS.to_vec())?;
+    let point_step = (POINT_FIELDS.len() * std::mem::size_of::<f32>()) as u32;
+    let mut data = Vec::with_capacity(points.len() * std::mem::size_of::<f32>());
+    for value in points.iter() {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    Ok(PointCloud2 {
+        header: Header {
+            stamp: Time::from_nanos(sweep.sweep_uuid.1),
+            frame_id: sweep.lidar_frame.clone(),
+        },
+        height: 1,
+        width: points.nrows() as u32,
+        fields: POINT_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, name)| PointField {
+                name: name.to_string(),
+                offset: (i * std::mem::size_of::<f32>()) as u32,
+                datatype: FLOAT32,
+                count: 1,
+            })
+            .collect(),
+        is_bigendian: false,
+        point_step,
+        row_step: point_step * points.nrows() as u32,
+        data,
+        is_dense: points.iter().all(|x| x.is_finite()),
+    })
+}
+
+/// Convert cuboids to cube markers in `frame_id`, colored by category. The array starts with a
+/// `DELETEALL` marker so that markers of a previous sweep are cleared.
+pub fn cuboids_to_marker_array(
+    cuboids: &CuboidList,
+    timestamp_ns: u64,
+    frame_id: &str,
+) -> MarkerArray {
+    let header = Header {
+        stamp: Time::from_nanos(timestamp_ns),
+        frame_id: frame_id.to_string(),
+    };
+    let clear = Marker {
+        header: header.clone(),
+        ns: "cuboids".to_string(),
+        action: MARKER_DELETE_ALL,
+        ..Default::default()
+    };
+    let markers = cuboids.iter().enumerate().map(|(i, cuboid)| {
+        let [tx, ty, tz] = cuboid.translation_m.map(|x| x as f64);
+        let [length, width, height] = cuboid.dims_lwh_m.map(|x| x as f64);
+        let [r, g, b] = cuboid
+            .category
+            .as_deref()
+            .map(_category_color)
+            .unwrap_or([0.5, 0.5, 0.5]);
+        Marker {
+            header: header.clone(),
+            ns: "cuboids".to_string(),
+            id: i as i32,
+            type_: MARKER_CUBE,
+            action: MARKER_ADD,
+            pose: Pose {
+                position: Vector3 {
+                    x: tx,
+                    y: ty,
+                    z: tz,
+                },
+                orientation: Quaternion::from_wxyz(&ArrayView::from(&cuboid.quat_wxyz)),
+            },
+            scale: Vector3 {
+                x: length,
+                y: width,
+                z: height,
+            },
+            color: ColorRGBA { r, g, b, a: 0.5 },
+            text: cuboid.category.clone().unwrap_or_default(),
+            ..Default::default()
+        }
+    });
+    MarkerArray {
+        markers: std::iter::once(clear).chain(markers).collect(),
+    }
+}
+
+/// Convert the poses of a sweep to transforms: the egovehicle in the city frame (if the log's
+/// poses are available) and each sensor in the egovehicle frame (if the extrinsics are loaded).
+pub fn sweep_to_tf_message(sweep: &Sweep) -> Result<TFMessage> {
+    let stamp = Time::from_nanos(sweep.sweep_uuid.1);
+    let mut transforms = vec![];
+    if let Some(city_se3_ego) = &sweep.city_se3_ego {
+        let city_se3_ego = SE3::from_transform_matrix(&city_se3_ego.view());
+        transforms.push(TransformStamped {
+            header: Header {
+                stamp,
+                frame_id: CITY_FRAME.to_string(),
+            },
+            child_frame_id: EGO_FRAME.to_string(),
+            transform: Transform::from(&city_se3_ego),
+        });
+    }
+    if let Some(extrinsics) = &sweep.extrinsics {
+        let extrinsics = &extrinsics.0;
+        let sensor_names = extrinsics.column("sensor_name")?.str()?;
+        let poses =
+            data_frame_to_ndarray_f32(extrinsics.clone(), crate::constants::POSE_COLUMNS.to_vec())?;
+        ensure!(sensor_names.len() == poses.nrows(), "Malformed extrinsics.");
+        for (sensor_name, pose) in sensor_names.into_iter().zip(poses.outer_iter()) {
+            let sensor_name = sensor_name.context("Missing sensor name.")?;
+            transforms.push(TransformStamped {
+                header: Header {
+                    stamp,
+                    frame_id: EGO_FRAME.to_string(),
+                },
+                child_frame_id: sensor_name.to_string(),
+                transform: Transform::from(&SE3::from_pose(&pose)?),
+            });
+        }
+    }
+    Ok(TFMessage { transforms })
+}
+
+/// Serialize a sweep as its transforms, lidar point cloud, and (if annotated) cuboid markers,
+/// stamped with the sweep timestamp.
+pub fn sweep_to_records(sweep: &Sweep) -> Result<Vec<Ros2Record>> {
+    let timestamp_ns = sweep.sweep_uuid.1;
+    let mut records = vec![
+        Ros2Record::new(TF_TOPIC, timestamp_ns, &sweep_to_tf_message(sweep)?),
+        Ros2Record::new(LIDAR_TOPIC, timestamp_ns, &sweep_to_point_cloud2(sweep)?),
+    ];
+    if let Some(cuboids) = &sweep.cuboids {
+        let cuboids = CuboidList::from_data_frame(&cuboids.0)?;
+        let markers = cuboids_to_marker_array(&cuboids, timestamp_ns, EGO_FRAME);
+        records.push(Ros2Record::new(CUBOIDS_TOPIC, timestamp_ns, &markers));
+    }
+    Ok(records)
+}
+
+/// Color of a category, which is stable across runs.
+fn _category_color(category: &str) -> [f32; 3] {
+    let hash = category.bytes().fold(0x811c9dc5_u32, |hash, x| {
+        (hash ^ x as u32).wrapping_mul(0x01000193)
+    });
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::{
+        data_loader::DataLoader,
+        structures::cuboid::CuboidList,
+        synthetic::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig},
+    };
+
+    use super::{
+        cuboids_to_marker_array, sweep_to_records, CdrSerialize, CdrWriter, Header, Ros2Message,
+        Time, CUBOIDS_TOPIC, LIDAR_TOPIC, TF_TOPIC,
+    };
+
+    #[test]
+    fn test_cdr() {
+        let header = Header {
+            stamp: Time::from_nanos(1_500_000_001),
+            frame_id: "ego".to_string(),
+        };
+        let mut writer = CdrWriter::default();
+        header.write_cdr(&mut writer);
+        writer.write_f64(1.);
+        assert_eq!(
+            writer.into_bytes(),
+            [
+                &[0, 1, 0, 0][..],
+                &1_i32.to_le_bytes(),
+                &500_000_001_u32.to_le_bytes(),
+                &4_u32.to_le_bytes(),
+                b"ego\0",
+                &1_f64.to_le_bytes(),
+            ]
+            .concat()
+        );
+
+        let cuboids = CuboidList::new(
+            array![[1., 2., 3., 4., 2., 1.5, 1., 0., 0., 0.]],
+            Some(vec!["REGULAR_VEHICLE".to_string()]),
+            None,
+        )
+        .unwrap();
+        let markers = cuboids_to_marker_array(&cuboids, 0, "ego");
+        assert_eq!(markers.markers.len(), 2);
+        assert_eq!(markers.markers[1].scale.x, 4.);
+        assert_eq!(markers.markers[1].pose.orientation.w, 1.);
+        assert!(markers.to_cdr().len() > 4);
+    }
+
+    #[test]
+    fn test_sweep_to_records() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, _) = test_dataset(&cfg);
+        let mut data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        data_loader.load_extrinsics = true;
+        let sweep = data_loader.get(0);
+        let records = sweep_to_records(&sweep).unwrap();
+        assert_eq!(
+            records.iter().map(|x| x.topic.as_str()).collect::<Vec<_>>(),
+            vec![TF_TOPIC, LIDAR_TOPIC, CUBOIDS_TOPIC]
+        );
+
+        let point_cloud = super::sweep_to_point_cloud2(&sweep).unwrap();
+        assert_eq!(point_cloud.width as usize, sweep.lidar.0.height());
+        assert_eq!(
+            point_cloud.data.len(),
+            (point_cloud.row_step * point_cloud.height) as usize
+        );
+        let tf = super::sweep_to_tf_message(&sweep).unwrap();
+        assert!(tf.transforms.len() > 1);
+        assert!(tf
+            .transforms
+            .iter()
+            .skip(1)
+            .all(|x| x.header.frame_id == "ego"));
+    }
+}
@@ -0,0 +1,199 @@
+//! # schema
+//!
+//! Expected columns of the dataset tables.
+//!
+//! The geometry routines select their columns inside `polars` expressions, where a missing or
+//! mistyped column surfaces as a panic (or an opaque `polars` error) deep within the pipeline.
+//! `validate_data_frame` checks a table against its schema up front and reports every missing,
+//! mistyped, or null column of the table in a single `Av2Error::Schema`.
+
+use std::fmt::{self, Display, Formatter};
+
+use itertools::Itertools;
+use polars::prelude::{DataFrame, DataType, Schema};
+
+use crate::error::{Av2Error, Av2Result};
+
+/// Type of a column, which is cast to its working type when read.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColumnType {
+    /// Floating-point column (read as `f32`).
+    Float,
+    /// Integer column (e.g., `timestamp_ns`).
+    Integer,
+    /// String column (e.g., `category`).
+    String,
+    /// Boolean column (e.g., `is_changed`).
+    Boolean,
+}
+
+impl ColumnType {
+    /// Returns `true` if a column of type `dtype` can be read as this type.
+    pub fn matches(&self, dtype: &DataType) -> bool {
+        match self {
+            ColumnType::Float => dtype.is_float(),
+            ColumnType::Integer => dtype.is_integer(),
+            ColumnType::String => dtype == &DataType::String,
+            ColumnType::Boolean => dtype == &DataType::Boolean,
+        }
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnType::Float => write!(f, "float"),
+            ColumnType::Integer => write!(f, "integer"),
+            ColumnType::String => write!(f, "string"),
+            ColumnType::Boolean => write!(f, "boolean"),
+        }
+    }
+}
+
+/// Coordinates of a lidar sweep.
+pub const LIDAR_SCHEMA: [(&str, ColumnType); 3] = [
+    ("x", ColumnType::Float),
+    ("y", ColumnType::Float),
+    ("z", ColumnType::Float),
+];
+
+/// Egovehicle poses in the city frame (`city_SE3_egovehicle.feather`).
+pub const POSE_SCHEMA: [(&str, ColumnType); 8] = [
+    ("timestamp_ns", ColumnType::Integer),
+    ("tx_m", ColumnType::Float),
+    ("ty_m", ColumnType::Float),
+    ("tz_m", ColumnType::Float),
+    ("qw", ColumnType::Float),
+    ("qx", ColumnType::Float),
+    ("qy", ColumnType::Float),
+    ("qz", ColumnType::Float),
+];
+
+/// Geometry of a cuboid table, in `CUBOID_COLUMNS` order.
+pub const CUBOID_SCHEMA: [(&str, ColumnType); 10] = [
+    ("tx_m", ColumnType::Float),
+    ("ty_m", ColumnType::Float),
+    ("tz_m", ColumnType::Float),
+    ("length_m", ColumnType::Float),
+    ("width_m", ColumnType::Float),
+    ("height_m", ColumnType::Float),
+    ("qw", ColumnType::Float),
+    ("qx", ColumnType::Float),
+    ("qy", ColumnType::Float),
+    ("qz", ColumnType::Float),
+];
+
+/// Annotation file (`annotations.feather`) columns read by the data-loader.
+pub const ANNOTATION_SCHEMA: [(&str, ColumnType); 14] = [
+    ("timestamp_ns", ColumnType::Integer),
+    ("track_uuid", ColumnType::String),
+    ("category", ColumnType::String),
+    ("length_m", ColumnType::Float),
+    ("width_m", ColumnType::Float),
+    ("height_m", ColumnType::Float),
+    ("qw", ColumnType::Float),
+    ("qx", ColumnType::Float),
+    ("qy", ColumnType::Float),
+    ("qz", ColumnType::Float),
+    ("tx_m", ColumnType::Float),
+    ("ty_m", ColumnType::Float),
+    ("tz_m", ColumnType::Float),
+    ("num_interior_pts", ColumnType::Integer),
+];
+
+/// Check that the `table` has the `columns` with their expected types.
+/// Every mismatch is listed in the returned error.
+pub fn validate_schema(
+    table: &str,
+    schema: &Schema,
+    columns: &[(&str, ColumnType)],
+) -> Av2Result<()> {
+    _report(table, _type_mismatches(schema, columns))
+}
+
+/// Check that the `table` has the `columns` with their expected types and without nulls.
+/// Every mismatch is listed in the returned error.
+pub fn validate_data_frame(
+    table: &str,
+    data_frame: &DataFrame,
+    columns: &[(&str, ColumnType)],
+) -> Av2Result<()> {
+    let mut mismatches = _type_mismatches(&data_frame.schema(), columns);
+    for (name, _) in columns {
+        let Ok(column) = data_frame.column(name) else {
+            continue;
+        };
+        if column.null_count() > 0 {
+            mismatches.push(format!(
+                "column `{name}` contains {} nulls",
+                column.null_count()
+            ));
+        }
+    }
+    _report(table, mismatches)
+}
+
+/// Missing and mistyped `columns` of the `schema`.
+fn _type_mismatches(schema: &Schema, columns: &[(&str, ColumnType)]) -> Vec<String> {
+    columns
+        .iter()
+        .filter_map(|(name, column_type)| match schema.get(name) {
+            None => Some(format!("missing column `{name}` ({column_type})")),
+            Some(dtype) if !column_type.matches(dtype) => Some(format!(
+                "column `{name}` has type {dtype} (expected {column_type})"
+            )),
+            Some(_) => None,
+        })
+        .collect()
+}
+
+/// Schema error listing the `mismatches` of the `table`, if any.
+fn _report(table: &str, mismatches: Vec<String>) -> Av2Result<()> {
+    match mismatches.is_empty() {
+        true => Ok(()),
+        false => Err(Av2Error::schema(table, mismatches.iter().join("; "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{col, df, lit, DataType, IntoLazy, NamedFrom};
+
+    use super::{validate_data_frame, validate_schema, POSE_SCHEMA};
+    use crate::error::Av2Error;
+
+    #[test]
+    fn test_validate_data_frame() {
+        let poses = df!(
+            "timestamp_ns" => [0_u64, 1],
+            "tx_m" => [0_f64, 1.],
+            "ty_m" => [0_f32, 0.],
+            "tz_m" => ["0", "0"],
+            "qw" => [Some(1_f64), None],
+            "qx" => [0_f64, 0.],
+            "qy" => [0_f64, 0.]
+        )
+        .unwrap();
+        let err = validate_data_frame("poses", &poses, &POSE_SCHEMA).unwrap_err();
+        assert!(matches!(err, Av2Error::Schema { .. }));
+        assert_eq!(
+            err.to_string(),
+            "Schema mismatch of the poses: column `tz_m` has type str (expected float); \
+             missing column `qz` (float); column `qw` contains 1 nulls"
+        );
+        // Nulls are only detected on data.
+        let err = validate_schema("poses", &poses.schema(), &POSE_SCHEMA).unwrap_err();
+        assert!(!err.to_string().contains("nulls"));
+
+        let poses = poses
+            .lazy()
+            .with_columns([
+                col("tz_m").cast(DataType::Float64),
+                col("qw").fill_null(1.0),
+                lit(0_f64).alias("qz"),
+            ])
+            .collect()
+            .unwrap();
+        validate_data_frame("poses", &poses, &POSE_SCHEMA).unwrap();
+    }
+}
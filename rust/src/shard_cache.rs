@@ -0,0 +1,326 @@
+//! # shard_cache
+//!
+//! Read-through decompression cache for `.tar.gz` dataset shards.
+//!
+//! Members are decompressed once and stored on local disk under
+//! `<cache_dir>/<shard_name>-<shard_size>-<shard_mtime>/<member>`, so a modified shard never
+//! serves stale members. Since a gzip stream can only be read sequentially, every member passed
+//! over while searching for the requested one is cached as well. The least recently used members
+//! are evicted once the cache exceeds its byte budget.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{Cursor, Read},
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use polars::prelude::{DataFrame, IpcReader, SerReader};
+use tar::Archive;
+use tracing::info_span;
+
+/// Shard cache configuration.
+#[derive(Clone, Debug)]
+pub struct ShardCacheConfig {
+    /// Directory of the decompressed members.
+    pub cache_dir: PathBuf,
+    /// Maximum total size of the decompressed members (in bytes).
+    pub max_size_bytes: u64,
+}
+
+impl Default for ShardCacheConfig {
+    fn default() -> Self {
+        Self {
+            cache_dir: dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("av2/shards"),
+            max_size_bytes: 16 << 30,
+        }
+    }
+}
+
+/// Read-through cache of decompressed shard members.
+#[derive(Debug)]
+pub struct ShardCache {
+    /// Cache configuration.
+    pub cfg: ShardCacheConfig,
+    /// Size and last access time of each cached member.
+    entries: Mutex<HashMap<PathBuf, (u64, SystemTime)>>,
+}
+
+impl ShardCache {
+    /// Open the cache, picking up members cached by previous runs.
+    pub fn new(cfg: ShardCacheConfig) -> Result<Self> {
+        fs::create_dir_all(&cfg.cache_dir)?;
+        let mut entries = HashMap::new();
+        _scan_dir(&cfg.cache_dir, &mut entries)?;
+        let cache = ShardCache {
+            cfg,
+            entries: Mutex::new(entries),
+        };
+        cache.evict(None)?;
+        Ok(cache)
+    }
+
+    /// Read `member` from the `.tar.gz` `shard`, decompressing the shard only on a cache miss.
+    pub fn read(&self, shard: &Path, member: &str) -> Result<Vec<u8>> {
+        let shard_dir = self.shard_dir(shard)?;
+        let cached_path = shard_dir.join(member);
+        if let Ok(bytes) = fs::read(&cached_path) {
+            self.touch(&cached_path);
+            return Ok(bytes);
+        }
+
+        let _span = info_span!("decompress_shard", shard = %shard.display()).entered();
+        let mut archive = Archive::new(GzDecoder::new(File::open(shard)?));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry.path()?.to_path_buf();
+            let path = path.strip_prefix(".").unwrap_or(&path).to_path_buf();
+            // Never write outside of the shard's cache directory.
+            if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+                continue;
+            }
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut bytes)?;
+
+            let is_requested = path == Path::new(member);
+            if !self.contains(&shard_dir.join(&path)) {
+                self.insert(&shard_dir.join(&path), &bytes, is_requested)?;
+            }
+            if is_requested {
+                return Ok(bytes);
+            }
+        }
+        bail!("{member} not found in {}.", shard.display())
+    }
+
+    /// Read a feather `member` from the `.tar.gz` `shard`.
+    pub fn read_feather(&self, shard: &Path, member: &str) -> Result<DataFrame> {
+        let bytes = self.read(shard, member)?;
+        Ok(IpcReader::new(Cursor::new(bytes)).finish()?)
+    }
+
+    /// Total size of the cached members (in bytes).
+    pub fn size_bytes(&self) -> u64 {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .map(|(size, _)| size)
+            .sum()
+    }
+
+    /// Remove every cached member.
+    pub fn clear(&self) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        fs::remove_dir_all(&self.cfg.cache_dir)?;
+        fs::create_dir_all(&self.cfg.cache_dir)?;
+        Ok(())
+    }
+
+    /// Cache directory of a shard, keyed by its name, size, and modification time.
+    fn shard_dir(&self, shard: &Path) -> Result<PathBuf> {
+        let metadata =
+            fs::metadata(shard).with_context(|| format!("{} not found.", shard.display()))?;
+        let mtime_ns = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_nanos();
+        let name = shard
+            .file_name()
+            .context("Invalid shard path.")?
+            .to_string_lossy();
+        Ok(self
+            .cfg
+            .cache_dir
+            .join(format!("{name}-{}-{mtime_ns}", metadata.len())))
+    }
+
+    fn contains(&self, path: &Path) -> bool {
+        self.entries.lock().unwrap().contains_key(path)
+    }
+
+    /// Mark a cached member as recently used (also on disk, for future runs).
+    fn touch(&self, path: &Path) {
+        let now = SystemTime::now();
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(path) {
+            entry.1 = now;
+        }
+        if let Ok(file) = File::options().append(true).open(path) {
+            let _ = file.set_modified(now);
+        }
+    }
+
+    /// Store a member. Members larger than the budget are not cached.
+    fn insert(&self, path: &Path, bytes: &[u8], is_requested: bool) -> Result<()> {
+        let size = bytes.len() as u64;
+        if size > self.cfg.max_size_bytes {
+            return Ok(());
+        }
+        fs::create_dir_all(path.parent().context("Invalid member path.")?)?;
+        // Write to a temporary file first so concurrent readers never see partial members.
+        let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, path)?;
+
+        // Members passed over while scanning are less recent than the requested one.
+        let accessed = match is_requested {
+            true => SystemTime::now(),
+            false => UNIX_EPOCH,
+        };
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (size, accessed));
+        self.evict(Some(path))
+    }
+
+    /// Evict the least recently used members (except `keep`) until the budget is met.
+    fn evict(&self, keep: Option<&Path>) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut size_bytes = entries.values().map(|(size, _)| size).sum::<u64>();
+        if size_bytes <= self.cfg.max_size_bytes {
+            return Ok(());
+        }
+        let mut candidates = entries
+            .iter()
+            .filter(|(path, _)| Some(path.as_path()) != keep)
+            .map(|(path, (size, accessed))| (*accessed, *size, path.clone()))
+            .collect::<Vec<_>>();
+        candidates.sort();
+        for (_, size, path) in candidates {
+            if size_bytes <= self.cfg.max_size_bytes {
+                break;
+            }
+            let _ = fs::remove_file(&path);
+            entries.remove(&path);
+            size_bytes -= size;
+        }
+        Ok(())
+    }
+}
+
+/// Recursively collect the cached members of `dir`.
+fn _scan_dir(dir: &Path, entries: &mut HashMap<PathBuf, (u64, SystemTime)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            _scan_dir(&entry.path(), entries)?;
+        } else {
+            entries.insert(entry.path(), (metadata.len(), metadata.modified()?));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use flate2::{write::GzEncoder, Compression};
+    use polars::prelude::{df, IpcWriter, NamedFrom, SerWriter};
+
+    use super::{ShardCache, ShardCacheConfig};
+    use crate::synthetic::test_dir;
+
+    /// Write a `.tar.gz` shard of the `members` and return its bytes.
+    fn write_shard(path: &Path, members: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        for (name, bytes) in members {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, &bytes[..]).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+        fs::write(path, &bytes).unwrap();
+        bytes
+    }
+
+    fn open_cache(cache_dir: &Path, max_size_bytes: u64) -> ShardCache {
+        ShardCache::new(ShardCacheConfig {
+            cache_dir: cache_dir.to_path_buf(),
+            max_size_bytes,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hit_and_miss() {
+        let root_dir = test_dir();
+        let shard = root_dir.join("shard.tar.gz");
+        let mut feather = vec![];
+        let mut data_frame = df!("x" => [1_f32, 2.]).unwrap();
+        IpcWriter::new(&mut feather)
+            .finish(&mut data_frame)
+            .unwrap();
+        write_shard(
+            &shard,
+            &[("a.bin", vec![1; 10]), ("b/c.feather", feather.clone())],
+        );
+        let cache = open_cache(&root_dir.join("cache"), 1 << 20);
+
+        // A miss decompresses the shard and caches the members passed over.
+        assert_eq!(cache.read(&shard, "b/c.feather").unwrap(), feather);
+        let shard_dir = cache.shard_dir(&shard).unwrap();
+        assert!(shard_dir.join("a.bin").exists());
+        assert_eq!(cache.size_bytes(), 10 + feather.len() as u64);
+
+        // A hit is served from disk without decompressing the shard.
+        fs::write(shard_dir.join("a.bin"), [7; 10]).unwrap();
+        assert_eq!(cache.read(&shard, "a.bin").unwrap(), vec![7; 10]);
+        assert!(cache
+            .read_feather(&shard, "b/c.feather")
+            .unwrap()
+            .equals(&data_frame));
+        assert!(cache.read(&shard, "missing.bin").is_err());
+    }
+
+    #[test]
+    fn test_eviction() {
+        let root_dir = test_dir();
+        let shard = root_dir.join("shard.tar.gz");
+        let members = ["a", "b", "c"].map(|name| (name, vec![0_u8; 100]));
+        write_shard(&shard, &members);
+        let cache = open_cache(&root_dir.join("cache"), 250);
+        let shard_dir = cache.shard_dir(&shard).unwrap();
+
+        // Reading `c` caches `b` in passing, which is evicted first to keep the budget.
+        cache.read(&shard, "a").unwrap();
+        cache.read(&shard, "c").unwrap();
+        assert_eq!(cache.size_bytes(), 200);
+        assert!(shard_dir.join("a").exists());
+        assert!(!shard_dir.join("b").exists());
+        assert!(shard_dir.join("c").exists());
+
+        // Reopening the cache with a smaller budget evicts on open.
+        let cache = open_cache(&root_dir.join("cache"), 100);
+        assert_eq!(cache.size_bytes(), 100);
+        cache.clear().unwrap();
+        assert_eq!(cache.size_bytes(), 0);
+    }
+
+    #[test]
+    fn test_corrupt_shard() {
+        let root_dir = test_dir();
+        let shard = root_dir.join("shard.tar.gz");
+        let bytes = write_shard(&shard, &[("a", vec![1; 1000]), ("b", vec![2; 1000])]);
+        let cache = open_cache(&root_dir.join("cache"), 1 << 20);
+
+        fs::write(&shard, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(cache.read(&shard, "b").is_err());
+        fs::write(&shard, b"not a gzip stream").unwrap();
+        assert!(cache.read(&shard, "a").is_err());
+        // A cached member which is not a feather file fails to decode.
+        write_shard(&shard, &[("a", vec![1; 1000])]);
+        assert!(cache.read_feather(&shard, "a").is_err());
+    }
+}
@@ -2,18 +2,26 @@
 //!
 //! Conversion methods between different libraries.
 
+use crate::error::{Av2Error, Av2Result};
 use ndarray::{Array, Ix2};
 use polars::{
+    export::arrow::{array::PrimitiveArray, datatypes::ArrowDataType},
     lazy::dsl::{cols, lit, Expr},
-    prelude::{DataFrame, Float32Type, IndexOrder, IntoLazy, NamedFrom},
-    series::Series,
+    prelude::{DataFrame, DataType, Float32Chunked, Float32Type, IndexOrder, IntoLazy, NamedFrom},
+    series::{IsSorted, Series},
 };
 
 /// Convert the columns of an `ndarray::Array` into a vector of `polars` expressions.
-pub fn ndarray_to_expr_vec(arr: Array<f32, Ix2>, column_names: Vec<&str>) -> Vec<Expr> {
+pub fn ndarray_to_expr_vec(arr: Array<f32, Ix2>, column_names: Vec<&str>) -> Av2Result<Vec<Expr>> {
     let num_dims = arr.shape()[1];
     if num_dims != column_names.len() {
-        panic!("Number of array columns and column names must match.");
+        return Err(Av2Error::invalid_parameter(
+            "column_names",
+            format!(
+                "Expected {num_dims} column names to match the array, got {}.",
+                column_names.len()
+            ),
+        ));
     }
 
     let mut series_vec = vec![];
@@ -24,19 +32,75 @@ pub fn ndarray_to_expr_vec(arr: Array<f32, Ix2>, column_names: Vec<&str>) -> Vec
         );
         series_vec.push(lit(series));
     }
-    series_vec
+    Ok(series_vec)
 }
 
 /// Convert a data frame to an `ndarray::Array::<f32, Ix2>`.
 pub fn data_frame_to_ndarray_f32(
     data_frame: DataFrame,
     column_names: Vec<&str>,
-) -> Array<f32, Ix2> {
-    data_frame
+) -> Av2Result<Array<f32, Ix2>> {
+    Ok(data_frame
         .lazy()
         .select(&[cols(column_names)])
-        .collect()
-        .unwrap()
-        .to_ndarray::<Float32Type>(IndexOrder::C)
-        .unwrap()
+        .collect()?
+        .to_ndarray::<Float32Type>(IndexOrder::C)?)
+}
+
+/// Call `f` with mutable views of the values of the `column_names` columns, which are cast to
+/// `Float32` and rechunked first if needed. Buffers which are not shared with another data frame
+/// are updated in place; shared buffers are copied once (copy-on-write).
+pub fn with_f32_columns_mut<const N: usize, R>(
+    data_frame: &mut DataFrame,
+    column_names: [&str; N],
+    f: impl FnOnce([&mut [f32]; N]) -> R,
+) -> Av2Result<R> {
+    let mut indices = [0; N];
+    for (index, name) in indices.iter_mut().zip(column_names) {
+        *index = data_frame.try_get_column_index(name)?;
+    }
+    if (1..N).any(|i| indices[..i].contains(&indices[i])) {
+        return Err(Av2Error::invalid_parameter(
+            "column_names",
+            "Columns must be distinct.",
+        ));
+    }
+
+    // SAFETY: Only the values of the columns are modified, not their names or lengths.
+    let columns = unsafe { data_frame.get_columns_mut() };
+    for &index in &indices {
+        let column = &mut columns[index];
+        if column.dtype() != &DataType::Float32 {
+            *column = column.cast(&DataType::Float32)?;
+        }
+        if column.n_chunks() > 1 {
+            *column = column.rechunk();
+        }
+        column.set_sorted_flag(IsSorted::Not);
+    }
+
+    let mut values: [Option<&mut [f32]>; N] = std::array::from_fn(|_| None);
+    for (index, column) in columns.iter_mut().enumerate() {
+        if let Some(i) = indices.iter().position(|x| *x == index) {
+            values[i] = Some(_f32_values_mut(column));
+        }
+    }
+    Ok(f(values.map(|x| x.unwrap())))
+}
+
+/// Mutable values of a single-chunk `Float32` series.
+fn _f32_values_mut(series: &mut Series) -> &mut [f32] {
+    let chunked_array: &mut Float32Chunked = series._get_inner_mut().as_mut();
+    // SAFETY: The length of the array is unchanged.
+    let Some(array) = (unsafe { chunked_array.downcast_iter_mut() }).next() else {
+        return &mut [];
+    };
+    if array.get_mut_values().is_none() {
+        *array = PrimitiveArray::new(
+            ArrowDataType::Float32,
+            array.values().to_vec().into(),
+            array.validity().cloned(),
+        );
+    }
+    array.get_mut_values().unwrap()
 }
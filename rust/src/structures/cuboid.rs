@@ -0,0 +1,352 @@
+//! # cuboid
+//!
+//! Typed cuboids backed by the annotation `DataFrame` schema.
+//!
+//! The geometry modules operate on (N,10) parameter arrays. `CuboidList` is the single place
+//! where those arrays are read from and written back to `DataFrame` columns, so a schema change
+//! fails here with the name of the offending column.
+
+use anyhow::{ensure, Result};
+use ndarray::{s, Array, ArrayView, ArrayViewMut, Ix1, Ix2, Ix3};
+use polars::prelude::{DataFrame, NamedFrom, Series};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::polytope::{
+        compute_interior_points_assignment, cuboids_to_polygons, AssignmentPolicy,
+    },
+    share::with_f32_columns_mut,
+};
+
+use super::prediction::{_f32_column, _str_column};
+
+/// Geometry columns of a cuboid `DataFrame` in parameter order.
+pub const CUBOID_COLUMNS: [&str; 10] = [
+    "tx_m", "ty_m", "tz_m", "length_m", "width_m", "height_m", "qw", "qx", "qy", "qz",
+];
+
+/// Oriented cuboid in the egovehicle (or city) frame.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Cuboid {
+    /// Center `(tx_m, ty_m, tz_m)` (in meters).
+    pub translation_m: [f32; 3],
+    /// Extents `(length_m, width_m, height_m)` along the object's axes (in meters).
+    pub dims_lwh_m: [f32; 3],
+    /// Scalar-first orientation quaternion `(qw, qx, qy, qz)`.
+    pub quat_wxyz: [f32; 4],
+    /// Object category (e.g., `REGULAR_VEHICLE`), if known.
+    pub category: Option<String>,
+    /// Track UUID, if known.
+    pub track_uuid: Option<String>,
+}
+
+/// List of cuboids stored as (N,10) parameters with optional categories and track UUIDs.
+/// Deserialized lists are validated like those built with `CuboidList::new`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "_CuboidListFields")]
+pub struct CuboidList {
+    /// (N,10) parameters in `CUBOID_COLUMNS` order.
+    params: Array<f32, Ix2>,
+    categories: Option<Vec<String>>,
+    track_uuids: Option<Vec<String>>,
+}
+
+/// Fields of a serialized `CuboidList` before validation.
+#[derive(Deserialize)]
+struct _CuboidListFields {
+    params: Array<f32, Ix2>,
+    categories: Option<Vec<String>>,
+    track_uuids: Option<Vec<String>>,
+}
+
+impl TryFrom<_CuboidListFields> for CuboidList {
+    type Error = anyhow::Error;
+
+    fn try_from(fields: _CuboidListFields) -> Result<Self> {
+        CuboidList::new(fields.params, fields.categories, fields.track_uuids)
+    }
+}
+
+impl CuboidList {
+    /// Construct a list from (N,10) parameters in `CUBOID_COLUMNS` order.
+    pub fn new(
+        params: Array<f32, Ix2>,
+        categories: Option<Vec<String>>,
+        track_uuids: Option<Vec<String>>,
+    ) -> Result<Self> {
+        ensure!(
+            params.shape()[1] == CUBOID_COLUMNS.len(),
+            "Expected (N,{}) cuboid parameters, got {:?}.",
+            CUBOID_COLUMNS.len(),
+            params.shape()
+        );
+        for (name, values) in [("categories", &categories), ("track UUIDs", &track_uuids)] {
+            if let Some(values) = values {
+                ensure!(
+                    values.len() == params.shape()[0],
+                    "Expected {} {name}, got {}.",
+                    params.shape()[0],
+                    values.len()
+                );
+            }
+        }
+        Ok(Self {
+            params,
+            categories,
+            track_uuids,
+        })
+    }
+
+    /// Read the `CUBOID_COLUMNS` and, if present, the `category` and `track_uuid` columns.
+    pub fn from_data_frame(data_frame: &DataFrame) -> Result<Self> {
+        let mut params = Array::<f32, Ix2>::zeros((data_frame.height(), CUBOID_COLUMNS.len()));
+        for (mut column, name) in params.columns_mut().into_iter().zip(CUBOID_COLUMNS) {
+            column.assign(&Array::from_vec(_f32_column(data_frame, name)?));
+        }
+        let optional_column = |name: &str| match data_frame.column(name) {
+            Ok(_) => _str_column(data_frame, name).map(Some),
+            Err(_) => Ok(None),
+        };
+        Self::new(
+            params,
+            optional_column("category")?,
+            optional_column("track_uuid")?,
+        )
+    }
+
+    /// Encode the list as a `DataFrame` with the `CUBOID_COLUMNS` followed by the `category` and
+    /// `track_uuid` columns (when known).
+    pub fn to_data_frame(&self) -> DataFrame {
+        let mut columns = CUBOID_COLUMNS
+            .into_iter()
+            .zip(self.params.columns())
+            .map(|(name, values)| Series::new(name, values.to_vec()))
+            .collect::<Vec<_>>();
+        for (name, values) in [
+            ("category", &self.categories),
+            ("track_uuid", &self.track_uuids),
+        ] {
+            if let Some(values) = values {
+                columns.push(Series::new(name, values));
+            }
+        }
+        DataFrame::new(columns).unwrap()
+    }
+
+    /// Overwrite the `CUBOID_COLUMNS` of `data_frame` (with one row per cuboid), keeping every
+    /// other column.
+    pub fn update_data_frame(&self, data_frame: &DataFrame) -> Result<DataFrame> {
+        ensure!(
+            data_frame.height() == self.len(),
+            "Expected {} rows, got {}.",
+            self.len(),
+            data_frame.height()
+        );
+        let mut data_frame = data_frame.clone();
+        for (name, values) in CUBOID_COLUMNS.into_iter().zip(self.params.columns()) {
+            data_frame.with_column(Series::new(name, values.to_vec()))?;
+        }
+        Ok(data_frame)
+    }
+
+    /// Overwrite the `CUBOID_COLUMNS` of `data_frame` (with one row per cuboid) in place.
+    pub fn write_data_frame(&self, data_frame: &mut DataFrame) -> Result<()> {
+        ensure!(
+            data_frame.height() == self.len(),
+            "Expected {} rows, got {}.",
+            self.len(),
+            data_frame.height()
+        );
+        with_f32_columns_mut(data_frame, CUBOID_COLUMNS, |columns| {
+            for (column, values) in columns.into_iter().zip(self.params.columns()) {
+                for (x, value) in column.iter_mut().zip(values) {
+                    *x = *value;
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Number of cuboids.
+    #[must_use]
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.params.shape()[0]
+    }
+
+    /// Returns `true` if the list has no cuboids.
+    #[must_use]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// (N,10) parameters in `CUBOID_COLUMNS` order.
+    pub fn params(&self) -> ArrayView<'_, f32, Ix2> {
+        self.params.view()
+    }
+
+    /// Mutable (N,10) parameters in `CUBOID_COLUMNS` order.
+    pub fn params_mut(&mut self) -> ArrayViewMut<'_, f32, Ix2> {
+        self.params.view_mut()
+    }
+
+    /// (N,3) centers (in meters).
+    pub fn translation_m(&self) -> ArrayView<'_, f32, Ix2> {
+        self.params.slice(s![.., 0..3])
+    }
+
+    /// Mutable (N,3) centers (in meters).
+    pub fn translation_m_mut(&mut self) -> ArrayViewMut<'_, f32, Ix2> {
+        self.params.slice_mut(s![.., 0..3])
+    }
+
+    /// (N,3) extents along the object axes (in meters).
+    pub fn dims_lwh_m(&self) -> ArrayView<'_, f32, Ix2> {
+        self.params.slice(s![.., 3..6])
+    }
+
+    /// Mutable (N,3) extents along the object axes (in meters).
+    pub fn dims_lwh_m_mut(&mut self) -> ArrayViewMut<'_, f32, Ix2> {
+        self.params.slice_mut(s![.., 3..6])
+    }
+
+    /// (N,4) scalar-first orientation quaternions.
+    pub fn quat_wxyz(&self) -> ArrayView<'_, f32, Ix2> {
+        self.params.slice(s![.., 6..10])
+    }
+
+    /// Mutable (N,4) scalar-first orientation quaternions.
+    pub fn quat_wxyz_mut(&mut self) -> ArrayViewMut<'_, f32, Ix2> {
+        self.params.slice_mut(s![.., 6..10])
+    }
+
+    /// Categories, if known.
+    pub fn categories(&self) -> Option<&[String]> {
+        self.categories.as_deref()
+    }
+
+    /// Track UUIDs, if known.
+    pub fn track_uuids(&self) -> Option<&[String]> {
+        self.track_uuids.as_deref()
+    }
+
+    /// (N,8,3) vertices of the cuboids.
+    pub fn vertices(&self) -> Array<f32, Ix3> {
+        cuboids_to_polygons(&self.params.view())
+    }
+
+    /// (N,) index of the cuboid containing each (N,3+) point, or `-1` for background points.
+    /// See `compute_interior_points_assignment`.
+    pub fn assign_points(
+        &self,
+        points: &ArrayView<f32, Ix2>,
+        policy: AssignmentPolicy,
+    ) -> Array<i64, Ix1> {
+        compute_interior_points_assignment(points, &self.vertices().view(), policy)
+    }
+
+    /// Track UUID of the cuboid containing each (N,3+) point (`None` for background points), or
+    /// `None` if the track UUIDs are unknown.
+    pub fn assign_track_uuids(
+        &self,
+        points: &ArrayView<f32, Ix2>,
+        policy: AssignmentPolicy,
+    ) -> Option<Vec<Option<String>>> {
+        let track_uuids = self.track_uuids()?;
+        let assignment = self.assign_points(points, policy);
+        Some(
+            assignment
+                .iter()
+                .map(|j| usize::try_from(*j).ok().map(|j| track_uuids[j].clone()))
+                .collect(),
+        )
+    }
+
+    /// The `index`-th cuboid.
+    pub fn get(&self, index: usize) -> Cuboid {
+        let p = self.params.row(index);
+        Cuboid {
+            translation_m: [p[0], p[1], p[2]],
+            dims_lwh_m: [p[3], p[4], p[5]],
+            quat_wxyz: [p[6], p[7], p[8], p[9]],
+            category: self.categories.as_ref().map(|x| x[index].clone()),
+            track_uuid: self.track_uuids.as_ref().map(|x| x[index].clone()),
+        }
+    }
+
+    /// Iterate over the cuboids.
+    pub fn iter(&self) -> impl Iterator<Item = Cuboid> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use polars::prelude::{df, NamedFrom};
+    use serde_json::json;
+
+    use super::{Cuboid, CuboidList};
+
+    #[test]
+    fn test_cuboid_list_data_frame() {
+        let data_frame = df!(
+            "tx_m" => [1_f32, 2.],
+            "ty_m" => [0_f32, 0.],
+            "tz_m" => [0_f32, 1.],
+            "length_m" => [4_f32, 1.],
+            "width_m" => [2_f32, 1.],
+            "height_m" => [1.5_f32, 2.],
+            "qw" => [1_f32, 1.],
+            "qx" => [0_f32, 0.],
+            "qy" => [0_f32, 0.],
+            "qz" => [0_f32, 0.],
+            "num_interior_pts" => [10_u32, 3],
+            "category" => ["REGULAR_VEHICLE", "PEDESTRIAN"]
+        )
+        .unwrap();
+        let mut cuboids = CuboidList::from_data_frame(&data_frame).unwrap();
+        assert_eq!(cuboids.len(), 2);
+        assert_eq!(cuboids.get(1).dims_lwh_m, [1., 1., 2.]);
+        assert_eq!(cuboids.get(1).category.as_deref(), Some("PEDESTRIAN"));
+        assert_eq!(cuboids.track_uuids(), None);
+
+        cuboids.translation_m_mut().mapv_inplace(|x| 2. * x);
+        let updated = cuboids.update_data_frame(&data_frame).unwrap();
+        assert_eq!(updated.get_column_names(), data_frame.get_column_names());
+        assert_eq!(
+            CuboidList::from_data_frame(&updated).unwrap(),
+            CuboidList::from_data_frame(&cuboids.to_data_frame()).unwrap()
+        );
+
+        let error = CuboidList::from_data_frame(&data_frame.drop("qw").unwrap()).unwrap_err();
+        assert_eq!(error.to_string(), "Missing column `qw`.");
+    }
+
+    #[test]
+    fn test_cuboid_list_serde() {
+        let cuboids = CuboidList::new(
+            array![[1_f32, 0., 0., 4., 2., 1.5, 1., 0., 0., 0.]],
+            Some(vec!["REGULAR_VEHICLE".to_string()]),
+            None,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&cuboids).unwrap();
+        assert_eq!(serde_json::from_str::<CuboidList>(&json).unwrap(), cuboids);
+        let bytes = bincode::serialize(&cuboids).unwrap();
+        assert_eq!(bincode::deserialize::<CuboidList>(&bytes).unwrap(), cuboids);
+
+        let cuboid = cuboids.get(0);
+        let json = serde_json::to_string(&cuboid).unwrap();
+        assert_eq!(serde_json::from_str::<Cuboid>(&json).unwrap(), cuboid);
+
+        // Mismatched categories are rejected.
+        let json = json!({
+            "params": {"v": 1, "dim": [1, 10], "data": [0., 0., 0., 1., 1., 1., 1., 0., 0., 0.]},
+            "categories": ["REGULAR_VEHICLE", "PEDESTRIAN"],
+            "track_uuids": null,
+        });
+        assert!(serde_json::from_value::<CuboidList>(json).is_err());
+    }
+}
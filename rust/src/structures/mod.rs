@@ -2,5 +2,9 @@
 //!
 //! Structures for sensor data.
 
+/// Typed cuboids backed by the annotation schema.
+pub mod cuboid;
+/// Prediction schema shared by the evaluators and exporters.
+pub mod prediction;
 /// Image at a particular timestamp in nanoseconds.
 pub mod timestamped_image;
@@ -0,0 +1,366 @@
+//! # prediction
+//!
+//! Prediction schema shared by the evaluators and exporters.
+//!
+//! Each record type is serde-serializable and has a canonical `DataFrame` encoding with one row
+//! per record and one column per field.
+
+use anyhow::{Context, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Columns of a 3D object detection `DataFrame`.
+pub const DETECTION_COLUMNS: [&str; 14] = [
+    "tx_m",
+    "ty_m",
+    "tz_m",
+    "length_m",
+    "width_m",
+    "height_m",
+    "qw",
+    "qx",
+    "qy",
+    "qz",
+    "score",
+    "log_id",
+    "timestamp_ns",
+    "category",
+];
+
+/// Columns of a 3D tracking `DataFrame`.
+pub const TRACKING_COLUMNS: [&str; 15] = [
+    "tx_m",
+    "ty_m",
+    "tz_m",
+    "length_m",
+    "width_m",
+    "height_m",
+    "qw",
+    "qx",
+    "qy",
+    "qz",
+    "score",
+    "log_id",
+    "timestamp_ns",
+    "category",
+    "track_id",
+];
+
+/// Columns of a motion forecasting `DataFrame`.
+pub const FORECASTING_COLUMNS: [&str; 5] = [
+    "scenario_id",
+    "track_id",
+    "probability",
+    "predicted_trajectory_x",
+    "predicted_trajectory_y",
+];
+
+/// Record with a canonical `DataFrame` encoding.
+pub trait FrameRecord: Sized {
+    /// Columns of the encoding.
+    const COLUMNS: &'static [&'static str];
+
+    /// Encode records as a `DataFrame`.
+    fn to_data_frame(records: &[Self]) -> DataFrame;
+
+    /// Decode records from a `DataFrame` containing (at least) the `COLUMNS`.
+    fn from_data_frame(data_frame: &DataFrame) -> Result<Vec<Self>>;
+}
+
+/// 3D object detection in the egovehicle frame.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct CuboidDetection {
+    /// Center x-coordinate (in meters).
+    pub tx_m: f32,
+    /// Center y-coordinate (in meters).
+    pub ty_m: f32,
+    /// Center z-coordinate (in meters).
+    pub tz_m: f32,
+    /// Extent along the object's x-axis (in meters).
+    pub length_m: f32,
+    /// Extent along the object's y-axis (in meters).
+    pub width_m: f32,
+    /// Extent along the object's z-axis (in meters).
+    pub height_m: f32,
+    /// Scalar component of the orientation quaternion.
+    pub qw: f32,
+    /// First vector component of the orientation quaternion.
+    pub qx: f32,
+    /// Second vector component of the orientation quaternion.
+    pub qy: f32,
+    /// Third vector component of the orientation quaternion.
+    pub qz: f32,
+    /// Confidence score in `[0, 1]`.
+    pub score: f32,
+    /// Log id of the sweep.
+    pub log_id: String,
+    /// Nanosecond timestamp of the sweep.
+    pub timestamp_ns: u64,
+    /// Object category (e.g., `REGULAR_VEHICLE`).
+    pub category: String,
+}
+
+/// 3D object detection associated with a track.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct TrackedCuboid {
+    /// Detection of the track at the sweep.
+    #[serde(flatten)]
+    pub detection: CuboidDetection,
+    /// Track id (unique within a log).
+    pub track_id: u64,
+}
+
+/// Forecasted trajectory of an agent. Each agent has `K` trajectories whose probabilities sum to one.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ForecastedTrajectory {
+    /// Scenario id.
+    pub scenario_id: String,
+    /// Track id of the forecasted agent.
+    pub track_id: String,
+    /// Probability of the trajectory.
+    pub probability: f32,
+    /// Predicted x-coordinates (in meters).
+    pub predicted_trajectory_x: Vec<f32>,
+    /// Predicted y-coordinates (in meters).
+    pub predicted_trajectory_y: Vec<f32>,
+}
+
+impl FrameRecord for CuboidDetection {
+    const COLUMNS: &'static [&'static str] = &DETECTION_COLUMNS;
+
+    fn to_data_frame(records: &[Self]) -> DataFrame {
+        let f32_column = |name: &str, f: fn(&Self) -> f32| {
+            Series::new(name, records.iter().map(f).collect::<Vec<_>>())
+        };
+        DataFrame::new(vec![
+            f32_column("tx_m", |x| x.tx_m),
+            f32_column("ty_m", |x| x.ty_m),
+            f32_column("tz_m", |x| x.tz_m),
+            f32_column("length_m", |x| x.length_m),
+            f32_column("width_m", |x| x.width_m),
+            f32_column("height_m", |x| x.height_m),
+            f32_column("qw", |x| x.qw),
+            f32_column("qx", |x| x.qx),
+            f32_column("qy", |x| x.qy),
+            f32_column("qz", |x| x.qz),
+            f32_column("score", |x| x.score),
+            Series::new(
+                "log_id",
+                records
+                    .iter()
+                    .map(|x| x.log_id.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                "timestamp_ns",
+                records.iter().map(|x| x.timestamp_ns).collect::<Vec<_>>(),
+            ),
+            Series::new(
+                "category",
+                records
+                    .iter()
+                    .map(|x| x.category.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn from_data_frame(data_frame: &DataFrame) -> Result<Vec<Self>> {
+        let [tx_m, ty_m, tz_m, length_m, width_m, height_m, qw, qx, qy, qz, score] = [
+            "tx_m", "ty_m", "tz_m", "length_m", "width_m", "height_m", "qw", "qx", "qy", "qz",
+            "score",
+        ]
+        .map(|name| _f32_column(data_frame, name));
+        let log_id = _str_column(data_frame, "log_id")?;
+        let timestamp_ns = _u64_column(data_frame, "timestamp_ns")?;
+        let category = _str_column(data_frame, "category")?;
+        let (tx_m, ty_m, tz_m, length_m, width_m, height_m) =
+            (tx_m?, ty_m?, tz_m?, length_m?, width_m?, height_m?);
+        let (qw, qx, qy, qz, score) = (qw?, qx?, qy?, qz?, score?);
+
+        Ok((0..data_frame.height())
+            .map(|i| CuboidDetection {
+                tx_m: tx_m[i],
+                ty_m: ty_m[i],
+                tz_m: tz_m[i],
+                length_m: length_m[i],
+                width_m: width_m[i],
+                height_m: height_m[i],
+                qw: qw[i],
+                qx: qx[i],
+                qy: qy[i],
+                qz: qz[i],
+                score: score[i],
+                log_id: log_id[i].clone(),
+                timestamp_ns: timestamp_ns[i],
+                category: category[i].clone(),
+            })
+            .collect())
+    }
+}
+
+impl FrameRecord for TrackedCuboid {
+    const COLUMNS: &'static [&'static str] = &TRACKING_COLUMNS;
+
+    fn to_data_frame(records: &[Self]) -> DataFrame {
+        let detections = records
+            .iter()
+            .map(|x| x.detection.clone())
+            .collect::<Vec<_>>();
+        let mut data_frame = CuboidDetection::to_data_frame(&detections);
+        data_frame
+            .with_column(Series::new(
+                "track_id",
+                records.iter().map(|x| x.track_id).collect::<Vec<_>>(),
+            ))
+            .unwrap();
+        data_frame
+    }
+
+    fn from_data_frame(data_frame: &DataFrame) -> Result<Vec<Self>> {
+        let detections = CuboidDetection::from_data_frame(data_frame)?;
+        let track_ids = _u64_column(data_frame, "track_id")?;
+        Ok(detections
+            .into_iter()
+            .zip(track_ids)
+            .map(|(detection, track_id)| TrackedCuboid {
+                detection,
+                track_id,
+            })
+            .collect())
+    }
+}
+
+impl FrameRecord for ForecastedTrajectory {
+    const COLUMNS: &'static [&'static str] = &FORECASTING_COLUMNS;
+
+    fn to_data_frame(records: &[Self]) -> DataFrame {
+        let list_column = |name: &str, f: fn(&Self) -> &Vec<f32>| {
+            Series::new(
+                name,
+                records
+                    .iter()
+                    .map(|x| Series::new("", f(x)))
+                    .collect::<Vec<_>>(),
+            )
+        };
+        DataFrame::new(vec![
+            Series::new(
+                "scenario_id",
+                records
+                    .iter()
+                    .map(|x| x.scenario_id.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                "track_id",
+                records
+                    .iter()
+                    .map(|x| x.track_id.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            Series::new(
+                "probability",
+                records.iter().map(|x| x.probability).collect::<Vec<_>>(),
+            ),
+            list_column("predicted_trajectory_x", |x| &x.predicted_trajectory_x),
+            list_column("predicted_trajectory_y", |x| &x.predicted_trajectory_y),
+        ])
+        .unwrap()
+    }
+
+    fn from_data_frame(data_frame: &DataFrame) -> Result<Vec<Self>> {
+        let scenario_id = _str_column(data_frame, "scenario_id")?;
+        let track_id = _str_column(data_frame, "track_id")?;
+        let probability = _f32_column(data_frame, "probability")?;
+        let predicted_trajectory_x = _list_f32_column(data_frame, "predicted_trajectory_x")?;
+        let predicted_trajectory_y = _list_f32_column(data_frame, "predicted_trajectory_y")?;
+        Ok((0..data_frame.height())
+            .map(|i| ForecastedTrajectory {
+                scenario_id: scenario_id[i].clone(),
+                track_id: track_id[i].clone(),
+                probability: probability[i],
+                predicted_trajectory_x: predicted_trajectory_x[i].clone(),
+                predicted_trajectory_y: predicted_trajectory_y[i].clone(),
+            })
+            .collect())
+    }
+}
+
+pub(crate) fn _column<'a>(data_frame: &'a DataFrame, name: &str) -> Result<&'a Series> {
+    data_frame
+        .column(name)
+        .with_context(|| format!("Missing column `{name}`."))
+}
+
+pub(crate) fn _f32_column(data_frame: &DataFrame, name: &str) -> Result<Vec<f32>> {
+    let series = _column(data_frame, name)?.cast(&DataType::Float32)?;
+    let values = series.f32()?.into_iter().collect::<Option<Vec<_>>>();
+    values.with_context(|| format!("Column `{name}` contains nulls."))
+}
+
+fn _u64_column(data_frame: &DataFrame, name: &str) -> Result<Vec<u64>> {
+    let series = _column(data_frame, name)?.cast(&DataType::UInt64)?;
+    let values = series.u64()?.into_iter().collect::<Option<Vec<_>>>();
+    values.with_context(|| format!("Column `{name}` contains nulls."))
+}
+
+pub(crate) fn _str_column(data_frame: &DataFrame, name: &str) -> Result<Vec<String>> {
+    let series = _column(data_frame, name)?.cast(&DataType::String)?;
+    let values = series
+        .str()?
+        .into_iter()
+        .map(|x| x.map(|x| x.to_string()))
+        .collect::<Option<Vec<_>>>();
+    values.with_context(|| format!("Column `{name}` contains nulls."))
+}
+
+fn _list_f32_column(data_frame: &DataFrame, name: &str) -> Result<Vec<Vec<f32>>> {
+    let series = _column(data_frame, name)?.cast(&DataType::List(Box::new(DataType::Float32)))?;
+    series
+        .list()?
+        .into_iter()
+        .map(|x| {
+            let x = x.with_context(|| format!("Column `{name}` contains nulls."))?;
+            let values = x.f32()?.into_iter().collect::<Option<Vec<_>>>();
+            values.with_context(|| format!("Column `{name}` contains nulls."))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CuboidDetection, FrameRecord, TrackedCuboid, TRACKING_COLUMNS};
+
+    #[test]
+    fn test_tracked_cuboid_round_trip() {
+        let records = (0..3)
+            .map(|i| TrackedCuboid {
+                detection: CuboidDetection {
+                    tx_m: i as f32,
+                    ty_m: 1.,
+                    tz_m: 2.,
+                    length_m: 4.,
+                    width_m: 2.,
+                    height_m: 1.5,
+                    qw: 1.,
+                    qx: 0.,
+                    qy: 0.,
+                    qz: 0.,
+                    score: 0.5,
+                    log_id: "log".to_string(),
+                    timestamp_ns: 100 + i,
+                    category: "REGULAR_VEHICLE".to_string(),
+                },
+                track_id: i,
+            })
+            .collect::<Vec<_>>();
+        let data_frame = TrackedCuboid::to_data_frame(&records);
+        assert_eq!(data_frame.get_column_names(), TRACKING_COLUMNS);
+        assert_eq!(
+            TrackedCuboid::from_data_frame(&data_frame).unwrap(),
+            records
+        );
+    }
+}
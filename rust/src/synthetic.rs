@@ -0,0 +1,775 @@
+//! # synthetic
+//!
+//! Synthetic scenes and miniature, schema-complete datasets.
+//!
+//! A synthetic scene is an egovehicle driving along a straight two-lane road among a handful of
+//! objects. Writing scenes as logs yields a tiny "mini-AV2" directory tree (sweeps, annotations,
+//! poses, calibration, images, and maps) for integration tests and CI of downstream projects.
+
+use std::{
+    f32::consts::PI,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use image::{Rgb, RgbImage};
+use ndarray::{Array, Ix2, Ix3};
+use polars::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use strum::IntoEnumIterator;
+
+use crate::{
+    constants::{CameraNames, DEFAULT_MAP_FILE_NAME, NUM_LASERS_PER_LIDAR},
+    geometry::so3::mat3_to_quat,
+    io::write_feather_eager,
+    map::{DrivableArea, LaneSegment, MapPoint, PedestrianCrossing, VectorMapJson},
+};
+
+/// Width of each lane of the synthetic road (in meters).
+pub const LANE_WIDTH_M: f32 = 3.5;
+/// Length of each lane segment of the synthetic road (in meters).
+pub const LANE_SEGMENT_LENGTH_M: f32 = 20.;
+/// Resolution of the ground height raster (in meters per pixel).
+pub const GROUND_HEIGHT_RESOLUTION_M: f32 = 0.3;
+
+/// Categories and `(length_m, width_m, height_m)` of the synthetic objects.
+const OBJECT_TEMPLATES: [(&str, [f32; 3]); 3] = [
+    ("REGULAR_VEHICLE", [4.5, 1.9, 1.6]),
+    ("PEDESTRIAN", [0.6, 0.6, 1.7]),
+    ("BICYCLE", [1.7, 0.6, 1.2]),
+];
+
+/// Synthetic scene configuration.
+#[derive(Clone, Debug)]
+pub struct SyntheticSceneConfig {
+    /// Number of lidar sweeps.
+    pub num_sweeps: usize,
+    /// Interval between consecutive sweeps (in nanoseconds).
+    pub sweep_interval_ns: u64,
+    /// Interval between consecutive egovehicle poses (in nanoseconds).
+    pub pose_interval_ns: u64,
+    /// Timestamp of the first sweep (in nanoseconds).
+    pub start_timestamp_ns: u64,
+    /// Egovehicle speed along the road (in meters per second).
+    pub ego_speed_mps: f32,
+    /// Number of annotated objects.
+    pub num_objects: usize,
+    /// Number of lidar returns sampled inside each object.
+    pub num_points_per_object: usize,
+    /// Number of lidar returns sampled on the ground.
+    pub num_ground_points: usize,
+    /// Maximum range of the ground returns (in meters).
+    pub max_range_m: f32,
+    /// Image width (in pixels).
+    pub image_width_px: u32,
+    /// Image height (in pixels).
+    pub image_height_px: u32,
+    /// Random seed.
+    pub seed: u64,
+}
+
+impl Default for SyntheticSceneConfig {
+    fn default() -> Self {
+        Self {
+            num_sweeps: 5,
+            sweep_interval_ns: 100_000_000,
+            pose_interval_ns: 10_000_000,
+            start_timestamp_ns: 315_966_000_000_000_000,
+            ego_speed_mps: 5.,
+            num_objects: 4,
+            num_points_per_object: 20,
+            num_ground_points: 1024,
+            max_range_m: 50.,
+            image_width_px: 64,
+            image_height_px: 48,
+            seed: 0,
+        }
+    }
+}
+
+/// Synthetic scene with the tables of a sensor dataset log.
+#[derive(Clone, Debug)]
+pub struct SyntheticScene {
+    /// Log id.
+    pub log_id: String,
+    /// Egovehicle poses, as in `city_SE3_egovehicle.feather`.
+    pub city_pose: DataFrame,
+    /// Annotations in the egovehicle frame, as in `annotations.feather`.
+    pub annotations: DataFrame,
+    /// Sensor extrinsics, as in `calibration/egovehicle_SE3_sensor.feather`.
+    pub extrinsics: DataFrame,
+    /// Camera intrinsics, as in `calibration/intrinsics.feather`.
+    pub intrinsics: DataFrame,
+    /// Lidar sweeps in the egovehicle frame keyed by timestamp.
+    pub sweeps: Vec<(u64, DataFrame)>,
+    /// Vector map in the city frame.
+    pub map: VectorMapJson,
+    /// Image width (in pixels).
+    pub image_width_px: u32,
+    /// Image height (in pixels).
+    pub image_height_px: u32,
+}
+
+/// Object moving with constant velocity along the road.
+struct SyntheticObject {
+    track_uuid: String,
+    category: &'static str,
+    dims_lwh_m: [f32; 3],
+    /// City frame center at the first sweep.
+    translation_m: [f32; 3],
+    yaw_rad: f32,
+    speed_mps: f32,
+}
+
+impl SyntheticObject {
+    fn translation_at(&self, elapsed_s: f32) -> [f32; 3] {
+        let [x, y, z] = self.translation_m;
+        let distance_m = self.speed_mps * elapsed_s;
+        [
+            x + distance_m * self.yaw_rad.cos(),
+            y + distance_m * self.yaw_rad.sin(),
+            z,
+        ]
+    }
+}
+
+/// Generate a synthetic scene. Scenes are deterministic given the configuration.
+pub fn generate_synthetic_scene(cfg: &SyntheticSceneConfig) -> SyntheticScene {
+    let mut rng = StdRng::seed_from_u64(cfg.seed);
+    let log_id = _random_uuid(&mut rng);
+    let sweep_timestamps_ns = (0..cfg.num_sweeps as u64)
+        .map(|i| cfg.start_timestamp_ns + i * cfg.sweep_interval_ns)
+        .collect::<Vec<_>>();
+    let elapsed_s = |timestamp_ns: u64| (timestamp_ns - cfg.start_timestamp_ns) as f32 * 1e-9;
+    let ego_x_m = |timestamp_ns: u64| cfg.ego_speed_mps * elapsed_s(timestamp_ns);
+
+    // Cover the whole drive (with a margin) by the road.
+    let duration_ns = sweep_timestamps_ns
+        .last()
+        .copied()
+        .unwrap_or(cfg.start_timestamp_ns)
+        - cfg.start_timestamp_ns;
+    let road_start_m = -LANE_SEGMENT_LENGTH_M;
+    let road_end_m = ego_x_m(cfg.start_timestamp_ns + duration_ns) + 3. * LANE_SEGMENT_LENGTH_M;
+
+    let objects = (0..cfg.num_objects)
+        .map(|_| {
+            let (category, dims_lwh_m) = OBJECT_TEMPLATES[rng.gen_range(0..OBJECT_TEMPLATES.len())];
+            let (y_m, speed_mps) = match category {
+                "PEDESTRIAN" => (rng.gen_range(LANE_WIDTH_M + 0.5..LANE_WIDTH_M + 3.), 0.),
+                _ => (
+                    rng.gen_range(-LANE_WIDTH_M..LANE_WIDTH_M) * 0.8,
+                    rng.gen_range(0. ..cfg.ego_speed_mps),
+                ),
+            };
+            SyntheticObject {
+                track_uuid: _random_uuid(&mut rng),
+                category,
+                dims_lwh_m,
+                translation_m: [rng.gen_range(5. ..30.), y_m, dims_lwh_m[2] / 2.],
+                yaw_rad: if speed_mps > 0. {
+                    0.
+                } else {
+                    rng.gen_range(-PI..PI)
+                },
+                speed_mps,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let pose_timestamps_ns = (0..=duration_ns / cfg.pose_interval_ns + 1)
+        .map(|i| cfg.start_timestamp_ns + i * cfg.pose_interval_ns)
+        .collect::<Vec<_>>();
+    let num_poses = pose_timestamps_ns.len();
+    let city_pose = df!(
+        "timestamp_ns" => pose_timestamps_ns.iter().map(|x| *x as i64).collect::<Vec<_>>(),
+        "qw" => vec![1_f64; num_poses],
+        "qx" => vec![0_f64; num_poses],
+        "qy" => vec![0_f64; num_poses],
+        "qz" => vec![0_f64; num_poses],
+        "tx_m" => pose_timestamps_ns.iter().map(|x| ego_x_m(*x) as f64).collect::<Vec<_>>(),
+        "ty_m" => vec![0_f64; num_poses],
+        "tz_m" => vec![0_f64; num_poses]
+    )
+    .unwrap();
+
+    let mut annotations = _AnnotationColumns::default();
+    let mut sweeps = Vec::with_capacity(cfg.num_sweeps);
+    for &timestamp_ns in &sweep_timestamps_ns {
+        // The egovehicle drives along the city x-axis without rotating, so the egovehicle frame is
+        // the city frame shifted by its position.
+        let ego_x_m = ego_x_m(timestamp_ns);
+        let mut lidar = _LidarColumns::default();
+        for _ in 0..cfg.num_ground_points {
+            let range_m = rng.gen_range(2. ..cfg.max_range_m);
+            let azimuth = rng.gen_range(-PI..PI);
+            lidar.push(
+                &mut rng,
+                [range_m * azimuth.cos(), range_m * azimuth.sin(), 0.],
+            );
+        }
+        for object in &objects {
+            let [x, y, z] = object.translation_at(elapsed_s(timestamp_ns));
+            let [length_m, width_m, height_m] = object.dims_lwh_m;
+            let (sin, cos) = object.yaw_rad.sin_cos();
+            for _ in 0..cfg.num_points_per_object {
+                let local = [
+                    rng.gen_range(-0.5..0.5) * length_m,
+                    rng.gen_range(-0.5..0.5) * width_m,
+                    rng.gen_range(-0.5..0.5) * height_m,
+                ];
+                lidar.push(
+                    &mut rng,
+                    [
+                        x - ego_x_m + cos * local[0] - sin * local[1],
+                        y + sin * local[0] + cos * local[1],
+                        z + local[2],
+                    ],
+                );
+            }
+            annotations.push(timestamp_ns, object, [x - ego_x_m, y, z], cfg);
+        }
+        sweeps.push((timestamp_ns, lidar.into_data_frame()));
+    }
+
+    let (width_px, height_px) = (cfg.image_width_px as f64, cfg.image_height_px as f64);
+    // 60 degrees horizontal field of view.
+    let focal_length_px = width_px / 2. / (PI as f64 / 6.).tan();
+    let camera_names = CameraNames::iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+    let num_cameras = camera_names.len();
+    let intrinsics = df!(
+        "sensor_name" => camera_names.clone(),
+        "fx_px" => vec![focal_length_px; num_cameras],
+        "fy_px" => vec![focal_length_px; num_cameras],
+        "cx_px" => vec![width_px / 2.; num_cameras],
+        "cy_px" => vec![height_px / 2.; num_cameras],
+        "k1" => vec![-0.1_f64; num_cameras],
+        "k2" => vec![0_f64; num_cameras],
+        "k3" => vec![0_f64; num_cameras],
+        "height_px" => vec![cfg.image_height_px as u16; num_cameras],
+        "width_px" => vec![cfg.image_width_px as u16; num_cameras]
+    )
+    .unwrap();
+
+    SyntheticScene {
+        log_id,
+        city_pose,
+        annotations: annotations.into_data_frame(),
+        extrinsics: _extrinsics(&camera_names),
+        intrinsics,
+        sweeps,
+        map: _straight_road_map(road_start_m, road_end_m),
+        image_width_px: cfg.image_width_px,
+        image_height_px: cfg.image_height_px,
+    }
+}
+
+impl SyntheticScene {
+    /// Write the scene as a sensor dataset log in `log_dir`. Annotations are omitted if
+    /// `with_annotations` is `false` (e.g., for the test split).
+    pub fn write(&self, log_dir: &Path, with_annotations: bool) -> Result<()> {
+        let (lidar_dir, map_dir, calibration_dir) = (
+            log_dir.join("sensors/lidar"),
+            log_dir.join("map"),
+            log_dir.join("calibration"),
+        );
+        for dir in [&lidar_dir, &map_dir, &calibration_dir] {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}.", dir.display()))?;
+        }
+
+        write_feather_eager(
+            &log_dir.join("city_SE3_egovehicle.feather"),
+            self.city_pose.clone(),
+        )?;
+        if with_annotations {
+            write_feather_eager(
+                &log_dir.join("annotations.feather"),
+                self.annotations.clone(),
+            )?;
+        }
+        write_feather_eager(
+            &calibration_dir.join("egovehicle_SE3_sensor.feather"),
+            self.extrinsics.clone(),
+        )?;
+        write_feather_eager(
+            &calibration_dir.join("intrinsics.feather"),
+            self.intrinsics.clone(),
+        )?;
+
+        for (timestamp_ns, lidar) in &self.sweeps {
+            write_feather_eager(
+                &lidar_dir.join(format!("{timestamp_ns}.feather")),
+                lidar.clone(),
+            )?;
+        }
+        for (i, camera_name) in CameraNames::iter().enumerate() {
+            let camera_dir = log_dir
+                .join("sensors/cameras")
+                .join(camera_name.to_string());
+            fs::create_dir_all(&camera_dir)?;
+            let shade = (32 * i) as u8;
+            let image = RgbImage::from_pixel(
+                self.image_width_px,
+                self.image_height_px,
+                Rgb([shade, 128, 255 - shade]),
+            );
+            for (timestamp_ns, _) in &self.sweeps {
+                // Cameras trigger shortly after the lidar sweeps start.
+                let path = camera_dir.join(format!("{}.jpg", timestamp_ns + 1_000_000));
+                image
+                    .save(&path)
+                    .with_context(|| format!("Failed to write {}.", path.display()))?;
+            }
+        }
+
+        let map_file = File::create(map_dir.join(DEFAULT_MAP_FILE_NAME))?;
+        serde_json::to_writer(map_file, &self.map)?;
+        self._write_ground_height(&map_dir)
+    }
+
+    /// Write a flat ground height raster covering the drivable areas and its city frame transform.
+    fn _write_ground_height(&self, map_dir: &Path) -> Result<()> {
+        let boundary = self
+            .map
+            .drivable_areas
+            .values()
+            .flat_map(|x| &x.area_boundary);
+        let (min_x, min_y, max_x, max_y) = boundary.fold(
+            (
+                f32::INFINITY,
+                f32::INFINITY,
+                f32::NEG_INFINITY,
+                f32::NEG_INFINITY,
+            ),
+            |(min_x, min_y, max_x, max_y), p| {
+                (
+                    min_x.min(p.x),
+                    min_y.min(p.y),
+                    max_x.max(p.x),
+                    max_y.max(p.y),
+                )
+            },
+        );
+        let scale = 1. / GROUND_HEIGHT_RESOLUTION_M;
+        let shape = (
+            ((max_y - min_y) * scale).ceil() as usize,
+            ((max_x - min_x) * scale).ceil() as usize,
+        );
+        _write_npy_f32(
+            &map_dir.join(format!(
+                "{}_ground_height_surface____DEFAULT.npy",
+                self.log_id
+            )),
+            &Array::<f32, Ix2>::zeros(shape),
+        )?;
+        let sim2 = serde_json::json!({
+            "R": [1., 0., 0., 1.],
+            "t": [-min_x, -min_y],
+            "s": scale,
+        });
+        let sim2_file =
+            File::create(map_dir.join(format!("{}___img_Sim2_city.json", self.log_id)))?;
+        serde_json::to_writer(sim2_file, &sim2)?;
+        Ok(())
+    }
+}
+
+/// Miniature dataset configuration.
+#[derive(Clone, Debug)]
+pub struct MiniDatasetConfig {
+    /// Dataset name (e.g., `av2`).
+    pub dataset_name: String,
+    /// Dataset type (e.g., `sensor`).
+    pub dataset_type: String,
+    /// Split names. The `test` split has no annotations.
+    pub split_names: Vec<String>,
+    /// Number of logs in each split.
+    pub num_logs_per_split: usize,
+    /// Configuration of the scenes. Each log offsets the seed by its index.
+    pub scene: SyntheticSceneConfig,
+}
+
+impl Default for MiniDatasetConfig {
+    fn default() -> Self {
+        Self {
+            dataset_name: "av2".to_string(),
+            dataset_type: "sensor".to_string(),
+            split_names: vec!["train".to_string(), "val".to_string(), "test".to_string()],
+            num_logs_per_split: 2,
+            scene: SyntheticSceneConfig::default(),
+        }
+    }
+}
+
+/// Generate a miniature dataset in `<root_dir>/<dataset_name>/<dataset_type>/<split_name>`.
+/// Returns the log directories.
+pub fn generate_mini_dataset(root_dir: &Path, cfg: &MiniDatasetConfig) -> Result<Vec<PathBuf>> {
+    let mut log_dirs = vec![];
+    for (i, split_name) in cfg.split_names.iter().enumerate() {
+        let split_dir = root_dir
+            .join(&cfg.dataset_name)
+            .join(&cfg.dataset_type)
+            .join(split_name);
+        for j in 0..cfg.num_logs_per_split {
+            let scene_cfg = SyntheticSceneConfig {
+                seed: cfg.scene.seed + (i * cfg.num_logs_per_split + j) as u64,
+                ..cfg.scene.clone()
+            };
+            let scene = generate_synthetic_scene(&scene_cfg);
+            let log_dir = split_dir.join(&scene.log_id);
+            scene.write(&log_dir, split_name != "test")?;
+            log_dirs.push(log_dir);
+        }
+    }
+    Ok(log_dirs)
+}
+
+/// Temporary directory of a test, which is removed when dropped (even if the test panics).
+#[cfg(test)]
+pub(crate) struct TestDir(tempfile::TempDir);
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+#[cfg(test)]
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+/// Create an empty temporary directory.
+#[cfg(test)]
+pub(crate) fn test_dir() -> TestDir {
+    TestDir(tempfile::Builder::new().prefix("av2_").tempdir().unwrap())
+}
+
+/// Generate a miniature dataset in a temporary directory (see `generate_mini_dataset`).
+/// Returns the directory and the log directories.
+#[cfg(test)]
+pub(crate) fn test_dataset(cfg: &MiniDatasetConfig) -> (TestDir, Vec<PathBuf>) {
+    let root_dir = test_dir();
+    let log_dirs = generate_mini_dataset(&root_dir, cfg).unwrap();
+    (root_dir, log_dirs)
+}
+
+#[derive(Default)]
+struct _LidarColumns {
+    xyz: [Vec<f32>; 3],
+    intensity: Vec<u8>,
+    laser_number: Vec<u8>,
+    offset_ns: Vec<i32>,
+}
+
+impl _LidarColumns {
+    fn push(&mut self, rng: &mut StdRng, xyz: [f32; 3]) {
+        for (column, value) in self.xyz.iter_mut().zip(xyz) {
+            column.push(value);
+        }
+        self.intensity.push(rng.gen());
+        self.laser_number
+            .push(rng.gen_range(0..2 * NUM_LASERS_PER_LIDAR));
+        self.offset_ns.push(rng.gen_range(0..100_000_000));
+    }
+
+    fn into_data_frame(self) -> DataFrame {
+        let [x, y, z] = self.xyz;
+        df!(
+            "x" => x,
+            "y" => y,
+            "z" => z,
+            "intensity" => self.intensity,
+            "laser_number" => self.laser_number,
+            "offset_ns" => self.offset_ns
+        )
+        .unwrap()
+    }
+}
+
+#[derive(Default)]
+struct _AnnotationColumns {
+    timestamp_ns: Vec<i64>,
+    track_uuid: Vec<String>,
+    category: Vec<String>,
+    params: Vec<[f64; 10]>,
+    num_interior_pts: Vec<i64>,
+}
+
+impl _AnnotationColumns {
+    fn push(
+        &mut self,
+        timestamp_ns: u64,
+        object: &SyntheticObject,
+        translation_m: [f32; 3],
+        cfg: &SyntheticSceneConfig,
+    ) {
+        let [length_m, width_m, height_m] = object.dims_lwh_m;
+        let [tx_m, ty_m, tz_m] = translation_m;
+        let (qz, qw) = (object.yaw_rad / 2.).sin_cos();
+        self.timestamp_ns.push(timestamp_ns as i64);
+        self.track_uuid.push(object.track_uuid.clone());
+        self.category.push(object.category.to_string());
+        self.params.push(
+            [
+                length_m, width_m, height_m, qw, 0., 0., qz, tx_m, ty_m, tz_m,
+            ]
+            .map(|x| x as f64),
+        );
+        self.num_interior_pts.push(cfg.num_points_per_object as i64);
+    }
+
+    fn into_data_frame(self) -> DataFrame {
+        let param = |i: usize| self.params.iter().map(|x| x[i]).collect::<Vec<_>>();
+        df!(
+            "timestamp_ns" => &self.timestamp_ns,
+            "track_uuid" => &self.track_uuid,
+            "category" => &self.category,
+            "length_m" => param(0),
+            "width_m" => param(1),
+            "height_m" => param(2),
+            "qw" => param(3),
+            "qx" => param(4),
+            "qy" => param(5),
+            "qz" => param(6),
+            "tx_m" => param(7),
+            "ty_m" => param(8),
+            "tz_m" => param(9),
+            "num_interior_pts" => &self.num_interior_pts
+        )
+        .unwrap()
+    }
+}
+
+/// Extrinsics of the two lidars and the ring cameras, which are evenly spaced in yaw from the
+/// rear left to the rear right camera.
+fn _extrinsics(camera_names: &[String]) -> DataFrame {
+    let num_cameras = camera_names.len();
+    // Camera axes: x to the right, y down, and z along the optical axis.
+    let mut rotations = Array::<f32, Ix3>::zeros((num_cameras, 3, 3));
+    for (i, mut rotation) in rotations.outer_iter_mut().enumerate() {
+        let yaw_rad = PI - 2. * PI * (i as f32 + 0.5) / num_cameras as f32;
+        let (sin, cos) = yaw_rad.sin_cos();
+        for (j, axis) in [[sin, -cos, 0.], [0., 0., -1.], [cos, sin, 0.]]
+            .into_iter()
+            .enumerate()
+        {
+            for (k, value) in axis.into_iter().enumerate() {
+                rotation[[k, j]] = value;
+            }
+        }
+    }
+    let camera_quats = mat3_to_quat(&rotations.view());
+
+    let sensor_names = ["up_lidar", "down_lidar"]
+        .into_iter()
+        .map(|x| x.to_string())
+        .chain(camera_names.iter().cloned())
+        .collect::<Vec<_>>();
+    // The lidars are aligned with the egovehicle frame.
+    let quat = |i: usize| {
+        let lidar_value = if i == 0 { 1. } else { 0. };
+        [lidar_value; 2]
+            .into_iter()
+            .chain(camera_quats.column(i).iter().map(|x| *x as f64))
+            .collect::<Vec<_>>()
+    };
+    let num_sensors = sensor_names.len();
+    df!(
+        "sensor_name" => sensor_names,
+        "qw" => quat(0),
+        "qx" => quat(1),
+        "qy" => quat(2),
+        "qz" => quat(3),
+        "tx_m" => vec![1.4_f64; num_sensors],
+        "ty_m" => vec![0_f64; num_sensors],
+        "tz_m" => [1.9_f64, 1.9].into_iter().chain(vec![1.4; num_cameras]).collect::<Vec<_>>()
+    )
+    .unwrap()
+}
+
+/// Two-lane road along the city x-axis between `start_m` and `end_m` with a pedestrian crossing.
+fn _straight_road_map(start_m: f32, end_m: f32) -> VectorMapJson {
+    let point = |x: f32, y: f32| MapPoint { x, y, z: 0. };
+    let num_segments = ((end_m - start_m) / LANE_SEGMENT_LENGTH_M).ceil() as u64;
+    let mut map = VectorMapJson::default();
+    for i in 0..num_segments {
+        let x_0 = start_m + i as f32 * LANE_SEGMENT_LENGTH_M;
+        let x_1 = x_0 + LANE_SEGMENT_LENGTH_M;
+        // Left lanes have odd ids and right lanes have even ids.
+        for (lane, y_left, y_right) in [(0, 0., -LANE_WIDTH_M), (1, LANE_WIDTH_M, 0.)] {
+            let id = 2 * i + lane + 1;
+            let neighbor_id = 2 * i + (1 - lane) + 1;
+            let lane_segment = LaneSegment {
+                id,
+                is_intersection: false,
+                lane_type: "VEHICLE".to_string(),
+                left_lane_boundary: vec![point(x_0, y_left), point(x_1, y_left)],
+                left_lane_mark_type: match lane {
+                    0 => "DASHED_WHITE",
+                    _ => "SOLID_WHITE",
+                }
+                .to_string(),
+                right_lane_boundary: vec![point(x_0, y_right), point(x_1, y_right)],
+                right_lane_mark_type: match lane {
+                    0 => "SOLID_WHITE",
+                    _ => "DASHED_WHITE",
+                }
+                .to_string(),
+                successors: (i + 1 < num_segments)
+                    .then_some(id + 2)
+                    .into_iter()
+                    .collect(),
+                predecessors: (i > 0).then(|| id - 2).into_iter().collect(),
+                left_neighbor_id: (lane == 0).then_some(neighbor_id),
+                right_neighbor_id: (lane == 1).then_some(neighbor_id),
+            };
+            map.lane_segments.insert(id.to_string(), lane_segment);
+        }
+    }
+
+    let crossing_id = 2 * num_segments + 1;
+    let crossing_x = start_m + 2. * LANE_SEGMENT_LENGTH_M;
+    map.pedestrian_crossings.insert(
+        crossing_id.to_string(),
+        PedestrianCrossing {
+            id: crossing_id,
+            edge1: vec![
+                point(crossing_x, -LANE_WIDTH_M),
+                point(crossing_x, LANE_WIDTH_M),
+            ],
+            edge2: vec![
+                point(crossing_x + 3., -LANE_WIDTH_M),
+                point(crossing_x + 3., LANE_WIDTH_M),
+            ],
+        },
+    );
+
+    let drivable_area_id = crossing_id + 1;
+    let half_width_m = LANE_WIDTH_M + 1.;
+    let end_m = start_m + num_segments as f32 * LANE_SEGMENT_LENGTH_M;
+    map.drivable_areas.insert(
+        drivable_area_id.to_string(),
+        DrivableArea {
+            id: drivable_area_id,
+            area_boundary: vec![
+                point(start_m, -half_width_m),
+                point(end_m, -half_width_m),
+                point(end_m, half_width_m),
+                point(start_m, half_width_m),
+            ],
+        },
+    );
+    map
+}
+
+/// Random version 4 UUID.
+fn _random_uuid(rng: &mut StdRng) -> String {
+    let bytes: [u8; 16] = rng.gen();
+    let hex = bytes
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| match i {
+            6 => format!("{:02x}", (byte & 0x0f) | 0x40),
+            8 => format!("{:02x}", (byte & 0x3f) | 0x80),
+            _ => format!("{byte:02x}"),
+        })
+        .collect::<String>();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Write a little-endian `float32` array in the NumPy `.npy` format (version 1.0).
+fn _write_npy_f32(path: &Path, array: &Array<f32, Ix2>) -> Result<()> {
+    let (num_rows, num_columns) = array.dim();
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': ({num_rows}, {num_columns}), }}"
+    );
+    // The magic string, version, and header length take 10 bytes. The header is padded with
+    // spaces and terminated by a newline so the data is 64-byte aligned.
+    let padding = 63 - (10 + header.len()) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut file =
+        File::create(path).with_context(|| format!("Failed to create {}.", path.display()))?;
+    file.write_all(b"\x93NUMPY\x01\x00")?;
+    file.write_all(&(header.len() as u16).to_le_bytes())?;
+    file.write_all(header.as_bytes())?;
+    for value in array.as_standard_layout().iter() {
+        file.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::s;
+
+    use super::{test_dataset, MiniDatasetConfig, SyntheticSceneConfig, LANE_WIDTH_M};
+    use crate::{
+        data_loader::DataLoader,
+        geometry::{camera::pinhole_camera::PinholeCamera, coordinate_frames::LogFrames},
+        map::load_vector_map,
+    };
+
+    #[test]
+    fn test_generate_mini_dataset() {
+        let cfg = MiniDatasetConfig {
+            split_names: vec!["val".to_string(), "test".to_string()],
+            num_logs_per_split: 1,
+            scene: SyntheticSceneConfig {
+                num_sweeps: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (root_dir, log_dirs) = test_dataset(&cfg);
+        assert_eq!(log_dirs.len(), 2);
+        assert!(!log_dirs[1].join("annotations.feather").exists());
+
+        let data_loader =
+            DataLoader::new(root_dir.to_str().unwrap(), "av2", "sensor", "val", 1, false);
+        assert_eq!(data_loader.len(), 3);
+        let sweep = data_loader.get(1);
+        assert_eq!(sweep.lidar.0.height(), 1024 + 4 * 20);
+        assert_eq!(sweep.cuboids.unwrap().0.height(), 4);
+        assert_eq!(data_loader.get_synchronized_images(0).len(), 7);
+
+        let log_dir = &log_dirs[0];
+        let frames = LogFrames::load(log_dir).unwrap();
+        assert_eq!(frames.ego_se3_sensor.len(), 9);
+        let camera = PinholeCamera::from_feather(log_dir, "ring_front_center");
+        assert_eq!(camera.width_px(), 64);
+        let map = load_vector_map(log_dir, &root_dir.join("maps")).unwrap();
+        assert!(map.lane_segments.height() > 0);
+        // Centerlines run down the middle of each lane.
+        let centerlines = map.lane_centerlines(5).unwrap();
+        assert_eq!(centerlines.shape(), &[map.lane_segments.height(), 5, 3]);
+        assert!(centerlines
+            .slice(s![.., .., 1])
+            .iter()
+            .all(|y| (y.abs() - LANE_WIDTH_M / 2.).abs() < 1e-5));
+        let mut points = centerlines.slice(s![..1, 2, ..2]).to_owned();
+        points[[0, 1]] += 0.3;
+        let (ids, distances) = map.nearest_lanes(&points.view(), 1.).unwrap();
+        assert_eq!(ids[0], map.lane_segments["id"].u64().unwrap().get(0));
+        assert!((distances[0] - 0.3).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,1076 @@
+//! # tbv
+//!
+//! Trust, but Verify (TbV) dataset layout and splits.
+//!
+//! TbV logs are stored in a single directory (e.g., `~/data/datasets/av2/tbv/<log_id>`) with the
+//! sensor dataset's log layout. The split of each log is given by the log id lists below.
+
+use anyhow::{bail, Result};
+
+/// Dataset type of the TbV dataset.
+pub const TBV_DATASET_TYPE: &str = "tbv";
+
+/// Log ids of the TbV train split.
+pub const TRAIN: [&str; 799] = [
+    "wwZ8efHWR8Rk5bgf78abyVPsE5KWJxYj__Autumn_2020",
+    "i0wSmy4ev3smqSYhVl2cUgtD3CUZ5p4Q__Autumn_2020",
+    "sacCWCoBKEpKMGmOitZOjkpoYrne1vuB__Autumn_2020",
+    "uUP2mRIdXuBJgqPOw5lYBq5wHAPzZ15L__Spring_2020",
+    "IA6qK2kZYhFkYdtJOiIKEZt0OP2qsIhR__Winter_2021",
+    "MMT6ZnCLWyMnacsnIkXuvm2monGPrA9y__Summer_2020",
+    "OBLhcOEzl44hDkmmuob0DoRkwRV1IYvm__Spring_2020",
+    "VjKJdI2wslXLG8tiyIiOP6UVzg2Z4tnm__Winter_2021",
+    "PxCNWHEHNYenjKryBO1XwdO3igRMZYAm__Autumn_2020",
+    "zsbd9Um9DIcF181WLqPOx0OFHK7AOpV6__Autumn_2020",
+    "uV9MkgxW81EtbE7s2kWhayojpaBG3UfJ__Spring_2020",
+    "Tp3lETeLvPZZnYrhBc4prSaqCLWBUHQO__Winter_2021",
+    "oN364EEFI3GeUwrxaOT21NdNIGdA7zjH__Summer_2020",
+    "nksb4m8MngpMzRhxQcc8BcwOdgPYcLTL__Summer_2020",
+    "2iR9WaP4PjcJqCFfFiBbnQqKevADzaYQ__Spring_2020",
+    "SpCtxpQGxqQ3jNQvl8OyzH2m9MKUFQFI__Autumn_2020",
+    "bRSrYHoydiVs4m59fQZIaTpR9cPNnvly__Autumn_2020",
+    "lcCBKUmx89uFRLRC9sb3lTYIDNMJ0jCX__Summer_2020",
+    "2BkCiI45PU2Gtj9716t6V3UccKKAsxkD__Spring_2020",
+    "jTkcvsmio75RlkMw6yw6FCrNdFiH34e1__Autumn_2020",
+    "vlYt7CadIoaIx8fi1yv6fBSWuX3G5fmj__Spring_2020",
+    "nhB8oec3VchfV0yAX2p7HZTBHR6q6Q9D__Autumn_2020",
+    "FlSwXC3QbwFWSB3K72eXKqzDv63GmuvC__Spring_2020",
+    "vC1g2YDqu8uN8ueUCW4yEDruDZgN6BV7__Spring_2020",
+    "2U1dwmeMRA4i6Rnjmbt0c5COxSs48oxr__Autumn_2020",
+    "cSLEBr2jx2uX7PJWh0RPCUcO6o6Gr1wE__Winter_2021",
+    "Oe7M1OlKoZiDxW6Yowl5qgRpPeYmAmql__Autumn_2020",
+    "QNBpKJzxmyddyVMkouKlkZMOo95ERqdj__Autumn_2020",
+    "pfEuVJtEU5I25sqGhREBb2SKpzpmsPhC__Winter_2021",
+    "yEoG5C29D3Hnm2yvz1RFkKwHjYtGnlF0__Spring_2020",
+    "4Smff1QCLOVvoG6m0m4O4IlkeQWkck06__Spring_2020",
+    "4DLtPViY5sLp0u38HNXqjYH5KYuxVdE5__Winter_2021",
+    "mMGAbFPNgG1ZBWjXjjNAOj2heUDJtTLo__Spring_2020",
+    "mV8Ho3BChY2irFvzeoBeoUxbDYvSlio4__Winter_2021",
+    "Z74FYa0dX3sAY57WHwTavT36VL0rK31Y__Spring_2020",
+    "F3pfoyVhH8Mn3kD5ihKWODnr1mxVStPY__Autumn_2020",
+    "6TkOfMTvumeVM6lOvME9lAYHK3H5Xxwe__Spring_2020",
+    "kAjHseM7YsYyjI8LJ0vunSq38lOkjCL1__Summer_2020",
+    "nBFIHVuWRyo3hvSuFlfHUVWWyy4WkoAX__Autumn_2020",
+    "PbKQpiBjx6F4XcxFjjADQVcf2nYcf5oN__Spring_2020",
+    "DA7apdHelUKICdLUSjTuhBdb3YQEqfkF__Winter_2021",
+    "4w1bvpoOG57GNZqUWzp4Pls1vNMnQPIT__Summer_2020",
+    "uN5wqrzaoz3WI9yczzkNEH3wjThdfBlR__Summer_2020",
+    "zwDPRWBfzPONkUuJphTZCrPqWnavpSyj__Spring_2020",
+    "ekRKJsCtpr9QMghpqQGyC685YBvl3fdS__Spring_2020",
+    "0o93nP9r00gjOTeCuZYMzfThO7NGwvqM__Autumn_2020",
+    "Y8N50F2RkpXZMWkKXTVcLHGR4oPvktdg__Autumn_2020",
+    "BRjG4WGW0zNteExIqb6tNF7uyIbRrsoL__Summer_2020",
+    "bYbAavDQTnzElsT1YzfBkj731IO1JDfS__Summer_2020",
+    "N7Q1vcPYw2xSonND8QhQXGo7YDnIVs35__Summer_2020",
+    "sNEB95f51gmwz9oOJdA8AuOy7UKCHSTV__Spring_2020",
+    "FcS2OrsKIRHtfgeNu7gvYdWNrsRlYRpC__Spring_2020",
+    "8nJWS9DfEqrtcvBcMfrPCOaQACTB4KNs__Spring_2020",
+    "iT9gIaKgqbNDRj1vCSJMIs1iyvOLw0tV__Winter_2021",
+    "EgMkum4BTgbLFpoIzh3qqWyNibmPs5vU__Autumn_2020",
+    "jWIKvRJJjux6OX8E89VM3pKRajPUveQ6__Autumn_2020",
+    "fDy5K8pRAzNx6Gl5De4yXIWo1vY742ZS__Summer_2020",
+    "cuSKGBB0zXQbpDNZIkdraWkTYtcUPVrA__Autumn_2020",
+    "EuBM8jErVsSORescXdD4nQ2T0F9S1rx7__Autumn_2020",
+    "GFoRzloJNpA56Xymm5pX7ufbqvLgQNPO__Spring_2020",
+    "XGdQwnoIYz4DbZ2MB3rcrqh5JawoOyih__Autumn_2020",
+    "1aA2LN2wTD5Z2fRPdvqmDE85eT7kf5Zo__Summer_2020",
+    "5TRwJonP7AqixSzt47Ato5SuCIe7eq11__Spring_2020",
+    "OCaNX1bQSmlP3jEQH80C0TZYzZhKLV81__Spring_2020",
+    "u9sfK9gqkXuPi4Y6TBai26hfeSSYUNN8__Autumn_2020",
+    "AtYSB3LuNBwzbYFOxBePVkbahpDGLiYi__Summer_2020",
+    "iOcBZ93uoTCj7zgTvBPFCk7LgxLX47FL__Spring_2020",
+    "ssqwXLp2yT1Q3J2OVKmgJt1D9dLSKos4__Summer_2020",
+    "K5vvuR9yFtN4pKeS94eC5I30SuezcseM__Spring_2020",
+    "LjMGn26GbOTAQBOvVjt2icXCUX8JlWOP__Winter_2021",
+    "nzXlXhSw8fbLr4RLVAI68ORzyu3McdXh__Autumn_2020",
+    "R1mHNTUoAv9pSqhwu1mE4BEbQzNjZqVi__Spring_2020",
+    "HudrxlUyUn5SPhfqLIV6FfGIgMssXRnw__Winter_2021",
+    "2Ist0LOT9h4dc4NNFmoASoQ11LUPHTkP__Summer_2020",
+    "iYgqdMf7r45rSzv4DBYwHrH6kHTy4zwU__Winter_2021",
+    "KvNfRQSIgGMqADWVqLNSzaRBKT7KJVW1__Summer_2020",
+    "uhNynyAgPRsskL84Mt9JaRcyS5aDvAX5__Autumn_2020",
+    "9W5VLd3rCZksMBcd7tdjHMnZOWwIsyyO__Spring_2020",
+    "FfJ9NkcvDVic5SZTP7Irggmg50pX7IRp__Summer_2020",
+    "vOcgLgdc3ORIrTEDF7HPW9VEtZ9GQeQb__Spring_2020",
+    "Cw84lhd9ZvsoNCsk11S2CL2AlOSi8smO__Autumn_2020",
+    "6K0pdKKoPuggFCpignZMgB9PVRoYqC2J__Summer_2020",
+    "eZv0jiqZPYeGpTAgZHKHESpV4EruXTZv__Autumn_2020",
+    "59s9iSEJUmByTmcKTzRmAEqCcX1a5ABx__Spring_2020",
+    "rqZfx6sF7EriTsIEpDYTdszi5GRtWaV7__Summer_2020",
+    "L2QTLtRC2jKHGOuwqze9R13aFZYZQfZA__Autumn_2020",
+    "3pIbTbOgjtMdOj7rUFpng0GJy8cPVz0j__Summer_2020",
+    "aR41bAMTMTsAbyjz2vx8TpnVoGxDgstI__Summer_2020",
+    "rW7ooP3JrPNGXWlDSp2p6I8nxqy5HUzR__Winter_2021",
+    "ZIgrzwUog70G7BL5jwUeJixUX6qEWIhR__Summer_2020",
+    "jVQXlLNi4OPA3jqh1RUiY6MCBCTgNMkP__Winter_2021",
+    "Dz8mRbC17g2c6OOHLmGK5vNfi5ihzhyo__Spring_2020",
+    "Cz5X38WYpobDyfIl3tLiwk13GvA1Sub7__Autumn_2020",
+    "7YRWm9Irq4s9v9uwTqF7mly5dIFr4L0O__Autumn_2020",
+    "n4AaSlCrAMNwT8HrNY7PJltPH3pV61Uh__Autumn_2020",
+    "tMHbAXBFNGr5ykkR6bMfJfT6lPE3Mtc1__Summer_2020",
+    "MCA7E2vORmBAz2jeLSWrBKgvoWljtFnn__Autumn_2020",
+    "N1FSwwo379RIWXuMBsCyGFlqYmkG8A0X__Winter_2021",
+    "msguKLsW9PP16pT8r6RbqUTaoUetNieI__Summer_2020",
+    "GZBMRqSE2uwqqfxPyKNklmKwIHOq4tgd__Summer_2020",
+    "srbhDp8H4IjTQ83i3brbRzoeHJQBBGRQ__Autumn_2020",
+    "jkvuDKG7YC5k5d86IIgclR2DFNOGlCE9__Spring_2020",
+    "6cgsaV0ZC83JTRf4ZhmplHaru5CCSe1c__Autumn_2020",
+    "g6RZAAgvZbklmRQW057R9RtRRUW5FiCp__Spring_2020",
+    "12ClGooZWzAdgpy9ljqlxMCvkJxiy8wt__Autumn_2020",
+    "u0hb5p73TdT4mRMdvuSQy7wWpZSd6W5C__Winter_2021",
+    "sReLZGvYMt0PvT8AUSd7rpTwnMHDoVdH__Summer_2020",
+    "o4WogXUVy7eZOI1lW9bGRBFWjJ74ffBW__Summer_2020",
+    "rWoP3XKOvMfFec02xMVMGMXZ5Td9Vcd9__Spring_2020",
+    "yk7Q5Uh88eGkrJg1s7uDobV6rdzQG7pN__Winter_2021",
+    "2u0s9YkvcHampDQH6jPdUyVHmYlXpvmU__Summer_2020",
+    "JzRmW2QLa4IoCMSGg9wEg7mkTDaOgI0p__Summer_2020",
+    "2YRBX1AJs3vDm3T36PPpdk7BuLnwTnhN__Spring_2020",
+    "8CnBQUbhC5RECqcu1pVpkPa0lhRieOsj__Autumn_2020",
+    "0IHD1W1ypZyr2F1I2aA6n5SiRIOZ87RX__Summer_2020",
+    "lENIfBMhNvKmKCH1ybOEfLDMXvxLsnfj__Winter_2021",
+    "v2ccE97z1V5XnVJlMpBdF90ee8XLFWUr__Spring_2020",
+    "8aiL8AHVzFZRIhULgOzn7V651uqKihNx__Spring_2020",
+    "GU5b5y1yMJtYjs4MTwHqt8MPE628r5TX__Winter_2021",
+    "LXA9iir5CovhIlZFgUrl4Z4Qw31zpCHB__Winter_2021",
+    "f9rWQkXwYNeykXL1voUO4BXQVyMlIxkj__Spring_2020",
+    "wQuqJZOzsghLfwXZu1Nc5Mz2p9UYAnh8__Autumn_2020",
+    "MtxDY7fjCJbVxNGmW535vCHIQqcprCfH__Spring_2020",
+    "ILbWXlMgdZOEaNhI5GqpDIQ5fIjcQPpG__Summer_2020",
+    "gSOU2jpGZiprF5QtzxDQTpmjJLeristC__Spring_2020",
+    "wGnPCNq6BXeh3cLAtKoE6NJs55p2lcbA__Winter_2021",
+    "Jz65Qq7RcHfs92P3czukhfjS5JmES42a__Summer_2020",
+    "Oe1cbEQhfk2js42iBwTx2QRb23M30Rev__Spring_2020",
+    "43Q7VMBi0lN6hPdZPq57DtLP7hIIjeuh__Summer_2020",
+    "zoKZZpEsk9QtUmm62mNXzu94T7RmoXxq__Autumn_2020",
+    "QyvAGixkSs9iFrnagzuZ4Iju9zp1ED9e__Spring_2020",
+    "ad4hWl8hOsE7KFrk5o1h7nO9LgjWAssQ__Autumn_2020",
+    "m5Fgbp7xTWOqC4ps31OKJkqmWvtvAE5v__Winter_2020",
+    "jVyK3xUEG71Bmfzpqf1ahM5SAo9JRm76__Spring_2020",
+    "Zb2AqJrQ7JzcBHluaQVekUXk4Y2hGf64__Autumn_2020",
+    "2M2gvnw2o0IlxViTtiJPHeK1PANqGZx4__Autumn_2020",
+    "7nQ7cpFdF7GFGX8m1QTWE2jNhLqERcny__Autumn_2020",
+    "8yYa9NbAfXocK6TjU4mZLm0CcDm5Ixb7__Summer_2020",
+    "d54DdBePp0UniJgNDB7jxcnpbFySleHQ__Autumn_2020",
+    "qStJAH8GoFDmDR5OSbyFYQOsKkPxGDBF__Autumn_2020",
+    "MNiS3W3qdeYjb9qGFJN7mXr1329kNpom__Spring_2020",
+    "AdWmNj06mUDfUMZPszsFVpGIocm4Njq9__Autumn_2020",
+    "e25a23f2736e4cf79ab14746213e0c4f__Summer_2020",
+    "WJa3xuvODwNXuGua0zDqzPeoBBNKXoZS__Summer_2020",
+    "dnHmxALz44sIj625WeJp8rXobEPnOMZE__Autumn_2020",
+    "BxClSFM3d6RH9AgvHpUgxLT8yFftF7qQ__Autumn_2020",
+    "TBfbZj6zghBZSHjd7DzehTpM1Roc7QI5__Summer_2020",
+    "VoiQzblgGku27OgI8SQRbTD88Iki5BNJ__Autumn_2020",
+    "cmS9Zy4jqgDpN5yhuGCyc6hyCPtP4gJb__Autumn_2020",
+    "QVCZXFVAjNsVNLHNGmKdUTT7bUE9ijZv__Autumn_2020",
+    "hvZb5AP1p7Vt3n4ptc6O5h57tMI3kjzR__Winter_2020",
+    "PKlPJ9EhLZSWjouOesHCEDmmKNX7CsDJ__Spring_2020",
+    "DOIe1yPEzcqEhC1i0TrAb5hpZmrQteKw__Autumn_2020",
+    "FMCiiBWLW9wgEu6aao0JZ7xfgccr8siF__Spring_2020",
+    "epP5qEFrlACkpnlLSBU6Ya9dT86gLxdQ__Autumn_2020",
+    "jUJ25tYCN9xd9IPZcqasNGxrB4DENd7I__Summer_2020",
+    "tGSOZLU3cBbcg6unWNAMf9JF1bY1q1Mo__Summer_2020",
+    "9kwDkJQF7uB2s3I3VVZ3tpDYkMzycyrB__Summer_2020",
+    "8ztEmB6IinDiEcDkiFqeCwLlgNbbjyIF__Autumn_2020",
+    "xoKpU4AFAlYMWKldGrvHKuc08NO0J1hF__Autumn_2020",
+    "6Q8FSHvI2OOFOWocOX2isFpfpYFMJxwp__Summer_2020",
+    "6962AgjQzx7Z0lzMzNiapDDhlKBGs4G9__Autumn_2020",
+    "nWgl3USHqtDqpENyaWZJUJMx1Bw4VAeL__Autumn_2020",
+    "kfRo8pecRJ0LOghhr3eUsgJ7HmAnkGcp__Spring_2020",
+    "Tt7Vi2hWMA2AbosXwGT6GAVWsCDFHity__Summer_2020",
+    "7ewpqNNy0TKPTYO5nhkMMEPB9hckrHxE__Autumn_2020",
+    "9zYAYvPcQf1YVpmPxhgTK33BCyltATAz__Spring_2020",
+    "lVJviGPNt3zxcL2gE7PCZ7biOcalDs2u__Autumn_2020",
+    "Ru6t9xSgAAPcEFBUbN0TArvJGjmz0e1q__Winter_2020",
+    "yrWdQigzgiBMCXi6sQ1DtlysZIasAqoC__Winter_2021",
+    "wYhlzknS3DAxjlQuuNzdy29J8FjSZkIM__Autumn_2020",
+    "GlVGf5R0DHMTsSeMEWndaianKYmqcggq__Autumn_2020",
+    "HGXrsfVTH8d0NckISP3DcUoNR8hu8KNm__Autumn_2020",
+    "xjgzbgzcH5T47fovbontrdYopKVhCd0r__Spring_2020",
+    "5yrMSbkG8VcP3xwiuvVdHZuX107cUJn6__Spring_2020",
+    "4ce563f633a04d34a7a3b31b6adbb0c2__Winter_2021",
+    "pbjqZAmtTb7uV00Vzf282h2XfAv6zceC__Spring_2020",
+    "ms6oTo7vViiBIA6I1jM5HNcfYoUjJeav__Spring_2020",
+    "NvltLahhSbLMpPwLQyJWuVVfa6rU9eZ8__Winter_2021",
+    "435ECsWqEv0mmwp7mPd0rHP74w8EbWN9__Autumn_2020",
+    "QfL7oN7C6ZerylyLD5bflu7fnlEbrls6__Summer_2020",
+    "St6dYJFQnfUEBFPiZYRlDbpi7sK9D80q__Autumn_2020",
+    "ZcCGm0df3aTi0muMUYcKRZV8wlTMshJT__Autumn_2020",
+    "zVN0q6wJ4o8j50G3AeRB6DZrmAWt5wzW__Spring_2020",
+    "IzhNetLN31Se58oWqvWsaJM30ALjS3UW__Spring_2020",
+    "rkuRgsKhAgodOFGbCb5dluD94CZD4n5K__Autumn_2020",
+    "vpZOcBT9yXTGSsvlwQGNk2DVxMuSnuXd__Summer_2020",
+    "VUxFpcEbhyEDnNwV8w3qNYP9PPH2opHG__Winter_2021",
+    "lluTAJTMf9yELZ9Boc4ZmIpqUa81pApp__Spring_2020",
+    "Q2JzYcTuRa6mfv2Fkhyq3c1ofyIz6qej__Spring_2020",
+    "Y3jvE5mWWJ0NoOLW8WOke5NAE82wL20O__Autumn_2020",
+    "EbWyP7pZFQJD6ixm5AyWcUE6jbOVfwJR__Summer_2020",
+    "AOA5PSqrRXGO3YTtQINFZAddtxhZPVSD__Spring_2020",
+    "0Zs4IX50w6IbmSGnMBo0dIUyi2fpbgBF__Winter_2021",
+    "hTr5fN19In2dkJ8JXNSNusnnVNMRqZt6__Spring_2020",
+    "Qn2tNqunbv80YS0zP1Ns4JvLdHrboSGP__Summer_2020",
+    "TofuOFJ350LguZRD5bxbVNzPMfS6CBR8__Summer_2020",
+    "fVu2ph2szIbnZsDQsjlKO3axpMvwIzbk__Autumn_2020",
+    "G2xkPrWPEXCnDPlI5xS5BEMDWPqRHWP7__Autumn_2020",
+    "eWLMzuqxtXXCB81DROvhZayC8d7i6RyS__Autumn_2020",
+    "F6ZWoR9kHt0hZNpl957LNoL5X4zXxU4T__Summer_2020",
+    "emWrxk9zPEMeHeHnecB0fvJ09CoAIbQQ__Spring_2020",
+    "kQ3b5uLsBQjlHgtBBgNeeDiKQU0biayP__Spring_2020",
+    "WSFwCxP7Aof9HPDrW9R8oajFS2FfN3TB__Spring_2020",
+    "jIzJ9cAylyFAIeloGVQF8P5vKfK4XUMK__Autumn_2020",
+    "z2rm1VqmEd5C4w6sTGV4zCCL1wRF2qc4__Autumn_2020",
+    "QzXS3CMTCPgJ7auGsPKPg6GDONmI4Kcw__Spring_2020",
+    "L7mPbb4aHvRTJyGfFbjWzC9aQPKr4nmP__Autumn_2020",
+    "exklXpaGGa9hTAJ2N2DdCU0LwCjfDzbn__Summer_2020",
+    "12HJxaE7tpGLNinmJ2uTZT3jY4d084Qx__Winter_2020",
+    "xY4ckuhN3hrp3xFWD8hxl8DHM3BIojEe__Summer_2020",
+    "qrxPYKOTarzF3Kzsg9Bd77ZBnykdlv5J__Autumn_2020",
+    "0We5t9hiPmE10moaFT87PUUCdPVhIcev__Spring_2020",
+    "OZd6LzNrubFUAHAv0Cyg03meWfTTpHU7__Autumn_2020",
+    "mIqFih8ekgliTFuABqSIuO0zHTvmCiHd__Summer_2020",
+    "wmYAVaJolsOkFCGq0Yf3LTWhfhqZlefk__Spring_2020",
+    "SMs3LGlUBjCkcCKKs7o9zzcXgZKLt1XZ__Summer_2020",
+    "WSPkEzMB3QkOV6FWHM01CbFY5BOTQzqq__Summer_2020",
+    "6T1aikmJAhnsCKFnyI8Tl1jPn9Kbt73u__Winter_2021",
+    "zo2rjie29EMb4RmczU82bz2ZOJHlwe7K__Spring_2020",
+    "qZfSxuhEaYUVvLRtmM7ccS9zX0G8ttVL__Spring_2020",
+    "Zmx1JIZ5OnXlHU0JoaclPUU9UKIG7NEd__Spring_2020",
+    "0lkaQo076gxJG9XVgVdU66IFKBUxrEdE__Autumn_2020",
+    "GI4HpJyQKn1oDWFYhTX5v8XQpqLRKbJY__Winter_2021",
+    "SfRcCh8L9mFyW5SwPGYiSMt7En3zLG61__Summer_2020",
+    "kSc6QTUguKHTRNxFuzgi7GwlLiV7zU8t__Winter_2021",
+    "kQ5TX0OOPYl8aEKeLtp4iQ499hWMqCD5__Summer_2020",
+    "UxXAQBDRFzeMESuI7Oq73nPszKyG4gG3__Spring_2020",
+    "pzMQ3MHvRbZ0kvbb4BPlD0nCoyQQMryn__Autumn_2020",
+    "VvgE5LfOzIahbS266MFW7tP2al00LhQn__Autumn_2020",
+    "sQb3uKDLmeY2oxVLdLCPo2xXTkL13Pp0__Autumn_2020",
+    "RV7jWHo4qiyrdFZnhtgLNT3X2doYzNC2__Autumn_2020",
+    "7RpWJqCSS4YdbvJNopbF0FpgQSXi0fG1__Spring_2020",
+    "H560MhGOtmPfGGJl5lNo5Vre9ZeaQaYI__Spring_2020",
+    "PsPSBvvRxQpMrw7HyrtWgNDPJzMWIeQx__Spring_2020",
+    "E5CXvlueoeu69UNf4MuH81pqDHcSll8C__Winter_2021",
+    "CkkHKsV9W3dJPPAEWx1IJpR5Zy7hIURz__Summer_2020",
+    "fxHdNJaRlVadVcOOnrQVe89dc6XGu3R7__Autumn_2020",
+    "hzXm8XP4Qbg70hNukKNDOAttanYj6ui1__Autumn_2020",
+    "rdOfWw2uN4ZyS1zVtXMtqLyqiZKWLQCE__Autumn_2020",
+    "OwSGOnirGKbLmPr8SiDKUkdkAJHmbLDX__Winter_2020",
+    "Frd9FA0zQ8HolCJYpTHzjyhzD6hTejwt__Summer_2020",
+    "PW7PUx7SVWBdgobu3VvLtUpOUhbLGHvD__Autumn_2020",
+    "tpc4pyWoVBLlWctqJX2c7y1fnjdHoMdg__Autumn_2020",
+    "2DYjREaZgWJd5xYP6C76omNR1GNdtIys__Spring_2020",
+    "AGWMYmeRjQJskS49xelkPUaLk7Cq0Ju7__Spring_2020",
+    "4BAsbo3Hj6dm5Rsx7WnpuGDSDHcPXfXc__Winter_2021",
+    "Ef28lDhF8MGAIoh05V4Cw8OVWmr2JXjr__Summer_2020",
+    "9qV8W20AVayoWQZEfjou0OrQ8luNhTj8__Summer_2020",
+    "1ZHV39J5CEsYNiamsaR6u7PIIIOxdInn__Autumn_2020",
+    "TYiCqOHahpzvoxr9A9Q3y7AHJiiYojL6__Winter_2021",
+    "vwrnKFEv0MGpPq8uSF9S2H10itsTsmmo__Spring_2020",
+    "vnuliSl8TCiFe9INyVnrto7GIK5Xk75A__Spring_2020",
+    "GFncGmNnOVAN6Sb1jcOZX8eet53ExHyE__Winter_2021",
+    "OX2fU9Ws4Z5IIcy6AxiCho9eUWxOWvSm__Autumn_2020",
+    "vQWdXHsyUq7yfVcdqe9Wr0GgudXz8fF4__Spring_2020",
+    "YJEAMY7WXo15ZPn0DjQzb1jhDnrvLkRx__Autumn_2020",
+    "eTcDN4N8nf8tSbrvjHxvgImsZZrHY15O__Summer_2020",
+    "d8Sq0RF09MpwY2nvPWTL10WOeUKJRoag__Autumn_2020",
+    "VnpMskbiJqBLoRfjkErUZAzVbQxhiw9E__Winter_2021",
+    "FK4gVrnQshetpGrBEkJVWL4fyQtUDmsj__Autumn_2020",
+    "MaYBy52VXjHD2TD50aYGusHzHWfBHJfS__Winter_2021",
+    "GPqXRTdUB9TYzNcZDUU2NklFxR4Ucqvo__Autumn_2020",
+    "FGuBXzCbLFO1Lh095B07MjDro886TVtq__Winter_2021",
+    "TlhsnWuDKsNStTfaWG1hbqHv1ZXirQT4__Summer_2020",
+    "9UBvbJls7RHuq82OwTlYglQhAni7Ks0u__Summer_2020",
+    "aeAbdF8JUt0p6FZmnIRgKa3F8cfObFYb__Winter_2021",
+    "sHFvOsKswG1BTBM4qltaLvYHxkMBlYez__Autumn_2020",
+    "RsnVdP5zLSzBEltGYGuVYySlGYJ4dCdz__Summer_2020",
+    "HyScasKQ9a8wSmldKcnmwzx6xv38Nhxa__Summer_2020",
+    "JHSV9PAqPo0XaWYnFu9tZrW962m40Xlt__Summer_2020",
+    "feo9Pz5ps4DEvWW93x6Ag3qj1S79Uuo6__Summer_2020",
+    "JjEmX4SskkCjDHyI7lqyy7kyEvnOjk75__Spring_2020",
+    "8AXY9QlDEjjm3LWI8HAZqVfH5q0Gm6Ty__Winter_2021",
+    "07YOTznatmYypvQYpzviEcU3yGPsyaGg__Spring_2020",
+    "xrLZipJZzQqfzqBKyA9fcb9ZzgIXds91__Winter_2021",
+    "W81yDzmeaEw7nBBzlD8NLd2zU1dmFyx8__Spring_2020",
+    "sbXYxIg3JiMzKd8wA5jsJVD8pNGUbWGK__Spring_2020",
+    "7WSMfBEq7Czk31TGaJlVNhPSYTXAVRIw__Autumn_2020",
+    "YsSCymjHAxbJ1WFfMaoQsuPpnQywEekT__Autumn_2020",
+    "i9oFbSMijEfkJ8GBNoYCO9h20309RQkp__Spring_2020",
+    "18qGbmGQLzc7DwlN5XkurfMXpLCimupL__Autumn_2020",
+    "fKo3aIVkhrSjOkGk9WykILHRiyWVo2XP__Spring_2020",
+    "5pF5qTHYp1Q6NqJmaCGXVmSRJIDvo2fs__Autumn_2020",
+    "YA1rvbtuF9JG5V8aAhdZ4xotiwHycN5v__Autumn_2020",
+    "YEgKmwTVXNfJDu0anmmMhsHE7DSBH1FR__Spring_2020",
+    "S2Lpetcbzr3IDbHPNKAYeayRarvubYoQ__Autumn_2020",
+    "9AF6M3VwVGTABV6IKT7YUI9XABCaZ4tg__Winter_2021",
+    "eFviyHfmbkT7dHIkshhINU7qUAUNQ4L8__Autumn_2020",
+    "E3owZaedU6vpHrLRYZwd9muVROE4nNx8__Winter_2021",
+    "IqScAKj1L0C9ptPOV7fY41qqTeqMRae5__Autumn_2020",
+    "P4B8gkpF0l44OeKiUwapivnLtN7awfeF__Summer_2020",
+    "cdy5DCigDV2VI2dNPkOghwZFzTqZBWOl__Winter_2021",
+    "mckvIfQTuWsKcvuS14ByMniMv3jM27ox__Autumn_2020",
+    "jmTQT8MX5vOrGEjHY7kPTwqCcFuut9m8__Autumn_2020",
+    "GkQdumsYeYbUBPuyAaBV59ASEThLYFyR__Winter_2021",
+    "34FaQmqy2c9qAmQ5girAxVAtNvcINEtx__Spring_2020",
+    "Ve45397e3NLG5vfWJb9N0hos8ZaOqHsf__Summer_2020",
+    "MMJkj0Lx7KD4ZTUgs2GP60bRfrUJvoAQ__Summer_2020",
+    "AVD410sOd56ZGvCCR4MuwqG2C7VAQXfe__Winter_2021",
+    "ahMr9WZZoT4TAyS6EZvaHO4NRiuNk2ls__Autumn_2020",
+    "Mvtur26gXT04YUcYZAGu11VSD90YeV1Y__Autumn_2020",
+    "vT0WyAflTUwElRZCFn6HeBPLTGBMl6dm__Autumn_2020",
+    "yPaGpeVDrsuMzwieNeoYb8vcr3LP1KJf__Winter_2021",
+    "N22ycQLLwmaWvOeK7X2BAOpIdNnhsmch__Autumn_2020",
+    "QXQOe1nmg7SB4OPm4PWzMv36wRhzWuEL__Spring_2020",
+    "rB1KnQSjB2d8aMyfX6gSMtE98N1P4RYg__Summer_2020",
+    "d0OQFPMx05jHQWRdWLQiIehwRsXQROyA__Autumn_2020",
+    "IttOXBXScVUmRyg1Ty0jO87nmmWDO6Ui__Spring_2020",
+    "fS1XHUfAmHEUXcXC5hDgTxX7blRVVAq7__Winter_2021",
+    "sc1mWhYoDhhuA5pBjsHVzOfFs5whpfTT__Summer_2020",
+    "wxiqwJK3r1ShF61glumfQ7ddztlcedxA__Spring_2020",
+    "X0qSoA7RDfdutB5RJ8j1OpAxA2kayvWj__Spring_2020",
+    "a0fbf6a73d0b493380ee5cfca8da0ba9__Spring_2020",
+    "b4jvNSAo0Am7YJrG28JDiaR4CQzu9Tp0__Autumn_2020",
+    "xGgImDnA4OgLU9bcMiVFjU7KNLf2CRWy__Spring_2020",
+    "psEAbN9BKI9FwnxLU8HYpmdUalHKTin2__Summer_2020",
+    "1C7AzAN6liCRBHMwBgA4mynpN4kt05AO__Autumn_2020",
+    "lJiiMxUfgMSmeUxB3wKwf0WNYzZ8QR60__Spring_2020",
+    "6ptU4uGDEOafZm6ROU9DYLQplZBrSmGc__Winter_2021",
+    "0lLkS11st5JnckGoPM8NaF8Pe6ArUx0p__Autumn_2020",
+    "7SxCioGjaku2DdVmPLVUbbejWgBUAA7g__Spring_2020",
+    "nLlUhouz2ynxlMoUdUn7uFR9IAHONnIl__Winter_2021",
+    "CtIbsoIQCeA0bIW9AEFP2by8BiSG9hLC__Autumn_2020",
+    "Gt5MXatbDAR5gMQdAWScyIMxWqPfRKth__Spring_2020",
+    "12ADHiKS291vbdKttFbQM5HvYZiMpXqq__Autumn_2020",
+    "v30h0ePTVV8ENeJ9Z35ryEDrNglY5S0v__Winter_2021",
+    "yl1YMyu5nCOmC3t1SVodXnM0ke3rjFT4__Winter_2021",
+    "jFRjEux1xWEA5iGGplN3oIGeZ2YpVuki__Spring_2020",
+    "wACSZeZxTsOtd2SdntqIMiwjfuBdSLS6__Summer_2020",
+    "lddLODYfWQ35hZJpBf6tpTZMWCajyLqw__Summer_2020",
+    "SrFPaeUJkw8HoyNSEI4U1VlExXSpYckG__Summer_2020",
+    "nCdGFWv6wBv8wAlf5K6nw25u9nU07Ksw__Spring_2020",
+    "lqaW4VcfO5h4nHPOWY6tCdxhlk48XfYh__Autumn_2020",
+    "TFq0HGQDXLjunHifK0CJd4HSDQsxVsu0__Summer_2020",
+    "dgf9AWpKLaieUegLZzQHDmjHd9JWV9GG__Summer_2020",
+    "h62dhU7Vqdg7ZWJQoK8Jme9Ak1ti05gO__Autumn_2020",
+    "KnNABd6SYQkNDvTdCNI7RdwSeI0HPJQt__Summer_2020",
+    "gXzAdhtrMHeRvvAJNJpNOLEywQjqbvJo__Autumn_2020",
+    "AaUibCtkIB1aoA3oYy8VcbfLiajcDNXw__Summer_2020",
+    "JLL3K2ScJEgdNpfao8r6T6TsegEwXMpG__Summer_2020",
+    "XvkQW12Ir6KVvT2oP32qgHvUHZlmQZ3F__Autumn_2020",
+    "So72lAuTEdioScO16oqLGPqNzqusnrSq__Spring_2020",
+    "244ndreZH1GPOzkFh0bO5HROmIpsy9WI__Autumn_2020",
+    "avVGnryUYQYl6w5vNDcst8DQV7YgvXx7__Autumn_2020",
+    "J89FFjs0Ec59UDxy3lTPd4FJAKhrfJLy__Autumn_2020",
+    "LUh5eCpd7eZ82WlWehzY4r58qt3ElsJ2__Autumn_2020",
+    "zNoGpLVhqg87TEh3lGEIpDMSGeGG5Cic__Winter_2021",
+    "jykf29PazBLFoMAuGnfB2kEGKsFsNCm2__Winter_2021",
+    "OBh19hBlkNVfbYS0t92qjBpoKqldGuOv__Summer_2020",
+    "JP5tBpb3Ho1NCpdA6p5peD1ke0fDw7AB__Winter_2020",
+    "9UtoeKr9qseqzVU9Z91ZuZFgrsk85ccv__Spring_2020",
+    "tTxnpiy77QMNtGQ7y1CEtyNpqjbU0045__Summer_2020",
+    "XL7iLXpzr4iOQj8dBL913QoY8hQwcnDB__Autumn_2020",
+    "V3GNFcguicRUKose9oSqJf9p8vkXGGCk__Autumn_2020",
+    "av8pffsPzeSdQVoQTNT0kruq4RE07OQ6__Summer_2020",
+    "fHPnSlTN8Gk9DHn7roA19eB9PU0Q4iT0__Winter_2020",
+    "RYyOuimVLK9Ge5MeaGU5Dj48ZozRWyvg__Autumn_2020",
+    "uciHLhGoQfkewbGEIpSkun07D7pfQljC__Spring_2020",
+    "rmsLKbxuo7jUIFTTygnJvu1MIsKpYkxN__Spring_2020",
+    "mtPauqosC3OnynRHdZtB8HwVtoFQBeSJ__Spring_2020",
+    "YmPOfTdArdnfYlc4IAH96hd4ky8gYs6r__Winter_2021",
+    "oiy7Qe9wN7UK0085RUMujRDkIWQHG9fw__Summer_2020",
+    "B8tFMx8rh2gg7vQ40Xvo5xJPb7l7Ag5p__Spring_2020",
+    "E5pG4yFodYyUp3DnMAvcQvWGtWE5hJml__Autumn_2020",
+    "q78Lo3MUfvMzJDnvNZCfYWJbxeO8MPZT__Autumn_2020",
+    "BLflnKCZeaqfBxRmEtanH63JAepGfv4n__Spring_2020",
+    "ylN8Z5HzfOZzBS7G1o3Gnj7RcopKbDTi__Spring_2020",
+    "Dh2UhBo9z7cZHKAOSWc8SZMmLmDrxVn3__Autumn_2020",
+    "sdgThVTxMqPPTcAMvYyrOZkPtQaQXGJ8__Spring_2020",
+    "u9bk2pBt96jmPtotJpG9NtUsSPK1ONai__Autumn_2020",
+    "LVlLn2NEzJlgpPmyRGpiCQ30Gt1yerKn__Autumn_2020",
+    "2S1xkgWyVhoa5zwPLz9tl2MAG0lMxj6i__Autumn_2020",
+    "F0ouQQVzZlNd14S5mVGNLY9jHvmHLJKr__Summer_2020",
+    "S1QIEYY0VKvwJUFZEnEpJy5a9DPTKQ4L__Summer_2020",
+    "HJ44xqIO17A80eIwkgOUNkhLoUm5rcZw__Autumn_2020",
+    "2E8su1EVHLnvOShfJ4HrSSxwH46hNd3E__Summer_2020",
+    "uC0fYEOk0s188OZebFYw971AI32y9C2h__Autumn_2020",
+    "l14tFseTO8ZiIGxf4Rf1f6vw9SjTFett__Spring_2020",
+    "D5W3veyUjafppRWIFwpVYLMtd2irnpGQ__Spring_2020",
+    "Myp8SEHvxqkoHcIYplDOjvWk1eRFcRsh__Spring_2020",
+    "8srFkMOdl9LyOZfTe8eZg6Cs64QX5NZV__Spring_2020",
+    "julzvTEXS841JkPZB8ZENWRdjpshzHSE__Summer_2020",
+    "xPq4qrT5QQgLiGYzgXyAkRp2NWYsetr5__Autumn_2020",
+    "s6iz0LSaWaiblWuZg3ID8Y7x3vo2o0Pb__Spring_2020",
+    "9Y4wSZrjgU4CbBwXiC5qDT11Ia2M9MN2__Spring_2020",
+    "ay9cCetqo5AtIrsYEAEvhXz6OLOLM53F__Autumn_2020",
+    "3sMsSndTTJyRaSaJpRGeve3RX1VzmHgp__Autumn_2020",
+    "4MRLtQKyymNT0VxnCwmTNPIiPDC1s1il__Autumn_2020",
+    "cTX2vQmHOE4wOumEDNQd3TAUllaSdnx7__Spring_2020",
+    "zgryFELtnd7StGZCdea1v7qrfqJkiHxc__Summer_2020",
+    "cdEtbwNHGlu6K5QTU1ImfVGSYQJP1TJj__Summer_2020",
+    "LdJsc3rIkF2BBVvkC9I2Oed6jx2AdBJn__Summer_2020",
+    "1WIsSXYSIgeqCovYvm8qZyXOlxSnUz5U__Autumn_2020",
+    "CuRUbujLDcQAnwKuQMKAWOy5tIljE9Eo__Autumn_2020",
+    "3GOFtNddPpvYyC1Eh1pse0d4fwQ49i3A__Winter_2020",
+    "hkKTNVrHSiSkze0QxiOUVmj9K5JxJwqn__Summer_2020",
+    "G843Rv443h0B6HRLN6qBdLmfPTdn3JWY__Autumn_2020",
+    "JG10opC1VDGFeXK7NEfgLSf8Tn4D8agj__Spring_2020",
+    "85mbRdXyAeVxDKbxy7ahbHbswP0fCX8W__Autumn_2020",
+    "UyTltzAYAPmvh5lViisoYjLpeg5JwfRG__Spring_2020",
+    "a91zDgkKoZMmXraIFkWAn0Vmix9Nrn0d__Autumn_2020",
+    "khCzzlmcqQmyNyeYck6d1o8lmb8h0NYM__Winter_2021",
+    "EzxCeV2A5bG3qYQ0LotmX0AmZRACJVbC__Spring_2020",
+    "NOSIEZBpLrO4JZjAgZCTQmpYkFraBlJX__Autumn_2020",
+    "ir9kYi1l7QmrgXZTNShMAzFngFaFer8O__Spring_2020",
+    "nZtTvu8bmF9UN8aiKqD22N3rh37ZimkD__Winter_2021",
+    "YaPbYcXBkyXhEN2vTZKQCGiw3yXd1HUY__Summer_2020",
+    "arZSZYqp3LFiRWLtf4NmsvPS8tdDfrlF__Spring_2020",
+    "J20PNLLiDzUMpCCHG9pQVqqhwnC9mRYA__Autumn_2020",
+    "NmLYBpt1CqEgEh2MZHmzs8dn0s5Pbv2w__Summer_2020",
+    "DxySxxHvD5dox1Vi9gNmVuda1ZG7Y22k__Winter_2021",
+    "gvAOf21tsRWfJWr2py9NQ8imJMfNaVRB__Autumn_2020",
+    "aoCpdCnfTstUtIUp6JD8YEEVV6yAZ2tc__Summer_2020",
+    "bIjNFyWOEL9GIPim8TrMoe0qdx2o2GGZ__Autumn_2020",
+    "YVmvMhcehHc3dIhHraUI4NwzrbCMkIuP__Spring_2020",
+    "dAZfEEgpoHwuxHo3Dwo37hr5EnCXFAeZ__Summer_2020",
+    "3QDDskLMYLyzvCWHdq1BMGYJVkji2jdT__Spring_2020",
+    "xkd04BU9BKax7yTBOSE8S9hI25TlLQ3M__Autumn_2020",
+    "KvUvN2x2tatAduRZskMdQrtYaiZvCMje__Summer_2020",
+    "184EJJzojrJ10AgJsjd5ENB4Ef9hpDnn__Spring_2020",
+    "0cfNzxkFwK3x3JB9HpcKIqoRCztSAreo__Winter_2021",
+    "gzWAjuRCEiBFBCbmwbsjPX1emDtmrRjW__Autumn_2020",
+    "iKxePu97V0QOiuSeGnbYAzyig66sic50__Autumn_2020",
+    "OK4IlTlPT0TR67OagjRZLl1IvBqb63Py__Summer_2020",
+    "q4cYWorp1br6cO9kMPzTK6sMp7iKNs7B__Summer_2020",
+    "KdQjGk82FXbJO1a226pLaif2xy05J6rk__Spring_2020",
+    "n1nHVh0BDudRv3q5HAVDRYhSmw9VBDRH__Spring_2020",
+    "oEMe9Dq6aDzJupcaGGLQoM7x9ir8QJL4__Summer_2020",
+    "QRcqOt87MB2ybtH8PbVezggjR8w0piK1__Winter_2020",
+    "yhsJDf6v168wmGBM80XufwFLDrj6CKjI__Spring_2020",
+    "3nVFekkQoMBsLQCWLFFnM0124vmn9hEW__Spring_2020",
+    "3x2Jgu2FC6xaBt2O3mJZmVAQKfYdi6WM__Spring_2020",
+    "rp2109KXaSF9w4ODfsekBtTGJ8o8u7q1__Spring_2020",
+    "HFaowMzA8dyDCUaEJcx3J0inh7eKNMwj__Autumn_2020",
+    "abPssWpNtHhz4OCaI4Vr8eCYJm140QZu__Autumn_2020",
+    "UGXNaOHwPqqpbpFngheQ94EpDBswrq2B__Winter_2021",
+    "0Pl6cyE1PWIIFx3ldUI1C0sP8H5qpRp6__Spring_2020",
+    "vmjZTusZbyWeP6CMovGzGNc1q3osH2OA__Spring_2020",
+    "bWicQdTALmMWT7yHo9v9BliYY982uHIa__Spring_2020",
+    "pd39vohCVJWAcOLAdNQwE6eAFx0EOKAV__Autumn_2020",
+    "Un4KsDd32ox1TvUUlq7YE36ZivjfYM09__Autumn_2020",
+    "7zocaqOkA5nxowKww36JWShJGKo3vLRc__Autumn_2020",
+    "KfVoBdBR9G1frpAQHPEXzOIlcxyLG6lA__Winter_2021",
+    "6UMN4KByGiXcCX02uC8BrXWSA3cRmyqn__Summer_2020",
+    "b1bVUUQkcuHGIs4F72UeVKLOdhVVZITw__Autumn_2020",
+    "Epv1SbJreaCFq6u10dJbd6ffNDsoX82X__Summer_2020",
+    "TUtLRo6eZCD1UHaSaOaHBFVnbII1VqwZ__Autumn_2020",
+    "B4GkX7OSFA5pFJlWn89maPigon1HG25U__Summer_2020",
+    "kvw0fEulYl8LuWuTei9Kb0UCzsd4ThBO__Summer_2020",
+    "pi9LLP8WLaLr6lQSV5AbE45LaCqPVcsX__Winter_2021",
+    "cW1UmCsNqCrDa1B9WUYqszMt4iDvPZLz__Spring_2020",
+    "ObYODDSud3rbaOceLJHbOrIbkr6E2DUV__Summer_2020",
+    "gVcv36Ni6TbvOouyolmXzBHXkzNuLjFW__Autumn_2020",
+    "psizRekX9TIiJrrEO9Y4hAhVgamtzrzP__Autumn_2020",
+    "ike5H2XqUrFRfBjXwsh1nXpM0tf89TWc__Autumn_2020",
+    "lVuezO2wYFZ47C4vTn1vlemmzCpMOlTJ__Autumn_2020",
+    "74djbi6f7XkjHyUDHBxm41w34c4hXcY8__Summer_2020",
+    "c6CVdV6Z7qGMWTOnZS6csUGO8IRFwwH0__Spring_2020",
+    "Lsp9ui37oGnrWX9J02O1H8fMBdjSMrxr__Summer_2020",
+    "tFtisPeQX0Bnc3G4mTw8moMCKNr9jNHA__Autumn_2020",
+    "RzdRJnCsB04LtQcWtqgfmLXKG2uGuKML__Spring_2020",
+    "bucg6OUMWLftySg1so703fu0V1YYPLUk__Winter_2021",
+    "tDN9t2OsHTybda886PPKf8gAqLlQgpKN__Autumn_2020",
+    "mT5ZJoJ9aUub4DUfl5GJt3IxyrqIHZVk__Summer_2020",
+    "oz5P5yz5LfmeFkRLcmxWnLauy2Fl5Zfb__Spring_2020",
+    "Hx9POf7Gey6d6yx7N7Qmjausn7TenSW3__Spring_2020",
+    "v3I27UK1uO3TCrqqDYemhxLogGwPVGD0__Summer_2020",
+    "sXYymPYL0oBCcFRyl47qj0nKLiRHD0PA__Spring_2020",
+    "QgH23coDdL791UiLkUkzC8MHPes3QwEO__Spring_2020",
+    "TwmO1uxRlpxs0zvJoWzjDi6gBktkY9Og__Autumn_2020",
+    "rEQmrzpxOHeRF1odU0KUcmgwAjQkm5WU__Autumn_2020",
+    "hRRPr2dA15XQwQg7n4sTDToUZU76hTHx__Summer_2020",
+    "bYye4u0hxlsH5M7uC1krBo1XqFvtFA6s__Winter_2021",
+    "x1RHQrTfKf70JKqM24ayyxsddFMbGRc1__Autumn_2020",
+    "3kV3HGWGWJ5HUm45cb2tMo4dMNTY0hy8__Summer_2020",
+    "FN7wW5nucPvdWul7qshjlM7td6iivXGf__Spring_2020",
+    "MUUntdQVN06voRPhI88YA9SD3TqMfuuJ__Autumn_2020",
+    "slyiNqJohQZGQVkYdkDL57YIGcCHK9OY__Winter_2021",
+    "dHBfUHhMHrOhzA09aRcdwdglbgghk7C1__Autumn_2020",
+    "La7rY6yNFHCCIWSdl7YRFPiv2zHHt9yR__Winter_2021",
+    "KWfyCVH4NdpMVEJI3rHUjrhRj6S1UdLa__Winter_2021",
+    "CndHTxGnK9ATyZroDbeQXZFl4CVpdN37__Summer_2020",
+    "SjXI6FGBrERp5RzZX2Vlgmb9ui4XkzvC__Autumn_2020",
+    "HCxFLCso71u9OWQO34fijaWc2r56k8yK__Autumn_2020",
+    "x7SUuHsGdaL2bRD1igK9ubSH8QaKqoD7__Autumn_2020",
+    "vrEothbXxdtFdLIifN6Ngxdo47rxJkQn__Autumn_2020",
+    "XoJjeRCNev6ySFtcd1xWI4xM0C4TSHFS__Summer_2020",
+    "a3eqK8HdSbNrpUuyFBM3n1jW2nQdMz9g__Spring_2020",
+    "r3VtQU8H0uPcMMKqAPAOiWuZBN7uZhLa__Autumn_2020",
+    "PWpKpNBzxdsgJT0mAs24IyXGGHEME632__Summer_2020",
+    "RR19oRvokOqdHD7YcVDSILSjiaSkhyul__Autumn_2020",
+    "rddNNBFv9Qo9aUDVL5LpWifaYoH256eZ__Autumn_2020",
+    "BAB8fSmAv8eeYVhAcVh0jmonPx013tgw__Summer_2020",
+    "Nyx7lMO9sz0cOSZrcpBer9fw6q2MXHLc__Summer_2020",
+    "6oSGA6cEe53rkexQCqR9osKW7voHAC8K__Autumn_2020",
+    "hbYvi23cFCbGWfvVo5EzBDJGXw0BzGnA__Autumn_2020",
+    "SZyBtaADWWqmlkd8WfDOPu68tUQn5nqh__Autumn_2020",
+    "ne10cCmTBKX9TYTCN0e90sdH1tJEvET9__Spring_2020",
+    "XI7BQGK5afFswsrSJyvZ1VgqOhXufj5Q__Winter_2021",
+    "RuXCKn8CnRcXYGjw3OKp8aGqV9lLYHbj__Autumn_2020",
+    "Vxoc5Yc2g69ic4n6H8Ju5wvNvIa5Jcap__Spring_2020",
+    "H4icMb3zwrIy3BxbBePKf06XQNIYTiWq__Summer_2020",
+    "NNaZLdYiQ3zeFc50VQMhO6BOE7hQsEyQ__Summer_2020",
+    "LosxG5MpysVIXRgV5CCOAFnF9ouChdNI__Spring_2020",
+    "lj2RwrVzbmtymOr1wIVvpHJjipt8M3LH__Winter_2020",
+    "Ke9uLs1Gj1pv6a2D2xBsUBbIjNJQzMn5__Autumn_2020",
+    "Wu3WgXrgG6i4yRHXyWyy3oqakNcXnCg3__Spring_2020",
+    "hqiAzexdjvhy80fILI6RCG62mIii4H2H__Summer_2020",
+    "5muH9qNcqFjPWNLHBmK1GscjfBp9WTQV__Winter_2021",
+    "O1V5Ly1HE7Wtok8Zg34HpgztbVWLAkv9__Spring_2020",
+    "RLXL4ezWc6QkYsQbjZK7CVzTw7ZOAtOJ__Autumn_2020",
+    "6yyewoPugjT9nvNIx6YUw8gsMvPRzJ3f__Autumn_2020",
+    "CfmAqCd8QaKqYcylRZLKZIx2fALGFRwE__Autumn_2020",
+    "V17LgyVPyrd2yjWS4oEuipUBJQN5X0wZ__Spring_2020",
+    "xWXj2GMMvvQAMQW9VsegTZT6mh2rphk9__Spring_2020",
+    "vGWPzrBLLf1Idr0m3NkKo0zFvNSLaxyD__Autumn_2020",
+    "tOk1mKP3mTK7TbgeghluHDuzURxZNdb2__Summer_2020",
+    "R3gqLZW4fgNKTKw162t6trMdOHBpreQA__Autumn_2020",
+    "RwoKaSM59Q9nbk9cbpNrb6iObuKIPzz7__Winter_2021",
+    "JYyACT4xmBwrmYCEyp3SrqhW9rrByZbw__Autumn_2020",
+    "vwvE0YwBRnOKFZKmbyMaSNH9IVLWgFy3__Autumn_2020",
+    "NT67TS0qFtIP6zhQxogzVjrbgxfyjKon__Spring_2020",
+    "wnT4N4DGxlwt4Z1TL1EF9MPDcfnRMwgm__Summer_2020",
+    "lhBoHEJhuPWpORxST6V02FiovKsrCniw__Spring_2020",
+    "x8r2PS3QFmhp95NCFxyN4isZlZPw9Hfj__Summer_2020",
+    "ovYvZvojCx3sYZN2BTZb5Zswcg8yl58Z__Autumn_2020",
+    "HagGdNd0PMwBLf47FM5sRZBGty8pW3we__Winter_2021",
+    "mCsfbjXoU2lbARga2xwa0BCrSrXKzy0f__Autumn_2020",
+    "tcEJFqVfNF1QhomC0zV9a0kRDYNgeCy8__Spring_2020",
+    "mtkdmVs2qF09LOWkJmjYZWWsRN32EKks__Spring_2020",
+    "brck1O4DtY3gqgDBY18iXc5o5Nwh0lYv__Winter_2021",
+    "rZ8Y88BWECvkxRRMbd4dh64631qBy3yn__Autumn_2020",
+    "BnNpDNr0nz55immMi2JgdT3UUu2x6a0H__Autumn_2020",
+    "B4p5mYSrnNjqaDoi7jiT5WXr6q12gICI__Summer_2020",
+    "vc6BcGN5x9UYkJ6I61zarMCnyPbP9BTj__Summer_2020",
+    "EeZ86QxETe6US6VrwAnGekVPLDo90vau__Spring_2020",
+    "AxTXYf2rTGL7BEmV2lVNi6S6MqtJLtPz__Spring_2020",
+    "7CKDR4YKKgPGj8ahisyrtBmNy3A6FHHX__Spring_2020",
+    "sYnPkvMmni2WIqWulhPffKZS8DCFxhSp__Summer_2020",
+    "6ugeSgzPpo4aGS0nH40r1Y5VGqdfZn21__Autumn_2020",
+    "BIscg9d7QBGskRgex1FcseIODXUwtUqA__Autumn_2020",
+    "It9DjSSDSjFOxmyZveWUZH7ThfYSYVE9__Summer_2020",
+    "NkaYD6CUCILex5ZbOrnEjCa4Y0foRNKs__Autumn_2020",
+    "TGniUYUalLluMfmY8rzfIJEZFWUBBJG1__Autumn_2020",
+    "J9pjiaJA33mKn2esEZumSHALUUi6y6SE__Summer_2020",
+    "OK9Pgtnum9RMY6Bz09kXjz1d1jEvT6FB__Autumn_2020",
+    "r9bt6cLI4MHU015aOZH79OekNiVKxoEq__Spring_2020",
+    "jLlrnJRJbEA5v9ThHTVI4MYzVcOeufwg__Winter_2021",
+    "MWznzAdspA2E1TOk0LjJsuIWjjptnsUJ__Spring_2020",
+    "27THtUECrc24kdXgvZdRjFO1rscW0TAI__Spring_2020",
+    "apPic0ppO2XlHc5g3eMKg5nWYlNydjPY__Summer_2020",
+    "2OKDOf9DhoxaVC17YM5a4DN78RcUqj8i__Summer_2020",
+    "jUQXqMPhDcDNG4DY9gtSD6UWI0B0BdZ8__Winter_2021",
+    "TejPxgTIRGNRdYXzEiwdCaGEY6XZSPI3__Spring_2020",
+    "GgEzZgtFZ7xblomeMqUMC0STDZ2Vuo17__Autumn_2020",
+    "ZB4EpxUqybDhi7JSWdAB5i3DesJZJ5hE__Autumn_2020",
+    "oKELm0WTybUZnpSsE9LzCV23tUBubsRK__Spring_2020",
+    "dF3HEj9LhLcp10hlkx3pBtkwyPogRfaX__Winter_2021",
+    "FGQyuyTrtM6FhOd8SptxM4IFMXm4QWgM__Autumn_2020",
+    "Y9abHx9KTgty9KziRItqF8s3Agx7Yh6h__Spring_2020",
+    "832eab2d80c94d18837ecf1cfc9e09b7__Autumn_2020",
+    "x97qQZXpG0QPjcDHnHuO3MWgO6BjCRJr__Winter_2021",
+    "yjuXeq44ssLFaF6jxWCDqTqllzpgVoCS__Summer_2020",
+    "2fivqHl2FnlYzwv0E1djnWybhm52RYUO__Spring_2020",
+    "s1sUKHEejH5l8txLTK7hZfUuaslQ1sNG__Summer_2020",
+    "7f7BDrCffwUKHiC9fPJndlXEU9QsTCwK__Spring_2020",
+    "8giz8dYWO5lU0cvRJyaVxV4MaijyK4jS__Summer_2020",
+    "VN9wdLblWUyb72qEFRx97VADt3xGMhZ4__Spring_2020",
+    "dNHXMNqN6WeQKiltgCLKFpPc4XtWqEpz__Autumn_2020",
+    "q6RDdBggb4wrbs49u11lgZQcPQu0HWIg__Autumn_2020",
+    "SQ5JaIy4ZsXxj6vW7T60ba3VNHpiGA4c__Autumn_2020",
+    "HV4bxvwiEaAxlR7bcFogw6HwFuvith7i__Autumn_2020",
+    "skBaoCOwpOiof0cYcMJJmssrCeGKUGIx__Winter_2021",
+    "sgbemHkFm5rBR8jVyIubAxnjyesKbNqm__Autumn_2020",
+    "dDxJfmdVMkGWSaFhW3qgijKWItN0MzKU__Autumn_2020",
+    "8DFAKhFSa7t8964VjxcQlNcktGLyQlws__Autumn_2020",
+    "iwXEWJDJeDtbDWfX3fJPRRyna6c0DzTe__Autumn_2020",
+    "RJe15MtnDmhf2pyn8mlyp5iG9jPVHfA1__Summer_2020",
+    "YM4XSLtiT9rsmqgovHlLfVRVWmeu2oqc__Autumn_2020",
+    "gmkxXur6VHufvURnlYdlvAKWq6OHsBmj__Autumn_2020",
+    "0htBS9K6xCKaDSUd6bGlIfnaQQjbAVqe__Spring_2020",
+    "xJLRwAwOrYsisNbWeSAafuNDiY0GRmCh__Summer_2020",
+    "CbweqOkB6gOWPgCN81XjDc6AdD70E3SI__Spring_2020",
+    "Cjj3voqmk1wx8yLtevqPyF5g7Qt8GYg1__Summer_2020",
+    "Ss20gLvCoBkuHG8sxRXoi0BaoVsprvDP__Autumn_2020",
+    "excDt6MVSYa65lVBxA4BQ8Qb5KgfLpIr__Spring_2020",
+    "3L6AKh5uXmDo21zpnWR94ug0sAfbULfU__Spring_2020",
+    "8YhgWIdiWHsFLNb38FDcHRc48I78SJx0__Autumn_2020",
+    "u3lvpSTPicHRsVuQrmlEGA1nNlqGjDJx__Autumn_2020",
+    "F3T62HitjuGnTtJx2Nwv3zYcIph9GIlA__Summer_2020",
+    "G4Sgd3Jd0tk4r5MmfzHw9izTTbraXby1__Spring_2020",
+    "UUyaCSWrgWHgvxtZ7FAeWojKvxjfONGG__Autumn_2020",
+    "XcVhL8g6PjjmyI98HGqvHQAlrMo8FfBW__Spring_2020",
+    "Pv2T1MLEOxd7g5OJSsXNomqjSiCHHzHO__Summer_2020",
+    "K2JKeA4DhL9r8zQsjp8oGILophfGqy7l__Spring_2020",
+    "ZgvlOJKGc488i9hrNEaEVhIYSmZrvoc6__Summer_2020",
+    "HWDaXylUVJzsBCMOIFZqb4CExxsP9QKW__Spring_2020",
+    "RvsuAkcWjuGJ7emHxmHbzDXGpBjO8bsA__Autumn_2020",
+    "v3cf87XyAHNnGLLDL5yhFtGkCu8NMbjc__Winter_2021",
+    "guSbHkhDwnvEirY7bQvNfOpdW9hHFuZG__Autumn_2020",
+    "7xVnwLOYfA30lKM690NVxTYycUZ0RPjj__Autumn_2020",
+    "FKueS4lbTVLV9t0ix2j1eyyZ26zJDxmR__Winter_2020",
+    "mN4VohCor2BPhCGkCD8E2xkYTknvdGVu__Spring_2020",
+    "04akO6mLeIFQRjbq9XwT71QNx0IJ0sTy__Spring_2020",
+    "osHK6WmdiZW5u65eF8KK024lsmzwmNle__Spring_2020",
+    "ok9mkKWmYma2NyPQ449YDukvFYwuzghP__Spring_2020",
+    "VHH9sVs79yefG5P9zxTWBxwsCXkgsLuH__Autumn_2020",
+    "RaDY0iRuVFL1gCcXIn2agHcRk0fpUrbs__Winter_2021",
+    "mLbXBKfmzDUNsMiCISzbpgLjmnYMVsVt__Spring_2020",
+    "cSdbCh3oPfAHKuzvdvH1bsuqOkdd5sli__Autumn_2020",
+    "SBl2x7DlgA2WRu2vSNgI4S5D6D0HHbUS__Autumn_2020",
+    "P4qIURJEN8793diggvoNdfPI3PzfjbCz__Summer_2020",
+    "za9ilSZuDSu4EYQOo05Fwls1uQKtkyQj__Winter_2021",
+    "OsMaSGOYyNUXqXMx4XARYB7n91w1Q1Jc__Autumn_2020",
+    "1YcYdiNw6voi55sqS3YNa7IUmFIcyc6H__Spring_2020",
+    "OBZsWiTOJ6zOiDUktKVcNSoX4iPl1Ipz__Autumn_2020",
+    "SmYhrjwaJ3LQFzpE3uWWB5fkZMmvz5TC__Summer_2020",
+    "vjdUgqQb5RK8hZqGXBauZxIwyUdx01vR__Autumn_2020",
+    "Zi855jH8oYDzoCMnkyvoUTbU3KB4mXy4__Autumn_2020",
+    "cSfFjC8Ku1X76Y5oho8ObcI6HeQ7G5t1__Spring_2020",
+    "Z4OibRUqOC50jjNgBaQjl3FmRo61oUe9__Autumn_2020",
+    "gQUAwNjxleOG4YZJa3Iwd1KWvLC1z07P__Autumn_2020",
+    "hNfAsE9vUM6S3YH4iUe4e7OvUhVgbRqF__Summer_2020",
+    "so693JYpC4TWAJsmr2Hw33kEsXsGgZz5__Autumn_2020",
+    "EIrOQ2IQFWtj3n8oQ1rBAA5kJasWNDNw__Summer_2020",
+    "hSIMs574r62IidTPnZF7QAeByTDO67fs__Autumn_2020",
+    "qktHtGkRwIYuPrYh89EkSG8rslgdq0Bs__Summer_2020",
+    "o6PElVmTT9hFyjvyzBL2xYyJTD4HC0aH__Autumn_2020",
+    "rUnkyKljEn0UNtLilBhpAPK4BgIHztjR__Spring_2020",
+    "eafM007o3vy2oXpbmEOMDwgkdqwIlD9V__Winter_2021",
+    "ST4JoSUBflEei6I9XYOa07ZEB0hMOK0g__Winter_2021",
+    "Z2g6Tbxvh63SH00YVj6IO3lokaQrOMHY__Autumn_2020",
+    "M6p8mv9TxTBkRyDJHP9txWcVKsnEFyra__Spring_2020",
+    "3HhqWpYi3iJj038KJEVMzHtMSfVjcQIy__Summer_2020",
+    "E0qQHyKa1YA12xfUYpWLOtdXDGrOyTtP__Spring_2020",
+    "pKCJ3Nz7Plz4NTtbgtjy0XyScOpWcfRs__Summer_2020",
+    "RoQ07eFv9fuwwMaM2u9E3iIhyURKTK1t__Spring_2020",
+    "ceenZbPgcmh4Xc9ve2U2Pgi8wNdY2cPr__Spring_2020",
+    "v8eVTL0ej19Kdr3Ypgj3kjtuWCVWiTfC__Autumn_2020",
+    "PHLbMnHn1jzkP7A7JkDqIxBxH4geFZl6__Summer_2020",
+    "89BIX52CtpJYC3Y0iUHDE0iiJyjLIX6z__Spring_2020",
+    "h2cVa2i2EZ7CPwH6n8V2UP0kJCYGOOAw__Summer_2020",
+    "Z42Dqgu6UeMbDKlXruPHw3WA7Ymo1uCn__Spring_2020",
+    "RwhDKp2WKGAY9YszrcjtcZ3FxEl7KGnI__Autumn_2020",
+    "S3QB4UGHuuGiUKSIYgd345fr67EvPUog__Spring_2020",
+    "B1ZOO4EZFxfrxyKpQBKbXKH7jY2tsatq__Spring_2020",
+    "i1qcWZ15fSD2vfLljK8EgVPdyUWNgbp9__Winter_2021",
+    "7FuUqSNZB4JsaGe7AKV12BQlYTf6I4Sn__Autumn_2020",
+    "32zGVedhoFSwYjkQ3C7U0AvvJF5WHseP__Summer_2020",
+    "e361SjTrm7aUuN9QQt4caU6gG9SNASmP__Winter_2021",
+    "OmSwa6DeoLu6CYZCEvrNzoTACCLbmOuJ__Spring_2020",
+    "SWSOwhQC4Mfk89mvLo6SUe1FM7xpBk73__Autumn_2020",
+    "uZlYh6A9iOa7GgnOIy09YRlN7M8Bz4QO__Summer_2020",
+    "nv257PMjVGZITmuPwBCuzDilBMzupICs__Autumn_2020",
+    "EMKiEsGx5txbYYfm1zGEPCdvstLCSuYT__Autumn_2020",
+    "CVP2nHJdaYrz0amIAS6rnFZ6OFunKAVy__Autumn_2020",
+    "2KIMG1sZ0zRBYUHbJRoFfwAYvHJ4LRZu__Winter_2021",
+    "2GTxggHXDhco2lKaT5Xyig97wuDepNrR__Autumn_2020",
+    "05lBLQJs4ilyORCox6j9ndWAKZc31rs9__Autumn_2020",
+    "S3UeGoKZc47vMkhpPP4MweoalXHSgGjL__Winter_2021",
+    "xkUFpLk8hlALFVnaVTmzoPsLGgen64EC__Autumn_2020",
+    "6q8FsQgWdJorizMftnuirkwZl0eWKeN8__Autumn_2020",
+    "MM9h1HHQcpGC93cmb8HxEOmfVNAuD3Or__Spring_2020",
+    "OiSkVwYC1JNufA3albintjBK3dapCuTo__Spring_2020",
+    "uUIWfqSaIXpPIVl3aWzEjetRSBHZezkX__Summer_2020",
+    "BetLi74QossQfyT8NwXPB6NBWiNlgwms__Summer_2020",
+    "hhLgeBb0pQK8u0hjC0ZRzfhayAlFxAGf__Autumn_2020",
+    "8OqMSTZfIbEqEnntX3MgrLkZpJ5JKKYn__Spring_2020",
+    "4bMboWL4uDz2vFcBz5opZoCroO5nG43w__Autumn_2020",
+    "88rpf7UYXHBvEhanGvf232AgGowAE9ro__Autumn_2020",
+    "CzBIWpC4XBSeaOrzTCgTAgPZjLJdSvxg__Autumn_2020",
+    "eZcM57jKMZqZBRnWTFWhZPUCnALGJtR2__Autumn_2020",
+    "v4gIGANPAUZomtGZDfYeftqhEX3o1uz7__Spring_2020",
+    "aTIhr6LWntKTqxGVSZPvG7WOniqZCuuF__Summer_2020",
+    "gb5YJKbqRcfqXvE2yUKwNzFnrs7roodk__Spring_2020",
+    "n1NdT8oJpFPH5OHSZCl70vFsw5p37uJB__Autumn_2020",
+    "tu9IT9IPEV5mKJv82wL0HQ7Q3g4UxM4h__Spring_2020",
+    "g6C156zhsEJEqbI7ibR4Rj6AxJiYNQSO__Spring_2020",
+    "nrdJBznXxvHLQaIk95TznDLuinDCtzoY__Autumn_2020",
+    "89308a1bc2da41d498c5d3c702e99b56__Summer_2020",
+    "n4Q1lhfn54A50iyZd1HOjHeE2M4OAgyW__Summer_2020",
+    "WYLGLyvfbcBy9e60BRVy80iEVkWooGC6__Summer_2020",
+    "7xtXHHExlC2ZLQEE6zR0osLkYOT7ElOg__Winter_2021",
+    "RSeeJmSAQnXfIFXRxNzJz8J3RmTwElbu__Summer_2020",
+    "ip5xh4MR5G1qJJI9wtV1h1iS1znNVNDf__Autumn_2020",
+    "HuP0Mn3AxtfyRsynkbnssEwV4yWJn7me__Summer_2020",
+    "dDiJ1UCsCzL403kKIKygkTfFyadDyJEW__Summer_2020",
+    "fBYIhbkKsYdM6Uhxzbac779QVDwJJDyZ__Spring_2020",
+    "JQrTXX7RfxsQl4aYdtg9BgGwdTa8aaRU__Summer_2020",
+    "mEsKlgUaHDiC5FoSFe04gqvGEjqmAAl2__Summer_2020",
+    "EIIMQUMRlapwl3TrEFaUXHs6LlmxkTdj__Autumn_2020",
+    "imCLWtkdkiC5T75kER5cKd696f7nNNZZ__Autumn_2020",
+    "GWknYBoQ5ZG2hlz3ZGmVfBXLqDWqPqgF__Summer_2020",
+    "lorOINR7qBpJb7IEFKcnlH55bv6SMZCO__Winter_2021",
+    "qtaPVJESoZ9OvXBvJRBljp6AzZooi68z__Winter_2020",
+    "0262e2af16044492b13ca051d6ab4d80__Spring_2020",
+    "apa2yJViEtLGHblax0MR5dRSpdT8mjF6__Winter_2020",
+    "fY6myCeMIRHeEStqGECifjU1vW2aKzqg__Spring_2020",
+    "fyUk4f5bPEJHePvClgluRDgobAXJrxER__Summer_2020",
+    "WMM5oZkTtNwQb9mC9LYcfoPGqg4KOHtL__Autumn_2020",
+    "79Px4KYk3YiBn3CEmZzRxSAaixvNJc0I__Spring_2020",
+    "duqcfinlhPfEka5CfBrbZqut0g1HaiL3__Winter_2021",
+    "DfX5H3fA2sxsmQuRswhz5Ggde60YDtd5__Summer_2020",
+    "n2BhvHuPrQ3zgaAZn2pqAHEwTAw0FSSo__Summer_2020",
+    "XpTQwweHdYSHaU4sum017GV49NO6mQ5V__Summer_2020",
+    "xs6WinR0ajq0AZOOiypkk8ALZuGdso7X__Autumn_2020",
+    "oDaKEam7zyUfFJf8ryWON1eF5bguVT3p__Spring_2020",
+    "uJHqLHAXDKTEPVWegJdKSv6fdVqBAmdD__Autumn_2020",
+    "NwmmEmUgVGXNU68HeJIVtqO8noxds88p__Spring_2020",
+    "esv32TgWZyRVsoEKyfZ6zA81cYTj7MvJ__Winter_2020",
+    "2IJmNDyxd6oPxHonVX3LdXHavDrywI6y__Autumn_2020",
+    "tQea7SR87wbZR8WUDwW0Fj4jBzphxTS8__Spring_2020",
+    "zi4FyDcb8KIG7LYcou6oxxM1lN5kBOWc__Spring_2020",
+    "aWcmcpb9XXM17aP941Kb1W4JNDJuC6dz__Summer_2020",
+    "QMnNKZiFaxnuGQmxpGkZFdM2EE7uWqDQ__Spring_2020",
+    "DSvjjChpvWmeVHC8bvrQCQzfv7DneQTy__Winter_2021",
+    "SittvF57vGEEDeJcyfkicaSFXIsLRsg2__Spring_2020",
+    "9YXnH9Gbs9kHHoU4OXuz3yQK9nmWkmPe__Autumn_2020",
+    "I4qeitSnmOBEYVxGv3r7Ug34HMQSJBxL__Summer_2020",
+    "J1ssmRm7neTGs38NsyFI578rECfcUnxT__Summer_2020",
+    "lgBLZEUKmFO3Dbh7gBn6V1naSh1HLUZr__Summer_2020",
+    "wAyD9E8RtCuWcGIedK0rBU5CAUqbltpt__Summer_2020",
+    "DJCWKfo9OfD2Gw1MZaGkntQ6TiqFqIfd__Winter_2020",
+    "peGcDCi9N8m6WkOSflXhqVsJOXntOHOK__Spring_2020",
+    "WmMvQjo8jXr7hBWUVWZqgscRA4pmmj9W__Spring_2020",
+    "hmP9PvbywV3eYDao31nlyJYFMnTd6Mcf__Spring_2020",
+    "jD8Z8DSGEpCDG8GhIwiacXjavlaGbJBy__Spring_2020",
+    "IqaNEYTR3KsmecpvszV2YkeJbDpc87yQ__Spring_2020",
+    "lt4VAC74DjLF274tek4L8FYqTjbq4hHS__Autumn_2020",
+    "bp2so305F2oq21lBP0VY6G0kwXg2cRu0__Summer_2020",
+    "WULIzb9tC12A9IbAZvHApQwl6BIJNR83__Summer_2020",
+    "9TuiIzDfVCrUlfBaEDSktFtotKGKfQYg__Autumn_2020",
+    "WhfvXg5P1VIHt0voYpCH1ijZdjn0bzK1__Autumn_2020",
+    "Hb0XmQcRxJ6ZBXQ7FbJkAuxEET6ykgYH__Autumn_2020",
+    "IEBEkhqRrBfBaIUZXX10iLJjAn6XAED2__Summer_2020",
+    "D24VeB0aBo2Xx0bTj02PWwW0O7auraIn__Summer_2020",
+    "QFcC2jlhso2CiVNuxvVO71SUclOI1CtW__Winter_2021",
+    "fBSVZROWXlyrpVxP2La31PVy4dnrNE4R__Summer_2020",
+    "2IVIm4FhjMsL77DEo3c6v0QhMNFfsndj__Autumn_2020",
+    "XAAxptmzmbR1Y9OK4HAZfYnUKaritW30__Autumn_2020",
+    "rls7O7kmxG6dOzAZg64AVyMMnRuhUWo2__Summer_2020",
+    "VgiQeIYP468ltVcy4KD5nrTodz5cI7Rt__Autumn_2020",
+    "fco3TtJKXvY0G6PdHO0MAygGttIGP3e6__Autumn_2020",
+    "cTrSOEc1gW3XqELP562UlUFJYCmlRoa9__Spring_2020",
+    "MTHwUE9iNz50r2F1tOyOscWleVnDyxiM__Spring_2020",
+    "DS3gONkCIflqW37rxefWtZkuLHyp0iy0__Spring_2020",
+    "6FgCeEVc6MnEZgrKsRtwM8OQaUUZsa9R__Summer_2020",
+    "5TFuQKzoTL5plUceT8c4IovreOS6qaRb__Autumn_2020",
+    "S8wkHzOrin58iZNFtYK1xEuTPJwG87pk__Summer_2020",
+    "B9zGSrwlhwYFvGXepvpBAJmlsTQRJSRb__Summer_2020",
+    "MYfejm6P7kgrybv8bw9Re9v9bbulD3dn__Summer_2020",
+    "oAOa6sTT1xr9E4g5LoRDrQhfedLkCFFr__Autumn_2020",
+    "qzECQdvqcgt2LJC5L4nzp9SmCskcf8C0__Winter_2021",
+    "TVc1dlxVHaAoNjPFwGwYpsNISqcszHGT__Summer_2020",
+    "X3RQzuhCBHRW5BVdppjw97G4ezdUE5f2__Autumn_2020",
+    "LvJ7Q4akIVbNCu7IPmTHbFzrSEittxv2__Winter_2021",
+    "zVvE2gesA0W0LZ4zjlhtNOwHtJsD2YyH__Autumn_2020",
+    "PIwJBQ25sWTbSxkMvBPPAlVMf0CQxF6G__Winter_2021",
+    "bmeDIMsOQgKC8leCkjBy02q7nLXzra9E__Autumn_2020",
+    "hDqMym30dg4fA8pIQgK1EjKRZiNgyRye__Autumn_2020",
+    "ljPZGl00Has8A59E8V42N6XVVpP0vFRM__Spring_2020",
+    "7uwFZn0GeKB3DRqteMBROss7T1kgY5uV__Summer_2020",
+    "Y1sCPfRjLKjahjoWebUN6RhscBqOrnkO__Spring_2020",
+    "LXRhkbAC3KZ0fd0XjPQvjOo38xilTZwo__Spring_2020",
+    "rp8xVpLaDNrYi7ajuR5fqcepweCnKyuF__Summer_2020",
+    "T5UK37A6kH5YQ4wjvUpscYF7xtwwqGiO__Summer_2020",
+    "cJ3aUQ982qjLEaCkSJaJm4HEfRCG4vs6__Summer_2020",
+    "1KuQJcTCSK5HQNK5QvqSXaiWOwdORtKk__Spring_2020",
+    "Pjv4qlMdgMxjdrtvlhtMkbagu9G479RY__Autumn_2020",
+    "o5VMxSMN0KmommnB7ljWfaJWYe5eT2qQ__Summer_2020",
+    "7qDRp2DUuXmA8a1Tp5ojuYW6MOK8JyVS__Winter_2021",
+    "YhrxGXOcwtIEkfJyRh3CBqm4wEcF30k3__Spring_2020",
+    "qti2aMC4vMkKzO2eAEjh7LRnzaKcP9Ky__Summer_2020",
+    "M5P1YkJWh6yHs1pAStBFLVNuQOQ9WnPl__Spring_2020",
+    "BCRc16DyKH1TI6bDjNsliguHxk7PCSH8__Winter_2020",
+    "CcCkXfwbswRCaxcM4d8OkublGV3YlSF4__Autumn_2020",
+    "7nLUcyjVwBwzKoSaSBcTkCI1j5dmzPKR__Autumn_2020",
+    "BF3F0f2p6kuUWBIhOovTD7cqgdJUGYkL__Spring_2020",
+    "drPvg2b55lQY2B1UcMaNRhvFX2EtcPN6__Autumn_2020",
+    "wy5k4ANsqQSz7VHgaM5W2s5dkAEyKtmJ__Autumn_2020",
+    "MS9qwj2wWP8JyKHVVaOpFU0MMPotyFdR__Autumn_2020",
+    "FI0zqwQhU7EX7odahMUJOnkG2tZVEPhB__Summer_2020",
+    "K59HBRCj5iHFojsLmjJD71zBOxDiHYIt__Autumn_2020",
+    "sSFlz1eBT1tfSkBY2SNVbnBu00QenznF__Summer_2020",
+    "cR5jgpMWSQ5AcXX1LuWvFzMgvsL4JBp7__Autumn_2020",
+    "Fnl624lyZqFTdGd7WF4zahuGj87VaQhR__Summer_2020",
+    "TbhIbRvYtdgHLGGDT9hV2LUJ09vpKXvt__Spring_2020",
+    "hWFoxvOSGjWzms1w8JyvULPXqPzMoYO3__Winter_2021",
+    "SmuKaA8JdDBEC8iPGOOsAX8ZmVcpa6Cl__Winter_2020",
+    "JCg5uVJizbLNYDuUbBlUJTWRLpuhshqg__Summer_2020",
+    "5zyiTguA1SBMYyLJvhnI9PzEdOSawBNY__Winter_2020",
+    "gUJDr8NnJIDFdqHKLVjt2Gid1BFRCGln__Summer_2020",
+    "ES8t5grRIKQ6anY393ZSrmM6ZM5obMHz__Winter_2021",
+    "VVKroSoPEOTi8jr2YtSRU4TitSOSlXwx__Autumn_2020",
+    "zpsBV9bumWujV1OhD1hp5Z9uJ8QnvoSA__Winter_2021",
+    "dfBX3ikbp1py1KkFoIO1DnB67iZMAa4c__Winter_2021",
+    "rjQrybN7WW4W2iHeD7o9hXEZJiKjvg6t__Winter_2020",
+    "0OF3lawgf9vsILBoZVT27MF8zDNuq7zh__Autumn_2020",
+];
+
+/// Log ids of the TbV val split.
+pub const VAL: [&str; 111] = [
+    "X4zQYzXVLTQ6zlvX85RNExX833eTCXBy__Summer_2020",
+    "fnX5fTajNRYrkvTe3kgPEcqGND6xoJ83__Summer_2020",
+    "yoVO4riYwUN33RkD1jzX4UNaLaPJ4WwY__Winter_2021",
+    "QCgniBLhqw9yE7xHcNJ6Eud6cvxx9Q2c__Autumn_2020",
+    "ICcruTEH4V7xOTkYXre6rY6CI1rOIwym__Winter_2021",
+    "af170aac84653d7b82c564147e94af7d__Summer_2020",
+    "pha5AK0yAM7zlDeBTzdgbll7vSud231q__Spring_2020",
+    "jd4WeIiaSQrxZmoHyIhpGj4yR0j1GKnO__Summer_2020",
+    "LEIaZYYGdIH5PxnCG6FcFrc0e5PHGWK5__Autumn_2020",
+    "vrh2qhEhbgS1diwdmnsUBwQggBAVLIjg__Summer_2020",
+    "pImCkff0HddpkAiIAh9QSKpOiFpkD5wz__Summer_2020",
+    "7103cbc277fe4728b357bb95b1d17588__Summer_2020",
+    "vqHJUhcAKypL4OAvR2ubV5cY1dHLfMm2__Summer_2020",
+    "6EJwfT8vvFouPlGvQQp19O0NEpfekc36__Summer_2020",
+    "McHe1Ns3x3zlrt3SoYFup2QU4X3fgUyT__Summer_2020",
+    "hbpV4oknIcVLpZHSEG1nYu7YXl7AxGEX__Spring_2020",
+    "6946khzM4whpgC8SzZVuy7Sr7DDZSv34__Autumn_2020",
+    "mYgfbdqS00jEhgT9N38MWwZB1Lzjwez7__Winter_2021",
+    "7iShen3l9XJokWc7mVxaPTxWaKq8Jjgb__Autumn_2020",
+    "LrQRfUKwDViVzVs0wp1VNYDUQcwEgJj3__Autumn_2020",
+    "7dcece6971264b7aba6fc90e7f9df8ad__Autumn_2020",
+    "WzrqGbZA4v2nerkUpM3hbrmfg56eyb61__Autumn_2020",
+    "WKdv3YSBogcfx2ivUoSlNNRwX7gNDxiA__Autumn_2020",
+    "D1nzwIZVxEtXaWglThZBaW2jupdU5J89__Winter_2021",
+    "is199hEjAfihF1BTSf3s0gDwVwBemIdy__Spring_2020",
+    "7d749a7b99f243f5adb64125806f92e3__Summer_2020",
+    "1SKq8bGqPRZq6PUbFgTwVLyMZEwtiD2S__Summer_2020",
+    "ofvDkF5aBpNzn7kIuvlaW8QLhxuJ5X3A__Summer_2020",
+    "chLbAIiMJCL96sLQSvAKV5lAt05GU4d3__Summer_2020",
+    "fzt2N0vjcQ0qiV5APbtelI6beq8tMtEE__Summer_2020",
+    "FAgoA7JrnYrC0WJTE3eQZSf2R5r1b3On__Summer_2020",
+    "XiTmGzRNQHk6B3NOeOmwY0ruV72e3nZH__Summer_2020",
+    "4jptcpHjg76VmiNmHtb8x2qKkIBeBeBW__Summer_2020",
+    "8zeNElNT6mNvKxUAzB0IooE0qo8I75e9__Summer_2020",
+    "lLnldXKakQk3B27i01yaeaW8Fq3AOzBz__Summer_2020",
+    "5KfD7QylF1vufySVeCYZIBlknh2BV6vk__Spring_2020",
+    "35f6bbd914d34c4b9a5c63d86f77aece__Spring_2020",
+    "pm7wjqKSfq0SFsAcF40rummG3Sa1bqMe__Summer_2020",
+    "6NPQAzBEDnwOp6d2lSXmqjRITGdwQmIY__Summer_2020",
+    "pbADFDy5ElABBs4vTFGnGtkQjTqIDKyD__Summer_2020",
+    "pjKNfNqBGnvRZ4YoXHdwZAxxVojxSQgc__Autumn_2020",
+    "79TuaBnpYGOFKZAT1ZcmkUNEjHJtiiup__Autumn_2020",
+    "t2EYzOIfnpUeOGaO5kEQF4c2ogSXmVcQ__Summer_2020",
+    "15eb911365cc45e897494de6a5356092__Summer_2020",
+    "n9l6ATRvHumaZKpfteZTdVYKLEFCNVcS__Summer_2020",
+    "yrSiNEmxurcQKfJZ7preY9upbufo7MQ5__Summer_2020",
+    "VrKB4vQ6rn05E6lC7C6KNSWXRbqImakD__Summer_2020",
+    "mx4SgGLgvzlElDsIKMzAd71SAo47ktQ9__Summer_2020",
+    "Ixz1DHSjVyEyCNM7sF0EfXy1HxgDgjtP__Winter_2021",
+    "GNXSrcIoLYHLwFveJUWWdidRgPl1BXBA__Autumn_2020",
+    "AElCQ5nI41SJfDxI6BiNJcxvCmV4rRMK__Winter_2021",
+    "7K4i22BwSwR2AHsk0novsZmKk74oJlBd__Summer_2020",
+    "7NumovZt9STex6xdUaxPuHFiYeFaIGtw__Autumn_2020",
+    "QyCieQww0D5YwoRGcLpjd3pIr0IsGOhX__Autumn_2020",
+    "pe9dhAyNhN9Qvywcw1Vci366LeTeKhIc__Autumn_2020",
+    "VEZpQwdWWLEHGow9KZp5ivZQ5Wq6jn9m__Autumn_2020",
+    "iq1DbXgcmkBLNjBydqZ2b3uAVCESglqa__Summer_2020",
+    "Kyb6Sa3D6O9sbliMdufjWBZp5hOKFAba__Summer_2020",
+    "v6E56tiTAAK6x4aPUSb2J0pYf2qFq4r1__Summer_2020",
+    "EP9PjbeBPfKRiTfbvKhzzyzjh8KoHDxE__Summer_2020",
+    "nrrQP1daZFx5oKBfIcMEt4J004o3QSeK__Summer_2020",
+    "ZlF2B05IQPcc6xfq4wza7t9XqV1OxYyT__Spring_2020",
+    "M9XZ86KEJsQOT64pOQAkvlthhr3Z4Sk3__Spring_2020",
+    "Kgobyet2FXesqYI3wCkli0ft7Q7t0Plt__Spring_2020",
+    "XNiF0SpBmYYMykds22TpRftba3fip6uc__Spring_2020",
+    "ESCG9uyqR1lI8Vr5sMyOkUYWEMPrclWn__Autumn_2020",
+    "qT9M5446NgGW5izOozHsSM9gLGyGkD1u__Summer_2020",
+    "vb7eprKk3XG1rwkvfRmZV4ON3cJMFZER__Summer_2020",
+    "IfDKJGa3z4LEFCYj5JSLGEuw9LI4UIlI__Summer_2020",
+    "UaSNn6bBH1RYtTnXKovPAj84xpBeGnDB__Summer_2020",
+    "UQ6RQlcjsUjzTSHg11glWkaXCabaqkrM__Summer_2020",
+    "X1yjCkSira47fXPmQVIANIrJ85YWLTUo__Autumn_2020",
+    "d200493fbaf44aeebb7e8037a8e22279__Autumn_2020",
+    "wzWJgepoOossK6qanGVnCBY27hIMZ2IQ__Autumn_2020",
+    "iIvOTBBXaRQmpWkFTFC9WLRoeNoDSLA8__Autumn_2020",
+    "D8bJQtULZioDwmjylm0i6DYiU8Iarna0__Autumn_2020",
+    "yqNHd1H76GMchv6m66GzyYnPRNCpCDcT__Autumn_2020",
+    "iwTFTnqVb6WjR5iw8tjRgCN28zaBKgAY__Autumn_2020",
+    "tloQ89jLQcw2FX4S1Xrg0WJrnJVGXlDO__Autumn_2020",
+    "EhENzsWbQDmmxWbbGJXTPmeOcUDI3D0p__Autumn_2020",
+    "zlfTcv8ae4rVh4rDM8wOsUoSglTbC5AO__Autumn_2020",
+    "FfRsLioLHgbMgCLGKs8YAIHrExaYp3YP__Summer_2020",
+    "oxtpIablm3xjNLGDEsFA0to1xzsptvZJ__Autumn_2020",
+    "Vt5DOhIJC86BMPS5PKd7zg4Mk8ipMjXA__Winter_2020",
+    "p2Ukdm04pz5vFlpeQ69kdQpwiOXD0eAz__Summer_2020",
+    "zQ06g8U26AU3pRHhzNxLPm340nOybscg__Spring_2020",
+    "WY0cVNmhg7LtAs5Eny78Csltv2tbjdsd__Winter_2021",
+    "01bb304d7bd835f8bbef7086b688e35e__Summer_2019",
+    "KH70lsYpeBe1I2TqKoD4V5AKJXfcCfpO__Summer_2020",
+    "Nr6t0auYyTEC42fJNIqhkaSasyGjfV6E__Autumn_2020",
+    "jDONAwGVoVt9Ml4ZQW3FaTgDuhIlnAnt__Autumn_2020",
+    "cJiXgBbopBsjrK8AE9GuHcuJCAGsHodO__Autumn_2020",
+    "453e5558636338e3bf9b42b5ba0a6f1d__Summer_2020",
+    "9U3zmqrZK6BVF6yUmseXSe6P4U1h6jUZ__Summer_2020",
+    "5irmKvMAXQjJjUCC4VFT1FC7qsI8a4Kg__Summer_2020",
+    "vyWiJxFSWgVvk5Yd3KVeyGKauOLypJnC__Summer_2020",
+    "HGDGffqH58I2WAoFYKuTr1OYts3JWk4j__Summer_2020",
+    "PRRmJxg1eSDS95RprHX0M1Ord3OCMcsg__Summer_2020",
+    "s9aRUw1JDzPZPRmcx2h0wQCO2cerF66I__Autumn_2020",
+    "b86c6b2e0ed64f4f89f43c1b00391731__Autumn_2020",
+    "gu2UwLc2nU5YgX6tzcmfbqpPXWTngAOt__Autumn_2020",
+    "42d1b09f89cd4e1d866eff93fd05b80f__Autumn_2020",
+    "DKJk1QVisLulVf1jAvxijRTN33EBkgUE__Autumn_2020",
+    "Dgs3UYZvrcojLDw0QLWmD24dPn0jAXhC__Autumn_2020",
+    "zRVrsPX6zQD7NKHIuBs4h5SgijdoNpKw__Autumn_2020",
+    "3V9lgpdSQfTMXj3HjG2yt2gDbkcTK0v1__Winter_2021",
+    "WwiugaY1H3aulOMXUCHbFTZKLDoqWuNP__Winter_2021",
+    "cAstq5i3iTdHouG6HZh90LdA6BMbTW2A__Winter_2021",
+    "MVnyJgZdDbxqbQu9h9Q8kSjbziatTgxa__Autumn_2020",
+    "G6HCUIYJ0crBy1BbY5z5zx1OtCA8NZF0__Summer_2020",
+    "75e8adad50a6324587265e612db3d165__Autumn_2019",
+];
+
+/// Log ids of the TbV test split.
+pub const TEST: [&str; 133] = [
+    "yrQZTJSIjWgFJ3CNE9H4KL1p1QgFmrfb__Spring_2020",
+    "Fu5LS0Rw8Fu80C1NagC4A3deS8mgVt7b__Summer_2020",
+    "jQb5qhBrBcqhmrX25s7otzQWolLyYfAY__Summer_2020",
+    "2p9Pwfm1KbMltk4vpv6ZWlJmZAXi5pvh__Summer_2020",
+    "WfnwYRUwXgzuvvYBNIV5ffzVGN7ARkRn__Autumn_2020",
+    "8326651699a845b8ad0084e6871745f4__Autumn_2020",
+    "d6GIXhzvM5asQxBYlp9K2oXC0yS9CTc3__Winter_2021",
+    "HzDHGixXphRTwTPVaiBa7r5i0pmw4uO2__Summer_2020",
+    "ujhUH2flle6ctyPZawAe9GiSzoeqkAJX__Summer_2020",
+    "vbiL75K7RYZmeONb9dJogRi5fsyqwNt1__Summer_2020",
+    "g49jZtybu3cARdzTRtsP2fo8Gdc7S8oX__Summer_2020",
+    "2d32edc25f65413fb45942085dfdad2a__Summer_2020",
+    "TjYDxQLByFX0Go3Wj18JkY5gCVePA0Xl__Spring_2020",
+    "Hl998Yzj5om0qqGHGkMFI15srslWo2l0__Spring_2020",
+    "toxA95laeQskeSQ7zCwlcpt2z8kteGDE__Spring_2020",
+    "O4zg0xhmQDj1qas2qgZcn5a7LiYjLgMT__Spring_2020",
+    "50OQgpEJys7utcy6xf36RZmRrW6RjzhK__Spring_2020",
+    "sHD672b17MrFJXOfx49DNXq84UdDgtkU__Spring_2020",
+    "KCAFVr17ic63507rz5HyEBH9YU130yOY__Spring_2020",
+    "xrCsw3rTAajrGMPzXfRTZ8Gv4MWoGqmh__Summer_2020",
+    "bjalGQhAZWMLh50K0poYHX6GcXxnJPom__Summer_2020",
+    "zU1PSul4gjlW868IcKBXy4nNrfowwG7K__Summer_2020",
+    "9nS3Yf7Aj93OuzMPt9toWEQ8bF7Cs0PT__Summer_2020",
+    "rnMrMxQir82A9OcpbJzsDouA2485Y4aH__Spring_2020",
+    "3p7HJK6MSYl6Zm6YHNhMStAcbiu7G3sg__Spring_2020",
+    "gs1B8ZCv7DMi8cMt5aN5rSYjQidJXvGP__Summer_2020",
+    "zFb6NmwKZQ8zWRP2G0WM75YqUVTrhZ7p__Autumn_2020",
+    "SP1puuZR5pn6JY3eT7qIU9XQbw3qh2iK__Summer_2020",
+    "20dec8c7eaa54f6c98b6ab29382464f1__Summer_2020",
+    "uAWyh9vy6ABsSiN5LmAMgX4V0koRNEaW__Summer_2020",
+    "766c171f7fde4e1a80426edf5c527d13__Summer_2020",
+    "j1yqGvVV6p3xCOwU0JMm4dhAwxjX1Sfy__Spring_2020",
+    "Mp9dxH3TBKOIJ5uhSsUtuYDwicigLqgU__Autumn_2020",
+    "LmcFXsufJFxeWwZMrfHg2XOF0nVtugfs__Summer_2020",
+    "xpxgFOU6dHCdsqhWVWTkb5xYTAzxQUwj__Autumn_2020",
+    "9qzWKvwnai0YtPDIFkig8cstG4ibyhZa__Autumn_2020",
+    "4ab4f1a7ae0146399153d6170bb1dcd7__Autumn_2020",
+    "CTZa7zmqmUNsO0ZHYmHoP1EUSu1oT1Qh__Winter_2020",
+    "TQqZLr12AhhPnvRgR5p4czYhOiXqDND8__Autumn_2020",
+    "CInXhoNsgVjJ1HhnjfFIXnVPpnfHURRa__Summer_2020",
+    "e4f75a5b5d2d4accb458dc829c1e9f3f__Summer_2020",
+    "54bc6dbcebfb3fbab5b357f88b4b79ca__Winter_2020",
+    "0f0cdd79bc6c35cd9d997ae2fc7e165c__Winter_2020",
+    "Aom5hOS7NB2aexV59g91afgp4cLYxZxx__Summer_2020",
+    "2ZDdTQbgvWsyQZGwhg9EkKBRt9nk6fiY__Winter_2021",
+    "IFeHPOu37ZylGkr8ntl7vdU7fdRnUkGm__Summer_2020",
+    "AVxsoMHjo3GYsKovmgZPku9usXLBqfCP__Autumn_2020",
+    "pu5XaiqeMKZBOt1MUZsOXdJPjZqXU5J7__Autumn_2020",
+    "LOgutcHPKdh7rA0PLSGWhSqGyiShi3WI__Winter_2021",
+    "e55ruBKpsoV2IYOAmgLIEU5u4BcoQTff__Winter_2021",
+    "9p0jAvJXOWlcYlWHaHz3MUXVpfdrHN86__Winter_2021",
+    "36guOvKtHGyqGmkQrsTeWCvA8A6084se__Autumn_2020",
+    "ra05K0AiMQBFh2ifeW5hVbAO0rz2e7Mq__Summer_2020",
+    "0WHfUzbQi8mopDFgQ7BH7lGjgCEygaoN__Summer_2020",
+    "yMCh1ZqomU1kChqq9HHTKdrtrFzUH9Pq__Summer_2020",
+    "9hOChFGYejymWsN5rWVEaQccfsBjMHd5__Summer_2020",
+    "Jp6Jnwfg3lmF9h1lRugGBf6EV4cxFUlU__Summer_2020",
+    "g2sEJ9UhzZfXm58nA27c6H5kS9IcLm8J__Autumn_2020",
+    "L8IgAsQLP2oj45QCSU95d5kgl5teOoZx__Autumn_2020",
+    "AmwBapoTe2LDA2QLE1UfndhzXvW5qYx7__Summer_2020",
+    "0CjqAXeTID58UXtezwdAag5zt6bpsKFp__Summer_2020",
+    "EYYePho1wT9s2e4CH7DdCrJYBRB2Syjn__Summer_2020",
+    "Uyc6uDJkss47KUDFwhy2NZp6pFluCJ0j__Winter_2021",
+    "X3e1Hd8bYrP5JU0uBf0HMTx6zomMHyiC__Summer_2020",
+    "HYKgsU3gkodpc5EyJdDRR4XtReHb7zup__Summer_2020",
+    "92meA40rbhPx3yQSZv8NjKPj7oTi6ipn__Spring_2020",
+    "nKNY9xmAgQdC51KKhyy59VJzPgrCWmS6__Spring_2020",
+    "QsOXYRRypDjS4vjX2CW1h6kBgjodHjBU__Spring_2020",
+    "G1j077kzVj2YDDCGPfdJN5emwtvS6Ffa__Spring_2020",
+    "8qQumE8wEiK3wFWRGfGVzLEKqwebUKh6__Summer_2020",
+    "gjBPR5cBwUrfFlGvTokRAEXbsEUQjLsY__Summer_2020",
+    "4CRsqbn3dhINweAM1ZoMMdwcrIXMYTYh__Summer_2020",
+    "exmmrXSlFZJncbjug3PqKJPTmt0NkW5k__Summer_2020",
+    "Jd8kn8Nr0QSFCmXA4nBo0wtk2Ylii3Zp__Summer_2020",
+    "8qvpfIhDIsg38bFFmyoW2nmUtiWZcPmO__Summer_2020",
+    "DOM7SXgdXj6ZLWlRZdc9Y4PH4QgbToAq__Summer_2020",
+    "csZYLQhiOECYXex5uILcZoUgBL23MGHf__Autumn_2020",
+    "Q1KdcvPbyQ7gAFmXUxYzpSfgdfHQWqkv__Autumn_2020",
+    "WqwX2CqJKhhq9F7AJio4X6BqpS1sl57T__Winter_2021",
+    "93fSHwp6yVp92cnHoTCGnr0X5mOKb4Y6__Winter_2021",
+    "7IL65wUk7aHOux3AeAdF1XUKtoEPoUjt__Winter_2021",
+    "Sek2BIMukaeBarKaxPqPXS4FiKTkrxNB__Summer_2020",
+    "B6JWFEqqecVSM0Hxpt9IuZ9Y0YJI0AIm__Summer_2020",
+    "1mQ4VuN3CZ27Xh7iXUILqKuNw1tciHrv__Summer_2020",
+    "aQC9O61Cahjzjq3IibAWwavhkzpAQ5m4__Summer_2020",
+    "0RRXOItl29gFaocqQW297gkzqmpP0mog__Summer_2020",
+    "wvWtwTIZ3doB1xqX0j21vr0Qbaxcaoet__Summer_2020",
+    "DAkHLZRZorldGbqJfbQ6u5T9dmJgqclO__Spring_2020",
+    "LMv8CiEU9Ak8zHO1aKKy6PdQbsTjWUG9__Spring_2020",
+    "JhRijIKfPFnA78wiV0mG3Y2tsFEiusj6__Spring_2020",
+    "7808aac1740d4ac6b367e6f18e45d0dd__Spring_2020",
+    "NtMLrGFePjJ1DvFDZG1p9r9DFBf5d4VV__Spring_2020",
+    "5egFz1KKT87Q8h42m5cTm2vrzgSYordr__Spring_2020",
+    "1818f7906b2643f19dcb778306e63326__Spring_2020",
+    "KkdwO1CyPxSwQ24FbFyCKIbu86HOjYcO__Summer_2020",
+    "MiLpa3m1rDRmAFNDwc1T4kbnhB86Fe5M__Spring_2020",
+    "ZtukedfPrM7jTU0KAhPWlBIHZCUYjRok__Summer_2020",
+    "qTU3OANchNtEPsSuoNbjE3FsbNBvv47T__Autumn_2020",
+    "yAVHpYFQhhWjXlgAWTz8kDsyAQ4iMFMo__Summer_2020",
+    "58eb8572cbc7420eaa207e1a3137d6ed__Summer_2020",
+    "1iEFkgp7lcKN3NbORA3RJNXdHOmNVljX__Spring_2020",
+    "pemP2CGXlt8bmEFYmpCcI1y3zyY05Jxc__Summer_2020",
+    "RA5rggnWv3hZ6OwKMuRxaiR208i43CV8__Summer_2020",
+    "pJemcSiXghbfGkHVcEvFppgl5Ye9aMcC__Summer_2020",
+    "r1IDo9jm3BIO5ALqAA7Oz95yK8ZmGATb__Summer_2020",
+    "YEDRWy1MYuf5IONz4gQmQwAVuVQzkovm__Autumn_2020",
+    "9SHZcSSJ6WhZgxrAg6eL7y9qvpPrpJA6__Autumn_2020",
+    "9139e0ab9af848aabc3099e4f022db72__Autumn_2020",
+    "ByofqyOoTAkzeIocgXS7046IVehE4nGW__Autumn_2020",
+    "d7b3375238c941ca8f1bbc34bc276bb1__Autumn_2020",
+    "hhkdx3VMk8RMtc8FMeBuRKRm1mJWdQwY__Autumn_2020",
+    "e2Vvd8qpD57RA2ewZ40Sl1lwzvALFXfu__Summer_2020",
+    "X3zP8oWJB4ivWYkbv6X9dw2mc1lRsceY__Summer_2020",
+    "TOQaS7eCZB2ns552jjhmQoPs2MbdkgC6__Summer_2020",
+    "XMbQ7jb2aVss95K5Ie9wTHK3LUTX8nz0__Spring_2020",
+    "ps87cXMi82mKmZ66R5iUVqLOcE9ZdYps__Summer_2020",
+    "ouQqWBO1kAyPrbkIx7LcBVQgInxdntTq__Summer_2020",
+    "lBEMpTsvTu1k0SjZYLeZdVVcxgyZO6Gz__Summer_2020",
+    "gsYZ0RMDMFZdRo1vmdW2Hp0Pn6hnKoIw__Autumn_2020",
+    "hAsGqwlkaBv1zyxXl2tE7uqg4epa36ir__Winter_2021",
+    "aEEUxFn6Gm6XeqV4rLA9esoNpYUhs2Ql__Summer_2020",
+    "8pOtV4RsIeb0byPPCM6212tiiGNUvCyB__Summer_2020",
+    "GJ2Kd0JBW2QQKEst8XefgaKKKOjb5RoX__Summer_2020",
+    "5c2322b6817b4bdd8a23a43244e712c0__Summer_2020",
+    "FJVcWS3nQWkh9XU4TJzmCvaOXlByM947__Summer_2020",
+    "XE3HUDWFcKhT1LcGp8GS8HacmMmok9dc__Autumn_2020",
+    "rdWmCWPFNUuwwh9MBpEKzOEqJsmMuuyk__Winter_2021",
+    "i47t2l9q6nJQNDFbD6iI0MrQ11yq4JNB__Autumn_2020",
+    "2135a974fc804227843bd33514afbf5a__Autumn_2020",
+    "B1Ja9WqVlz5CAkSv2xsaiJK6KQq6egiT__Spring_2020",
+    "79O52mmzE8VvjKQy2P1U9qywm2tneeXS__Autumn_2020",
+    "sYzy2sKcm1qvahStIJf8h4Pl7HBEma7x__Autumn_2020",
+    "r86GRA2V3jgD7pswTLq6NLJJBQqq2EMs__Autumn_2020",
+];
+
+/// Log ids of the TbV split `split_name` (`train`, `val`, or `test`).
+pub fn split_log_ids(split_name: &str) -> Result<&'static [&'static str]> {
+    match split_name {
+        "train" => Ok(&TRAIN),
+        "val" => Ok(&VAL),
+        "test" => Ok(&TEST),
+        _ => bail!("Unknown TbV split `{split_name}`."),
+    }
+}
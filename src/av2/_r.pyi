@@ -1,8 +1,10 @@
 """Rust backend typing stubs."""
 
 from dataclasses import dataclass, field
-from typing import List, Optional, Tuple
+from typing import Dict, List, Optional, Tuple, Union
 
+import numpy as np
+import numpy.typing as npt
 import polars as pl
 import torch
 
@@ -14,16 +16,380 @@ class DataLoader:
     split_name: str
     num_accumulated_sweeps: int
     memory_map: bool
+    memory_map_dir: str = field(init=False)
 
     file_index: pl.DataFrame = field(init=False)
+    dropped_cameras: List[str] = field(init=False)
+    drop_up_lidar: bool = field(init=False)
+    max_latency_jitter_ns: int = field(init=False)
+    seed: int = field(init=False)
+    derived_columns: List[str] = field(init=False)
+    category_ids: Dict[str, int] = field(init=False)
+    load_images: bool = field(init=False)
+    load_extrinsics: bool = field(init=False)
+    prefetch_size: int = field(init=False)
+    num_prefetch_threads: int = field(init=False)
+    shuffle: bool = field(init=False)
+    shuffle_seed: int = field(init=False)
+    epoch: int = field(init=False)
+    rank: int = field(init=False)
+    world_size: int = field(init=False)
+    sharding_mode: str = field(init=False)
+    sample_order: List[int] = field(init=False)
+    class_balanced: bool = field(init=False)
+    class_balance_seed: int = field(init=False)
+    num_repeats: List[int] = field(init=False)
+    accumulation_frame: str = field(init=False)
+    compensate_per_point: bool = field(init=False)
 
+    def __init__(
+        self,
+        root_dir: str,
+        dataset_name: str = "av2",
+        dataset_type: str = "sensor",
+        split_name: str = "train",
+        num_accumulated_sweeps: int = 1,
+        memory_mapped: bool = False,
+        *,
+        max_range_m: Optional[float] = None,
+        x_range_m: Optional[Tuple[float, float]] = None,
+        y_range_m: Optional[Tuple[float, float]] = None,
+        z_range_m: Optional[Tuple[float, float]] = None,
+        drivable_area_margin_m: Optional[float] = None,
+        accumulation_frame: str = "ego",
+        compensate_per_point: bool = False,
+        cameras: Optional[List[str]] = None,
+        category_ids: Dict[str, int] = {},
+        lidar_columns: Optional[List[str]] = None,
+        lidar_ranges: List[Tuple[str, Optional[float], Optional[float]]] = [],
+        derived_columns: List[str] = [],
+        max_latency_jitter_ns: int = 0,
+        seed: int = 0,
+        memory_map_dir: Optional[str] = None,
+        manifest_path: Optional[str] = None,
+        prefetch_size: int = 0,
+        num_prefetch_threads: int = 1,
+    ) -> None: ...
+    @staticmethod
+    def from_manifest(
+        root_dir: str,
+        dataset_name: str,
+        dataset_type: str,
+        split_name: str,
+        num_accumulated_sweeps: int,
+        memory_mapped: bool,
+        manifest_path: str,
+    ) -> DataLoader: ...
+    def availability(self, log_id: str) -> ModalityAvailability: ...
     def get(self, index: int) -> Sweep: ...
-    def get_synchronized_images(self, index: int) -> List[torch.Tensor]: ...
+    def get_synchronized_images(self, index: int) -> List[Optional[torch.Tensor]]: ...
+    def refresh(self) -> Tuple[List[str], List[str], List[str]]: ...
+    def set_lidar_selection(
+        self,
+        columns: Optional[List[str]] = None,
+        ranges: List[Tuple[str, Optional[float], Optional[float]]] = [],
+    ) -> None: ...
+    def set_roi(
+        self,
+        max_range_m: Optional[float] = None,
+        x_range_m: Optional[Tuple[float, float]] = None,
+        y_range_m: Optional[Tuple[float, float]] = None,
+        z_range_m: Optional[Tuple[float, float]] = None,
+        drivable_area_margin_m: Optional[float] = None,
+    ) -> None: ...
+    def set_accumulation(self, frame: str = "ego", compensate_per_point: bool = False) -> None: ...
+    def log_ids(self) -> List[str]: ...
+    def num_sweeps_per_log(self) -> Dict[str, int]: ...
+    def iter_log(self, log_id: str) -> LogSweepIterator: ...
+    def read_track(
+        self, log_id: str, track_uuid: str, interpolate: bool = False
+    ) -> pl.DataFrame: ...
+    def read_track_summaries(self, log_id: str) -> pl.DataFrame: ...
+    def get_pair(self, index: int) -> SweepPair: ...
+    def pair_indices(self) -> List[int]: ...
+    def get_state(self) -> str: ...
+    def set_state(self, state: str) -> None: ...
+    def set_sharding(self, rank: int, world_size: int, mode: str = "strided") -> None: ...
+    def set_class_balanced(self, class_balanced: bool, seed: int = 0) -> None: ...
+    def set_shuffle(self, shuffle: bool, seed: int = 0) -> None: ...
+    def set_epoch(self, epoch: int) -> None: ...
+    def set_thread_pools(
+        self,
+        num_io_threads: Optional[int] = None,
+        num_decode_threads: Optional[int] = None,
+    ) -> None: ...
+    def set_packed_layout(
+        self, max_points: int = 131072, max_cuboids: int = 512, pad_value: float = 0.0
+    ) -> None: ...
+    def get_packed(self, index: int) -> PackedSample: ...
+    def get_augmented(self, index: int, augmentations: str) -> Sweep: ...
     def __len__(self) -> int: ...
 
+@dataclass
+class LogSweepIterator:
+    def __iter__(self) -> LogSweepIterator: ...
+    def __next__(self) -> Sweep: ...
+    def __len__(self) -> int: ...
+
+class PackedSample:
+    sweep_uuid: Tuple[str, int]
+    points: npt.NDArray[np.float32]
+    cuboids: npt.NDArray[np.float32]
+    class_ids: npt.NDArray[np.int64]
+    num_points: int
+    num_cuboids: int
+    descriptor: bytes
+
+    def to_dlpack(self) -> Tuple[DLPackTensor, DLPackTensor, DLPackTensor]: ...
+
+class ModalityAvailability:
+    lidar: bool
+    cameras: bool
+    annotations: bool
+    city_pose: bool
+    calibration: bool
+    map: bool
+
 @dataclass
 class Sweep:
-    city_pose: pl.DataFrame
+    city_pose: Optional[pl.DataFrame]
     lidar: pl.DataFrame
     sweep_uuid: Tuple[str, int]
     cuboids: Optional[pl.DataFrame]
+    images: Optional[List[SynchronizedImage]]
+    city_se3_ego: Optional[npt.NDArray[np.float32]]
+    extrinsics: Optional[pl.DataFrame]
+    lidar_frame: str
+    motion_compensated: bool
+    def lidar_arrow(self) -> ArrowTable: ...
+    def cuboids_arrow(self) -> Optional[ArrowTable]: ...
+
+@dataclass
+class SweepPair:
+    source: Sweep
+    target: Sweep
+    source_se3_target: npt.NDArray[np.float32]
+    correspondences: Optional[pl.DataFrame]
+
+class ArrowTable:
+    column_names: List[str]
+    def __arrow_c_stream__(self, requested_schema: Optional[object] = None) -> object: ...
+    def __len__(self) -> int: ...
+
+class DLPackTensor:
+    shape: List[int]
+    def __dlpack__(
+        self,
+        stream: Optional[object] = None,
+        max_version: Optional[Tuple[int, int]] = None,
+        dl_device: Optional[Tuple[int, int]] = None,
+        copy: Optional[bool] = None,
+    ) -> object: ...
+    def __dlpack_device__(self) -> Tuple[int, int]: ...
+    def __len__(self) -> int: ...
+
+class SynchronizedImage:
+    camera_name: str
+    timestamp_ns: int
+    image: npt.NDArray[np.uint8]
+    intrinsics: npt.NDArray[np.float32]
+    ego_se3_cam: npt.NDArray[np.float32]
+
+class CuboidBvh:
+    def __init__(self, cuboids: npt.NDArray[np.float32]) -> None: ...
+    def query_point(self, point: npt.NDArray[np.float32]) -> npt.NDArray[np.uint64]: ...
+    def query_box(
+        self, min: npt.NDArray[np.float32], max: npt.NDArray[np.float32]
+    ) -> npt.NDArray[np.uint64]: ...
+    def raycast(
+        self,
+        origins: npt.NDArray[np.float32],
+        directions: npt.NDArray[np.float32],
+        max_distance: float,
+    ) -> Tuple[npt.NDArray[np.int64], npt.NDArray[np.float32]]: ...
+    def __len__(self) -> int: ...
+
+class GroundHeightRaster:
+    def __init__(self, map_dir: str) -> None: ...
+    def get_ground_height_at(
+        self, points_city: npt.NDArray[np.float32]
+    ) -> npt.NDArray[np.float32]: ...
+    def get_ground_points_boolean(
+        self, points_city: npt.NDArray[np.float32]
+    ) -> npt.NDArray[np.bool_]: ...
+
+class KdTree:
+    def __init__(self, points: npt.NDArray[np.float32]) -> None: ...
+    def query_knn(
+        self, queries: npt.NDArray[np.float32], k: int
+    ) -> Tuple[npt.NDArray[np.uint64], npt.NDArray[np.float32]]: ...
+    def query_radius(
+        self, query: npt.NDArray[np.float32], radius: float
+    ) -> npt.NDArray[np.uint64]: ...
+    def __len__(self) -> int: ...
+
+class PolylineIndex:
+    def __init__(self, polylines: List[npt.NDArray[np.float32]]) -> None: ...
+    def query_nearest(
+        self, points: npt.NDArray[np.float32], max_distance_m: float
+    ) -> Tuple[npt.NDArray[np.int64], npt.NDArray[np.float32]]: ...
+    def __len__(self) -> int: ...
+
+class RecordBatchStream:
+    def __init__(
+        self,
+        paths: List[str],
+        batch_size: int = 65536,
+        columns: Optional[List[str]] = None,
+    ) -> None: ...
+    def __iter__(self) -> RecordBatchStream: ...
+    def __next__(self) -> pl.DataFrame: ...
+
+class ScenarioBatch:
+    scenario_ids: List[str]
+    track_ids: List[List[str]]
+    positions: npt.NDArray[np.float32]
+    headings: npt.NDArray[np.float32]
+    velocities: npt.NDArray[np.float32]
+    valid: npt.NDArray[np.bool_]
+    object_types: npt.NDArray[np.int64]
+    categories: npt.NDArray[np.int64]
+    centerlines: npt.NDArray[np.float32]
+    centerlines_valid: npt.NDArray[np.bool_]
+
+class ScenarioLoader:
+    num_centerlines: int
+    num_centerline_points: int
+    centerline_radius_m: float
+    scenario_ids: List[str]
+    def __init__(self, split_dir: str) -> None: ...
+    def get_batch(self, indices: List[int]) -> ScenarioBatch: ...
+    def __len__(self) -> int: ...
+
+def quat_to_mat3(quat_wxyz: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def mat3_to_quat(mat3: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def quat_to_yaw(quat_wxyz: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def yaw_to_quat(yaw_rad: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def quat_to_euler(quat_wxyz: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def euler_to_quat(euler_rad: npt.NDArray[np.float32]) -> npt.NDArray[np.float32]: ...
+def nms(
+    cuboids: npt.NDArray[np.float32],
+    scores: npt.NDArray[np.float32],
+    iou_threshold: float,
+    mode: str,
+) -> npt.NDArray[np.uintp]: ...
+def fit_oriented_cuboids(
+    points: npt.NDArray[np.float32], cluster_ids: npt.NDArray[np.int64]
+) -> Tuple[npt.NDArray[np.int64], npt.NDArray[np.float32]]: ...
+def interior_points_assignment(
+    points: npt.NDArray[np.float32], cuboids: npt.NDArray[np.float32], policy: str
+) -> npt.NDArray[np.int64]: ...
+def rasterize_bev(
+    points: npt.NDArray[np.float32],
+    resolution_m: float,
+    min_range_m: Tuple[float, float],
+    max_range_m: Tuple[float, float],
+    min_height_m: float,
+    max_height_m: float,
+    cuboids: Optional[npt.NDArray[np.float32]] = None,
+    dlpack: bool = False,
+) -> Union[npt.NDArray[np.float32], DLPackTensor]: ...
+def compute_forecasting_metrics(
+    forecasts: npt.NDArray[np.float32],
+    gts: npt.NDArray[np.float32],
+    probabilities: npt.NDArray[np.float32],
+    miss_threshold_m: float = 2.0,
+    normalize: bool = False,
+) -> Tuple[
+    npt.NDArray[np.float32],
+    npt.NDArray[np.float32],
+    npt.NDArray[np.bool_],
+    npt.NDArray[np.float32],
+]: ...
+def compute_scene_flow_metrics(
+    pred_flow: npt.NDArray[np.float32],
+    pred_dynamic: npt.NDArray[np.bool_],
+    gts: npt.NDArray[np.float32],
+    category_indices: npt.NDArray[np.uint8],
+    is_dynamic: npt.NDArray[np.bool_],
+    is_close: npt.NDArray[np.bool_],
+    is_valid: npt.NDArray[np.bool_],
+) -> pl.DataFrame: ...
+def evaluate_detections(
+    detections: pl.DataFrame,
+    annotations: pl.DataFrame,
+    categories: Optional[List[str]] = None,
+    affinity: str = "center",
+    affinity_thresholds: Optional[List[float]] = None,
+    tp_threshold: Optional[float] = None,
+    max_range_m: Optional[float] = None,
+    max_num_dts_per_category: Optional[int] = None,
+    range_buckets_m: Optional[List[float]] = None,
+) -> Tuple[pl.DataFrame, pl.DataFrame, pl.DataFrame, pl.DataFrame]: ...
+def evaluate_tracking(
+    tracks: pl.DataFrame,
+    annotations: pl.DataFrame,
+    categories: Optional[List[str]] = None,
+    zero_distance_m: float = 2.0,
+    similarity_threshold: float = 0.5,
+    range_buckets_m: Optional[List[float]] = None,
+) -> Tuple[pl.DataFrame, pl.DataFrame, pl.DataFrame]: ...
+def interpolate_cuboids(
+    annotations: pl.DataFrame, query_timestamp_ns: int
+) -> pl.DataFrame: ...
+def sample_random_object_scale(
+    lidar: pl.DataFrame,
+    cuboids: pl.DataFrame,
+    low_inclusive: float,
+    high_inclusive: float,
+) -> Tuple[pl.DataFrame, pl.DataFrame]: ...
+def sample_scene_global_rotation(
+    lidar: pl.DataFrame,
+    cuboids: pl.DataFrame,
+    low_inclusive: float,
+    upper_inclusive: float,
+) -> Tuple[pl.DataFrame, pl.DataFrame]: ...
+def sample_scene_global_scale(
+    lidar: pl.DataFrame,
+    cuboids: pl.DataFrame,
+    low_inclusive: float,
+    upper_inclusive: float,
+) -> Tuple[pl.DataFrame, pl.DataFrame]: ...
+def sample_scene_reflection_x(
+    lidar: pl.DataFrame, cuboids: pl.DataFrame, p: float = 0.5
+) -> Tuple[pl.DataFrame, pl.DataFrame]: ...
+def sample_scene_reflection_y(
+    lidar: pl.DataFrame, cuboids: pl.DataFrame, p: float = 0.5
+) -> Tuple[pl.DataFrame, pl.DataFrame]: ...
+def sweep_to_range_image(
+    lidar: pl.DataFrame,
+    num_beams: int = 64,
+    num_azimuth_bins: int = 1800,
+    dlpack: bool = False,
+) -> Tuple[
+    Union[npt.NDArray[np.float32], DLPackTensor],
+    Union[npt.NDArray[np.int64], DLPackTensor],
+]: ...
+def voxelize(
+    indices: npt.NDArray[np.uint64],
+    features: npt.NDArray[np.float32],
+    length: int,
+    width: int,
+    height: int,
+    dlpack: bool = False,
+) -> Tuple[
+    Union[npt.NDArray[np.uint64], DLPackTensor],
+    Union[npt.NDArray[np.float32], DLPackTensor],
+    Union[npt.NDArray[np.float32], DLPackTensor],
+]: ...
+def write_dataset_manifest(split_dir: str, path: str) -> pl.DataFrame: ...
+def verify_split(
+    split_dir: str,
+    file_sizes_path: Optional[str] = None,
+    validate_formats: bool = False,
+) -> Dict[str, List[Tuple[str, str]]]: ...
+def set_num_compute_threads(num_threads: Optional[int] = None) -> None: ...
+def num_compute_threads() -> int: ...
+def enable_stage_timings() -> None: ...
+def stage_timings() -> Dict[str, Tuple[int, float, float]]: ...
+def reset_stage_timings() -> None: ...
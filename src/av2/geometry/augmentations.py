@@ -0,0 +1,136 @@
+# <Copyright 2022, Argo AI, LLC. Released under the MIT license.>
+
+"""Geometric augmentations of lidar sweeps and cuboids backed by the Rust implementations.
+
+Each augmentation accepts pandas or polars DataFrames and returns DataFrames of the same kind.
+The lidar holds `x`, `y`, and `z` columns and the cuboids the standard cuboid columns.
+"""
+
+from __future__ import annotations
+
+from typing import Callable, Tuple, TypeVar, Union
+
+import pandas as pd
+import polars as pl
+
+import av2._r as rust
+
+FrameType = TypeVar("FrameType", pd.DataFrame, pl.DataFrame)
+
+
+def _apply(
+    augmentation: Callable[..., Tuple[pl.DataFrame, pl.DataFrame]],
+    lidar: FrameType,
+    cuboids: FrameType,
+    *args: Union[float, int],
+) -> Tuple[FrameType, FrameType]:
+    """Apply a Rust augmentation to pandas or polars frames.
+
+    Args:
+        augmentation: Rust augmentation taking and returning polars frames.
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        *args: Augmentation parameters.
+
+    Returns:
+        The augmented lidar sweep and cuboids as the same kind of frames as the inputs.
+    """
+    if isinstance(lidar, pd.DataFrame):
+        augmented_lidar, augmented_cuboids = augmentation(
+            pl.from_pandas(lidar), pl.from_pandas(cuboids), *args
+        )
+        return augmented_lidar.to_pandas(), augmented_cuboids.to_pandas()
+    return augmentation(lidar, cuboids, *args)
+
+
+def sample_scene_reflection_x(
+    lidar: FrameType, cuboids: FrameType, p: float = 0.5
+) -> Tuple[FrameType, FrameType]:
+    """Reflect the lidar sweep and cuboids across the x-axis with probability `p`.
+
+    Args:
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        p: Probability of the reflection.
+
+    Returns:
+        The augmented lidar sweep and cuboids.
+    """
+    return _apply(rust.sample_scene_reflection_x, lidar, cuboids, p)
+
+
+def sample_scene_reflection_y(
+    lidar: FrameType, cuboids: FrameType, p: float = 0.5
+) -> Tuple[FrameType, FrameType]:
+    """Reflect the lidar sweep and cuboids across the y-axis with probability `p`.
+
+    Args:
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        p: Probability of the reflection.
+
+    Returns:
+        The augmented lidar sweep and cuboids.
+    """
+    return _apply(rust.sample_scene_reflection_y, lidar, cuboids, p)
+
+
+def sample_scene_global_scale(
+    lidar: FrameType, cuboids: FrameType, low_inclusive: float, upper_inclusive: float
+) -> Tuple[FrameType, FrameType]:
+    """Scale the lidar sweep and cuboids by a uniformly sampled factor.
+
+    Args:
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        low_inclusive: Smallest scale factor.
+        upper_inclusive: Largest scale factor.
+
+    Returns:
+        The augmented lidar sweep and cuboids.
+    """
+    return _apply(
+        rust.sample_scene_global_scale, lidar, cuboids, low_inclusive, upper_inclusive
+    )
+
+
+def sample_scene_global_rotation(
+    lidar: FrameType, cuboids: FrameType, low_inclusive: float, upper_inclusive: float
+) -> Tuple[FrameType, FrameType]:
+    """Rotate the lidar sweep and cuboids about the z-axis by a uniformly sampled fraction of a turn.
+
+    Args:
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        low_inclusive: Smallest fraction of a turn.
+        upper_inclusive: Largest fraction of a turn.
+
+    Returns:
+        The augmented lidar sweep and cuboids.
+    """
+    return _apply(
+        rust.sample_scene_global_rotation,
+        lidar,
+        cuboids,
+        low_inclusive,
+        upper_inclusive,
+    )
+
+
+def sample_random_object_scale(
+    lidar: FrameType, cuboids: FrameType, low_inclusive: float, high_inclusive: float
+) -> Tuple[FrameType, FrameType]:
+    """Scale each cuboid and its interior points by a uniformly sampled factor.
+
+    Args:
+        lidar: (N,K) Lidar sweep.
+        cuboids: (M,L) Cuboids.
+        low_inclusive: Smallest scale factor.
+        high_inclusive: Largest scale factor.
+
+    Returns:
+        The augmented lidar sweep and cuboids.
+    """
+    return _apply(
+        rust.sample_random_object_scale, lidar, cuboids, low_inclusive, high_inclusive
+    )
@@ -4,7 +4,7 @@ from __future__ import annotations
 
 import logging
 from dataclasses import dataclass, field
-from typing import List
+from typing import List, Optional
 
 import torch
 from torch.utils.data import Dataset
@@ -79,7 +79,14 @@ class DetectionDataLoader(Dataset[Sweep]):  # type: ignore
         self._current_sweep_index += 1
         return datum
 
-    def get_synchronized_images(self, sweep_index: int) -> List[torch.Tensor]:
-        """Get the synchronized ring images associated with the sweep index.."""
+    def get_synchronized_images(
+        self, sweep_index: int
+    ) -> List[Optional[torch.Tensor]]:
+        """Get the synchronized ring images associated with the sweep index.
+
+        Cameras whose images or calibration are not available are `None`.
+        """
         synchronized_images_list = self._backend.get_synchronized_images(sweep_index)
-        return [torch.as_tensor(x) for x in synchronized_images_list]
+        return [
+            None if x is None else torch.as_tensor(x) for x in synchronized_images_list
+        ]
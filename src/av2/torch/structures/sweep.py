@@ -51,7 +51,14 @@ class Sweep:
 
         Returns:
             Sweep object.
+
+        Raises:
+            ValueError: If the egovehicle poses of the sweep's log are not available.
         """
+        if sweep.city_pose is None:
+            raise ValueError(
+                f"The egovehicle poses of log {sweep.sweep_uuid[0]} are not available."
+            )
         cuboids: Optional[Cuboids] = None
         if sweep.cuboids is not None:
             cuboids = Cuboids(_frame=sweep.cuboids.to_pandas())